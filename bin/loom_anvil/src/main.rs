@@ -46,6 +46,7 @@ use loom::strategy::merger::{ArbSwapPathMergerActor, DiffPathMergerActor, SamePa
 use loom::types::blockchain::{debug_trace_block, ChainParameters, LoomDataTypesEthereum, Mempool};
 use loom::types::entities::{
     AccountNonceAndBalanceState, BlockHistory, LatestBlock, Market, MarketState, PoolClass, PoolId, Swap, Token, TxSigners,
+    DEFAULT_MAX_STATE_SIZE_BYTES,
 };
 use loom::types::events::{
     MarketEvents, MempoolEvents, MessageBlock, MessageBlockHeader, MessageBlockLogs, MessageBlockStateUpdate, MessageHealthEvent,
@@ -195,7 +196,7 @@ async fn main() -> Result<()> {
     let market_instance = SharedState::new(market_instance);
     let market_state = SharedState::new(market_state_instance);
     let mempool_instance = SharedState::new(mempool_instance);
-    let block_history_state = SharedState::new(BlockHistory::new(10));
+    let block_history_state = SharedState::new(BlockHistory::new_with_limits(10, DEFAULT_MAX_STATE_SIZE_BYTES));
 
     let tx_signers = TxSigners::new();
     let accounts_state = AccountNonceAndBalanceState::new();
@@ -402,7 +403,7 @@ async fn main() -> Result<()> {
     if test_config.modules.encoder {
         info!("Starting swap router actor");
 
-        let mut swap_router_actor = SwapRouterActor::new();
+        let mut swap_router_actor = SwapRouterActor::new().with_eip7702_delegate(multicaller_address);
 
         match swap_router_actor
             .access(tx_signers.clone())
@@ -32,6 +32,7 @@ async fn main() -> Result<()> {
 
     let topology_config = TopologyConfig::load_from_file("config.toml".to_string())?;
     let influxdb_config = topology_config.influxdb.clone();
+    let merger_config = topology_config.strategy.merger.clone();
 
     // Parse the multicaller address from config before initializing topology
     let multicaller_address = "0x6E3b634eBd2EbBffb41a49fA6edF6df6bFe8c0Ee".parse().expect("Invalid multicaller address");
@@ -41,7 +42,7 @@ async fn main() -> Result<()> {
     encoder.set_address(multicaller_address);
     
     let mut topology =
-        Topology::<LoomDBType>::from_config(topology_config).with_swap_encoder(encoder);
+        Topology::<LoomDBType>::from_config(topology_config)?.with_swap_encoder(encoder);
 
     // Initialize blockchains field with "base" blockchain with chain ID 8453
     let mut chain_id_map = std::collections::HashMap::new();
@@ -76,8 +77,6 @@ async fn main() -> Result<()> {
     topology.set_multicaller_encoder("multicaller".to_string(), multicaller_address);
     topology.set_default_multicaller_encoder_name(Some("multicaller".to_string()));
     
-    let mut worker_task_vec = topology.start_actors().await?;
-
     let client = topology.get_client(None)?;
 
     // Load backrun strategy configuration
@@ -86,19 +85,32 @@ async fn main() -> Result<()> {
     let backrun_config = config_wrapper.backrun_strategy;
     info!("Backrun config loaded: {:?}", backrun_config);
 
+    // Fail fast on a wrong node before pools are loaded
+    backrun_config.chain_id_validation(&client).await?;
+
+    let (mut worker_task_vec, failed_actors) = topology.start_actors().await?;
+    if !failed_actors.is_empty() {
+        error!("Actors failed to start : {:?}", failed_actors);
+        if failed_actors.iter().any(|actor| actor.critical) {
+            return Err(eyre::eyre!("CRITICAL_ACTOR_FAILED"));
+        }
+    }
+
     // Get the blockchain for the backrun strategy
     let blockchain = topology.get_blockchain(Some(&"base".to_string()))?;
     let blockchain_state = topology.get_blockchain_state(Some(&"base".to_string()))?;
     let strategy = topology.get_strategy(Some(&"base".to_string()))?;
 
     // Create and start the backrun strategy actor
-    let mut backrun_actor = StateChangeArbActor::new(client.clone(), true, true, backrun_config);
+    let mut backrun_actor =
+        StateChangeArbActor::new(client.clone(), true, true, backrun_config).with_multicaller_address(multicaller_address);
     let backrun_tasks = backrun_actor
         .access(blockchain.market())
         .access(blockchain_state.market_state())
         .access(blockchain.mempool())  // Added to fix mempool None error
         .access(blockchain.latest_block()) // Added to fix latest_block None error
         .access(blockchain_state.block_history()) // Added to fix block_history None error
+        .access(blockchain.nonce_and_balance())
         .consume(blockchain.mempool_events_channel())
         .consume(blockchain.market_events_channel())
         .produce(strategy.swap_compose_channel())
@@ -112,6 +124,7 @@ async fn main() -> Result<()> {
 
     // Create and start the merger actors
     let mut same_path_merger = SamePathMergerActor::new(client.clone())
+        .with_profit_improvement_threshold(merger_config.profit_improvement_threshold_bps)
         .on_bc(&blockchain, &blockchain_state, &strategy);
     let same_path_merger_tasks = same_path_merger
         .consume(strategy.swap_compose_channel())
@@ -136,6 +149,7 @@ async fn main() -> Result<()> {
 
     let multicaller_address = topology.get_multicaller_address(None)?;
 let mut arb_swap_merger = ArbSwapPathMergerActor::new(multicaller_address)
+    .with_max_ready_requests(merger_config.max_ready_requests)
     .on_bc(&blockchain, &strategy);
 let arb_swap_merger_tasks = arb_swap_merger
     .consume(strategy.swap_compose_channel())
@@ -146,7 +160,7 @@ worker_task_vec.extend(arb_swap_merger_tasks);
 info!("Arb swap merger actor started successfully");
 
     // Create and start the router actor
-    let mut router_actor = SwapRouterActor::new();
+    let mut router_actor = SwapRouterActor::new().with_eip7702_delegate(multicaller_address);
     let router_tasks = router_actor
         .access(topology.get_signers(None)?)
         .access(blockchain.nonce_and_balance())
@@ -167,7 +181,7 @@ info!("Arb swap merger actor started successfully");
     worker_task_vec.extend(state_health_monitor_tasks);
     info!("State health monitor actor started successfully");
 
-    let mut stuffing_tx_monitor = StuffingTxMonitorActor::new(client.clone());
+    let mut stuffing_tx_monitor = StuffingTxMonitorActor::new(client.clone()).with_multicaller_address(multicaller_address);
     let stuffing_tx_monitor_tasks = stuffing_tx_monitor
         .on_bc(&blockchain)
         .start()?;
@@ -190,7 +204,8 @@ info!("Arb swap merger actor started successfully");
             influxdb_config.url,
             influxdb_config.database,
             influxdb_config.tags,
-        );
+        )
+        .with_batch_config(influxdb_config.batch_size, influxdb_config.flush_interval_ms);
         let influxdb_writer_tasks = influxdb_writer
             .consume(blockchain.influxdb_write_channel())
             .start()?;
@@ -203,10 +218,28 @@ info!("Arb swap merger actor started successfully");
 
     info!("All actors started successfully. Loom backrun bot is now running.");
     
-    // Wait for all tasks to complete (they should run indefinitely)
-    for task in worker_task_vec {
-        if let Err(e) = task.await {
-            error!("Task error: {:?}", e);
+    // Wait for all tasks to complete (they should run indefinitely), checking actors every minute
+    let mut health_check_interval = tokio::time::interval(std::time::Duration::from_secs(60));
+    let mut worker_tasks = futures::future::join_all(worker_task_vec);
+    loop {
+        tokio::select! {
+            results = &mut worker_tasks => {
+                for result in results {
+                    if let Err(e) = result {
+                        error!("Task error: {:?}", e);
+                    }
+                }
+                break;
+            }
+            _ = health_check_interval.tick() => {
+                let health_report = topology.health_check();
+                info!(
+                    "Actors health check : {} alive, {} dead, {} restarted",
+                    health_report.alive.len(),
+                    health_report.dead.len(),
+                    health_report.restarted.len()
+                );
+            }
         }
     }
     
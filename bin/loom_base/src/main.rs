@@ -1,20 +1,25 @@
+use std::sync::Arc;
+
 use alloy::providers::Provider;
 use eyre::Result;
 use tracing::{error, info};
 
-use loom::core::actors::{Accessor, Actor, Consumer, Producer};
+use loom::core::actors::{Accessor, Actor, Consumer, Producer, SharedState};
 use loom::core::router::SwapRouterActor;
-use loom::core::topology::{Topology, TopologyConfig};
+use loom::core::topology::{ConfigWatcherActor, Topology, TopologyConfig};
 use loom::defi::health_monitor::{MetricsRecorderActor, StateHealthMonitorActor, StuffingTxMonitorActor};
 use loom::evm::db::LoomDBType;
 use loom::execution::multicaller::MulticallerSwapEncoder;
 use loom_core_topology::InfluxDbConfig;
 use loom::metrics::InfluxDbWriterActor;
-use loom::strategy::backrun::{BackrunConfig, BackrunConfigSection, StateChangeArbActor};
+use loom::strategy::backrun::{
+    BackrunConfig, BackrunConfigSection, GasAuctionActor, GasAuctionState, LandingProbabilityActor, LandingProbabilityEstimator,
+    PathScoringActor, StateChangeArbActor,
+};
 use loom::strategy::merger::{ArbSwapPathMergerActor, DiffPathMergerActor, SamePathMergerActor};
 use loom::types::entities::strategy_config::load_from_file;
 use loom::types::events::MarketEvents;
-use loom::strategy::simple_arb::SimpleArbFinderActor;
+use loom::strategy::simple_arb::{SimpleArbConfig, SimpleArbFinderActor};
 use loom::broadcast::accounts::TxSignersActor;
 use loom::broadcast::broadcaster::FlashbotsBroadcastActor;
 use loom::broadcast::flashbots::Flashbots;
@@ -65,26 +70,42 @@ async fn main() -> Result<()> {
     // Load configuration
     let (topology_config, influxdb_config) = load_configuration().await?;
 
+    let simple_arb_config = topology_config.simple_arb.clone();
+    let merger_config = topology_config.strategy.merger.clone();
+
     let encoder = MulticallerSwapEncoder::default();
 
-    // Initialize topology
-    let topology =
-        Topology::<LoomDBType>::from_config(topology_config).with_swap_encoder(encoder).start_clients().await?;
+    // Load backrun configuration
+    let backrun_config: BackrunConfigSection = load_from_file("./config.toml".to_string().into()).await?;
+    let mut backrun_config: BackrunConfig = backrun_config.backrun_strategy;
 
-    let mut worker_task_vec = topology.start_actors().await.map_err(Into::<eyre::Report>::into)?;
+    // Initialize topology
+    let topology = Topology::<LoomDBType>::from_config(topology_config)?
+        .with_swap_encoder(encoder)
+        .start_clients()
+        .await?
+        .with_default_pool_loaders(Some("local".to_string()).as_ref())?;
 
-    // Get blockchain and client for Base network
     let client = topology.get_client(Some("local".to_string()).as_ref()).map_err(Into::<eyre::Report>::into)?;
+
+    // Fail fast on a wrong node before pools are loaded
+    backrun_config.chain_id_validation(&client).await?;
+
+    let (mut worker_task_vec, failed_actors) = topology.start_actors().await.map_err(Into::<eyre::Report>::into)?;
+    if !failed_actors.is_empty() {
+        error!("Actors failed to start : {:?}", failed_actors);
+        if failed_actors.iter().any(|actor| actor.critical) {
+            return Err(eyre::eyre!("CRITICAL_ACTOR_FAILED"));
+        }
+    }
+
+    // Get blockchain for Base network
     let blockchain = topology.get_blockchain(Some("base".to_string()).as_ref()).map_err(Into::<eyre::Report>::into)?;
     let blockchain_state = topology.get_blockchain_state(Some("base".to_string()).as_ref()).map_err(Into::<eyre::Report>::into)?;
     let strategy = topology.get_strategy(Some("base".to_string()).as_ref()).map_err(Into::<eyre::Report>::into)?;
 
     let tx_signers = topology.get_signers(Some("env_signer".to_string()).as_ref())?;
 
-    // Load backrun configuration
-    let backrun_config: BackrunConfigSection = load_from_file("./config.toml".to_string().into()).await?;
-    let mut backrun_config: BackrunConfig = backrun_config.backrun_strategy;
-    
     // Use the chain ID from the backrun config
     let chain_id = backrun_config.chain_id();
     info!("Using chain ID from config: {}", chain_id);
@@ -109,17 +130,76 @@ async fn main() -> Result<()> {
     
     info!("Current block: {}", block_nr);
 
+    let multicaller_address = topology.get_multicaller_address(None)?;
+
+    // Workers of the actors started here stop on the topology token together with the topology actors
+    let shutdown_token = topology.cancellation_token();
+
+    // Start the gas auction actor, it tunes the priority fee of backruns by our landed bundles
+    info!("Starting gas auction actor");
+    let gas_auction_state = SharedState::new(GasAuctionState::new());
+    let mut gas_auction_actor = GasAuctionActor::new(multicaller_address).with_cancellation_token(shutdown_token.clone());
+    let result = gas_auction_actor
+        .access(blockchain.latest_block())
+        .access(gas_auction_state.clone())
+        .consume(blockchain.market_events_channel())
+        .consume(blockchain.tx_compose_channel())
+        .consume(blockchain.health_monitor_channel())
+        .start();
+
+    worker_task_vec.extend(start_actor("Gas auction actor", result));
+
+    // Start the landing probability actor, swaps unlikely to land at their priority fee are skipped
+    info!("Starting landing probability actor");
+    let landing_probability = SharedState::new(LandingProbabilityEstimator::new());
+    let mut landing_probability_actor = LandingProbabilityActor::new().with_cancellation_token(shutdown_token.clone());
+    let result = landing_probability_actor
+        .access(blockchain.latest_block())
+        .access(landing_probability.clone())
+        .consume(blockchain.market_events_channel())
+        .consume(blockchain.tx_compose_channel())
+        .start();
+
+    worker_task_vec.extend(start_actor("Landing probability actor", result));
+
+    // Start the path scoring actor, it keeps swap path scores up to date with pool volume and recent profitable trades
+    info!("Starting path scoring actor");
+    let mut path_scoring_actor = PathScoringActor::new().with_cancellation_token(shutdown_token.clone());
+    let result = path_scoring_actor
+        .access(blockchain.market())
+        .consume(blockchain.market_events_channel())
+        .consume(strategy.swap_compose_channel())
+        .start();
+
+    worker_task_vec.extend(start_actor("Path scoring actor", result));
+
+    // Start the config watcher, backrun and simple arb configs are reloaded when config.toml changes
+    info!("Starting config watcher actor");
+    let mut config_watcher_actor = ConfigWatcherActor::new("./config.toml".into())
+        .with_section::<BackrunConfig>("backrun_strategy")
+        .with_section::<SimpleArbConfig>("simple_arb")
+        .with_cancellation_token(shutdown_token.clone());
+    let result = config_watcher_actor.produce(blockchain.tasks_channel()).start();
+
+    worker_task_vec.extend(start_actor("Config watcher actor", result));
+
     // Start the backrun actors
     info!("Starting state change arb actor");
-    let mut state_change_arb_actor = StateChangeArbActor::new(client.clone(), true, true, backrun_config.clone());
+    let mut state_change_arb_actor = StateChangeArbActor::new(client.clone(), true, true, backrun_config.clone())
+        .with_multicaller_address(multicaller_address)
+        .with_cancellation_token(shutdown_token.clone());
     let result = state_change_arb_actor
         .access(blockchain.mempool())
         .access(blockchain.latest_block())
         .access(blockchain.market())
+        .access(blockchain.nonce_and_balance())
         .access(blockchain_state.market_state())
         .access(blockchain_state.block_history())
+        .access(gas_auction_state.clone())
+        .access(landing_probability.clone())
         .consume(blockchain.market_events_channel())
         .consume(blockchain.mempool_events_channel())
+        .consume(blockchain.tasks_channel())
         .produce(strategy.swap_compose_channel())
         .produce(blockchain.health_monitor_channel())
         .produce(blockchain.influxdb_write_channel())
@@ -129,21 +209,24 @@ async fn main() -> Result<()> {
 
     // Start the simple arbitrage finder actor
     info!("Starting simple arbitrage finder actor");
-    let mut simple_arb_finder_actor = SimpleArbFinderActor::new();
+    let mut simple_arb_finder_actor = SimpleArbFinderActor::new(simple_arb_config).with_cancellation_token(shutdown_token.clone());
     let result = simple_arb_finder_actor
         .access(blockchain.market())
         .consume(blockchain.market_events_channel())
+        .consume(blockchain.tasks_channel())
         .produce(strategy.swap_compose_channel())
         .start();
     
     worker_task_vec.extend(start_actor("Simple arbitrage finder actor", result));
 
-    let multicaller_address = topology.get_multicaller_address(None)?;
     info!("Starting swap path encoder actor with multicaller at: {}", multicaller_address);
 
     // Start the swap router actor
     info!("Starting swap path encoder actor");
-    let mut swap_path_encoder_actor = SwapRouterActor::new();
+    let mut swap_path_encoder_actor = SwapRouterActor::new()
+        .with_min_landing_probability(backrun_config.min_landing_probability())
+        .with_eip7702_delegate(multicaller_address)
+        .with_cancellation_token(shutdown_token.clone());
     let result = swap_path_encoder_actor
         .access(tx_signers.clone())
         .access(blockchain.nonce_and_balance())
@@ -158,7 +241,8 @@ async fn main() -> Result<()> {
     info!("Starting EVM estimator actor");
     // Build encoder
     let multicaller_encoder = MulticallerSwapEncoder::default_with_address(multicaller_address);
-    let mut evm_estimator_actor = EvmEstimatorActor::new_with_provider(multicaller_encoder, Some(client.clone()));
+    let mut evm_estimator_actor =
+        EvmEstimatorActor::new_with_provider(multicaller_encoder, Some(client.clone())).with_cancellation_token(shutdown_token.clone());
     let result = evm_estimator_actor
         .consume(strategy.swap_compose_channel())
         .produce(strategy.swap_compose_channel())
@@ -170,7 +254,7 @@ async fn main() -> Result<()> {
 
     // Start the signers actor (critical for converting Sign -> Broadcast)
     info!("Starting signers actor");
-    let mut signers_actor = TxSignersActor::new();
+    let mut signers_actor = TxSignersActor::new().with_cancellation_token(shutdown_token.clone());
     let result = signers_actor
         .consume(blockchain.tx_compose_channel())
         .produce(blockchain.tx_compose_channel())
@@ -182,26 +266,34 @@ async fn main() -> Result<()> {
     info!("Starting flashbots broadcaster actor");
     // Initialize Flashbots client with default relays
     let flashbots = Flashbots::new(client.clone(), "https://relay.flashbots.net", None).with_default_relays();
-    let mut flashbots_broadcaster_actor = FlashbotsBroadcastActor::new(flashbots.into(), true); // true = allow broadcast
+    let mut flashbots_broadcaster_actor = FlashbotsBroadcastActor::new(flashbots.into(), true) // true = allow broadcast
+        .with_cancellation_token(shutdown_token.clone());
     let result = flashbots_broadcaster_actor
+        .access(blockchain.mempool())
         .consume(blockchain.tx_compose_channel())
+        .produce(blockchain.health_monitor_channel())
         .start();
     
     worker_task_vec.extend(start_actor("Flashbots broadcaster actor", result));
 
     // Start the merger actors
     info!("Starting swap path merger actor");
-    let mut swap_path_merger_actor = ArbSwapPathMergerActor::new(multicaller_address);
+    let mut swap_path_merger_actor = ArbSwapPathMergerActor::new(multicaller_address)
+        .with_max_ready_requests(merger_config.max_ready_requests)
+        .with_cancellation_token(shutdown_token.clone());
     let result = swap_path_merger_actor
         .access(blockchain.latest_block())
         .consume(blockchain.market_events_channel())
         .consume(strategy.swap_compose_channel())
         .produce(strategy.swap_compose_channel())
+        .produce(blockchain.influxdb_write_channel())
         .start();
     
     worker_task_vec.extend(start_actor("Swap path merger actor", result));
 
-    let mut same_path_merger_actor = SamePathMergerActor::new(client.clone());
+    let mut same_path_merger_actor = SamePathMergerActor::new(client.clone())
+        .with_profit_improvement_threshold(merger_config.profit_improvement_threshold_bps)
+        .with_cancellation_token(shutdown_token.clone());
     let result = same_path_merger_actor
         .access(blockchain_state.market_state())
         .access(blockchain.latest_block())
@@ -212,7 +304,7 @@ async fn main() -> Result<()> {
     
     worker_task_vec.extend(start_actor("Same path merger actor", result));
 
-    let mut diff_path_merger_actor = DiffPathMergerActor::new();
+    let mut diff_path_merger_actor = DiffPathMergerActor::new().with_cancellation_token(shutdown_token.clone());
     let result = diff_path_merger_actor
         .consume(blockchain.market_events_channel())
         .consume(strategy.swap_compose_channel())
@@ -222,7 +314,7 @@ async fn main() -> Result<()> {
     worker_task_vec.extend(start_actor("Diff path merger actor", result));
 
     // Start the health monitoring actors
-    let mut state_health_monitor_actor = StateHealthMonitorActor::new(client.clone());
+    let mut state_health_monitor_actor = StateHealthMonitorActor::new(client.clone()).with_cancellation_token(shutdown_token.clone());
     let result = state_health_monitor_actor
         .access(blockchain_state.market_state())
         .consume(blockchain.tx_compose_channel())
@@ -231,23 +323,31 @@ async fn main() -> Result<()> {
     
     worker_task_vec.extend(start_actor("State health monitor actor", result));
 
-    let mut stuffing_txs_monitor_actor = StuffingTxMonitorActor::new(client.clone());
+    let mut stuffing_txs_monitor_actor = StuffingTxMonitorActor::new(client.clone())
+        .with_multicaller_address(multicaller_address)
+        .with_chain_id(chain_id)
+        .with_weth_balance_of_slot(blockchain.weth_balance_of_slot())
+        .with_cancellation_token(shutdown_token.clone());
     let result = stuffing_txs_monitor_actor
         .access(blockchain.latest_block())
+        .access(blockchain.bundle_history())
         .consume(blockchain.tx_compose_channel())
         .consume(blockchain.market_events_channel())
         .produce(blockchain.influxdb_write_channel())
+        .produce(blockchain.health_monitor_channel())
         .start();
     
     worker_task_vec.extend(start_actor("Stuffing txs monitor actor", result));
 
     // Start InfluxDB metrics if configured
     if let Some(influxdb_config) = influxdb_config {
-        let mut influxdb_writer_actor = InfluxDbWriterActor::new(influxdb_config.url, influxdb_config.database, influxdb_config.tags);
+        let mut influxdb_writer_actor = InfluxDbWriterActor::new(influxdb_config.url, influxdb_config.database, influxdb_config.tags)
+            .with_batch_config(influxdb_config.batch_size, influxdb_config.flush_interval_ms)
+            .with_cancellation_token(shutdown_token.clone());
         let result = influxdb_writer_actor.consume(blockchain.influxdb_write_channel()).start();
         worker_task_vec.extend(start_actor("InfluxDB writer actor", result));
 
-        let mut block_latency_recorder_actor = MetricsRecorderActor::new();
+        let mut block_latency_recorder_actor = MetricsRecorderActor::new().with_cancellation_token(shutdown_token.clone());
         let result = block_latency_recorder_actor
             .access(blockchain.market())
             .access(blockchain_state.market_state())
@@ -258,16 +358,14 @@ async fn main() -> Result<()> {
         worker_task_vec.extend(start_actor("Block latency recorder actor", result));
     }
 
-    // Set up graceful shutdown handling
-    let (shutdown_sender, mut shutdown_receiver) = tokio::sync::mpsc::channel::<()>(1);
-    let shutdown_sender_clone = shutdown_sender.clone();
-    
-    // Handle Ctrl+C signal for graceful shutdown
+    // Handle Ctrl+C signal for graceful shutdown, the topology cancels the workers of all actors
+    let topology = Arc::new(topology);
+    let shutdown_topology = topology.clone();
     tokio::spawn(async move {
         match tokio::signal::ctrl_c().await {
             Ok(()) => {
                 info!("Received shutdown signal, initiating graceful shutdown...");
-                let _ = shutdown_sender_clone.send(()).await;
+                shutdown_topology.shutdown();
             }
             Err(err) => {
                 error!("Failed to listen for shutdown signal: {}", err);
@@ -312,9 +410,13 @@ async fn main() -> Result<()> {
         }
     });
     
+    // Check actors every minute, dead actors with restart_on_failure are restarted
+    let mut health_check_interval = tokio::time::interval(std::time::Duration::from_secs(60));
+
     // Main event loop with proper shutdown handling
     let mut shutdown_initiated = false;
     let mut shutdown_complete = false;
+    let mut shutdown_deadline = tokio::time::Instant::now();
     
     loop {
         // Use tokio::select to handle message reception, task completion, and shutdown signals
@@ -338,6 +440,16 @@ async fn main() -> Result<()> {
                 }
             }
             
+            _ = health_check_interval.tick(), if !shutdown_initiated => {
+                let health_report = topology.health_check();
+                info!(
+                    "Actors health check : {} alive, {} dead, {} restarted",
+                    health_report.alive.len(),
+                    health_report.dead.len(),
+                    health_report.restarted.len()
+                );
+            }
+            
             // Monitor task completion for shutdown coordination
             Some(_) = task_complete_rx.recv(), if shutdown_initiated => {
                 active_tasks -= 1;
@@ -350,23 +462,22 @@ async fn main() -> Result<()> {
             }
             
             // Handle shutdown signal
-            Some(_) = shutdown_receiver.recv() => {
-                if !shutdown_initiated {
-                    info!("Initiating graceful shutdown sequence");
-                    shutdown_initiated = true;
-                    
-                    // Here you would send shutdown signals to all actors
-                    // For example, you could have a shutdown channel for each actor
-                    
-                    // Wait for a maximum of 10 seconds for graceful shutdown
-                    tokio::spawn(async move {
-                        tokio::time::sleep(std::time::Duration::from_secs(10)).await;
-                        info!("Shutdown timeout reached, forcing exit");
-                        std::process::exit(0);
-                    });
+            _ = shutdown_token.cancelled(), if !shutdown_initiated => {
+                info!("Initiating graceful shutdown sequence");
+                shutdown_initiated = true;
+                
+                // Wait for a maximum of 10 seconds for the cancelled workers to stop
+                shutdown_deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(10);
+                if active_tasks == 0 {
+                    shutdown_complete = true;
                 }
             }
             
+            _ = tokio::time::sleep_until(shutdown_deadline), if shutdown_initiated && !shutdown_complete => {
+                error!("Shutdown timeout reached, {} worker tasks still running", active_tasks);
+                shutdown_complete = true;
+            }
+            
             // Add a small delay to prevent CPU spinning
             _ = tokio::time::sleep(throttle_delay), if !shutdown_initiated => {
                 // Just a throttle, do nothing
@@ -2,7 +2,7 @@ use alloy_network::Ethereum;
 use alloy_provider::Provider;
 use eyre::{eyre, Result};
 use loom_core_blockchain::create_robust_provider;
-use loom_core_topology::configure_dns_settings;
+use loom_core_topology::{configure_dns_settings, DnsConfig};
 use std::env;
 use std::time::Duration;
 use tracing::{error, info};
@@ -12,15 +12,16 @@ async fn main() -> Result<()> {
     // Initialize logging
     tracing_subscriber::fmt::init();
     
-    // Configure DNS settings
-    configure_dns_settings();
-    
     // Get RPC URL from environment or use default
     let rpc_url = env::var("RPC_URL").unwrap_or_else(|_| {
         info!("RPC_URL not set, using default mainnet RPC");
         "https://eth-mainnet.g.alchemy.com/v2/demo".to_string()
     });
     
+    // Resolver of the `[dns]` settings, DNS_SERVER sets the nameserver used instead of the system resolver, e.g. 8.8.8.8:53
+    let dns_config = DnsConfig { server: env::var("DNS_SERVER").ok(), ..DnsConfig::default() };
+    let _dns_resolver = configure_dns_settings(&dns_config)?;
+
     // Get transport type from environment or use default
     let transport_type = env::var("TRANSPORT_TYPE").unwrap_or_else(|_| {
         info!("TRANSPORT_TYPE not set, using HTTP");
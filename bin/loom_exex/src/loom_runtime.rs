@@ -25,6 +25,9 @@ use std::env;
 use std::future::Future;
 use tracing::info;
 
+// Snapshots older than this are ignored, every missed block is replayed with a debug_traceBlock call
+const MARKET_STATE_SNAPSHOT_MAX_AGE_BLOCKS: u64 = 50;
+
 pub async fn init<Node>(
     ctx: ExExContext<Node>,
     bc: Blockchain,
@@ -83,6 +86,7 @@ where
         .and_then(|b| b.get("mainnet"))
         .map(|b| match b {
             BroadcasterConfig::Flashbots(f) => f.relays(),
+            BroadcasterConfig::PrivateMempool(_) => Vec::new(),
         })
         .unwrap_or_default();
 
@@ -107,7 +111,8 @@ where
         .with_evm_estimator()? // estimate gas, add tips
         .with_signers()? // start signer actor that signs transactions before broadcasting
         .with_flashbots_broadcaster( true)? // broadcast signed txes to flashbots
-        .with_market_state_preloader()? // preload contracts to market state
+        .with_market_state_preloader_from_snapshot(db_pool.clone(), MARKET_STATE_SNAPSHOT_MAX_AGE_BLOCKS)? // restore and preload state
+        .with_market_state_snapshots(db_pool.clone())? // save market state snapshots every 100 blocks
         .with_nonce_and_balance_monitor()? // start monitoring balances of
         .with_pool_history_loader(pools_config.clone())? // load pools used in latest 10000 blocks
         //.with_curve_pool_protocol_loader()? // load curve + steth + wsteth
@@ -119,6 +124,7 @@ where
 
         .with_backrun_block(backrun_config.clone())? // load backrun searcher for incoming block
         .with_backrun_mempool(backrun_config)? // load backrun searcher for mempool txes
+        .with_profit_ledger(db_pool.clone())? // record realized profit of landed txes
         .with_web_server(webserver_host, Router::new(), db_pool)? // start web server
     ;
 
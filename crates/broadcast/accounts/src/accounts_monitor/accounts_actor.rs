@@ -5,7 +5,7 @@ use alloy_primitives::{Address, Log, U256};
 use alloy_provider::Provider;
 use alloy_rpc_types::BlockTransactions;
 use alloy_sol_types::SolEventInterface;
-use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, Consumer, SharedState, WorkerResult};
+use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, SharedState, WorkerResult};
 use loom_core_actors_macros::{Accessor, Consumer};
 
 use loom_defi_abi::IERC20::IERC20Events;
@@ -21,6 +21,7 @@ pub async fn nonce_and_balance_fetcher_worker<P, N>(
     client: P,
     accounts_state: SharedState<AccountNonceAndBalanceState>,
     only_once: bool,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult
 where
     N: Network,
@@ -48,7 +49,10 @@ where
             break;
         }
 
-        sleep(Duration::from_secs(20)).await;
+        tokio::select! {
+            _ = cancellation_token.cancelled() => break,
+            _ = sleep(Duration::from_secs(20)) => {}
+        }
     }
     Ok("Nonce and balance fetcher finished".to_string())
 }
@@ -57,11 +61,15 @@ pub async fn nonce_and_balance_monitor_worker(
     accounts_state: SharedState<AccountNonceAndBalanceState>,
     latest_block: SharedState<LatestBlock>,
     market_events_rx: Broadcaster<MarketEvents>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult {
     let mut market_events = market_events_rx.subscribe();
 
     loop {
         tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Nonce and balance monitor cancelled".to_string());
+            }
             msg = market_events.recv() => {
                 let market_event_msg : Result<MarketEvents, RecvError> = msg;
                 if let Ok(market_event_msg) = market_event_msg {
@@ -72,6 +80,8 @@ pub async fn nonce_and_balance_monitor_worker(
 
                                     // acquire accounts shared state write lock
                                     let mut accounts_lock = accounts_state.write().await;
+                                    // reserved nonces were for bundles targeting this block
+                                    accounts_lock.clear_reserved_nonces();
 
                                     for tx in txs {
                                         let tx_from : Address = tx.from;
@@ -147,6 +157,7 @@ pub struct NonceAndBalanceMonitorActor<P, N> {
     latest_block: Option<SharedState<LatestBlock>>,
     #[consumer]
     market_events: Option<Broadcaster<MarketEvents>>,
+    cancellation_token: CancellationToken,
     _n: PhantomData<N>,
 }
 
@@ -163,6 +174,7 @@ where
             market_events: None,
             only_once: false,
             with_fetcher: true,
+            cancellation_token: CancellationToken::new(),
             _n: PhantomData,
         }
     }
@@ -175,6 +187,10 @@ where
         Self { with_fetcher: false, ..self }
     }
 
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
     pub fn with_state(
         self,
         accounts_nonce_and_balance: SharedState<AccountNonceAndBalanceState>,
@@ -203,6 +219,7 @@ where
                 self.client.clone(),
                 self.accounts_nonce_and_balance.clone().unwrap(),
                 self.only_once,
+                self.cancellation_token.clone(),
             ));
 
             if self.only_once {
@@ -221,6 +238,7 @@ where
             self.accounts_nonce_and_balance.clone().unwrap(),
             self.latest_block.clone().unwrap(),
             self.market_events.clone().unwrap(),
+            self.cancellation_token.clone(),
         ));
         handles.push(monitor_task);
 
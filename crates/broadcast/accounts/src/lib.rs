@@ -1,5 +1,10 @@
 pub use crate::accounts_monitor::NonceAndBalanceMonitorActor;
-pub use crate::signers::{InitializeSignersOneShotBlockingActor, TxSignersActor};
+#[cfg(feature = "ledger")]
+pub use crate::signers::LedgerHidTransport;
+pub use crate::signers::{
+    open_ledger_hid_transport, parse_derivation_path, InitializeSignersOneShotBlockingActor, LedgerCommand, LedgerTransport, SignerBackend,
+    TxSignerLedger, TxSignersActor,
+};
 
 mod accounts_monitor;
 mod signers;
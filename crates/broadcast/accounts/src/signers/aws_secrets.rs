@@ -0,0 +1,64 @@
+use aws_config::{BehaviorVersion, Region};
+use aws_sdk_kms::primitives::Blob;
+use eyre::{eyre, Result};
+use tracing::debug;
+
+/// Region is the fourth field of `arn:aws:secretsmanager:<region>:<account>:secret:<name>`
+pub(crate) fn region_from_arn(secret_arn: &str) -> Option<String> {
+    secret_arn.split(':').nth(3).filter(|region| !region.is_empty()).map(|region| region.to_string())
+}
+
+/// Fetch the KMS encrypted private key stored in AWS Secrets Manager and decrypt it with KMS.
+/// The secret is either a binary ciphertext blob or a hex encoded ciphertext string.
+pub(crate) async fn fetch_private_key(secret_arn: &str, region: Option<String>) -> Result<Vec<u8>> {
+    let mut config_loader = aws_config::defaults(BehaviorVersion::latest());
+    if let Some(region) = region.or_else(|| region_from_arn(secret_arn)) {
+        config_loader = config_loader.region(Region::new(region));
+    }
+    let config = config_loader.load().await;
+
+    let secret = aws_sdk_secretsmanager::Client::new(&config)
+        .get_secret_value()
+        .secret_id(secret_arn)
+        .send()
+        .await
+        .map_err(|e| eyre!("Failed to fetch secret {}: {}", secret_arn, e))?;
+
+    let ciphertext = match (secret.secret_binary(), secret.secret_string()) {
+        (Some(binary), _) => binary.as_ref().to_vec(),
+        (None, Some(string)) => hex::decode(string.trim().trim_start_matches("0x")).map_err(|e| eyre!("Failed to decode hex: {}", e))?,
+        (None, None) => return Err(eyre!("SECRET_IS_EMPTY")),
+    };
+    debug!(secret_arn, "Encrypted key fetched from secrets manager");
+
+    let decrypted = aws_sdk_kms::Client::new(&config)
+        .decrypt()
+        .ciphertext_blob(Blob::new(ciphertext))
+        .send()
+        .await
+        .map_err(|e| eyre!("Failed to decrypt key with KMS: {}", e))?;
+
+    let plaintext = decrypted.plaintext().ok_or_else(|| eyre!("KMS_PLAINTEXT_IS_EMPTY"))?.as_ref().to_vec();
+
+    // Raw 32 bytes key or hex string
+    if plaintext.len() == 32 {
+        Ok(plaintext)
+    } else {
+        let key_str = String::from_utf8(plaintext).map_err(|_| eyre!("KMS_PLAINTEXT_NOT_UTF8"))?;
+        hex::decode(key_str.trim().trim_start_matches("0x")).map_err(|e| eyre!("Failed to decode hex: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_region_from_arn() {
+        assert_eq!(
+            region_from_arn("arn:aws:secretsmanager:eu-west-1:123456789012:secret:loom-key-AbCdEf"),
+            Some("eu-west-1".to_string())
+        );
+        assert_eq!(region_from_arn("loom-key"), None);
+    }
+}
@@ -1,3 +1,6 @@
+use std::fmt;
+use std::sync::Arc;
+
 use alloy_primitives::{hex, Bytes, B256};
 use eyre::eyre;
 use tracing::{error, info};
@@ -7,10 +10,30 @@ use loom_core_actors_macros::Accessor;
 
 use loom_types_entities::{AccountNonceAndBalanceState, KeyStore, LoomTxSigner, TxSigners};
 
+use super::ledger::{open_ledger_hid_transport, TxSignerLedger};
+
+/// Where the key of the signer is kept
+#[derive(Clone)]
+pub enum SignerBackend {
+    /// Raw private key bytes held in memory
+    PrivateKey(Vec<u8>),
+    /// Ledger device connected over USB HID, the key at the BIP-32 derivation path like `m/44'/60'/0'/0/0` signs
+    LedgerHid { derivation_path: String },
+}
+
+impl fmt::Debug for SignerBackend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SignerBackend::PrivateKey(_) => f.write_str("PrivateKey"),
+            SignerBackend::LedgerHid { derivation_path } => f.debug_struct("LedgerHid").field("derivation_path", derivation_path).finish(),
+        }
+    }
+}
+
 /// The one-shot actor adds a new signer to the signers and monitor list after and stops.
 #[derive(Accessor)]
 pub struct InitializeSignersOneShotBlockingActor {
-    key: Option<Vec<u8>>,
+    backend: Option<SignerBackend>,
     #[accessor]
     signers: Option<SharedState<TxSigners>>,
     #[accessor]
@@ -18,13 +41,21 @@ pub struct InitializeSignersOneShotBlockingActor {
 }
 
 async fn initialize_signers_one_shot_worker(
-    key: Vec<u8>,
+    backend: SignerBackend,
     signers: SharedState<TxSigners>,
     monitor: SharedState<AccountNonceAndBalanceState>,
 ) -> WorkerResult {
-    let new_signer = signers.write().await.add_privkey(Bytes::from(key));
-    monitor.write().await.add_account(new_signer.address());
-    info!("New signer added {:?}", new_signer.address());
+    let address = match backend {
+        SignerBackend::PrivateKey(key) => signers.write().await.add_privkey(Bytes::from(key)).address(),
+        SignerBackend::LedgerHid { derivation_path } => {
+            let new_signer = TxSignerLedger::new(open_ledger_hid_transport()?, &derivation_path)?;
+            let address = new_signer.address();
+            signers.write().await.add_signer(Arc::new(new_signer));
+            address
+        }
+    };
+    monitor.write().await.add_account(address);
+    info!("New signer added {:?}", address);
     Ok("Signer added".to_string())
 }
 
@@ -32,10 +63,21 @@ impl InitializeSignersOneShotBlockingActor {
     pub fn new(key: Option<Vec<u8>>) -> InitializeSignersOneShotBlockingActor {
         let key = key.unwrap_or_else(|| B256::random().to_vec());
 
-        InitializeSignersOneShotBlockingActor { key: Some(key), signers: None, monitor: None }
+        InitializeSignersOneShotBlockingActor { backend: Some(SignerBackend::PrivateKey(key)), signers: None, monitor: None }
     }
 
+    /// Sign with the Ledger device key at `derivation_path`, requires the `ledger` feature
+    pub fn new_from_ledger(derivation_path: String) -> InitializeSignersOneShotBlockingActor {
+        InitializeSignersOneShotBlockingActor { backend: Some(SignerBackend::LedgerHid { derivation_path }), signers: None, monitor: None }
+    }
+
+    /// Use the Ledger device if `LEDGER_DERIVATION_PATH` is set, otherwise the encrypted key of `DATA`
     pub fn new_from_encrypted_env() -> eyre::Result<InitializeSignersOneShotBlockingActor> {
+        if let Ok(derivation_path) = std::env::var("LEDGER_DERIVATION_PATH") {
+            info!(%derivation_path, "Signer key is kept in the Ledger device");
+            return Ok(Self::new_from_ledger(derivation_path));
+        }
+
         let key = match std::env::var("DATA") {
             Ok(priv_key_enc) => {
                 let keystore = KeyStore::new();
@@ -46,14 +88,31 @@ impl InitializeSignersOneShotBlockingActor {
             _ => None,
         };
 
-        Ok(InitializeSignersOneShotBlockingActor { key, signers: None, monitor: None })
+        Ok(InitializeSignersOneShotBlockingActor { backend: key.map(SignerBackend::PrivateKey), signers: None, monitor: None })
     }
 
     pub fn new_from_encrypted_key(priv_key_enc: Vec<u8>) -> eyre::Result<InitializeSignersOneShotBlockingActor> {
         let keystore = KeyStore::new();
         let key = keystore.encrypt_once(priv_key_enc.as_slice())?;
 
-        Ok(InitializeSignersOneShotBlockingActor { key: Some(key), signers: None, monitor: None })
+        Ok(InitializeSignersOneShotBlockingActor { backend: Some(SignerBackend::PrivateKey(key)), signers: None, monitor: None })
+    }
+
+    /// Fetch the KMS encrypted key from AWS Secrets Manager, region is taken from the ARN
+    #[cfg(feature = "aws")]
+    pub async fn new_from_aws_secrets_manager(secret_arn: String) -> eyre::Result<InitializeSignersOneShotBlockingActor> {
+        Self::new_from_aws_secrets_manager_with_region(secret_arn, None).await
+    }
+
+    #[cfg(feature = "aws")]
+    pub async fn new_from_aws_secrets_manager_with_region(
+        secret_arn: String,
+        region: Option<String>,
+    ) -> eyre::Result<InitializeSignersOneShotBlockingActor> {
+        let key = super::aws_secrets::fetch_private_key(&secret_arn, region).await?;
+        info!(%secret_arn, "Signer key loaded from AWS secrets manager");
+
+        Ok(InitializeSignersOneShotBlockingActor { backend: Some(SignerBackend::PrivateKey(key)), signers: None, monitor: None })
     }
 
     pub fn with_monitor(self, monitor: SharedState<AccountNonceAndBalanceState>) -> Self {
@@ -67,8 +126,8 @@ impl InitializeSignersOneShotBlockingActor {
 
 impl Actor for InitializeSignersOneShotBlockingActor {
     fn start_and_wait(&self) -> eyre::Result<()> {
-        let key = match self.key.clone() {
-            Some(key) => key,
+        let backend = match self.backend.clone() {
+            Some(backend) => backend,
             _ => {
                 error!("No signer keys found");
                 return Err(eyre!("NO_SIGNER_KEY"));
@@ -83,7 +142,7 @@ impl Actor for InitializeSignersOneShotBlockingActor {
         };
 
         let rt = tokio::runtime::Runtime::new()?; // we need a different runtime to wait for the result
-        let handle = rt.spawn(async { initialize_signers_one_shot_worker(key, signers, monitor).await });
+        let handle = rt.spawn(async { initialize_signers_one_shot_worker(backend, signers, monitor).await });
 
         self.wait(Ok(vec![handle]))?;
         rt.shutdown_background();
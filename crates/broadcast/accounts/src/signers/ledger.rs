@@ -0,0 +1,346 @@
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+use alloy_consensus::{SignableTransaction, TxEnvelope, TypedTransaction};
+use alloy_network::TransactionBuilder;
+use alloy_primitives::{Address, PrimitiveSignature, U256};
+use alloy_rpc_types::{Transaction, TransactionRequest};
+use eyre::{eyre, OptionExt, Result};
+use tracing::{debug, error};
+
+use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
+use loom_types_entities::LoomTxSigner;
+
+// Ethereum app of the Ledger device
+const LEDGER_CLA: u8 = 0xe0;
+const LEDGER_INS_GET_ADDRESS: u8 = 0x02;
+const LEDGER_INS_SIGN_TRANSACTION: u8 = 0x04;
+const LEDGER_P1_FIRST_CHUNK: u8 = 0x00;
+const LEDGER_P1_MORE_CHUNKS: u8 = 0x80;
+const LEDGER_APDU_MAX_DATA_LEN: usize = 255;
+
+const LEDGER_STATUS_OK: u16 = 0x9000;
+const LEDGER_STATUS_USER_REJECTED: u16 = 0x6985;
+
+const HARDENED_BIT: u32 = 0x8000_0000;
+
+/// APDU command sent to the Ethereum app of the Ledger device
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LedgerCommand {
+    pub cla: u8,
+    pub ins: u8,
+    pub p1: u8,
+    pub p2: u8,
+    pub data: Vec<u8>,
+}
+
+/// Exchanges APDU commands with the Ledger device, returns the response data and the status word
+pub trait LedgerTransport: Send {
+    fn exchange(&mut self, command: &LedgerCommand) -> Result<(Vec<u8>, u16)>;
+}
+
+/// Ledger device connected over USB HID
+#[cfg(feature = "ledger")]
+pub struct LedgerHidTransport {
+    transport: ledger_transport_hid::TransportNativeHID,
+}
+
+#[cfg(feature = "ledger")]
+impl LedgerHidTransport {
+    pub fn new() -> Result<Self> {
+        let hid_api = ledger_transport_hid::hidapi::HidApi::new().map_err(|e| eyre!("Failed to open HID API: {}", e))?;
+        let transport =
+            ledger_transport_hid::TransportNativeHID::new(&hid_api).map_err(|e| eyre!("Failed to open Ledger device: {}", e))?;
+        Ok(Self { transport })
+    }
+}
+
+#[cfg(feature = "ledger")]
+impl LedgerTransport for LedgerHidTransport {
+    fn exchange(&mut self, command: &LedgerCommand) -> Result<(Vec<u8>, u16)> {
+        let command =
+            ledger_apdu::APDUCommand { cla: command.cla, ins: command.ins, p1: command.p1, p2: command.p2, data: command.data.clone() };
+        let answer = self.transport.exchange(&command).map_err(|e| eyre!("Ledger HID exchange failed: {}", e))?;
+        Ok((answer.data().to_vec(), answer.retcode()))
+    }
+}
+
+/// Opens the first Ledger device connected over USB HID, requires the `ledger` feature
+pub fn open_ledger_hid_transport() -> Result<Box<dyn LedgerTransport>> {
+    #[cfg(feature = "ledger")]
+    {
+        Ok(Box::new(LedgerHidTransport::new()?))
+    }
+    #[cfg(not(feature = "ledger"))]
+    {
+        Err(eyre!("LEDGER_FEATURE_NOT_ENABLED"))
+    }
+}
+
+/// Parse a BIP-32 derivation path like `m/44'/60'/0'/0/0`
+pub fn parse_derivation_path(derivation_path: &str) -> Result<Vec<u32>> {
+    let mut components = derivation_path.trim().split('/');
+    if components.next() != Some("m") {
+        return Err(eyre!("DERIVATION_PATH_MUST_START_WITH_M"));
+    }
+    let path = components
+        .map(|component| {
+            let (index, hardened) = match component.strip_suffix('\'').or_else(|| component.strip_suffix('h')) {
+                Some(index) => (index, true),
+                None => (component, false),
+            };
+            let index: u32 = index.parse().map_err(|_| eyre!("INVALID_DERIVATION_PATH_COMPONENT"))?;
+            if index & HARDENED_BIT != 0 {
+                return Err(eyre!("DERIVATION_PATH_INDEX_TOO_LARGE"));
+            }
+            Ok(if hardened { index | HARDENED_BIT } else { index })
+        })
+        .collect::<Result<Vec<u32>>>()?;
+    if path.is_empty() || path.len() > 10 {
+        return Err(eyre!("INVALID_DERIVATION_PATH_LENGTH"));
+    }
+    Ok(path)
+}
+
+// Path length byte followed by the big endian indexes
+fn encode_derivation_path(path: &[u32]) -> Vec<u8> {
+    let mut data = vec![path.len() as u8];
+    for index in path {
+        data.extend_from_slice(&index.to_be_bytes());
+    }
+    data
+}
+
+/// Signs with the key of the Ledger device at the derivation path, the private key never leaves the device. Every exchange
+/// blocks until the device answers, the transaction may need a confirmation on the device, so `sign` runs on the blocking pool.
+#[derive(Clone)]
+pub struct TxSignerLedger {
+    address: Address,
+    derivation_path: String,
+    path: Vec<u32>,
+    transport: Arc<Mutex<Box<dyn LedgerTransport>>>,
+}
+
+impl fmt::Debug for TxSignerLedger {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TxSignerLedger")
+            .field("address", &self.address.to_string())
+            .field("derivation_path", &self.derivation_path)
+            .finish()
+    }
+}
+
+impl TxSignerLedger {
+    /// Load the address of the derivation path from the device
+    pub fn new(transport: Box<dyn LedgerTransport>, derivation_path: &str) -> Result<Self> {
+        let path = parse_derivation_path(derivation_path)?;
+        let mut signer =
+            Self { address: Address::ZERO, derivation_path: derivation_path.to_string(), path, transport: Arc::new(Mutex::new(transport)) };
+        signer.address = signer.get_address()?;
+        debug!(address = %signer.address, derivation_path, "Ledger signer address loaded");
+        Ok(signer)
+    }
+
+    fn exchange(transport: &mut dyn LedgerTransport, ins: u8, p1: u8, data: Vec<u8>) -> Result<Vec<u8>> {
+        let (response, status) = transport.exchange(&LedgerCommand { cla: LEDGER_CLA, ins, p1, p2: 0x00, data })?;
+        match status {
+            LEDGER_STATUS_OK => Ok(response),
+            LEDGER_STATUS_USER_REJECTED => Err(eyre!("LEDGER_USER_REJECTED")),
+            status => {
+                error!("Ledger command {:#04x} failed with status {:#06x}", ins, status);
+                Err(eyre!("LEDGER_COMMAND_FAILED"))
+            }
+        }
+    }
+
+    // Response is the public key length, the public key, the address length and the hex address
+    fn get_address(&self) -> Result<Address> {
+        let mut transport = self.transport.lock().map_err(|_| eyre!("LEDGER_TRANSPORT_POISONED"))?;
+        let response = Self::exchange(transport.as_mut(), LEDGER_INS_GET_ADDRESS, 0x00, encode_derivation_path(&self.path))?;
+        let public_key_len = *response.first().ok_or_eyre("LEDGER_ADDRESS_RESPONSE_EMPTY")? as usize;
+        let address_len = *response.get(1 + public_key_len).ok_or_eyre("LEDGER_ADDRESS_RESPONSE_TOO_SHORT")? as usize;
+        let address = response.get(2 + public_key_len..2 + public_key_len + address_len).ok_or_eyre("LEDGER_ADDRESS_RESPONSE_TOO_SHORT")?;
+        std::str::from_utf8(address)?.parse().map_err(|_| eyre!("LEDGER_ADDRESS_INVALID"))
+    }
+
+    /// Send the derivation path and the encoded transaction in chunks, the device answers the last one with v, r and s
+    fn sign_transaction<T: SignableTransaction<PrimitiveSignature>>(&self, tx: &T) -> Result<PrimitiveSignature> {
+        let mut data = encode_derivation_path(&self.path);
+        data.extend(tx.encoded_for_signing());
+
+        let mut response = Vec::new();
+        {
+            let mut transport = self.transport.lock().map_err(|_| eyre!("LEDGER_TRANSPORT_POISONED"))?;
+            for (idx, chunk) in data.chunks(LEDGER_APDU_MAX_DATA_LEN).enumerate() {
+                let p1 = if idx == 0 { LEDGER_P1_FIRST_CHUNK } else { LEDGER_P1_MORE_CHUNKS };
+                response = Self::exchange(transport.as_mut(), LEDGER_INS_SIGN_TRANSACTION, p1, chunk.to_vec())?;
+            }
+        }
+
+        if response.len() != 65 {
+            return Err(eyre!("LEDGER_SIGNATURE_RESPONSE_INVALID"));
+        }
+        let y_parity = match response[0] {
+            0 | 27 => false,
+            1 | 28 => true,
+            _ => return Err(eyre!("LEDGER_SIGNATURE_V_INVALID")),
+        };
+        let signature = PrimitiveSignature::new(U256::from_be_slice(&response[1..33]), U256::from_be_slice(&response[33..65]), y_parity);
+
+        if signature.recover_address_from_prehash(&tx.signature_hash())? != self.address {
+            return Err(eyre!("LEDGER_SIGNATURE_ADDRESS_MISMATCH"));
+        }
+        Ok(signature)
+    }
+}
+
+impl LoomTxSigner<LoomDataTypesEthereum> for TxSignerLedger {
+    fn address(&self) -> <LoomDataTypesEthereum as LoomDataTypes>::Address {
+        self.address
+    }
+
+    fn sign<'a>(
+        &'a self,
+        tx_req: <LoomDataTypesEthereum as LoomDataTypes>::TransactionRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<<LoomDataTypesEthereum as LoomDataTypes>::Transaction>> + Send + 'a>> {
+        let signer = self.clone();
+        Box::pin(async move { tokio::task::spawn_blocking(move || signer.sign_sync(tx_req)).await? })
+    }
+
+    fn sign_sync(&self, tx_req: TransactionRequest) -> Result<Transaction> {
+        let typed_tx = tx_req.build_unsigned().map_err(|e| eyre!(format!("CANNOT_BUILD_UNSIGNED with error: {}", e)))?;
+
+        // Transactions with an authorization list are EIP-7702, all others must be EIP-1559
+        let tx_env: TxEnvelope = match typed_tx {
+            TypedTransaction::Eip7702(typed_tx) => {
+                let signature = self.sign_transaction(&typed_tx)?;
+                typed_tx.into_signed(signature).into()
+            }
+            typed_tx => {
+                let typed_tx = typed_tx.eip1559().ok_or_eyre("TRANSACTION_IS_NOT_EIP1559")?.clone();
+                let signature = self.sign_transaction(&typed_tx)?;
+                typed_tx.into_signed(signature).into()
+            }
+        };
+        Ok(Transaction {
+            inner: tx_env,
+            block_hash: None,
+            block_number: None,
+            transaction_index: None,
+            effective_gas_price: None,
+            from: self.address,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::{keccak256, Bytes, B256};
+    use alloy_signer::SignerSync;
+    use alloy_signer_local::PrivateKeySigner;
+    use loom_types_blockchain::LoomTx;
+
+    // Ethereum app answering with a local key, the transaction is signed once all of its bytes are received
+    struct MockLedger {
+        wallet: PrivateKeySigner,
+        path: Vec<u8>,
+        reject: bool,
+        tx_data: Vec<u8>,
+    }
+
+    impl MockLedger {
+        fn new(reject: bool) -> Self {
+            let wallet = PrivateKeySigner::from_bytes(&B256::repeat_byte(1)).unwrap();
+            let path = encode_derivation_path(&parse_derivation_path("m/44'/60'/0'/0/0").unwrap());
+            Self { wallet, path, reject, tx_data: Vec::new() }
+        }
+
+        fn tx_complete(tx_data: &[u8]) -> bool {
+            let mut payload = &tx_data[1..];
+            alloy_rlp::Header::decode(&mut payload).is_ok_and(|header| payload.len() >= header.payload_length)
+        }
+    }
+
+    impl LedgerTransport for MockLedger {
+        fn exchange(&mut self, command: &LedgerCommand) -> Result<(Vec<u8>, u16)> {
+            match (command.ins, command.p1) {
+                (LEDGER_INS_GET_ADDRESS, _) => {
+                    // Public key is not used by the signer
+                    let mut response = vec![65, 0x04];
+                    response.extend_from_slice(&[0; 64]);
+                    response.push(40);
+                    response.extend_from_slice(hex::encode(self.wallet.address()).as_bytes());
+                    Ok((response, LEDGER_STATUS_OK))
+                }
+                (LEDGER_INS_SIGN_TRANSACTION, p1) => {
+                    if p1 == LEDGER_P1_FIRST_CHUNK {
+                        assert!(command.data.starts_with(&self.path));
+                        self.tx_data = command.data[self.path.len()..].to_vec();
+                    } else {
+                        self.tx_data.extend_from_slice(&command.data);
+                    }
+                    if !Self::tx_complete(&self.tx_data) {
+                        return Ok((vec![], LEDGER_STATUS_OK));
+                    }
+                    if self.reject {
+                        return Ok((vec![], LEDGER_STATUS_USER_REJECTED));
+                    }
+                    let signature = self.wallet.sign_hash_sync(&keccak256(&self.tx_data))?;
+                    let mut response = vec![signature.v() as u8];
+                    response.extend_from_slice(&signature.r().to_be_bytes::<32>());
+                    response.extend_from_slice(&signature.s().to_be_bytes::<32>());
+                    Ok((response, LEDGER_STATUS_OK))
+                }
+                _ => Ok((vec![], 0x6d00)),
+            }
+        }
+    }
+
+    fn tx_request(input_len: usize) -> TransactionRequest {
+        TransactionRequest::default()
+            .with_to(Address::ZERO)
+            .with_nonce(1)
+            .with_gas_limit(1)
+            .with_max_fee_per_gas(1)
+            .with_max_priority_fee_per_gas(1)
+            .with_chain_id(1)
+            .with_input(Bytes::from(vec![0x11; input_len]))
+    }
+
+    #[test]
+    fn test_parse_derivation_path() {
+        assert_eq!(parse_derivation_path("m/44'/60'/0'/0/1").unwrap(), vec![0x8000_002c, 0x8000_003c, 0x8000_0000, 0, 1]);
+        assert_eq!(parse_derivation_path("m/44h/60h/0h").unwrap(), vec![0x8000_002c, 0x8000_003c, 0x8000_0000]);
+        assert!(parse_derivation_path("44'/60'/0'/0/0").is_err());
+        assert!(parse_derivation_path("m/44'/x/0").is_err());
+        assert!(parse_derivation_path("m").is_err());
+        assert_eq!(encode_derivation_path(&[0x8000_002c, 1]), vec![2, 0x80, 0, 0, 0x2c, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_ledger_sign_sync() -> Result<()> {
+        let signer = TxSignerLedger::new(Box::new(MockLedger::new(false)), "m/44'/60'/0'/0/0")?;
+        let wallet = PrivateKeySigner::from_bytes(&B256::repeat_byte(1))?;
+        assert_eq!(signer.address(), wallet.address());
+
+        // Calldata longer than one APDU is sent in several chunks
+        let tx = signer.sign_sync(tx_request(600))?;
+        let TxEnvelope::Eip1559(signed_tx) = &tx.inner else {
+            panic!("TRANSACTION_IS_NOT_EIP1559");
+        };
+        assert_eq!(signed_tx.recover_signer()?, wallet.address());
+        assert!(!tx.encode().is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ledger_sign_rejected() -> Result<()> {
+        let signer = TxSignerLedger::new(Box::new(MockLedger::new(true)), "m/44'/60'/0'/0/0")?;
+        let error = signer.sign(tx_request(10)).await.unwrap_err();
+        assert_eq!(error.to_string(), "LEDGER_USER_REJECTED");
+        Ok(())
+    }
+}
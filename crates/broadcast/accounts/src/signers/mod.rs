@@ -1,5 +1,11 @@
-pub use initialize_actor::InitializeSignersOneShotBlockingActor;
+pub use initialize_actor::{InitializeSignersOneShotBlockingActor, SignerBackend};
+#[cfg(feature = "ledger")]
+pub use ledger::LedgerHidTransport;
+pub use ledger::{open_ledger_hid_transport, parse_derivation_path, LedgerCommand, LedgerTransport, TxSignerLedger};
 pub use signers_actor::TxSignersActor;
 
+#[cfg(feature = "aws")]
+mod aws_secrets;
 mod initialize_actor;
+mod ledger;
 mod signers_actor;
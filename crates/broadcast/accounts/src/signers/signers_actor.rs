@@ -4,7 +4,7 @@ use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::broadcast::Receiver;
 use tracing::{error, info};
 
-use loom_core_actors::{Actor, ActorResult, Broadcaster, Consumer, Producer, WorkerResult};
+use loom_core_actors::{Actor, ActorResult, Broadcaster, CancellationToken, Consumer, Producer, WorkerResult};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
 
 use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum, LoomTx};
@@ -72,11 +72,15 @@ async fn sign_task<LDT: LoomDataTypes>(
 async fn request_listener_worker<LDT: LoomDataTypes>(
     compose_channel_rx: Broadcaster<MessageTxCompose<LDT>>,
     compose_channel_tx: Broadcaster<MessageTxCompose<LDT>>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult {
     let mut compose_channel_rx = compose_channel_rx.subscribe();
 
     loop {
         tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Signers actor cancelled".to_string());
+            }
             msg = compose_channel_rx.recv() => {
                 let compose_request_msg : Result<MessageTxCompose<LDT>, RecvError> = msg;
                 match compose_request_msg {
@@ -104,11 +108,12 @@ pub struct TxSignersActor<LDT: LoomDataTypes + 'static = LoomDataTypesEthereum>
     compose_channel_rx: Option<Broadcaster<MessageTxCompose<LDT>>>,
     #[producer]
     compose_channel_tx: Option<Broadcaster<MessageTxCompose<LDT>>>,
+    cancellation_token: CancellationToken,
 }
 
 impl<LDT: LoomDataTypes + 'static> Default for TxSignersActor<LDT> {
     fn default() -> Self {
-        Self { compose_channel_rx: None, compose_channel_tx: None }
+        Self { compose_channel_rx: None, compose_channel_tx: None, cancellation_token: CancellationToken::new() }
     }
 }
 
@@ -118,7 +123,11 @@ impl<LDT: LoomDataTypes> TxSignersActor<LDT> {
     }
 
     pub fn with_compose_channel(self, compose_channel: Broadcaster<MessageTxCompose<LDT>>) -> Self {
-        Self { compose_channel_rx: Some(compose_channel.clone()), compose_channel_tx: Some(compose_channel) }
+        Self { compose_channel_rx: Some(compose_channel.clone()), compose_channel_tx: Some(compose_channel), ..self }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
     }
 }
 
@@ -139,7 +148,7 @@ impl<LDT: LoomDataTypes> Actor for TxSignersActor<LDT> {
             }
         };
 
-        let task = tokio::task::spawn(request_listener_worker(compose_channel_rx, compose_channel_tx));
+        let task = tokio::task::spawn(request_listener_worker(compose_channel_rx, compose_channel_tx, self.cancellation_token.clone()));
 
         Ok(vec![task])
     }
@@ -7,7 +7,7 @@ use eyre::Result;
 use tokio::sync::broadcast::error::RecvError;
 use tracing::{error, info};
 
-use loom_core_actors::{Actor, ActorResult, Broadcaster, Consumer, WorkerResult};
+use loom_core_actors::{Actor, ActorResult, Broadcaster, CancellationToken, Consumer, WorkerResult};
 use loom_core_actors_macros::{Accessor, Consumer};
 
 use loom_node_debug_provider::AnvilProviderExt;
@@ -40,7 +40,11 @@ where
     Ok(())
 }
 
-async fn anvil_broadcaster_worker<P>(client: P, bundle_rx: Broadcaster<MessageTxCompose>) -> WorkerResult
+async fn anvil_broadcaster_worker<P>(
+    client: P,
+    bundle_rx: Broadcaster<MessageTxCompose>,
+    cancellation_token: CancellationToken,
+) -> WorkerResult
 where
     P: Provider<Ethereum> + AnvilProviderExt<Ethereum> + Send + Sync + Clone + 'static,
 {
@@ -48,6 +52,9 @@ where
 
     loop {
         tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Anvil broadcaster cancelled".to_string());
+            }
             msg = bundle_rx.recv() => {
                 let broadcast_msg : Result<MessageTxCompose,RecvError> = msg;
                 match broadcast_msg {
@@ -86,6 +93,7 @@ pub struct AnvilBroadcastActor<P, LDT: LoomDataTypes + 'static = LoomDataTypesEt
     client: P,
     #[consumer]
     tx_compose_rx: Option<Broadcaster<MessageTxCompose<LDT>>>,
+    cancellation_token: CancellationToken,
 }
 
 impl<P> AnvilBroadcastActor<P>
@@ -93,12 +101,16 @@ where
     P: Provider<Ethereum> + AnvilProviderExt<Ethereum> + Send + Sync + Clone + 'static,
 {
     pub fn new(client: P) -> AnvilBroadcastActor<P> {
-        Self { client, tx_compose_rx: None }
+        Self { client, tx_compose_rx: None, cancellation_token: CancellationToken::new() }
     }
 
     pub fn with_compose_channel(self, tx_compose_rx: Broadcaster<MessageTxCompose<LoomDataTypesEthereum>>) -> Self {
         Self { tx_compose_rx: Some(tx_compose_rx), ..self }
     }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
 }
 
 impl<P> Actor for AnvilBroadcastActor<P>
@@ -106,7 +118,11 @@ where
     P: Provider<Ethereum> + AnvilProviderExt<Ethereum> + Send + Sync + Clone + 'static,
 {
     fn start(&self) -> ActorResult {
-        let task = tokio::task::spawn(anvil_broadcaster_worker(self.client.clone(), self.tx_compose_rx.clone().unwrap()));
+        let task = tokio::task::spawn(anvil_broadcaster_worker(
+            self.client.clone(),
+            self.tx_compose_rx.clone().unwrap(),
+            self.cancellation_token.clone(),
+        ));
         Ok(vec![task])
     }
 
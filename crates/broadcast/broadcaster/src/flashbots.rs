@@ -5,20 +5,55 @@ use alloy_primitives::Bytes;
 use alloy_provider::Provider;
 use eyre::{eyre, Result};
 use tokio::sync::broadcast::error::RecvError;
-use tracing::error;
+use tracing::{debug, error, warn};
 
 use loom_broadcast_flashbots::Flashbots;
-use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, Consumer, WorkerResult};
-use loom_core_actors_macros::{Accessor, Consumer};
+use loom_core_actors::{
+    subscribe, Accessor, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, Producer, SharedState, WorkerResult,
+};
+use loom_core_actors_macros::{Accessor, Consumer, Producer};
 
-use loom_types_events::{MessageTxCompose, RlpState, TxComposeData, TxComposeMessageType};
+use loom_types_blockchain::Mempool;
+use loom_types_events::{HealthEvent, MessageHealthEvent, MessageTxCompose, RlpState, TxComposeData, TxComposeMessageType};
 
-async fn broadcast_task<P>(broadcast_request: TxComposeData, client: Arc<Flashbots<P>>) -> Result<()>
+/// Rough inclusion estimate, every conflicting pending tx competes for the same sender nonce
+fn inclusion_probability(conflicts_count: usize) -> f64 {
+    1.0 / (1.0 + conflicts_count as f64)
+}
+
+async fn broadcast_task<P>(
+    broadcast_request: TxComposeData,
+    client: Arc<Flashbots<P>>,
+    mempool: Option<SharedState<Mempool>>,
+    health_monitor_channel_tx: Option<Broadcaster<MessageHealthEvent>>,
+    skip_simulation: bool,
+) -> Result<()>
 where
     P: Provider<Ethereum> + Send + Sync + Clone + 'static,
 {
     let block_number = broadcast_request.next_block_number;
 
+    if let Some(mempool) = mempool {
+        let conflicts = mempool.read().await.get_bundle_conflict_candidates(&broadcast_request.stuffing_txs_hashes);
+        if !conflicts.is_empty() {
+            let inclusion_probability = inclusion_probability(conflicts.len());
+            warn!(
+                block_number,
+                conflicts = conflicts.len(),
+                inclusion_probability,
+                tips = ?broadcast_request.tips,
+                "Bundle conflicts with pending transactions"
+            );
+            // The bundle is already signed here, the gas auction raises the tip of the next bundles
+            if let Some(health_monitor_channel_tx) = &health_monitor_channel_tx {
+                let health_event = HealthEvent::BundleConflicts { block_number, conflicts: conflicts.len(), inclusion_probability };
+                if let Err(e) = health_monitor_channel_tx.send(MessageHealthEvent::new(health_event)) {
+                    error!("Failed to send health event : {}", e);
+                }
+            }
+        }
+    }
+
     if let Some(rlp_bundle) = broadcast_request.rlp_bundle.clone() {
         let stuffing_rlp_bundle: Vec<Bytes> = rlp_bundle.iter().map(|item| item.unwrap()).collect();
         let backrun_rlp_bundle: Vec<Bytes> =
@@ -27,6 +62,29 @@ where
         if stuffing_rlp_bundle.iter().any(|i| i.is_empty()) || backrun_rlp_bundle.iter().any(|i| i.is_empty()) {
             Err(eyre!("RLP_BUNDLE_IS_INCORRECT"))
         } else {
+            if !skip_simulation {
+                // A failed simulation request does not block the broadcast, only a revert does
+                match client.simulate_bundle(&stuffing_rlp_bundle, block_number).await {
+                    Ok(sim_result) if !sim_result.success => {
+                        warn!(block_number, revert_reason = ?sim_result.revert_reason, "Bundle reverted in simulation, not broadcasting");
+                        if let Some(health_monitor_channel_tx) = health_monitor_channel_tx {
+                            let health_event =
+                                HealthEvent::BundleRevertedInSimulation { block_number, revert_reason: sim_result.revert_reason };
+                            if let Err(e) = health_monitor_channel_tx.send(MessageHealthEvent::new(health_event)) {
+                                error!("Failed to send health event : {}", e);
+                            }
+                        }
+                        return Err(eyre!("BUNDLE_REVERTED_IN_SIMULATION"));
+                    }
+                    Ok(sim_result) => {
+                        debug!(block_number, gas_used = sim_result.gas_used, coinbase_diff = %sim_result.coinbase_diff, "Bundle simulated");
+                    }
+                    Err(e) => {
+                        warn!(block_number, "Bundle simulation failed, broadcasting anyway : {}", e);
+                    }
+                }
+            }
+
             client.broadcast_txes(backrun_rlp_bundle.clone(), block_number).await?;
             client.broadcast_txes(stuffing_rlp_bundle.clone(), block_number).await?;
 
@@ -41,7 +99,11 @@ where
 async fn flashbots_broadcaster_worker<P>(
     client: Arc<Flashbots<P>>,
     bundle_rx: Broadcaster<MessageTxCompose>,
+    mempool: Option<SharedState<Mempool>>,
+    health_monitor_channel_tx: Option<Broadcaster<MessageHealthEvent>>,
     allow_broadcast: bool,
+    skip_simulation: bool,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult
 where
     P: Provider<Ethereum> + Send + Sync + Clone + 'static,
@@ -52,6 +114,9 @@ where
 
     loop {
         tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Flashbots broadcaster cancelled".to_string());
+            }
             msg = bundle_rx.recv() => {
                 let broadcast_msg : Result<MessageTxCompose, RecvError> = msg;
                 match broadcast_msg {
@@ -62,6 +127,9 @@ where
                                         broadcast_task(
                                             broadcast_request,
                                             client.clone(),
+                                            mempool.clone(),
+                                            health_monitor_channel_tx.clone(),
+                                            skip_simulation,
                                         )
                                     );
                                 }
@@ -109,12 +177,21 @@ where
     }
 }
 
-#[derive(Accessor, Consumer)]
+/// Broadcasts bundles to the Flashbots relays. Bundles are simulated with `eth_callBundle` first unless `skip_simulation` is set,
+/// reverting bundles are reported with `HealthEvent::BundleRevertedInSimulation` instead of being broadcast. Bundles competing with
+/// pending mempool transactions are reported with `HealthEvent::BundleConflicts`.
+#[derive(Accessor, Consumer, Producer)]
 pub struct FlashbotsBroadcastActor<P> {
     client: Arc<Flashbots<P>>,
     #[consumer]
     tx_compose_channel_rx: Option<Broadcaster<MessageTxCompose>>,
+    #[accessor]
+    mempool: Option<SharedState<Mempool>>,
+    #[producer]
+    health_monitor_channel_tx: Option<Broadcaster<MessageHealthEvent>>,
     allow_broadcast: bool,
+    skip_simulation: bool,
+    cancellation_token: CancellationToken,
 }
 
 impl<P> FlashbotsBroadcastActor<P>
@@ -122,12 +199,37 @@ where
     P: Provider<Ethereum> + Send + Sync + Clone + 'static,
 {
     pub fn new(client: Arc<Flashbots<P>>, allow_broadcast: bool) -> FlashbotsBroadcastActor<P> {
-        FlashbotsBroadcastActor { client, tx_compose_channel_rx: None, allow_broadcast }
+        FlashbotsBroadcastActor {
+            client,
+            tx_compose_channel_rx: None,
+            mempool: None,
+            health_monitor_channel_tx: None,
+            allow_broadcast,
+            skip_simulation: false,
+            cancellation_token: CancellationToken::new(),
+        }
     }
 
     pub fn with_compose_channel(self, tx_compose_channel_rx: Broadcaster<MessageTxCompose>) -> Self {
         Self { tx_compose_channel_rx: Some(tx_compose_channel_rx), ..self }
     }
+
+    pub fn with_mempool(self, mempool: SharedState<Mempool>) -> Self {
+        Self { mempool: Some(mempool), ..self }
+    }
+
+    pub fn with_health_monitor_channel(self, health_monitor_channel_tx: Broadcaster<MessageHealthEvent>) -> Self {
+        Self { health_monitor_channel_tx: Some(health_monitor_channel_tx), ..self }
+    }
+
+    /// Broadcast without simulating the bundle first, saves a relay round trip in latency sensitive setups
+    pub fn with_skip_simulation(self, skip_simulation: bool) -> Self {
+        Self { skip_simulation, ..self }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
 }
 
 impl<P> Actor for FlashbotsBroadcastActor<P>
@@ -138,7 +240,11 @@ where
         let task = tokio::task::spawn(flashbots_broadcaster_worker(
             self.client.clone(),
             self.tx_compose_channel_rx.clone().unwrap(),
+            self.mempool.clone(),
+            self.health_monitor_channel_tx.clone(),
             self.allow_broadcast,
+            self.skip_simulation,
+            self.cancellation_token.clone(),
         ));
         Ok(vec![task])
     }
@@ -1,5 +1,7 @@
 pub use anvil::AnvilBroadcastActor;
 pub use flashbots::FlashbotsBroadcastActor;
+pub use private_mempool::PrivateMempoolBroadcastActor;
 
 mod anvil;
 mod flashbots;
+mod private_mempool;
@@ -0,0 +1,130 @@
+use std::sync::Arc;
+
+use alloy_primitives::Bytes;
+use eyre::{eyre, Result};
+use futures::future::join_all;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{error, info};
+
+use loom_broadcast_flashbots::{PrivateRelay, PrivateRelayConfig};
+use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, WorkerResult};
+use loom_core_actors_macros::Consumer;
+use loom_types_events::{MessageTxCompose, TxComposeData, TxComposeMessageType};
+
+async fn broadcast_task(broadcast_request: TxComposeData, relays: Arc<Vec<PrivateRelay>>) -> Result<()> {
+    let block_number = broadcast_request.next_block_number;
+
+    let Some(rlp_bundle) = broadcast_request.rlp_bundle else {
+        error!("rlp_bundle is None");
+        return Err(eyre!("RLP_BUNDLE_IS_NONE"));
+    };
+
+    let bundle: Vec<Bytes> = rlp_bundle.iter().map(|item| item.unwrap()).collect();
+
+    if bundle.iter().any(|i| i.is_empty()) {
+        return Err(eyre!("RLP_BUNDLE_IS_INCORRECT"));
+    }
+
+    // The whole bundle is sent so a backrun is only included right after the victim transaction it targets
+    let results = join_all(relays.iter().map(|relay| {
+        let txs = bundle.clone();
+        async move { (relay.name(), relay.send_transactions(txs, block_number).await) }
+    }))
+    .await;
+
+    for (name, result) in results {
+        match result {
+            Ok(_) => info!(block_number, relay = name, "Transactions sent to private relay"),
+            Err(e) => error!(block_number, relay = name, "Private relay error : {}", e),
+        }
+    }
+    Ok(())
+}
+
+async fn private_mempool_broadcaster_worker(
+    relays: Arc<Vec<PrivateRelay>>,
+    bundle_rx: Broadcaster<MessageTxCompose>,
+    allow_broadcast: bool,
+    cancellation_token: CancellationToken,
+) -> WorkerResult {
+    subscribe!(bundle_rx);
+
+    loop {
+        let broadcast_msg: Result<MessageTxCompose, RecvError> = tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Private mempool broadcaster cancelled".to_string());
+            }
+            msg = bundle_rx.recv() => msg,
+        };
+        match broadcast_msg {
+            Ok(compose_request) => {
+                if let TxComposeMessageType::Broadcast(broadcast_request) = compose_request.inner {
+                    if allow_broadcast {
+                        tokio::task::spawn(broadcast_task(broadcast_request, relays.clone()));
+                    }
+                }
+            }
+            Err(RecvError::Closed) => {
+                error!("Tx compose channel closed");
+                break Err(eyre!("TX_COMPOSE_RX_CLOSED"));
+            }
+            Err(e) => {
+                error!("private_mempool_broadcaster_worker {}", e)
+            }
+        }
+    }
+}
+
+/// Broadcasts signed transactions to private relays like MEV Blocker and BloxRoute
+#[derive(Consumer)]
+pub struct PrivateMempoolBroadcastActor {
+    relays: Arc<Vec<PrivateRelay>>,
+    #[consumer]
+    tx_compose_channel_rx: Option<Broadcaster<MessageTxCompose>>,
+    allow_broadcast: bool,
+    cancellation_token: CancellationToken,
+}
+
+impl PrivateMempoolBroadcastActor {
+    pub fn new(relays: Vec<PrivateRelayConfig>, allow_broadcast: bool) -> PrivateMempoolBroadcastActor {
+        PrivateMempoolBroadcastActor {
+            relays: Arc::new(relays.into_iter().map(PrivateRelay::new).collect()),
+            tx_compose_channel_rx: None,
+            allow_broadcast,
+            cancellation_token: CancellationToken::new(),
+        }
+    }
+
+    /// Relay requests are sent with `http_client`, e.g. one with a configured DNS resolver
+    pub fn with_http_client(self, http_client: reqwest::Client) -> Self {
+        let relays = self.relays.iter().map(|relay| PrivateRelay::with_http_client(relay.config().clone(), http_client.clone())).collect();
+        Self { relays: Arc::new(relays), ..self }
+    }
+
+    pub fn with_compose_channel(self, tx_compose_channel_rx: Broadcaster<MessageTxCompose>) -> Self {
+        Self { tx_compose_channel_rx: Some(tx_compose_channel_rx), ..self }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+}
+
+impl Actor for PrivateMempoolBroadcastActor {
+    fn start(&self) -> ActorResult {
+        if self.relays.is_empty() {
+            return Err(eyre!("NO_PRIVATE_RELAYS"));
+        }
+        let task = tokio::task::spawn(private_mempool_broadcaster_worker(
+            self.relays.clone(),
+            self.tx_compose_channel_rx.clone().unwrap(),
+            self.allow_broadcast,
+            self.cancellation_token.clone(),
+        ));
+        Ok(vec![task])
+    }
+
+    fn name(&self) -> &'static str {
+        "PrivateMempoolBroadcastActor"
+    }
+}
@@ -23,6 +23,16 @@ pub enum BundleTransaction {
     Raw(Bytes),
 }
 
+impl BundleTransaction {
+    /// EIP-2718 encoded transaction
+    pub fn raw(&self) -> Bytes {
+        match self {
+            BundleTransaction::Signed(inner) => Bytes::from(inner.inner.encoded_2718()),
+            BundleTransaction::Raw(inner) => inner.clone(),
+        }
+    }
+}
+
 impl From<TxEnvelope> for BundleTransaction {
     fn from(tx: TxEnvelope) -> Self {
         let rlp = tx.encoded_2718();
@@ -93,16 +103,7 @@ pub fn serialize_txs<S>(txs: &[BundleTransaction], s: S) -> Result<S::Ok, S::Err
 where
     S: Serializer,
 {
-    let raw_txs: Result<Vec<Bytes>> = txs
-        .iter()
-        .map(|tx| match tx {
-            BundleTransaction::Signed(inner) => {
-                let tx = inner.as_ref().clone();
-                Ok(Bytes::from(tx.inner.encoded_2718()))
-            }
-            BundleTransaction::Raw(inner) => Ok(inner.clone()),
-        })
-        .collect();
+    let raw_txs: Result<Vec<Bytes>> = txs.iter().map(|tx| Ok(tx.raw())).collect();
 
     raw_txs.map_err(S::Error::custom)?.serialize(s)
 }
@@ -410,6 +411,33 @@ impl SimulatedBundle {
     }
 }
 
+/// Outcome of a bundle simulation.
+///
+/// A bundle is successful if none of its transactions failed or reverted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleSimResult {
+    /// Whether all transactions of the bundle succeeded.
+    pub success: bool,
+    /// The difference in coinbase's balance due to this bundle.
+    pub coinbase_diff: U256,
+    /// The total amount of gas used in this bundle.
+    pub gas_used: u64,
+    /// The revert reason or error of the first failed transaction.
+    pub revert_reason: Option<String>,
+}
+
+impl From<&SimulatedBundle> for BundleSimResult {
+    fn from(simulated_bundle: &SimulatedBundle) -> Self {
+        let revert_reason = simulated_bundle.transactions.iter().find_map(|tx| tx.revert.clone().or_else(|| tx.error.clone()));
+        BundleSimResult {
+            success: revert_reason.is_none(),
+            coinbase_diff: simulated_bundle.coinbase_diff,
+            gas_used: simulated_bundle.gas_used.saturating_to(),
+            revert_reason,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
@@ -522,6 +550,12 @@ mod tests {
         assert_eq!(simulated_bundle.transactions[1].error, None);
         assert_eq!(simulated_bundle.transactions[1].value, Some(Bytes::from(vec![0x1])));
         assert_eq!(simulated_bundle.transactions[2].to, None);
+
+        let sim_result = BundleSimResult::from(&simulated_bundle);
+        assert!(!sim_result.success);
+        assert_eq!(sim_result.coinbase_diff, U256::from(20000000000126000u64));
+        assert_eq!(sim_result.gas_used, 42000);
+        assert_eq!(sim_result.revert_reason, Some("execution reverted".into()));
     }
 
     #[test]
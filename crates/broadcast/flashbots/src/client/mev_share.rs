@@ -0,0 +1,168 @@
+use alloy_primitives::{Address, Bytes, TxHash, U64};
+use serde::{Serialize, Serializer};
+
+/// Transaction of a MEV Share bundle
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum MevShareBundleItem {
+    /// Pending transaction shared by the MEV Share node, known only by its hash
+    #[serde(rename_all = "camelCase")]
+    Hash { hash: TxHash },
+    /// Own EIP-2718 encoded signed transaction
+    #[serde(rename_all = "camelCase")]
+    Tx { tx: Bytes, can_revert: bool },
+}
+
+/// Data of the bundle transactions shared with searchers when the bundle is forwarded by the MEV Share node
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MevShareHints {
+    pub calldata: bool,
+    pub contract_address: bool,
+    pub function_selector: bool,
+    pub logs: bool,
+    pub tx_hash: bool,
+    pub hash: bool,
+}
+
+impl MevShareHints {
+    /// Hint names of the `privacy.hints` field
+    pub fn to_hints(&self) -> Vec<&'static str> {
+        [
+            (self.calldata, "calldata"),
+            (self.contract_address, "contract_address"),
+            (self.function_selector, "function_selector"),
+            (self.logs, "logs"),
+            (self.tx_hash, "tx_hash"),
+            (self.hash, "hash"),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, hint)| enabled.then_some(hint))
+        .collect()
+    }
+}
+
+/// Share of the bundle value refunded to the sender of the transaction at `body_idx`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Refund {
+    pub body_idx: u64,
+    pub percent: u64,
+}
+
+/// Share of the own refund paid to `address`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefundConfig {
+    pub address: Address,
+    pub percent: u64,
+}
+
+/// Refund requirements the bundle has to satisfy to be included
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleValidity {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub refund: Vec<Refund>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub refund_config: Vec<RefundConfig>,
+}
+
+/// Bundle submitted with `mev_sendBundle` to a MEV Share node, it can backrun pending transactions shared by their hash
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MevShareBundle {
+    pub transactions: Vec<MevShareBundleItem>,
+    pub block_number: u64,
+    pub hints: MevShareHints,
+    pub validity: BundleValidity,
+}
+
+impl MevShareBundle {
+    pub fn new(block_number: u64) -> Self {
+        Self { block_number, ..Self::default() }
+    }
+
+    pub fn with_hints(self, hints: MevShareHints) -> Self {
+        Self { hints, ..self }
+    }
+
+    pub fn with_validity(self, validity: BundleValidity) -> Self {
+        Self { validity, ..self }
+    }
+
+    pub fn push_hash(mut self, hash: TxHash) -> Self {
+        self.transactions.push(MevShareBundleItem::Hash { hash });
+        self
+    }
+
+    pub fn push_transaction(mut self, tx: Bytes, can_revert: bool) -> Self {
+        self.transactions.push(MevShareBundleItem::Tx { tx, can_revert });
+        self
+    }
+}
+
+#[derive(Serialize)]
+struct Inclusion {
+    block: U64,
+}
+
+#[derive(Serialize)]
+struct Privacy {
+    hints: Vec<&'static str>,
+}
+
+#[derive(Serialize)]
+struct SendBundleRequest<'a> {
+    version: &'static str,
+    inclusion: Inclusion,
+    body: &'a [MevShareBundleItem],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    validity: Option<&'a BundleValidity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    privacy: Option<Privacy>,
+}
+
+impl Serialize for MevShareBundle {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let hints = self.hints.to_hints();
+        let request = SendBundleRequest {
+            version: "v0.1",
+            inclusion: Inclusion { block: U64::from(self.block_number) },
+            body: &self.transactions,
+            validity: (self.validity != BundleValidity::default()).then_some(&self.validity),
+            privacy: (!hints.is_empty()).then_some(Privacy { hints }),
+        };
+        request.serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mev_share_bundle_serialize() {
+        let bundle = MevShareBundle::new(2)
+            .push_hash(TxHash::repeat_byte(1))
+            .push_transaction(Bytes::from(vec![0x2]), false)
+            .with_hints(MevShareHints { calldata: true, logs: true, ..MevShareHints::default() })
+            .with_validity(BundleValidity {
+                refund: vec![Refund { body_idx: 0, percent: 90 }],
+                refund_config: vec![RefundConfig { address: Address::repeat_byte(3), percent: 100 }],
+            });
+
+        assert_eq!(
+            serde_json::to_string(&bundle).unwrap(),
+            r#"{"version":"v0.1","inclusion":{"block":"0x2"},"body":[{"hash":"0x0101010101010101010101010101010101010101010101010101010101010101"},{"tx":"0x02","canRevert":false}],"validity":{"refund":[{"bodyIdx":0,"percent":90}],"refundConfig":[{"address":"0x0303030303030303030303030303030303030303","percent":100}]},"privacy":{"hints":["calldata","logs"]}}"#
+        );
+    }
+
+    #[test]
+    fn mev_share_bundle_serialize_without_hints() {
+        let bundle = MevShareBundle::new(16).push_transaction(Bytes::from(vec![0x1]), true);
+
+        assert_eq!(
+            serde_json::to_string(&bundle).unwrap(),
+            r#"{"version":"v0.1","inclusion":{"block":"0x10"},"body":[{"tx":"0x01","canRevert":true}]}"#
+        );
+    }
+}
@@ -60,6 +60,11 @@ where
         Self { provider, relay: Relay::new(relay_url, None), simulation_relay: None }
     }
 
+    /// Initialize a new Flashbots middleware sending relay requests with `client`.
+    pub fn with_http_client(relay_url: impl Into<Url>, provider: P, signer: Option<PrivateKeySigner>, client: reqwest::Client) -> Self {
+        Self { provider, relay: Relay::with_http_client(relay_url, signer, client), simulation_relay: None }
+    }
+
     /// Get the relay client used by the middleware.
     pub fn relay(&self) -> &Relay {
         &self.relay
@@ -4,8 +4,9 @@
 //! [Flashbots](https://docs.flashbots.net) bundles.
 //!
 pub use body::make_signed_body;
-pub use bundle::{BundleHash, BundleRequest, BundleTransaction, SimulatedBundle, SimulatedTransaction};
+pub use bundle::{BundleHash, BundleRequest, BundleSimResult, BundleTransaction, SimulatedBundle, SimulatedTransaction};
 pub use jsonrpc::SendBundleResponseType;
+pub use mev_share::{BundleValidity, MevShareBundle, MevShareBundleItem, MevShareHints, Refund, RefundConfig};
 pub use middleware::{FlashbotsMiddleware, FlashbotsMiddlewareError};
 pub use relay::{Relay, RelayConfig, RelayError};
 
@@ -14,6 +15,7 @@ mod bundle;
 mod middleware;
 
 mod jsonrpc;
+mod mev_share;
 mod relay;
 
 mod body;
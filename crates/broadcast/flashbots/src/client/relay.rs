@@ -62,9 +62,11 @@ pub enum RelayError {
 impl Relay {
     /// Initializes a new relay client.
     pub fn new(url: impl Into<Url>, signer: Option<PrivateKeySigner>) -> Self {
-        //let client = Client::builder().trust_dns(true).build().unwrap();
-        let client = Client::new();
+        Self::with_http_client(url, signer, Client::new())
+    }
 
+    /// Initializes a new relay client sending requests with `client`, e.g. one with a configured DNS resolver.
+    pub fn with_http_client(url: impl Into<Url>, signer: Option<PrivateKeySigner>, client: Client) -> Self {
         Self { id: Arc::new(AtomicU64::new(0)), client, url: url.into(), signer }
     }
 
@@ -0,0 +1,76 @@
+use alloy_primitives::{Address, Bytes, U64};
+use eyre::{eyre, Result};
+use reqwest::Client;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::debug;
+
+pub const EDEN_BUNDLE_URL: &str = "https://api.edennetwork.io/v1/bundle";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EdenBundleParams {
+    txs: Vec<Bytes>,
+    block_number: U64,
+    staker_address: Address,
+}
+
+#[derive(Serialize)]
+struct EdenBundleRequest {
+    jsonrpc: &'static str,
+    id: u64,
+    method: &'static str,
+    params: [EdenBundleParams; 1],
+}
+
+/// Eden Network bundle relay. Bundles are authenticated with an API key header instead of a Flashbots signature
+/// and carry the EDEN staker address to get priority ordering.
+pub struct EdenRelay {
+    req_id: AtomicU64,
+    client: Client,
+    url: String,
+    staker_address: Address,
+    api_key: String,
+}
+
+impl EdenRelay {
+    pub fn new(staker_address: Address, api_key: String) -> Self {
+        Self::with_http_client(staker_address, api_key, Client::new())
+    }
+
+    pub fn with_http_client(staker_address: Address, api_key: String, client: Client) -> Self {
+        Self { req_id: AtomicU64::new(0), client, url: EDEN_BUNDLE_URL.to_string(), staker_address, api_key }
+    }
+
+    pub fn name(&self) -> &str {
+        self.url.as_str()
+    }
+
+    pub async fn send_bundle(&self, txs: Vec<Bytes>, target_block: u64) -> Result<()> {
+        let req_id = self.req_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let request = EdenBundleRequest {
+            jsonrpc: "2.0",
+            id: req_id,
+            method: "eth_sendBundle",
+            params: [EdenBundleParams { txs, block_number: U64::from(target_block), staker_address: self.staker_address }],
+        };
+
+        let response = self
+            .client
+            .post(self.url.as_str())
+            .header("Content-Type", "application/json")
+            .header("X-Api-Key", self.api_key.as_str())
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| eyre!("Eden request error: {}", e))?;
+
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(eyre!("EDEN_RELAY_ERROR {} {}", status, text));
+        }
+        debug!("Eden response: {}", text);
+        Ok(())
+    }
+}
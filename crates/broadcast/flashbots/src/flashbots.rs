@@ -1,12 +1,14 @@
 use crate::client::{
-    make_signed_body, BundleRequest, BundleTransaction, FlashbotsMiddleware, FlashbotsMiddlewareError, RelayConfig, SendBundleResponseType,
-    SimulatedBundle,
+    make_signed_body, BundleRequest, BundleSimResult, BundleTransaction, FlashbotsMiddleware, FlashbotsMiddlewareError, MevShareBundle,
+    RelayConfig, SendBundleResponseType, SimulatedBundle,
 };
+use crate::eden::{EdenRelay, EDEN_BUNDLE_URL};
 use alloy_network::Ethereum;
-use alloy_primitives::{TxHash, U64};
+use alloy_primitives::{Address, Bytes, TxHash, U64};
 use alloy_provider::Provider;
 use alloy_signer_local::PrivateKeySigner;
 use eyre::{eyre, Result};
+use reqwest::Client;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tracing::{debug, error, info};
@@ -38,6 +40,14 @@ where
         FlashbotsClient { flashbots_middleware: flashbots_client, name }
     }
 
+    /// Relay client sending requests with `http_client`, requests are signed with a random key if `sign` is set
+    pub fn with_http_client(provider: P, url: &str, sign: bool, http_client: Client) -> Self {
+        let signer = sign.then(PrivateKeySigner::random);
+        let flashbots_middleware = FlashbotsMiddleware::with_http_client(Url::parse(url).unwrap(), provider, signer, http_client);
+
+        FlashbotsClient { flashbots_middleware, name: url.to_string() }
+    }
+
     fn create_flashbots_middleware(provider: P, url: &str) -> FlashbotsMiddleware<P> {
         let flashbots: FlashbotsMiddleware<P> = FlashbotsMiddleware::new(Url::parse(url).unwrap(), provider);
 
@@ -59,6 +69,17 @@ where
         }
     }
 
+    /// Simulate the bundle with `eth_callBundle` of the relay
+    pub async fn call_bundle_relay(&self, request: &BundleRequest) -> Result<SimulatedBundle> {
+        match self.flashbots_middleware.relay().request("eth_callBundle", [request]).await {
+            Ok(x) => Ok(x),
+            Err(e) => {
+                error!("{} {}", self.name, e);
+                Err(eyre!("FLASHBOTS_RELAY_ERROR"))
+            }
+        }
+    }
+
     #[allow(dead_code)]
     pub async fn send_bundle(&self, request: &BundleRequest) -> Result<()> {
         match self.flashbots_middleware.send_bundle(request).await {
@@ -101,8 +122,11 @@ pub struct Flashbots<P> {
     req_id: AtomicU64,
     signer: PrivateKeySigner,
     provider: P,
+    http_client: Client,
     simulation_client: FlashbotsClient<P>,
     clients: Vec<Arc<FlashbotsClient<P>>>,
+    eden_relay: Option<Arc<EdenRelay>>,
+    mev_share_client: Option<Arc<FlashbotsClient<P>>>,
 }
 
 impl<P> Flashbots<P>
@@ -110,32 +134,52 @@ where
     P: Provider<Ethereum> + Send + Sync + Clone + 'static,
 {
     pub fn new(provider: P, simulation_endpoint: &str, signer: Option<PrivateKeySigner>) -> Self {
+        Self::new_with_http_client(provider, simulation_endpoint, signer, Client::new())
+    }
+
+    /// Relay requests are sent with `http_client`, e.g. one with a configured DNS resolver
+    pub fn new_with_http_client(provider: P, simulation_endpoint: &str, signer: Option<PrivateKeySigner>, http_client: Client) -> Self {
         let signer = signer.unwrap_or(PrivateKeySigner::random());
-        let simulation_client = FlashbotsClient::new(provider.clone(), simulation_endpoint);
+        let simulation_client = FlashbotsClient::with_http_client(provider.clone(), simulation_endpoint, true, http_client.clone());
+
+        Flashbots {
+            req_id: AtomicU64::new(0),
+            signer,
+            provider,
+            http_client,
+            clients: vec![],
+            simulation_client,
+            eden_relay: None,
+            mev_share_client: None,
+        }
+    }
 
-        Flashbots { req_id: AtomicU64::new(0), signer, provider, clients: vec![], simulation_client }
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    fn relay_client(&self, url: &str, sign: bool) -> FlashbotsClient<P> {
+        FlashbotsClient::with_http_client(self.provider.clone(), url, sign, self.http_client.clone())
     }
 
     pub fn with_default_relays(self) -> Self {
-        let provider = self.provider.clone();
-
-        let flashbots = FlashbotsClient::new(provider.clone(), "https://relay.flashbots.net");
-        let beaverbuild = FlashbotsClient::new(provider.clone(), "https://rpc.beaverbuild.org/");
-        let titan = FlashbotsClient::new(provider.clone(), "https://rpc.titanbuilder.xyz");
-        let rsync = FlashbotsClient::new(provider.clone(), "https://rsync-builder.xyz");
-        //let builder0x69 = FlashbotsClient::new_no_sign(provider.clone(), "https://builder0x69.io");
-        let eden = FlashbotsClient::new(provider.clone(), "https://api.edennetwork.io/v1/bundle");
-        let eth_builder = FlashbotsClient::new_no_sign(provider.clone(), "https://eth-builder.com");
-        let secureapi = FlashbotsClient::new_no_sign(provider.clone(), "https://api.securerpc.com/v1");
-        //let blocknative = FlashbotsClient::new(provider.clone(), "https://api.blocknative.com/v1/auction");
-        let buildai = FlashbotsClient::new_no_sign(provider.clone(), "https://BuildAI.net");
-        let payloadde = FlashbotsClient::new_no_sign(provider.clone(), "https://rpc.payload.de");
-        let fibio = FlashbotsClient::new(provider.clone(), "https://rpc.f1b.io");
-        let loki = FlashbotsClient::new(provider.clone(), "https://rpc.lokibuilder.xyz");
-        let ibuilder = FlashbotsClient::new(provider.clone(), "https://rpc.ibuilder.xyz");
-        let jetbuilder = FlashbotsClient::new(provider.clone(), "https://rpc.jetbldr.xyz");
-        let penguinbuilder = FlashbotsClient::new(provider.clone(), "https://rpc.penguinbuild.org");
-        let gambitbuilder = FlashbotsClient::new(provider.clone(), "https://builder.gmbit.co/rpc");
+        let flashbots = self.relay_client("https://relay.flashbots.net", true);
+        let beaverbuild = self.relay_client("https://rpc.beaverbuild.org/", true);
+        let titan = self.relay_client("https://rpc.titanbuilder.xyz", true);
+        let rsync = self.relay_client("https://rsync-builder.xyz", true);
+        //let builder0x69 = self.relay_client("https://builder0x69.io", false);
+        let eden = self.relay_client("https://api.edennetwork.io/v1/bundle", true);
+        let eth_builder = self.relay_client("https://eth-builder.com", false);
+        let secureapi = self.relay_client("https://api.securerpc.com/v1", false);
+        //let blocknative = self.relay_client("https://api.blocknative.com/v1/auction", true);
+        let buildai = self.relay_client("https://BuildAI.net", false);
+        let payloadde = self.relay_client("https://rpc.payload.de", false);
+        let fibio = self.relay_client("https://rpc.f1b.io", true);
+        let loki = self.relay_client("https://rpc.lokibuilder.xyz", true);
+        let ibuilder = self.relay_client("https://rpc.ibuilder.xyz", true);
+        let jetbuilder = self.relay_client("https://rpc.jetbldr.xyz", true);
+        let penguinbuilder = self.relay_client("https://rpc.penguinbuild.org", true);
+        let gambitbuilder = self.relay_client("https://builder.gmbit.co/rpc", true);
 
         let clients_vec = vec![
             flashbots,
@@ -161,25 +205,44 @@ where
     }
 
     pub fn with_relay(self, url: &str) -> Self {
+        let client = Arc::new(self.relay_client(url, true));
         let mut clients = self.clients;
-        clients.push(Arc::new(FlashbotsClient::new(self.provider.clone(), url)));
+        clients.push(client);
         Self { clients, ..self }
     }
 
     pub fn with_relays(self, relays: Vec<RelayConfig>) -> Self {
         let clients: Vec<Arc<FlashbotsClient<P>>> = relays
             .into_iter()
-            .map(|relay| {
-                if relay.no_sign.unwrap_or(false) {
-                    Arc::new(FlashbotsClient::new_no_sign(self.provider.clone(), relay.url.as_str()))
-                } else {
-                    Arc::new(FlashbotsClient::new(self.provider.clone(), relay.url.as_str()))
-                }
-            })
+            .map(|relay| Arc::new(self.relay_client(relay.url.as_str(), !relay.no_sign.unwrap_or(false))))
             .collect();
         Self { clients, ..self }
     }
 
+    /// Submit bundles to Eden Network with its own API format, the Flashbots format Eden relay is replaced
+    pub fn with_eden_relay(self, staker_address: Address, eden_api_key: String) -> Self {
+        let eden_relay = EdenRelay::with_http_client(staker_address, eden_api_key, self.http_client.clone());
+        let clients = self.clients.into_iter().filter(|client| client.name != EDEN_BUNDLE_URL).collect();
+        Self { clients, eden_relay: Some(Arc::new(eden_relay)), ..self }
+    }
+
+    /// Submit `mev_sendBundle` requests to the MEV Share node at `hint_relay`, e.g. `https://relay.flashbots.net`
+    pub fn with_mev_share(self, hint_relay: &str) -> Self {
+        Self { mev_share_client: Some(Arc::new(self.relay_client(hint_relay, true))), ..self }
+    }
+
+    pub async fn send_mev_share_bundle(&self, bundle: MevShareBundle) -> Result<()> {
+        let mev_share_client = self.mev_share_client.as_ref().ok_or(eyre!("MEV_SHARE_NOT_CONFIGURED"))?;
+        let req_id = self.req_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let (body, signature) = make_signed_body(req_id, "mev_sendBundle", bundle, &self.signer)?;
+        mev_share_client.send_signed_body(body, signature).await
+    }
+
+    pub async fn submit_to_eden_network(&self, txs: Vec<Bytes>, target_block: u64) -> Result<()> {
+        let eden_relay = self.eden_relay.as_ref().ok_or(eyre!("EDEN_RELAY_NOT_CONFIGURED"))?;
+        eden_relay.send_bundle(txs, target_block).await
+    }
+
     pub async fn simulate_txes<TX>(
         &self,
         txs: Vec<TX>,
@@ -201,6 +264,23 @@ where
         self.simulation_client.call_bundle(&bundle).await
     }
 
+    /// Simulate the bundle for `block_number` on top of the previous block with the Flashbots `eth_callBundle` endpoint
+    pub async fn simulate_bundle<TX>(&self, bundle: &[TX], block_number: u64) -> Result<BundleSimResult>
+    where
+        TX: Clone,
+        BundleTransaction: From<TX>,
+    {
+        let mut request =
+            BundleRequest::new().set_target_block(U64::from(block_number)).set_simulation_block(U64::from(block_number.saturating_sub(1)));
+
+        for t in bundle.iter().cloned() {
+            request = request.push_transaction(t);
+        }
+
+        let simulated_bundle = self.simulation_client.call_bundle_relay(&request).await?;
+        Ok(BundleSimResult::from(&simulated_bundle))
+    }
+
     pub async fn broadcast_txes<TX>(&self, txs: Vec<TX>, target_block: u64) -> Result<()>
     where
         BundleTransaction: From<TX>,
@@ -214,6 +294,17 @@ where
         let next_req_id = self.req_id.load(Ordering::SeqCst) + 1;
         self.req_id.store(next_req_id, Ordering::SeqCst);
 
+        if let Some(eden_relay) = &self.eden_relay {
+            let eden_relay = eden_relay.clone();
+            let raw_txs: Vec<Bytes> = bundle.transactions().iter().map(|tx| tx.raw()).collect();
+            tokio::task::spawn(async move {
+                debug!("Sending bundle to {}", eden_relay.name());
+                if let Err(e) = eden_relay.send_bundle(raw_txs, target_block).await {
+                    error!("Broadcasting error to {} : {}", eden_relay.name(), e);
+                }
+            });
+        }
+
         let (body, signature) = make_signed_body(next_req_id, "eth_sendBundle", bundle, &self.signer)?;
 
         for client in self.clients.iter() {
@@ -1,4 +1,8 @@
+pub use eden::{EdenRelay, EDEN_BUNDLE_URL};
 pub use flashbots::{Flashbots, FlashbotsClient};
+pub use private_relay::{PrivateRelay, PrivateRelayConfig, PrivateRelayType};
 
 pub mod client;
+mod eden;
 mod flashbots;
+mod private_relay;
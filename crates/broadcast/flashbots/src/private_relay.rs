@@ -0,0 +1,157 @@
+use alloy_primitives::{hex, Bytes, U64};
+use eyre::{eyre, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::debug;
+
+/// API of a private transaction relay
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PrivateRelayType {
+    /// MEV Blocker and other relays accepting `eth_sendBundle`
+    #[default]
+    MevBlocker,
+    /// BloxRoute `blxr_submit_bundle`
+    BloxRoute,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PrivateRelayConfig {
+    pub name: String,
+    pub url: String,
+    /// Value of the `Authorization` header, BloxRoute requires it
+    pub auth_header: Option<String>,
+    #[serde(rename = "type", default)]
+    pub relay_type: PrivateRelayType,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MevBlockerBundleParams {
+    txs: Vec<Bytes>,
+    block_number: U64,
+}
+
+#[derive(Serialize)]
+struct BloxRouteBundleParams {
+    transaction: Vec<String>,
+    block_number: U64,
+}
+
+#[derive(Serialize)]
+struct JsonRpcRequest<T> {
+    jsonrpc: &'static str,
+    id: u64,
+    method: &'static str,
+    params: T,
+}
+
+/// Private transaction relay. Transactions are not gossiped to the public mempool.
+pub struct PrivateRelay {
+    req_id: AtomicU64,
+    client: Client,
+    config: PrivateRelayConfig,
+}
+
+impl PrivateRelay {
+    pub fn new(config: PrivateRelayConfig) -> Self {
+        Self::with_http_client(config, Client::new())
+    }
+
+    pub fn with_http_client(config: PrivateRelayConfig, client: Client) -> Self {
+        Self { req_id: AtomicU64::new(0), client, config }
+    }
+
+    pub fn name(&self) -> &str {
+        self.config.name.as_str()
+    }
+
+    pub fn config(&self) -> &PrivateRelayConfig {
+        &self.config
+    }
+
+    pub fn relay_type(&self) -> PrivateRelayType {
+        self.config.relay_type
+    }
+
+    /// JSON-RPC request sending `txs` as one bundle targeting `target_block`. The transactions keep their order, so a
+    /// backrun follows the victim transaction it targets.
+    fn bundle_request(&self, txs: Vec<Bytes>, target_block: u64) -> Result<serde_json::Value> {
+        let id = self.req_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let request = match self.config.relay_type {
+            PrivateRelayType::MevBlocker => serde_json::to_value(JsonRpcRequest {
+                jsonrpc: "2.0",
+                id,
+                method: "eth_sendBundle",
+                params: [MevBlockerBundleParams { txs, block_number: U64::from(target_block) }],
+            })?,
+            PrivateRelayType::BloxRoute => serde_json::to_value(JsonRpcRequest {
+                jsonrpc: "2.0",
+                id,
+                method: "blxr_submit_bundle",
+                params: BloxRouteBundleParams { transaction: txs.iter().map(hex::encode).collect(), block_number: U64::from(target_block) },
+            })?,
+        };
+        Ok(request)
+    }
+
+    async fn send_request(&self, request: serde_json::Value) -> Result<()> {
+        let mut req = self.client.post(self.config.url.as_str()).header("Content-Type", "application/json").json(&request);
+        if let Some(auth_header) = &self.config.auth_header {
+            req = req.header("Authorization", auth_header.as_str());
+        }
+
+        let response = req.send().await.map_err(|e| eyre!("Private relay {} request error: {}", self.config.name, e))?;
+
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(eyre!("PRIVATE_RELAY_ERROR {} {} {}", self.config.name, status, text));
+        }
+        debug!("Private relay {} response: {}", self.config.name, text);
+        Ok(())
+    }
+
+    /// Sends signed transactions as one bundle targeting `target_block`, e.g. a victim transaction followed by its backrun
+    pub async fn send_transactions(&self, txs: Vec<Bytes>, target_block: u64) -> Result<()> {
+        let request = self.bundle_request(txs, target_block)?;
+        self.send_request(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn relay(relay_type: PrivateRelayType) -> PrivateRelay {
+        let config = PrivateRelayConfig { name: "relay".to_string(), url: "http://localhost".to_string(), auth_header: None, relay_type };
+        PrivateRelay::new(config)
+    }
+
+    #[test]
+    fn mev_blocker_bundle_request() {
+        let request = relay(PrivateRelayType::MevBlocker).bundle_request(vec![Bytes::from(vec![0x1]), Bytes::from(vec![0x2])], 16).unwrap();
+
+        assert_eq!(
+            request,
+            json!({"jsonrpc": "2.0", "id": 1, "method": "eth_sendBundle", "params": [{"txs": ["0x01", "0x02"], "blockNumber": "0x10"}]})
+        );
+    }
+
+    #[test]
+    fn bloxroute_bundle_request() {
+        let request = relay(PrivateRelayType::BloxRoute).bundle_request(vec![Bytes::from(vec![0x1]), Bytes::from(vec![0x2])], 16).unwrap();
+
+        assert_eq!(
+            request,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "blxr_submit_bundle",
+                "params": {"transaction": ["01", "02"], "block_number": "0x10"}
+            })
+        );
+    }
+}
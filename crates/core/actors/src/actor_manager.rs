@@ -1,13 +1,121 @@
-use eyre::Result;
-use tokio::task::JoinHandle;
-use tokio::time::{sleep, Duration};
-use tracing::{error, info};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Instant;
 
-use crate::{Actor, WorkerResult};
+use eyre::{eyre, Result};
+use tokio::task::{AbortHandle, JoinError, JoinHandle};
+use tokio::time::{sleep, timeout, Duration};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
 
-#[derive(Default)]
+use crate::{Actor, ActorResult, WorkerResult};
+
+const RESTART_BACKOFF_INITIAL_SECS: u64 = 1;
+const RESTART_BACKOFF_MAX_SECS: u64 = 60;
+const SHUTDOWN_TIMEOUT_SECS: u64 = 5;
+
+type ActorFactory = Arc<dyn Fn() -> ActorResult + Send + Sync>;
+type RestartListener = Arc<dyn Fn(&str) + Send + Sync>;
+type ManagerTask = Pin<Box<dyn Future<Output = ManagerEvent> + Send>>;
+
+/// Starts an actor again, returns the workers of the new instance
+pub type RestartActorFn = Box<dyn Fn() -> ActorResult + Send + Sync>;
+
+/// Actors found alive and dead by `ActorsManager::check_actors`. Dead actors that were started again are listed in `restarted` too.
+#[derive(Clone, Debug, Default)]
+pub struct HealthReport {
+    pub alive: Vec<String>,
+    pub dead: Vec<String>,
+    pub restarted: Vec<String>,
+}
+
+impl HealthReport {
+    pub fn is_healthy(&self) -> bool {
+        self.dead.is_empty()
+    }
+}
+
+enum ManagerEvent {
+    WorkerFinished { name: String, generation: u64, result: Result<()> },
+    RestartDue { name: String, generation: u64 },
+}
+
+struct ManagedActor {
+    // Not set for actors tracked without a restart fn
+    factory: Option<ActorFactory>,
+    restart_on_failure: bool,
+    restart_scheduled: bool,
+    restart_due_at: Instant,
+    backoff_secs: u64,
+    // Bumped on every restart, events of workers of older generations are ignored
+    generation: u64,
+    started_at: Instant,
+    workers: Vec<AbortHandle>,
+}
+
+fn worker_result(result: std::result::Result<WorkerResult, JoinError>) -> Result<()> {
+    match result {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(e)) => {
+            error!("Actor worker error: {:?}", e);
+            Err(e)
+        }
+        Err(e) => {
+            error!("Actor worker join error: {:?}", e);
+            Err(e.into())
+        }
+    }
+}
+
+// Convert JoinHandle<Result<String, ErrReport>> to JoinHandle<Result<()>>. Once the token is cancelled the worker has
+// `shutdown_timeout` to stop on its own before it is aborted
+fn supervise_worker(
+    worker: JoinHandle<WorkerResult>,
+    cancellation_token: CancellationToken,
+    shutdown_timeout: Duration,
+) -> JoinHandle<Result<()>> {
+    tokio::spawn(async move {
+        let mut worker = worker;
+        tokio::select! {
+            result = &mut worker => worker_result(result),
+            _ = cancellation_token.cancelled() => {
+                match timeout(shutdown_timeout, &mut worker).await {
+                    Ok(result) => worker_result(result),
+                    Err(_) => {
+                        warn!("Actor worker did not stop in {:?}, aborting", shutdown_timeout);
+                        worker.abort();
+                        let _ = worker.await;
+                        Ok(())
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Tracks started actors by name. A failed actor registered with `restart_on_failure` is recreated by its factory with
+/// exponential backoff while `wait` runs, or by `check_actors` for callers polling the manager. Once the cancellation token is cancelled actors are no longer restarted, workers
+/// are expected to stop on the token and are aborted after the shutdown timeout
 pub struct ActorsManager {
-    tasks: Vec<JoinHandle<()>>,
+    tasks: Vec<ManagerTask>,
+    actors: HashMap<String, ManagedActor>,
+    restart_listener: Option<RestartListener>,
+    cancellation_token: CancellationToken,
+    shutdown_timeout: Duration,
+}
+
+impl Default for ActorsManager {
+    fn default() -> Self {
+        Self {
+            tasks: Vec::new(),
+            actors: HashMap::new(),
+            restart_listener: None,
+            cancellation_token: CancellationToken::new(),
+            shutdown_timeout: Duration::from_secs(SHUTDOWN_TIMEOUT_SECS),
+        }
+    }
 }
 
 impl ActorsManager {
@@ -15,27 +123,58 @@ impl ActorsManager {
         Self::default()
     }
 
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
+    /// Time workers have to stop on their own after the cancellation token is cancelled
+    pub fn with_shutdown_timeout(self, shutdown_timeout: Duration) -> Self {
+        Self { shutdown_timeout, ..self }
+    }
+
+    /// Called with the actor name after every successful restart
+    pub fn with_restart_listener<L>(self, listener: L) -> Self
+    where
+        L: Fn(&str) + Send + Sync + 'static,
+    {
+        Self { restart_listener: Some(Arc::new(listener)), ..self }
+    }
+
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation_token.clone()
+    }
+
+    /// Starts the actor and restarts it when one of its workers fails
     pub fn start<F>(&mut self, actor_factory: F) -> Result<()>
     where
-        F: Fn() -> Box<dyn Actor + Send + Sync> + Send + Sync + Clone + 'static,
+        F: Fn() -> Box<dyn Actor + Send + Sync> + Send + Sync + 'static,
+    {
+        self.start_with_restart(actor_factory, true)
+    }
+
+    pub fn start_with_restart<F>(&mut self, actor_factory: F, restart_on_failure: bool) -> Result<()>
+    where
+        F: Fn() -> Box<dyn Actor + Send + Sync> + Send + Sync + 'static,
     {
         let actor = actor_factory();
-        let actor_name = actor.name().to_string();
+        let actor_name = self.unique_name(actor.name());
         match actor.start() {
             Ok(workers) => {
                 info!("{} started successfully", actor_name);
-                for worker in workers {
-                    // Convert JoinHandle<Result<String, ErrReport>> to JoinHandle<()>
-                    let handle = tokio::spawn(async move {
-                        match worker.await {
-                            Ok(Ok(_)) => (),
-                            Ok(Err(e)) => error!("Actor worker error: {:?}", e),
-                            Err(e) => error!("Actor worker join error: {:?}", e),
-                        }
-                    });
-                    // Clone actor_factory for each spawn_with_restart call
-                    self.spawn_with_restart(actor_name.clone(), handle, actor_factory.clone());
-                }
+                let workers = self.supervise_workers(&actor_name, 0, workers);
+                self.actors.insert(
+                    actor_name,
+                    ManagedActor {
+                        factory: Some(Arc::new(move || actor_factory().start())),
+                        restart_on_failure,
+                        restart_scheduled: false,
+                        restart_due_at: Instant::now(),
+                        backoff_secs: RESTART_BACKOFF_INITIAL_SECS,
+                        generation: 0,
+                        started_at: Instant::now(),
+                        workers,
+                    },
+                );
                 Ok(())
             }
             Err(e) => {
@@ -45,58 +184,177 @@ impl ActorsManager {
         }
     }
 
-    fn spawn_with_restart<F>(&mut self, name: String, mut handle: JoinHandle<()>, actor_factory: F)
-    where
-        F: Fn() -> Box<dyn Actor + Send + Sync> + Send + Sync + Clone + 'static,
-    {
-        let tasks = &mut self.tasks;
-        let task_name = name.clone();
-        // Move actor_factory into async task without cloning
-        let task = tokio::spawn(async move {
-            let mut backoff = 1;
-            let mut handle = handle;
-            let factory = actor_factory;
-            loop {
-                match &mut handle.await {
-                    Ok(_) => {
-                        info!("ActorWorker {} finished successfully", task_name);
-                        break;
-                    }
+    /// Tracks the workers of an actor started by the caller. With `restart` set the actor is started again by it when
+    /// one of its workers finished, see `check_actors`. Workers are not awaited by `wait`
+    pub fn track(&mut self, name: &str, workers: &[JoinHandle<WorkerResult>], restart: Option<RestartActorFn>) {
+        let actor_name = self.unique_name(name);
+        let restart_on_failure = restart.is_some();
+        self.actors.insert(
+            actor_name,
+            ManagedActor {
+                factory: restart.map(ActorFactory::from),
+                restart_on_failure,
+                restart_scheduled: false,
+                restart_due_at: Instant::now(),
+                backoff_secs: RESTART_BACKOFF_INITIAL_SECS,
+                generation: 0,
+                started_at: Instant::now(),
+                workers: workers.iter().map(|worker| worker.abort_handle()).collect(),
+            },
+        );
+    }
+
+    /// Aborts the workers of the actor registered as `name` and starts a new instance created by its factory
+    pub fn restart_actor(&mut self, name: &str) -> Result<()> {
+        let Some(managed_actor) = self.actors.get_mut(name) else {
+            return Err(eyre!("ACTOR_NOT_FOUND"));
+        };
+        let Some(factory) = managed_actor.factory.clone() else {
+            return Err(eyre!("ACTOR_NOT_RESTARTABLE"));
+        };
+        for worker in managed_actor.workers.drain(..) {
+            worker.abort();
+        }
+        managed_actor.generation += 1;
+        managed_actor.restart_scheduled = false;
+        let generation = managed_actor.generation;
+
+        let workers = match factory() {
+            Ok(workers) => workers,
+            Err(e) => {
+                error!("{} restart failed: {}", name, e);
+                return Err(e);
+            }
+        };
+        let workers = self.supervise_workers(name, generation, workers);
+        if let Some(managed_actor) = self.actors.get_mut(name) {
+            managed_actor.workers = workers;
+            managed_actor.started_at = Instant::now();
+        }
+        info!("{} restarted successfully", name);
+
+        if let Some(listener) = &self.restart_listener {
+            listener(name);
+        }
+        Ok(())
+    }
+
+    /// Actor is dead once any of its workers has finished. Dead actors with `restart_on_failure` are restarted once the
+    /// backoff scheduled on the first check that found them dead elapsed, the same backoff `wait` applies
+    pub fn check_actors(&mut self) -> HealthReport {
+        let mut report = HealthReport::default();
+        let mut names: Vec<String> = self.actors.keys().cloned().collect();
+        names.sort();
+
+        for name in names {
+            let Some(managed_actor) = self.actors.get(&name) else {
+                continue;
+            };
+            // An actor that failed to restart has no workers left
+            if !managed_actor.workers.is_empty() && !managed_actor.workers.iter().any(|worker| worker.is_finished()) {
+                report.alive.push(name);
+                continue;
+            }
+            report.dead.push(name.clone());
+            if !managed_actor.restart_on_failure {
+                warn!("Actor {} is dead", name);
+                continue;
+            }
+            if !managed_actor.restart_scheduled {
+                self.schedule_restart(&name);
+            }
+            let restart_due = self
+                .actors
+                .get(&name)
+                .is_some_and(|managed_actor| managed_actor.restart_scheduled && managed_actor.restart_due_at <= Instant::now());
+            if restart_due && !self.cancellation_token.is_cancelled() && self.restart_actor(&name).is_ok() {
+                report.restarted.push(name);
+            }
+        }
+
+        report
+    }
+
+    fn unique_name(&self, name: &str) -> String {
+        let mut unique_name = name.to_string();
+        let mut index = 1;
+        while self.actors.contains_key(&unique_name) {
+            index += 1;
+            unique_name = format!("{}#{}", name, index);
+        }
+        unique_name
+    }
+
+    fn supervise_workers(&mut self, name: &str, generation: u64, workers: Vec<JoinHandle<WorkerResult>>) -> Vec<AbortHandle> {
+        let mut abort_handles = Vec::new();
+        for worker in workers {
+            abort_handles.push(worker.abort_handle());
+            let handle = supervise_worker(worker, self.cancellation_token.clone(), self.shutdown_timeout);
+            let name = name.to_string();
+            self.tasks.push(Box::pin(async move {
+                let result = handle.await.unwrap_or_else(|e| Err(e.into()));
+                ManagerEvent::WorkerFinished { name, generation, result }
+            }));
+        }
+        abort_handles
+    }
+
+    fn schedule_restart(&mut self, name: &str) {
+        let cancellation_token = self.cancellation_token.clone();
+        let Some(managed_actor) = self.actors.get_mut(name) else {
+            return;
+        };
+        if !managed_actor.restart_on_failure || managed_actor.restart_scheduled || cancellation_token.is_cancelled() {
+            return;
+        }
+        // An actor that ran long enough before failing starts over with the initial backoff
+        if managed_actor.started_at.elapsed() > Duration::from_secs(RESTART_BACKOFF_MAX_SECS) {
+            managed_actor.backoff_secs = RESTART_BACKOFF_INITIAL_SECS;
+        }
+        let backoff_secs = managed_actor.backoff_secs;
+        managed_actor.backoff_secs = std::cmp::min(backoff_secs * 2, RESTART_BACKOFF_MAX_SECS);
+        managed_actor.restart_scheduled = true;
+        managed_actor.restart_due_at = Instant::now() + Duration::from_secs(backoff_secs);
+
+        error!("Restarting actor {} after {} seconds", name, backoff_secs);
+        let name = name.to_string();
+        let generation = managed_actor.generation;
+        self.tasks.push(Box::pin(async move {
+            tokio::select! {
+                _ = sleep(Duration::from_secs(backoff_secs)) => {}
+                _ = cancellation_token.cancelled() => {}
+            }
+            ManagerEvent::RestartDue { name, generation }
+        }));
+    }
+
+    fn on_event(&mut self, event: ManagerEvent) {
+        match event {
+            ManagerEvent::WorkerFinished { name, generation, result } => {
+                let is_current = self.actors.get(&name).is_some_and(|managed_actor| managed_actor.generation == generation);
+                match result {
+                    Ok(_) => info!("ActorWorker {} finished successfully", name),
+                    Err(_) if !is_current => {}
                     Err(e) => {
-                        error!("ActorWorker {} join error: {:?}", task_name, e);
+                        error!("ActorWorker {} failed : {}", name, e);
+                        self.schedule_restart(&name);
                     }
                 }
-                error!("Restarting actor task {} after {} seconds", task_name, backoff);
-                sleep(Duration::from_secs(backoff)).await;
-                backoff = std::cmp::min(backoff * 2, 60);
-                // Restart the actor task by spawning it again
-                let new_actor = factory();
-                match new_actor.start() {
-                    Ok(new_workers) => {
-                        info!("{} restarted successfully", task_name);
-                        if let Some(new_worker) = new_workers.into_iter().next() {
-                            // Wrap new_worker (JoinHandle<Result<...>>) into JoinHandle<()> by spawning a new task
-                            handle = tokio::spawn(async move {
-                                match new_worker.await {
-                                    Ok(Ok(_)) => (),
-                                    Ok(Err(e)) => error!("Actor worker error: {:?}", e),
-                                    Err(e) => error!("Actor worker join error: {:?}", e),
-                                }
-                            });
-                            continue;
-                        } else {
-                            error!("{} restart failed: no worker returned", task_name);
-                            break;
-                        }
-                    }
-                    Err(e) => {
-                        error!("{} restart failed: {}", task_name, e);
-                        break;
-                    }
+            }
+            ManagerEvent::RestartDue { name, generation } => {
+                let is_current = self.actors.get(&name).is_some_and(|managed_actor| managed_actor.generation == generation);
+                if !is_current {
+                    return;
+                }
+                if self.cancellation_token.is_cancelled() {
+                    info!("ActorWorker {} cancelled, not restarting", name);
+                    return;
+                }
+                if self.restart_actor(&name).is_err() {
+                    self.schedule_restart(&name);
                 }
             }
-        });
-        tasks.push(task);
+        }
     }
 
     pub fn start_and_wait(&mut self, actor: impl Actor + Send + Sync + 'static) -> Result<()> {
@@ -114,20 +372,159 @@ impl ActorsManager {
 
     pub async fn wait(&mut self) {
         let mut f_remaining_futures = std::mem::take(&mut self.tasks);
-        let mut futures_counter = f_remaining_futures.len();
 
-        while futures_counter > 0 {
-            let (result, _index, remaining_futures) = futures::future::select_all(f_remaining_futures).await;
-            match result {
-                Ok(_) => {
-                    info!("ActorWorker finished successfully")
-                }
-                Err(e) => {
-                    error!("ActorWorker join error : {e}")
-                }
-            }
+        while !f_remaining_futures.is_empty() {
+            let (event, _index, remaining_futures) = futures::future::select_all(f_remaining_futures).await;
             f_remaining_futures = remaining_futures;
-            futures_counter = f_remaining_futures.len();
+            self.on_event(event);
+            // Restarts push new worker and backoff futures
+            f_remaining_futures.append(&mut self.tasks);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ActorResult;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct PendingActor;
+
+    impl Actor for PendingActor {
+        fn start(&self) -> ActorResult {
+            let task = tokio::spawn(async {
+                std::future::pending::<()>().await;
+                Ok("PendingActor finished".to_string())
+            });
+            Ok(vec![task])
         }
+
+        fn name(&self) -> &'static str {
+            "PendingActor"
+        }
+    }
+
+    struct CancellableActor {
+        cancellation_token: CancellationToken,
+        stopped: Arc<AtomicUsize>,
+    }
+
+    impl Actor for CancellableActor {
+        fn start(&self) -> ActorResult {
+            let cancellation_token = self.cancellation_token.clone();
+            let stopped = self.stopped.clone();
+            let task = tokio::spawn(async move {
+                cancellation_token.cancelled().await;
+                stopped.fetch_add(1, Ordering::Relaxed);
+                Ok("CancellableActor finished".to_string())
+            });
+            Ok(vec![task])
+        }
+
+        fn name(&self) -> &'static str {
+            "CancellableActor"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancellation_stops_workers() {
+        let cancellation_token = CancellationToken::new();
+        let stopped = Arc::new(AtomicUsize::new(0));
+        let mut actor_manager = ActorsManager::new().with_cancellation_token(cancellation_token.clone());
+        let (actor_token, actor_stopped) = (cancellation_token.clone(), stopped.clone());
+        actor_manager
+            .start(move || {
+                Box::new(CancellableActor { cancellation_token: actor_token.clone(), stopped: actor_stopped.clone() })
+                    as Box<dyn Actor + Send + Sync>
+            })
+            .unwrap();
+
+        cancellation_token.cancel();
+        tokio::time::timeout(Duration::from_secs(1), actor_manager.wait()).await.unwrap();
+        assert_eq!(stopped.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cancellation_aborts_workers_after_timeout() {
+        let cancellation_token = CancellationToken::new();
+        let mut actor_manager =
+            ActorsManager::new().with_cancellation_token(cancellation_token.clone()).with_shutdown_timeout(Duration::from_millis(100));
+        actor_manager.start(|| Box::new(PendingActor) as Box<dyn Actor + Send + Sync>).unwrap();
+
+        cancellation_token.cancel();
+        tokio::time::timeout(Duration::from_secs(1), actor_manager.wait()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_restart_actor() {
+        let started = Arc::new(AtomicUsize::new(0));
+        let restarted = Arc::new(AtomicUsize::new(0));
+
+        let restarted_clone = restarted.clone();
+        let mut actor_manager = ActorsManager::new().with_shutdown_timeout(Duration::from_millis(100)).with_restart_listener(move |name| {
+            assert_eq!(name, "PendingActor");
+            restarted_clone.fetch_add(1, Ordering::Relaxed);
+        });
+        let started_clone = started.clone();
+        actor_manager
+            .start(move || {
+                started_clone.fetch_add(1, Ordering::Relaxed);
+                Box::new(PendingActor) as Box<dyn Actor + Send + Sync>
+            })
+            .unwrap();
+
+        actor_manager.restart_actor("PendingActor").unwrap();
+        assert_eq!(started.load(Ordering::Relaxed), 2);
+        assert_eq!(restarted.load(Ordering::Relaxed), 1);
+        assert!(actor_manager.restart_actor("UnknownActor").is_err());
+
+        actor_manager.cancellation_token().cancel();
+        tokio::time::timeout(Duration::from_secs(1), actor_manager.wait()).await.unwrap();
+    }
+
+    fn pending_task() -> JoinHandle<WorkerResult> {
+        tokio::task::spawn(async {
+            tokio::time::sleep(Duration::from_secs(3600)).await;
+            Ok("done".to_string())
+        })
+    }
+
+    #[tokio::test]
+    async fn test_check_actors() {
+        let finished_task = tokio::task::spawn(async { Ok("done".to_string()) });
+        let finished_restartable_task = tokio::task::spawn(async { Ok("done".to_string()) });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let restarts = Arc::new(AtomicUsize::new(0));
+        let restarts_clone = restarts.clone();
+        let restart: RestartActorFn = Box::new(move || {
+            restarts_clone.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![pending_task()])
+        });
+
+        let mut actor_manager = ActorsManager::new();
+        actor_manager.track("AliveActor", &[pending_task()], None);
+        actor_manager.track("DeadActor", &[finished_task], None);
+        actor_manager.track("RestartedActor", &[finished_restartable_task], Some(restart));
+
+        // Restart is scheduled with the initial backoff
+        let report = actor_manager.check_actors();
+        assert_eq!(report.alive, vec!["AliveActor".to_string()]);
+        assert_eq!(report.dead, vec!["DeadActor".to_string(), "RestartedActor".to_string()]);
+        assert!(report.restarted.is_empty());
+        assert!(actor_manager.restart_actor("DeadActor").is_err());
+
+        tokio::time::sleep(Duration::from_secs(RESTART_BACKOFF_INITIAL_SECS) + Duration::from_millis(100)).await;
+        let report = actor_manager.check_actors();
+        assert_eq!(report.restarted, vec!["RestartedActor".to_string()]);
+        assert_eq!(restarts.load(Ordering::SeqCst), 1);
+
+        let report = actor_manager.check_actors();
+        assert_eq!(report.alive, vec!["AliveActor".to_string(), "RestartedActor".to_string()]);
+        assert_eq!(report.dead, vec!["DeadActor".to_string()]);
+        assert!(report.restarted.is_empty());
+        assert!(!report.is_healthy());
+        assert_eq!(restarts.load(Ordering::SeqCst), 1);
     }
 }
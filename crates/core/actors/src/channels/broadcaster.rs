@@ -1,23 +1,82 @@
 use eyre::{eyre, Result};
-use std::sync::{Arc, RwLock};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 use tokio::sync::broadcast;
 use tokio::sync::broadcast::error::{RecvError, SendError};
 use tokio::sync::broadcast::Receiver;
 use tracing::{debug, error, warn};
 
+/// Calls `on_lag` once when the receiver lag exceeds `threshold`, rearmed when the lag drops back
+struct LagMonitor {
+    threshold: usize,
+    on_lag: Box<dyn Fn(usize) + Send + Sync>,
+    lagging: bool,
+}
+
+impl LagMonitor {
+    fn update(&mut self, lag: usize) {
+        if lag > self.threshold {
+            if !self.lagging {
+                self.lagging = true;
+                (self.on_lag)(lag);
+            }
+        } else {
+            self.lagging = false;
+        }
+    }
+}
+
 /// A wrapper around Receiver that tracks active subscribers
 pub struct TrackedReceiver<T> {
     receiver: Receiver<T>,
     active_subscribers: Arc<RwLock<usize>>,
+    lag_monitor: Option<LagMonitor>,
 }
 
 impl<T: Clone> TrackedReceiver<T> {
     pub async fn recv(&mut self) -> Result<T, RecvError> {
-        self.receiver.recv().await
+        let result = self.receiver.recv().await;
+        let skipped = match &result {
+            Err(RecvError::Lagged(skipped)) => *skipped as usize,
+            _ => 0,
+        };
+        self.track_lag(skipped);
+        result
     }
     
     pub fn try_recv(&mut self) -> Result<T, tokio::sync::broadcast::error::TryRecvError> {
-        self.receiver.try_recv()
+        let result = self.receiver.try_recv();
+        let skipped = match &result {
+            Err(tokio::sync::broadcast::error::TryRecvError::Lagged(skipped)) => *skipped as usize,
+            _ => 0,
+        };
+        self.track_lag(skipped);
+        result
+    }
+
+    /// Call `on_lag` with the lag, the number of messages between the sender and this receiver,
+    /// when it exceeds `threshold`. Messages skipped by an overflow are counted as lag.
+    pub fn with_lag_monitor(mut self, threshold: usize, on_lag: impl Fn(usize) + Send + Sync + 'static) -> Self {
+        self.lag_monitor = Some(LagMonitor { threshold, on_lag: Box::new(on_lag), lagging: false });
+        self
+    }
+
+    fn track_lag(&mut self, skipped: usize) {
+        if let Some(lag_monitor) = self.lag_monitor.as_mut() {
+            lag_monitor.update(self.receiver.len() + skipped);
+        }
+    }
+
+    /// Number of messages queued for this receiver
+    pub fn len(&self) -> usize {
+        self.receiver.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.receiver.is_empty()
     }
 }
 
@@ -31,6 +90,62 @@ impl<T> Drop for TrackedReceiver<T> {
     }
 }
 
+/// Remembers message hashes for a time window so repeated sends can be dropped
+struct DedupFilter<T> {
+    window: Duration,
+    hasher: fn(&T) -> u64,
+    seen: Mutex<DedupState>,
+}
+
+struct DedupState {
+    hashes: HashMap<u64, Instant>,
+    last_prune: Instant,
+}
+
+impl<T> DedupFilter<T> {
+    fn new(window: Duration, hasher: fn(&T) -> u64) -> Self {
+        Self { window, hasher, seen: Mutex::new(DedupState { hashes: HashMap::new(), last_prune: Instant::now() }) }
+    }
+
+    /// Returns true if the same message was already seen inside the window, otherwise records it
+    fn is_duplicate(&self, value: &T) -> bool {
+        let hash = (self.hasher)(value);
+        let now = Instant::now();
+        let mut state = self.seen.lock().unwrap();
+
+        // Expired entries are only swept once per window to keep send cheap on busy channels
+        if now.duration_since(state.last_prune) >= self.window {
+            let window = self.window;
+            state.hashes.retain(|_, seen_at| now.duration_since(*seen_at) < window);
+            state.last_prune = now;
+        }
+
+        match state.hashes.get(&hash) {
+            Some(seen_at) if now.duration_since(*seen_at) < self.window => true,
+            _ => {
+                state.hashes.insert(hash, now);
+                false
+            }
+        }
+    }
+}
+
+/// Drops counted between overflow reports
+#[derive(Default)]
+struct OverflowState {
+    dropped: u64,
+    last_report: Option<Instant>,
+}
+
+/// Overflows are reported at most once per interval with the number of messages dropped since the previous report
+const OVERFLOW_REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+fn hash_message<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
 /// Enhanced Broadcaster with reconnection capability and keep-alive mechanism
 #[derive(Clone)]
 pub struct Broadcaster<T>
@@ -41,6 +156,11 @@ where
     // Track active subscribers to prevent channel closure
     active_subscribers: Arc<RwLock<usize>>,
     capacity: usize,
+    // Optional duplicate filter, shared between clones of the broadcaster
+    dedup: Option<Arc<DedupFilter<T>>>,
+    name: &'static str,
+    overflow_callback: Option<fn(u64)>,
+    overflow_state: Arc<Mutex<OverflowState>>,
 }
 
 impl<T: Clone + Send + Sync + 'static> Broadcaster<T> {
@@ -50,11 +170,61 @@ impl<T: Clone + Send + Sync + 'static> Broadcaster<T> {
             sender: Arc::new(RwLock::new(sender)),
             active_subscribers: Arc::new(RwLock::new(0)),
             capacity,
+            dedup: None,
+            name: "unnamed",
+            overflow_callback: None,
+            overflow_state: Arc::new(Mutex::new(OverflowState::default())),
         }
     }
 
-    /// Send a message through the broadcast channel with automatic reconnection
+    /// Channel name used in overflow reports
+    pub fn with_name(self, name: &'static str) -> Self {
+        Self { name, ..self }
+    }
+
+    /// Call `cb` with the number of dropped messages instead of logging an error when the channel overflows.
+    /// A message is dropped when a send evicts a message not yet received by the slowest subscriber.
+    pub fn with_overflow_callback(self, cb: fn(dropped: u64)) -> Self {
+        Self { overflow_callback: Some(cb), ..self }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Share of the channel buffer holding messages not yet received by all subscribers, from 0.0 to 1.0
+    pub fn utilization(&self) -> f64 {
+        // Tokio rounds the buffer up to a power of two
+        self.sender.read().unwrap().len() as f64 / self.capacity.next_power_of_two() as f64
+    }
+
+    fn record_overflow(&self) {
+        let mut state = self.overflow_state.lock().unwrap();
+        state.dropped += 1;
+        let now = Instant::now();
+        if state.last_report.is_some_and(|last_report| now.duration_since(last_report) < OVERFLOW_REPORT_INTERVAL) {
+            return;
+        }
+        let dropped = std::mem::take(&mut state.dropped);
+        state.last_report = Some(now);
+        drop(state);
+
+        match self.overflow_callback {
+            Some(cb) => cb(dropped),
+            None => error!("Channel {} overflow, dropped {} messages", self.name, dropped),
+        }
+    }
+
+    /// Send a message through the broadcast channel with automatic reconnection.
+    /// If a dedup window is configured, duplicates are dropped and `Ok(0)` is returned.
     pub fn send(&self, value: T) -> Result<usize, SendError<T>> {
+        if let Some(dedup) = &self.dedup {
+            if dedup.is_duplicate(&value) {
+                debug!("Duplicate message dropped");
+                return Ok(0);
+            }
+        }
+
         // Check if we need to recreate the channel
         let subscriber_count = self.sender.read().unwrap().receiver_count();
         if subscriber_count == 0 {
@@ -71,6 +241,11 @@ impl<T: Clone + Send + Sync + 'static> Broadcaster<T> {
             }
         }
         
+        // A full buffer evicts the oldest message, subscribers that have not received it yet lose it
+        if self.sender.read().unwrap().len() >= self.capacity.next_power_of_two() {
+            self.record_overflow();
+        }
+
         // Attempt to send the message
         match self.sender.read().unwrap().send(value.clone()) {
             Ok(count) => Ok(count),
@@ -116,9 +291,14 @@ impl<T: Clone + Send + Sync + 'static> Broadcaster<T> {
         TrackedReceiver {
             receiver,
             active_subscribers,
+            lag_monitor: None,
         }
     }
     
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
     /// Get the current number of active subscribers
     pub fn subscriber_count(&self) -> usize {
         *self.active_subscribers.read().unwrap()
@@ -129,3 +309,95 @@ impl<T: Clone + Send + Sync + 'static> Broadcaster<T> {
         self.sender.read().unwrap().receiver_count() > 0
     }
 }
+
+impl<T: Hash + Clone + Send + Sync + 'static> Broadcaster<T> {
+    /// Drop messages whose hash was already sent within `window`.
+    /// Useful when several actors (e.g. block actor and ExEx) feed the same channel.
+    pub fn with_dedup_window(mut self, window: Duration) -> Self {
+        self.dedup = Some(Arc::new(DedupFilter::new(window, hash_message::<T>)));
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    #[tokio::test]
+    async fn test_dedup_window_drops_duplicates() {
+        let broadcaster: Broadcaster<u64> = Broadcaster::new(10).with_dedup_window(Duration::from_secs(60));
+        let mut rx = broadcaster.subscribe();
+
+        assert_eq!(broadcaster.send(1).unwrap(), 1);
+        assert_eq!(broadcaster.send(1).unwrap(), 0);
+        assert_eq!(broadcaster.send(2).unwrap(), 1);
+
+        assert_eq!(rx.recv().await.unwrap(), 1);
+        assert_eq!(rx.recv().await.unwrap(), 2);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dedup_window_expires() {
+        let broadcaster: Broadcaster<u64> = Broadcaster::new(10).with_dedup_window(Duration::from_millis(10));
+        let _rx = broadcaster.subscribe();
+
+        assert_eq!(broadcaster.send(1).unwrap(), 1);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(broadcaster.send(1).unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_utilization_and_overflow() {
+        static DROPPED: AtomicU64 = AtomicU64::new(0);
+
+        let broadcaster: Broadcaster<u64> = Broadcaster::new(4).with_name("test").with_overflow_callback(|dropped| {
+            DROPPED.fetch_add(dropped, Ordering::SeqCst);
+        });
+        let mut rx = broadcaster.subscribe();
+
+        assert_eq!(broadcaster.utilization(), 0.0);
+        for i in 0..2 {
+            broadcaster.send(i).unwrap();
+        }
+        assert_eq!(broadcaster.utilization(), 0.5);
+        for i in 2..4 {
+            broadcaster.send(i).unwrap();
+        }
+        assert_eq!(broadcaster.utilization(), 1.0);
+        assert_eq!(DROPPED.load(Ordering::SeqCst), 0);
+
+        // First overflow is reported immediately, later ones are counted until the next report
+        broadcaster.send(4).unwrap();
+        broadcaster.send(5).unwrap();
+        assert_eq!(DROPPED.load(Ordering::SeqCst), 1);
+        assert!(matches!(rx.recv().await, Err(RecvError::Lagged(2))));
+        assert_eq!(rx.recv().await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_lag_monitor() {
+        let broadcaster: Broadcaster<u64> = Broadcaster::new(10);
+        let lags: Arc<Mutex<Vec<usize>>> = Arc::new(Mutex::new(Vec::new()));
+        let lags_clone = lags.clone();
+        let mut rx = broadcaster.subscribe().with_lag_monitor(3, move |lag| lags_clone.lock().unwrap().push(lag));
+
+        for i in 0..6 {
+            broadcaster.send(i).unwrap();
+        }
+        // Reported once while lagging
+        assert_eq!(rx.recv().await.unwrap(), 0);
+        assert_eq!(rx.recv().await.unwrap(), 1);
+        assert_eq!(*lags.lock().unwrap(), vec![5]);
+
+        for _ in 0..4 {
+            rx.recv().await.unwrap();
+        }
+        for i in 0..5 {
+            broadcaster.send(i).unwrap();
+        }
+        rx.recv().await.unwrap();
+        assert_eq!(*lags.lock().unwrap(), vec![5, 4]);
+    }
+}
@@ -1,7 +1,8 @@
 pub use actor::{Accessor, Actor, ActorResult, Consumer, Producer, WorkerResult};
-pub use actor_manager::ActorsManager;
-pub use channels::{Broadcaster, MultiProducer};
+pub use actor_manager::{ActorsManager, HealthReport, RestartActorFn};
+pub use channels::{Broadcaster, MultiProducer, TrackedReceiver};
 pub use shared_state::SharedState;
+pub use tokio_util::sync::CancellationToken;
 
 mod actor;
 mod actor_manager;
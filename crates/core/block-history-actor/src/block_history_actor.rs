@@ -3,13 +3,17 @@ use alloy_primitives::{BlockHash, BlockNumber};
 use alloy_provider::Provider;
 use alloy_rpc_types::Header;
 use eyre::{eyre, Result};
-use loom_core_actors::{run_sync, subscribe, Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
+use futures::stream::{self, StreamExt};
+use loom_core_actors::{
+    run_sync, subscribe, Accessor, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, Producer, SharedState, WorkerResult,
+};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
 use loom_core_blockchain_shared::{Blockchain, BlockchainState};
 use loom_evm_db::DatabaseLoomExt;
 use loom_node_debug_provider::DebugProviderExt;
-use loom_types_blockchain::ChainParameters;
-use loom_types_entities::{BlockHistory, BlockHistoryManager, BlockHistoryState, LatestBlock, MarketState};
+use loom_types_blockchain::{ChainParameters, GethStateUpdate};
+use loom_types_entities::required_state::RequiredStateReader;
+use loom_types_entities::{BlockHistory, BlockHistoryManager, BlockHistoryState, LatestBlock, Market, MarketState, PoolWrapper};
 use loom_types_events::{MarketEvents, MessageBlock, MessageBlockHeader, MessageBlockLogs, MessageBlockStateUpdate};
 use revm::{Database, DatabaseCommit, DatabaseRef};
 use std::borrow::BorrowMut;
@@ -17,6 +21,67 @@ use std::ops::DerefMut;
 use tokio::sync::broadcast::error::RecvError;
 use tracing::{debug, error, info, trace, warn};
 
+// Reorgs up to this depth roll back block history, deeper reorgs reload the market state
+const MAX_REORG_DEPTH: usize = 3;
+
+// Pools fetched at once while the market state is reloaded
+const RELOAD_CONCURRENCY: usize = 32;
+
+/// Fetches required state of all enabled pools at `block_number` and applies it to the market state in one update.
+/// Runs before the next message is processed, so state updates of later blocks are applied on top of the reloaded state.
+async fn reload_market_state<P, DB>(
+    client: P,
+    market: SharedState<Market>,
+    market_state: SharedState<MarketState<DB>>,
+    block_number: BlockNumber,
+    block_hash: BlockHash,
+) where
+    P: Provider<Ethereum> + DebugProviderExt<Ethereum> + Send + Sync + Clone + 'static,
+    DB: DatabaseRef + Database + DatabaseCommit + Send + Sync + Clone + 'static,
+{
+    let pools: Vec<PoolWrapper> = {
+        let market_guard = market.read().await;
+        market_guard.pools().iter().filter(|(pool_id, _)| !market_guard.is_pool_disabled(pool_id)).map(|(_, pool)| pool.clone()).collect()
+    };
+
+    let updates: Vec<GethStateUpdate> = stream::iter(pools)
+        .map(|pool| {
+            let client = client.clone();
+            async move {
+                let required_state =
+                    pool.get_state_required().map_err(|e| error!(pool = %pool.get_address(), "get_state_required : {}", e)).ok()?;
+                RequiredStateReader::fetch_calls_and_slots(client, required_state, Some(block_number))
+                    .await
+                    .map_err(|e| error!(pool = %pool.get_address(), block_number, "fetch_calls_and_slots : {}", e))
+                    .ok()
+            }
+        })
+        .buffer_unordered(RELOAD_CONCURRENCY)
+        .filter_map(|update| async move { update })
+        .collect()
+        .await;
+
+    let reloaded = updates.len();
+    let mut state_update = GethStateUpdate::new();
+    for update in updates {
+        for (address, account) in update {
+            let entry = state_update.entry(address).or_default();
+            entry.balance = account.balance.or(entry.balance);
+            entry.nonce = account.nonce.or(entry.nonce);
+            entry.code = account.code.or(entry.code.take());
+            entry.storage.extend(account.storage);
+        }
+    }
+
+    let mut market_state_guard = market_state.write().await;
+    market_state_guard.apply_geth_update(state_update);
+    market_state_guard.block_number = block_number;
+    market_state_guard.block_hash = block_hash;
+    info!(block_number, %block_hash, reloaded, "Market state reloaded");
+}
+
+/// Returns `(is_new_block, reorg_depth, reload_at)`, the market state is to be reloaded at `reload_at` after a deep reorg
+/// once the block history and latest block locks are released.
 pub async fn set_chain_head<P, DB>(
     block_history_manager: &BlockHistoryManager<P, DB>,
     block_history: &mut BlockHistory<DB>,
@@ -24,13 +89,15 @@ pub async fn set_chain_head<P, DB>(
     market_events_tx: Broadcaster<MarketEvents>,
     header: Header,
     chain_parameters: &ChainParameters,
-) -> Result<(bool, usize)>
+) -> Result<(bool, usize, Option<(BlockNumber, BlockHash)>)>
 where
     P: Provider<Ethereum> + DebugProviderExt<Ethereum> + Send + Sync + Clone + 'static,
-    DB: Clone,
+    DB: DatabaseRef + Database + DatabaseCommit + Send + Sync + Clone + 'static,
 {
     let block_number = header.number;
     let block_hash = header.hash;
+    let parent_hash = header.parent_hash;
+    let old_tip = latest_block.block_hash;
 
     debug!(%block_number, %block_hash, "set_chain_head block_number");
 
@@ -40,6 +107,23 @@ where
                 debug!("Re-org detected. Block {} Depth {} New hash {}", block_number, reorg_depth, block_hash);
             }
 
+            // Parent of the new head is not the previous head, either blocks were missed or the previous head was replaced
+            let mut reload_at = None;
+            if is_new_block && !old_tip.is_zero() && old_tip != block_hash && old_tip != parent_hash {
+                match block_history.rollback_to_common_ancestor(&old_tip, &block_hash) {
+                    Some((0, _)) => {}
+                    Some((depth, ancestor_hash)) if depth <= MAX_REORG_DEPTH => {
+                        warn!(block_number, depth, %old_tip, new_tip = %block_hash, %ancestor_hash, "Reorg detected");
+                        run_sync!(market_events_tx.send(MarketEvents::Reorg { depth, old_tip, new_tip: block_hash }));
+                    }
+                    depth => {
+                        warn!(block_number, depth = ?depth.map(|(depth, _)| depth), %old_tip, new_tip = %block_hash, "Deep reorg detected");
+                        run_sync!(market_events_tx.send(MarketEvents::DeepReorg { old_tip, new_tip: block_hash }));
+                        reload_at = Some((block_number, block_hash));
+                    }
+                }
+            }
+
             if is_new_block {
                 let base_fee = header.base_fee_per_gas.unwrap_or_default();
                 let next_base_fee = chain_parameters.calc_next_block_base_fee(header.gas_used, header.gas_limit, base_fee);
@@ -55,7 +139,7 @@ where
                 }
             }
 
-            Ok((is_new_block, reorg_depth))
+            Ok((is_new_block, reorg_depth, reload_at))
         }
         Err(e) => {
             error!("block_history_manager.set_chain_head error at {} hash {} error : {} ", block_number, block_hash, e);
@@ -69,6 +153,7 @@ pub async fn new_block_history_worker<P, DB>(
     client: P,
     chain_parameters: ChainParameters,
     latest_block: SharedState<LatestBlock>,
+    market: SharedState<Market>,
     market_state: SharedState<MarketState<DB>>,
     block_history: SharedState<BlockHistory<DB>>,
     block_header_update_rx: Broadcaster<MessageBlockHeader>,
@@ -76,10 +161,11 @@ pub async fn new_block_history_worker<P, DB>(
     log_update_rx: Broadcaster<MessageBlockLogs>,
     state_update_rx: Broadcaster<MessageBlockStateUpdate>,
     market_events_tx: Broadcaster<MarketEvents>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult
 where
     P: Provider<Ethereum> + DebugProviderExt<Ethereum> + Send + Sync + Clone + 'static,
-    DB: BlockHistoryState + DatabaseRef + DatabaseCommit + DatabaseLoomExt + Send + Sync + Clone + 'static,
+    DB: BlockHistoryState + DatabaseRef + Database + DatabaseCommit + DatabaseLoomExt + Send + Sync + Clone + 'static,
 {
     subscribe!(block_header_update_rx);
     subscribe!(block_update_rx);
@@ -90,8 +176,19 @@ where
 
     let block_history_manager = BlockHistoryManager::new(client);
 
+    // Head of the last deep reorg, reloaded at the start of the next iteration when no locks are held
+    let mut reload_at: Option<(BlockNumber, BlockHash)> = None;
+
     loop {
+        if let Some((block_number, block_hash)) = reload_at.take() {
+            let client = block_history_manager.client().clone();
+            reload_market_state(client, market.clone(), market_state.clone(), block_number, block_hash).await;
+        }
+
         tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Block history cancelled".to_string());
+            }
             msg = block_header_update_rx.recv() => {
                 let block_update : Result<MessageBlockHeader, RecvError>  = msg;
                 match block_update {
@@ -102,7 +199,7 @@ where
                         debug!("Block Header, Update {} {}", block_header.header.number, block_header.header.hash_slow());
 
 
-                        set_chain_head(
+                        let (_, _, block_reload_at) = set_chain_head(
                             &block_history_manager,
                             block_history_guard.borrow_mut(),
                             latest_block_guard.borrow_mut(),
@@ -110,6 +207,7 @@ where
                             block_header.inner.header,
                             &chain_parameters
                         ).await?;
+                        reload_at = reload_at.or(block_reload_at);
                     }
                     Err(e)=>{
                         error!("block_update error {}", e)
@@ -140,7 +238,8 @@ where
                             &chain_parameters
                         ).await
                             {
-                                Ok(_)=>{
+                                Ok((_, _, block_reload_at))=>{
+                                    reload_at = reload_at.or(block_reload_at);
                                     match block_history_guard.add_block(block.clone()) {
                                         Ok(_)=>{
                                             if block_hash == latest_block_guard.block_hash {
@@ -190,7 +289,8 @@ where
                             &chain_parameters
                         ).await
                         {
-                            Ok(_)=>{
+                            Ok((_, _, block_reload_at))=>{
+                                reload_at = reload_at.or(block_reload_at);
                                 match block_history_guard.add_logs(block_hash,blocklogs.logs.clone()) {
                                     Ok(_)=>{
                                         if block_hash == latest_block_guard.block_hash {
@@ -241,10 +341,13 @@ where
                 let mut market_state_guard = market_state.write().await;
 
 
-                if let Err(e) = set_chain_head(&block_history_manager, block_history_guard.borrow_mut(),
-                    latest_block_guard.borrow_mut(),market_events_tx.clone(), msg_block_header, &chain_parameters).await {
-                    error!("set_chain_head : {}", e);
-                    continue
+                match set_chain_head(&block_history_manager, block_history_guard.borrow_mut(),
+                    latest_block_guard.borrow_mut(), market_events_tx.clone(), msg_block_header, &chain_parameters).await {
+                    Ok((_, _, block_reload_at)) => reload_at = reload_at.or(block_reload_at),
+                    Err(e) => {
+                        error!("set_chain_head : {}", e);
+                        continue
+                    }
                 }
 
                 let (latest_block_number, latest_block_hash) = latest_block_guard.number_and_hash();
@@ -255,6 +358,7 @@ where
                     if let Err(err) = block_history_guard.add_state_diff(msg_block_hash,  msg.state_update.clone()) {
                         error!(%err, %msg_block_number, %msg_block_hash, "Error during add_state_diff.");
                     }
+                    block_history_guard.prune_if_needed();
                 } else{
                     latest_block_guard.update(msg_block_number, msg_block_hash, None, None, None, Some(msg.state_update.clone()) );
 
@@ -286,6 +390,7 @@ where
                         error!(%err, %msg_block_number, %msg_block_hash, "Error during block_history.add_db.");
                         continue
                     }
+                    block_history_guard.prune_if_needed();
 
                     debug!("Block History len: {}", block_history_guard.len());
 
@@ -349,6 +454,8 @@ pub struct BlockHistoryActor<P, DB> {
     #[accessor]
     latest_block: Option<SharedState<LatestBlock>>,
     #[accessor]
+    market: Option<SharedState<Market>>,
+    #[accessor]
     market_state: Option<SharedState<MarketState<DB>>>,
     #[accessor]
     block_history: Option<SharedState<BlockHistory<DB>>>,
@@ -362,6 +469,7 @@ pub struct BlockHistoryActor<P, DB> {
     state_update_rx: Option<Broadcaster<MessageBlockStateUpdate>>,
     #[producer]
     market_events_tx: Option<Broadcaster<MarketEvents>>,
+    cancellation_token: CancellationToken,
 }
 
 impl<P, DB> BlockHistoryActor<P, DB>
@@ -374,6 +482,7 @@ where
             client,
             chain_parameters: ChainParameters::ethereum(),
             latest_block: None,
+            market: None,
             market_state: None,
             block_history: None,
             block_header_update_rx: None,
@@ -381,13 +490,19 @@ where
             log_update_rx: None,
             state_update_rx: None,
             market_events_tx: None,
+            cancellation_token: CancellationToken::new(),
         }
     }
 
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
     pub fn on_bc(self, bc: &Blockchain, state: &BlockchainState<DB>) -> Self {
         Self {
             chain_parameters: bc.chain_parameters(),
             latest_block: Some(bc.latest_block()),
+            market: Some(bc.market()),
             block_header_update_rx: Some(bc.new_block_headers_channel()),
             block_update_rx: Some(bc.new_block_with_tx_channel()),
             log_update_rx: Some(bc.new_block_logs_channel()),
@@ -403,13 +518,14 @@ where
 impl<P, DB> Actor for BlockHistoryActor<P, DB>
 where
     P: Provider<Ethereum> + DebugProviderExt<Ethereum> + Sync + Send + Clone + 'static,
-    DB: BlockHistoryState + DatabaseRef + DatabaseCommit + DatabaseLoomExt + Send + Sync + Clone + 'static,
+    DB: BlockHistoryState + DatabaseRef + Database + DatabaseCommit + DatabaseLoomExt + Send + Sync + Clone + 'static,
 {
     fn start(&self) -> ActorResult {
         let task = tokio::task::spawn(new_block_history_worker(
             self.client.clone(),
             self.chain_parameters.clone(),
             self.latest_block.clone().unwrap(),
+            self.market.clone().unwrap(),
             self.market_state.clone().unwrap(),
             self.block_history.clone().unwrap(),
             self.block_header_update_rx.clone().unwrap(),
@@ -417,6 +533,7 @@ where
             self.log_update_rx.clone().unwrap(),
             self.state_update_rx.clone().unwrap(),
             self.market_events_tx.clone().unwrap(),
+            self.cancellation_token.clone(),
         ));
         Ok(vec![task])
     }
@@ -27,14 +27,18 @@ use loom_core_blockchain::{Blockchain, BlockchainState, Strategy};
 use loom_core_mempool::MempoolActor;
 use loom_core_router::SwapRouterActor;
 use loom_defi_address_book::TokenAddressEth;
-use loom_defi_health_monitor::{MetricsRecorderActor, PoolHealthMonitorActor, StuffingTxMonitorActor};
+use loom_defi_health_monitor::{MetricsRecorderActor, PoolHealthMonitorActor, ProfitLedgerActor, StuffingTxMonitorActor};
 use loom_defi_market::{
     HistoryPoolLoaderOneShotActor, NewPoolLoaderActor, PoolLoaderActor, ProtocolPoolLoaderOneShotActor, RequiredPoolLoaderActor,
 };
-use loom_defi_pools::{PoolLoadersBuilder, PoolsLoadingConfig, UniswapV2PoolLoader, UniswapV3PoolLoader, MaverickPoolLoader};
-use loom_defi_preloader::MarketStatePreloadedOneShotActor;
+use loom_defi_pools::{
+    BalancerV2PoolLoader, CurvePoolLoader, MaverickPoolLoader, MaverickV2PoolLoader, PoolLoadersBuilder, PoolsLoadingConfig,
+    UniswapV2PoolLoader, UniswapV3PoolLoader, UniswapV4PoolLoader, VaultPoolLoader,
+};
+use loom_defi_preloader::{MarketStatePreloadedOneShotActor, MarketStateSnapshotActor, MarketStateSnapshotRestoreOneShotActor};
 use loom_types_entities::{PoolId, PoolClass, BlockHistoryState, SwapEncoder, TxSigners};
 use loom_types_entities::required_state::RequiredState;
+use loom_types_events::LoomTask;
 use loom_types_blockchain::loom_data_types_ethereum::LoomDataTypesEthereum;
 use tokio::runtime::Runtime;
 use futures::executor::block_on;
@@ -57,6 +61,7 @@ use loom_strategy_backrun::{
     BackrunConfig, BlockStateChangeProcessorActor, PendingTxStateChangeProcessorActor, StateChangeArbSearcherActor,
 };
 use loom_strategy_merger::{ArbSwapPathMergerActor, DiffPathMergerActor, SamePathMergerActor};
+use loom_strategy_sandwich::SandwichDetectorActor;
 use revm::{Database, DatabaseCommit, DatabaseRef};
 use std::collections::HashMap;
 use tokio_util::sync::CancellationToken;
@@ -68,6 +73,7 @@ pub struct BlockchainActors<P, DB: Clone + Send + Sync + 'static, E: Clone = Mul
     strategy: Strategy<DB>,
     pub signers: SharedState<TxSigners>,
     actor_manager: ActorsManager,
+    cancellation_token: CancellationToken,
     encoder: Option<E>,
     has_mempool: bool,
     has_state_update: bool,
@@ -99,13 +105,18 @@ where
         strategy: Strategy<DB>,
         relays: Vec<RelayConfig>,
     ) -> Self {
+        let cancellation_token = CancellationToken::new();
+        let tasks_channel = bc.tasks_channel();
         Self {
             provider,
             bc,
             state,
             strategy,
             signers: SharedState::new(TxSigners::new()),
-            actor_manager: ActorsManager::new(),
+            actor_manager: ActorsManager::new().with_cancellation_token(cancellation_token.clone()).with_restart_listener(move |name| {
+                let _ = tasks_channel.send(LoomTask::ActorRestarted { name: name.to_string() });
+            }),
+            cancellation_token,
             encoder: Some(encoder),
             has_mempool: false,
             has_state_update: false,
@@ -145,12 +156,23 @@ where
             builder = builder.add_loader(PoolClass::UniswapV2, UniswapV2PoolLoader::with_provider(provider.clone()));
         }
         if pool_classes.contains(&PoolClass::Curve) {
-            // CurvePoolLoader is not defined, so skip adding loader for Curve pools or replace with correct loader if available
-            // builder = builder.add_loader(PoolClass::Curve, CurvePoolLoader::with_provider(provider.clone()));
+            builder = builder.add_loader(PoolClass::Curve, CurvePoolLoader::with_provider(provider.clone()));
+        }
+        if pool_classes.contains(&PoolClass::UniswapV4) {
+            builder = builder.add_loader(PoolClass::UniswapV4, UniswapV4PoolLoader::with_provider(provider.clone()));
         }
         if pool_classes.contains(&PoolClass::Maverick) {
             builder = builder.add_loader(PoolClass::Maverick, MaverickPoolLoader::with_provider(provider.clone()));
         }
+        if pool_classes.contains(&PoolClass::MaverickV2) {
+            builder = builder.add_loader(PoolClass::MaverickV2, MaverickV2PoolLoader::with_provider(provider.clone()));
+        }
+        if pool_classes.contains(&PoolClass::BalancerV2) {
+            builder = builder.add_loader(PoolClass::BalancerV2, BalancerV2PoolLoader::with_provider(provider.clone()));
+        }
+        if pool_classes.contains(&PoolClass::Erc4626Vault) {
+            builder = builder.add_loader(PoolClass::Erc4626Vault, VaultPoolLoader::with_provider(provider.clone()));
+        }
 
         let pool_loaders = builder.build();
 
@@ -294,7 +316,11 @@ where
     pub fn with_signers(&mut self) -> Result<&mut Self> {
         if !self.has_signers {
             self.has_signers = true;
-            let closure = move || Box::new(TxSignersActor::<LoomDataTypesEthereum>::new()) as Box<dyn LoomActor + Send + Sync>;
+            let cancellation_token = self.cancellation_token.clone();
+            let closure = move || {
+                Box::new(TxSignersActor::<LoomDataTypesEthereum>::new().with_cancellation_token(cancellation_token.clone()))
+                    as Box<dyn LoomActor + Send + Sync>
+            };
             self.actor_manager.start(closure)?;
         }
         Ok(self)
@@ -303,9 +329,18 @@ where
     pub fn with_swap_encoder(&mut self, swap_encoder: E) -> Result<&mut Self> {
         self.mutlicaller_address = Some(swap_encoder.address());
         self.encoder = Some(swap_encoder);
+        let delegate = swap_encoder.address();
         let bc = self.bc.clone();
         let strategy = self.strategy.clone();
-        let closure = move || Box::new(SwapRouterActor::<DB>::new().on_bc(&bc, &strategy)) as Box<dyn LoomActor + Send + Sync>;
+        let cancellation_token = self.cancellation_token.clone();
+        let closure = move || {
+            Box::new(
+                SwapRouterActor::<DB>::new()
+                    .with_eip7702_delegate(delegate)
+                    .on_bc(&bc, &strategy)
+                    .with_cancellation_token(cancellation_token.clone()),
+            ) as Box<dyn LoomActor + Send + Sync>
+        };
         self.actor_manager.start(closure)?;
         Ok(self)
     }
@@ -326,12 +361,38 @@ where
         self.actor_manager.start(closure)?;
         Ok(self)
     }
+    /// Restores the latest market state snapshot not older than max_age_blocks and starts market state preloader for the
+    /// accounts missing in it
+    pub fn with_market_state_preloader_from_snapshot(&mut self, db_pool: DbPool, max_age_blocks: u64) -> Result<&mut Self> {
+        // One-shot actors, the preloader runs after the snapshot is restored
+        let restore_actor = MarketStateSnapshotRestoreOneShotActor::<P, Ethereum, DB>::new(self.provider.clone(), db_pool, max_age_blocks).on_bc(&self.bc, &self.state);
+        self.actor_manager.start_and_wait(restore_actor)?;
+        let preload_actor = MarketStatePreloadedOneShotActor::<P, Ethereum, DB>::new(self.provider.clone()).on_bc(&self.bc, &self.state);
+        self.actor_manager.start_and_wait(preload_actor)?;
+        Ok(self)
+    }
+    /// Starts writing market state snapshots to the database
+    pub fn with_market_state_snapshots(&mut self, db_pool: DbPool) -> Result<&mut Self> {
+        let bc = self.bc.clone();
+        let state = self.state.clone();
+        let cancellation_token = self.cancellation_token.clone();
+        let closure = move || {
+            Box::new(MarketStateSnapshotActor::<DB>::new(db_pool.clone()).on_bc(&bc, &state).with_cancellation_token(cancellation_token.clone()))
+                as Box<dyn LoomActor + Send + Sync>
+        };
+        self.actor_manager.start(closure)?;
+        Ok(self)
+    }
     /// Starts nonce and balance monitor
     pub fn with_nonce_and_balance_monitor(&mut self) -> Result<&mut Self> {
         use std::sync::Arc;
         let provider = Arc::new(self.provider.clone());
         let _provider_clone = provider.clone();
-        let closure = move || Box::new(NonceAndBalanceMonitorActor::new(provider.clone())) as Box<dyn LoomActor + Send + Sync>;
+        let cancellation_token = self.cancellation_token.clone();
+        let closure = move || {
+            Box::new(NonceAndBalanceMonitorActor::new(provider.clone()).with_cancellation_token(cancellation_token.clone()))
+                as Box<dyn LoomActor + Send + Sync>
+        };
         self.actor_manager.start(closure)?;
         Ok(self)
     }
@@ -341,7 +402,11 @@ where
         let provider = Arc::new(self.provider.clone());
         let bc = Arc::new(self.bc.clone());
         let state = Arc::new(self.state.clone());
-        let closure = move || Box::new(BlockHistoryActor::new((*provider).clone()).on_bc(&bc, &state)) as Box<dyn LoomActor + Send + Sync>;
+        let cancellation_token = self.cancellation_token.clone();
+        let closure = move || {
+            Box::new(BlockHistoryActor::new((*provider).clone()).on_bc(&bc, &state).with_cancellation_token(cancellation_token.clone()))
+                as Box<dyn LoomActor + Send + Sync>
+        };
         self.actor_manager.start(closure)?;
         Ok(self)
     }
@@ -350,7 +415,36 @@ where
         use std::sync::Arc;
         let provider = Arc::new(self.provider.clone());
         let bc = Arc::new(self.bc.clone());
-        let closure = move || Box::new(PriceActor::new(provider.clone()).on_bc(&bc)) as Box<dyn LoomActor + Send + Sync>;
+        let cancellation_token = self.cancellation_token.clone();
+        let closure = move || {
+            Box::new(PriceActor::new(provider.clone()).on_bc(&bc).with_cancellation_token(cancellation_token.clone()))
+                as Box<dyn LoomActor + Send + Sync>
+        };
+        self.actor_manager.start(closure)?;
+        Ok(self)
+    }
+    /// Starts sandwich detector, victims of detected sandwiches are not backrun
+    pub fn with_sandwich_detector(&mut self) -> Result<&mut Self> {
+        use std::sync::Arc;
+        let bc = Arc::new(self.bc.clone());
+        let cancellation_token = self.cancellation_token.clone();
+        let closure = move || {
+            Box::new(SandwichDetectorActor::new().on_bc(&bc).with_cancellation_token(cancellation_token.clone()))
+                as Box<dyn LoomActor + Send + Sync>
+        };
+        self.actor_manager.start(closure)?;
+        Ok(self)
+    }
+    /// Starts profit ledger, realized profit of landed backrun transactions is written to the database
+    pub fn with_profit_ledger(&mut self, db_pool: DbPool) -> Result<&mut Self> {
+        use std::sync::Arc;
+        let provider = Arc::new(self.provider.clone());
+        let bc = Arc::new(self.bc.clone());
+        let cancellation_token = self.cancellation_token.clone();
+        let closure = move || {
+            Box::new(ProfitLedgerActor::new(provider.clone(), db_pool.clone()).on_bc(&bc).with_cancellation_token(cancellation_token.clone()))
+                as Box<dyn LoomActor + Send + Sync>
+        };
         self.actor_manager.start(closure)?;
         Ok(self)
     }
@@ -364,9 +458,10 @@ where
         let _provider_clone = provider.clone();
         let bc_clone = bc.clone();
         let config_clone = config.clone();
+        let cancellation_token = self.cancellation_token.clone();
         let closure = move || {
             let actor = NodeBlockActor::new((*provider).clone(), config_clone.clone());
-            let actor = actor.on_bc(&bc_clone);
+            let actor = actor.on_bc(&bc_clone).with_cancellation_token(cancellation_token.clone());
             Box::new(actor) as Box<dyn LoomActor + Send + Sync>
         };
         self.actor_manager.start(closure)?;
@@ -376,7 +471,11 @@ where
     pub fn with_exex_events(&mut self) -> Result<&mut Self> {
         self.mempool()?;
         let bc = self.bc.clone();
-        let closure = move || Box::new(NodeExExGrpcActor::new("http://[::1]:10000".to_string()).on_bc(&bc)) as Box<dyn LoomActor + Send + Sync>;
+        let cancellation_token = self.cancellation_token.clone();
+        let closure = move || {
+            Box::new(NodeExExGrpcActor::new("http://[::1]:10000".to_string()).on_bc(&bc).with_cancellation_token(cancellation_token.clone()))
+                as Box<dyn LoomActor + Send + Sync>
+        };
         self.actor_manager.start(closure)?;
         Ok(self)
     }
@@ -385,7 +484,10 @@ where
         if !self.has_mempool {
             self.has_mempool = true;
             let bc = self.bc.clone();
-            let closure = move || Box::new(MempoolActor::new().on_bc(&bc)) as Box<dyn LoomActor + Send + Sync>;
+            let cancellation_token = self.cancellation_token.clone();
+            let closure = move || {
+                Box::new(MempoolActor::new().on_bc(&bc).with_cancellation_token(cancellation_token.clone())) as Box<dyn LoomActor + Send + Sync>
+            };
             self.actor_manager.start(closure)?;
         }
         Ok(self)
@@ -404,7 +506,11 @@ where
         let flashbots = Arc::new(flashbots);
         let closure = {
             let flashbots = flashbots.clone();
-            move || Box::new(FlashbotsBroadcastActor::new(flashbots.clone(), allow_broadcast)) as Box<dyn LoomActor + Send + Sync>
+            let cancellation_token = self.cancellation_token.clone();
+            move || {
+                Box::new(FlashbotsBroadcastActor::new(flashbots.clone(), allow_broadcast).with_cancellation_token(cancellation_token.clone()))
+                    as Box<dyn LoomActor + Send + Sync>
+            }
         };
         self.actor_manager.start(closure)?;
         Ok(self)
@@ -413,7 +519,11 @@ where
     /// Starts EVM estimator actor
     pub fn with_evm_estimator(&mut self) -> Result<&mut Self> {
         let encoder = self.encoder.clone().expect("Encoder must be set before starting EvmEstimatorActor");
-        let closure = move || Box::new(EvmEstimatorActor::<P, Ethereum, E, DB>::new(encoder.clone())) as Box<dyn LoomActor + Send + Sync>;
+        let cancellation_token = self.cancellation_token.clone();
+        let closure = move || {
+            Box::new(EvmEstimatorActor::<P, Ethereum, E, DB>::new(encoder.clone()).with_cancellation_token(cancellation_token.clone()))
+                as Box<dyn LoomActor + Send + Sync>
+        };
         self.actor_manager.start(closure)?;
         Ok(self)
     }
@@ -454,7 +564,12 @@ where
 
         let closure = {
             let pool_loaders = pool_loaders.clone();
-            move || Box::new(NewPoolLoaderActor::new(pool_loaders.clone())) as Box<dyn LoomActor + Send + Sync>
+            let bc = self.bc.clone();
+            let cancellation_token = self.cancellation_token.clone();
+            move || {
+                Box::new(NewPoolLoaderActor::new(pool_loaders.clone()).on_bc(&bc).with_cancellation_token(cancellation_token.clone()))
+                    as Box<dyn LoomActor + Send + Sync>
+            }
         };
         self.actor_manager.start(closure)?;
         Ok(self)
@@ -477,7 +592,13 @@ where
         let closure = {
             let pool_loaders = pool_loaders.clone();
             let provider = self.provider.clone();
-            move || Box::new(PoolLoaderActor::<P, P, alloy_network::Ethereum, DB>::new(provider.clone(), pool_loaders.clone(), pools_config.clone())) as Box<dyn LoomActor + Send + Sync>
+            let cancellation_token = self.cancellation_token.clone();
+            move || {
+                Box::new(
+                    PoolLoaderActor::<P, P, alloy_network::Ethereum, DB>::new(provider.clone(), pool_loaders.clone(), pools_config.clone())
+                        .with_cancellation_token(cancellation_token.clone()),
+                ) as Box<dyn LoomActor + Send + Sync>
+            }
         };
         self.actor_manager.start(closure)?;
         Ok(self)
@@ -489,19 +610,29 @@ where
         let state = self.state.clone();
         let strategy = self.strategy.clone();
         let multicaller_address = self.mutlicaller_address.ok_or_else(|| eyre::eyre!("Multicaller address not set"))?;
+        let cancellation_token = self.cancellation_token.clone();
 
         let closure_arb = {
             let multicaller_address = multicaller_address.clone();
             let bc = bc.clone();
             let strategy = strategy.clone();
-            move || Box::new(ArbSwapPathMergerActor::new(multicaller_address).on_bc(&bc, &strategy)) as Box<dyn LoomActor + Send + Sync>
+            let cancellation_token = cancellation_token.clone();
+            move || {
+                Box::new(
+                    ArbSwapPathMergerActor::new(multicaller_address).on_bc(&bc, &strategy).with_cancellation_token(cancellation_token.clone()),
+                ) as Box<dyn LoomActor + Send + Sync>
+            }
         };
         self.actor_manager.start(closure_arb)?;
 
         let closure_diff = {
             let bc = bc.clone();
             let strategy = strategy.clone();
-            move || Box::new(DiffPathMergerActor::new().on_bc(&bc, &strategy)) as Box<dyn LoomActor + Send + Sync>
+            let cancellation_token = cancellation_token.clone();
+            move || {
+                Box::new(DiffPathMergerActor::new().on_bc(&bc, &strategy).with_cancellation_token(cancellation_token.clone()))
+                    as Box<dyn LoomActor + Send + Sync>
+            }
         };
         self.actor_manager.start(closure_diff)?;
 
@@ -510,7 +641,13 @@ where
             let state = state.clone();
             let strategy = strategy.clone();
             let provider = self.provider.clone();
-            move || Box::new(SamePathMergerActor::new(provider.clone()).on_bc(&bc, &state, &strategy)) as Box<dyn LoomActor + Send + Sync>
+            move || {
+                Box::new(
+                    SamePathMergerActor::new(provider.clone())
+                        .on_bc(&bc, &state, &strategy)
+                        .with_cancellation_token(cancellation_token.clone()),
+                ) as Box<dyn LoomActor + Send + Sync>
+            }
         };
         self.actor_manager.start(closure_same)?;
 
@@ -528,7 +665,12 @@ where
             let state = state.clone();
             let strategy = strategy.clone();
             let backrun_config = backrun_config.clone();
-            move || Box::new(BlockStateChangeProcessorActor::new().on_bc(&bc, &state, &strategy)) as Box<dyn LoomActor + Send + Sync>
+            let cancellation_token = self.cancellation_token.clone();
+            move || {
+                Box::new(
+                    BlockStateChangeProcessorActor::new().on_bc(&bc, &state, &strategy).with_cancellation_token(cancellation_token.clone()),
+                ) as Box<dyn LoomActor + Send + Sync>
+            }
         };
         self.actor_manager.start(closure)?;
         Ok(self)
@@ -545,7 +687,14 @@ where
             let state = state.clone();
             let strategy = strategy.clone();
             let provider = self.provider.clone();
-            move || Box::new(PendingTxStateChangeProcessorActor::new(provider.clone()).on_bc(&bc, &state, &strategy)) as Box<dyn LoomActor + Send + Sync>
+            let cancellation_token = self.cancellation_token.clone();
+            move || {
+                Box::new(
+                    PendingTxStateChangeProcessorActor::new(provider.clone())
+                        .on_bc(&bc, &state, &strategy)
+                        .with_cancellation_token(cancellation_token.clone()),
+                ) as Box<dyn LoomActor + Send + Sync>
+            }
         };
         self.actor_manager.start(closure)?;
         Ok(self)
@@ -555,12 +704,78 @@ where
         self.actor_manager.wait().await;
     }
 
+    /// Token cancelled on shutdown, for workers started outside of the actor manager
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation_token.clone()
+    }
+
+    pub fn bc(&self) -> &Blockchain {
+        &self.bc
+    }
+
+    /// Cancel all started actors and wait for their workers to stop
+    pub async fn shutdown(&mut self) {
+        self.cancellation_token.cancel();
+        self.actor_manager.wait().await;
+    }
+
+    /// Starts web server on `host` serving live market and strategy state, `/health` and Prometheus `/metrics`
     pub fn with_web_server(
         &mut self,
-        _host: String,
-        _router: Router,
-        _db_pool: DbPool,
+        host: String,
+        router: Router,
+        db_pool: DbPool,
     ) -> Result<&mut Self> {
+        let cancellation_token = self.cancellation_token.clone();
+        let bc = self.bc.clone();
+        let state = self.state.clone();
+        let strategy = self.strategy.clone();
+        let closure = move || {
+            Box::new(
+                WebServerActor::new(host.clone(), router.clone(), db_pool.clone(), cancellation_token.clone())
+                    .on_bc(&bc, &state)
+                    .on_strategy(&strategy),
+            ) as Box<dyn LoomActor + Send + Sync>
+        };
+        self.actor_manager.start(closure)?;
+        Ok(self)
+    }
+
+    /// Starts web server as `with_web_server` does, with the block, opportunity, bundle and profit counters of
+    /// `MetricsRecorderActor` appended to `/metrics`
+    #[cfg(feature = "prometheus")]
+    pub fn with_web_server_and_prometheus(&mut self, host: String, router: Router, db_pool: DbPool) -> Result<&mut Self> {
+        let prometheus_metrics = loom_defi_health_monitor::PrometheusMetrics::new()?;
+        let cancellation_token = self.cancellation_token.clone();
+        let bc = self.bc.clone();
+        let state = self.state.clone();
+        let strategy = self.strategy.clone();
+
+        let metrics_recorder_closure = {
+            let (bc, state, strategy, prometheus_metrics) = (bc.clone(), state.clone(), strategy.clone(), prometheus_metrics.clone());
+            let cancellation_token = cancellation_token.clone();
+            move || {
+                Box::new(
+                    MetricsRecorderActor::new()
+                        .on_bc(&bc, &state)
+                        .on_strategy(&strategy)
+                        .with_prometheus(prometheus_metrics.clone())
+                        .with_cancellation_token(cancellation_token.clone()),
+                ) as Box<dyn LoomActor + Send + Sync>
+            }
+        };
+        self.actor_manager.start(metrics_recorder_closure)?;
+
+        let registry = prometheus_metrics.registry();
+        let closure = move || {
+            Box::new(
+                WebServerActor::new(host.clone(), router.clone(), db_pool.clone(), cancellation_token.clone())
+                    .on_bc(&bc, &state)
+                    .on_strategy(&strategy)
+                    .with_prometheus_registry(registry.clone()),
+            ) as Box<dyn LoomActor + Send + Sync>
+        };
+        self.actor_manager.start(closure)?;
         Ok(self)
     }
 
@@ -571,7 +786,11 @@ where
         tags: Vec<(String, String)>,
     ) -> Result<&mut Self> {
         let tags_map: std::collections::HashMap<String, String> = tags.clone().into_iter().collect();
-        let closure = move || Box::new(InfluxDbWriterActor::new(url.clone(), database.clone(), tags_map.clone())) as Box<dyn LoomActor + Send + Sync>;
+        let cancellation_token = self.cancellation_token.clone();
+        let closure = move || {
+            Box::new(InfluxDbWriterActor::new(url.clone(), database.clone(), tags_map.clone()).with_cancellation_token(cancellation_token.clone()))
+                as Box<dyn LoomActor + Send + Sync>
+        };
         self.actor_manager.start(closure)?;
         Ok(self)
     }
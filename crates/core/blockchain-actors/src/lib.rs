@@ -1,3 +1,5 @@
 pub use actor::BlockchainActors;
+pub use multichain::MultiChainBlockchainActors;
 
 mod actor;
+mod multichain;
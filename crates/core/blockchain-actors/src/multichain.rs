@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+use alloy_network::Ethereum;
+use alloy_primitives::ChainId;
+use alloy_provider::Provider;
+use eyre::{ErrReport, OptionExt, Result};
+use loom_core_actors::Actor as LoomActor;
+use loom_core_actors::ActorsManager;
+use loom_core_blockchain::Blockchain;
+use loom_evm_db::DatabaseLoomExt;
+use loom_execution_multicaller::MulticallerSwapEncoder;
+use loom_node_debug_provider::DebugProviderExt;
+use loom_strategy_cross_chain::{CrossChainArbFinderActor, CrossChainToken};
+use loom_types_entities::{BlockHistoryState, SwapEncoder};
+use revm::{Database, DatabaseCommit, DatabaseRef};
+use tokio_util::sync::CancellationToken;
+
+use crate::BlockchainActors;
+
+/// Actors of several chains running side by side, with actors spanning more than one chain
+pub struct MultiChainBlockchainActors<P, DB: Clone + Send + Sync + 'static, E: Clone = MulticallerSwapEncoder> {
+    chains: HashMap<ChainId, BlockchainActors<P, DB, E>>,
+    actor_manager: ActorsManager,
+    cancellation_token: CancellationToken,
+}
+
+impl<P, DB, E> Default for MultiChainBlockchainActors<P, DB, E>
+where
+    DB: Clone + Send + Sync + 'static,
+    E: Clone,
+{
+    fn default() -> Self {
+        let cancellation_token = CancellationToken::new();
+        Self {
+            chains: HashMap::new(),
+            actor_manager: ActorsManager::new().with_cancellation_token(cancellation_token.clone()),
+            cancellation_token,
+        }
+    }
+}
+
+impl<P, DB, E> MultiChainBlockchainActors<P, DB, E>
+where
+    P: Provider<Ethereum> + DebugProviderExt<Ethereum> + Send + Sync + Clone + 'static,
+    DB: DatabaseRef<Error = ErrReport>
+        + Database<Error = ErrReport>
+        + DatabaseCommit
+        + DatabaseLoomExt
+        + BlockHistoryState
+        + Send
+        + Sync
+        + Clone
+        + Default
+        + 'static,
+    E: SwapEncoder + Send + Sync + Clone + 'static,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds actors of a chain, actors already added for the same chain id are replaced
+    pub fn with_chain(mut self, chain_actors: BlockchainActors<P, DB, E>) -> Self {
+        self.chains.insert(chain_actors.bc().chain_id(), chain_actors);
+        self
+    }
+
+    pub fn chain(&mut self, chain_id: ChainId) -> Result<&mut BlockchainActors<P, DB, E>> {
+        self.chains.get_mut(&chain_id).ok_or_eyre("CHAIN_NOT_FOUND")
+    }
+
+    pub fn chain_ids(&self) -> Vec<ChainId> {
+        let mut chain_ids: Vec<ChainId> = self.chains.keys().copied().collect();
+        chain_ids.sort();
+        chain_ids
+    }
+
+    /// Starts a cross chain arb finder for every pair of chains. Tokens of `tokens` priced at least `min_delta_bps` apart are
+    /// alerted to the health monitor channel of the chain with the lower chain id.
+    pub fn with_cross_chain_arb_finder(&mut self, tokens: Vec<CrossChainToken>, min_delta_bps: u64) -> Result<&mut Self> {
+        let chain_ids = self.chain_ids();
+        for (idx, chain_id) in chain_ids.iter().enumerate() {
+            for other_chain_id in chain_ids.iter().skip(idx + 1) {
+                let bc: Blockchain = self.chains[chain_id].bc().clone();
+                let other_bc: Blockchain = self.chains[other_chain_id].bc().clone();
+                let tokens = tokens.clone();
+                let cancellation_token = self.cancellation_token.clone();
+                let closure = move || {
+                    Box::new(
+                        CrossChainArbFinderActor::new(tokens.clone(), min_delta_bps)
+                            .on_bc(&bc, &other_bc)
+                            .with_cancellation_token(cancellation_token.clone()),
+                    ) as Box<dyn LoomActor + Send + Sync>
+                };
+                self.actor_manager.start(closure)?;
+            }
+        }
+        Ok(self)
+    }
+
+    /// Wait for actors of all chains
+    pub async fn wait(&mut self) {
+        for chain_actors in self.chains.values_mut() {
+            chain_actors.wait().await;
+        }
+        self.actor_manager.wait().await;
+    }
+
+    /// Cancel actors of all chains and wait for their workers to stop
+    pub async fn shutdown(&mut self) {
+        self.cancellation_token.cancel();
+        for chain_actors in self.chains.values_mut() {
+            chain_actors.shutdown().await;
+        }
+        self.actor_manager.wait().await;
+    }
+}
@@ -1,16 +1,29 @@
-use alloy::primitives::{BlockHash, ChainId};
+use alloy::primitives::{BlockHash, ChainId, U256};
 use influxdb::WriteQuery;
-use loom_core_actors::{Broadcaster, SharedState};
+use loom_core_actors::{Broadcaster, SharedState, TrackedReceiver};
 use loom_types_blockchain::{ChainParameters, Mempool, LoomDataTypes, LoomDataTypesEthereum};
-use loom_types_entities::{AccountNonceAndBalanceState, LatestBlock, Market, BlockHistory, BlockHistoryState, MarketState};
+use loom_types_entities::{
+    AccountNonceAndBalanceState, BlockHistory, BlockHistoryState, BundleHistory, LatestBlock, Market, MarketState,
+    DEFAULT_MAX_STATE_SIZE_BYTES,
+};
 use loom_types_events::{
-    LoomTask, MarketEvents, MempoolEvents, MessageBlock, MessageBlockHeader, MessageBlockLogs, MessageBlockStateUpdate, MessageHealthEvent,
-    MessageMempoolDataUpdate, MessageTxCompose,
+    HealthEvent, LoomTask, MarketEvents, MempoolEvents, MessageBlock, MessageBlockHeader, MessageBlockLogs, MessageBlockStateUpdate,
+    MessageHealthEvent, MessageMempoolDataUpdate, MessageTxCompose, SwapTraces,
 };
 use revm::{Database, DatabaseCommit, DatabaseRef};
-use tracing::error;
+use std::time::Duration;
+use tracing::{error, warn};
 use loom_evm_db::DatabaseLoomExt;
 
+// Block and mempool updates can arrive from several sources (node block actor, ExEx), drop repeats within this window
+const DEDUP_WINDOW_SECS: u64 = 30;
+
+// Queued messages after which a tracked subscriber is reported as lagging, half of the market events channel capacity
+pub const DEFAULT_CHANNEL_LAG_THRESHOLD: usize = 50;
+
+// Slot of the `balanceOf` mapping of WETH9, WETH on Ethereum and the Base predeploy share the layout
+pub const DEFAULT_WETH_BALANCE_OF_SLOT: u64 = 3;
+
 #[derive(Clone)]
 pub struct Blockchain<LDT: LoomDataTypes + 'static = LoomDataTypesEthereum> {
     chain_id: ChainId,
@@ -19,6 +32,8 @@ pub struct Blockchain<LDT: LoomDataTypes + 'static = LoomDataTypesEthereum> {
     latest_block: SharedState<LatestBlock<LDT>>,
     mempool: SharedState<Mempool<LDT>>,
     account_nonce_and_balance: SharedState<AccountNonceAndBalanceState<LDT>>,
+    swap_traces: SharedState<SwapTraces>,
+    bundle_history: SharedState<BundleHistory>,
 
     new_block_headers_channel: Broadcaster<MessageBlockHeader<LDT>>,
     new_block_with_tx_channel: Broadcaster<MessageBlock<LDT>>,
@@ -32,24 +47,33 @@ pub struct Blockchain<LDT: LoomDataTypes + 'static = LoomDataTypesEthereum> {
     pool_health_monitor_channel: Broadcaster<MessageHealthEvent<LDT>>,
     influxdb_write_channel: Broadcaster<WriteQuery>,
     tasks_channel: Broadcaster<LoomTask>,
+
+    min_profit_wei: Option<U256>,
+    log_subscription_prefilter: bool,
+    expected_block_time: Duration,
+    channel_lag_threshold: usize,
+    weth_balance_of_slot: u64,
 }
 
 impl Blockchain<LoomDataTypesEthereum> {
     pub fn new(chain_id: ChainId) -> Blockchain<LoomDataTypesEthereum> {
-        let new_block_headers_channel: Broadcaster<MessageBlockHeader> = Broadcaster::new(10);
-        let new_block_with_tx_channel: Broadcaster<MessageBlock> = Broadcaster::new(10);
-        let new_block_state_update_channel: Broadcaster<MessageBlockStateUpdate> = Broadcaster::new(10);
-        let new_block_logs_channel: Broadcaster<MessageBlockLogs> = Broadcaster::new(10);
+        let new_block_headers_channel: Broadcaster<MessageBlockHeader> =
+            Broadcaster::new(10).with_name("new_block_headers").with_dedup_window(Duration::from_secs(DEDUP_WINDOW_SECS));
+        let new_block_with_tx_channel: Broadcaster<MessageBlock> = Broadcaster::new(10).with_name("new_block_with_tx");
+        let new_block_state_update_channel: Broadcaster<MessageBlockStateUpdate> =
+            Broadcaster::new(10).with_name("new_block_state_update");
+        let new_block_logs_channel: Broadcaster<MessageBlockLogs> = Broadcaster::new(10).with_name("new_block_logs");
 
-        let new_mempool_tx_channel: Broadcaster<MessageMempoolDataUpdate> = Broadcaster::new(5000);
+        let new_mempool_tx_channel: Broadcaster<MessageMempoolDataUpdate> =
+            Broadcaster::new(5000).with_name("new_mempool_tx").with_dedup_window(Duration::from_secs(DEDUP_WINDOW_SECS));
 
-        let market_events_channel: Broadcaster<MarketEvents> = Broadcaster::new(100);
-        let mempool_events_channel: Broadcaster<MempoolEvents> = Broadcaster::new(2000);
-        let tx_compose_channel: Broadcaster<MessageTxCompose> = Broadcaster::new(2000);
+        let market_events_channel: Broadcaster<MarketEvents> = Broadcaster::new(100).with_name("market_events");
+        let mempool_events_channel: Broadcaster<MempoolEvents> = Broadcaster::new(2000).with_name("mempool_events");
+        let tx_compose_channel: Broadcaster<MessageTxCompose> = Broadcaster::new(2000).with_name("tx_compose");
 
-        let pool_health_monitor_channel: Broadcaster<MessageHealthEvent> = Broadcaster::new(1000);
-        let influx_write_channel: Broadcaster<WriteQuery> = Broadcaster::new(1000);
-        let tasks_channel: Broadcaster<LoomTask> = Broadcaster::new(1000);
+        let pool_health_monitor_channel: Broadcaster<MessageHealthEvent> = Broadcaster::new(1000).with_name("health_monitor");
+        let influx_write_channel: Broadcaster<WriteQuery> = Broadcaster::new(1000).with_name("influxdb_write");
+        let tasks_channel: Broadcaster<LoomTask> = Broadcaster::new(1000).with_name("tasks");
 
         let market_instance = Market::default();
 
@@ -65,6 +89,8 @@ impl Blockchain<LoomDataTypesEthereum> {
             mempool: SharedState::new(Mempool::<LoomDataTypesEthereum>::new()),
             latest_block: SharedState::new(LatestBlock::new(0, BlockHash::ZERO)),
             account_nonce_and_balance: SharedState::new(AccountNonceAndBalanceState::new()),
+            swap_traces: SharedState::new(SwapTraces::default()),
+            bundle_history: SharedState::new(BundleHistory::new()),
             new_block_headers_channel,
             new_block_with_tx_channel,
             new_block_state_update_channel,
@@ -76,11 +102,35 @@ impl Blockchain<LoomDataTypesEthereum> {
             tx_compose_channel,
             influxdb_write_channel: influx_write_channel,
             tasks_channel,
+            min_profit_wei: None,
+            log_subscription_prefilter: false,
+            expected_block_time: Duration::from_secs(12),
+            channel_lag_threshold: DEFAULT_CHANNEL_LAG_THRESHOLD,
+            weth_balance_of_slot: DEFAULT_WETH_BALANCE_OF_SLOT,
+        }
+    }
+
+    /// Base has 2 seconds blocks, cheap gas and a much higher log volume than Ethereum
+    pub fn with_base_defaults(self) -> Self {
+        Self {
+            mempool_events_channel: Broadcaster::new(5000).with_name("mempool_events"),
+            min_profit_wei: Some(U256::from(1_000_000_000_000u64)),
+            log_subscription_prefilter: true,
+            expected_block_time: Duration::from_secs(2),
+            ..self
         }
     }
 }
 
 impl<LDT: LoomDataTypes> Blockchain<LDT> {
+    pub fn with_channel_lag_threshold(self, channel_lag_threshold: usize) -> Self {
+        Self { channel_lag_threshold, ..self }
+    }
+
+    pub fn with_weth_balance_of_slot(self, weth_balance_of_slot: u64) -> Self {
+        Self { weth_balance_of_slot, ..self }
+    }
+
     pub fn chain_id(&self) -> u64 {
         self.chain_id
     }
@@ -105,6 +155,16 @@ impl<LDT: LoomDataTypes> Blockchain<LDT> {
         self.account_nonce_and_balance.clone()
     }
 
+    /// Execution traces of simulated swaps, filled by estimators built with the `debug-traces` feature
+    pub fn swap_traces(&self) -> SharedState<SwapTraces> {
+        self.swap_traces.clone()
+    }
+
+    /// Latest own bundles found in mined blocks, filled by `StuffingTxMonitorActor`
+    pub fn bundle_history(&self) -> SharedState<BundleHistory> {
+        self.bundle_history.clone()
+    }
+
     pub fn new_block_headers_channel(&self) -> Broadcaster<MessageBlockHeader<LDT>> {
         self.new_block_headers_channel.clone()
     }
@@ -129,6 +189,19 @@ impl<LDT: LoomDataTypes> Blockchain<LDT> {
         self.market_events_channel.clone()
     }
 
+    /// Subscribe to market events. `HealthEvent::ChannelLag` is sent to the health monitor channel when the subscriber
+    /// falls behind the sender by more than the channel lag threshold.
+    pub fn subscribe_market_events_tracked(&self, subscriber_name: &'static str) -> TrackedReceiver<MarketEvents<LDT>> {
+        let health_monitor_channel = self.pool_health_monitor_channel.clone();
+        self.market_events_channel.subscribe().with_lag_monitor(self.channel_lag_threshold, move |lag| {
+            warn!(subscriber = subscriber_name, lag, "Market events subscriber is lagging");
+            let health_event = HealthEvent::ChannelLag { subscriber: subscriber_name, lag };
+            if let Err(e) = health_monitor_channel.send(MessageHealthEvent::new(health_event)) {
+                error!("health_monitor_channel.send : {}", e);
+            }
+        })
+    }
+
     pub fn mempool_events_channel(&self) -> Broadcaster<MempoolEvents<LDT>> {
         self.mempool_events_channel.clone()
     }
@@ -148,6 +221,27 @@ impl<LDT: LoomDataTypes> Blockchain<LDT> {
     pub fn tasks_channel(&self) -> Broadcaster<LoomTask> {
         self.tasks_channel.clone()
     }
+
+    pub fn min_profit_wei(&self) -> Option<U256> {
+        self.min_profit_wei
+    }
+
+    pub fn log_subscription_prefilter(&self) -> bool {
+        self.log_subscription_prefilter
+    }
+
+    pub fn expected_block_time(&self) -> Duration {
+        self.expected_block_time
+    }
+
+    pub fn channel_lag_threshold(&self) -> usize {
+        self.channel_lag_threshold
+    }
+
+    /// Slot of the `balanceOf` mapping of the wrapped native token of the chain
+    pub fn weth_balance_of_slot(&self) -> u64 {
+        self.weth_balance_of_slot
+    }
 }
 
 #[derive(Clone)]
@@ -164,14 +258,14 @@ impl<DB: DatabaseRef + Database + DatabaseCommit + BlockHistoryState + DatabaseL
 
 impl<DB: DatabaseRef + Database + DatabaseCommit + BlockHistoryState + DatabaseLoomExt + Send + Sync + Clone + Default + 'static> BlockchainState<DB> {
     pub fn new() -> Self {
-        BlockchainState {
-            market_state: SharedState::new(MarketState::new(DB::default())),
-            block_history_state: SharedState::new(BlockHistory::new(10)),
-        }
+        Self::new_with_market_state(MarketState::new(DB::default()))
     }
 
     pub fn new_with_market_state(market_state: MarketState<DB>) -> Self {
-        Self { market_state: SharedState::new(market_state), block_history_state: SharedState::new(BlockHistory::new(10)) }
+        BlockchainState {
+            market_state: SharedState::new(market_state),
+            block_history_state: SharedState::new(BlockHistory::new_with_limits(10, DEFAULT_MAX_STATE_SIZE_BYTES)),
+        }
     }
 
     pub fn with_market_state(self, market_state: MarketState<DB>) -> BlockchainState<DB> {
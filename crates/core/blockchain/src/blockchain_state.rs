@@ -1,6 +1,6 @@
 use loom_core_actors::SharedState;
 use loom_evm_db::DatabaseLoomExt;
-use loom_types_entities::{BlockHistory, BlockHistoryState, MarketState};
+use loom_types_entities::{BlockHistory, BlockHistoryState, MarketState, DEFAULT_MAX_STATE_SIZE_BYTES};
 use revm::{Database, DatabaseCommit, DatabaseRef};
 
 #[derive(Clone)]
@@ -21,14 +21,14 @@ impl<DB: DatabaseRef + Database + DatabaseCommit + BlockHistoryState + DatabaseL
     BlockchainState<DB>
 {
     pub fn new() -> Self {
-        BlockchainState {
-            market_state: SharedState::new(MarketState::new(DB::default())),
-            block_history_state: SharedState::new(BlockHistory::new(10)),
-        }
+        Self::new_with_market_state(MarketState::new(DB::default()))
     }
 
     pub fn new_with_market_state(market_state: MarketState<DB>) -> Self {
-        Self { market_state: SharedState::new(market_state), block_history_state: SharedState::new(BlockHistory::new(10)) }
+        BlockchainState {
+            market_state: SharedState::new(market_state),
+            block_history_state: SharedState::new(BlockHistory::new_with_limits(10, DEFAULT_MAX_STATE_SIZE_BYTES)),
+        }
     }
 
     pub fn with_market_state(self, market_state: MarketState<DB>) -> BlockchainState<DB> {
@@ -1,4 +1,4 @@
-pub use loom_core_blockchain_shared::{Blockchain, BlockchainState};
+pub use loom_core_blockchain_shared::{Blockchain, BlockchainState, DEFAULT_WETH_BALANCE_OF_SLOT};
 pub use robust_client::create_robust_provider;
 pub use strategy::Strategy;
 
@@ -23,8 +23,9 @@ impl<DB: DatabaseRef + Database + DatabaseCommit + BlockHistoryState + DatabaseL
     Strategy<DB, LoomDataTypesEthereum>
 {
     pub fn new() -> Self {
-        let compose_channel: Broadcaster<MessageSwapCompose<DB, LoomDataTypesEthereum>> = Broadcaster::new(100);
-        let state_update_channel: Broadcaster<StateUpdateEvent<DB, LoomDataTypesEthereum>> = Broadcaster::new(100);
+        let compose_channel: Broadcaster<MessageSwapCompose<DB, LoomDataTypesEthereum>> = Broadcaster::new(100).with_name("swap_compose");
+        let state_update_channel: Broadcaster<StateUpdateEvent<DB, LoomDataTypesEthereum>> =
+            Broadcaster::new(100).with_name("state_update");
         Strategy { swap_compose_channel: compose_channel, state_update_channel }
     }
 }
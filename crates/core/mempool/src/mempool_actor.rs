@@ -5,13 +5,16 @@ use influxdb::{Timestamp, WriteQuery};
 use tokio::sync::broadcast::error::RecvError;
 use tracing::{debug, error, info, trace};
 
-use loom_core_actors::{run_sync, subscribe, Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
+use loom_core_actors::{
+    run_sync, subscribe, Accessor, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, Producer, SharedState, WorkerResult,
+};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
 use loom_core_blockchain::Blockchain;
 use loom_types_blockchain::{ChainParameters, Mempool, MempoolTx};
 use loom_types_blockchain::{LoomBlock, LoomDataTypes, LoomDataTypesEthereum, LoomHeader, LoomTx};
 use loom_types_events::{MempoolEvents, MessageBlock, MessageBlockHeader, MessageMempoolDataUpdate};
 
+#[allow(clippy::too_many_arguments)]
 pub async fn new_mempool_worker<LDT: LoomDataTypes>(
     chain_parameters: ChainParameters,
     mempool: SharedState<Mempool<LDT>>,
@@ -20,6 +23,7 @@ pub async fn new_mempool_worker<LDT: LoomDataTypes>(
     block_with_txs_rx: Broadcaster<MessageBlock<LDT>>,
     broadcaster: Broadcaster<MempoolEvents<LDT>>,
     influxdb_write_channel_tx: Broadcaster<WriteQuery>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult {
     subscribe!(mempool_update_rx);
     subscribe!(block_header_rx);
@@ -30,6 +34,9 @@ pub async fn new_mempool_worker<LDT: LoomDataTypes>(
 
     loop {
         tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Mempool cancelled".to_string());
+            }
             msg = mempool_update_rx.recv() => {
                 let mempool_update_msg = match msg {
                     Ok(mempool_update_msg) => mempool_update_msg,
@@ -64,7 +71,7 @@ pub async fn new_mempool_worker<LDT: LoomDataTypes>(
                 }
                 if let Some(tx) = &mempool_update_msg.mempool_tx.tx {
                     if mempool_entry.tx.is_none() {
-                        mempool_entry.tx = Some(tx.clone());
+                        mempool_guard.add_tx(tx.clone());
                         if let Some(cur_gas_price) = current_gas_price {
                             if tx.gas_limit() > 30000 && tx.gas_price() >= cur_gas_price && mempool_guard.is_valid_tx(tx) {
                                 run_sync!(broadcaster.send(MempoolEvents::MempoolActualTxUpdate {tx_hash }));
@@ -200,6 +207,7 @@ pub struct MempoolActor<LDT: LoomDataTypes + 'static = LoomDataTypesEthereum> {
     mempool_events_tx: Option<Broadcaster<MempoolEvents<LDT>>>,
     #[producer]
     influxdb_write_channel_tx: Option<Broadcaster<WriteQuery>>,
+    cancellation_token: CancellationToken,
 }
 
 impl<LDT: LoomDataTypes> Default for MempoolActor<LDT> {
@@ -212,6 +220,7 @@ impl<LDT: LoomDataTypes> Default for MempoolActor<LDT> {
             block_header_rx: None,
             block_with_txs_rx: None,
             influxdb_write_channel_tx: None,
+            cancellation_token: CancellationToken::new(),
         }
     }
 }
@@ -221,6 +230,10 @@ impl<LDT: LoomDataTypes> MempoolActor<LDT> {
         MempoolActor::default()
     }
 
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
     pub fn on_bc(self, bc: &Blockchain<LDT>) -> MempoolActor<LDT> {
         Self {
             chain_parameters: bc.chain_parameters(),
@@ -230,6 +243,7 @@ impl<LDT: LoomDataTypes> MempoolActor<LDT> {
             block_with_txs_rx: Some(bc.new_block_with_tx_channel()),
             mempool_events_tx: Some(bc.mempool_events_channel()),
             influxdb_write_channel_tx: Some(bc.influxdb_write_channel()),
+            ..self
         }
     }
 }
@@ -244,6 +258,7 @@ impl<LDT: LoomDataTypes> Actor for MempoolActor<LDT> {
             self.block_with_txs_rx.clone().unwrap(),
             self.mempool_events_tx.clone().unwrap(),
             self.influxdb_write_channel_tx.clone().unwrap(),
+            self.cancellation_token.clone(),
         ));
         Ok(vec![task])
     }
@@ -1,23 +1,83 @@
+use alloy_primitives::Address;
 use eyre::{eyre, Result};
-use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
+use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, Producer, SharedState, WorkerResult};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
 #[cfg(feature = "with-blockchain")]
 use loom_core_blockchain::{Blockchain, Strategy};
-use loom_types_entities::{AccountNonceAndBalanceState, TxSigners};
+use loom_types_entities::{AccountNonceAndBalanceState, Swap, SwapAmountType, TxSigners};
 use loom_types_events::{MessageSwapCompose, MessageTxCompose, SwapComposeData, SwapComposeMessage, TxComposeData};
+use revm::primitives::Bytecode;
 use revm::DatabaseRef;
+use std::hash::{DefaultHasher, Hash, Hasher};
 use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::broadcast::Receiver;
 use tracing::{debug, error, info};
 use crate::utils::json_log;
 use tracing::Level;
 
+// Gas charged for an EIP-7702 authorization of an account, PER_EMPTY_ACCOUNT_COST
+const EIP7702_AUTHORIZATION_GAS: u64 = 25_000;
+// Every n-th swap below the landing probability cutoff is routed anyway, so the landing rate of its fee bucket keeps updating
+const LANDING_EXPLORATION_INTERVAL: u64 = 20;
+
+/// Swaps with an estimated landing probability under `min_landing_probability` are skipped, except every
+/// `LANDING_EXPLORATION_INTERVAL`-th of them. Swaps without an estimate are routed
+fn skip_by_landing_probability(
+    estimated_landing_probability: Option<f32>,
+    min_landing_probability: Option<f32>,
+    skipped: &mut u64,
+) -> bool {
+    let (Some(estimated), Some(min)) = (estimated_landing_probability, min_landing_probability) else {
+        return false;
+    };
+    if estimated >= min {
+        return false;
+    }
+    *skipped += 1;
+    *skipped % LANDING_EXPLORATION_INTERVAL != 0
+}
+
+/// EIP-7702 swaps run on the balances of the delegated EOA, the EOA must hold the input amount of the swap
+fn eoa_funds_swap(swap: &Swap, account_monitor: &AccountNonceAndBalanceState, eoa: Address) -> bool {
+    let Swap::BackrunSwapLine(swap_line) = swap else {
+        return false;
+    };
+    let (Some(token), SwapAmountType::Set(amount_in)) = (swap_line.get_first_token(), swap_line.amount_in) else {
+        return false;
+    };
+    account_monitor.get_account(&eoa).is_some_and(|account| account.get_balance(&token.get_address()) >= amount_in)
+}
+
+/// `account` is delegated to `delegate` with EIP-7702 already, so a new authorization is not needed
+fn is_delegated_to<DB: DatabaseRef>(db: &DB, account: Address, delegate: Address) -> bool {
+    let Ok(Some(account_info)) = db.basic_ref(account) else {
+        return false;
+    };
+    let code = match account_info.code {
+        Some(code) => code,
+        None => match db.code_by_hash_ref(account_info.code_hash) {
+            Ok(code) => code,
+            Err(_) => return false,
+        },
+    };
+    matches!(code, Bytecode::Eip7702(code) if code.delegated_address == delegate)
+}
+
+/// Composes backrunning the same stuffing transactions in the same block are alternatives, at most one of them lands
+fn nonce_group(tx_compose: &TxComposeData) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    tx_compose.next_block_number.hash(&mut hasher);
+    tx_compose.stuffing_txs_hashes.hash(&mut hasher);
+    hasher.finish()
+}
+
 /// encoder task performs initial routing for swap request
 async fn router_task_prepare<DB: DatabaseRef + Send + Sync + Clone + 'static>(
     route_request: SwapComposeData<DB>,
     compose_channel_tx: Broadcaster<MessageSwapCompose<DB>>,
     signers: SharedState<TxSigners>,
     account_monitor: SharedState<AccountNonceAndBalanceState>,
+    eip7702_delegate: Option<Address>,
 ) -> Result<()> {
     json_log(Level::DEBUG, "router_task_prepare started", &[
         ("swap", &format!("{}", route_request.swap)),
@@ -29,18 +89,49 @@ async fn router_task_prepare<DB: DatabaseRef + Send + Sync + Clone + 'static>(
         None => signers.read().await.get_random_signer().ok_or(eyre!("NO_SIGNER"))?,
     };
 
-    let nonce = account_monitor.read().await.get_account(&signer.address()).unwrap().get_nonce();
-    let eth_balance = account_monitor.read().await.get_account(&signer.address()).unwrap().get_eth_balance();
-
     if route_request.tx_compose.next_block_base_fee == 0 {
         json_log(Level::ERROR, "Block base fee is not set", &[]);
         return Err(eyre!("NO_BLOCK_GAS_FEE"));
     }
 
-    let gas = (route_request.swap.pre_estimate_gas()) * 2;
+    // Reserve under one write lock so concurrent composes share a nonce only when they are alternatives
+    let (nonce, eth_balance, use_eip7702) = {
+        let mut account_monitor_guard = account_monitor.write().await;
+        let nonce = account_monitor_guard.reserve_nonce(signer.address(), nonce_group(&route_request.tx_compose));
+        let eth_balance = account_monitor_guard.get_account(&signer.address()).map(|account| account.get_eth_balance()).unwrap_or_default();
+        // Capital held by the multicaller is not available to the delegated EOA, such swaps call the multicaller
+        let use_eip7702 = route_request.tx_compose.use_eip7702
+            && eip7702_delegate.is_some()
+            && eoa_funds_swap(&route_request.swap, &account_monitor_guard, signer.address());
+        (nonce, eth_balance, use_eip7702)
+    };
+    if route_request.tx_compose.use_eip7702 && !use_eip7702 {
+        debug!(eoa = %signer.address(), swap = %route_request.swap, "No delegate or the EOA does not fund the swap, EIP-7702 disabled");
+    }
+
+    // The authorization is sent once, later swaps call the EOA already delegated to the multicaller
+    let eip7702_authorize = match eip7702_delegate {
+        Some(delegate) if use_eip7702 => {
+            !route_request.poststate.as_ref().is_some_and(|db| is_delegated_to(db, signer.address(), delegate))
+        }
+        _ => false,
+    };
+
+    let mut gas = (route_request.swap.pre_estimate_gas()) * 2;
+    if eip7702_authorize {
+        gas += EIP7702_AUTHORIZATION_GAS;
+    }
 
     let estimate_request = SwapComposeData {
-        tx_compose: TxComposeData { signer: Some(signer), nonce, eth_balance, gas, ..route_request.tx_compose },
+        tx_compose: TxComposeData {
+            signer: Some(signer),
+            nonce,
+            eth_balance,
+            gas,
+            use_eip7702,
+            eip7702_authorize,
+            ..route_request.tx_compose
+        },
         ..route_request
     };
     let estimate_request = MessageSwapCompose::estimate(estimate_request);
@@ -48,6 +139,7 @@ async fn router_task_prepare<DB: DatabaseRef + Send + Sync + Clone + 'static>(
     match compose_channel_tx.send(estimate_request) {
         Err(_) => {
             json_log(Level::ERROR, "compose_channel_tx.send(estimate_request) failed", &[]);
+            account_monitor.write().await.release_nonce(signer.address(), nonce);
             Err(eyre!("ERROR_SENDING_REQUEST"))
         }
         Ok(_) => Ok(()),
@@ -75,19 +167,27 @@ async fn router_task_broadcast<DB: DatabaseRef + Send + Sync + Clone + 'static>(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn swap_router_worker<DB: DatabaseRef + Clone + Send + Sync + 'static>(
+    min_landing_probability: Option<f32>,
+    eip7702_delegate: Option<Address>,
     signers: SharedState<TxSigners>,
     account_monitor: SharedState<AccountNonceAndBalanceState>,
     swap_compose_channel_rx: Broadcaster<MessageSwapCompose<DB>>,
     swap_compose_channel_tx: Broadcaster<MessageSwapCompose<DB>>,
     tx_compose_channel_tx: Broadcaster<MessageTxCompose>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult {
     let mut compose_channel_rx = swap_compose_channel_rx.subscribe();
+    let mut landing_skipped = 0u64;
 
     info!("swap router worker started");
 
     loop {
         tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Swap router cancelled".to_string());
+            }
             msg = compose_channel_rx.recv() => {
                 let msg : Result<MessageSwapCompose<DB>, RecvError> = msg;
                 match msg {
@@ -95,12 +195,18 @@ async fn swap_router_worker<DB: DatabaseRef + Clone + Send + Sync + 'static>(
                         match compose_request.inner {
                             SwapComposeMessage::Prepare(swap_compose_request)=>{
                                 debug!("MessageSwapComposeRequest::Prepare received. stuffing: {:?} swap: {}", swap_compose_request.tx_compose.stuffing_txs_hashes, swap_compose_request.swap);
+                                let estimated_landing_probability = swap_compose_request.estimated_landing_probability;
+                                if skip_by_landing_probability(estimated_landing_probability, min_landing_probability, &mut landing_skipped) {
+                                    debug!(?estimated_landing_probability, swap = %swap_compose_request.swap, "Swap skipped by landing probability");
+                                    continue;
+                                }
                                 tokio::task::spawn(
                                     router_task_prepare(
                                         swap_compose_request,
                                         swap_compose_channel_tx.clone(),
                                         signers.clone(),
                                         account_monitor.clone(),
+                                        eip7702_delegate,
                                     )
                                 );
                             }
@@ -113,6 +219,13 @@ async fn swap_router_worker<DB: DatabaseRef + Clone + Send + Sync + 'static>(
                                     )
                                 );
                             }
+                            SwapComposeMessage::Rejected(swap_compose_request)=>{
+                                if let Some(signer) = &swap_compose_request.tx_compose.signer {
+                                    let nonce = swap_compose_request.tx_compose.nonce;
+                                    let released = account_monitor.write().await.release_nonce(signer.address(), nonce);
+                                    debug!(address=%signer.address(), nonce, released, "MessageSwapComposeRequest::Rejected received");
+                                }
+                            }
                             _=>{}
 
                         }
@@ -126,6 +239,8 @@ async fn swap_router_worker<DB: DatabaseRef + Clone + Send + Sync + 'static>(
 
 #[derive(Consumer, Producer, Accessor, Default)]
 pub struct SwapRouterActor<DB: Send + Sync + Clone + 'static> {
+    min_landing_probability: Option<f32>,
+    eip7702_delegate: Option<Address>,
     #[accessor]
     signers: Option<SharedState<TxSigners>>,
     #[accessor]
@@ -136,6 +251,7 @@ pub struct SwapRouterActor<DB: Send + Sync + Clone + 'static> {
     swap_compose_channel_tx: Option<Broadcaster<MessageSwapCompose<DB>>>,
     #[producer]
     tx_compose_channel_tx: Option<Broadcaster<MessageTxCompose>>,
+    cancellation_token: CancellationToken,
 }
 
 impl<DB> SwapRouterActor<DB>
@@ -144,11 +260,14 @@ where
 {
     pub fn new() -> SwapRouterActor<DB> {
         SwapRouterActor {
+            min_landing_probability: None,
+            eip7702_delegate: None,
             signers: None,
             account_nonce_balance: None,
             swap_compose_channel_rx: None,
             swap_compose_channel_tx: None,
             tx_compose_channel_tx: None,
+            cancellation_token: CancellationToken::new(),
         }
     }
 
@@ -156,6 +275,20 @@ where
         Self { signers: Some(signers), ..self }
     }
 
+    /// Skip swaps with a lower estimated bundle landing probability
+    pub fn with_min_landing_probability(self, min_landing_probability: f32) -> Self {
+        Self { min_landing_probability: Some(min_landing_probability), ..self }
+    }
+
+    /// Route swaps with `use_eip7702` to the EOA delegated to this contract, without it they call the multicaller
+    pub fn with_eip7702_delegate(self, delegate: Address) -> Self {
+        Self { eip7702_delegate: Some(delegate), ..self }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
     #[cfg(feature = "with-blockchain")]
     pub fn on_bc(self, bc: &Blockchain, strategy: &Strategy<DB>) -> Self {
         Self {
@@ -185,11 +318,14 @@ where
             .ok_or_else(|| eyre!("SwapRouterActor: tx_compose_channel_tx not set"))?;
 
         let task = tokio::task::spawn(swap_router_worker(
+            self.min_landing_probability,
+            self.eip7702_delegate,
             signers,
             account_nonce_balance,
             swap_compose_channel_rx,
             swap_compose_channel_tx,
             tx_compose_channel_tx,
+            self.cancellation_token.clone(),
         ));
         Ok(vec![task])
     }
@@ -198,3 +334,97 @@ where
         "SwapRouterActor"
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::TxHash;
+    use loom_types_entities::LoomTxSigner;
+    use revm::db::{CacheDB, EmptyDB};
+    use revm::primitives::AccountInfo;
+    use std::collections::{HashMap, HashSet};
+
+    #[test]
+    fn test_skip_by_landing_probability() {
+        let mut skipped = 0;
+        assert!(!skip_by_landing_probability(None, Some(0.05), &mut skipped));
+        assert!(!skip_by_landing_probability(Some(0.01), None, &mut skipped));
+        assert!(!skip_by_landing_probability(Some(0.05), Some(0.05), &mut skipped));
+        assert_eq!(skipped, 0);
+
+        // A bucket below the cutoff keeps being sampled
+        let routed = (0..LANDING_EXPLORATION_INTERVAL * 3)
+            .filter(|_| !skip_by_landing_probability(Some(0.01), Some(0.05), &mut skipped))
+            .count();
+        assert_eq!(routed, 3);
+    }
+
+    #[test]
+    fn test_is_delegated_to() {
+        let (eoa, delegate) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let mut db = CacheDB::new(EmptyDB::default());
+        assert!(!is_delegated_to(&db, eoa, delegate));
+
+        db.insert_account_info(eoa, AccountInfo { code: Some(Bytecode::new_eip7702(delegate)), ..AccountInfo::default() });
+        assert!(is_delegated_to(&db, eoa, delegate));
+        assert!(!is_delegated_to(&db, eoa, Address::repeat_byte(3)));
+    }
+
+    #[tokio::test]
+    async fn test_prepare_without_eip7702_delegate() {
+        let mut signers = TxSigners::new();
+        signers.add_testkey();
+        let account_monitor = SharedState::new(AccountNonceAndBalanceState::new());
+
+        let compose_channel: Broadcaster<MessageSwapCompose<EmptyDB>> = Broadcaster::new(10);
+        let mut compose_channel_rx = compose_channel.subscribe();
+
+        let tx_compose = TxComposeData { next_block_base_fee: 1, use_eip7702: true, ..TxComposeData::default() };
+        let route_request = SwapComposeData { tx_compose, ..SwapComposeData::default() };
+        router_task_prepare(route_request, compose_channel, SharedState::new(signers), account_monitor, None).await.unwrap();
+
+        // Without a delegate the swap calls the multicaller
+        let msg = compose_channel_rx.recv().await.unwrap();
+        assert!(!msg.tx_compose.use_eip7702);
+        assert!(!msg.tx_compose.eip7702_authorize);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_prepare_reserves_unique_nonces() {
+        let mut signers = TxSigners::new();
+        let signer = signers.add_testkey();
+        let signers = SharedState::new(signers);
+
+        let mut account_state = AccountNonceAndBalanceState::new();
+        account_state.add_account(signer.address()).set_nonce(5);
+        let account_monitor = SharedState::new(account_state);
+
+        let compose_channel: Broadcaster<MessageSwapCompose<EmptyDB>> = Broadcaster::new(100);
+        let mut compose_channel_rx = compose_channel.subscribe();
+
+        // Two alternative composes for each of ten stuffing transactions
+        let tasks: Vec<_> = (0..20)
+            .map(|i| {
+                let tx_compose = TxComposeData {
+                    next_block_base_fee: 1,
+                    stuffing_txs_hashes: vec![TxHash::repeat_byte(i / 2)],
+                    ..TxComposeData::default()
+                };
+                let route_request = SwapComposeData { tx_compose, ..SwapComposeData::default() };
+                let task = router_task_prepare(route_request, compose_channel.clone(), signers.clone(), account_monitor.clone(), None);
+                tokio::task::spawn(task)
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+
+        let mut nonces: HashMap<TxHash, u64> = HashMap::new();
+        for _ in 0..20 {
+            let msg = compose_channel_rx.recv().await.unwrap();
+            let nonce = *nonces.entry(msg.tx_compose.stuffing_txs_hashes[0]).or_insert(msg.tx_compose.nonce);
+            assert_eq!(nonce, msg.tx_compose.nonce);
+        }
+        assert_eq!(nonces.into_values().collect::<HashSet<u64>>(), (5..15).collect::<HashSet<u64>>());
+    }
+}
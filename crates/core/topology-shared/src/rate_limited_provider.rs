@@ -1,7 +1,14 @@
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::task::{Context, Poll};
 use std::time::{Duration, Instant};
 use tokio::sync::{Mutex, Semaphore};
+use tokio::time::{Interval, MissedTickBehavior};
+use alloy_json_rpc::{RequestPacket, ResponsePacket};
 use alloy_provider::{Provider, RootProvider};
+use alloy_transport::{TransportError, TransportFut};
+use serde::Deserialize;
+use tower::{Layer, Service};
 use tracing::{debug, warn, error};
 use std::sync::atomic::{AtomicU64, Ordering};
 
@@ -111,4 +118,144 @@ pub struct ProviderHealthStats {
     pub total_errors: u64,
     pub error_rate: f64,
     pub is_healthy: bool,
-}
\ No newline at end of file
+}
+
+/// Requests per second limit of RPC methods starting with `method_prefix`, e.g. `debug_` or `eth_getStorageAt`
+#[derive(Clone, Debug, Deserialize)]
+pub struct MethodRateLimit {
+    pub method_prefix: String,
+    pub max_rps: u32,
+}
+
+fn rps_interval(max_rps: u32) -> Arc<Mutex<Interval>> {
+    let mut interval = tokio::time::interval(Duration::from_secs_f64(1.0 / max_rps as f64));
+    // Idle time does not allow a burst of requests
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    Arc::new(Mutex::new(interval))
+}
+
+/// Per-method rate limiters. A request waits for the limiter of the longest matching method prefix,
+/// or for the global limiter when no method limit matches. Must be created inside a tokio runtime.
+pub struct MethodRateLimiter {
+    global: Option<Arc<Mutex<Interval>>>,
+    methods: HashMap<String, Arc<Mutex<Interval>>>,
+}
+
+impl MethodRateLimiter {
+    /// rate_limit_rps: global requests per second limit. If 0, requests without a method limit are not limited.
+    pub fn new(rate_limit_rps: u32, method_limits: &[MethodRateLimit]) -> Self {
+        let global = (rate_limit_rps > 0).then(|| rps_interval(rate_limit_rps));
+        let methods = method_limits
+            .iter()
+            .filter(|limit| limit.max_rps > 0)
+            .map(|limit| (limit.method_prefix.clone(), rps_interval(limit.max_rps)))
+            .collect();
+        Self { global, methods }
+    }
+
+    fn limiter(&self, method: &str) -> Option<&Arc<Mutex<Interval>>> {
+        self.methods
+            .iter()
+            .filter(|(prefix, _)| method.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, interval)| interval)
+            .or(self.global.as_ref())
+    }
+
+    pub async fn wait(&self, method: &str) {
+        if let Some(interval) = self.limiter(method) {
+            interval.lock().await.tick().await;
+        }
+    }
+}
+
+/// Transport layer applying `MethodRateLimiter` before forwarding each RPC call,
+/// used with `ClientBuilder::default().layer(..)`
+#[derive(Clone)]
+pub struct RateLimitLayer {
+    limiter: Arc<MethodRateLimiter>,
+}
+
+impl RateLimitLayer {
+    pub fn new(rate_limit_rps: u32, method_limits: &[MethodRateLimit]) -> Self {
+        Self { limiter: Arc::new(MethodRateLimiter::new(rate_limit_rps, method_limits)) }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService { inner, limiter: self.limiter.clone() }
+    }
+}
+
+#[derive(Clone)]
+pub struct RateLimitService<S> {
+    inner: S,
+    limiter: Arc<MethodRateLimiter>,
+}
+
+impl<S> Service<RequestPacket> for RateLimitService<S>
+where
+    S: Service<RequestPacket, Response = ResponsePacket, Error = TransportError, Future = TransportFut<'static>>
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+{
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: RequestPacket) -> Self::Future {
+        let limiter = self.limiter.clone();
+        let mut inner = self.inner.clone();
+        Box::pin(async move {
+            for method in req.method_names() {
+                limiter.wait(method).await;
+            }
+            inner.call(req).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn method_limit(method_prefix: &str, max_rps: u32) -> MethodRateLimit {
+        MethodRateLimit { method_prefix: method_prefix.to_string(), max_rps }
+    }
+
+    #[tokio::test]
+    async fn test_method_rate_limiter() {
+        let limiter = MethodRateLimiter::new(100, &[method_limit("debug_", 10), method_limit("debug_traceTransaction", 5)]);
+
+        let trace_limiter = limiter.limiter("debug_traceTransaction").unwrap();
+        assert!(Arc::ptr_eq(trace_limiter, &limiter.methods["debug_traceTransaction"]));
+        let debug_limiter = limiter.limiter("debug_traceCall").unwrap();
+        assert!(Arc::ptr_eq(debug_limiter, &limiter.methods["debug_"]));
+        let global_limiter = limiter.limiter("eth_getStorageAt").unwrap();
+        assert!(Arc::ptr_eq(global_limiter, limiter.global.as_ref().unwrap()));
+
+        let limiter = MethodRateLimiter::new(0, &[method_limit("debug_", 10)]);
+        assert!(limiter.limiter("eth_call").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_method_rate_limiter_wait() {
+        let limiter = MethodRateLimiter::new(0, &[method_limit("debug_", 20)]);
+        let start = std::time::Instant::now();
+        for _ in 0..3 {
+            limiter.wait("debug_traceCall").await;
+            limiter.wait("eth_call").await;
+        }
+        // First tick is immediate, then one tick every 50 ms
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+}
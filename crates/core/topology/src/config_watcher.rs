@@ -0,0 +1,186 @@
+use std::any::Any;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use eyre::{eyre, Result};
+use serde::de::DeserializeOwned;
+use tokio::fs;
+use tracing::{debug, error, info, warn};
+
+use loom_core_actors::{Actor, ActorResult, Broadcaster, CancellationToken, Producer, WorkerResult};
+use loom_core_actors_macros::Producer;
+use loom_types_events::LoomTask;
+
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+type ConfigPayload = Arc<dyn Any + Send + Sync>;
+
+type SectionParser = Arc<dyn Fn(toml::Value) -> Result<ConfigPayload> + Send + Sync>;
+
+/// Parses the watched sections present in `contents`. Sections missing from the file are skipped.
+fn parse_config_sections(contents: &str, sections: &[(String, SectionParser)]) -> Result<Vec<(String, ConfigPayload)>> {
+    let table: toml::Table = contents.parse()?;
+    let mut ret = Vec::new();
+    for (section, parser) in sections {
+        let Some(value) = table.get(section) else {
+            debug!(section, "Config section not found");
+            continue;
+        };
+        let config = parser(value.clone()).map_err(|e| eyre!("CONFIG_SECTION_PARSE_ERROR {section} : {e}"))?;
+        ret.push((section.clone(), config));
+    }
+    Ok(ret)
+}
+
+async fn modified_time(file_path: &Path) -> Result<SystemTime> {
+    Ok(fs::metadata(file_path).await?.modified()?)
+}
+
+pub async fn config_watcher_worker(
+    file_path: PathBuf,
+    poll_interval: Duration,
+    sections: Vec<(String, SectionParser)>,
+    tasks_tx: Broadcaster<LoomTask>,
+    cancellation_token: CancellationToken,
+) -> WorkerResult {
+    let mut last_modified = modified_time(&file_path).await.ok();
+    let mut interval = tokio::time::interval(poll_interval);
+
+    info!(file = %file_path.display(), "Config watcher started");
+
+    loop {
+        tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Config watcher cancelled".to_string());
+            }
+            _ = interval.tick() => {}
+        }
+
+        let modified = match modified_time(&file_path).await {
+            Ok(modified) => modified,
+            Err(error) => {
+                warn!(file = %file_path.display(), %error, "Config file metadata not available");
+                continue;
+            }
+        };
+        if last_modified == Some(modified) {
+            continue;
+        }
+        last_modified = Some(modified);
+
+        // A config with errors is not applied, the previous config stays active until the file is fixed
+        let contents = match fs::read_to_string(&file_path).await {
+            Ok(contents) => contents,
+            Err(error) => {
+                error!(file = %file_path.display(), %error, "Config file read failed");
+                continue;
+            }
+        };
+        let configs = match parse_config_sections(&contents, &sections) {
+            Ok(configs) => configs,
+            Err(error) => {
+                error!(file = %file_path.display(), %error, "Config reload failed");
+                continue;
+            }
+        };
+
+        for (section, config) in configs {
+            info!(section, "Config section reloaded");
+            if let Err(error) = tasks_tx.send(LoomTask::ConfigReloaded(config)) {
+                error!(%error, "tasks_tx.send");
+            }
+        }
+    }
+}
+
+/// Polls the config file for changes and broadcasts watched sections as `LoomTask::ConfigReloaded` on the tasks channel.
+/// Receivers downcast the payload to their config type.
+#[derive(Producer)]
+pub struct ConfigWatcherActor {
+    file_path: PathBuf,
+    poll_interval: Duration,
+    sections: Vec<(String, SectionParser)>,
+    #[producer]
+    tasks_tx: Option<Broadcaster<LoomTask>>,
+    cancellation_token: CancellationToken,
+}
+
+impl ConfigWatcherActor {
+    pub fn new(file_path: PathBuf) -> Self {
+        Self {
+            file_path,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            sections: Vec::new(),
+            tasks_tx: None,
+            cancellation_token: CancellationToken::new(),
+        }
+    }
+
+    pub fn with_poll_interval(self, poll_interval: Duration) -> Self {
+        Self { poll_interval, ..self }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
+    /// Watch `section` of the config file, reloaded sections are broadcast as `C`
+    pub fn with_section<C: DeserializeOwned + Send + Sync + 'static>(mut self, section: &str) -> Self {
+        let parser: SectionParser = Arc::new(|value: toml::Value| Ok(Arc::new(value.try_into::<C>()?) as ConfigPayload));
+        self.sections.push((section.to_string(), parser));
+        self
+    }
+}
+
+impl Actor for ConfigWatcherActor {
+    fn start(&self) -> ActorResult {
+        let task = tokio::task::spawn(config_watcher_worker(
+            self.file_path.clone(),
+            self.poll_interval,
+            self.sections.clone(),
+            self.tasks_tx.clone().unwrap(),
+            self.cancellation_token.clone(),
+        ));
+        Ok(vec![task])
+    }
+
+    fn name(&self) -> &'static str {
+        "ConfigWatcherActor"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use loom_strategy_backrun::BackrunConfig;
+    use loom_strategy_simple_arb::SimpleArbConfig;
+
+    #[test]
+    fn test_parse_config_sections() {
+        let actor = ConfigWatcherActor::new(PathBuf::from("config.toml"))
+            .with_section::<BackrunConfig>("backrun_strategy")
+            .with_section::<SimpleArbConfig>("simple_arb");
+
+        let contents = r#"
+            [simple_arb]
+            max_path_length = 4
+            min_liquidity_usd = 50000
+        "#;
+        let configs = parse_config_sections(contents, &actor.sections).unwrap();
+        assert_eq!(configs.len(), 1);
+        let (section, config) = &configs[0];
+        assert_eq!(section, "simple_arb");
+        let config: LoomTask = LoomTask::ConfigReloaded(config.clone());
+        assert!(config.reloaded_config::<BackrunConfig>().is_none());
+        let simple_arb_config = config.reloaded_config::<SimpleArbConfig>().unwrap();
+        assert_eq!(simple_arb_config.max_path_length, 4);
+        assert_eq!(simple_arb_config.min_liquidity_usd, 50000);
+
+        let contents = r#"
+            [backrun_strategy]
+            smart = "yes"
+        "#;
+        assert!(parse_config_sections(contents, &actor.sections).is_err());
+    }
+}
@@ -1,24 +1,80 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
 use std::time::Duration;
+
+use crate::DnsConfig;
+use eyre::Result;
+use hickory_resolver::config::{NameServerConfigGroup, ResolverConfig, ResolverOpts};
+use hickory_resolver::system_conf::read_system_conf;
+use hickory_resolver::TokioAsyncResolver;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
 use tracing::{info, warn};
 
-/// Configures DNS resolution settings for the application
-pub fn configure_dns_settings() {
-    // Set DNS timeout environment variables if not already set
-    if std::env::var("RESOLVE_TIMEOUT_MS").is_err() {
-        std::env::set_var("RESOLVE_TIMEOUT_MS", "10000");
-        info!("Set DNS resolution timeout to 10 seconds");
+/// Builds the resolver of the node and relay clients from the `[dns]` section
+pub fn configure_dns_settings(config: &DnsConfig) -> Result<DnsResolver> {
+    DnsResolver::new(config)
+}
+
+/// Resolves the hosts of the node and relay clients with the nameserver, timeout and cache TTL of the `[dns]` section
+#[derive(Clone)]
+pub struct DnsResolver {
+    resolver: Arc<TokioAsyncResolver>,
+}
+
+impl DnsResolver {
+    pub fn new(config: &DnsConfig) -> Result<Self> {
+        let (resolver_config, mut opts) = match &config.server {
+            Some(server) => {
+                let server: SocketAddr = server.parse()?;
+                let name_servers = NameServerConfigGroup::from_ips_clear(&[server.ip()], server.port(), true);
+                info!(%server, "DNS server set");
+                (ResolverConfig::from_parts(None, vec![], name_servers), ResolverOpts::default())
+            }
+            None => read_system_conf().unwrap_or_else(|error| {
+                warn!(%error, "System DNS config not read, using the default resolver config");
+                (ResolverConfig::default(), ResolverOpts::default())
+            }),
+        };
+        opts.timeout = Duration::from_millis(config.timeout_ms);
+        opts.positive_max_ttl = Some(Duration::from_secs(config.cache_ttl_secs));
+        opts.negative_max_ttl = Some(Duration::from_secs(config.cache_ttl_secs));
+        info!(timeout_ms = config.timeout_ms, cache_ttl_secs = config.cache_ttl_secs, "DNS resolver configured");
+
+        Ok(Self { resolver: Arc::new(TokioAsyncResolver::tokio(resolver_config, opts)) })
     }
-    
-    // Set DNS cache TTL if not already set
-    if std::env::var("RESOLVE_CACHE_TTL_SECONDS").is_err() {
-        std::env::set_var("RESOLVE_CACHE_TTL_SECONDS", "300");
-        info!("Set DNS cache TTL to 300 seconds");
+
+    /// Resolver shared by the HTTP and WebSocket clients
+    pub fn shared(&self) -> Arc<dyn Resolve> {
+        Arc::new(self.clone())
+    }
+
+    /// HTTP client resolving hosts with this resolver
+    pub fn http_client(&self) -> Result<reqwest::Client> {
+        Ok(reqwest::Client::builder().dns_resolver(self.shared()).build()?)
+    }
+}
+
+impl Resolve for DnsResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let resolver = self.resolver.clone();
+        Box::pin(async move {
+            let lookup = resolver.lookup_ip(name.as_str()).await?;
+            let addrs: Addrs = Box::new(lookup.into_iter().map(|ip| SocketAddr::new(ip, 0)).collect::<Vec<_>>().into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_dns_resolver_config() {
+        let config = DnsConfig { server: Some("8.8.8.8:53".to_string()), timeout_ms: 2000, cache_ttl_secs: 60 };
+        assert!(configure_dns_settings(&config).is_ok());
+
+        let config = DnsConfig { server: Some("dns.google".to_string()), ..DnsConfig::default() };
+        assert!(configure_dns_settings(&config).is_err());
     }
-    
-    // Configure DNS settings for reqwest
-    std::env::set_var("REQWEST_CONNECT_TIMEOUT", "30000");
-    std::env::set_var("REQWEST_TIMEOUT", "60000");
-    
-    // Log DNS configuration
-    warn!("DNS resolution settings configured");
-}
\ No newline at end of file
+}
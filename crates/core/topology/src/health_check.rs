@@ -0,0 +1,30 @@
+/// Actor that failed to start. The bot cannot run without a `critical` actor
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FailedActor {
+    pub name: String,
+    pub critical: bool,
+}
+
+impl FailedActor {
+    pub fn new(name: String) -> Self {
+        Self { name, critical: false }
+    }
+
+    pub fn critical(name: String) -> Self {
+        Self { name, critical: true }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failed_actor() {
+        let failed_actors = vec![FailedActor::new("ArbSwapPathMergerActor".to_string()), FailedActor::critical("MempoolActor".to_string())];
+        assert!(!failed_actors[0].critical);
+        assert!(failed_actors[1].critical);
+        assert!(failed_actors.iter().any(|actor| actor.critical));
+        assert!(!failed_actors[..1].iter().any(|actor| actor.critical));
+    }
+}
@@ -1,8 +1,17 @@
+#[cfg(feature = "stress-test")]
+pub use stress_test::StressTestReport;
+pub use config_watcher::ConfigWatcherActor;
+pub use health_check::FailedActor;
+pub use loom_core_actors::HealthReport;
 pub use topology::Topology;
 pub use topology_config::*;
 pub use loom_core_topology_shared::RateLimitedProvider;
 
+mod config_watcher;
+mod health_check;
 mod topology;
 mod topology_config;
 mod dns_config;
-pub use dns_config::configure_dns_settings;
+#[cfg(feature = "stress-test")]
+mod stress_test;
+pub use dns_config::{configure_dns_settings, DnsResolver};
@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use alloy_primitives::{B256, U256};
+use loom_core_actors::Broadcaster;
+use loom_core_blockchain::Blockchain;
+use loom_types_blockchain::MempoolTx;
+use loom_types_events::{HealthEvent, MarketEvents, MempoolEvents, Message, MessageHealthEvent, MessageMempoolDataUpdate, NodeMempoolDataUpdate};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+
+/// Stress rate multiplier over the expected message rate
+const RATE_MULTIPLIER: u64 = 10;
+/// Channel is reported as bottleneck when receiver queue exceeds this share of capacity, percent
+const BOTTLENECK_CAPACITY_PCT: usize = 80;
+/// Producer sends messages in batches every tick
+const PRODUCER_TICK: Duration = Duration::from_millis(10);
+
+#[derive(Clone, Debug, Default)]
+pub struct StressTestReport {
+    pub bottleneck_channels: Vec<String>,
+    pub max_latency_ms: HashMap<String, u64>,
+}
+
+struct ChannelStressResult {
+    name: String,
+    max_latency_ms: u64,
+    avg_latency_ms: u64,
+    max_queue_len: usize,
+    lagged: u64,
+    capacity: usize,
+}
+
+impl ChannelStressResult {
+    fn is_bottleneck(&self) -> bool {
+        self.lagged > 0 || self.max_queue_len * 100 > self.capacity * BOTTLENECK_CAPACITY_PCT
+    }
+}
+
+fn seq_hash(seq: u64) -> B256 {
+    B256::from(U256::from(seq))
+}
+
+fn hash_seq(hash: &B256) -> u64 {
+    U256::from_be_bytes(hash.0).to::<u64>()
+}
+
+/// Send mock messages at `rate_per_sec` for `duration` and measure receiver latency and queue usage.
+/// Message sequence number is carried inside the message and extracted on receive.
+async fn stress_channel<T, M, X>(
+    name: String,
+    channel: Broadcaster<T>,
+    rate_per_sec: u64,
+    duration: Duration,
+    make_message: M,
+    extract_seq: X,
+) -> ChannelStressResult
+where
+    T: Clone + Send + Sync + 'static,
+    M: Fn(u64) -> T + Send + 'static,
+    X: Fn(&T) -> Option<u64> + Send + 'static,
+{
+    let sent_at: Arc<Mutex<HashMap<u64, Instant>>> = Arc::new(Mutex::new(HashMap::new()));
+    let capacity = channel.capacity();
+    let mut receiver = channel.subscribe();
+
+    let producer_sent_at = sent_at.clone();
+    let producer = tokio::task::spawn(async move {
+        let batch = (rate_per_sec * PRODUCER_TICK.as_millis() as u64 / 1000).max(1);
+        let start = Instant::now();
+        let mut interval = tokio::time::interval(PRODUCER_TICK);
+        let mut seq = 0u64;
+        while start.elapsed() < duration {
+            interval.tick().await;
+            for _ in 0..batch {
+                producer_sent_at.lock().unwrap().insert(seq, Instant::now());
+                if channel.send(make_message(seq)).is_err() {
+                    warn!("Stress test send failed");
+                }
+                seq += 1;
+            }
+        }
+        seq
+    });
+
+    let mut max_latency = Duration::ZERO;
+    let mut total_latency = Duration::ZERO;
+    let mut received = 0u64;
+    let mut max_queue_len = 0usize;
+    let mut lagged = 0u64;
+
+    let deadline = tokio::time::Instant::now() + duration + Duration::from_secs(1);
+    loop {
+        let msg = match tokio::time::timeout_at(deadline, receiver.recv()).await {
+            Ok(msg) => msg,
+            Err(_) => break,
+        };
+        max_queue_len = max_queue_len.max(receiver.len());
+        match msg {
+            Ok(msg) => {
+                let Some(seq) = extract_seq(&msg) else { continue };
+                if let Some(sent) = sent_at.lock().unwrap().remove(&seq) {
+                    let latency = sent.elapsed();
+                    max_latency = max_latency.max(latency);
+                    total_latency += latency;
+                    received += 1;
+                }
+            }
+            Err(RecvError::Lagged(lag)) => lagged += lag,
+            Err(RecvError::Closed) => break,
+        }
+    }
+
+    let sent = producer.await.unwrap_or_default();
+    let avg_latency_ms = if received > 0 { (total_latency / received as u32).as_millis() as u64 } else { 0 };
+
+    info!(
+        channel = name,
+        sent,
+        received,
+        lagged,
+        max_queue_len,
+        capacity,
+        max_latency_ms = max_latency.as_millis() as u64,
+        avg_latency_ms,
+        "Channel stress test finished"
+    );
+
+    ChannelStressResult { name, max_latency_ms: max_latency.as_millis() as u64, avg_latency_ms, max_queue_len, lagged, capacity }
+}
+
+/// Stress channels of a blockchain. Must be run before actors are started, mock messages are delivered to every subscriber.
+pub(crate) async fn stress_test_blockchain_channels(bc_name: &str, bc: &Blockchain, duration: Duration) -> StressTestReport {
+    // Expected message rates per second on mainnet
+    const MEMPOOL_TX_RATE: u64 = 200;
+    const MEMPOOL_EVENTS_RATE: u64 = 200;
+    const MARKET_EVENTS_RATE: u64 = 10;
+    const HEALTH_EVENTS_RATE: u64 = 50;
+
+    let results = tokio::join!(
+        stress_channel(
+            format!("{bc_name}.new_mempool_tx"),
+            bc.new_mempool_tx_channel(),
+            MEMPOOL_TX_RATE * RATE_MULTIPLIER,
+            duration,
+            |seq| -> MessageMempoolDataUpdate {
+                let tx_hash = seq_hash(seq);
+                Message::new(NodeMempoolDataUpdate { tx_hash, mempool_tx: MempoolTx { tx_hash, ..MempoolTx::default() } })
+            },
+            |msg| Some(hash_seq(&msg.inner.tx_hash)),
+        ),
+        stress_channel(
+            format!("{bc_name}.mempool_events"),
+            bc.mempool_events_channel(),
+            MEMPOOL_EVENTS_RATE * RATE_MULTIPLIER,
+            duration,
+            |seq| MempoolEvents::MempoolTxUpdate { tx_hash: seq_hash(seq) },
+            |msg| match msg {
+                MempoolEvents::MempoolTxUpdate { tx_hash } => Some(hash_seq(tx_hash)),
+                _ => None,
+            },
+        ),
+        stress_channel(
+            format!("{bc_name}.market_events"),
+            bc.market_events_channel(),
+            MARKET_EVENTS_RATE * RATE_MULTIPLIER,
+            duration,
+            |seq| MarketEvents::BlockStateUpdate { block_hash: seq_hash(seq) },
+            |msg| match msg {
+                MarketEvents::BlockStateUpdate { block_hash } => Some(hash_seq(block_hash)),
+                _ => None,
+            },
+        ),
+        stress_channel(
+            format!("{bc_name}.health_monitor"),
+            bc.health_monitor_channel(),
+            HEALTH_EVENTS_RATE * RATE_MULTIPLIER,
+            duration,
+            |seq| -> MessageHealthEvent { Message::new(HealthEvent::MonitorTx(seq_hash(seq))) },
+            |msg| match &msg.inner {
+                HealthEvent::MonitorTx(tx_hash) => Some(hash_seq(tx_hash)),
+                _ => None,
+            },
+        ),
+    );
+
+    let mut report = StressTestReport::default();
+    for result in [results.0, results.1, results.2, results.3] {
+        if result.is_bottleneck() {
+            warn!(
+                channel = result.name,
+                max_queue_len = result.max_queue_len,
+                capacity = result.capacity,
+                lagged = result.lagged,
+                avg_latency_ms = result.avg_latency_ms,
+                "Channel capacity bottleneck"
+            );
+            report.bottleneck_channels.push(result.name.clone());
+        }
+        report.max_latency_ms.insert(result.name, result.max_latency_ms);
+    }
+    report
+}
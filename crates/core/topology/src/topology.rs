@@ -1,40 +1,50 @@
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 
-use loom_core_topology_shared::RateLimitedProvider;
+use loom_core_topology_shared::{RateLimitLayer, RateLimitedProvider};
+use crate::{configure_dns_settings, DnsResolver};
+use crate::health_check::FailedActor;
 use crate::topology_config::TransportType;
-use crate::topology_config::{BroadcasterConfig, ClientConfig, EncoderConfig, EstimatorConfig, SignersConfig, TopologyConfig};
-use alloy_primitives::Address;
+use crate::topology_config::{
+    BroadcasterConfig, ClientConfig, EncoderConfig, EstimatorConfig, SignersConfig, StrategyEntryConfig, TopologyConfig,
+};
+use alloy_primitives::{Address, B256};
 use alloy_provider::network::Ethereum;
 use alloy_provider::{Network, Provider, ProviderBuilder, RootProvider};
 use alloy_rpc_client::ClientBuilder;
-use alloy_transport_ipc::IpcConnect;
-use alloy_transport_ws::WsConnect;
+use alloy_transport_http::Http;
 use eyre::{eyre, ErrReport, Result};
 use url::Url;
 use loom_broadcast_accounts::{InitializeSignersOneShotBlockingActor, NonceAndBalanceMonitorActor, TxSignersActor};
-use loom_broadcast_broadcaster::FlashbotsBroadcastActor;
+use loom_broadcast_broadcaster::{FlashbotsBroadcastActor, PrivateMempoolBroadcastActor};
 use loom_broadcast_flashbots::Flashbots;
-use loom_core_actors::{Accessor, Actor, Consumer, Producer, SharedState, WorkerResult};
+use loom_core_actors::{
+    Accessor, Actor, ActorsManager, CancellationToken, Consumer, HealthReport, Producer, RestartActorFn, SharedState, WorkerResult,
+};
 #[cfg(feature = "loom-core-block-history-actor")]
 use loom_core_block_history_actor::BlockHistoryActor;
 use loom_core_blockchain::{Blockchain, BlockchainState, Strategy};
 #[cfg(not(feature = "with-blockchain"))]
 compile_error!("The feature \"with-blockchain\" must be enabled to use loom-core-topology crate because it depends on loom-core-blockchain optionally.");
 use loom_core_mempool::MempoolActor;
+use loom_core_router::SwapRouterActor;
 use loom_defi_health_monitor::PoolHealthMonitorActor;
-use loom_defi_market::{HistoryPoolLoaderOneShotActor, NewPoolLoaderActor, PoolLoaderActor, ProtocolPoolLoaderOneShotActor};
+use loom_defi_market::{EventFilter, HistoryPoolLoaderOneShotActor, NewPoolLoaderActor, PoolLoaderActor, ProtocolPoolLoaderOneShotActor};
 use loom_defi_pools::PoolLoadersBuilder;
 use loom_defi_preloader::MarketStatePreloadedOneShotActor;
 use loom_defi_price::PriceActor;
 use loom_evm_db::DatabaseLoomExt;
-use loom_execution_estimator::{EvmEstimatorActor, GethEstimatorActor};
+use loom_execution_estimator::{EstimatorSupervisorActor, EvmEstimatorActor, GethEstimatorActor};
 use loom_execution_multicaller::MulticallerSwapEncoder;
-use loom_node_actor_config::NodeBlockActorConfig;
+use loom_node_actor_config::{MempoolActorConfig, NodeBlockActorConfig};
 #[cfg(feature = "db-access")]
 use loom_node_db_access::RethDbAccessBlockActor;
 use loom_node_grpc::NodeExExGrpcActor;
-use loom_node_json_rpc::{NodeBlockActor, NodeMempoolActor};
+use loom_node_json_rpc::{websocket_config, NodeBlockActor, NodeMempoolActor, ResolvedWsConnect, RobustSubscriptionManager};
+use loom_strategy_backrun::StateChangeArbActor;
+use loom_strategy_sandwich::SandwichDetectorActor;
+use loom_strategy_simple_arb::SimpleArbFinderActor;
 use loom_types_blockchain::LoomDataTypes;
 use loom_types_entities::pool_config::PoolsLoadingConfig;
 use loom_types_entities::{BlockHistoryState, MarketState, PoolLoaders, SwapEncoder, TxSigners};
@@ -43,6 +53,9 @@ use revm::{Database, DatabaseCommit, DatabaseRef};
 use tokio::task::JoinHandle;
 use tracing::{error, info, warn};
 
+// Connection attempts to an IPC socket before the client is skipped, about 30 seconds with backoff
+const IPC_MAX_CONNECT_ATTEMPTS: usize = 3;
+
 pub struct Topology<
     DB: DatabaseRef + Database + DatabaseCommit + BlockHistoryState + DatabaseLoomExt + Clone + Send + Sync + Default + 'static,
     E: Send + Sync + Clone + 'static = MulticallerSwapEncoder,
@@ -52,6 +65,7 @@ pub struct Topology<
     config: TopologyConfig,
     clients: HashMap<String, RootProvider<N>>,
     blockchains: HashMap<String, Blockchain<LoomDataTypesEthereum>>,
+    chain_id_to_name: HashMap<u64, String>,
     blockchain_states: HashMap<String, BlockchainState<DB>>,
     strategies: HashMap<String, Strategy<DB, LoomDataTypesEthereum>>,
     signers: HashMap<String, SharedState<TxSigners>>,
@@ -62,6 +76,14 @@ pub struct Topology<
     default_signer_name: Option<String>,
     swap_encoder: E,
     pool_loaders: Arc<PoolLoaders<P, N, LoomDataTypesEthereum>>,
+    // Started actors checked by `health_check`, restartable ones are restarted by it
+    actors_manager: Mutex<ActorsManager>,
+    // HTTP client of the node and relay connections, resolves hosts with the `[dns]` settings
+    http_client: reqwest::Client,
+    // Resolver of the `[dns]` settings, set when the clients are started
+    dns_resolver: Option<DnsResolver>,
+    // Cancelled on shutdown, workers of the started actors stop on it
+    cancellation_token: CancellationToken,
 }
 
 impl<DB, E, P, N> Topology<DB, E, P, N>
@@ -110,10 +132,71 @@ impl<
             .ok_or_else(|| eyre!("Blockchain not found: {}", name))
     }
 
+    /// Events of the enabled pool classes and pool creation events of the extra factories watched on the chain, requested from
+    /// the node in log prefilter mode
+    fn log_prefilter_signatures(&self, chain_id: u64) -> Result<Vec<B256>> {
+        let mut signatures = self.pool_loaders.event_signatures();
+        for params in self.config.actors.pools.iter().flat_map(|pool_actors| pool_actors.values()) {
+            if !params.new || self.get_blockchain(params.blockchain.as_ref())?.chain_id() != chain_id {
+                continue;
+            }
+            for factory in params.extra_factories.iter() {
+                let signature = EventFilter::new(&factory.event, 0, factory.class).signature;
+                if !signatures.contains(&signature) {
+                    signatures.push(signature);
+                }
+            }
+        }
+        Ok(signatures)
+    }
+
+    fn get_blockchain_name_for_chain_id(&self, chain_id: u64) -> Result<&String> {
+        self.chain_id_to_name.get(&chain_id)
+            .ok_or_else(|| eyre!("Blockchain not found for chain id: {}", chain_id))
+    }
+
+    pub fn get_blockchain_for_chain_id(&self, chain_id: u64) -> Result<&Blockchain<LoomDataTypesEthereum>> {
+        self.get_blockchain(Some(self.get_blockchain_name_for_chain_id(chain_id)?))
+    }
+
+    pub fn get_blockchain_state_for_chain_id(&self, chain_id: u64) -> Result<&BlockchainState<DB>> {
+        self.get_blockchain_state(Some(self.get_blockchain_name_for_chain_id(chain_id)?))
+    }
+
+    pub fn get_strategy_for_chain_id(&self, chain_id: u64) -> Result<&Strategy<DB, LoomDataTypesEthereum>> {
+        self.get_strategy(Some(self.get_blockchain_name_for_chain_id(chain_id)?))
+    }
+
+    /// Send mock messages at 10x the expected rate on blockchain channels and report channels close to capacity.
+    /// Intended for pre-deployment validation, run it before `start_actors`.
+    #[cfg(feature = "stress-test")]
+    pub async fn stress_test_channels(&self, duration: std::time::Duration) -> crate::StressTestReport {
+        let mut report = crate::StressTestReport::default();
+        for (name, bc) in self.blockchains.iter() {
+            let bc_report = crate::stress_test::stress_test_blockchain_channels(name, bc, duration).await;
+            report.bottleneck_channels.extend(bc_report.bottleneck_channels);
+            report.max_latency_ms.extend(bc_report.max_latency_ms);
+        }
+        report
+    }
+
     pub fn initialize_blockchains(&mut self, chain_id_map: &std::collections::HashMap<String, i64>) -> Result<()> {
         use loom_core_blockchain::Blockchain;
         for (name, chain_id) in chain_id_map.iter() {
             let blockchain = Blockchain::new((*chain_id).try_into().unwrap()); // Convert i64 to u64
+            let blockchain = match self.config.blockchains.get(name) {
+                Some(blockchain_config) if blockchain_config.base_defaults => blockchain.with_base_defaults(),
+                _ => blockchain,
+            };
+            let blockchain = match self.config.blockchains.get(name).and_then(|blockchain_config| blockchain_config.weth_balance_of_slot) {
+                Some(weth_balance_of_slot) => blockchain.with_weth_balance_of_slot(weth_balance_of_slot),
+                None => blockchain,
+            };
+            if let Some(other_name) = self.chain_id_to_name.get(&blockchain.chain_id()) {
+                warn!("Blockchain {name} shares chain id {chain_id} with {other_name}, lookup by chain id returns {other_name}");
+            } else {
+                self.chain_id_to_name.insert(blockchain.chain_id(), name.clone());
+            }
             self.blockchains.insert(name.clone(), blockchain);
             
             // Initialize corresponding blockchain state
@@ -124,6 +207,12 @@ impl<
             let strategy = Strategy::<DB, LoomDataTypesEthereum>::new();
             self.strategies.insert(name.clone(), strategy);
         }
+
+        // Every [[strategies]] entry gets its own swap compose channel
+        for strategy_config in self.config.strategies.iter() {
+            let strategy = Strategy::<DB, LoomDataTypesEthereum>::new();
+            self.strategies.insert(strategy_config.name().clone(), strategy);
+        }
         
         // Set default blockchain name if not already set
         if self.default_blockchain_name.is_none() && !chain_id_map.is_empty() {
@@ -227,14 +316,23 @@ impl<
         P: Provider<Ethereum> + Send + Sync + Clone + 'static,
     > Topology<DB, E, P, Ethereum>
 {
-    pub fn from_config(config: TopologyConfig) -> Topology<DB, MulticallerSwapEncoder> {
+    pub fn from_config(config: TopologyConfig) -> Result<Topology<DB, MulticallerSwapEncoder>> {
+        if let Err(errors) = config.validate() {
+            for error in errors.iter() {
+                error!("Topology config: {}", error);
+            }
+            return Err(eyre!("INVALID_TOPOLOGY_CONFIG: {}", errors.join("; ")));
+        }
+
         let encoder = MulticallerSwapEncoder::default();
         let pool_loaders = Arc::new(PoolLoadersBuilder::<RootProvider>::new().build());
+        let cancellation_token = CancellationToken::new();
 
-        Topology::<DB, MulticallerSwapEncoder> {
+        Ok(Topology::<DB, MulticallerSwapEncoder> {
             config,
             clients: HashMap::new(),
             blockchains: HashMap::new(),
+            chain_id_to_name: HashMap::new(),
             blockchain_states: HashMap::new(),
             strategies: HashMap::new(),
             signers: HashMap::new(),
@@ -245,7 +343,11 @@ impl<
             default_signer_name: None,
             swap_encoder: encoder,
             pool_loaders,
-        }
+            actors_manager: Mutex::new(ActorsManager::new().with_cancellation_token(cancellation_token.clone())),
+            http_client: reqwest::Client::new(),
+            dns_resolver: None,
+            cancellation_token,
+        })
     }
 
     pub fn with_swap_encoder<NE: SwapEncoder + Send + Sync + Clone + 'static>(
@@ -256,6 +358,7 @@ impl<
             config: self.config,
             clients: self.clients,
             blockchains: self.blockchains,
+            chain_id_to_name: self.chain_id_to_name,
             blockchain_states: self.blockchain_states,
             strategies: self.strategies,
             signers: self.signers,
@@ -266,6 +369,10 @@ impl<
             default_signer_name: self.default_signer_name,
             pool_loaders: self.pool_loaders,
             swap_encoder,
+            actors_manager: self.actors_manager,
+            http_client: self.http_client,
+            dns_resolver: self.dns_resolver,
+            cancellation_token: self.cancellation_token,
         }
     }
 
@@ -277,6 +384,7 @@ impl<
             config: self.config,
             clients: self.clients,
             blockchains: self.blockchains,
+            chain_id_to_name: self.chain_id_to_name,
             blockchain_states: self.blockchain_states,
             strategies: self.strategies,
             signers: self.signers,
@@ -287,15 +395,55 @@ impl<
             default_signer_name: self.default_signer_name,
             swap_encoder: self.swap_encoder,
             pool_loaders: Arc::new(pool_loaders),
+            actors_manager: self.actors_manager,
+            http_client: self.http_client,
+            dns_resolver: self.dns_resolver,
+            cancellation_token: self.cancellation_token,
         }
     }
 
+    /// Default pool loaders on the client, factory overrides and the largest tick preload range of all pools configs are applied
+    pub fn with_default_pool_loaders(self, client_name: Option<&String>) -> Result<Topology<DB, E, RootProvider, Ethereum>> {
+        let client = self.get_client(client_name)?;
+
+        let factory_overrides: HashMap<String, Address> = self
+            .config
+            .actors
+            .pools
+            .iter()
+            .flat_map(|pools| pools.values())
+            .flat_map(|pools_config| pools_config.factory_overrides.clone())
+            .collect();
+
+        let tick_preload_range = self
+            .config
+            .actors
+            .pools
+            .iter()
+            .flat_map(|pools| pools.values())
+            .filter_map(|pools_config| pools_config.tick_preload_range)
+            .max();
+        let mut pools_loading_config = PoolsLoadingConfig::new();
+        if let Some(tick_range) = tick_preload_range {
+            pools_loading_config = pools_loading_config.with_tick_preload(tick_range);
+        }
+
+        let pool_loaders =
+            PoolLoadersBuilder::default_pool_loaders_with_factory_overrides(client, pools_loading_config, &factory_overrides);
+        Ok(self.with_pool_loaders(pool_loaders))
+    }
+
     pub async fn start_clients(mut self) -> Result<Self> {
+        let dns_resolver = configure_dns_settings(&self.config.dns)?;
+        self.http_client = dns_resolver.http_client()?;
+        self.dns_resolver = Some(dns_resolver.clone());
+
         let mut clients = HashMap::new();
         for (name, v) in self.config.clients.iter() {
             let config_params = v.clone();
 
             info!("Connecting to {name} : {v:?}");
+            let rate_limit_layer = RateLimitLayer::new(config_params.rate_limit_rps.unwrap_or_default(), &config_params.method_rate_limits);
             
             // First try to connect with WebSocket for better subscription support
             // If the URL is HTTP, try to convert it to WebSocket
@@ -313,8 +461,9 @@ impl<
             let mut client_result = None;
             if let Some(ws_url) = ws_url {
                 info!("Attempting WebSocket connection to {name} at {ws_url}");
-                let transport = WsConnect { url: ws_url, auth: None, config: None };
-                let ws_client = ClientBuilder::default().ws(transport).await;
+                let config = websocket_config(config_params.max_message_size_bytes);
+                let transport = ResolvedWsConnect::new(ws_url, config, dns_resolver.shared());
+                let ws_client = ClientBuilder::default().layer(rate_limit_layer.clone()).pubsub(transport).await;
                 if let Ok(client) = ws_client {
                     info!("Successfully connected to {name} via WebSocket (subscriptions supported)");
                     client_result = Some(Ok(client));
@@ -326,19 +475,26 @@ impl<
             if client_result.is_none() {
                 client_result = Some(match config_params.transport {
                     TransportType::Ipc => {
+                        // Connection errors of a restarting node are retried with backoff, the socket path needs no resolution
                         info!("Starting IPC connection");
-                        let transport = IpcConnect::from(config_params.url);
-                        ClientBuilder::default().ipc(transport).await
+                        RobustSubscriptionManager::new(config_params.url, vec![])
+                            .with_max_reconnect_attempts(IPC_MAX_CONNECT_ATTEMPTS)
+                            .with_max_message_size_bytes(config_params.max_message_size_bytes)
+                            .connect_robust_client()
+                            .await
                     }
                     TransportType::Http => {
                         info!("Starting HTTP connection (subscriptions not supported)");
                         let url = Url::parse(&config_params.url)?;
-                        Ok(ClientBuilder::default().http(url))
+                        let transport = Http::with_client(self.http_client.clone(), url);
+                        let is_local = transport.guess_local();
+                        Ok(ClientBuilder::default().layer(rate_limit_layer).transport(transport, is_local))
                     }
                     TransportType::Ws => {
                         info!("Starting WS connection");
-                        let transport = WsConnect { url: config_params.url, auth: None, config: None };
-                        ClientBuilder::default().ws(transport).await
+                        let config = websocket_config(config_params.max_message_size_bytes);
+                        let transport = ResolvedWsConnect::new(config_params.url, config, dns_resolver.shared());
+                        ClientBuilder::default().layer(rate_limit_layer).pubsub(transport).await.map_err(ErrReport::from)
                     }
                 });
             }
@@ -408,8 +564,36 @@ impl<
         Ok(Topology { clients, ..self })
     }
 
-    pub async fn start_actors(&self) -> Result<Vec<JoinHandle<WorkerResult>>> {
+    fn track_actor(&self, name: String, tasks: &[JoinHandle<WorkerResult>], restart: Option<RestartActorFn>) {
+        self.actors_manager.lock().unwrap_or_else(|e| e.into_inner()).track(&name, tasks, restart);
+    }
+
+    /// Token the workers of the started actors stop on
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation_token.clone()
+    }
+
+    /// Cancels the workers of all actors started by `start_actors`, their tasks finish once the workers stop
+    pub fn shutdown(&self) {
+        info!("Shutting down topology actors");
+        self.cancellation_token.cancel();
+    }
+
+    /// Checks whether the tasks of the actors started by `start_actors` are still running. Dead actors with
+    /// `restart_on_failure` set in their config are started again by the actors manager once their backoff elapsed.
+    pub fn health_check(&self) -> HealthReport {
+        let report = self.actors_manager.lock().unwrap_or_else(|e| e.into_inner()).check_actors();
+        if !report.dead.is_empty() {
+            warn!("Dead actors : {:?}, restarted : {:?}", report.dead, report.restarted);
+        }
+        report
+    }
+
+    /// Starts all configured actors. An actor that fails to start is logged and skipped, names of failed actors are
+    /// returned with the started tasks so the caller can decide whether to continue. Started actors are checked by `health_check`.
+    pub async fn start_actors(&self) -> Result<(Vec<JoinHandle<WorkerResult>>, Vec<FailedActor>)> {
         let mut tasks: Vec<JoinHandle<WorkerResult>> = Vec::new();
+        let mut failed_actors: Vec<FailedActor> = Vec::new();
 
         if self.clients.is_empty() {
             return Err(eyre!("NO_CLIENTS_CONNECTED"));
@@ -423,9 +607,10 @@ impl<
             #[cfg(feature = "loom-core-block-history-actor")]
             {
                 info!("Starting block history actor {k}");
-                let mut block_history_actor = BlockHistoryActor::new(client);
+                let mut block_history_actor = BlockHistoryActor::new(client).with_cancellation_token(self.cancellation_token.clone());
                 match block_history_actor
                     .access(blockchain.latest_block())
+                    .access(blockchain.market())
                     .access(blockchain_state.market_state())
                     .access(blockchain_state.block_history())
                     .consume(blockchain.new_block_headers_channel())
@@ -436,17 +621,19 @@ impl<
                     .start()
                 {
                     Ok(r) => {
+                        self.track_actor(format!("BlockHistoryActor {k}"), &r, None);
                         tasks.extend(r);
                         info!("Block history actor started successfully");
                     }
                     Err(e) => {
-                        panic!("{}", e)
+                        error!("Cannot start block history actor {k} : {}", e);
+                        failed_actors.push(FailedActor::critical(format!("BlockHistoryActor {k}")));
                     }
                 }
             }
 
             info!("Starting mempool actor {k}");
-            let mut mempool_actor = MempoolActor::new();
+            let mut mempool_actor = MempoolActor::new().with_cancellation_token(self.cancellation_token.clone());
             match mempool_actor
                 .access(blockchain.mempool())
                 .consume(blockchain.new_mempool_tx_channel())
@@ -457,59 +644,82 @@ impl<
                 .start()
             {
                 Ok(r) => {
+                    self.track_actor(format!("MempoolActor {k}"), &r, None);
                     tasks.extend(r);
                     info!("Mempool actor started successfully");
                 }
                 Err(e) => {
-                    panic!("{}", e)
+                    error!("Cannot start mempool actor {k} : {}", e);
+                    failed_actors.push(FailedActor::critical(format!("MempoolActor {k}")));
                 }
             }
 
             info!("Starting pool health monitor actor {k}");
-            let mut new_pool_health_monitor_actor = PoolHealthMonitorActor::new();
+            let mut new_pool_health_monitor_actor = PoolHealthMonitorActor::new()
+                .with_config(self.config.pool_health_monitor.clone())
+                .with_cancellation_token(self.cancellation_token.clone());
             match new_pool_health_monitor_actor
                 .access(blockchain.market())
+                .consume(blockchain.market_events_channel())
                 .consume(blockchain.health_monitor_channel())
                 .produce(blockchain.influxdb_write_channel())
                 .start()
             {
                 Ok(r) => {
+                    self.track_actor(format!("PoolHealthMonitorActor {k}"), &r, None);
                     tasks.extend(r);
                     info!("Pool monitor monitor actor started");
                 }
                 Err(e) => {
-                    panic!("PoolHealthMonitorActor error {}", e)
+                    error!("Cannot start pool health monitor actor {k} : {}", e);
+                    failed_actors.push(FailedActor::new(format!("PoolHealthMonitorActor {k}")));
                 }
             }
         }
 
         for (name, params) in self.config.signers.iter() {
             let signers = self.get_signers(Some(name))?;
-            match params {
-                SignersConfig::Env(params) => {
+            let blockchain = self.get_blockchain(params.blockchain())?;
+
+            let initialize_signers_actor = match params {
+                SignersConfig::Env(_) => {
                     info!("Starting initialize env signers actor {name}");
-                    let blockchain = self.get_blockchain(params.blockchain.as_ref())?;
+                    InitializeSignersOneShotBlockingActor::new_from_encrypted_env()
+                }
+                #[cfg(feature = "aws")]
+                SignersConfig::AwsSecretsManager(params) => {
+                    info!("Starting initialize AWS secrets manager signers actor {name}");
+                    InitializeSignersOneShotBlockingActor::new_from_aws_secrets_manager_with_region(
+                        params.secret_arn.clone(),
+                        Some(params.region.clone()),
+                    )
+                    .await
+                }
+                #[cfg(not(feature = "aws"))]
+                SignersConfig::AwsSecretsManager(_) => Err(eyre!("AWS_FEATURE_NOT_ENABLED")),
+            };
 
-                    let initialize_signers_actor = InitializeSignersOneShotBlockingActor::new_from_encrypted_env();
-                    match initialize_signers_actor?.access(signers.clone()).access(blockchain.nonce_and_balance()).start_and_wait() {
-                        Ok(_) => {
-                            info!("Signers have been initialized");
-                        }
-                        Err(e) => {
-                            panic!("Cannot initialize signers {}", e);
-                        }
-                    }
+            match initialize_signers_actor?.access(signers.clone()).access(blockchain.nonce_and_balance()).start_and_wait() {
+                Ok(_) => {
+                    info!("Signers have been initialized");
+                }
+                Err(e) => {
+                    error!("Cannot initialize signers {name} : {}", e);
+                    failed_actors.push(FailedActor::new(format!("InitializeSignersOneShotBlockingActor {name}")));
+                    continue;
+                }
+            }
 
-                    let mut signers_actor = TxSignersActor::<LoomDataTypesEthereum>::new();
-                    match signers_actor.consume(blockchain.tx_compose_channel()).produce(blockchain.tx_compose_channel()).start() {
-                        Ok(r) => {
-                            tasks.extend(r);
-                            info!("Signers actor has been started");
-                        }
-                        Err(e) => {
-                            panic!("Cannot start signers actor {}", e)
-                        }
-                    }
+            let mut signers_actor = TxSignersActor::<LoomDataTypesEthereum>::new().with_cancellation_token(self.cancellation_token.clone());
+            match signers_actor.consume(blockchain.tx_compose_channel()).produce(blockchain.tx_compose_channel()).start() {
+                Ok(r) => {
+                    self.track_actor(format!("TxSignersActor {name}"), &r, None);
+                    tasks.extend(r);
+                    info!("Signers actor has been started");
+                }
+                Err(e) => {
+                    error!("Cannot start signers actor {name} : {}", e);
+                    failed_actors.push(FailedActor::new(format!("TxSignersActor {name}")));
                 }
             }
         }
@@ -530,7 +740,8 @@ impl<
                         info!("Market state preload actor executed successfully");
                     }
                     Err(e) => {
-                        panic!("MarketStatePreloadedOneShotActor : {}", e)
+                        error!("Cannot preload market state {name} : {}", e);
+                        failed_actors.push(FailedActor::new(format!("MarketStatePreloadedOneShotActor {name}")));
                     }
                 }
             }
@@ -567,21 +778,24 @@ impl<
                 let blockchain = self.get_blockchain(params.blockchain.as_ref())?;
                 let url = params.url.clone().unwrap_or("http://[::1]:10000".to_string());
                 info!("Starting node actor {name}");
-                let mut node_exex_block_actor = NodeExExGrpcActor::new(url);
+                let mut node_exex_block_actor = NodeExExGrpcActor::new(url).with_cancellation_token(self.cancellation_token.clone());
                 match node_exex_block_actor
                     .produce(blockchain.new_block_headers_channel())
                     .produce(blockchain.new_block_with_tx_channel())
                     .produce(blockchain.new_block_logs_channel())
                     .produce(blockchain.new_block_state_update_channel())
                     .produce(blockchain.new_mempool_tx_channel())
+                    .produce(blockchain.market_events_channel())
                     .start()
                 {
                     Ok(r) => {
+                        self.track_actor(format!("NodeExExGrpcActor {name}"), &r, None);
                         tasks.extend(r);
                         info!("Node ExEx actor started successfully for : {} @ {}", name, blockchain.chain_id());
                     }
                     Err(e) => {
-                        panic!("{}", e)
+                        error!("Cannot start node ExEx actor {name} @ {} : {}", blockchain.chain_id(), e);
+                        failed_actors.push(FailedActor::new(format!("NodeExExGrpcActor {name}")));
                     }
                 }
             }
@@ -595,42 +809,81 @@ impl<
                 info!("Starting node actor {name}");
                 #[cfg(feature = "db-access")]
                 if client_config.db_path.is_some() {
-                    let mut node_block_actor = RethDbAccessBlockActor::new(
-                        client.clone(),
-                        NodeBlockActorConfig::all_enabled(),
-                        client_config.db_path.clone().unwrap_or_default(),
-                    );
-                    match node_block_actor
-                        .produce(blockchain.new_block_headers_channel())
-                        .produce(blockchain.new_block_with_tx_channel())
-                        .produce(blockchain.new_block_logs_channel())
-                        .produce(blockchain.new_block_state_update_channel())
-                        .start()
-                    {
+                    let start_node_block_actor = {
+                        let client = client.clone();
+                        let blockchain = blockchain.clone();
+                        let db_path = client_config.db_path.clone().unwrap_or_default();
+                        let cancellation_token = self.cancellation_token.clone();
+                        move || {
+                            let mut node_block_actor =
+                                RethDbAccessBlockActor::new(client.clone(), NodeBlockActorConfig::all_enabled(), db_path.clone())
+                                    .with_cancellation_token(cancellation_token.clone());
+                            node_block_actor
+                                .produce(blockchain.new_block_headers_channel())
+                                .produce(blockchain.new_block_with_tx_channel())
+                                .produce(blockchain.new_block_logs_channel())
+                                .produce(blockchain.new_block_state_update_channel())
+                                .start()
+                        }
+                    };
+                    match start_node_block_actor() {
                         Ok(r) => {
+                            let restart = params.restart_on_failure.then(|| Box::new(start_node_block_actor) as RestartActorFn);
+                            self.track_actor(format!("RethDbAccessBlockActor {name}"), &r, restart);
                             tasks.extend(r);
                             info!("Reth db access node actor started successfully for : {} @ {}", name, blockchain.chain_id());
                         }
                         Err(e) => {
-                            panic!("{}", e)
+                            error!("Cannot start reth db access node actor {name} @ {} : {}", blockchain.chain_id(), e);
+                            failed_actors.push(FailedActor::new(format!("RethDbAccessBlockActor {name}")));
                         }
                     }
                 }
                 if client_config.db_path.is_none() {
-                    let mut node_block_actor = NodeBlockActor::new(client, NodeBlockActorConfig::all_enabled());
-                    match node_block_actor
-                        .produce(blockchain.new_block_headers_channel())
-                        .produce(blockchain.new_block_with_tx_channel())
-                        .produce(blockchain.new_block_logs_channel())
-                        .produce(blockchain.new_block_state_update_channel())
-                        .start()
-                    {
+                    // HTTP transport does not support eth_subscribe
+                    let use_subscription = client_config.transport != TransportType::Http;
+                    if !use_subscription {
+                        warn!("Client of node actor {name} uses HTTP transport, falling back to block polling");
+                    }
+                    let mut node_block_config = NodeBlockActorConfig::all_enabled()
+                        .with_use_subscription(use_subscription)
+                        .with_log_signatures(self.log_prefilter_signatures(blockchain.chain_id())?);
+                    if blockchain.log_subscription_prefilter() {
+                        node_block_config = node_block_config.with_log_subscription_prefilter();
+                    }
+                    if matches!(client_config.transport, TransportType::Ws | TransportType::Ipc) {
+                        node_block_config =
+                            node_block_config.with_subscription_url(client_config.url.clone(), client_config.max_message_size_bytes);
+                    }
+                    let start_node_block_actor = {
+                        let blockchain = blockchain.clone();
+                        let dns_resolver = self.dns_resolver.clone();
+                        let cancellation_token = self.cancellation_token.clone();
+                        move || {
+                            let node_block_config = node_block_config.clone();
+                            let mut node_block_actor =
+                                NodeBlockActor::new(client.clone(), node_block_config).with_cancellation_token(cancellation_token.clone());
+                            if let Some(dns_resolver) = &dns_resolver {
+                                node_block_actor = node_block_actor.with_dns_resolver(dns_resolver.shared());
+                            }
+                            node_block_actor
+                                .produce(blockchain.new_block_headers_channel())
+                                .produce(blockchain.new_block_with_tx_channel())
+                                .produce(blockchain.new_block_logs_channel())
+                                .produce(blockchain.new_block_state_update_channel())
+                                .start()
+                        }
+                    };
+                    match start_node_block_actor() {
                         Ok(r) => {
+                            let restart = params.restart_on_failure.then(|| Box::new(start_node_block_actor) as RestartActorFn);
+                            self.track_actor(format!("NodeBlockActor {name}"), &r, restart);
                             tasks.extend(r);
                             info!("Node actor started successfully for : {} @ {}", name, blockchain.chain_id());
                         }
                         Err(e) => {
-                            panic!("{}", e)
+                            error!("Cannot start node actor {name} @ {} : {}", blockchain.chain_id(), e);
+                            failed_actors.push(FailedActor::new(format!("NodeBlockActor {name}")));
                         }
                     }
                 }
@@ -643,14 +896,30 @@ impl<
                 match self.get_client(params.client.as_ref()) {
                     Ok(client) => {
                         info!("Starting node mempool actor {name}");
-                        let mut node_mempool_actor = NodeMempoolActor::new(client).with_name(name.clone());
-                        match node_mempool_actor.produce(blockchain.new_mempool_tx_channel()).start() {
+                        let start_node_mempool_actor = {
+                            let name = name.clone();
+                            let new_mempool_tx_channel = blockchain.new_mempool_tx_channel();
+                            let mempool_actor_config =
+                                MempoolActorConfig::default().with_full_tx_subscription(params.full_tx_subscription.unwrap_or(true));
+                            let cancellation_token = self.cancellation_token.clone();
+                            move || {
+                                let mut node_mempool_actor = NodeMempoolActor::new(client.clone())
+                                    .with_name(name.clone())
+                                    .with_config(mempool_actor_config.clone())
+                                    .with_cancellation_token(cancellation_token.clone());
+                                node_mempool_actor.produce(new_mempool_tx_channel.clone()).start()
+                            }
+                        };
+                        match start_node_mempool_actor() {
                             Ok(r) => {
+                                let restart = params.restart_on_failure.then(|| Box::new(start_node_mempool_actor) as RestartActorFn);
+                                self.track_actor(format!("NodeMempoolActor {name}"), &r, restart);
                                 tasks.extend(r);
                                 info!("Node mempool actor started successfully {name}");
                             }
                             Err(e) => {
-                                panic!("{}", e)
+                                error!("Cannot start node mempool actor {name} : {}", e);
+                                failed_actors.push(FailedActor::new(format!("NodeMempoolActor {name}")));
                             }
                         }
                     }
@@ -666,14 +935,30 @@ impl<
                 let client = self.get_client(c.client.as_ref())?;
                 let blockchain = self.get_blockchain(c.blockchain.as_ref())?;
                 info!("Starting price actor");
-                let mut price_actor = PriceActor::new(client);
-                match price_actor.access(blockchain.market()).start() {
+                let start_price_actor = {
+                    let market = blockchain.market();
+                    let new_block_headers_channel = blockchain.new_block_headers_channel();
+                    let health_monitor_channel = blockchain.health_monitor_channel();
+                    let cancellation_token = self.cancellation_token.clone();
+                    move || {
+                        let mut price_actor = PriceActor::new(client.clone()).with_cancellation_token(cancellation_token.clone());
+                        price_actor
+                            .access(market.clone())
+                            .consume(new_block_headers_channel.clone())
+                            .produce(health_monitor_channel.clone())
+                            .start()
+                    }
+                };
+                match start_price_actor() {
                     Ok(r) => {
+                        let restart = c.restart_on_failure.then(|| Box::new(start_price_actor) as RestartActorFn);
+                        self.track_actor(format!("PriceActor {name}"), &r, restart);
                         tasks.extend(r);
                         info!("Price actor has been initialized : {}", name);
                     }
                     Err(e) => {
-                        panic!("Cannot initialize price actor {} : {}", name, e);
+                        error!("Cannot initialize price actor {} : {}", name, e);
+                        failed_actors.push(FailedActor::new(format!("PriceActor {name}")));
                     }
                 }
             }
@@ -686,19 +971,29 @@ impl<
                 let client = self.get_client(c.client.as_ref())?;
                 let blockchain = self.get_blockchain(c.blockchain.as_ref())?;
                 info!("Starting nonce and balance monitor actor {name}");
-                let mut nonce_and_balance_monitor = NonceAndBalanceMonitorActor::new(client);
-                match nonce_and_balance_monitor
-                    .access(blockchain.nonce_and_balance())
-                    .access(blockchain.latest_block())
-                    .consume(blockchain.market_events_channel())
-                    .start()
-                {
+                let start_nonce_and_balance_monitor = {
+                    let blockchain = blockchain.clone();
+                    let cancellation_token = self.cancellation_token.clone();
+                    move || {
+                        let mut nonce_and_balance_monitor =
+                            NonceAndBalanceMonitorActor::new(client.clone()).with_cancellation_token(cancellation_token.clone());
+                        nonce_and_balance_monitor
+                            .access(blockchain.nonce_and_balance())
+                            .access(blockchain.latest_block())
+                            .consume(blockchain.market_events_channel())
+                            .start()
+                    }
+                };
+                match start_nonce_and_balance_monitor() {
                     Ok(r) => {
+                        let restart = c.restart_on_failure.then(|| Box::new(start_nonce_and_balance_monitor) as RestartActorFn);
+                        self.track_actor(format!("NonceAndBalanceMonitorActor {name}"), &r, restart);
                         tasks.extend(r);
                         info!("Nonce monitor has been initialized {name} for {}", blockchain.chain_id());
                     }
                     Err(e) => {
-                        panic!("Cannot initialize nonce and balance monitor {} : {}", name, e);
+                        error!("Cannot initialize nonce and balance monitor {} : {}", name, e);
+                        failed_actors.push(FailedActor::new(format!("NonceAndBalanceMonitorActor {name}")));
                     }
                 }
             }
@@ -712,15 +1007,43 @@ impl<
                     BroadcasterConfig::Flashbots(params) => {
                         let client = self.get_client(params.client.as_ref())?;
                         let blockchain = self.get_blockchain(params.blockchain.as_ref())?;
-                        let flashbots_client = Flashbots::new(client, "https://relay.flashbots.net", None).with_default_relays();
-                        let mut flashbots_actor = FlashbotsBroadcastActor::new(flashbots_client.into(), true);
+                        let mut flashbots_client =
+                            Flashbots::new_with_http_client(client, "https://relay.flashbots.net", None, self.http_client.clone())
+                                .with_default_relays();
+                        if let Some(eden) = &params.eden {
+                            flashbots_client = flashbots_client.with_eden_relay(eden.staker_address, eden.api_key.clone());
+                        }
+                        let mut flashbots_actor = FlashbotsBroadcastActor::new(flashbots_client.into(), true)
+                            .with_mempool(blockchain.mempool())
+                            .with_health_monitor_channel(blockchain.health_monitor_channel())
+                            .with_skip_simulation(params.skip_simulation)
+                            .with_cancellation_token(self.cancellation_token.clone());
                         match flashbots_actor.consume(blockchain.tx_compose_channel()).start() {
                             Ok(r) => {
+                                self.track_actor(format!("FlashbotsBroadcastActor {name}"), &r, None);
                                 tasks.extend(r);
                                 info!("Flashbots broadcaster actor {name} started successfully for {}", blockchain.chain_id());
                             }
                             Err(e) => {
-                                panic!("Error starting flashbots broadcaster actor {name} for {} : {}", blockchain.chain_id(), e)
+                                error!("Error starting flashbots broadcaster actor {name} for {} : {}", blockchain.chain_id(), e);
+                                failed_actors.push(FailedActor::new(format!("FlashbotsBroadcastActor {name}")));
+                            }
+                        }
+                    }
+                    BroadcasterConfig::PrivateMempool(params) => {
+                        let blockchain = self.get_blockchain(params.blockchain.as_ref())?;
+                        let mut private_mempool_actor = PrivateMempoolBroadcastActor::new(params.relays.clone(), true)
+                            .with_http_client(self.http_client.clone())
+                            .with_cancellation_token(self.cancellation_token.clone());
+                        match private_mempool_actor.consume(blockchain.tx_compose_channel()).start() {
+                            Ok(r) => {
+                                self.track_actor(format!("PrivateMempoolBroadcastActor {name}"), &r, None);
+                                tasks.extend(r);
+                                info!("Private mempool broadcaster actor {name} started successfully for {}", blockchain.chain_id());
+                            }
+                            Err(e) => {
+                                error!("Error starting private mempool broadcaster actor {name} for {} : {}", blockchain.chain_id(), e);
+                                failed_actors.push(FailedActor::new(format!("PrivateMempoolBroadcastActor {name}")));
                             }
                         }
                     }
@@ -747,7 +1070,8 @@ impl<
                             info!("History pool loader actor started successfully {name}");
                         }
                         Err(e) => {
-                            panic!("HistoryPoolLoaderOneShotActor : {}", e)
+                            error!("HistoryPoolLoaderOneShotActor {name} : {}", e);
+                            failed_actors.push(FailedActor::new(format!("HistoryPoolLoaderOneShotActor {name}")));
                         }
                     }
                 }
@@ -760,25 +1084,45 @@ impl<
                             info!("Protocol pool loader actor started successfully {name}");
                         }
                         Err(e) => {
-                            panic!("ProtocolPoolLoaderOneShotActor : {}", e)
+                            error!("ProtocolPoolLoaderOneShotActor {name} : {}", e);
+                            failed_actors.push(FailedActor::new(format!("ProtocolPoolLoaderOneShotActor {name}")));
                         }
                     }
                 }
                 if params.new {
                     info!("Starting new pool loader actor {name}");
-                    let mut new_pool_actor = NewPoolLoaderActor::new(pool_loaders.clone());
-                    match new_pool_actor.consume(blockchain.new_block_logs_channel()).produce(blockchain.tasks_channel()).start() {
+                    let factory_event_filters = params
+                        .extra_factories
+                        .iter()
+                        .map(|factory| (factory.address, EventFilter::new(&factory.event, factory.pool_address_word, factory.class)))
+                        .collect();
+                    let mut new_pool_actor = NewPoolLoaderActor::new(pool_loaders.clone())
+                        .with_factory_event_filters(factory_event_filters)
+                        .with_cancellation_token(self.cancellation_token.clone());
+                    match new_pool_actor
+                        .access(blockchain.market())
+                        .consume(blockchain.new_block_logs_channel())
+                        .produce(blockchain.tasks_channel())
+                        .start()
+                    {
                         Ok(r) => {
+                            self.track_actor(format!("NewPoolLoaderActor {name}"), &r, None);
                             tasks.extend(r);
                             info!("New pool actor started successfully {name}");
                         }
                         Err(e) => {
-                            panic!("NewPoolLoaderActor : {}", e)
+                            error!("NewPoolLoaderActor {name} : {}", e);
+                            failed_actors.push(FailedActor::new(format!("NewPoolLoaderActor {name}")));
                         }
                     }
                 }
                 info!("Starting pool loader actor {name}");
-                let mut pool_loader_actor = PoolLoaderActor::new(client.clone(), pool_loaders.clone(), PoolsLoadingConfig::new());
+                let mut pools_config = PoolsLoadingConfig::new();
+                if let Some(file) = &params.swap_paths_cache_file {
+                    pools_config = pools_config.with_swap_paths_cache(PathBuf::from(file), params.swap_paths_cache_max_age_blocks);
+                }
+                let mut pool_loader_actor = PoolLoaderActor::new(client.clone(), pool_loaders.clone(), pools_config)
+                    .with_cancellation_token(self.cancellation_token.clone());
                 match pool_loader_actor
                     .access(blockchain.market())
                     .access(blockchain_state.market_state())
@@ -787,11 +1131,13 @@ impl<
                     .start()
                 {
                     Ok(r) => {
+                        self.track_actor(format!("PoolLoaderActor {name}"), &r, None);
                         tasks.extend(r);
                         info!("Pool loader actor started successfully {name}");
                     }
                     Err(e) => {
-                        panic!("PoolLoaderActor : {}", e)
+                        error!("PoolLoaderActor {name} : {}", e);
+                        failed_actors.push(FailedActor::new(format!("PoolLoaderActor {name}")));
                     }
                 }
             }
@@ -809,7 +1155,8 @@ impl<
                         let multicaller_address = self.get_multicaller_address(params.encoder.as_ref())?;
                         let mut encoder = self.swap_encoder.clone();
                         encoder.set_address(multicaller_address);
-                        let mut evm_estimator_actor = EvmEstimatorActor::new_with_provider(encoder, client);
+                        let mut evm_estimator_actor =
+                            EvmEstimatorActor::new_with_provider(encoder, client).with_cancellation_token(self.cancellation_token.clone());
                         match evm_estimator_actor
                             .consume(strategy.swap_compose_channel())
                             .produce(strategy.swap_compose_channel())
@@ -818,11 +1165,13 @@ impl<
                             .start()
                         {
                             Ok(r) => {
+                                self.track_actor(format!("EvmEstimatorActor {name}"), &r, None);
                                 tasks.extend(r);
                                 info!("EVM estimator actor started successfully {name} @ {}", blockchain.chain_id());
                             }
                             Err(e) => {
-                                panic!("Error starting EVM estimator actor {name} @ {} : {}", blockchain.chain_id(), e)
+                                error!("Error starting EVM estimator actor {name} @ {} : {}", blockchain.chain_id(), e);
+                                failed_actors.push(FailedActor::new(format!("EvmEstimatorActor {name}")));
                             }
                         }
                     }
@@ -833,15 +1182,68 @@ impl<
                         let multicaller_address = self.get_multicaller_address(params.encoder.as_ref())?;
                         let mut encoder = self.swap_encoder.clone();
                         encoder.set_address(multicaller_address);
-                        let flashbots_client = Arc::new(Flashbots::new(client, "https://relay.flashbots.net", None).with_default_relays());
-                        let mut geth_estimator_actor = GethEstimatorActor::new(flashbots_client, encoder);
+                        let flashbots_client = Arc::new(
+                            Flashbots::new_with_http_client(client, "https://relay.flashbots.net", None, self.http_client.clone())
+                                .with_default_relays(),
+                        );
+                        let mut geth_estimator_actor =
+                            GethEstimatorActor::new(flashbots_client, encoder).with_cancellation_token(self.cancellation_token.clone());
                         match geth_estimator_actor.consume(strategy.swap_compose_channel()).produce(strategy.swap_compose_channel()).start() {
                             Ok(r) => {
+                                self.track_actor(format!("GethEstimatorActor {name}"), &r, None);
                                 tasks.extend(r);
                                 info!("Geth estimator actor started successfully {name} @ {}", blockchain.chain_id());
                             }
                             Err(e) => {
-                                panic!("Error starting Geth estimator actor for {name} @ {} : {}", blockchain.chain_id(), e)
+                                error!("Error starting Geth estimator actor for {name} @ {} : {}", blockchain.chain_id(), e);
+                                failed_actors.push(FailedActor::new(format!("GethEstimatorActor {name}")));
+                            }
+                        }
+                    }
+                    EstimatorConfig::Supervisor(params) => {
+                        let client = self.get_client(params.client.as_ref())?;
+                        let blockchain = self.get_blockchain(params.blockchain.as_ref())?;
+                        let strategy = self.get_strategy(params.blockchain.as_ref())?;
+                        let multicaller_address = self.get_multicaller_address(params.encoder.as_ref())?;
+                        let mut encoder = self.swap_encoder.clone();
+                        encoder.set_address(multicaller_address);
+
+                        let mut evm_estimator_actor = EvmEstimatorActor::new_with_provider(encoder.clone(), Some(client.clone()))
+                            .with_cancellation_token(self.cancellation_token.clone());
+                        evm_estimator_actor
+                            .produce(strategy.swap_compose_channel())
+                            .produce(blockchain.health_monitor_channel())
+                            .produce(blockchain.influxdb_write_channel());
+                        let flashbots_client = Arc::new(
+                            Flashbots::new_with_http_client(client, "https://relay.flashbots.net", None, self.http_client.clone())
+                                .with_default_relays(),
+                        );
+                        let mut geth_estimator_actor =
+                            GethEstimatorActor::new(flashbots_client, encoder).with_cancellation_token(self.cancellation_token.clone());
+                        geth_estimator_actor.produce(strategy.swap_compose_channel());
+
+                        let mut estimator_supervisor_actor = EstimatorSupervisorActor::new(evm_estimator_actor, geth_estimator_actor)
+                            .with_cancellation_token(self.cancellation_token.clone());
+                        if let (Some(max_divergences), Some(divergence_window_blocks)) =
+                            (params.max_divergences, params.divergence_window_blocks)
+                        {
+                            estimator_supervisor_actor =
+                                estimator_supervisor_actor.with_divergence_limit(max_divergences, divergence_window_blocks);
+                        }
+                        match estimator_supervisor_actor
+                            .consume(strategy.swap_compose_channel())
+                            .consume(blockchain.market_events_channel())
+                            .consume(blockchain.health_monitor_channel())
+                            .start()
+                        {
+                            Ok(r) => {
+                                self.track_actor(format!("EstimatorSupervisorActor {name}"), &r, None);
+                                tasks.extend(r);
+                                info!("Estimator supervisor actor started successfully {name} @ {}", blockchain.chain_id());
+                            }
+                            Err(e) => {
+                                error!("Error starting estimator supervisor actor for {name} @ {} : {}", blockchain.chain_id(), e);
+                                failed_actors.push(FailedActor::new(format!("EstimatorSupervisorActor {name}")));
                             }
                         }
                     }
@@ -851,7 +1253,136 @@ impl<
             warn!("No estimator actors in config");
         }
 
-        Ok(tasks)
+        let (strategy_tasks, strategy_failed_actors) = self.start_strategies()?;
+        tasks.extend(strategy_tasks);
+        failed_actors.extend(strategy_failed_actors);
+
+        if !failed_actors.is_empty() {
+            warn!("Failed to start {} actors : {:?}", failed_actors.len(), failed_actors);
+        }
+
+        Ok((tasks, failed_actors))
+    }
+
+    /// Start one actor set per `[[strategies]]` entry. Backrun and simple arb strategies get their own swap router and
+    /// EVM estimator on the swap compose channel of the strategy, so candidates of different strategies are not mixed.
+    fn start_strategies(&self) -> Result<(Vec<JoinHandle<WorkerResult>>, Vec<FailedActor>)> {
+        let mut tasks: Vec<JoinHandle<WorkerResult>> = Vec::new();
+        let mut failed_actors: Vec<FailedActor> = Vec::new();
+
+        for strategy_config in self.config.strategies.iter() {
+            let name = strategy_config.name();
+            let blockchain = self.get_blockchain(strategy_config.blockchain())?;
+            let strategy = self.get_strategy(Some(name))?;
+
+            let (actor_name, result, client, signers_name, encoder_name, enable_eip7702) = match strategy_config {
+                StrategyEntryConfig::Backrun(params) => {
+                    let blockchain_state = self.get_blockchain_state(params.blockchain.as_ref())?;
+                    let client = self.get_client(params.client.as_ref())?;
+                    let mut state_change_arb_actor = StateChangeArbActor::new(client.clone(), true, true, params.config.clone())
+                        .with_cancellation_token(self.cancellation_token.clone());
+                    if let Ok(multicaller_address) = self.get_multicaller_address(params.encoder.as_ref()) {
+                        state_change_arb_actor = state_change_arb_actor.with_multicaller_address(multicaller_address);
+                    }
+                    let result = state_change_arb_actor
+                        .access(blockchain.mempool())
+                        .access(blockchain.latest_block())
+                        .access(blockchain.market())
+                        .access(blockchain.nonce_and_balance())
+                        .access(blockchain_state.market_state())
+                        .access(blockchain_state.block_history())
+                        .consume(blockchain.market_events_channel())
+                        .consume(blockchain.mempool_events_channel())
+                        .consume(blockchain.tasks_channel())
+                        .produce(strategy.swap_compose_channel())
+                        .produce(blockchain.health_monitor_channel())
+                        .produce(blockchain.influxdb_write_channel())
+                        .start();
+                    let enable_eip7702 = params.config.enable_eip7702();
+                    ("StateChangeArbActor", result, Some(client), params.signers.as_ref(), params.encoder.as_ref(), enable_eip7702)
+                }
+                StrategyEntryConfig::SimpleArb(params) => {
+                    let client = self.get_client(params.client.as_ref())?;
+                    let result = SimpleArbFinderActor::new(params.config.clone())
+                        .on_bc(blockchain, strategy)
+                        .with_cancellation_token(self.cancellation_token.clone())
+                        .start();
+                    ("SimpleArbFinderActor", result, Some(client), params.signers.as_ref(), params.encoder.as_ref(), false)
+                }
+                StrategyEntryConfig::Sandwich(_) => {
+                    let result =
+                        SandwichDetectorActor::new().on_bc(blockchain).with_cancellation_token(self.cancellation_token.clone()).start();
+                    ("SandwichDetectorActor", result, None, None, None, false)
+                }
+            };
+            match result {
+                Ok(r) => {
+                    self.track_actor(format!("{actor_name} {name}"), &r, None);
+                    tasks.extend(r);
+                    info!("{actor_name} started successfully for strategy {name}");
+                }
+                Err(e) => {
+                    error!("Error starting {actor_name} for strategy {name} : {}", e);
+                    failed_actors.push(FailedActor::new(format!("{actor_name} {name}")));
+                    continue;
+                }
+            }
+
+            let Some(client) = client else {
+                continue;
+            };
+
+            let mut swap_router_actor = SwapRouterActor::<DB>::new()
+                .with_signers(self.get_signers(signers_name)?)
+                .with_cancellation_token(self.cancellation_token.clone());
+            // Only strategies configured for EIP-7702 route their swaps to the delegated EOA
+            if enable_eip7702 {
+                if let Ok(multicaller_address) = self.get_multicaller_address(encoder_name) {
+                    swap_router_actor = swap_router_actor.with_eip7702_delegate(multicaller_address);
+                }
+            }
+            match swap_router_actor
+                .access(blockchain.nonce_and_balance())
+                .consume(strategy.swap_compose_channel())
+                .produce(strategy.swap_compose_channel())
+                .produce(blockchain.tx_compose_channel())
+                .start()
+            {
+                Ok(r) => {
+                    self.track_actor(format!("SwapRouterActor {name}"), &r, None);
+                    tasks.extend(r);
+                    info!("Swap router actor started successfully for strategy {name}");
+                }
+                Err(e) => {
+                    error!("Error starting swap router actor for strategy {name} : {}", e);
+                    failed_actors.push(FailedActor::new(format!("SwapRouterActor {name}")));
+                }
+            }
+
+            let mut encoder = self.swap_encoder.clone();
+            encoder.set_address(self.get_multicaller_address(encoder_name)?);
+            let mut evm_estimator_actor =
+                EvmEstimatorActor::new_with_provider(encoder, Some(client)).with_cancellation_token(self.cancellation_token.clone());
+            match evm_estimator_actor
+                .consume(strategy.swap_compose_channel())
+                .produce(strategy.swap_compose_channel())
+                .produce(blockchain.health_monitor_channel())
+                .produce(blockchain.influxdb_write_channel())
+                .start()
+            {
+                Ok(r) => {
+                    self.track_actor(format!("EvmEstimatorActor {name}"), &r, None);
+                    tasks.extend(r);
+                    info!("EVM estimator actor started successfully for strategy {name}");
+                }
+                Err(e) => {
+                    error!("Error starting EVM estimator actor for strategy {name} : {}", e);
+                    failed_actors.push(FailedActor::new(format!("EvmEstimatorActor {name}")));
+                }
+            }
+        }
+
+        Ok((tasks, failed_actors))
     }
 }
 
@@ -1,8 +1,18 @@
+use alloy_primitives::Address;
 use eyre::Result;
 use loom_broadcast_flashbots::client::RelayConfig;
+use loom_broadcast_flashbots::PrivateRelayConfig;
+use loom_core_topology_shared::MethodRateLimit;
+use loom_defi_health_monitor::{PoolHealthMonitorConfig, SimulationDifferentialConfig};
+use loom_node_json_rpc::DEFAULT_MAX_MESSAGE_SIZE_BYTES;
+use loom_strategy_backrun::BackrunConfig;
+use loom_strategy_merger::MergerConfig;
+use loom_strategy_simple_arb::SimpleArbConfig;
+use loom_types_entities::PoolClass;
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::fs;
+use std::net::SocketAddr;
 use strum_macros::Display;
 use std::marker::PhantomData;
 
@@ -11,6 +21,12 @@ use std::marker::PhantomData;
 #[derive(Clone, Debug, Deserialize)]
 pub struct BlockchainConfig {
     pub chain_id: Option<i64>,
+    /// Apply `Blockchain::with_base_defaults`, Base has 2 seconds blocks and cheap gas
+    #[serde(default)]
+    pub base_defaults: bool,
+    /// Slot of the `balanceOf` mapping of the wrapped native token, WETH9 layout if not set
+    #[serde(default)]
+    pub weth_balance_of_slot: Option<u64>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Display)]
@@ -40,6 +56,10 @@ pub struct InfluxDbConfig {
     pub url: String,
     pub database: String,
     pub tags: HashMap<String, String>,
+    /// Writes are flushed when the batch reaches this size, 500 by default
+    pub batch_size: Option<usize>,
+    /// Writes are flushed at least this often, 1000 ms by default
+    pub flush_interval_ms: Option<u64>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -49,6 +69,8 @@ pub struct ClientConfig<P, N> {
     pub transport: TransportType,
     pub db_path: Option<String>,
     pub exex: Option<String>,
+    #[serde(default = "default_max_message_size_bytes")]
+    pub max_message_size_bytes: usize,
     #[serde(skip)]
     pub provider: Option<P>,
     #[serde(skip)]
@@ -63,6 +85,7 @@ impl<P, N> Default for ClientConfig<P, N> {
             transport: TransportType::default(),
             db_path: None,
             exex: None,
+            max_message_size_bytes: DEFAULT_MAX_MESSAGE_SIZE_BYTES,
             provider: None,
             _n: PhantomData,
         }
@@ -117,11 +140,30 @@ pub struct EnvSingerConfig {
     pub blockchain: Option<String>,
 }
 
+#[derive(Clone, Debug, Deserialize)]
+pub struct AwsSecretsManagerSignerConfig {
+    #[serde(rename = "bc")]
+    pub blockchain: Option<String>,
+    pub secret_arn: String,
+    pub region: String,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(tag = "type")]
 pub enum SignersConfig {
     #[serde(rename = "env")]
     Env(EnvSingerConfig),
+    #[serde(rename = "aws_secrets_manager")]
+    AwsSecretsManager(AwsSecretsManagerSignerConfig),
+}
+
+impl SignersConfig {
+    pub fn blockchain(&self) -> Option<&String> {
+        match self {
+            Self::Env(params) => params.blockchain.as_ref(),
+            Self::AwsSecretsManager(params) => params.blockchain.as_ref(),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -150,6 +192,11 @@ pub struct BlockchainClientConfig {
     #[serde(rename = "bc")]
     pub blockchain: Option<String>,
     pub client: Option<String>,
+    /// Start the actor again when `Topology::health_check` finds it dead
+    #[serde(default)]
+    pub restart_on_failure: bool,
+    /// Mempool actors only, subscribe to full pending transactions instead of hashes, enabled by default
+    pub full_tx_subscription: Option<bool>,
 }
 #[derive(Clone, Debug, Deserialize)]
 pub struct ExExClientConfig {
@@ -172,6 +219,12 @@ impl From<FlashbotsRelayConfig> for RelayConfig {
     }
 }
 
+#[derive(Clone, Debug, Deserialize)]
+pub struct EdenRelayConfig {
+    pub staker_address: Address,
+    pub api_key: String,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct FlashbotsBroadcasterConfig {
     #[serde(rename = "bc")]
@@ -179,6 +232,10 @@ pub struct FlashbotsBroadcasterConfig {
     pub client: Option<String>,
     pub smart: Option<bool>,
     pub relays: Option<Vec<FlashbotsRelayConfig>>,
+    pub eden: Option<EdenRelayConfig>,
+    /// Broadcast bundles without simulating them with `eth_callBundle` first
+    #[serde(default)]
+    pub skip_simulation: bool,
 }
 
 impl FlashbotsBroadcasterConfig {
@@ -187,11 +244,20 @@ impl FlashbotsBroadcasterConfig {
     }
 }
 
+#[derive(Clone, Debug, Deserialize)]
+pub struct PrivateMempoolConfig {
+    #[serde(rename = "bc")]
+    pub blockchain: Option<String>,
+    pub relays: Vec<PrivateRelayConfig>,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(tag = "type")]
 pub enum BroadcasterConfig {
     #[serde(rename = "flashbots")]
     Flashbots(FlashbotsBroadcasterConfig),
+    #[serde(rename = "private")]
+    PrivateMempool(PrivateMempoolConfig),
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -210,6 +276,18 @@ pub struct GethEstimatorConfig {
     pub encoder: Option<String>,
 }
 
+/// EVM estimator with failover to the Geth estimator while EVM gas estimates diverge from gas used on-chain
+#[derive(Clone, Debug, Deserialize)]
+pub struct SupervisorEstimatorConfig {
+    pub client: Option<String>,
+    #[serde(rename = "bc")]
+    pub blockchain: Option<String>,
+    pub encoder: Option<String>,
+    /// Divergences within `divergence_window_blocks` blocks that switch estimates to the Geth estimator
+    pub max_divergences: Option<usize>,
+    pub divergence_window_blocks: Option<u64>,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(tag = "type")]
 pub enum EstimatorConfig {
@@ -217,6 +295,8 @@ pub enum EstimatorConfig {
     Evm(EvmEstimatorConfig),
     #[serde(rename = "geth")]
     Geth(GethEstimatorConfig),
+    #[serde(rename = "supervisor")]
+    Supervisor(SupervisorEstimatorConfig),
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -227,6 +307,34 @@ pub struct PoolsConfig {
     pub history: bool,
     pub new: bool,
     pub protocol: bool,
+    /// Factory addresses of forked protocols by protocol name, e.g. `aerodrome`
+    #[serde(default)]
+    pub factory_overrides: HashMap<String, Address>,
+    /// File to save swap paths to, paths are loaded from it on start instead of being built
+    pub swap_paths_cache_file: Option<String>,
+    /// Blocks after which the swap paths cache file is stale and paths are built again
+    #[serde(default = "default_swap_paths_cache_max_age_blocks")]
+    pub swap_paths_cache_max_age_blocks: u64,
+    /// Factories watched for pool creation events by the new pool loader
+    #[serde(default)]
+    pub extra_factories: Vec<ExtraFactoryConfig>,
+    /// Initialized ticks within `current tick ± tick_preload_range` of UniswapV3 like pools are preloaded on load
+    pub tick_preload_range: Option<i32>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ExtraFactoryConfig {
+    pub address: Address,
+    /// Pool creation event declaration, e.g. `PoolCreated(address,address,int24,address)`
+    pub event: String,
+    /// 32 byte word of the event data holding the pool address
+    #[serde(default)]
+    pub pool_address_word: usize,
+    pub class: PoolClass,
+}
+
+fn default_swap_paths_cache_max_age_blocks() -> u64 {
+    300
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -245,6 +353,22 @@ pub struct DatabaseConfig {
     pub url: String,
 }
 
+/// DNS resolution of the node and relay endpoints, used by the `DnsResolver` of their HTTP clients
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct DnsConfig {
+    /// Nameserver used instead of the system resolver, e.g. `8.8.8.8:53`
+    pub server: Option<String>,
+    pub timeout_ms: u64,
+    pub cache_ttl_secs: u64,
+}
+
+impl Default for DnsConfig {
+    fn default() -> Self {
+        DnsConfig { server: None, timeout_ms: 10000, cache_ttl_secs: 300 }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct ActorConfig {
     pub broadcaster: Option<HashMap<String, BroadcasterConfig>>,
@@ -265,6 +389,16 @@ pub struct DeserializableClientConfig {
     pub db_path: Option<String>,
     pub exex: Option<String>,
     pub rate_limit_rps: Option<u32>,
+    /// Limits of RPC methods, the longest matching method prefix applies. Other methods use `rate_limit_rps`
+    #[serde(default)]
+    pub method_rate_limits: Vec<MethodRateLimit>,
+    /// Largest WebSocket or IPC message accepted from the node, block traces can exceed the 64 MiB default
+    #[serde(default = "default_max_message_size_bytes")]
+    pub max_message_size_bytes: usize,
+}
+
+fn default_max_message_size_bytes() -> usize {
+    DEFAULT_MAX_MESSAGE_SIZE_BYTES
 }
 
 impl DeserializableClientConfig {
@@ -275,12 +409,80 @@ impl DeserializableClientConfig {
             transport: self.transport,
             db_path: self.db_path,
             exex: self.exex,
+            max_message_size_bytes: self.max_message_size_bytes,
             provider: None,
             _n: PhantomData,
         }
     }
 }
 
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct StrategiesConfig {
+    pub merger: MergerConfig,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct BackrunStrategyConfig {
+    pub name: String,
+    #[serde(rename = "bc")]
+    pub blockchain: Option<String>,
+    pub client: Option<String>,
+    pub signers: Option<String>,
+    pub encoder: Option<String>,
+    #[serde(flatten)]
+    pub config: BackrunConfig,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SimpleArbStrategyConfig {
+    pub name: String,
+    #[serde(rename = "bc")]
+    pub blockchain: Option<String>,
+    pub client: Option<String>,
+    pub signers: Option<String>,
+    pub encoder: Option<String>,
+    #[serde(flatten)]
+    pub config: SimpleArbConfig,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SandwichStrategyConfig {
+    pub name: String,
+    #[serde(rename = "bc")]
+    pub blockchain: Option<String>,
+}
+
+/// Entry of the `[[strategies]]` array, every entry runs its own actor set with its own swap compose channel
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "type")]
+pub enum StrategyEntryConfig {
+    #[serde(rename = "backrun")]
+    Backrun(BackrunStrategyConfig),
+    #[serde(rename = "simple_arb")]
+    SimpleArb(SimpleArbStrategyConfig),
+    #[serde(rename = "sandwich")]
+    Sandwich(SandwichStrategyConfig),
+}
+
+impl StrategyEntryConfig {
+    pub fn name(&self) -> &String {
+        match self {
+            Self::Backrun(params) => &params.name,
+            Self::SimpleArb(params) => &params.name,
+            Self::Sandwich(params) => &params.name,
+        }
+    }
+
+    pub fn blockchain(&self) -> Option<&String> {
+        match self {
+            Self::Backrun(params) => params.blockchain.as_ref(),
+            Self::SimpleArb(params) => params.blockchain.as_ref(),
+            Self::Sandwich(params) => params.blockchain.as_ref(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct TopologyConfig {
     pub influxdb: Option<InfluxDbConfig>,
@@ -292,6 +494,18 @@ pub struct TopologyConfig {
     pub preloaders: Option<HashMap<String, PreloaderConfig>>,
     pub webserver: Option<WebserverConfig>,
     pub database: Option<DatabaseConfig>,
+    #[serde(default)]
+    pub dns: DnsConfig,
+    #[serde(default)]
+    pub simple_arb: SimpleArbConfig,
+    #[serde(default)]
+    pub pool_health_monitor: PoolHealthMonitorConfig,
+    #[serde(default)]
+    pub simulation_differential: SimulationDifferentialConfig,
+    #[serde(default)]
+    pub strategy: StrategiesConfig,
+    #[serde(default)]
+    pub strategies: Vec<StrategyEntryConfig>,
 }
 
 impl TopologyConfig {
@@ -300,12 +514,248 @@ impl TopologyConfig {
         let config: TopologyConfig = toml::from_str(&contents)?;
         Ok(config)
     }
+
+    /// Cross-check named references between sections and parse address strings without any I/O.
+    /// Returns all problems found.
+    pub fn validate(&self) -> std::result::Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        let mut check = |section: String, kind: &str, name: Option<&String>, known: bool| {
+            if let Some(name) = name {
+                if !known {
+                    errors.push(format!("{section}: {kind} '{name}' not found"));
+                }
+            }
+        };
+        let client = |name: Option<&String>| name.is_none_or(|name| self.clients.contains_key(name));
+        let blockchain = |name: Option<&String>| name.is_none_or(|name| self.blockchains.contains_key(name));
+
+        for (name, signers) in self.signers.iter() {
+            check(format!("signers.{name}"), "blockchain", signers.blockchain(), blockchain(signers.blockchain()));
+        }
+
+        for (name, preloader) in self.preloaders.iter().flatten() {
+            let section = format!("preloaders.{name}");
+            check(section.clone(), "client", preloader.client.as_ref(), client(preloader.client.as_ref()));
+            check(section.clone(), "blockchain", preloader.blockchain.as_ref(), blockchain(preloader.blockchain.as_ref()));
+            check(
+                section.clone(),
+                "encoder",
+                preloader.encoder.as_ref(),
+                preloader.encoder.as_ref().is_none_or(|encoder| self.encoders.contains_key(encoder)),
+            );
+            check(
+                section,
+                "signers",
+                preloader.signers.as_ref(),
+                preloader.signers.as_ref().is_none_or(|signers| self.signers.contains_key(signers)),
+            );
+        }
+
+        let blockchain_client_sections = [
+            ("node", &self.actors.node),
+            ("mempool", &self.actors.mempool),
+            ("price", &self.actors.price),
+            ("noncebalance", &self.actors.noncebalance),
+        ];
+        for (actor, configs) in blockchain_client_sections {
+            for (name, config) in configs.iter().flatten() {
+                let section = format!("actors.{actor}.{name}");
+                check(section.clone(), "client", config.client.as_ref(), client(config.client.as_ref()));
+                check(section, "blockchain", config.blockchain.as_ref(), blockchain(config.blockchain.as_ref()));
+            }
+        }
+
+        for (name, config) in self.actors.node_exex.iter().flatten() {
+            check(format!("actors.node_exex.{name}"), "blockchain", config.blockchain.as_ref(), blockchain(config.blockchain.as_ref()));
+        }
+
+        for (name, config) in self.actors.pools.iter().flatten() {
+            let section = format!("actors.pools.{name}");
+            check(section.clone(), "client", config.client.as_ref(), client(config.client.as_ref()));
+            check(section, "blockchain", config.blockchain.as_ref(), blockchain(config.blockchain.as_ref()));
+        }
+
+        for (name, config) in self.actors.broadcaster.iter().flatten() {
+            let section = format!("actors.broadcaster.{name}");
+            match config {
+                BroadcasterConfig::Flashbots(config) => {
+                    check(section.clone(), "client", config.client.as_ref(), client(config.client.as_ref()));
+                    check(section, "blockchain", config.blockchain.as_ref(), blockchain(config.blockchain.as_ref()));
+                }
+                BroadcasterConfig::PrivateMempool(config) => {
+                    check(section, "blockchain", config.blockchain.as_ref(), blockchain(config.blockchain.as_ref()));
+                }
+            }
+        }
+
+        for (name, config) in self.actors.estimator.iter().flatten() {
+            let section = format!("actors.estimator.{name}");
+            let (client_name, blockchain_name, encoder_name) = match config {
+                EstimatorConfig::Evm(config) => (config.client.as_ref(), config.blockchain.as_ref(), config.encoder.as_ref()),
+                EstimatorConfig::Geth(config) => (config.client.as_ref(), config.blockchain.as_ref(), config.encoder.as_ref()),
+                EstimatorConfig::Supervisor(config) => (config.client.as_ref(), config.blockchain.as_ref(), config.encoder.as_ref()),
+            };
+            check(section.clone(), "client", client_name, client(client_name));
+            check(section.clone(), "blockchain", blockchain_name, blockchain(blockchain_name));
+            check(section, "encoder", encoder_name, encoder_name.is_none_or(|encoder| self.encoders.contains_key(encoder)));
+        }
+
+        for strategy in self.strategies.iter() {
+            let section = format!("strategies.{}", strategy.name());
+            let (client_name, signers_name, encoder_name) = match strategy {
+                StrategyEntryConfig::Backrun(config) => (config.client.as_ref(), config.signers.as_ref(), config.encoder.as_ref()),
+                StrategyEntryConfig::SimpleArb(config) => (config.client.as_ref(), config.signers.as_ref(), config.encoder.as_ref()),
+                StrategyEntryConfig::Sandwich(_) => (None, None, None),
+            };
+            check(section.clone(), "client", client_name, client(client_name));
+            check(section.clone(), "blockchain", strategy.blockchain(), blockchain(strategy.blockchain()));
+            check(section.clone(), "signers", signers_name, signers_name.is_none_or(|signers| self.signers.contains_key(signers)));
+            check(section, "encoder", encoder_name, encoder_name.is_none_or(|encoder| self.encoders.contains_key(encoder)));
+        }
+
+        for (name, encoder) in self.encoders.iter() {
+            match encoder {
+                EncoderConfig::SwapStep(config) => {
+                    if let Err(error) = config.address.parse::<Address>() {
+                        errors.push(format!("encoders.{name}: invalid address '{}': {error}", config.address));
+                    }
+                }
+            }
+        }
+
+        if let Some(server) = &self.dns.server {
+            if let Err(error) = server.parse::<SocketAddr>() {
+                errors.push(format!("dns: invalid server '{server}': {error}"));
+            }
+        }
+
+        // Strategies share the strategy map with blockchains
+        let mut strategy_names: Vec<&String> = self.blockchains.keys().collect();
+        for strategy in self.strategies.iter() {
+            if strategy_names.contains(&strategy.name()) {
+                errors.push(format!("strategies.{}: name is already used", strategy.name()));
+            }
+            strategy_names.push(strategy.name());
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    const VALID_CONFIG: &str = r#"
+        [clients]
+        local = { url = "ws://localhost:8546", transport = "ws", node = "geth" }
+
+        [blockchains]
+        mainnet = {}
+
+        [signers]
+        env_signer = { type = "env", bc = "mainnet" }
+
+        [encoders]
+        mainnet = { type = "swapstep", address = "0x6E3b634eBd2EbBffb41a49fA6edF6df6bFe8c0Ee" }
+
+        [preloaders]
+        mainnet = { client = "local", bc = "mainnet", encoder = "mainnet", signers = "env_signer" }
+
+        [actors.node]
+        mainnet_node = { client = "local", bc = "mainnet" }
+
+        [actors.estimator]
+        mainnet = { type = "evm", bc = "mainnet", encoder = "mainnet" }
+    "#;
+
+    #[test]
+    fn test_validate() {
+        let config: TopologyConfig = toml::from_str(VALID_CONFIG).unwrap();
+        assert_eq!(config.validate(), Ok(()));
+
+        let invalid_config = VALID_CONFIG
+            .replace(r#"mainnet_node = { client = "local""#, r#"mainnet_node = { client = "remote""#)
+            .replace(r#"type = "evm", bc = "mainnet", encoder = "mainnet""#, r#"type = "evm", bc = "mainnet", encoder = "base""#)
+            .replace("0x6E3b634eBd2EbBffb41a49fA6edF6df6bFe8c0Ee", "0x6E3b");
+        let config: TopologyConfig = toml::from_str(&invalid_config).unwrap();
+        let mut errors = config.validate().unwrap_err();
+        errors.sort();
+        assert_eq!(errors.len(), 3);
+        assert_eq!(errors[0], "actors.estimator.mainnet: encoder 'base' not found");
+        assert_eq!(errors[1], "actors.node.mainnet_node: client 'remote' not found");
+        assert!(errors[2].starts_with("encoders.mainnet: invalid address '0x6E3b'"));
+    }
+
+    #[test]
+    fn test_strategy_merger_config() {
+        let config: TopologyConfig = toml::from_str(VALID_CONFIG).unwrap();
+        assert_eq!(config.strategy.merger.profit_improvement_threshold_bps, 50);
+
+        let config: TopologyConfig =
+            toml::from_str(&format!("{VALID_CONFIG}\n[strategy.merger]\nprofit_improvement_threshold_bps = 100\n")).unwrap();
+        assert_eq!(config.strategy.merger.profit_improvement_threshold_bps, 100);
+    }
+
+    #[test]
+    fn test_dns_config() {
+        let config: TopologyConfig = toml::from_str(VALID_CONFIG).unwrap();
+        assert_eq!(config.dns.server, None);
+        assert_eq!(config.dns.timeout_ms, 10000);
+
+        let config: TopologyConfig =
+            toml::from_str(&format!("{VALID_CONFIG}\n[dns]\nserver = \"8.8.8.8:53\"\ntimeout_ms = 2000\ncache_ttl_secs = 60\n")).unwrap();
+        assert_eq!(config.dns.server.as_deref(), Some("8.8.8.8:53"));
+        assert_eq!(config.dns.timeout_ms, 2000);
+        assert_eq!(config.dns.cache_ttl_secs, 60);
+        assert_eq!(config.validate(), Ok(()));
+
+        let config: TopologyConfig = toml::from_str(&format!("{VALID_CONFIG}\n[dns]\nserver = \"dns.google\"\n")).unwrap();
+        assert_eq!(config.dns.cache_ttl_secs, 300);
+        let errors = config.validate().unwrap_err();
+        assert!(errors[0].starts_with("dns: invalid server 'dns.google'"));
+    }
+
+    #[test]
+    fn test_strategies_config() {
+        let config: TopologyConfig = toml::from_str(VALID_CONFIG).unwrap();
+        assert!(config.strategies.is_empty());
+
+        let strategies = r#"
+        [[strategies]]
+        name = "fast"
+        type = "backrun"
+        bc = "mainnet"
+        client = "local"
+        smart = true
+        max_concurrent_searches = 16
+
+        [[strategies]]
+        name = "slow"
+        type = "simple_arb"
+        bc = "mainnet"
+        max_path_length = 4
+
+        [[strategies]]
+        name = "mainnet"
+        type = "sandwich"
+        bc = "base"
+        "#;
+        let config: TopologyConfig = toml::from_str(&format!("{VALID_CONFIG}\n{strategies}")).unwrap();
+        assert_eq!(config.strategies.len(), 3);
+        assert!(matches!(&config.strategies[0], StrategyEntryConfig::Backrun(params) if params.config.smart()));
+        assert!(matches!(&config.strategies[1], StrategyEntryConfig::SimpleArb(params) if params.config.max_path_length == 4));
+
+        let mut errors = config.validate().unwrap_err();
+        errors.sort();
+        assert_eq!(errors, vec!["strategies.mainnet: blockchain 'base' not found", "strategies.mainnet: name is already used"]);
+    }
+
     #[test]
     fn test_load() {
         match TopologyConfig::load_from_file("../../config.toml".to_string()) {
@@ -0,0 +1,26 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+/// On-chain kill switch of the multicaller. Encoded swaps start with a static call of requireNotStopped, so every swap
+/// reverts once the operator has called stop().
+contract EmergencyStop {
+    address public immutable operator;
+    bool public isStopped;
+
+    error Stopped();
+    error NotOperator();
+
+    constructor(address operator_) {
+        operator = operator_;
+    }
+
+    /// Reverts when stopped, the multicaller static call cannot branch on the isStopped() result
+    function requireNotStopped() external view {
+        if (isStopped) revert Stopped();
+    }
+
+    function stop() external {
+        if (msg.sender != operator) revert NotOperator();
+        isStopped = true;
+    }
+}
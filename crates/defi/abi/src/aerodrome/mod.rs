@@ -0,0 +1,5 @@
+pub use pool::{IAerodromeCLPool, IAerodromePool};
+pub use quoter::IAerodromeCLQuoterV2;
+
+mod pool;
+mod quoter;
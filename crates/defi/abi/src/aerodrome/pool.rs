@@ -0,0 +1,49 @@
+use alloy::sol;
+
+sol! {
+    #[sol(abi = true, rpc)]
+    #[derive(Debug, PartialEq, Eq)]
+    interface IAerodromeCLPool {
+
+        function factory() external view returns (address);
+
+        function token0() external view returns (address);
+
+        function token1() external view returns (address);
+
+        function fee() external view returns (uint24);
+
+        function tickSpacing() external view returns (int24);
+
+        function liquidity() external view returns (uint128);
+
+        function slot0()
+            external
+            view
+            returns (
+                uint160 sqrtPriceX96,
+                int24 tick,
+                uint16 observationIndex,
+                uint16 observationCardinality,
+                uint16 observationCardinalityNext,
+                bool unlocked
+            );
+    }
+}
+
+sol! {
+    #[derive(Debug, PartialEq, Eq)]
+    interface IAerodromePool {
+        event Mint(address indexed sender, uint256 amount0, uint256 amount1);
+        event Burn(address indexed sender, address indexed to, uint256 amount0, uint256 amount1);
+        event Swap(
+            address indexed sender,
+            address indexed to,
+            uint256 amount0In,
+            uint256 amount1In,
+            uint256 amount0Out,
+            uint256 amount1Out
+        );
+        event Sync(uint256 reserve0, uint256 reserve1);
+    }
+}
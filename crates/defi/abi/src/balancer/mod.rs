@@ -1,3 +1,5 @@
+pub use pool::*;
 pub use vault::*;
 
+mod pool;
 mod vault;
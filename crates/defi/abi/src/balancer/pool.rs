@@ -0,0 +1,13 @@
+use alloy::sol;
+
+sol! {
+    #[derive(Debug, PartialEq, Eq)]
+    interface IBalancerPool {
+        function getPoolId() external view returns (bytes32);
+        function getVault() external view returns (address);
+        function getSwapFeePercentage() external view returns (uint256);
+        function getScalingFactors() external view returns (uint256[] memory);
+        function getNormalizedWeights() external view returns (uint256[] memory);
+        function getAmplificationParameter() external view returns (uint256 value, bool isUpdating, uint256 precision);
+    }
+}
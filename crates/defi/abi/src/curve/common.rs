@@ -35,3 +35,29 @@ sol! {
         function balances(int128) external view returns (uint256);
     }
 }
+
+sol! {
+    #[sol(abi = true, rpc)]
+    #[derive(Debug, PartialEq, Eq)]
+    interface ICurveRegistry {
+        function get_A(address) external view returns (uint256);
+        function get_rates(address) external view returns (uint256[8]);
+        function get_fees(address) external view returns (uint256[2]);
+        function get_n_coins(address) external view returns (uint256[2]);
+    }
+}
+
+sol! {
+    #[derive(Debug, PartialEq, Eq)]
+    interface ICurveStableSwapEvents {
+        event TokenExchange(address indexed buyer, int128 sold_id, uint256 tokens_sold, int128 bought_id, uint256 tokens_bought);
+        event TokenExchangeUnderlying(address indexed buyer, int128 sold_id, uint256 tokens_sold, int128 bought_id, uint256 tokens_bought);
+    }
+}
+
+sol! {
+    #[derive(Debug, PartialEq, Eq)]
+    interface ICurveCryptoSwapEvents {
+        event TokenExchange(address indexed buyer, uint256 sold_id, uint256 tokens_sold, uint256 bought_id, uint256 tokens_bought);
+    }
+}
@@ -0,0 +1,12 @@
+use alloy::sol;
+
+sol! {
+    #[sol(abi=true,rpc)]
+    #[derive(Debug, PartialEq, Eq)]
+    interface IEmergencyStop {
+        function isStopped() external view returns (bool);
+        // Reverts when stopped, equivalent of require(!isStopped()). Source in contracts/EmergencyStop.sol
+        function requireNotStopped() external view;
+        function stop() external;
+    }
+}
@@ -1,16 +1,27 @@
 pub use abi_helpers::AbiEncoderHelper;
+pub use chainlink::IAggregatorV3;
+pub use emergency_stop::IEmergencyStop;
 pub use erc20::IERC20;
+pub use erc4626::IERC4626;
+pub use multicall3::IMulticall3;
 pub use multicaller::IMultiCaller;
+pub use permit2::ISignatureTransfer;
 pub use weth::IWETH;
 
 mod abi_helpers;
 
+pub mod aerodrome;
 pub mod balancer;
+mod chainlink;
 pub mod curve;
+mod emergency_stop;
 mod erc20;
+mod erc4626;
 pub mod lido;
 pub mod maverick;
+mod multicall3;
 pub mod multicaller;
+mod permit2;
 pub mod uniswap2;
 pub mod uniswap3;
 pub mod uniswap4;
@@ -0,0 +1,20 @@
+use alloy::sol;
+
+sol! {
+    #[sol(abi=true, rpc)]
+    #[derive(Debug, PartialEq, Eq)]
+    interface IMulticall3 {
+        struct Call3 {
+            address target;
+            bool allowFailure;
+            bytes callData;
+        }
+
+        struct Call3Result {
+            bool success;
+            bytes returnData;
+        }
+
+        function aggregate3(Call3[] calldata calls) external payable returns (Call3Result[] memory returnData);
+    }
+}
@@ -4,6 +4,18 @@ sol! {
     #[sol(abi = true, rpc)]
     #[derive(Debug, PartialEq, Eq)]
     interface IPancakeV3Pool {
+        event Swap(
+            address indexed sender,
+            address indexed recipient,
+            int256 amount0,
+            int256 amount1,
+            uint160 sqrtPriceX96,
+            uint128 liquidity,
+            int24 tick,
+            uint128 protocolFeesToken0,
+            uint128 protocolFeesToken1
+        );
+
 
         function factory() external view returns (address);
 
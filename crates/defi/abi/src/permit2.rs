@@ -0,0 +1,30 @@
+use alloy::sol;
+
+sol! {
+    #[sol(abi=true,rpc)]
+    #[derive(Debug, PartialEq, Eq)]
+    interface ISignatureTransfer {
+        struct TokenPermissions {
+            address token;
+            uint256 amount;
+        }
+
+        struct PermitBatchTransferFrom {
+            TokenPermissions[] permitted;
+            uint256 nonce;
+            uint256 deadline;
+        }
+
+        struct SignatureTransferDetails {
+            address to;
+            uint256 requestedAmount;
+        }
+
+        function permitBatchTransferFrom(
+            PermitBatchTransferFrom memory permit,
+            SignatureTransferDetails[] calldata transferDetails,
+            address owner,
+            bytes calldata signature
+        ) external;
+    }
+}
@@ -333,6 +333,11 @@ sol! {
             /// @param newDynamicLPFee The new dynamic pool LP fee
             function updateDynamicLPFee(PoolKey memory key, uint24 newDynamicLPFee) external;
 
+            /// @notice Called by external contracts to access transient storage of the contract
+            /// @param slot Key of slot to tload
+            /// @return value The value of the slot as bytes32
+            function exttload(bytes32 slot) external view returns (bytes32 value);
+
     }
 
     #[derive(Debug, PartialEq, Eq)]
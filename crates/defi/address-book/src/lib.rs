@@ -5,6 +5,8 @@ pub struct TokenAddressEth;
 
 impl TokenAddressEth {
     pub const ETH_NATIVE: Address = Address::ZERO;
+    /// Native ETH placeholder used by aggregators and routers
+    pub const ETH_SENTINEL: Address = address!("EeeeeEeeeEeEeeEeEeEeeEEEeeeeEeeeeeeeEEeE");
     pub const WETH: Address = address!("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2");
     pub const USDC: Address = address!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48");
     pub const USDT: Address = address!("dac17f958d2ee523a2206206994597c13d831ec7");
@@ -22,6 +24,10 @@ impl TokenAddressEth {
     pub fn is_eth(&address: &Address) -> bool {
         address.eq(&Self::ETH_NATIVE)
     }
+    /// Native ETH as the zero address or the `ETH_SENTINEL` placeholder
+    pub fn is_eth_or_sentinel(&address: &Address) -> bool {
+        address.eq(&Self::ETH_NATIVE) || address.eq(&Self::ETH_SENTINEL)
+    }
 }
 
 #[non_exhaustive]
@@ -50,6 +56,10 @@ impl TokenAddressBase {
     pub const ETH_NATIVE: Address = Address::ZERO;
     pub const WETH: Address = address!("4200000000000000000000000000000000000006");
     pub const USDC: Address = address!("833589fCD6eDb6E08f4c7C32D4f71b54bdA02913");
+
+    pub fn is_weth(&address: &Address) -> bool {
+        address.eq(&Self::WETH)
+    }
 }
 
 #[non_exhaustive]
@@ -78,7 +88,13 @@ impl FactoryAddress {
 
     pub const MAVERICK_V2: Address = address!("0A7e848Aca42d879EF06507Fca0E7b33A0a63c1e");
 
+    // Aerodrome Slipstream CLFactory on Base
+    pub const AERODROME_CL: Address = address!("5e7BB104d84c7CB9B682AaC2F3d509f5F406809A");
+
     pub const UNISWAP_V4_POOL_MANAGER_ADDRESS: Address = address!("000000000004444c5dc75cB358380D2e3dE08A90");
+
+    // Balancer V2 Vault, same address on all chains
+    pub const BALANCER_V2_VAULT: Address = address!("BA12222222228d8Ba445958a75a0704d566BF2C8");
 }
 
 #[non_exhaustive]
@@ -89,6 +105,7 @@ impl PeripheryAddress {
     pub const UNISWAP_V2_ROUTER: Address = address!("7a250d5630b4cf539739df2c5dacb4c659f2488d");
     pub const UNISWAP_V3_QUOTER_V2: Address = address!("61ffe014ba17989e743c5f6cb21bf9697530b21e");
     pub const UNISWAP_V3_TICK_LENS: Address = address!("bfd8137f7d1516d3ea5ca83523914859ec47f573");
+    pub const MULTICALL3: Address = address!("cA11bde05977b3631167028862bE2a173976CA11");
     pub const PANCAKE_V3_QUOTER: Address = address!("b048bbc1ee6b733fffcfb9e9cef7375518e25997");
     pub const PANCAKE_V3_TICK_LENS: Address = address!("9a489505a00ce272eaa5e07dba6491314cae3796");
     pub const MAVERICK_QUOTER: Address = address!("9980ce3b5570e41324904f46a06ce7b466925e23");
@@ -96,6 +113,17 @@ impl PeripheryAddress {
     pub const UNISWAPV4_STATE_VIEW_ADDRESS: Address = address!("7fFE42C4a5DEeA5b0feC41C94C136Cf115597227");
     pub const MAVERICK_V2_QUOTER: Address = address!("b40AfdB85a07f37aE217E7D6462e609900dD8D7A");
     pub const MAVERICK_V2_TICK_LENS: Address = address!("6A9EB38DE5D349Fe751E0aDb4c0D9D391f94cc8D");
+    // Aerodrome Slipstream QuoterV2 on Base
+    pub const AERODROME_CL_QUOTER: Address = address!("254cF9E1E6e233aa1AC962CB9B05b2cfeAaE15b0");
+    pub const UNISWAP_V3_TICK_LENS_BASE: Address = address!("0CdeE061c75D43c82520eD998C23ac2991c9ac6d");
+
+    /// Uniswap V3 TickLens deployed on `chain_id`, the Ethereum one on unknown chains
+    pub fn uniswap_v3_tick_lens(chain_id: u64) -> Address {
+        match chain_id {
+            8453 => Self::UNISWAP_V3_TICK_LENS_BASE,
+            _ => Self::UNISWAP_V3_TICK_LENS,
+        }
+    }
 }
 
 #[non_exhaustive]
@@ -1,10 +1,22 @@
 mod pool_health_monitor;
+mod pool_health_monitor_config;
+mod profit_ledger_actor;
+mod simulation_differential;
+mod simulation_differential_config;
 mod state_health_monitor;
 mod stuffing_tx_monitor;
 
 mod metrics_recorder_actor;
+#[cfg(feature = "prometheus")]
+mod prometheus_metrics;
 
 pub use metrics_recorder_actor::MetricsRecorderActor;
 pub use pool_health_monitor::PoolHealthMonitorActor;
+pub use pool_health_monitor_config::PoolHealthMonitorConfig;
+pub use profit_ledger_actor::ProfitLedgerActor;
+#[cfg(feature = "prometheus")]
+pub use prometheus_metrics::PrometheusMetrics;
+pub use simulation_differential::SimulationDifferentialActor;
+pub use simulation_differential_config::SimulationDifferentialConfig;
 pub use state_health_monitor::StateHealthMonitorActor;
 pub use stuffing_tx_monitor::StuffingTxMonitorActor;
@@ -1,38 +1,53 @@
 use eyre::eyre;
 use influxdb::{Timestamp, WriteQuery};
 use loom_core_actors::Producer;
-use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, WorkerResult};
+use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, CancellationToken, WorkerResult};
 use loom_core_actors::{Accessor, Consumer, SharedState};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
+#[cfg(feature = "prometheus")]
+use loom_core_blockchain::Strategy;
 use loom_core_blockchain::{Blockchain, BlockchainState};
 use loom_evm_db::DatabaseLoomExt;
+#[cfg(feature = "prometheus")]
+use loom_types_entities::LatestBlock;
 use loom_types_entities::{Market, MarketState};
 use loom_types_events::MessageBlockHeader;
+#[cfg(feature = "prometheus")]
+use loom_types_events::{MarketEvents, MessageSwapCompose, MessageTxCompose};
 use revm::DatabaseRef;
 use std::time::Duration;
 use tikv_jemalloc_ctl::stats;
 use tokio::sync::broadcast::error::RecvError;
 use tracing::{error, info};
 
+#[cfg(feature = "prometheus")]
+use crate::prometheus_metrics::{prometheus_metrics_worker, PrometheusMetrics};
+
 async fn metrics_recorder_worker<DB: DatabaseLoomExt + DatabaseRef + Send + Sync + 'static>(
     market: SharedState<Market>,
     market_state: SharedState<MarketState<DB>>,
     block_header_update_rx: Broadcaster<MessageBlockHeader>,
     influx_channel_tx: Broadcaster<WriteQuery>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult {
     subscribe!(block_header_update_rx);
     loop {
-        let block_header = match block_header_update_rx.recv().await {
-            Ok(block) => block,
-            Err(e) => match e {
-                RecvError::Closed => {
-                    error!("Block header channel closed");
-                    return Err(eyre!("Block header channel closed".to_string()));
-                }
-                RecvError::Lagged(lag) => {
-                    info!("Block header channel lagged: {}", lag);
-                    continue;
-                }
+        let block_header = tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Metrics recorder cancelled".to_string());
+            }
+            msg = block_header_update_rx.recv() => match msg {
+                Ok(block) => block,
+                Err(e) => match e {
+                    RecvError::Closed => {
+                        error!("Block header channel closed");
+                        return Err(eyre!("Block header channel closed".to_string()));
+                    }
+                    RecvError::Lagged(lag) => {
+                        info!("Block header channel lagged: {}", lag);
+                        continue;
+                    }
+                },
             },
         };
 
@@ -131,6 +146,21 @@ pub struct MetricsRecorderActor<DB: Clone + Send + Sync + 'static> {
     block_header_rx: Option<Broadcaster<MessageBlockHeader>>,
     #[producer]
     influxdb_write_channel_tx: Option<Broadcaster<WriteQuery>>,
+    #[cfg(feature = "prometheus")]
+    #[accessor]
+    latest_block: Option<SharedState<LatestBlock>>,
+    #[cfg(feature = "prometheus")]
+    #[consumer]
+    market_events_rx: Option<Broadcaster<MarketEvents>>,
+    #[cfg(feature = "prometheus")]
+    #[consumer]
+    swap_compose_rx: Option<Broadcaster<MessageSwapCompose<DB>>>,
+    #[cfg(feature = "prometheus")]
+    #[consumer]
+    tx_compose_channel_rx: Option<Broadcaster<MessageTxCompose>>,
+    #[cfg(feature = "prometheus")]
+    prometheus_metrics: Option<PrometheusMetrics>,
+    cancellation_token: CancellationToken,
 }
 
 impl<DB> MetricsRecorderActor<DB>
@@ -138,7 +168,27 @@ where
     DB: DatabaseRef + DatabaseLoomExt + Clone + Send + Sync + 'static,
 {
     pub fn new() -> Self {
-        Self { market: None, market_state: None, block_header_rx: None, influxdb_write_channel_tx: None }
+        Self {
+            market: None,
+            market_state: None,
+            block_header_rx: None,
+            influxdb_write_channel_tx: None,
+            #[cfg(feature = "prometheus")]
+            latest_block: None,
+            #[cfg(feature = "prometheus")]
+            market_events_rx: None,
+            #[cfg(feature = "prometheus")]
+            swap_compose_rx: None,
+            #[cfg(feature = "prometheus")]
+            tx_compose_channel_rx: None,
+            #[cfg(feature = "prometheus")]
+            prometheus_metrics: None,
+            cancellation_token: CancellationToken::new(),
+        }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
     }
 
     pub fn on_bc(self, bc: &Blockchain, bc_state: &BlockchainState<DB>) -> Self {
@@ -147,8 +197,28 @@ where
             market_state: Some(bc_state.market_state()),
             block_header_rx: Some(bc.new_block_headers_channel()),
             influxdb_write_channel_tx: Some(bc.influxdb_write_channel()),
+            #[cfg(feature = "prometheus")]
+            latest_block: Some(bc.latest_block()),
+            #[cfg(feature = "prometheus")]
+            market_events_rx: Some(bc.market_events_channel()),
+            #[cfg(feature = "prometheus")]
+            tx_compose_channel_rx: Some(bc.tx_compose_channel()),
+            ..self
         }
     }
+
+    /// Count the swaps found by the strategy searchers
+    #[cfg(feature = "prometheus")]
+    pub fn on_strategy(self, strategy: &Strategy<DB>) -> Self {
+        Self { swap_compose_rx: Some(strategy.swap_compose_channel()), ..self }
+    }
+
+    /// Record block, opportunity, bundle and profit counters to `metrics`, export them with
+    /// `WebServerActor::with_prometheus_registry`
+    #[cfg(feature = "prometheus")]
+    pub fn with_prometheus(self, metrics: PrometheusMetrics) -> Self {
+        Self { prometheus_metrics: Some(metrics), ..self }
+    }
 }
 
 impl<DB> Actor for MetricsRecorderActor<DB>
@@ -156,13 +226,31 @@ where
     DB: DatabaseRef + DatabaseLoomExt + Clone + Send + Sync + 'static,
 {
     fn start(&self) -> ActorResult {
-        let task = tokio::task::spawn(metrics_recorder_worker(
-            self.market.clone().unwrap(),
-            self.market_state.clone().unwrap(),
-            self.block_header_rx.clone().unwrap(),
-            self.influxdb_write_channel_tx.clone().unwrap(),
-        ));
-        Ok(vec![task])
+        let mut tasks = Vec::new();
+        // InfluxDB is optional when the metrics are exported to Prometheus
+        if let Some(influxdb_write_channel_tx) = self.influxdb_write_channel_tx.clone() {
+            tasks.push(tokio::task::spawn(metrics_recorder_worker(
+                self.market.clone().unwrap(),
+                self.market_state.clone().unwrap(),
+                self.block_header_rx.clone().unwrap(),
+                influxdb_write_channel_tx,
+                self.cancellation_token.clone(),
+            )));
+        }
+        #[cfg(feature = "prometheus")]
+        if let Some(prometheus_metrics) = self.prometheus_metrics.clone() {
+            tasks.push(tokio::task::spawn(prometheus_metrics_worker(
+                prometheus_metrics,
+                self.market.clone().unwrap(),
+                self.latest_block.clone().unwrap(),
+                self.block_header_rx.clone().unwrap(),
+                self.market_events_rx.clone().unwrap(),
+                self.swap_compose_rx.clone().unwrap(),
+                self.tx_compose_channel_rx.clone().unwrap(),
+                self.cancellation_token.clone(),
+            )));
+        }
+        Ok(tasks)
     }
 
     fn name(&self) -> &'static str {
@@ -1,4 +1,4 @@
-use alloy_primitives::Address;
+use alloy_primitives::{Address, BlockNumber};
 use eyre::Result;
 use influxdb::{Timestamp, WriteQuery};
 use std::collections::{HashMap, HashSet};
@@ -7,12 +7,16 @@ use tokio::sync::broadcast::error::RecvError;
 use tracing::{debug, error, info};
 
 use lazy_static::lazy_static;
-use loom_core_actors::{subscribe, Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
+use loom_core_actors::{
+    subscribe, Accessor, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, Producer, SharedState, WorkerResult,
+};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
 use loom_core_blockchain::Blockchain;
 use loom_defi_address_book::TokenAddressEth;
 use loom_types_entities::{Market, PoolId, PoolProtocol};
-use loom_types_events::{HealthEvent, MessageHealthEvent};
+use loom_types_events::{HealthEvent, MarketEvents, MessageHealthEvent};
+
+use crate::PoolHealthMonitorConfig;
 
 lazy_static! {
     static ref TRUSTED_TOKENS: HashSet<Address> = HashSet::from_iter(vec![
@@ -27,18 +31,93 @@ lazy_static! {
     ]);
 }
 
+/// Swap error counters of pools, each counter halves every `decay_blocks` blocks
+struct PoolErrorCounters {
+    decay_blocks: u64,
+    // pool -> (counter, block of the last update)
+    counters: HashMap<PoolId, (f64, BlockNumber)>,
+}
+
+impl PoolErrorCounters {
+    fn new(decay_blocks: u64) -> Self {
+        Self { decay_blocks: decay_blocks.max(1), counters: HashMap::new() }
+    }
+
+    fn decay(&self, counter: f64, from_block: BlockNumber, to_block: BlockNumber) -> f64 {
+        counter * 0.5f64.powf(to_block.saturating_sub(from_block) as f64 / self.decay_blocks as f64)
+    }
+
+    /// Count a swap error of the pool, returns the decayed counter
+    fn add_error(&mut self, pool_id: PoolId, block_number: BlockNumber) -> f64 {
+        let counter = match self.counters.get(&pool_id) {
+            Some((counter, last_block)) => self.decay(*counter, *last_block, block_number) + 1.0,
+            None => 1.0,
+        };
+        self.counters.insert(pool_id, (counter, block_number));
+        counter
+    }
+
+    fn reset(&mut self, pool_id: &PoolId) {
+        self.counters.remove(pool_id);
+    }
+}
+
+/// Pool and the direction (token_from, token_to) of a failed swap
+type PoolDirection = (PoolId, Address, Address);
+
+/// Pool directions disabled at least `cooldown_blocks` blocks ago
+fn pools_to_enable(
+    disabled_pools: &HashMap<PoolDirection, BlockNumber>,
+    block_number: BlockNumber,
+    cooldown_blocks: u64,
+) -> Vec<PoolDirection> {
+    disabled_pools
+        .iter()
+        .filter(|(_, disabled_block)| block_number.saturating_sub(**disabled_block) >= cooldown_blocks)
+        .map(|(pool_direction, _)| *pool_direction)
+        .collect()
+}
+
 pub async fn pool_health_monitor_worker(
+    config: PoolHealthMonitorConfig,
     market: SharedState<Market>,
+    market_events_rx: Broadcaster<MarketEvents>,
     pool_health_monitor_rx: Broadcaster<MessageHealthEvent>,
     influx_channel_tx: Broadcaster<WriteQuery>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult {
+    subscribe!(market_events_rx);
     subscribe!(pool_health_monitor_rx);
 
-    let mut pool_errors_map: HashMap<PoolId, u32> = HashMap::new();
+    let mut block_number: BlockNumber = 0;
+    let mut pool_error_counters = PoolErrorCounters::new(config.decay_blocks);
+    // pool direction -> block the direction was disabled at
+    let mut disabled_pools: HashMap<PoolDirection, BlockNumber> = HashMap::new();
     //let mut estimate_errors_map: HashMap<u64, u32> = HashMap::new();
 
     loop {
         tokio::select! {
+                    _ = cancellation_token.cancelled() => {
+                        break Ok("Pool health monitor cancelled".to_string());
+                    }
+                    msg = market_events_rx.recv() => {
+                        if let Ok(MarketEvents::BlockHeaderUpdate { block_number: header_block_number, .. }) = msg {
+                            block_number = header_block_number;
+
+                            let enable_vec = pools_to_enable(&disabled_pools, block_number, config.cooldown_blocks);
+                            if !enable_vec.is_empty() {
+                                let mut market_guard = market.write().await;
+                                for pool_direction in enable_vec {
+                                    let (pool_id, token_from, token_to) = pool_direction;
+                                    disabled_pools.remove(&pool_direction);
+                                    pool_error_counters.reset(&pool_id);
+                                    market_guard.set_pool_disabled(pool_id, token_from, token_to, false);
+                                    market_guard.set_pool_reliability(pool_id, 1.0);
+                                    info!(%pool_id, %token_from, %token_to, block_number, "Enabling pool after cooldown");
+                                }
+                            }
+                        }
+                    }
                     msg = pool_health_monitor_rx.recv() => {
 
                         let pool_health_update : Result<MessageHealthEvent, RecvError>  = msg;
@@ -121,64 +200,47 @@ pub async fn pool_health_monitor_worker(
                                     }
                                     HealthEvent::PoolSwapError(swap_error)=>{
                                         debug!("Pool health_monitor message update: {:?} {} {} ", swap_error.pool, swap_error.msg, swap_error.amount);
-                                        let entry = pool_errors_map.entry(swap_error.pool).or_insert(0);
-                                        *entry += 1;
-                                        if *entry >= 10 {
+                                        let counter = pool_error_counters.add_error(swap_error.pool, block_number);
+                                        let pool_direction = (swap_error.pool, swap_error.token_from, swap_error.token_to);
+
+                                        // The market is locked only to disable the failing direction of the pool
+                                        if counter > config.error_threshold as f64 && !disabled_pools.contains_key(&pool_direction) {
                                             let start_time=std::time::Instant::now();
                                             let mut market_guard = market.write().await;
                                             debug!(elapsed = start_time.elapsed().as_micros(), "market_guard market.write acquired");
-
-                                            //if !market_guard.is_pool_disabled(&swap_error.pool) {
-                                                market_guard.set_pool_disabled(swap_error.pool, swap_error.token_from, swap_error.token_to, true);
-
-
-                                                match market_guard.get_pool(&swap_error.pool) {
-                                                    Some(pool)=>{
-                                                        info!("Disabling pool: protocol={}, address={:?}, msg={} amount={}", pool.get_protocol(),swap_error.pool, swap_error.msg, swap_error.amount);
-
-                                                        let amount_f64 = if let Some(token_in) = market_guard.get_token(&swap_error.token_from) {
-                                                            token_in.to_float(swap_error.amount)
-                                                        } else {
-                                                            -1.0f64
-                                                        };
-
-                                                        let pool_protocol = pool.get_protocol().to_string();
-                                                        let pool_id = pool.get_pool_id().to_string();
-                                                        let influx_channel_clone = influx_channel_tx.clone();
-
-                                                        if let Err(e) = tokio::time::timeout(
-                                                            Duration::from_secs(1),
-                                                            async move {
-                                                                let start_time_utc =   chrono::Utc::now();
-
-                                                                let write_query = WriteQuery::new(Timestamp::from(start_time_utc), "pool_disabled")
-                                                                    .add_field("message", swap_error.msg)
-                                                                    .add_field("amount", amount_f64)
-                                                                    .add_tag("id", pool_id)
-                                                                    .add_tag("protocol", pool_protocol)
-                                                                    .add_tag("token_from", swap_error.token_from.to_checksum(None))
-                                                                    .add_tag("token_to", swap_error.token_to.to_checksum(None));
-
-                                                                if let Err(e) = influx_channel_clone.send(write_query) {
-                                                                   error!("Failed to failed pool to influxdb: {:?}", e);
-                                                                }
-                                                            }
-                                                        ).await {
-                                                            error!("Failed to send failed pool info to influxdb: {:?}", e);
-                                                        }
-
-
-
-                                                    }
-                                                    _=>{
-                                                        error!("Disabled pool missing in market: address={:?}, msg={} amount={}", swap_error.pool, swap_error.msg, swap_error.amount);
+                                            market_guard.set_pool_reliability(swap_error.pool, 0.0);
+                                            market_guard.set_pool_disabled(swap_error.pool, swap_error.token_from, swap_error.token_to, true);
+                                            disabled_pools.insert(pool_direction, block_number);
+
+                                            match market_guard.get_pool(&swap_error.pool) {
+                                                Some(pool)=>{
+                                                    info!("Disabling pool: protocol={}, address={:?}, msg={} amount={} errors={:.2}", pool.get_protocol(),swap_error.pool, swap_error.msg, swap_error.amount, counter);
+
+                                                    let amount_f64 = if let Some(token_in) = market_guard.get_token(&swap_error.token_from) {
+                                                        token_in.to_float(swap_error.amount)
+                                                    } else {
+                                                        -1.0f64
+                                                    };
+
+                                                    let write_query = WriteQuery::new(Timestamp::from(chrono::Utc::now()), "pool_disabled")
+                                                        .add_field("message", swap_error.msg)
+                                                        .add_field("amount", amount_f64)
+                                                        .add_tag("id", pool.get_pool_id().to_string())
+                                                        .add_tag("protocol", pool.get_protocol().to_string())
+                                                        .add_tag("token_from", swap_error.token_from.to_checksum(None))
+                                                        .add_tag("token_to", swap_error.token_to.to_checksum(None));
+
+                                                    if let Err(e) = influx_channel_tx.send(write_query) {
+                                                       error!("Failed to send failed pool info to influxdb: {:?}", e);
                                                     }
                                                 }
-                                            //}
+                                                _=>{
+                                                    error!("Disabled pool missing in market: address={:?}, msg={} amount={}", swap_error.pool, swap_error.msg, swap_error.amount);
+                                                }
+                                            }
 
                                             drop(market_guard);
                                             debug!(elapsed = start_time.elapsed().as_micros(), "market_guard market.write released");
-
                                         }
                                     }
                                     _=>{}
@@ -196,12 +258,16 @@ pub async fn pool_health_monitor_worker(
 
 #[derive(Accessor, Consumer, Producer, Default)]
 pub struct PoolHealthMonitorActor {
+    config: PoolHealthMonitorConfig,
     #[accessor]
     market: Option<SharedState<Market>>,
     #[consumer]
+    market_events_rx: Option<Broadcaster<MarketEvents>>,
+    #[consumer]
     pool_health_update_rx: Option<Broadcaster<MessageHealthEvent>>,
     #[producer]
     influxdb_tx: Option<Broadcaster<WriteQuery>>,
+    cancellation_token: CancellationToken,
 }
 
 impl PoolHealthMonitorActor {
@@ -209,11 +275,21 @@ impl PoolHealthMonitorActor {
         PoolHealthMonitorActor::default()
     }
 
+    pub fn with_config(self, config: PoolHealthMonitorConfig) -> Self {
+        Self { config, ..self }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
     pub fn on_bc(self, bc: &Blockchain) -> Self {
         Self {
             market: Some(bc.market()),
+            market_events_rx: Some(bc.market_events_channel()),
             pool_health_update_rx: Some(bc.health_monitor_channel()),
             influxdb_tx: Some(bc.influxdb_write_channel()),
+            ..self
         }
     }
 }
@@ -221,9 +297,12 @@ impl PoolHealthMonitorActor {
 impl Actor for PoolHealthMonitorActor {
     fn start(&self) -> ActorResult {
         let task = tokio::task::spawn(pool_health_monitor_worker(
+            self.config.clone(),
             self.market.clone().unwrap(),
+            self.market_events_rx.clone().unwrap(),
             self.pool_health_update_rx.clone().unwrap(),
             self.influxdb_tx.clone().unwrap(),
+            self.cancellation_token.clone(),
         ));
         Ok(vec![task])
     }
@@ -232,3 +311,35 @@ impl Actor for PoolHealthMonitorActor {
         "PoolHealthMonitorActor"
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pool_error_counters_decay() {
+        let pool_id = PoolId::Address(Address::repeat_byte(1));
+        let mut counters = PoolErrorCounters::new(100);
+
+        assert_eq!(counters.add_error(pool_id, 1000), 1.0);
+        assert_eq!(counters.add_error(pool_id, 1000), 2.0);
+        // Halved after decay_blocks
+        assert_eq!(counters.add_error(pool_id, 1100), 2.0);
+        assert_eq!(counters.add_error(pool_id, 1300), 1.5);
+
+        counters.reset(&pool_id);
+        assert_eq!(counters.add_error(pool_id, 1300), 1.0);
+    }
+
+    #[test]
+    fn test_pools_to_enable() {
+        let (token_from, token_to) = (Address::repeat_byte(3), Address::repeat_byte(4));
+        let pool_a = (PoolId::Address(Address::repeat_byte(1)), token_from, token_to);
+        let pool_b = (PoolId::Address(Address::repeat_byte(2)), token_to, token_from);
+        let disabled_pools = HashMap::from([(pool_a, 100), (pool_b, 150)]);
+
+        assert!(pools_to_enable(&disabled_pools, 120, 50).is_empty());
+        assert_eq!(pools_to_enable(&disabled_pools, 160, 50), vec![pool_a]);
+        assert_eq!(pools_to_enable(&disabled_pools, 200, 50).len(), 2);
+    }
+}
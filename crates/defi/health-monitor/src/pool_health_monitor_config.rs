@@ -0,0 +1,18 @@
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize, Debug)]
+#[serde(default)]
+pub struct PoolHealthMonitorConfig {
+    /// Pool is disabled when its decayed swap error counter exceeds this threshold
+    pub error_threshold: u32,
+    /// Swap error counters halve every `decay_blocks` blocks
+    pub decay_blocks: u64,
+    /// Disabled pools are enabled again after this number of blocks
+    pub cooldown_blocks: u64,
+}
+
+impl Default for PoolHealthMonitorConfig {
+    fn default() -> Self {
+        Self { error_threshold: 10, decay_blocks: 100, cooldown_blocks: 1800 }
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+
+use alloy_network::Ethereum;
+use alloy_primitives::{hex, keccak256, Address, TxHash, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::Log;
+use alloy_sol_types::SolEvent;
+use chrono::Utc;
+use eyre::{eyre, OptionExt, Result};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, error, info};
+
+use loom_core_actors::{subscribe, Accessor, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, SharedState, WorkerResult};
+use loom_core_actors_macros::{Accessor, Consumer};
+use loom_core_blockchain::Blockchain;
+use loom_defi_abi::{IERC20, IWETH};
+use loom_defi_address_book::TokenAddressEth;
+use loom_evm_utils::NWETH;
+use loom_storage_db::{insert_pnl_entry, DbPool, PnlLedgerEntry};
+use loom_types_entities::{Market, Swap};
+use loom_types_events::{MarketEvents, MessageTxCompose, RlpState, TxComposeMessageType};
+
+/// Own backrun transaction broadcast for `block`
+#[derive(Clone, Debug)]
+struct PendingLedgerTx {
+    block: u64,
+    strategy: String,
+    swap: Swap,
+}
+
+/// Amounts of `token` received and sent by `account` in transaction logs. WETH unwrapped by `account` is counted as sent.
+fn token_flows(logs: &[Log], token: Address, account: Address) -> (U256, U256) {
+    let mut received = U256::ZERO;
+    let mut sent = U256::ZERO;
+    for log in logs.iter().filter(|log| log.address() == token) {
+        let Some(topic0) = log.topic0() else {
+            continue;
+        };
+        if *topic0 == IERC20::Transfer::SIGNATURE_HASH {
+            if let Ok(event) = IERC20::Transfer::decode_log(&log.inner, false) {
+                if event.to == account {
+                    received += event.value;
+                }
+                if event.from == account {
+                    sent += event.value;
+                }
+            }
+        } else if *topic0 == IWETH::Withdrawal::SIGNATURE_HASH && token == TokenAddressEth::WETH {
+            if let Ok(event) = IWETH::Withdrawal::decode_log(&log.inner, false) {
+                if event.src == account {
+                    sent += event.wad;
+                }
+            }
+        }
+    }
+    (received, sent)
+}
+
+/// Fetches the receipt of a broadcast transaction and writes its realized profit to the ledger. Transactions not landed are skipped.
+async fn record_ledger_entry<P: Provider<Ethereum> + 'static>(
+    client: P,
+    market: SharedState<Market>,
+    db_pool: DbPool,
+    tx_hash: TxHash,
+    pending_tx: PendingLedgerTx,
+) -> Result<()> {
+    let Some(receipt) = client.get_transaction_receipt(tx_hash).await? else {
+        debug!(%tx_hash, block = pending_tx.block, "Transaction not landed");
+        return Ok(());
+    };
+
+    let multicaller = receipt.to.ok_or_eyre("TX_TO_NOT_SET")?;
+    let token_in = pending_tx.swap.get_first_token().ok_or_eyre("TOKEN_IN_NOT_FOUND")?;
+    let (received, sent) = token_flows(receipt.inner.logs(), token_in.get_address(), multicaller);
+
+    let received_eth = token_in.calc_eth_value(received).ok_or_eyre("TOKEN_ETH_PRICE_NOT_SET")?;
+    let sent_eth = token_in.calc_eth_value(sent).ok_or_eyre("TOKEN_ETH_PRICE_NOT_SET")?;
+    let gas_cost = NWETH::to_float(U256::from(receipt.gas_used) * U256::from(receipt.effective_gas_price));
+    let profit_eth = NWETH::to_float(received_eth) - NWETH::to_float(sent_eth) - gas_cost;
+
+    let eth_usd = market
+        .read()
+        .await
+        .get_token(&TokenAddressEth::USDC)
+        .and_then(|usdc| usdc.calc_token_value_from_eth(NWETH::from_float(1.0)).map(|value| usdc.to_float(value)));
+
+    let entry = PnlLedgerEntry {
+        timestamp: Utc::now(),
+        strategy: pending_tx.strategy,
+        tx_hash: tx_hash.to_string(),
+        path: pending_tx.swap.get_pool_address_vec().iter().map(|address| address.to_string()).collect::<Vec<_>>().join(","),
+        profit_eth,
+        profit_usd: eth_usd.map(|eth_usd| profit_eth * eth_usd),
+        gas_cost,
+        block_number: receipt.block_number.unwrap_or(pending_tx.block) as i64,
+        path_fingerprint: pending_tx.swap.path_fingerprint().map(hex::encode_prefixed),
+    };
+    info!(%tx_hash, block_number = entry.block_number, strategy = entry.strategy, profit_eth, gas_cost, "Realized profit");
+
+    insert_pnl_entry(&db_pool, &entry).await?;
+    Ok(())
+}
+
+pub async fn profit_ledger_worker<P: Provider<Ethereum> + Clone + 'static>(
+    client: P,
+    market: SharedState<Market>,
+    db_pool: DbPool,
+    tx_compose_channel_rx: Broadcaster<MessageTxCompose>,
+    market_events_rx: Broadcaster<MarketEvents>,
+    cancellation_token: CancellationToken,
+) -> WorkerResult {
+    subscribe!(tx_compose_channel_rx);
+    subscribe!(market_events_rx);
+
+    let mut pending_txs: HashMap<TxHash, PendingLedgerTx> = HashMap::new();
+
+    loop {
+        tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Profit ledger cancelled".to_string());
+            }
+            msg = market_events_rx.recv() => {
+                let market_event_msg: Result<MarketEvents, RecvError> = msg;
+                match market_event_msg {
+                    Ok(MarketEvents::BlockHeaderUpdate { block_number, .. }) => {
+                        // Bundles are sent for a single block, receipts of transactions sent for this block are final
+                        let tx_hashes: Vec<TxHash> =
+                            pending_txs.iter().filter(|(_, pending_tx)| pending_tx.block <= block_number).map(|(tx_hash, _)| *tx_hash).collect();
+                        for tx_hash in tx_hashes {
+                            let Some(pending_tx) = pending_txs.remove(&tx_hash) else {
+                                continue;
+                            };
+                            let client_clone = client.clone();
+                            let market_clone = market.clone();
+                            let db_pool_clone = db_pool.clone();
+                            tokio::task::spawn(async move {
+                                if let Err(e) = record_ledger_entry(client_clone, market_clone, db_pool_clone, tx_hash, pending_tx).await {
+                                    error!("record_ledger_entry {:?} : {}", tx_hash, e);
+                                }
+                            });
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Closed) => {
+                        error!("Market events channel closed");
+                        break Err(eyre!("MARKET_EVENTS_RX_CLOSED"));
+                    }
+                    Err(RecvError::Lagged(lag)) => {
+                        error!("Market events channel lagged by {} messages", lag);
+                    }
+                }
+            }
+            msg = tx_compose_channel_rx.recv() => {
+                let tx_compose_msg: Result<MessageTxCompose, RecvError> = msg;
+                match tx_compose_msg {
+                    Ok(tx_compose_msg) => {
+                        let TxComposeMessageType::Broadcast(tx_compose_data) = tx_compose_msg.inner else {
+                            continue;
+                        };
+                        let Some(swap) = tx_compose_data.swap else {
+                            continue;
+                        };
+                        for rlp_state in tx_compose_data.rlp_bundle.iter().flatten() {
+                            if let RlpState::Backrun(rlp) = rlp_state {
+                                let pending_tx = PendingLedgerTx {
+                                    block: tx_compose_data.next_block_number,
+                                    strategy: tx_compose_data.origin.clone().unwrap_or("unknown".to_string()),
+                                    swap: swap.clone(),
+                                };
+                                pending_txs.insert(keccak256(rlp), pending_tx);
+                            }
+                        }
+                    }
+                    Err(RecvError::Closed) => {
+                        error!("Tx compose channel closed");
+                        break Err(eyre!("TX_COMPOSE_RX_CLOSED"));
+                    }
+                    Err(RecvError::Lagged(lag)) => {
+                        error!("Tx compose channel lagged by {} messages", lag);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Records realized profit of own landed backrun transactions to the `pnl_ledger` table
+#[derive(Accessor, Consumer)]
+pub struct ProfitLedgerActor<P> {
+    client: P,
+    db_pool: DbPool,
+    #[accessor]
+    market: Option<SharedState<Market>>,
+    #[consumer]
+    tx_compose_channel_rx: Option<Broadcaster<MessageTxCompose>>,
+    #[consumer]
+    market_events_rx: Option<Broadcaster<MarketEvents>>,
+    cancellation_token: CancellationToken,
+}
+
+impl<P: Provider<Ethereum> + Send + Sync + Clone + 'static> ProfitLedgerActor<P> {
+    pub fn new(client: P, db_pool: DbPool) -> Self {
+        ProfitLedgerActor {
+            client,
+            db_pool,
+            market: None,
+            tx_compose_channel_rx: None,
+            market_events_rx: None,
+            cancellation_token: CancellationToken::new(),
+        }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
+    pub fn on_bc(self, bc: &Blockchain) -> Self {
+        Self {
+            market: Some(bc.market()),
+            tx_compose_channel_rx: Some(bc.tx_compose_channel()),
+            market_events_rx: Some(bc.market_events_channel()),
+            ..self
+        }
+    }
+}
+
+impl<P> Actor for ProfitLedgerActor<P>
+where
+    P: Provider<Ethereum> + Send + Sync + Clone + 'static,
+{
+    fn start(&self) -> ActorResult {
+        let task = tokio::task::spawn(profit_ledger_worker(
+            self.client.clone(),
+            self.market.clone().unwrap(),
+            self.db_pool.clone(),
+            self.tx_compose_channel_rx.clone().unwrap(),
+            self.market_events_rx.clone().unwrap(),
+            self.cancellation_token.clone(),
+        ));
+        Ok(vec![task])
+    }
+
+    fn name(&self) -> &'static str {
+        "ProfitLedgerActor"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn log<E: SolEvent>(address: Address, event: &E) -> Log {
+        Log { inner: alloy_primitives::Log { address, data: event.encode_log_data() }, ..Log::default() }
+    }
+
+    #[test]
+    fn test_token_flows() {
+        let multicaller = Address::repeat_byte(0x01);
+        let pool = Address::repeat_byte(0x02);
+        let weth = TokenAddressEth::WETH;
+
+        let logs = vec![
+            log(weth, &IERC20::Transfer { from: multicaller, to: pool, value: U256::from(100) }),
+            log(weth, &IERC20::Transfer { from: pool, to: multicaller, value: U256::from(130) }),
+            log(weth, &IWETH::Withdrawal { src: multicaller, wad: U256::from(20) }),
+            // Other token and other accounts are ignored
+            log(TokenAddressEth::USDC, &IERC20::Transfer { from: pool, to: multicaller, value: U256::from(1000) }),
+            log(weth, &IERC20::Transfer { from: pool, to: Address::repeat_byte(0x03), value: U256::from(1000) }),
+        ];
+
+        assert_eq!(token_flows(&logs, weth, multicaller), (U256::from(130), U256::from(120)));
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+
+use alloy_primitives::{keccak256, TxHash};
+use eyre::{eyre, Result};
+use loom_core_actors::{subscribe, Broadcaster, CancellationToken, SharedState, WorkerResult};
+use loom_evm_utils::NWETH;
+use loom_types_entities::{LatestBlock, Market};
+use loom_types_events::{
+    MarketEvents, MessageBlockHeader, MessageSwapCompose, MessageTxCompose, RlpState, SwapComposeMessage, TxComposeMessageType,
+};
+use prometheus::{Counter, IntCounter, IntGauge, Registry, TextEncoder};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, error};
+
+/// Loom counters in a Prometheus registry, clones share the same metrics
+#[derive(Clone)]
+pub struct PrometheusMetrics {
+    registry: Registry,
+    blocks_processed: IntCounter,
+    arb_opportunities_found: IntCounter,
+    bundles_submitted: IntCounter,
+    bundles_landed: IntCounter,
+    profit_eth: Counter,
+    market_pool_count: IntGauge,
+}
+
+impl PrometheusMetrics {
+    pub fn new() -> Result<Self> {
+        let registry = Registry::new();
+        let blocks_processed = IntCounter::new("loom_blocks_processed_total", "Block headers processed")?;
+        let arb_opportunities_found = IntCounter::new("loom_arb_opportunities_found_total", "Swaps found by the searchers")?;
+        let bundles_submitted = IntCounter::new("loom_bundles_submitted_total", "Bundles sent for broadcast")?;
+        let bundles_landed = IntCounter::new("loom_bundles_landed_total", "Bundles with the backrun transaction included in a block")?;
+        let profit_eth = Counter::new("loom_profit_eth_total", "Estimated profit of the landed bundles in ETH")?;
+        let market_pool_count = IntGauge::new("loom_market_pool_count", "Pools in the market")?;
+
+        registry.register(Box::new(blocks_processed.clone()))?;
+        registry.register(Box::new(arb_opportunities_found.clone()))?;
+        registry.register(Box::new(bundles_submitted.clone()))?;
+        registry.register(Box::new(bundles_landed.clone()))?;
+        registry.register(Box::new(profit_eth.clone()))?;
+        registry.register(Box::new(market_pool_count.clone()))?;
+
+        Ok(Self { registry, blocks_processed, arb_opportunities_found, bundles_submitted, bundles_landed, profit_eth, market_pool_count })
+    }
+
+    /// Registry to export, for `WebServerActor::with_prometheus_registry`
+    pub fn registry(&self) -> Registry {
+        self.registry.clone()
+    }
+
+    /// Metrics in the Prometheus text format
+    pub fn encode(&self) -> Result<String> {
+        Ok(TextEncoder::new().encode_to_string(&self.registry.gather())?)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn prometheus_metrics_worker<DB: Clone + Send + Sync + 'static>(
+    metrics: PrometheusMetrics,
+    market: SharedState<Market>,
+    latest_block: SharedState<LatestBlock>,
+    block_header_rx: Broadcaster<MessageBlockHeader>,
+    market_events_rx: Broadcaster<MarketEvents>,
+    swap_compose_rx: Broadcaster<MessageSwapCompose<DB>>,
+    tx_compose_channel_rx: Broadcaster<MessageTxCompose>,
+    cancellation_token: CancellationToken,
+) -> WorkerResult {
+    subscribe!(block_header_rx);
+    subscribe!(market_events_rx);
+    subscribe!(swap_compose_rx);
+    subscribe!(tx_compose_channel_rx);
+
+    // backrun tx hash -> (block the bundle was sent for, estimated profit in ETH)
+    let mut pending_txs: HashMap<TxHash, (u64, f64)> = HashMap::new();
+
+    loop {
+        tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Prometheus metrics cancelled".to_string());
+            }
+            msg = block_header_rx.recv() => {
+                match msg {
+                    Ok(_) => {
+                        metrics.blocks_processed.inc();
+                        metrics.market_pool_count.set(market.read().await.pools().len() as i64);
+                    }
+                    Err(RecvError::Closed) => {
+                        error!("Block header channel closed");
+                        break Err(eyre!("BLOCK_HEADER_RX_CLOSED"));
+                    }
+                    Err(RecvError::Lagged(lag)) => {
+                        error!("Block header channel lagged by {} messages", lag);
+                    }
+                }
+            }
+            msg = market_events_rx.recv() => {
+                let market_event_msg: Result<MarketEvents, RecvError> = msg;
+                match market_event_msg {
+                    Ok(MarketEvents::BlockTxUpdate { block_number, .. }) => {
+                        if let Some(txs) = latest_block.read().await.txs() {
+                            for tx in txs.iter() {
+                                if let Some((_, profit_eth)) = pending_txs.remove(tx.inner.tx_hash()) {
+                                    debug!(tx_hash = %tx.inner.tx_hash(), block_number, "Bundle landed");
+                                    metrics.bundles_landed.inc();
+                                    metrics.profit_eth.inc_by(profit_eth.max(0.0));
+                                }
+                            }
+                        }
+                        // Bundles are sent for a single block
+                        pending_txs.retain(|_, (block, _)| *block > block_number);
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Closed) => {
+                        error!("Market events channel closed");
+                        break Err(eyre!("MARKET_EVENTS_RX_CLOSED"));
+                    }
+                    Err(RecvError::Lagged(lag)) => {
+                        error!("Market events channel lagged by {} messages", lag);
+                    }
+                }
+            }
+            msg = swap_compose_rx.recv() => {
+                match msg {
+                    Ok(swap_compose) => {
+                        if let SwapComposeMessage::Prepare(_) = swap_compose.inner {
+                            metrics.arb_opportunities_found.inc();
+                        }
+                    }
+                    Err(RecvError::Closed) => {
+                        error!("Swap compose channel closed");
+                        break Err(eyre!("SWAP_COMPOSE_RX_CLOSED"));
+                    }
+                    Err(RecvError::Lagged(lag)) => {
+                        error!("Swap compose channel lagged by {} messages", lag);
+                    }
+                }
+            }
+            msg = tx_compose_channel_rx.recv() => {
+                let tx_compose_msg: Result<MessageTxCompose, RecvError> = msg;
+                match tx_compose_msg {
+                    Ok(tx_compose_msg) => {
+                        let TxComposeMessageType::Broadcast(tx_compose_data) = tx_compose_msg.inner else {
+                            continue;
+                        };
+                        metrics.bundles_submitted.inc();
+                        let profit_eth =
+                            tx_compose_data.swap.as_ref().map(|swap| NWETH::to_float(swap.abs_profit_eth())).unwrap_or_default();
+                        for rlp_state in tx_compose_data.rlp_bundle.iter().flatten() {
+                            if let RlpState::Backrun(rlp) = rlp_state {
+                                pending_txs.insert(keccak256(rlp), (tx_compose_data.next_block_number, profit_eth));
+                            }
+                        }
+                    }
+                    Err(RecvError::Closed) => {
+                        error!("Tx compose channel closed");
+                        break Err(eyre!("TX_COMPOSE_RX_CLOSED"));
+                    }
+                    Err(RecvError::Lagged(lag)) => {
+                        error!("Tx compose channel lagged by {} messages", lag);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_prometheus_metrics_encode() {
+        let metrics = PrometheusMetrics::new().unwrap();
+        metrics.blocks_processed.inc();
+        metrics.profit_eth.inc_by(0.25);
+        metrics.market_pool_count.set(42);
+
+        let exported = metrics.encode().unwrap();
+        assert!(exported.contains("loom_blocks_processed_total 1\n"));
+        assert!(exported.contains("loom_bundles_landed_total 0\n"));
+        assert!(exported.contains("loom_profit_eth_total 0.25\n"));
+        assert!(exported.contains("loom_market_pool_count 42\n"));
+        assert!(exported.contains("# TYPE loom_market_pool_count gauge\n"));
+    }
+}
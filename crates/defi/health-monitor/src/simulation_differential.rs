@@ -0,0 +1,187 @@
+use alloy_primitives::{TxHash, U256};
+use revm::DatabaseRef;
+use std::collections::HashMap;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{error, warn};
+
+use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, Producer, WorkerResult};
+use loom_core_actors_macros::{Consumer, Producer};
+use loom_core_blockchain::{Blockchain, Strategy};
+use loom_evm_utils::NWETH;
+use loom_types_entities::Swap;
+use loom_types_events::{
+    HealthEvent, Message, MessageHealthEvent, MessageSwapCompose, SwapComposeMessage, EVM_ESTIMATOR_SOURCE, GETH_ESTIMATOR_SOURCE,
+};
+
+use crate::SimulationDifferentialConfig;
+
+#[derive(Clone, Copy, Debug, Default)]
+struct EstimatorProfits {
+    evm: Option<U256>,
+    geth: Option<U256>,
+}
+
+/// Relative difference of two profits in basis points of the larger one
+fn profit_delta_bps(evm_profit: U256, geth_profit: U256) -> u64 {
+    let max_profit = evm_profit.max(geth_profit);
+    if max_profit.is_zero() {
+        return 0;
+    }
+    let delta = evm_profit.abs_diff(geth_profit);
+    (delta * U256::from(10000) / max_profit).to::<u64>()
+}
+
+async fn simulation_differential_worker<DB: DatabaseRef + Send + Sync + Clone + 'static>(
+    swap_compose_channel_rx: Broadcaster<MessageSwapCompose<DB>>,
+    health_monitor_channel_tx: Broadcaster<MessageHealthEvent>,
+    config: SimulationDifferentialConfig,
+    cancellation_token: CancellationToken,
+) -> WorkerResult {
+    subscribe!(swap_compose_channel_rx);
+
+    // (block, path hash, first stuffing tx) -> profits realized in the simulation of each estimator
+    let mut pending: HashMap<(u64, u64, TxHash), EstimatorProfits> = HashMap::new();
+    let mut current_block: u64 = 0;
+
+    loop {
+        let msg: Result<MessageSwapCompose<DB>, RecvError> = tokio::select! {
+            _ = cancellation_token.cancelled() => break,
+            msg = swap_compose_channel_rx.recv() => msg,
+        };
+        let msg = match msg {
+            Ok(msg) => msg,
+            Err(RecvError::Lagged(lag)) => {
+                warn!("SimulationDifferential lagged {} messages", lag);
+                continue;
+            }
+            Err(e) => {
+                error!("swap_compose_channel_rx error {}", e);
+                break;
+            }
+        };
+
+        let source = msg.source();
+        let SwapComposeMessage::Ready(estimate) = msg.inner else {
+            continue;
+        };
+        let Swap::BackrunSwapLine(swap_line) = &estimate.swap else {
+            continue;
+        };
+
+        let block = estimate.tx_compose.next_block_number;
+        if block > current_block {
+            current_block = block;
+            pending.retain(|(entry_block, _, _), _| *entry_block >= current_block);
+        }
+
+        let path_hash = swap_line.path.get_hash();
+        let key = (block, path_hash, estimate.first_stuffing_hash());
+        let Some(profit) = estimate.simulated_profit_eth else {
+            continue;
+        };
+
+        let entry = pending.entry(key).or_default();
+        match source.as_str() {
+            EVM_ESTIMATOR_SOURCE => entry.evm = Some(profit),
+            GETH_ESTIMATOR_SOURCE => entry.geth = Some(profit),
+            _ => continue,
+        }
+
+        if let EstimatorProfits { evm: Some(evm_profit), geth: Some(geth_profit) } = *entry {
+            pending.remove(&key);
+
+            let delta_bps = profit_delta_bps(evm_profit, geth_profit);
+            if delta_bps > config.max_delta_bps {
+                warn!(
+                    "Simulation discrepancy path_hash={:#x} evm_profit={} geth_profit={} delta_bps={} : {}",
+                    path_hash,
+                    NWETH::to_float(evm_profit),
+                    NWETH::to_float(geth_profit),
+                    delta_bps,
+                    swap_line
+                );
+                let health_event = HealthEvent::SimulationDiscrepancy { path_hash, evm_profit, geth_profit, delta_bps };
+                if let Err(e) = health_monitor_channel_tx.send(Message::new(health_event)) {
+                    error!("health_monitor_channel_tx.send error {}", e);
+                }
+            }
+        }
+    }
+
+    Ok("SimulationDifferentialActor finished".to_string())
+}
+
+/// Pairs ready requests from EVM and Geth estimators for the same path and reports discrepancies of the profit realized in
+/// their simulations
+#[derive(Consumer, Producer)]
+pub struct SimulationDifferentialActor<DB: Clone + Send + Sync + 'static> {
+    config: SimulationDifferentialConfig,
+    #[consumer]
+    swap_compose_channel_rx: Option<Broadcaster<MessageSwapCompose<DB>>>,
+    #[producer]
+    health_monitor_channel_tx: Option<Broadcaster<MessageHealthEvent>>,
+    cancellation_token: CancellationToken,
+}
+
+impl<DB: DatabaseRef + Send + Sync + Clone + 'static> Default for SimulationDifferentialActor<DB> {
+    fn default() -> Self {
+        Self {
+            config: SimulationDifferentialConfig::default(),
+            swap_compose_channel_rx: None,
+            health_monitor_channel_tx: None,
+            cancellation_token: CancellationToken::new(),
+        }
+    }
+}
+
+impl<DB: DatabaseRef + Send + Sync + Clone + 'static> SimulationDifferentialActor<DB> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn on_bc(self, bc: &Blockchain, strategy: &Strategy<DB>) -> Self {
+        Self {
+            swap_compose_channel_rx: Some(strategy.swap_compose_channel()),
+            health_monitor_channel_tx: Some(bc.health_monitor_channel()),
+            ..self
+        }
+    }
+
+    pub fn with_config(self, config: SimulationDifferentialConfig) -> Self {
+        Self { config, ..self }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+}
+
+impl<DB: DatabaseRef + Send + Sync + Clone + 'static> Actor for SimulationDifferentialActor<DB> {
+    fn start(&self) -> ActorResult {
+        let task = tokio::task::spawn(simulation_differential_worker(
+            self.swap_compose_channel_rx.clone().unwrap(),
+            self.health_monitor_channel_tx.clone().unwrap(),
+            self.config.clone(),
+            self.cancellation_token.clone(),
+        ));
+        Ok(vec![task])
+    }
+
+    fn name(&self) -> &'static str {
+        "SimulationDifferentialActor"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_profit_delta_bps() {
+        assert_eq!(profit_delta_bps(U256::ZERO, U256::ZERO), 0);
+        assert_eq!(profit_delta_bps(U256::from(100), U256::from(100)), 0);
+        assert_eq!(profit_delta_bps(U256::from(100), U256::from(95)), 500);
+        assert_eq!(profit_delta_bps(U256::from(90), U256::from(100)), 1000);
+        assert_eq!(profit_delta_bps(U256::ZERO, U256::from(100)), 10000);
+    }
+}
@@ -0,0 +1,14 @@
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize, Debug)]
+#[serde(default)]
+pub struct SimulationDifferentialConfig {
+    /// Discrepancy of the EVM and Geth simulated profits in basis points above which it is reported
+    pub max_delta_bps: u64,
+}
+
+impl Default for SimulationDifferentialConfig {
+    fn default() -> Self {
+        Self { max_delta_bps: 500 }
+    }
+}
@@ -10,7 +10,7 @@ use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::broadcast::Receiver;
 use tracing::{error, info, warn};
 
-use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, Consumer, SharedState, WorkerResult};
+use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, SharedState, WorkerResult};
 use loom_core_actors_macros::{Accessor, Consumer};
 use loom_core_blockchain::{Blockchain, BlockchainState};
 use loom_evm_db::DatabaseLoomExt;
@@ -60,6 +60,7 @@ pub async fn state_health_monitor_worker<
     market_state: SharedState<MarketState<DB>>,
     tx_compose_channel_rx: Broadcaster<MessageTxCompose>,
     market_events_rx: Broadcaster<MarketEvents>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult {
     let mut tx_compose_channel_rx = tx_compose_channel_rx.subscribe();
     let mut market_events_rx = market_events_rx.subscribe();
@@ -69,6 +70,9 @@ pub async fn state_health_monitor_worker<
 
     loop {
         tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("State health monitor cancelled".to_string());
+            }
             msg = market_events_rx.recv() => {
                 let market_event_msg : Result<MarketEvents, RecvError> = msg;
                 match market_event_msg {
@@ -131,6 +135,7 @@ pub struct StateHealthMonitorActor<P, DB: Clone + Send + Sync + 'static> {
     tx_compose_channel_rx: Option<Broadcaster<MessageTxCompose>>,
     #[consumer]
     market_events_rx: Option<Broadcaster<MarketEvents>>,
+    cancellation_token: CancellationToken,
 }
 
 impl<P, DB> StateHealthMonitorActor<P, DB>
@@ -139,7 +144,17 @@ where
     DB: DatabaseRef + DatabaseLoomExt + Send + Sync + Clone + Default + 'static,
 {
     pub fn new(client: P) -> Self {
-        StateHealthMonitorActor { client, market_state: None, tx_compose_channel_rx: None, market_events_rx: None }
+        StateHealthMonitorActor {
+            client,
+            market_state: None,
+            tx_compose_channel_rx: None,
+            market_events_rx: None,
+            cancellation_token: CancellationToken::new(),
+        }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
     }
 
     pub fn on_bc(self, bc: &Blockchain, state: &BlockchainState<DB>) -> Self {
@@ -163,6 +178,7 @@ where
             self.market_state.clone().unwrap(),
             self.tx_compose_channel_rx.clone().unwrap(),
             self.market_events_rx.clone().unwrap(),
+            self.cancellation_token.clone(),
         ));
         Ok(vec![task])
     }
@@ -1,23 +1,36 @@
 use alloy_consensus::transaction::Transaction;
 use alloy_network::{Ethereum, TransactionResponse};
-use alloy_primitives::{Address, TxHash, U256};
+use alloy_primitives::{keccak256, Address, TxHash, B256, I256, U256};
 use alloy_provider::Provider;
-use eyre::{eyre, Result};
+use alloy_sol_types::SolValue;
+use eyre::{eyre, OptionExt, Result};
 use influxdb::{Timestamp, WriteQuery};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::broadcast::Receiver;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
-use loom_core_blockchain::Blockchain;
+use loom_core_blockchain::{Blockchain, DEFAULT_WETH_BALANCE_OF_SLOT};
 use loom_evm_utils::NWETH;
-use loom_types_entities::{LatestBlock, Swap, Token};
+use loom_defi_address_book::TokenAddressEth;
+use loom_types_entities::{BundleHistory, ConfirmedBundle, LatestBlock, Swap, Token};
 
-use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
+use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, Producer, SharedState, WorkerResult};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
-use loom_types_blockchain::debug_trace_transaction;
-use loom_types_events::{MarketEvents, MessageTxCompose, TxComposeMessageType};
+use loom_types_blockchain::{debug_trace_transaction, GethStateUpdate, LoomDataTypesEthereum};
+use loom_types_events::{HealthEvent, MarketEvents, MessageHealthEvent, MessageTxCompose, RlpState, TxComposeMessageType};
+
+/// Share of the estimated gas in percent by which gas used of a landed transaction may differ before
+/// `HealthEvent::EstimatorDivergence` is sent
+const ESTIMATOR_DIVERGENCE_PCT: u64 = 20;
+
+/// Wrapped native token of the chain and the slot of its `balanceOf` mapping
+#[derive(Clone, Copy, Debug)]
+struct WethToken {
+    address: Address,
+    balance_of_slot: u64,
+}
 
 #[derive(Clone, Debug)]
 struct TxToCheck {
@@ -29,6 +42,105 @@ struct TxToCheck {
     swap: Swap,
 }
 
+/// Own backrun transaction sent for `block` with the gas estimated by the estimator
+#[derive(Clone, Debug)]
+struct OwnTxToCheck {
+    block: u64,
+    estimated_gas: u64,
+}
+
+fn is_estimator_divergence(estimated_gas: u64, gas_used: u64) -> bool {
+    gas_used.abs_diff(estimated_gas) * 100 > estimated_gas * ESTIMATOR_DIVERGENCE_PCT
+}
+
+async fn check_estimator_divergence<P: Provider<Ethereum> + 'static>(
+    client: P,
+    tx_hash: TxHash,
+    estimated_gas: u64,
+    health_monitor_channel_tx: Broadcaster<MessageHealthEvent>,
+) -> Result<()> {
+    let receipt = client.get_transaction_receipt(tx_hash).await?.ok_or_eyre("RECEIPT_NOT_FOUND")?;
+    let gas_used = receipt.gas_used;
+    if is_estimator_divergence(estimated_gas, gas_used) {
+        warn!(%tx_hash, estimated_gas, gas_used, "Estimated gas diverges from gas used on-chain");
+        let health_event = HealthEvent::EstimatorDivergence { tx_hash, estimated_gas, gas_used };
+        if let Err(e) = health_monitor_channel_tx.send(MessageHealthEvent::new(health_event)) {
+            error!("health_monitor_channel_tx.send : {}", e);
+        }
+    }
+    Ok(())
+}
+
+/// Balance change of the swap funds holder net of the gas cost of the transaction
+fn realized_profit(input_balance: U256, output_balance: U256, gas_cost: U256) -> I256 {
+    I256::from_raw(output_balance).saturating_sub(I256::from_raw(input_balance)).saturating_sub(I256::from_raw(gas_cost))
+}
+
+/// Storage key of the WETH balance of `account`
+fn weth_balance_key(weth: WethToken, account: Address) -> B256 {
+    keccak256((account, U256::from(weth.balance_of_slot)).abi_encode())
+}
+
+/// WETH balances of `account` before and after the transaction from a prestate diff trace. Unchanged slots are omitted from
+/// both sides of the diff and cleared slots from the post state, missing slots are read as zero.
+fn weth_balances(pre: &GethStateUpdate, post: &GethStateUpdate, weth: WethToken, account: Address) -> (U256, U256) {
+    let key = weth_balance_key(weth, account);
+    let balance = |state: &GethStateUpdate| {
+        state.get(&weth.address).and_then(|weth| weth.storage.get(&key)).map(|value| U256::from_be_bytes(value.0)).unwrap_or_default()
+    };
+    (balance(pre), balance(post))
+}
+
+/// Confirm the bundle by the WETH balance change of `funds_holder`, the multicaller or the EOA delegated to it with EIP-7702
+async fn confirm_bundle<P: Provider<Ethereum> + Clone + 'static>(
+    client: P,
+    tx_hash: TxHash,
+    block_number: u64,
+    position: usize,
+    funds_holder: Address,
+    weth: WethToken,
+) -> Result<ConfirmedBundle> {
+    let receipt = client.get_transaction_receipt(tx_hash).await?.ok_or_eyre("RECEIPT_NOT_FOUND")?;
+    let gas_cost = U256::from(receipt.gas_used) * U256::from(receipt.effective_gas_price);
+
+    let (pre, post) = debug_trace_transaction(client, tx_hash, true).await?;
+    let (input_balance, output_balance) = weth_balances(&pre, &post, weth, funds_holder);
+
+    Ok(ConfirmedBundle {
+        tx_hash,
+        block_number,
+        position,
+        gas_used: receipt.gas_used,
+        realized_profit_wei: realized_profit(input_balance, output_balance, gas_cost),
+    })
+}
+
+async fn track_confirmed_bundle<P: Provider<Ethereum> + Clone + 'static>(
+    client: P,
+    tx_hash: TxHash,
+    block_number: u64,
+    position: usize,
+    funds_holder: Address,
+    weth: WethToken,
+    health_monitor_channel_tx: Option<Broadcaster<MessageHealthEvent>>,
+    bundle_history: Option<SharedState<BundleHistory>>,
+) -> Result<()> {
+    let bundle = confirm_bundle(client, tx_hash, block_number, position, funds_holder, weth).await?;
+    let realized_profit_wei = bundle.realized_profit_wei;
+    info!(%tx_hash, block_number, position, gas_used = bundle.gas_used, %realized_profit_wei, "Bundle confirmed");
+
+    if let Some(health_monitor_channel_tx) = health_monitor_channel_tx {
+        let health_event = HealthEvent::BundleConfirmed { tx_hash, block_number, position, realized_profit_wei };
+        if let Err(e) = health_monitor_channel_tx.send(MessageHealthEvent::new(health_event)) {
+            error!("health_monitor_channel_tx.send : {}", e);
+        }
+    }
+    if let Some(bundle_history) = bundle_history {
+        bundle_history.write().await.add(bundle);
+    }
+    Ok(())
+}
+
 async fn calc_coinbase_diff<P: Provider<Ethereum> + 'static>(client: P, tx_hash: TxHash, coinbase: Address) -> Result<U256> {
     let (pre, post) = debug_trace_transaction(client, tx_hash, true).await?;
 
@@ -41,20 +153,30 @@ async fn calc_coinbase_diff<P: Provider<Ethereum> + 'static>(client: P, tx_hash:
     Ok(balance_diff)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn stuffing_tx_monitor_worker<P: Provider<Ethereum> + Clone + 'static>(
     client: P,
     latest_block: SharedState<LatestBlock>,
     tx_compose_channel_rx: Broadcaster<MessageTxCompose>,
     market_events_rx: Broadcaster<MarketEvents>,
     influxdb_write_channel_tx: Broadcaster<WriteQuery>,
+    health_monitor_channel_tx: Option<Broadcaster<MessageHealthEvent>>,
+    multicaller_address: Option<Address>,
+    weth: WethToken,
+    bundle_history: Option<SharedState<BundleHistory>>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult {
     let mut tx_compose_channel_rx = tx_compose_channel_rx.subscribe();
     let mut market_events_rx = market_events_rx.subscribe();
 
     let mut txs_to_check: HashMap<TxHash, TxToCheck> = HashMap::new();
+    let mut own_txs_to_check: HashMap<TxHash, OwnTxToCheck> = HashMap::new();
 
     loop {
         tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Stuffing tx monitor cancelled".to_string());
+            }
             msg = market_events_rx.recv() => {
                 let market_event_msg : Result<MarketEvents, RecvError> = msg;
                 match market_event_msg {
@@ -96,8 +218,46 @@ pub async fn stuffing_tx_monitor_worker<P: Provider<Ethereum> + Clone + 'static>
                                         }
                                         txs_to_check.remove::<TxHash>(&tx.tx_hash());
                                     }
+                                    // Own EIP-7702 transactions are sent to the EOA delegated to the multicaller
+                                    let funds_holder = match multicaller_address {
+                                        Some(multicaller_address) if tx.to() == Some(multicaller_address) => Some(multicaller_address),
+                                        Some(_) if tx.to() == Some(tx.from()) && own_txs_to_check.contains_key(&tx_hash) => Some(tx.from()),
+                                        _ => None,
+                                    };
+                                    if let Some(funds_holder) = funds_holder {
+                                        let client_clone = client.clone();
+                                        let health_monitor_channel_tx = health_monitor_channel_tx.clone();
+                                        let bundle_history = bundle_history.clone();
+                                        tokio::task::spawn(async move {
+                                            if let Err(e) = track_confirmed_bundle(
+                                                client_clone,
+                                                tx_hash,
+                                                block_number,
+                                                idx,
+                                                funds_holder,
+                                                weth,
+                                                health_monitor_channel_tx,
+                                                bundle_history,
+                                            ).await {
+                                                error!("track_confirmed_bundle {:?} : {}", tx_hash, e);
+                                            }
+                                        });
+                                    }
+                                    if let Some(own_tx) = own_txs_to_check.remove(&tx_hash) {
+                                        if let Some(health_monitor_channel_tx) = health_monitor_channel_tx.clone() {
+                                            let client_clone = client.clone();
+                                            tokio::task::spawn(async move {
+                                                let estimated_gas = own_tx.estimated_gas;
+                                                if let Err(e) = check_estimator_divergence(client_clone, tx_hash, estimated_gas, health_monitor_channel_tx).await {
+                                                    error!("check_estimator_divergence {:?} : {}", tx_hash, e);
+                                                }
+                                            });
+                                        }
+                                    }
                                 }
                             }
+                            // Bundles are sent for a single block, transactions not landed by now are never landed
+                            own_txs_to_check.retain(|_, own_tx| own_tx.block > block_number);
                             info!("Stuffing txs to check : {} at block {}", txs_to_check.len(), block_number);
 
 
@@ -119,6 +279,14 @@ pub async fn stuffing_tx_monitor_worker<P: Provider<Ethereum> + Clone + 'static>
                 let tx_compose_update : Result<MessageTxCompose, RecvError>  = msg;
                 match tx_compose_update {
                     Ok(tx_compose_msg)=>{
+                        if let TxComposeMessageType::Broadcast(tx_compose_data) = &tx_compose_msg.inner {
+                            for rlp_state in tx_compose_data.rlp_bundle.iter().flatten() {
+                                if let RlpState::Backrun(rlp) = rlp_state {
+                                    let own_tx = OwnTxToCheck { block: tx_compose_data.next_block_number, estimated_gas: tx_compose_data.gas };
+                                    own_txs_to_check.insert(keccak256(rlp), own_tx);
+                                }
+                            }
+                        }
                         if let TxComposeMessageType::Sign(tx_compose_data) = tx_compose_msg.inner {
                             for stuffing_tx_hash in tx_compose_data.stuffing_txs_hashes.iter() {
                                 let Some(swap) = & tx_compose_data.swap else {continue};
@@ -172,6 +340,13 @@ pub struct StuffingTxMonitorActor<P> {
     market_events_rx: Option<Broadcaster<MarketEvents>>,
     #[producer]
     influxdb_write_channel_tx: Option<Broadcaster<WriteQuery>>,
+    #[producer]
+    health_monitor_channel_tx: Option<Broadcaster<MessageHealthEvent>>,
+    #[accessor]
+    bundle_history: Option<SharedState<BundleHistory>>,
+    multicaller_address: Option<Address>,
+    weth: WethToken,
+    cancellation_token: CancellationToken,
 }
 
 impl<P: Provider<Ethereum> + Send + Sync + Clone + 'static> StuffingTxMonitorActor<P> {
@@ -182,16 +357,43 @@ impl<P: Provider<Ethereum> + Send + Sync + Clone + 'static> StuffingTxMonitorAct
             tx_compose_channel_rx: None,
             market_events_rx: None,
             influxdb_write_channel_tx: None,
+            health_monitor_channel_tx: None,
+            bundle_history: None,
+            multicaller_address: None,
+            weth: WethToken { address: TokenAddressEth::WETH, balance_of_slot: DEFAULT_WETH_BALANCE_OF_SLOT },
+            cancellation_token: CancellationToken::new(),
         }
     }
 
+    /// Transactions to `multicaller_address` in mined blocks are tracked as confirmed bundles
+    pub fn with_multicaller_address(self, multicaller_address: Address) -> Self {
+        Self { multicaller_address: Some(multicaller_address), ..self }
+    }
+
+    /// Realized profit of confirmed bundles is the WETH balance change, WETH is resolved for `chain_id`
+    pub fn with_chain_id(self, chain_id: u64) -> Self {
+        let address = Token::<LoomDataTypesEthereum>::new(TokenAddressEth::ETH_NATIVE).get_canonical_address(chain_id);
+        Self { weth: WethToken { address, ..self.weth }, ..self }
+    }
+
+    /// Slot of the `balanceOf` mapping of the WETH of the chain
+    pub fn with_weth_balance_of_slot(self, balance_of_slot: u64) -> Self {
+        Self { weth: WethToken { balance_of_slot, ..self.weth }, ..self }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
     pub fn on_bc(self, bc: &Blockchain) -> Self {
         Self {
             latest_block: Some(bc.latest_block()),
             tx_compose_channel_rx: Some(bc.tx_compose_channel()),
             market_events_rx: Some(bc.market_events_channel()),
             influxdb_write_channel_tx: Some(bc.influxdb_write_channel()),
-            ..self
+            health_monitor_channel_tx: Some(bc.health_monitor_channel()),
+            bundle_history: Some(bc.bundle_history()),
+            ..self.with_chain_id(bc.chain_id()).with_weth_balance_of_slot(bc.weth_balance_of_slot())
         }
     }
 }
@@ -207,6 +409,11 @@ where
             self.tx_compose_channel_rx.clone().unwrap(),
             self.market_events_rx.clone().unwrap(),
             self.influxdb_write_channel_tx.clone().unwrap(),
+            self.health_monitor_channel_tx.clone(),
+            self.multicaller_address,
+            self.weth,
+            self.bundle_history.clone(),
+            self.cancellation_token.clone(),
         ));
         Ok(vec![task])
     }
@@ -215,3 +422,43 @@ where
         "StuffingTxMonitorActor"
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_estimator_divergence() {
+        assert!(!is_estimator_divergence(100_000, 100_000));
+        assert!(!is_estimator_divergence(100_000, 120_000));
+        assert!(!is_estimator_divergence(100_000, 80_000));
+        assert!(is_estimator_divergence(100_000, 120_001));
+        assert!(is_estimator_divergence(100_000, 79_999));
+    }
+
+    #[test]
+    fn test_realized_profit() {
+        let (input_balance, gas_cost) = (U256::from(1_000_000), U256::from(30_000));
+        assert_eq!(realized_profit(input_balance, U256::from(1_100_000), gas_cost), I256::try_from(70_000).unwrap());
+        assert_eq!(realized_profit(input_balance, input_balance, gas_cost), I256::try_from(-30_000).unwrap());
+    }
+
+    #[test]
+    fn test_weth_balances() {
+        let weth = WethToken { address: TokenAddressEth::WETH, balance_of_slot: DEFAULT_WETH_BALANCE_OF_SLOT };
+        let multicaller_address = Address::repeat_byte(1);
+        let key = weth_balance_key(weth, multicaller_address);
+        let mut pre = GethStateUpdate::new();
+        let mut post = GethStateUpdate::new();
+        pre.entry(weth.address).or_default().storage.insert(key, B256::from(U256::from(1_000_000)));
+        post.entry(weth.address).or_default().storage.insert(key, B256::from(U256::from(1_100_000)));
+
+        assert_eq!(weth_balances(&pre, &post, weth, multicaller_address), (U256::from(1_000_000), U256::from(1_100_000)));
+        // Cleared balances are omitted from the post state
+        assert_eq!(weth_balances(&pre, &GethStateUpdate::new(), weth, multicaller_address), (U256::from(1_000_000), U256::ZERO));
+        assert_eq!(weth_balances(&pre, &post, weth, Address::repeat_byte(2)), (U256::ZERO, U256::ZERO));
+        // Balances of another balanceOf layout are not read
+        let other_weth = WethToken { balance_of_slot: 0, ..weth };
+        assert_eq!(weth_balances(&pre, &post, other_weth, multicaller_address), (U256::ZERO, U256::ZERO));
+    }
+}
@@ -0,0 +1,77 @@
+use alloy_primitives::{keccak256, Address, B256};
+use alloy_rpc_types::Log;
+
+use loom_types_entities::{PoolClass, PoolId};
+
+/// Pool creation event of a factory. The pool address is read from the `pool_address_word` 32 byte word of the event data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventFilter {
+    pub signature: B256,
+    pub pool_address_word: usize,
+    pub pool_class: PoolClass,
+}
+
+impl EventFilter {
+    /// Filter by the event declaration, e.g. `PairCreated(address,address,address,uint256)`
+    pub fn new(event: &str, pool_address_word: usize, pool_class: PoolClass) -> Self {
+        Self { signature: keccak256(event.as_bytes()), pool_address_word, pool_class }
+    }
+
+    pub fn uniswap_v2_pair_created() -> Self {
+        Self::new("PairCreated(address,address,address,uint256)", 0, PoolClass::UniswapV2)
+    }
+
+    pub fn uniswap_v3_pool_created() -> Self {
+        Self::new("PoolCreated(address,address,uint24,int24,address)", 1, PoolClass::UniswapV3)
+    }
+
+    /// Aerodrome Slipstream CLFactory, pools are loaded by the `aerodrome` factory override of the UniswapV3 class
+    pub fn aerodrome_cl_pool_created() -> Self {
+        Self::new("PoolCreated(address,address,int24,address)", 0, PoolClass::UniswapV3)
+    }
+
+    /// Address of the created pool if the log is the filtered event
+    pub fn pool_address(&self, log_entry: &Log) -> Option<Address> {
+        if log_entry.topics().first() != Some(&self.signature) {
+            return None;
+        }
+        let offset = self.pool_address_word * 32;
+        let word = log_entry.data().data.get(offset..offset + 32)?;
+        Some(Address::from_slice(&word[12..]))
+    }
+}
+
+/// Pool created by a log of one of the factories
+pub fn pool_from_factory_log(factory_event_filters: &[(Address, EventFilter)], log_entry: &Log) -> Option<(PoolId, PoolClass)> {
+    factory_event_filters.iter().filter(|(factory, _)| *factory == log_entry.address()).find_map(|(_, event_filter)| {
+        event_filter.pool_address(log_entry).map(|pool_address| (PoolId::Address(pool_address), event_filter.pool_class))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::{Bytes, LogData};
+
+    #[test]
+    fn test_pool_from_factory_log() {
+        let (factory, pool) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let factory_event_filters = vec![(factory, EventFilter::aerodrome_cl_pool_created())];
+
+        let mut data = [0u8; 32];
+        data[12..].copy_from_slice(pool.as_slice());
+        let topics = vec![EventFilter::aerodrome_cl_pool_created().signature, B256::ZERO, B256::ZERO, B256::ZERO];
+        let log_entry = |address: Address, topics: Vec<B256>| Log {
+            inner: alloy_primitives::Log { address, data: LogData::new_unchecked(topics, Bytes::from(data.to_vec())) },
+            ..Log::default()
+        };
+
+        assert_eq!(
+            pool_from_factory_log(&factory_event_filters, &log_entry(factory, topics.clone())),
+            Some((PoolId::Address(pool), PoolClass::UniswapV3))
+        );
+        assert_eq!(pool_from_factory_log(&factory_event_filters, &log_entry(pool, topics)), None);
+        let v3_topics = vec![EventFilter::uniswap_v3_pool_created().signature, B256::ZERO, B256::ZERO, B256::ZERO];
+        assert_eq!(pool_from_factory_log(&factory_event_filters, &log_entry(factory, v3_topics)), None);
+    }
+}
@@ -36,7 +36,7 @@ where
         let filter = Filter::new().from_block(current_block).to_block(current_block + block_size - 1);
         match client.get_logs(&filter).await {
             Ok(logs) => {
-                process_log_entries(logs, pool_loaders.as_ref(), tasks_tx.clone()).await?;
+                process_log_entries(logs, pool_loaders.as_ref(), &[], tasks_tx.clone()).await?;
             }
             Err(e) => {
                 error!("{}", e)
@@ -1,12 +1,15 @@
+pub use factory_events::{pool_from_factory_log, EventFilter};
 pub use history_pool_loader_actor::HistoryPoolLoaderOneShotActor;
 pub use new_pool_actor::NewPoolLoaderActor;
 pub use pool_loader_actor::{fetch_and_add_pool_by_pool_id, fetch_state_and_add_pool, PoolLoaderActor};
 pub use protocol_pool_loader_actor::ProtocolPoolLoaderOneShotActor;
 pub use required_pools_actor::RequiredPoolLoaderActor;
 
+mod factory_events;
 mod history_pool_loader_actor;
 mod logs_parser;
 mod new_pool_actor;
 mod pool_loader_actor;
 mod protocol_pool_loader_actor;
 mod required_pools_actor;
+mod uniswap_v4_events;
@@ -1,16 +1,23 @@
 use alloy_network::Network;
+use alloy_primitives::Address;
 use alloy_provider::Provider;
 use alloy_rpc_types::Log;
+use alloy_sol_types::SolEvent;
 use eyre::Result;
 use std::collections::HashMap;
 
 use loom_core_actors::{run_sync, Broadcaster};
-use loom_types_entities::PoolLoaders;
+use loom_defi_abi::uniswap3::IUniswapV3Pool;
+use loom_defi_pools::UniswapV3Pool;
+use loom_types_entities::{Market, PoolId, PoolLoaders};
 use loom_types_events::LoomTask;
 
+use crate::factory_events::{pool_from_factory_log, EventFilter};
+
 pub async fn process_log_entries<P, N>(
     log_entries: Vec<Log>,
     pool_loaders: &PoolLoaders<P, N>,
+    factory_event_filters: &[(Address, EventFilter)],
     tasks_tx: Broadcaster<LoomTask>,
 ) -> Result<()>
 where
@@ -21,6 +28,14 @@ where
     let mut processed_pools = HashMap::new();
 
     for log_entry in log_entries.into_iter() {
+        // Pools created by the factories are emitted by the factory, the pool address is in the event data
+        if let Some((pool_id, pool_class)) = pool_from_factory_log(factory_event_filters, &log_entry) {
+            if processed_pools.insert(pool_id.address_or_zero(), true).is_none() {
+                pool_to_fetch.push((pool_id, pool_class));
+            }
+            continue;
+        }
+
         if let Some((pool_id, pool_class)) = pool_loaders.determine_pool_class(&log_entry) {
             // was this pool already processed?
             if processed_pools.insert(log_entry.address(), true).is_some() {
@@ -34,3 +49,23 @@ where
     run_sync!(tasks_tx.send(LoomTask::FetchAndAddPools(pool_to_fetch)));
     Ok(())
 }
+
+/// Drop the preloaded ticks of UniswapV3 pools with liquidity changed by Mint or Burn logs, returns the invalidated pools
+pub fn invalidate_preloaded_ticks(market: &Market, log_entries: &[Log]) -> Vec<UniswapV3Pool> {
+    let mut invalidated = Vec::new();
+    for log_entry in log_entries.iter() {
+        let Some(topic0) = log_entry.topic0() else {
+            continue;
+        };
+        if *topic0 != IUniswapV3Pool::Mint::SIGNATURE_HASH && *topic0 != IUniswapV3Pool::Burn::SIGNATURE_HASH {
+            continue;
+        }
+        let Some(pool) = market.get_pool(&PoolId::Address(log_entry.address())) else {
+            continue;
+        };
+        if let Some(pool) = pool.as_any().downcast_ref::<UniswapV3Pool>().filter(|pool| pool.invalidate_preloaded_ticks()) {
+            invalidated.push(pool.clone());
+        }
+    }
+    invalidated
+}
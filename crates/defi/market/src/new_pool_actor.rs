@@ -1,22 +1,51 @@
 use alloy_network::Network;
+use alloy_primitives::Address;
 use alloy_provider::Provider;
 use eyre::Result;
 use std::sync::Arc;
 use tokio::sync::broadcast::error::RecvError;
 use tracing::{debug, error};
 
-use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, Consumer, Producer, WorkerResult};
-use loom_core_actors_macros::{Consumer, Producer};
+use loom_core_actors::{
+    subscribe, Accessor, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, Producer, SharedState, WorkerResult,
+};
+use loom_core_actors_macros::{Accessor, Consumer, Producer};
 use loom_core_blockchain::Blockchain;
-use loom_types_entities::PoolLoaders;
+use loom_defi_pools::UniswapV3Pool;
+use loom_types_entities::{Market, Pool, PoolLoaders};
 use loom_types_events::{LoomTask, MessageBlockLogs};
 
-use crate::logs_parser::process_log_entries;
+use crate::factory_events::EventFilter;
+use crate::logs_parser::{invalidate_preloaded_ticks, process_log_entries};
+
+/// Reloads the invalidated ticks in the background, the pools are quoted with the EVM meanwhile
+fn reload_preloaded_ticks<P, N>(pools_loaders: &PoolLoaders<P, N>, pools: Vec<UniswapV3Pool>)
+where
+    N: Network,
+    P: Provider<N> + Send + Sync + Clone + 'static,
+{
+    let (Some(provider), Some(tick_range)) =
+        (pools_loaders.provider(), pools_loaders.config().and_then(|config| config.tick_preload_range()))
+    else {
+        return;
+    };
+    for pool in pools {
+        let provider = provider.clone();
+        tokio::task::spawn(async move {
+            if let Err(error) = pool.reload_preloaded_ticks(provider, tick_range).await {
+                error!(%error, address = %pool.get_address(), "Cannot reload preloaded ticks");
+            }
+        });
+    }
+}
 
 pub async fn new_pool_worker<P, N>(
     log_update_rx: Broadcaster<MessageBlockLogs>,
+    market: SharedState<Market>,
     pools_loaders: Arc<PoolLoaders<P, N>>,
+    factory_event_filters: Vec<(Address, EventFilter)>,
     tasks_tx: Broadcaster<LoomTask>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult
 where
     N: Network,
@@ -26,15 +55,24 @@ where
 
     loop {
         tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("New pool loader cancelled".to_string());
+            }
             msg = log_update_rx.recv() => {
                 debug!("Log update");
 
                 let log_update : Result<MessageBlockLogs, RecvError>  = msg;
                 match log_update {
                     Ok(log_update_msg)=>{
+                        let invalidated = invalidate_preloaded_ticks(&*market.read().await, &log_update_msg.inner.logs);
+                        if !invalidated.is_empty() {
+                            debug!(invalidated = invalidated.len(), "Preloaded ticks invalidated");
+                            reload_preloaded_ticks(&pools_loaders, invalidated);
+                        }
                         process_log_entries(
                                 log_update_msg.inner.logs,
                                 &pools_loaders,
+                                &factory_event_filters,
                                 tasks_tx.clone(),
                         ).await?
                     }
@@ -48,17 +86,24 @@ where
     }
 }
 
-#[derive(Consumer, Producer)]
+/// Loads pools found in new block logs by the pool loaders. Pool creation events of the factories in `factory_event_filters`
+/// are watched as well, e.g. to load pools of several factories on Base. Preloaded ticks of pools with Mint or Burn logs are
+/// invalidated.
+#[derive(Accessor, Consumer, Producer)]
 pub struct NewPoolLoaderActor<P, N>
 where
     N: Network,
     P: Provider<N> + Send + Sync + Clone + 'static,
 {
     pool_loaders: Arc<PoolLoaders<P, N>>,
+    factory_event_filters: Vec<(Address, EventFilter)>,
+    #[accessor]
+    market: Option<SharedState<Market>>,
     #[consumer]
     log_update_rx: Option<Broadcaster<MessageBlockLogs>>,
     #[producer]
     tasks_tx: Option<Broadcaster<LoomTask>>,
+    cancellation_token: CancellationToken,
 }
 
 impl<P, N> NewPoolLoaderActor<P, N>
@@ -67,11 +112,26 @@ where
     P: Provider<N> + Send + Sync + Clone + 'static,
 {
     pub fn new(pool_loaders: Arc<PoolLoaders<P, N>>) -> Self {
-        NewPoolLoaderActor { log_update_rx: None, pool_loaders, tasks_tx: None }
+        NewPoolLoaderActor {
+            log_update_rx: None,
+            pool_loaders,
+            factory_event_filters: Vec::new(),
+            market: None,
+            tasks_tx: None,
+            cancellation_token: CancellationToken::new(),
+        }
+    }
+
+    pub fn with_factory_event_filters(self, factory_event_filters: Vec<(Address, EventFilter)>) -> Self {
+        Self { factory_event_filters, ..self }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
     }
 
     pub fn on_bc(self, bc: &Blockchain) -> Self {
-        Self { log_update_rx: Some(bc.new_block_logs_channel()), tasks_tx: Some(bc.tasks_channel()), ..self }
+        Self { market: Some(bc.market()), log_update_rx: Some(bc.new_block_logs_channel()), tasks_tx: Some(bc.tasks_channel()), ..self }
     }
 }
 
@@ -83,8 +143,11 @@ where
     fn start(&self) -> ActorResult {
         let task = tokio::task::spawn(new_pool_worker(
             self.log_update_rx.clone().unwrap(),
+            self.market.clone().unwrap(),
             self.pool_loaders.clone(),
+            self.factory_event_filters.clone(),
             self.tasks_tx.clone().unwrap(),
+            self.cancellation_token.clone(),
         ));
         Ok(vec![task])
     }
@@ -1,28 +1,97 @@
 use std::collections::{BTreeMap, HashMap};
 use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use alloy_network::Network;
 use alloy_provider::Provider;
 use eyre::Result;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
-use loom_core_actors::{run_sync, subscribe, Actor, ActorResult, Broadcaster, Producer, SharedState, WorkerResult};
+use loom_core_actors::{run_sync, subscribe, Actor, ActorResult, Broadcaster, CancellationToken, Producer, SharedState, WorkerResult};
 use loom_core_actors::{Accessor, Consumer};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
 use loom_core_blockchain::{Blockchain, BlockchainState};
 use loom_node_debug_provider::DebugProviderExt;
 use loom_types_entities::required_state::RequiredStateReader;
-use loom_types_entities::{Market, MarketState, PoolClass, PoolId, PoolLoaders, PoolWrapper, SwapDirection};
-use loom_types_events::{LoomTask, MarketEvents};
+use loom_types_entities::{Market, MarketState, PoolClass, PoolId, PoolLoaders, PoolWrapper, SwapDirection, SwapPathsCache};
+use loom_types_events::{LoomTask, MarketEvents, MessageBlockLogs};
 
 use loom_types_blockchain::get_touched_addresses;
 use loom_types_entities::pool_config::PoolsLoadingConfig;
 use revm::{Database, DatabaseCommit, DatabaseRef};
 use tokio::sync::Semaphore;
 
+use crate::uniswap_v4_events::pool_manager_swap_state_update;
+
 const MAX_CONCURRENT_TASKS: usize = 20;
+const SWAP_PATHS_CACHE_SAVE_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Read the swap paths cache if it was saved within `max_age_blocks` of the current block
+async fn read_swap_paths_cache<P, N>(client: &P, file: &Path, max_age_blocks: u64) -> Option<Arc<SwapPathsCache>>
+where
+    N: Network,
+    P: Provider<N>,
+{
+    let swap_paths_cache = match SwapPathsCache::read_from_file(file) {
+        Ok(swap_paths_cache) => swap_paths_cache,
+        Err(error) => {
+            warn!(%error, file = %file.display(), "Swap paths cache not loaded");
+            return None;
+        }
+    };
+    let block_number = match client.get_block_number().await {
+        Ok(block_number) => block_number,
+        Err(error) => {
+            error!(%error, "Cannot get block number, swap paths cache not loaded");
+            return None;
+        }
+    };
+    if !swap_paths_cache.is_fresh(block_number, max_age_blocks) {
+        info!(cache_block = swap_paths_cache.block_number, block_number, "Swap paths cache is stale");
+        return None;
+    }
+    info!(paths = swap_paths_cache.len(), cache_block = swap_paths_cache.block_number, "Swap paths cache loaded");
+    Some(Arc::new(swap_paths_cache))
+}
+
+/// Save swap paths of the market to `file` periodically
+pub async fn swap_paths_cache_worker<P, N>(
+    client: P,
+    market: SharedState<Market>,
+    file: PathBuf,
+    cancellation_token: CancellationToken,
+) -> WorkerResult
+where
+    N: Network,
+    P: Provider<N> + Send + Sync + Clone + 'static,
+{
+    let mut interval = tokio::time::interval(SWAP_PATHS_CACHE_SAVE_INTERVAL);
+    // First tick completes immediately, paths are not loaded yet
+    interval.tick().await;
+    loop {
+        tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Swap paths cache cancelled".to_string());
+            }
+            _ = interval.tick() => {}
+        }
+        let block_number = match client.get_block_number().await {
+            Ok(block_number) => block_number,
+            Err(error) => {
+                error!(%error, "Cannot get block number, swap paths cache not saved");
+                continue;
+            }
+        };
+        match market.read().await.save_paths_to_file(&file, block_number) {
+            Ok(()) => debug!(block_number, file = %file.display(), "Swap paths cache saved"),
+            Err(error) => error!(%error, file = %file.display(), "Cannot save swap paths cache"),
+        }
+    }
+}
 
+#[allow(clippy::too_many_arguments)]
 pub async fn pool_loader_worker<P, PL, N, DB>(
     client: P,
     pool_loaders: Arc<PoolLoaders<PL, N>>,
@@ -31,6 +100,7 @@ pub async fn pool_loader_worker<P, PL, N, DB>(
     market_state: SharedState<MarketState<DB>>,
     tasks_rx: Broadcaster<LoomTask>,
     market_events_tx: Broadcaster<MarketEvents>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult
 where
     N: Network,
@@ -40,12 +110,23 @@ where
 {
     let mut processed_pools = HashMap::new();
     let semaphore = std::sync::Arc::new(Semaphore::new(pools_config.threads().unwrap_or(MAX_CONCURRENT_TASKS)));
+    let swap_paths_cache = match pools_config.swap_paths_cache_file() {
+        Some(file) => read_swap_paths_cache(&client, file, pools_config.swap_paths_cache_max_age_blocks()).await,
+        None => None,
+    };
 
     subscribe!(tasks_rx);
     loop {
-        if let Ok(task) = tasks_rx.recv().await {
+        let msg = tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Pool loader cancelled".to_string());
+            }
+            msg = tasks_rx.recv() => msg,
+        };
+        if let Ok(task) = msg {
             let pools = match task {
                 LoomTask::FetchAndAddPools(pools) => pools,
+                LoomTask::ConfigReloaded(_) | LoomTask::ActorRestarted { .. } => continue,
             };
 
             for (pool_id, pool_class) in pools {
@@ -60,20 +141,25 @@ where
                 let market_state = market_state.clone();
                 let pool_loaders_clone = pool_loaders.clone();
                 let market_events_tx_clone = market_events_tx.clone();
+                let swap_paths_cache_clone = swap_paths_cache.clone();
 
                 tokio::task::spawn(async move {
                     match sema_clone.acquire().await {
                         Ok(permit) => {
-                            match fetch_and_add_pool_by_pool_id(
-                                client_clone,
-                                market_clone,
-                                market_state,
-                                pool_loaders_clone,
-                                pool_id,
-                                pool_class,
-                            )
-                            .await
-                            {
+                            let result = match pool_loaders_clone.load_pool_without_provider(pool_id, &pool_class).await {
+                                Ok(pool) => {
+                                    fetch_state_and_add_pool_with_paths_cache(
+                                        client_clone,
+                                        market_clone,
+                                        market_state,
+                                        pool,
+                                        swap_paths_cache_clone,
+                                    )
+                                    .await
+                                }
+                                Err(error) => Err(error),
+                            };
+                            match result {
                                 Ok((pool_id, swap_path_idx_vec)) => {
                                     info!(%pool_id, %pool_class, "Pool loaded successfully");
                                     run_sync!(market_events_tx_clone.send(MarketEvents::NewPoolLoaded { pool_id, swap_path_idx_vec }))
@@ -95,6 +181,36 @@ where
     }
 }
 
+/// Apply Uniswap V4 PoolManager `Swap` events directly to the market state instead of re-fetching pool state
+pub async fn pool_manager_events_worker<DB>(
+    market: SharedState<Market>,
+    market_state: SharedState<MarketState<DB>>,
+    block_logs_rx: Broadcaster<MessageBlockLogs>,
+    cancellation_token: CancellationToken,
+) -> WorkerResult
+where
+    DB: Database + DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'static,
+{
+    subscribe!(block_logs_rx);
+    loop {
+        let msg = tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Pool manager events cancelled".to_string());
+            }
+            msg = block_logs_rx.recv() => msg,
+        };
+        if let Ok(block_logs) = msg {
+            let market_guard = market.read().await;
+            let mut market_state_guard = market_state.write().await;
+
+            if let Some((update, pools)) = pool_manager_swap_state_update(&market_guard, &market_state_guard.state_db, &block_logs.logs) {
+                market_state_guard.apply_geth_update(update);
+                debug!(pools_count = pools.len(), "Uniswap V4 pool states updated from PoolManager events");
+            }
+        }
+    }
+}
+
 /// Fetch pool data, add it to the market and fetch the required state
 pub async fn fetch_and_add_pool_by_pool_id<P, PL, N, DB>(
     client: P,
@@ -127,67 +243,96 @@ where
     P: Provider<N> + DebugProviderExt<N> + Send + Sync + Clone + 'static,
     DB: Database + DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'static,
 {
-    match pool_wrapped.get_state_required() {
-        Ok(required_state) => match RequiredStateReader::fetch_calls_and_slots(client, required_state, None).await {
-            Ok(state) => {
-                let pool_address = pool_wrapped.get_address();
-                {
-                    let updated_addresses = get_touched_addresses(&state);
-
-                    let mut market_state_write_guard = market_state.write().await;
-                    market_state_write_guard.apply_geth_update(state);
-                    market_state_write_guard.config.disable_cell_vec(pool_address, pool_wrapped.get_read_only_cell_vec());
-
-                    let pool_tokens = pool_wrapped.get_tokens();
-
-                    for updated_address in updated_addresses {
-                        if !pool_tokens.contains(&updated_address) {
-                            market_state_write_guard.config.add_force_insert(updated_address);
-                        }
-                    }
+    fetch_state_and_add_pool_with_paths_cache(client, market, market_state, pool_wrapped, None).await
+}
+
+/// Fetch pool state and add it to the market. Swap paths of pools found in `swap_paths_cache` are taken from the cache
+/// instead of being built. The state of pools restored from a market state snapshot is not fetched again.
+async fn fetch_state_and_add_pool_with_paths_cache<P, N, DB>(
+    client: P,
+    market: SharedState<Market>,
+    market_state: SharedState<MarketState<DB>>,
+    pool_wrapped: PoolWrapper,
+    swap_paths_cache: Option<Arc<SwapPathsCache>>,
+) -> Result<(PoolId, Vec<usize>)>
+where
+    N: Network,
+    P: Provider<N> + DebugProviderExt<N> + Send + Sync + Clone + 'static,
+    DB: Database + DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'static,
+{
+    let pool_address = pool_wrapped.get_address();
+    let pool_manager_cells = pool_wrapped.get_pool_manager_cells();
 
-                    drop(market_state_write_guard);
+    // Pools sharing a pool manager contract are always fetched, the snapshot cannot tell their states apart
+    let restored = pool_manager_cells.is_empty() && market_state.read().await.config.is_restored(&pool_address);
+    if restored {
+        debug!(%pool_address, "Pool state restored from snapshot, fetch skipped");
+        let mut market_state_write_guard = market_state.write().await;
+        market_state_write_guard.config.disable_cell_vec(pool_address, pool_wrapped.get_read_only_cell_vec());
+        market_state_write_guard.config.add_force_insert(pool_address);
+    } else {
+        let state = match pool_wrapped.get_state_required() {
+            Ok(required_state) => match RequiredStateReader::fetch_calls_and_slots(client, required_state, None).await {
+                Ok(state) => state,
+                Err(e) => {
+                    error!("{}", e);
+                    return Err(e);
                 }
+            },
+            Err(e) => {
+                error!("{}", e);
+                return Err(e);
+            }
+        };
 
-                let directions_vec = pool_wrapped.get_swap_directions();
-                let pool_manager_cells = pool_wrapped.get_pool_manager_cells();
-                let pool_id = pool_wrapped.get_pool_id();
+        let updated_addresses = get_touched_addresses(&state);
 
-                let mut directions_tree: BTreeMap<PoolWrapper, Vec<SwapDirection>> = BTreeMap::new();
-                directions_tree.insert(pool_wrapped.clone(), directions_vec);
+        let mut market_state_write_guard = market_state.write().await;
+        market_state_write_guard.apply_geth_update(state);
+        market_state_write_guard.config.disable_cell_vec(pool_address, pool_wrapped.get_read_only_cell_vec());
 
-                let start_time = std::time::Instant::now();
-                let mut market_write_guard = market.write().await;
-                debug!(elapsed = start_time.elapsed().as_micros(), "market_guard market.write acquired");
-                // Ignore error if pool already exists because it was maybe already added by e.g. db pool loader
-                let _ = market_write_guard.add_pool(pool_wrapped);
+        let pool_tokens = pool_wrapped.get_tokens();
 
-                let swap_paths = market_write_guard.build_swap_path_vec(&directions_tree)?;
-                let swap_paths_added = market_write_guard.add_paths(swap_paths);
+        for updated_address in updated_addresses {
+            if !pool_tokens.contains(&updated_address) {
+                market_state_write_guard.config.add_force_insert(updated_address);
+            }
+        }
 
-                for (pool_manager_address, cells_vec) in pool_manager_cells {
-                    for cell in cells_vec {
-                        market_write_guard.add_pool_manager_cell(pool_manager_address, pool_id, cell)
-                    }
-                }
+        drop(market_state_write_guard);
+    }
 
-                debug!(elapsed = start_time.elapsed().as_micros(),  market = %market_write_guard, "market_guard path added");
+    let directions_vec = pool_wrapped.get_swap_directions();
+    let pool_id = pool_wrapped.get_pool_id();
 
-                drop(market_write_guard);
-                debug!(elapsed = start_time.elapsed().as_micros(), "market_guard market.write releases");
+    let mut directions_tree: BTreeMap<PoolWrapper, Vec<SwapDirection>> = BTreeMap::new();
+    directions_tree.insert(pool_wrapped.clone(), directions_vec);
 
-                Ok((pool_id, swap_paths_added))
-            }
-            Err(e) => {
-                error!("{}", e);
-                Err(e)
-            }
-        },
-        Err(e) => {
-            error!("{}", e);
-            Err(e)
+    let start_time = std::time::Instant::now();
+    let mut market_write_guard = market.write().await;
+    debug!(elapsed = start_time.elapsed().as_micros(), "market_guard market.write acquired");
+    // Ignore error if pool already exists because it was maybe already added by e.g. db pool loader
+    let _ = market_write_guard.add_pool(pool_wrapped);
+
+    // Cached paths of the pool are added once all their pools are loaded
+    let swap_paths = match swap_paths_cache.as_ref().filter(|swap_paths_cache| swap_paths_cache.contains_pool(&pool_id)) {
+        Some(swap_paths_cache) => swap_paths_cache.pool_swap_paths(&market_write_guard, &pool_id),
+        None => market_write_guard.build_swap_path_vec(&directions_tree)?,
+    };
+    let swap_paths_added = market_write_guard.add_paths(swap_paths);
+
+    for (pool_manager_address, cells_vec) in pool_manager_cells {
+        for cell in cells_vec {
+            market_write_guard.add_pool_manager_cell(pool_manager_address, pool_id, cell)
         }
     }
+
+    debug!(elapsed = start_time.elapsed().as_micros(),  market = %market_write_guard, "market_guard path added");
+
+    drop(market_write_guard);
+    debug!(elapsed = start_time.elapsed().as_micros(), "market_guard market.write releases");
+
+    Ok((pool_id, swap_paths_added))
 }
 
 #[derive(Accessor, Consumer, Producer)]
@@ -207,8 +352,11 @@ where
     market_state: Option<SharedState<MarketState<DB>>>,
     #[consumer]
     tasks_rx: Option<Broadcaster<LoomTask>>,
+    #[consumer]
+    block_logs_rx: Option<Broadcaster<MessageBlockLogs>>,
     #[producer]
     market_events_channel_tx: Option<Broadcaster<MarketEvents>>,
+    cancellation_token: CancellationToken,
     _n: PhantomData<N>,
 }
 
@@ -227,16 +375,23 @@ where
             market: None,
             market_state: None,
             tasks_rx: None,
+            block_logs_rx: None,
             market_events_channel_tx: None,
+            cancellation_token: CancellationToken::new(),
             _n: PhantomData,
         }
     }
 
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
     pub fn on_bc(self, bc: &Blockchain, state: &BlockchainState<DB>) -> Self {
         Self {
             market: Some(bc.market()),
             market_state: Some(state.market_state_commit()),
             tasks_rx: Some(bc.tasks_channel()),
+            block_logs_rx: Some(bc.new_block_logs_channel()),
             market_events_channel_tx: Some(bc.market_events_channel()),
             ..self
         }
@@ -259,8 +414,29 @@ where
             self.market_state.clone().unwrap(),
             self.tasks_rx.clone().unwrap(),
             self.market_events_channel_tx.clone().unwrap(),
+            self.cancellation_token.clone(),
         ));
-        Ok(vec![task])
+        let mut tasks = vec![task];
+
+        if let Some(file) = self.pools_config.swap_paths_cache_file() {
+            tasks.push(tokio::task::spawn(swap_paths_cache_worker(
+                self.client.clone(),
+                self.market.clone().unwrap(),
+                file.clone(),
+                self.cancellation_token.clone(),
+            )));
+        }
+
+        if let Some(block_logs_rx) = self.block_logs_rx.clone() {
+            tasks.push(tokio::task::spawn(pool_manager_events_worker(
+                self.market.clone().unwrap(),
+                self.market_state.clone().unwrap(),
+                block_logs_rx,
+                self.cancellation_token.clone(),
+            )));
+        }
+
+        Ok(tasks)
     }
 
     fn name(&self) -> &'static str {
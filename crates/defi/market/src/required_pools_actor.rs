@@ -1,11 +1,16 @@
 use alloy_network::Network;
 use alloy_primitives::Address;
 use alloy_provider::Provider;
+use eyre::{Report, Result};
 use revm::DatabaseRef;
 use revm::{Database, DatabaseCommit};
+use std::future::Future;
 use std::marker::PhantomData;
 use std::sync::Arc;
-use tracing::{debug, error, info};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tracing::{debug, error, info, warn};
 
 use crate::pool_loader_actor::fetch_and_add_pool_by_pool_id;
 use loom_core_actors::{Accessor, Actor, ActorResult, SharedState, WorkerResult};
@@ -15,10 +20,85 @@ use loom_node_debug_provider::DebugProviderExt;
 use loom_types_entities::required_state::{RequiredState, RequiredStateReader};
 use loom_types_entities::{Market, MarketState, PoolClass, PoolId, PoolLoaders};
 
+// Pools loaded concurrently as one batch
+const REQUIRED_POOLS_CHUNK_SIZE: usize = 20;
+const DEFAULT_MAX_CONCURRENCY: usize = 4;
+const CHUNK_MAX_RETRIES: u32 = 3;
+const CHUNK_RETRY_BASE_DELAY_MS: u64 = 200;
+
+/// Load all items of the chunk concurrently, retry failed items with exponential backoff.
+/// Returns the items that still fail after all retries.
+async fn load_chunk_with_retry<T, F, Fut>(chunk: Vec<T>, load: F) -> Vec<(T, Report)>
+where
+    T: Clone + Send + 'static,
+    F: Fn(T) -> Fut,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    let mut pending = chunk;
+    let mut attempt = 0;
+    loop {
+        let mut join_set = JoinSet::new();
+        for item in pending {
+            let fut = load(item.clone());
+            join_set.spawn(async move { (item, fut.await) });
+        }
+
+        let mut failed = Vec::new();
+        while let Some(result) = join_set.join_next().await {
+            match result {
+                Ok((item, Err(error))) => failed.push((item, error)),
+                Ok((_, Ok(_))) => {}
+                Err(error) => error!(%error, "Required pool load task failed"),
+            }
+        }
+
+        if failed.is_empty() || attempt >= CHUNK_MAX_RETRIES {
+            return failed;
+        }
+        attempt += 1;
+        let delay = CHUNK_RETRY_BASE_DELAY_MS * 2u64.pow(attempt - 1);
+        warn!(failed = failed.len(), attempt, delay, "Retrying required pools");
+        tokio::time::sleep(Duration::from_millis(delay)).await;
+        pending = failed.into_iter().map(|(item, _)| item).collect();
+    }
+}
+
+/// Split `items` into chunks of `chunk_size` and load at most `max_concurrency` chunks at once.
+/// Returns the items failed after all retries.
+async fn load_in_chunks<T, F, Fut>(items: Vec<T>, chunk_size: usize, max_concurrency: usize, load: F) -> Vec<(T, Report)>
+where
+    T: Clone + Send + 'static,
+    F: Fn(T) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    let semaphore = Arc::new(Semaphore::new(max_concurrency.max(1)));
+    let mut join_set = JoinSet::new();
+
+    for chunk in items.chunks(chunk_size.max(1)) {
+        let chunk = chunk.to_vec();
+        let semaphore = semaphore.clone();
+        let load = load.clone();
+        join_set.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            load_chunk_with_retry(chunk, load).await
+        });
+    }
+
+    let mut failed = Vec::new();
+    while let Some(result) = join_set.join_next().await {
+        match result {
+            Ok(chunk_failed) => failed.extend(chunk_failed),
+            Err(error) => error!(%error, "Required pools chunk task failed"),
+        }
+    }
+    failed
+}
+
 async fn required_pools_loader_worker<P, N, DB>(
     client: P,
     pool_loaders: Arc<PoolLoaders<P, N>>,
     pools: Vec<(PoolId, PoolClass)>,
+    max_concurrency: usize,
     required_state: Option<RequiredState>,
     market: SharedState<Market>,
     market_state: SharedState<MarketState<DB>>,
@@ -28,19 +108,30 @@ where
     P: Provider<N> + DebugProviderExt<N> + Send + Sync + Clone + 'static,
     DB: Database + DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'static,
 {
-    for (pool_id, pool_class) in pools {
-        debug!(class=%pool_class, %pool_id, "Loading pool");
-        match fetch_and_add_pool_by_pool_id(client.clone(), market.clone(), market_state.clone(), pool_loaders.clone(), pool_id, pool_class)
-            .await
-        {
-            Ok(_) => {
-                info!(class=%pool_class, %pool_id, "pool loaded")
-            }
-            Err(error) => {
-                error!(%error, "load_pool_with_provider")
+    let pools_len = pools.len();
+    let load_pool = {
+        let client = client.clone();
+        let market = market.clone();
+        let market_state = market_state.clone();
+        move |(pool_id, pool_class): (PoolId, PoolClass)| {
+            let client = client.clone();
+            let market = market.clone();
+            let market_state = market_state.clone();
+            let pool_loaders = pool_loaders.clone();
+            async move {
+                debug!(class=%pool_class, %pool_id, "Loading pool");
+                fetch_and_add_pool_by_pool_id(client, market, market_state, pool_loaders, pool_id, pool_class).await?;
+                info!(class=%pool_class, %pool_id, "pool loaded");
+                Ok(())
             }
         }
+    };
+
+    let failed = load_in_chunks(pools, REQUIRED_POOLS_CHUNK_SIZE, max_concurrency, load_pool).await;
+    for ((pool_id, pool_class), error) in failed.iter() {
+        error!(%error, class=%pool_class, %pool_id, "load_pool_with_provider");
     }
+    info!(loaded = pools_len - failed.len(), failed = failed.len(), "Required pools loaded");
     //
     //
     //     match pool_class {
@@ -84,6 +175,7 @@ where
     client: P,
     pool_loaders: Arc<PoolLoaders<P, N>>,
     pools: Vec<(PoolId, PoolClass)>,
+    max_concurrency: usize,
     required_state: Option<RequiredState>,
     #[accessor]
     market: Option<SharedState<Market>>,
@@ -99,7 +191,21 @@ where
     DB: Database + DatabaseRef + DatabaseCommit + Clone + Send + Sync + 'static,
 {
     pub fn new(client: P, pool_loaders: Arc<PoolLoaders<P, N>>) -> Self {
-        Self { client, pools: Vec::new(), pool_loaders, required_state: None, market: None, market_state: None, _n: PhantomData }
+        Self {
+            client,
+            pools: Vec::new(),
+            pool_loaders,
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+            required_state: None,
+            market: None,
+            market_state: None,
+            _n: PhantomData,
+        }
+    }
+
+    /// Number of pool chunks loaded at once, tune it by the RPC rate limit
+    pub fn with_max_concurrency(self, max_concurrency: usize) -> Self {
+        Self { max_concurrency, ..self }
     }
 
     pub fn with_pool_address(self, address: Address, pool_class: PoolClass) -> Self {
@@ -128,6 +234,7 @@ where
             self.client.clone(),
             self.pool_loaders.clone(),
             self.pools.clone(),
+            self.max_concurrency,
             self.required_state.clone(),
             self.market.clone().unwrap(),
             self.market_state.clone().unwrap(),
@@ -140,3 +247,55 @@ where
         "RequiredPoolLoaderActor"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eyre::eyre;
+    use rand::Rng;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    #[tokio::test]
+    async fn test_load_in_chunks_with_random_delays() {
+        let attempts: Arc<Mutex<HashMap<usize, u32>>> = Arc::new(Mutex::new(HashMap::new()));
+        let loaded: Arc<Mutex<Vec<usize>>> = Arc::new(Mutex::new(Vec::new()));
+
+        let load = {
+            let attempts = attempts.clone();
+            let loaded = loaded.clone();
+            move |item: usize| {
+                let attempts = attempts.clone();
+                let loaded = loaded.clone();
+                async move {
+                    let delay = rand::thread_rng().gen_range(0..20);
+                    tokio::time::sleep(Duration::from_millis(delay)).await;
+
+                    let attempt = {
+                        let mut attempts = attempts.lock().unwrap();
+                        let entry = attempts.entry(item).or_default();
+                        *entry += 1;
+                        *entry
+                    };
+                    // Every 7th item times out once, item 100 never loads
+                    if item == 100 || (item % 7 == 0 && attempt == 1) {
+                        return Err(eyre!("TIMEOUT"));
+                    }
+                    loaded.lock().unwrap().push(item);
+                    Ok(())
+                }
+            }
+        };
+
+        let failed = load_in_chunks((0..210).collect(), REQUIRED_POOLS_CHUNK_SIZE, 3, load).await;
+
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0, 100);
+        assert_eq!(attempts.lock().unwrap()[&100], CHUNK_MAX_RETRIES + 1);
+        assert_eq!(attempts.lock().unwrap()[&7], 2);
+
+        let mut loaded = loaded.lock().unwrap().clone();
+        loaded.sort();
+        assert_eq!(loaded, (0..210).filter(|item| *item != 100).collect::<Vec<_>>());
+    }
+}
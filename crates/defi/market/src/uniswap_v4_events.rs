@@ -0,0 +1,157 @@
+use alloy_primitives::{keccak256, Address, B256, U256};
+use alloy_rpc_types::Log;
+use alloy_rpc_types_trace::geth::AccountState;
+use alloy_sol_types::SolEvent;
+use loom_defi_abi::uniswap4::IUniswapV4PoolManagerEvents::Swap;
+use loom_defi_address_book::FactoryAddress;
+use loom_types_blockchain::GethStateUpdate;
+use loom_types_entities::{Market, PoolId};
+use revm::DatabaseRef;
+
+// PoolManager storage layout: mapping(PoolId => Pool.State) _pools at slot 6
+const POOLS_SLOT: U256 = U256::from_limbs([6, 0, 0, 0]);
+// Offset of liquidity inside Pool.State (slot0, feeGrowthGlobal0X128, feeGrowthGlobal1X128, liquidity)
+const LIQUIDITY_OFFSET: U256 = U256::from_limbs([3, 0, 0, 0]);
+
+const SQRT_PRICE_BITS: usize = 160;
+const TICK_BITS: usize = 24;
+
+/// Storage slot of Pool.State for the given V4 pool id
+pub fn pool_state_slot(pool_id: B256) -> U256 {
+    let mut buf = [0u8; 64];
+    buf[..32].copy_from_slice(pool_id.as_slice());
+    buf[32..].copy_from_slice(&POOLS_SLOT.to_be_bytes::<32>());
+    keccak256(buf).into()
+}
+
+/// Replace sqrtPriceX96 and tick in a packed slot0 word, protocolFee and lpFee are preserved
+pub fn pack_slot0(current_slot0: U256, sqrt_price_x96: U256, tick: i32) -> U256 {
+    let sqrt_price_mask = (U256::from(1) << SQRT_PRICE_BITS) - U256::from(1);
+    let tick_mask = ((U256::from(1) << TICK_BITS) - U256::from(1)) << SQRT_PRICE_BITS;
+    let fees = current_slot0 & !(sqrt_price_mask | tick_mask);
+
+    let tick_bits = U256::from((tick as u32) & 0x00FF_FFFF) << SQRT_PRICE_BITS;
+
+    fees | tick_bits | (sqrt_price_x96 & sqrt_price_mask)
+}
+
+/// Decode PoolManager `Swap` events for pools known to the market and build storage updates for the PoolManager.
+/// Only slot0 and liquidity are written, ticks are unchanged by a swap that does not cross initialized ticks
+/// and will be refreshed by the regular state update otherwise.
+pub fn pool_manager_swap_state_update<DB: DatabaseRef>(
+    market: &Market,
+    state_db: &DB,
+    logs: &[Log],
+) -> Option<(GethStateUpdate, Vec<PoolId>)> {
+    let pool_manager: Address = FactoryAddress::UNISWAP_V4_POOL_MANAGER_ADDRESS;
+
+    let mut account_state = AccountState::default();
+    let mut updated_pools: Vec<PoolId> = Vec::new();
+
+    for log in logs.iter() {
+        if log.address() != pool_manager {
+            continue;
+        }
+        let Ok(swap) = Swap::decode_log_data(log.data(), false) else {
+            continue;
+        };
+
+        let pool_id = PoolId::Bytes32(swap.id);
+        if !market.is_pool(&pool_id) {
+            continue;
+        }
+
+        let state_slot = pool_state_slot(swap.id);
+        // Take the latest value written in this batch first, db value otherwise
+        let current_slot0: U256 = match account_state.storage.get(&B256::from(state_slot)) {
+            Some(value) => (*value).into(),
+            None => state_db.storage_ref(pool_manager, state_slot).unwrap_or_default(),
+        };
+
+        let slot0 = pack_slot0(current_slot0, U256::from(swap.sqrtPriceX96), swap.tick.as_i32());
+
+        account_state.storage.insert(state_slot.into(), slot0.into());
+        account_state.storage.insert((state_slot + LIQUIDITY_OFFSET).into(), U256::from(swap.liquidity).into());
+
+        if !updated_pools.contains(&pool_id) {
+            updated_pools.push(pool_id);
+        }
+    }
+
+    if updated_pools.is_empty() {
+        return None;
+    }
+
+    let mut update = GethStateUpdate::new();
+    update.insert(pool_manager, account_state);
+    Some((update, updated_pools))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::aliases::{I24, U160, U24};
+    use loom_defi_abi::uniswap4::PoolKey;
+    use loom_defi_pools::UniswapV4Pool;
+    use revm::db::EmptyDB;
+
+    fn swap_log(address: Address, id: B256, sqrt_price_x96: U256, liquidity: u128, tick: i32) -> Log {
+        let swap = Swap {
+            id: id.into(),
+            sender: Address::repeat_byte(0x22),
+            amount0: -1000,
+            amount1: 990,
+            sqrtPriceX96: U160::from(sqrt_price_x96),
+            liquidity,
+            tick: I24::try_from(tick).unwrap(),
+            fee: U24::from(3000),
+        };
+        Log { inner: alloy_primitives::Log { address, data: swap.encode_log_data() }, ..Log::default() }
+    }
+
+    #[test]
+    fn test_pack_slot0() {
+        let fees = U256::from(0xABCDEFu64) << (SQRT_PRICE_BITS + TICK_BITS);
+        let sqrt_price_x96 = U256::from(1u64) << 96;
+
+        let slot0 = pack_slot0(fees | U256::from(12345), sqrt_price_x96, -1);
+        assert_eq!(slot0 & ((U256::from(1) << SQRT_PRICE_BITS) - U256::from(1)), sqrt_price_x96);
+        assert_eq!((slot0 >> SQRT_PRICE_BITS) & U256::from(0x00FF_FFFF), U256::from(0x00FF_FFFF));
+        assert_eq!(slot0 >> (SQRT_PRICE_BITS + TICK_BITS), U256::from(0xABCDEFu64));
+    }
+
+    #[test]
+    fn test_pool_manager_swap_state_update() {
+        let pool = UniswapV4Pool::from_pool_key(PoolKey {
+            currency0: Address::ZERO,
+            currency1: Address::repeat_byte(0x11),
+            fee: U24::from(3000),
+            tickSpacing: I24::try_from(60).unwrap(),
+            hooks: Address::ZERO,
+        });
+        let pool_id = pool.pool_id;
+        let mut market = Market::default();
+        market.add_pool(pool).unwrap();
+
+        let pool_manager = FactoryAddress::UNISWAP_V4_POOL_MANAGER_ADDRESS;
+        let sqrt_price_x96 = U256::from(1u64) << 96;
+        let logs = vec![
+            swap_log(pool_manager, B256::repeat_byte(0x33), sqrt_price_x96, 1, 1),
+            swap_log(Address::repeat_byte(0x44), pool_id, sqrt_price_x96, 1, 1),
+            swap_log(pool_manager, pool_id, sqrt_price_x96, 500, -10),
+            swap_log(pool_manager, pool_id, sqrt_price_x96 * U256::from(2), 700, 13863),
+        ];
+
+        let (update, updated_pools) = pool_manager_swap_state_update(&market, &EmptyDB::default(), &logs).unwrap();
+        assert_eq!(updated_pools, vec![PoolId::Bytes32(pool_id)]);
+
+        // The last swap of the pool wins
+        let storage = &update.get(&pool_manager).unwrap().storage;
+        assert_eq!(storage.len(), 2);
+        let state_slot = pool_state_slot(pool_id);
+        assert_eq!(U256::from_be_bytes(storage[&B256::from(state_slot)].0), pack_slot0(U256::ZERO, sqrt_price_x96 * U256::from(2), 13863));
+        assert_eq!(U256::from_be_bytes(storage[&B256::from(state_slot + LIQUIDITY_OFFSET)].0), U256::from(700));
+
+        assert!(pool_manager_swap_state_update(&market, &EmptyDB::default(), &logs[..2]).is_none());
+    }
+}
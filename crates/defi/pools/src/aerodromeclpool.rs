@@ -0,0 +1,253 @@
+use std::any::Any;
+
+use crate::state_readers::{ERC20StateReader, UniswapV3StateReader};
+use crate::uniswapv3pool::UniswapV3AbiSwapEncoder;
+use crate::UniswapV3Pool;
+use alloy::primitives::aliases::{I24, U24};
+use alloy::primitives::{Address, U256};
+use alloy::providers::{Network, Provider};
+use alloy::sol_types::SolCall;
+use eyre::{eyre, ErrReport, Result};
+use loom_defi_abi::aerodrome::{IAerodromeCLPool, IAerodromeCLQuoterV2};
+use loom_defi_abi::IERC20;
+use loom_defi_address_book::PeripheryAddress;
+use loom_evm_utils::evm::evm_call;
+use loom_types_entities::required_state::RequiredState;
+use loom_types_entities::{Pool, PoolAbiEncoder, PoolClass, PoolId, PoolProtocol, PreswapRequirement, SwapDirection};
+use revm::primitives::Env;
+use revm::DatabaseRef;
+
+/// Aerodrome Slipstream concentrated liquidity pool.
+/// Swap and callback interfaces are the same as Uniswap V3, fee tier is not bound to tick spacing.
+#[derive(Clone)]
+pub struct AerodromeCLPool {
+    address: Address,
+    pub token0: Address,
+    pub token1: Address,
+    liquidity0: U256,
+    liquidity1: U256,
+    fee: U24,
+    tick_spacing: I24,
+    factory: Address,
+    quoter: Address,
+    encoder: UniswapV3AbiSwapEncoder,
+}
+
+impl AerodromeCLPool {
+    pub fn factory(&self) -> Address {
+        self.factory
+    }
+
+    pub fn tick_spacing(&self) -> I24 {
+        self.tick_spacing
+    }
+
+    pub fn with_quoter(self, quoter: Address) -> Self {
+        Self { quoter, ..self }
+    }
+
+    pub async fn fetch_pool_data<N: Network, P: Provider<N> + Send + Sync + Clone + 'static>(client: P, address: Address) -> Result<Self> {
+        let pool = IAerodromeCLPool::IAerodromeCLPoolInstance::new(address, client.clone());
+
+        let token0: Address = pool.token0().call().await?._0;
+        let token1: Address = pool.token1().call().await?._0;
+        let fee = pool.fee().call().await?._0;
+        let tick_spacing = pool.tickSpacing().call().await?._0;
+        let factory: Address = pool.factory().call().await?._0;
+
+        let token0_erc20 = IERC20::IERC20Instance::new(token0, client.clone());
+        let token1_erc20 = IERC20::IERC20Instance::new(token1, client.clone());
+
+        let liquidity0: U256 = token0_erc20.balanceOf(address).call().await?._0;
+        let liquidity1: U256 = token1_erc20.balanceOf(address).call().await?._0;
+
+        Ok(AerodromeCLPool {
+            address,
+            token0,
+            token1,
+            liquidity0,
+            liquidity1,
+            fee,
+            tick_spacing,
+            factory,
+            quoter: PeripheryAddress::AERODROME_CL_QUOTER,
+            encoder: UniswapV3AbiSwapEncoder::new(address),
+        })
+    }
+
+    /// Pool data read from `db`, token and fee getters are the same as Uniswap V3
+    pub fn fetch_pool_data_evm(db: &dyn DatabaseRef<Error = ErrReport>, env: Env, address: Address) -> Result<Self> {
+        let token0 = UniswapV3StateReader::token0(&db, env.clone(), address)?;
+        let token1 = UniswapV3StateReader::token1(&db, env.clone(), address)?;
+        let fee = UniswapV3StateReader::fee(&db, env.clone(), address)?;
+        let factory = UniswapV3StateReader::factory(&db, env.clone(), address)?;
+        let call_data_result = evm_call(&db, env.clone(), address, IAerodromeCLPool::tickSpacingCall {}.abi_encode())?.0;
+        let tick_spacing = IAerodromeCLPool::tickSpacingCall::abi_decode_returns(&call_data_result, false)?._0;
+
+        let liquidity0 = ERC20StateReader::balance_of(&db, env.clone(), token0, address)?;
+        let liquidity1 = ERC20StateReader::balance_of(&db, env, token1, address)?;
+
+        Ok(AerodromeCLPool {
+            address,
+            token0,
+            token1,
+            liquidity0,
+            liquidity1,
+            fee,
+            tick_spacing,
+            factory,
+            quoter: PeripheryAddress::AERODROME_CL_QUOTER,
+            encoder: UniswapV3AbiSwapEncoder::new(address),
+        })
+    }
+
+    fn quote_exact_input_call(&self, token_from: Address, token_to: Address, amount: U256) -> Vec<u8> {
+        IAerodromeCLQuoterV2::quoteExactInputSingleCall {
+            params: IAerodromeCLQuoterV2::QuoteExactInputSingleParams {
+                tokenIn: token_from,
+                tokenOut: token_to,
+                amountIn: amount,
+                tickSpacing: self.tick_spacing,
+                sqrtPriceLimitX96: UniswapV3Pool::get_price_limit(&token_from, &token_to),
+            },
+        }
+        .abi_encode()
+    }
+}
+
+impl Pool for AerodromeCLPool {
+    fn as_any<'a>(&self) -> &dyn Any {
+        self
+    }
+
+    fn get_class(&self) -> PoolClass {
+        PoolClass::UniswapV3
+    }
+
+    fn get_protocol(&self) -> PoolProtocol {
+        PoolProtocol::AerodromeCL
+    }
+
+    fn get_address(&self) -> Address {
+        self.address
+    }
+
+    fn get_pool_id(&self) -> PoolId {
+        PoolId::Address(self.address)
+    }
+
+    fn get_fee(&self) -> U256 {
+        U256::from(self.fee)
+    }
+
+    fn get_tokens(&self) -> Vec<Address> {
+        vec![self.token0, self.token1]
+    }
+
+    fn get_swap_directions(&self) -> Vec<SwapDirection> {
+        vec![(self.token0, self.token1).into(), (self.token1, self.token0).into()]
+    }
+
+    fn calculate_out_amount(
+        &self,
+        state_db: &dyn DatabaseRef<Error = ErrReport>,
+        env: Env,
+        token_address_from: &Address,
+        token_address_to: &Address,
+        in_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        let mut env = env;
+        env.tx.gas_limit = 1_000_000;
+
+        let call_data = self.quote_exact_input_call(*token_address_from, *token_address_to, in_amount);
+
+        let (value, gas_used) = evm_call(state_db, env, self.quoter, call_data)?;
+
+        let ret = IAerodromeCLQuoterV2::quoteExactInputSingleCall::abi_decode_returns(&value, false)?;
+
+        if ret.amountOut.is_zero() {
+            Err(eyre!("ZERO_OUT_AMOUNT"))
+        } else {
+            Ok((ret.amountOut - U256::from(1), gas_used))
+        }
+    }
+
+    fn calculate_in_amount(
+        &self,
+        state_db: &dyn DatabaseRef<Error = ErrReport>,
+        env: Env,
+        token_address_from: &Address,
+        token_address_to: &Address,
+        out_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        let mut env = env;
+        env.tx.gas_limit = 1_000_000;
+
+        let call_data = IAerodromeCLQuoterV2::quoteExactOutputSingleCall {
+            params: IAerodromeCLQuoterV2::QuoteExactOutputSingleParams {
+                tokenIn: *token_address_from,
+                tokenOut: *token_address_to,
+                amount: out_amount,
+                tickSpacing: self.tick_spacing,
+                sqrtPriceLimitX96: UniswapV3Pool::get_price_limit(token_address_from, token_address_to),
+            },
+        }
+        .abi_encode();
+
+        let (value, gas_used) = evm_call(state_db, env, self.quoter, call_data)?;
+
+        let ret = IAerodromeCLQuoterV2::quoteExactOutputSingleCall::abi_decode_returns(&value, false)?;
+
+        if ret.amountIn.is_zero() {
+            Err(eyre!("ZERO_IN_AMOUNT"))
+        } else {
+            Ok((ret.amountIn + U256::from(1), gas_used))
+        }
+    }
+
+    fn can_flash_swap(&self) -> bool {
+        true
+    }
+
+    fn can_calculate_in_amount(&self) -> bool {
+        true
+    }
+
+    fn supports_exact_output(&self) -> bool {
+        true
+    }
+
+    fn get_abi_encoder(&self) -> Option<&dyn PoolAbiEncoder> {
+        Some(&self.encoder)
+    }
+
+    fn get_read_only_cell_vec(&self) -> Vec<U256> {
+        Vec::new()
+    }
+
+    fn get_state_required(&self) -> Result<RequiredState> {
+        let pool_address = self.get_address();
+        let mut state_required = RequiredState::new();
+
+        // Quotes of 1% of the balances touch the ticks crossed by typical swaps
+        state_required
+            .add_call(pool_address, IAerodromeCLPool::slot0Call {}.abi_encode())
+            .add_call(pool_address, IAerodromeCLPool::liquidityCall {}.abi_encode())
+            .add_call(self.quoter, self.quote_exact_input_call(self.token0, self.token1, self.liquidity0 / U256::from(100)))
+            .add_call(self.quoter, self.quote_exact_input_call(self.token1, self.token0, self.liquidity1 / U256::from(100)))
+            .add_slot_range(pool_address, U256::from(0), 0x20);
+
+        for token_address in self.get_tokens() {
+            state_required.add_call(token_address, IERC20::balanceOfCall { account: pool_address }.abi_encode());
+        }
+        Ok(state_required)
+    }
+
+    fn is_native(&self) -> bool {
+        false
+    }
+
+    fn preswap_requirement(&self) -> PreswapRequirement {
+        PreswapRequirement::Callback
+    }
+}
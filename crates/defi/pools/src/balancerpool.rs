@@ -0,0 +1,334 @@
+use std::any::Any;
+
+use alloy::primitives::{Address, B256, U256};
+use alloy::providers::{Network, Provider};
+use alloy::sol_types::SolCall;
+use eyre::{eyre, ErrReport, OptionExt, Result};
+use loom_defi_abi::balancer::{IBalancerPool, IVault};
+use loom_defi_abi::IERC20;
+use loom_defi_address_book::FactoryAddress;
+use loom_evm_utils::evm::evm_call;
+use loom_types_entities::required_state::RequiredState;
+use loom_types_entities::{Pool, PoolAbiEncoder, PoolClass, PoolId, PoolProtocol, PreswapRequirement, SwapDirection};
+use revm::primitives::Env;
+use revm::DatabaseRef;
+
+use crate::virtual_impl::{BalancerPoolKind, BalancerPoolParams, BalancerPoolVirtual};
+
+const WEIGHTED_GAS_ESTIMATE: u64 = 120_000;
+const STABLE_GAS_ESTIMATE: u64 = 150_000;
+
+/// Balancer V2 weighted or stable pool. Balances are held by the vault, swaps are calculated with the pool math
+/// from vault balances in the state db and sent to the vault as single swaps.
+#[derive(Clone)]
+pub struct BalancerPool {
+    address: Address,
+    pool_id: B256,
+    vault: Address,
+    pub tokens: Vec<Address>,
+    pub balances: Vec<U256>,
+    params: BalancerPoolParams,
+}
+
+impl BalancerPool {
+    pub fn new(address: Address, pool_id: B256, tokens: Vec<Address>, balances: Vec<U256>, params: BalancerPoolParams) -> Self {
+        Self { address, pool_id, vault: FactoryAddress::BALANCER_V2_VAULT, tokens, balances, params }
+    }
+
+    pub fn with_vault(self, vault: Address) -> Self {
+        Self { vault, ..self }
+    }
+
+    pub fn params(&self) -> &BalancerPoolParams {
+        &self.params
+    }
+
+    pub fn is_stable(&self) -> bool {
+        matches!(self.params.kind, BalancerPoolKind::Stable { .. })
+    }
+
+    /// Weighted pools are identified by `getNormalizedWeights`, stable pools by `getAmplificationParameter`
+    pub async fn fetch_pool_data<N: Network, P: Provider<N> + Send + Sync + Clone + 'static>(client: P, pool_id: B256) -> Result<Self> {
+        let vault = IVault::IVaultInstance::new(FactoryAddress::BALANCER_V2_VAULT, client.clone());
+        let address = vault.getPool(pool_id).call().await?._0;
+        let pool_tokens = vault.getPoolTokens(pool_id).call().await?;
+
+        // Composable pools hold their own BPT, the invariant excludes it
+        if pool_tokens.tokens.contains(&address) {
+            return Err(eyre!("BALANCER_COMPOSABLE_POOL_NOT_SUPPORTED"));
+        }
+
+        let pool = IBalancerPool::IBalancerPoolInstance::new(address, client.clone());
+        let fee = pool.getSwapFeePercentage().call().await?._0;
+
+        let kind = match pool.getNormalizedWeights().call().await {
+            Ok(weights) => BalancerPoolKind::Weighted { weights: weights._0 },
+            Err(_) => {
+                let amp = pool.getAmplificationParameter().call().await.map_err(|_| eyre!("BALANCER_POOL_TYPE_NOT_SUPPORTED"))?;
+                BalancerPoolKind::Stable { amp: amp.value }
+            }
+        };
+
+        let scaling_factors = match pool.getScalingFactors().call().await {
+            Ok(scaling_factors) => scaling_factors._0,
+            Err(_) => {
+                let mut scaling_factors = Vec::new();
+                for token in pool_tokens.tokens.iter() {
+                    let decimals = IERC20::IERC20Instance::new(*token, client.clone()).decimals().call().await?._0;
+                    let decimals_diff = U256::from(18).checked_sub(decimals).ok_or_eyre("BAD_TOKEN_DECIMALS")?;
+                    scaling_factors.push(U256::from(10).pow(U256::from(18) + decimals_diff));
+                }
+                scaling_factors
+            }
+        };
+
+        Ok(BalancerPool::new(address, pool_id, pool_tokens.tokens, pool_tokens.balances, BalancerPoolParams { kind, scaling_factors, fee }))
+    }
+
+    fn get_token_idx(&self, address: &Address) -> Result<usize> {
+        self.tokens.iter().position(|token| token == address).ok_or_eyre("TOKEN_NOT_FOUND")
+    }
+
+    fn get_pool_tokens_call(&self) -> Vec<u8> {
+        IVault::getPoolTokensCall { poolId: self.pool_id }.abi_encode()
+    }
+
+    fn fetch_balances_from_db(&self, state_db: &dyn DatabaseRef<Error = ErrReport>, env: Env) -> Result<Vec<U256>> {
+        let (value, _gas_used) = evm_call(state_db, env, self.vault, self.get_pool_tokens_call())?;
+        let pool_tokens = IVault::getPoolTokensCall::abi_decode_returns(&value, false)?;
+        if pool_tokens.balances.len() != self.tokens.len() {
+            return Err(eyre!("CANNOT_GET_BALANCER_BALANCES"));
+        }
+        Ok(pool_tokens.balances)
+    }
+
+    fn gas_estimate(&self) -> u64 {
+        if self.is_stable() {
+            STABLE_GAS_ESTIMATE
+        } else {
+            WEIGHTED_GAS_ESTIMATE
+        }
+    }
+}
+
+impl Pool for BalancerPool {
+    fn as_any<'a>(&self) -> &dyn Any {
+        self
+    }
+
+    fn get_class(&self) -> PoolClass {
+        PoolClass::BalancerV2
+    }
+
+    fn get_protocol(&self) -> PoolProtocol {
+        PoolProtocol::BalancerV2
+    }
+
+    fn get_address(&self) -> Address {
+        self.address
+    }
+
+    fn get_pool_id(&self) -> PoolId {
+        PoolId::Bytes32(self.pool_id)
+    }
+
+    fn get_fee(&self) -> U256 {
+        self.params.fee
+    }
+
+    fn get_tokens(&self) -> Vec<Address> {
+        self.tokens.clone()
+    }
+
+    fn get_swap_directions(&self) -> Vec<SwapDirection> {
+        let mut ret: Vec<SwapDirection> = Vec::new();
+        for token_from in self.tokens.iter() {
+            for token_to in self.tokens.iter() {
+                if token_from != token_to {
+                    ret.push((*token_from, *token_to).into());
+                }
+            }
+        }
+        ret
+    }
+
+    fn calculate_out_amount(
+        &self,
+        state_db: &dyn DatabaseRef<Error = ErrReport>,
+        env: Env,
+        token_address_from: &Address,
+        token_address_to: &Address,
+        in_amount: U256,
+    ) -> Result<(U256, u64)> {
+        let mut env = env;
+        env.tx.gas_limit = 500_000;
+
+        let i = self.get_token_idx(token_address_from)?;
+        let j = self.get_token_idx(token_address_to)?;
+        let balances = self.fetch_balances_from_db(state_db, env)?;
+
+        let ret = BalancerPoolVirtual::calc_out_given_in(&self.params, &balances, i, j, in_amount)?;
+        if ret.is_zero() {
+            Err(eyre!("ZERO_OUT_AMOUNT"))
+        } else {
+            Ok((ret, self.gas_estimate()))
+        }
+    }
+
+    fn calculate_in_amount(
+        &self,
+        state_db: &dyn DatabaseRef<Error = ErrReport>,
+        env: Env,
+        token_address_from: &Address,
+        token_address_to: &Address,
+        out_amount: U256,
+    ) -> Result<(U256, u64)> {
+        let mut env = env;
+        env.tx.gas_limit = 500_000;
+
+        let i = self.get_token_idx(token_address_from)?;
+        let j = self.get_token_idx(token_address_to)?;
+        let balances = self.fetch_balances_from_db(state_db, env)?;
+
+        let ret = BalancerPoolVirtual::calc_in_given_out(&self.params, &balances, i, j, out_amount)?;
+        if ret.is_zero() {
+            Err(eyre!("ZERO_IN_AMOUNT"))
+        } else {
+            Ok((ret, self.gas_estimate()))
+        }
+    }
+
+    fn can_flash_swap(&self) -> bool {
+        false
+    }
+
+    fn can_calculate_in_amount(&self) -> bool {
+        true
+    }
+
+    fn get_abi_encoder(&self) -> Option<&dyn PoolAbiEncoder> {
+        None
+    }
+
+    fn get_read_only_cell_vec(&self) -> Vec<U256> {
+        Vec::new()
+    }
+
+    fn get_state_required(&self) -> Result<RequiredState> {
+        let mut state_required = RequiredState::new();
+        state_required.add_call(self.vault, self.get_pool_tokens_call());
+
+        for token_address in self.get_tokens() {
+            state_required.add_call(token_address, IERC20::balanceOfCall { account: self.vault }.abi_encode());
+        }
+        Ok(state_required)
+    }
+
+    fn is_native(&self) -> bool {
+        false
+    }
+
+    fn preswap_requirement(&self) -> PreswapRequirement {
+        PreswapRequirement::Allowance
+    }
+
+    fn get_state_addresses(&self) -> Vec<Address> {
+        vec![self.vault]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use eyre::Result;
+
+    use alloy::primitives::{b256, U256};
+    use alloy::providers::network::primitives::BlockTransactionsKind;
+    use alloy::providers::Provider;
+    use alloy::rpc::types::BlockNumberOrTag;
+    use env_logger::Env as EnvLog;
+    use loom_defi_abi::balancer::IVault;
+    use loom_defi_address_book::FactoryAddress;
+    use loom_evm_db::{DatabaseLoomExt, LoomDBType};
+    use loom_node_debug_provider::AnvilDebugProviderFactory;
+    use loom_types_entities::required_state::RequiredStateReader;
+    use loom_types_entities::{MarketState, Pool};
+    use tracing::debug;
+
+    use crate::BalancerPool;
+
+    #[tokio::test]
+    async fn test_pools() -> Result<()> {
+        let _ = env_logger::try_init_from_env(EnvLog::default().default_filter_or("info,alloy_rpc_client=off"));
+
+        let node_url = std::env::var("MAINNET_WS")?;
+
+        let client = AnvilDebugProviderFactory::from_node_on_block(node_url, 20045799).await?;
+
+        let block_header = client.get_block_by_number(BlockNumberOrTag::Latest, BlockTransactionsKind::Hashes).await?.unwrap().header;
+        let mut evm_env = revm::primitives::Env::default();
+        evm_env.block.number = U256::from(block_header.number);
+        evm_env.block.timestamp = U256::from(block_header.timestamp);
+
+        let vault = IVault::IVaultInstance::new(FactoryAddress::BALANCER_V2_VAULT, client.clone());
+
+        let pool_ids = [
+            // B-80BAL-20WETH weighted pool
+            b256!("5c6ee304399dbdb9c8ef030ab642b10820db8f56000200000000000000000014"),
+            // staBAL3 DAI/USDC/USDT stable pool
+            b256!("06df3b2bbb68adc8b0e302443692037ed9f91b42000000000000000000000063"),
+        ];
+
+        for pool_id in pool_ids {
+            let pool = BalancerPool::fetch_pool_data(client.clone(), pool_id).await?;
+
+            let mut market_state = MarketState::new(LoomDBType::new());
+            let state_required = pool.get_state_required()?;
+            let state_required = RequiredStateReader::fetch_calls_and_slots(client.clone(), state_required, None).await?;
+            market_state.state_db.apply_geth_update(state_required);
+
+            let tokens = pool.tokens.clone();
+            for i in 0..tokens.len() {
+                for j in 0..tokens.len() {
+                    if i == j {
+                        continue;
+                    }
+                    let in_amount = pool.balances[i] / U256::from(1000);
+                    let (out_amount, _gas_used) =
+                        pool.calculate_out_amount(&market_state.state_db, evm_env.clone(), &tokens[i], &tokens[j], in_amount)?;
+
+                    // Vault query runs the pool contract, the same reference the Balancer SDK uses
+                    let deltas = vault
+                        .queryBatchSwap(
+                            IVault::SwapKind::GIVEN_IN,
+                            vec![IVault::BatchSwapStep {
+                                poolId: pool_id,
+                                assetInIndex: U256::ZERO,
+                                assetOutIndex: U256::from(1),
+                                amount: in_amount,
+                                userData: Default::default(),
+                            }],
+                            vec![tokens[i], tokens[j]],
+                            IVault::FundManagement {
+                                sender: Default::default(),
+                                fromInternalBalance: false,
+                                recipient: Default::default(),
+                                toInternalBalance: false,
+                            },
+                        )
+                        .call()
+                        .await?
+                        .assetDeltas;
+                    let out_amount_fetched = deltas[1].unsigned_abs();
+                    debug!("Balancer {} -> {} : {} -> {} fetched {}", tokens[i], tokens[j], in_amount, out_amount, out_amount_fetched);
+                    // Pool versions differ in rounding of the fee and the invariant
+                    assert!(out_amount.abs_diff(out_amount_fetched) <= out_amount_fetched / U256::from(1_000_000_000) + U256::from(1));
+
+                    let (back_amount, _gas_used) =
+                        pool.calculate_in_amount(&market_state.state_db, evm_env.clone(), &tokens[i], &tokens[j], out_amount)?;
+                    assert!(back_amount.abs_diff(in_amount) <= in_amount / U256::from(1_000_000));
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -6,6 +6,7 @@ use alloy::providers::{Network, Provider};
 use alloy::sol_types::SolCall;
 use eyre::{eyre, ErrReport, OptionExt, Result};
 use lazy_static::lazy_static;
+use loom_defi_abi::curve::{ICurveCommon, ICurveCommonI128};
 use loom_defi_abi::IERC20;
 use loom_defi_address_book::TokenAddressEth;
 use loom_evm_utils::evm::evm_call;
@@ -16,11 +17,15 @@ use revm::DatabaseRef;
 use tracing::error;
 
 use crate::protocols::{CurveCommonContract, CurveContract, CurveProtocol};
+use crate::virtual_impl::{CurvePoolVirtual, CurveStableSwapParams};
 
 lazy_static! {
     static ref U256_ONE: U256 = U256::from(1);
 }
 
+/// Gas used by a plain StableSwap `get_dy`, returned when calculated without EVM
+const STABLESWAP_GAS_ESTIMATE: u64 = 100_000;
+
 pub struct CurvePool<P, N, E = CurvePoolAbiEncoder<P, N>>
 where
     N: Network,
@@ -36,6 +41,7 @@ where
     abi_encoder: Option<Arc<E>>,
     is_meta: bool,
     is_native: bool,
+    stableswap_params: Option<CurveStableSwapParams>,
 }
 
 impl<P, N, E> Clone for CurvePool<P, N, E>
@@ -55,6 +61,7 @@ where
             abi_encoder: self.abi_encoder.clone(),
             is_meta: self.is_meta,
             is_native: self.is_native,
+            stableswap_params: self.stableswap_params.clone(),
         }
     }
 }
@@ -80,6 +87,45 @@ where
         Self { abi_encoder: Some(Arc::new(e)), ..self }
     }
 
+    /// Enable analytical StableSwap calculation for plain coin swaps
+    pub fn with_stableswap_params(self, stableswap_params: CurveStableSwapParams) -> Self {
+        Self { stableswap_params: Some(stableswap_params), ..self }
+    }
+
+    pub fn stableswap_params(&self) -> Option<&CurveStableSwapParams> {
+        self.stableswap_params.as_ref()
+    }
+
+    fn fetch_balance_from_db(&self, state_db: &dyn DatabaseRef<Error = ErrReport>, env: Env, coin_id: usize) -> Result<U256> {
+        let call_data = ICurveCommon::balancesCall { _0: U256::from(coin_id) }.abi_encode();
+        let value = match evm_call(state_db, env.clone(), self.get_address(), call_data) {
+            Ok((value, _)) => value,
+            Err(_) => {
+                let call_data = ICurveCommonI128::balancesCall { _0: coin_id as i128 }.abi_encode();
+                evm_call(state_db, env, self.get_address(), call_data)?.0
+            }
+        };
+        if value.len() < 32 {
+            return Err(eyre!("CANNOT_GET_CURVE_BALANCE"));
+        }
+        Ok(U256::from_be_slice(&value[0..32]))
+    }
+
+    /// StableSwap `get_dy` with balances read from the state db
+    fn calculate_stableswap_out_amount(
+        &self,
+        state_db: &dyn DatabaseRef<Error = ErrReport>,
+        env: Env,
+        params: &CurveStableSwapParams,
+        i: usize,
+        j: usize,
+        in_amount: U256,
+    ) -> Result<U256> {
+        let balances: Vec<U256> =
+            (0..self.tokens.len()).map(|coin_id| self.fetch_balance_from_db(state_db, env.clone(), coin_id)).collect::<Result<_>>()?;
+        CurvePoolVirtual::get_dy(i, j, in_amount, &balances, params)
+    }
+
     pub fn get_meta_coin_idx(&self, address: Address) -> Result<u32> {
         match self.get_coin_idx(address) {
             Ok(i) => Ok(i),
@@ -159,6 +205,7 @@ where
             lp_token,
             is_meta,
             is_native,
+            stableswap_params: None,
         })
     }
 }
@@ -204,6 +251,7 @@ where
             lp_token,
             is_meta,
             is_native,
+            stableswap_params: None,
         };
 
         let abi_encoder = Arc::new(CurvePoolAbiEncoder::new(&pool));
@@ -284,6 +332,18 @@ where
         let mut env = env;
         env.tx.gas_limit = 500_000;
 
+        // Plain coin swaps of registry pools are calculated with the StableSwap invariant
+        if let (false, Some(params)) = (self.is_meta, &self.stableswap_params) {
+            if let (Ok(i), Ok(j)) = (self.get_coin_idx(*token_address_from), self.get_coin_idx(*token_address_to)) {
+                let ret = self.calculate_stableswap_out_amount(state_db, env.clone(), params, i as usize, j as usize, in_amount)?;
+                return if ret.is_zero() {
+                    Err(eyre!("ZERO_OUT_AMOUNT"))
+                } else {
+                    Ok((ret.checked_sub(*U256_ONE).ok_or_eyre("SUB_OVERFLOWN")?, STABLESWAP_GAS_ESTIMATE))
+                };
+            }
+        }
+
         let call_data = if self.is_meta {
             let i: Result<u32> = self.get_coin_idx(*token_address_from);
             let j: Result<u32> = self.get_coin_idx(*token_address_to);
@@ -594,7 +654,7 @@ where
 mod tests {
     use eyre::Result;
 
-    use alloy::primitives::U256;
+    use alloy::primitives::{address, U256};
     use alloy::providers::network::primitives::BlockTransactionsKind;
     use alloy::providers::Provider;
     use alloy::rpc::types::BlockNumberOrTag;
@@ -608,6 +668,58 @@ mod tests {
     use crate::protocols::CurveProtocol;
     use crate::CurvePool;
 
+    #[tokio::test]
+    async fn test_stableswap_pool() -> Result<()> {
+        let _ = env_logger::try_init_from_env(EnvLog::default().default_filter_or("info,alloy_rpc_client=off"));
+
+        let node_url = std::env::var("MAINNET_WS")?;
+
+        let client = AnvilDebugProviderFactory::from_node_on_block(node_url, 20045799).await?;
+
+        let mut market_state = MarketState::new(LoomDBType::new());
+
+        // 3pool
+        let pool_address = address!("bEbc44782C7dB0a1A60Cb6fe97d0b483032FF1C7");
+        let curve_contract = CurveProtocol::get_contract_from_code(client.clone(), pool_address).await?;
+        let stableswap_params = CurveProtocol::get_stableswap_params(client.clone(), pool_address).await?;
+        assert_eq!(stableswap_params.rates.len(), 3);
+
+        let pool = CurvePool::fetch_pool_data_with_default_encoder(client.clone(), curve_contract)
+            .await?
+            .with_stableswap_params(stableswap_params);
+
+        let state_required = pool.get_state_required()?;
+        let state_required = RequiredStateReader::fetch_calls_and_slots(client.clone(), state_required, None).await?;
+        market_state.state_db.apply_geth_update(state_required);
+
+        let block_header = client.get_block_by_number(BlockNumberOrTag::Latest, BlockTransactionsKind::Hashes).await?.unwrap().header;
+        let mut evm_env = revm::primitives::Env::default();
+        evm_env.block.number = U256::from(block_header.number);
+        evm_env.block.timestamp = U256::from(block_header.timestamp);
+
+        let tokens = pool.tokens.clone();
+        let balances = pool.balances.clone();
+        for i in 0..tokens.len() {
+            for j in 0..tokens.len() {
+                if i == j {
+                    continue;
+                }
+                let in_amount = balances[i] / U256::from(100);
+                let (out_amount, _gas_used) =
+                    pool.calculate_out_amount(&market_state.state_db, evm_env.clone(), &tokens[i], &tokens[j], in_amount)?;
+                let out_amount_fetched = pool.fetch_out_amount(tokens[i], tokens[j], in_amount).await?;
+                debug!("StableSwap {} -> {} : {} -> {} fetched {}", tokens[i], tokens[j], in_amount, out_amount, out_amount_fetched);
+                assert_eq!(out_amount, out_amount_fetched - U256::from(1));
+
+                // Round trip on the same state returns less than swapped in
+                let (back_amount, _gas_used) =
+                    pool.calculate_out_amount(&market_state.state_db, evm_env.clone(), &tokens[j], &tokens[i], out_amount)?;
+                assert!(back_amount < in_amount);
+            }
+        }
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_pool() -> Result<()> {
         let _ = env_logger::try_init_from_env(EnvLog::default().default_filter_or("info,alloy_rpc_client=off"));
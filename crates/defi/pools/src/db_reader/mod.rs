@@ -1,3 +1,5 @@
 pub use uniswapv3::UniswapV3DBReader;
+pub use uniswapv4::{UniswapV4DBReader, UniswapV4Slot0};
 
 mod uniswapv3;
+mod uniswapv4;
@@ -0,0 +1,85 @@
+use std::ops::{Shl, Shr};
+
+use alloy::primitives::{keccak256, Address, Signed, Uint, B256, I256, U256};
+use eyre::Result;
+use lazy_static::lazy_static;
+use loom_defi_address_book::FactoryAddress;
+use loom_evm_utils::remv_db_direct_access::{try_read_cell, try_read_hashmap_cell};
+use revm::DatabaseRef;
+
+// PoolManager storage layout: mapping(PoolId => Pool.State) _pools at slot 6
+const POOLS_SLOT: U256 = U256::from_limbs([6, 0, 0, 0]);
+// Pool.State layout: slot0, feeGrowthGlobal0X128, feeGrowthGlobal1X128, liquidity, ticks, tickBitmap, positions
+const LIQUIDITY_OFFSET: U256 = U256::from_limbs([3, 0, 0, 0]);
+const TICKS_OFFSET: U256 = U256::from_limbs([4, 0, 0, 0]);
+const TICK_BITMAP_OFFSET: U256 = U256::from_limbs([5, 0, 0, 0]);
+
+lazy_static! {
+    static ref BITS160MASK: U256 = U256::from(1).shl(160) - U256::from(1);
+    static ref BITS24MASK: U256 = U256::from(1).shl(24) - U256::from(1);
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct UniswapV4Slot0 {
+    pub sqrt_price_x96: U256,
+    pub tick: i32,
+    pub protocol_fee: u32,
+    pub lp_fee: u32,
+}
+
+/// Direct reader of the V4 PoolManager storage
+pub struct UniswapV4DBReader {}
+
+impl UniswapV4DBReader {
+    /// Storage slot of Pool.State for the given pool id
+    pub fn pool_state_slot(pool_id: B256) -> U256 {
+        let mut buf = [0u8; 64];
+        buf[..32].copy_from_slice(pool_id.as_slice());
+        buf[32..].copy_from_slice(&POOLS_SLOT.to_be_bytes::<32>());
+        keccak256(buf).into()
+    }
+
+    fn pool_manager() -> Address {
+        FactoryAddress::UNISWAP_V4_POOL_MANAGER_ADDRESS
+    }
+
+    pub fn slot0<DB: DatabaseRef>(db: &DB, pool_id: B256) -> Result<UniswapV4Slot0> {
+        let cell = try_read_cell(db, &Self::pool_manager(), &Self::pool_state_slot(pool_id))?;
+
+        let tick: Uint<24, 1> = (cell.shr(160) & *BITS24MASK).to();
+        let tick: Signed<24, 1> = Signed::<24, 1>::from_raw(tick);
+
+        Ok(UniswapV4Slot0 {
+            sqrt_price_x96: cell & *BITS160MASK,
+            tick: tick.as_i32(),
+            protocol_fee: (cell.shr(184) & *BITS24MASK).to(),
+            lp_fee: (cell.shr(208) & *BITS24MASK).to(),
+        })
+    }
+
+    pub fn liquidity<DB: DatabaseRef>(db: &DB, pool_id: B256) -> Result<u128> {
+        let cell = try_read_cell(db, &Self::pool_manager(), &(Self::pool_state_slot(pool_id) + LIQUIDITY_OFFSET))?;
+        Ok(cell.saturating_to())
+    }
+
+    pub fn ticks_liquidity_net<DB: DatabaseRef>(db: &DB, pool_id: B256, tick: i32) -> Result<i128> {
+        let cell = try_read_hashmap_cell(
+            db,
+            &Self::pool_manager(),
+            &(Self::pool_state_slot(pool_id) + TICKS_OFFSET),
+            &U256::from_be_bytes(I256::try_from(tick)?.to_be_bytes::<32>()),
+        )?;
+        let liquidity_net: u128 = cell.shr(128).to();
+        Ok(liquidity_net as i128)
+    }
+
+    pub fn tick_bitmap<DB: DatabaseRef>(db: &DB, pool_id: B256, word: i16) -> Result<U256> {
+        try_read_hashmap_cell(
+            db,
+            &Self::pool_manager(),
+            &(Self::pool_state_slot(pool_id) + TICK_BITMAP_OFFSET),
+            &U256::from_be_bytes(I256::try_from(word)?.to_be_bytes::<32>()),
+        )
+    }
+}
+
@@ -0,0 +1,294 @@
+use std::any::Any;
+
+use alloy::primitives::{Address, Bytes, U256};
+use alloy::providers::{Network, Provider};
+use alloy::sol_types::SolCall;
+use eyre::{eyre, ErrReport, OptionExt, Result};
+use loom_defi_abi::IERC4626;
+use loom_evm_utils::evm::evm_call;
+use loom_types_entities::required_state::RequiredState;
+use loom_types_entities::{Pool, PoolAbiEncoder, PoolClass, PoolId, PoolProtocol, PreswapRequirement, SwapDirection};
+use revm::primitives::Env;
+use revm::DatabaseRef;
+
+/// Exchange rate is the amount of assets for one share scaled by 1e18
+const EXCHANGE_RATE_SCALE: U256 = U256::from_limbs([1_000_000_000_000_000_000, 0, 0, 0]);
+
+const DEPOSIT_GAS_ESTIMATE: u64 = 100_000;
+const REDEEM_GAS_ESTIMATE: u64 = 90_000;
+
+/// ERC-4626 vault wrapped as a synthetic pool between the share token and the underlying asset token. Swaps are
+/// calculated with the exchange rate read from the state, assets are deposited and shares are redeemed by the multicaller.
+#[derive(Clone)]
+pub struct Erc4626VaultPool {
+    share_token: Address,
+    pub asset_token: Address,
+    /// Exchange rate at load time
+    pub exchange_rate: U256,
+    encoder: Erc4626VaultAbiSwapEncoder,
+}
+
+impl Erc4626VaultPool {
+    pub fn new(share_token: Address, asset_token: Address, exchange_rate: U256) -> Self {
+        Self { share_token, asset_token, exchange_rate, encoder: Erc4626VaultAbiSwapEncoder::new(share_token, asset_token) }
+    }
+
+    pub async fn fetch_pool_data<N: Network, P: Provider<N> + Send + Sync + Clone + 'static>(client: P, address: Address) -> Result<Self> {
+        let vault = IERC4626::IERC4626Instance::new(address, client.clone());
+
+        let asset_token: Address = vault.asset().call().await?._0;
+        let exchange_rate: U256 = vault.convertToAssets(EXCHANGE_RATE_SCALE).call().await?._0;
+        if exchange_rate.is_zero() {
+            return Err(eyre!("ZERO_EXCHANGE_RATE"));
+        }
+
+        Ok(Self::new(address, asset_token, exchange_rate))
+    }
+
+    pub fn fetch_exchange_rate(&self, state_db: &dyn DatabaseRef<Error = ErrReport>, env: Env) -> Result<U256> {
+        let call_data = IERC4626::convertToAssetsCall { shares: EXCHANGE_RATE_SCALE }.abi_encode();
+        let (value, _gas_used) = evm_call(state_db, env, self.share_token, call_data)?;
+        let exchange_rate = IERC4626::convertToAssetsCall::abi_decode_returns(&value, false)?._0;
+        if exchange_rate.is_zero() {
+            return Err(eyre!("ZERO_EXCHANGE_RATE"));
+        }
+        Ok(exchange_rate)
+    }
+
+    fn shares_to_assets(shares: U256, exchange_rate: U256) -> Result<U256> {
+        Ok(shares.checked_mul(exchange_rate).ok_or_eyre("MULTIPLICATION_OVERFLOWN")? / EXCHANGE_RATE_SCALE)
+    }
+
+    fn assets_to_shares(assets: U256, exchange_rate: U256) -> Result<U256> {
+        Ok(assets.checked_mul(EXCHANGE_RATE_SCALE).ok_or_eyre("MULTIPLICATION_OVERFLOWN")? / exchange_rate)
+    }
+
+    fn is_redeem(&self, token_address_from: &Address, token_address_to: &Address) -> Result<bool> {
+        if *token_address_from == self.share_token && *token_address_to == self.asset_token {
+            Ok(true)
+        } else if *token_address_from == self.asset_token && *token_address_to == self.share_token {
+            Ok(false)
+        } else {
+            Err(eyre!("TOKEN_NOT_FOUND"))
+        }
+    }
+}
+
+impl Pool for Erc4626VaultPool {
+    fn as_any<'a>(&self) -> &dyn Any {
+        self
+    }
+
+    fn get_class(&self) -> PoolClass {
+        PoolClass::Erc4626Vault
+    }
+
+    fn get_protocol(&self) -> PoolProtocol {
+        PoolProtocol::Erc4626Vault
+    }
+
+    fn get_address(&self) -> Address {
+        self.share_token
+    }
+
+    fn get_pool_id(&self) -> PoolId {
+        PoolId::Address(self.share_token)
+    }
+
+    fn get_fee(&self) -> U256 {
+        U256::ZERO
+    }
+
+    fn get_tokens(&self) -> Vec<Address> {
+        vec![self.share_token, self.asset_token]
+    }
+
+    fn get_swap_directions(&self) -> Vec<SwapDirection> {
+        vec![(self.share_token, self.asset_token).into(), (self.asset_token, self.share_token).into()]
+    }
+
+    fn calculate_out_amount(
+        &self,
+        state_db: &dyn DatabaseRef<Error = ErrReport>,
+        env: Env,
+        token_address_from: &Address,
+        token_address_to: &Address,
+        in_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        let is_redeem = self.is_redeem(token_address_from, token_address_to)?;
+        let exchange_rate = self.fetch_exchange_rate(state_db, env)?;
+        let (out_amount, gas_used) = if is_redeem {
+            (Self::shares_to_assets(in_amount, exchange_rate)?, REDEEM_GAS_ESTIMATE)
+        } else {
+            (Self::assets_to_shares(in_amount, exchange_rate)?, DEPOSIT_GAS_ESTIMATE)
+        };
+
+        if out_amount.is_zero() {
+            Err(eyre!("ZERO_OUT_AMOUNT"))
+        } else {
+            Ok((out_amount, gas_used))
+        }
+    }
+
+    fn calculate_in_amount(
+        &self,
+        state_db: &dyn DatabaseRef<Error = ErrReport>,
+        env: Env,
+        token_address_from: &Address,
+        token_address_to: &Address,
+        out_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        let is_redeem = self.is_redeem(token_address_from, token_address_to)?;
+        let exchange_rate = self.fetch_exchange_rate(state_db, env)?;
+        // Rounded up so the in amount always covers the out amount
+        let (in_amount, gas_used) = if is_redeem {
+            (Self::assets_to_shares(out_amount, exchange_rate)? + U256::from(1), REDEEM_GAS_ESTIMATE)
+        } else {
+            (Self::shares_to_assets(out_amount, exchange_rate)? + U256::from(1), DEPOSIT_GAS_ESTIMATE)
+        };
+
+        Ok((in_amount, gas_used))
+    }
+
+    fn can_flash_swap(&self) -> bool {
+        false
+    }
+
+    fn can_calculate_in_amount(&self) -> bool {
+        true
+    }
+
+    fn get_abi_encoder(&self) -> Option<&dyn PoolAbiEncoder> {
+        Some(&self.encoder)
+    }
+
+    fn get_read_only_cell_vec(&self) -> Vec<U256> {
+        Vec::new()
+    }
+
+    fn get_state_required(&self) -> Result<RequiredState> {
+        let mut state_required = RequiredState::new();
+        state_required.add_call(self.share_token, IERC4626::assetCall {}.abi_encode());
+        state_required.add_call(self.share_token, IERC4626::totalAssetsCall {}.abi_encode());
+        state_required.add_call(self.share_token, IERC4626::totalSupplyCall {}.abi_encode());
+        state_required.add_call(self.share_token, IERC4626::convertToAssetsCall { shares: EXCHANGE_RATE_SCALE }.abi_encode());
+
+        Ok(state_required)
+    }
+
+    fn is_native(&self) -> bool {
+        false
+    }
+
+    fn preswap_requirement(&self) -> PreswapRequirement {
+        PreswapRequirement::Allowance
+    }
+}
+
+#[derive(Clone)]
+struct Erc4626VaultAbiSwapEncoder {
+    share_token: Address,
+    asset_token: Address,
+}
+
+impl Erc4626VaultAbiSwapEncoder {
+    pub fn new(share_token: Address, asset_token: Address) -> Self {
+        Self { share_token, asset_token }
+    }
+}
+
+impl PoolAbiEncoder for Erc4626VaultAbiSwapEncoder {
+    // The recipient redeems its own shares, the multicaller holds them
+    fn encode_swap_in_amount_provided(
+        &self,
+        token_from_address: Address,
+        token_to_address: Address,
+        amount: U256,
+        recipient: Address,
+        _payload: Bytes,
+    ) -> Result<Bytes> {
+        if token_from_address == self.share_token && token_to_address == self.asset_token {
+            Ok(Bytes::from(IERC4626::redeemCall { shares: amount, receiver: recipient, owner: recipient }.abi_encode()))
+        } else if token_from_address == self.asset_token && token_to_address == self.share_token {
+            Ok(Bytes::from(IERC4626::depositCall { assets: amount, receiver: recipient }.abi_encode()))
+        } else {
+            Err(eyre!("TOKEN_NOT_FOUND"))
+        }
+    }
+
+    fn swap_in_amount_offset(&self, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0x04)
+    }
+
+    // deposit and redeem return the out amount
+    fn swap_in_amount_return_offset(&self, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0x0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy::primitives::address;
+    use loom_evm_db::LoomDBType;
+    use revm::primitives::{AccountInfo, Bytecode};
+
+    const SHARE_TOKEN: Address = address!("83F20F44975D03b1b09e64809B757c47f942BEeA");
+    const ASSET_TOKEN: Address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+
+    // Vault returning `exchange_rate` for any call
+    fn vault_db(exchange_rate: U256) -> LoomDBType {
+        let mut code = vec![0x7f];
+        code.extend_from_slice(&exchange_rate.to_be_bytes::<32>());
+        // PUSH1 0 MSTORE PUSH1 0x20 PUSH1 0 RETURN
+        code.extend_from_slice(&[0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3]);
+        let mut db = LoomDBType::default();
+        db.insert_account_info(SHARE_TOKEN, AccountInfo { code: Some(Bytecode::new_raw(code.into())), ..Default::default() });
+        db
+    }
+
+    #[test]
+    fn test_calculate_amounts() -> Result<()> {
+        // 1.1 assets per share, the rate at load time is ignored
+        let pool = Erc4626VaultPool::new(SHARE_TOKEN, ASSET_TOKEN, EXCHANGE_RATE_SCALE);
+        let db = vault_db(U256::from(1_100_000_000_000_000_000u128));
+
+        let (assets, _) = pool.calculate_out_amount(&db, Env::default(), &SHARE_TOKEN, &ASSET_TOKEN, U256::from(1_000_000u64))?;
+        assert_eq!(assets, U256::from(1_100_000u64));
+
+        let (shares, _) = pool.calculate_out_amount(&db, Env::default(), &ASSET_TOKEN, &SHARE_TOKEN, U256::from(1_100_000u64))?;
+        assert_eq!(shares, U256::from(1_000_000u64));
+
+        let (shares_in, _) = pool.calculate_in_amount(&db, Env::default(), &SHARE_TOKEN, &ASSET_TOKEN, U256::from(1_100_000u64))?;
+        assert!(shares_in >= U256::from(1_000_000u64));
+
+        assert!(pool.calculate_out_amount(&db, Env::default(), &ASSET_TOKEN, &Address::ZERO, U256::from(1u64)).is_err());
+
+        // the rate follows the state
+        let db = vault_db(U256::from(1_200_000_000_000_000_000u128));
+        let (assets, _) = pool.calculate_out_amount(&db, Env::default(), &SHARE_TOKEN, &ASSET_TOKEN, U256::from(1_000_000u64))?;
+        assert_eq!(assets, U256::from(1_200_000u64));
+
+        assert!(pool.calculate_out_amount(&LoomDBType::default(), Env::default(), &SHARE_TOKEN, &ASSET_TOKEN, U256::from(1u64)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_swap() -> Result<()> {
+        let pool = Erc4626VaultPool::new(SHARE_TOKEN, ASSET_TOKEN, EXCHANGE_RATE_SCALE);
+        let encoder = pool.get_abi_encoder().unwrap();
+        let recipient = Address::repeat_byte(0x55);
+
+        let deposit_data = encoder.encode_swap_in_amount_provided(ASSET_TOKEN, SHARE_TOKEN, U256::from(1000), recipient, Bytes::new())?;
+        let deposit_call = IERC4626::depositCall::abi_decode(&deposit_data, true)?;
+        assert_eq!((deposit_call.assets, deposit_call.receiver), (U256::from(1000), recipient));
+
+        let redeem_data = encoder.encode_swap_in_amount_provided(SHARE_TOKEN, ASSET_TOKEN, U256::from(1000), recipient, Bytes::new())?;
+        let redeem_call = IERC4626::redeemCall::abi_decode(&redeem_data, true)?;
+        assert_eq!((redeem_call.shares, redeem_call.receiver, redeem_call.owner), (U256::from(1000), recipient, recipient));
+
+        assert!(encoder.encode_swap_in_amount_provided(ASSET_TOKEN, Address::ZERO, U256::from(1000), recipient, Bytes::new()).is_err());
+        Ok(())
+    }
+}
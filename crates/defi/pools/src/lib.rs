@@ -1,20 +1,31 @@
 extern crate core;
 
+pub use aerodromeclpool::AerodromeCLPool;
+pub use balancerpool::BalancerPool;
 pub use curvepool::{CurvePool, CurvePoolAbiEncoder};
+pub use erc4626vaultpool::Erc4626VaultPool;
 pub use loaders::*;
 pub use loom_types_entities::pool_config::PoolsLoadingConfig;
 pub use maverickpool::MaverickPool;
+pub use maverickv2pool::{MaverickV2Pool, MaverickV2TickReserves};
 pub use pancakev3pool::PancakeV3Pool;
 pub use uniswapv2pool::UniswapV2Pool;
 pub use uniswapv3pool::{Slot0, UniswapV3Pool};
+pub use uniswapv4pool::UniswapV4Pool;
+pub use virtual_impl::{BalancerPoolKind, BalancerPoolParams, CurveStableSwapParams, PreloadedTicks};
 
 pub mod db_reader;
 mod maverickpool;
+mod maverickv2pool;
 pub mod state_readers;
 mod uniswapv2pool;
 mod uniswapv3pool;
+mod uniswapv4pool;
 
+mod aerodromeclpool;
+mod balancerpool;
 mod curvepool;
+mod erc4626vaultpool;
 pub mod protocols;
 
 mod loaders;
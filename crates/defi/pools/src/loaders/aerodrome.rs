@@ -0,0 +1,123 @@
+use crate::protocols::fetch_uni3_factory;
+use crate::state_readers::UniswapV3StateReader;
+use crate::{AerodromeCLPool, UniswapV3PoolLoader};
+use alloy::primitives::{Address, Bytes, B256};
+use alloy::providers::network::Ethereum;
+use alloy::providers::Provider;
+use eyre::{eyre, ErrReport};
+use futures::Stream;
+use loom_defi_address_book::FactoryAddress;
+use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
+use loom_types_entities::{PoolClass, PoolId, PoolLoader, PoolWrapper};
+use revm::primitives::Env;
+use revm::DatabaseRef;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Uniswap V3 loader for chains with Aerodrome Slipstream, pools created by the Aerodrome factory are loaded as
+/// `AerodromeCLPool`, all other pools are passed to `UniswapV3PoolLoader`
+#[derive(Clone)]
+pub struct AerodromePoolLoader<P> {
+    provider: Option<P>,
+    factory: Address,
+    inner: UniswapV3PoolLoader<P, Ethereum, LoomDataTypesEthereum>,
+}
+
+impl<P> AerodromePoolLoader<P>
+where
+    P: Provider<Ethereum> + Clone + 'static,
+{
+    pub fn new() -> Self {
+        Self { provider: None, factory: FactoryAddress::AERODROME_CL, inner: UniswapV3PoolLoader::new() }
+    }
+
+    pub fn with_provider(provider: P) -> Self {
+        Self {
+            provider: Some(provider.clone()),
+            factory: FactoryAddress::AERODROME_CL,
+            inner: UniswapV3PoolLoader::with_provider(provider),
+        }
+    }
+
+    pub fn with_factory(self, factory: Address) -> Self {
+        Self { factory, ..self }
+    }
+}
+
+impl<P> Default for AerodromePoolLoader<P>
+where
+    P: Provider<Ethereum> + Clone + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P> PoolLoader<P, Ethereum, LoomDataTypesEthereum> for AerodromePoolLoader<P>
+where
+    P: Provider<Ethereum> + Clone + 'static,
+{
+    fn get_pool_class_by_log(
+        &self,
+        log_entry: &<LoomDataTypesEthereum as LoomDataTypes>::Log,
+    ) -> Option<(PoolId<LoomDataTypesEthereum>, PoolClass)> {
+        self.inner.get_pool_class_by_log(log_entry)
+    }
+
+    fn event_signatures(&self) -> Vec<B256> {
+        self.inner.event_signatures()
+    }
+
+    fn fetch_pool_by_id<'a>(
+        &'a self,
+        pool_id: PoolId<LoomDataTypesEthereum>,
+    ) -> Pin<Box<dyn Future<Output = eyre::Result<PoolWrapper<LoomDataTypesEthereum>>> + Send + 'a>> {
+        Box::pin(async move {
+            if let Some(provider) = &self.provider {
+                self.fetch_pool_by_id_from_provider(pool_id, provider.clone()).await
+            } else {
+                Err(eyre!("NO_PROVIDER"))
+            }
+        })
+    }
+
+    fn fetch_pool_by_id_from_provider<'a>(
+        &'a self,
+        pool_id: PoolId<LoomDataTypesEthereum>,
+        provider: P,
+    ) -> Pin<Box<dyn Future<Output = eyre::Result<PoolWrapper<LoomDataTypesEthereum>>> + Send + 'a>> {
+        Box::pin(async move {
+            let pool_address = pool_id.address()?;
+
+            if fetch_uni3_factory(provider.clone(), pool_address).await? == self.factory {
+                Ok(PoolWrapper::new(Arc::new(AerodromeCLPool::fetch_pool_data(provider, pool_address).await?)))
+            } else {
+                self.inner.fetch_pool_by_id_from_provider(pool_id, provider).await
+            }
+        })
+    }
+
+    fn fetch_pool_by_id_from_evm(
+        &self,
+        pool_id: PoolId<LoomDataTypesEthereum>,
+        db: &dyn DatabaseRef<Error = ErrReport>,
+        env: Env,
+    ) -> eyre::Result<PoolWrapper<LoomDataTypesEthereum>> {
+        let pool_address = pool_id.address()?;
+
+        if UniswapV3StateReader::factory(&db, env.clone(), pool_address)? == self.factory {
+            Ok(PoolWrapper::new(Arc::new(AerodromeCLPool::fetch_pool_data_evm(db, env, pool_address)?)))
+        } else {
+            self.inner.fetch_pool_by_id_from_evm(pool_id, db, env)
+        }
+    }
+
+    fn is_code(&self, code: &Bytes) -> bool {
+        self.inner.is_code(code)
+    }
+
+    fn protocol_loader(&self) -> eyre::Result<Pin<Box<dyn Stream<Item = (PoolId, PoolClass)> + Send>>> {
+        Err(eyre!("NOT_IMPLEMENTED"))
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{pool_loader, BalancerPool};
+use alloy::primitives::{Bytes, B256};
+use alloy::primitives::Log as EVMLog;
+use alloy::providers::network::Ethereum;
+use alloy::sol_types::{SolEvent, SolEventInterface};
+use eyre::{eyre, ErrReport};
+use futures::Stream;
+use loom_defi_abi::balancer::IVault::{self, IVaultEvents};
+use loom_defi_address_book::FactoryAddress;
+use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
+use loom_types_entities::{PoolClass, PoolId, PoolLoader, PoolWrapper};
+use revm::primitives::Env;
+use revm::DatabaseRef;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+pool_loader!(BalancerV2PoolLoader);
+
+impl<P> PoolLoader<P, Ethereum, LoomDataTypesEthereum> for BalancerV2PoolLoader<P, Ethereum, LoomDataTypesEthereum>
+where
+    P: Provider<Ethereum> + Clone + 'static,
+{
+    fn get_pool_class_by_log(
+        &self,
+        log_entry: &<LoomDataTypesEthereum as LoomDataTypes>::Log,
+    ) -> Option<(PoolId<LoomDataTypesEthereum>, PoolClass)> {
+        if log_entry.address() != FactoryAddress::BALANCER_V2_VAULT {
+            return None;
+        }
+        let log_entry: Option<EVMLog> = EVMLog::new(log_entry.address(), log_entry.topics().to_vec(), log_entry.data().data.clone());
+        match log_entry {
+            Some(log_entry) => match IVaultEvents::decode_log(&log_entry, false) {
+                Ok(event) => match event.data {
+                    IVaultEvents::PoolRegistered(event) => Some((PoolId::Bytes32(event.poolId), PoolClass::BalancerV2)),
+                    IVaultEvents::Swap(event) => Some((PoolId::Bytes32(event.poolId), PoolClass::BalancerV2)),
+                    _ => None,
+                },
+                Err(_) => None,
+            },
+            None => None,
+        }
+    }
+
+    fn event_signatures(&self) -> Vec<B256> {
+        vec![IVault::Swap::SIGNATURE_HASH, IVault::PoolBalanceChanged::SIGNATURE_HASH, IVault::PoolRegistered::SIGNATURE_HASH]
+    }
+
+    fn fetch_pool_by_id<'a>(
+        &'a self,
+        pool_id: PoolId<LoomDataTypesEthereum>,
+    ) -> Pin<Box<dyn Future<Output = eyre::Result<PoolWrapper<LoomDataTypesEthereum>>> + Send + 'a>> {
+        Box::pin(async move {
+            if let Some(provider) = &self.provider {
+                self.fetch_pool_by_id_from_provider(pool_id, provider.clone()).await
+            } else {
+                Err(eyre!("NO_PROVIDER"))
+            }
+        })
+    }
+
+    fn fetch_pool_by_id_from_provider<'a>(
+        &'a self,
+        pool_id: PoolId<LoomDataTypesEthereum>,
+        provider: P,
+    ) -> Pin<Box<dyn Future<Output = eyre::Result<PoolWrapper<LoomDataTypesEthereum>>> + Send + 'a>> {
+        Box::pin(async move {
+            let pool_id = pool_id.bytes32()?;
+            Ok(PoolWrapper::new(Arc::new(BalancerPool::fetch_pool_data(provider, pool_id).await?)))
+        })
+    }
+
+    fn fetch_pool_by_id_from_evm(
+        &self,
+        _pool_id: PoolId<LoomDataTypesEthereum>,
+        _db: &dyn DatabaseRef<Error = ErrReport>,
+        _env: Env,
+    ) -> eyre::Result<PoolWrapper<LoomDataTypesEthereum>> {
+        // Pool type is detected with calls that may revert
+        Err(eyre!("NOT_IMPLEMENTED"))
+    }
+
+    fn is_code(&self, _code: &Bytes) -> bool {
+        false
+    }
+
+    fn protocol_loader(&self) -> eyre::Result<Pin<Box<dyn Stream<Item = (PoolId, PoolClass)> + Send>>> {
+        Err(eyre!("NOT_IMPLEMENTED"))
+    }
+}
@@ -1,10 +1,12 @@
 use crate::protocols::CurveProtocol;
 use crate::{pool_loader, CurvePool};
-use alloy::primitives::Bytes;
+use alloy::primitives::{Bytes, B256};
 use alloy::providers::network::Ethereum;
+use alloy::sol_types::SolEvent;
 use async_stream::stream;
 use eyre::{eyre, ErrReport};
 use futures::Stream;
+use loom_defi_abi::curve::{ICurveCryptoSwapEvents, ICurveStableSwapEvents};
 use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
 use loom_types_entities::{PoolClass, PoolId, PoolLoader, PoolWrapper};
 use revm::primitives::Env;
@@ -12,7 +14,7 @@ use revm::DatabaseRef;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
-use tracing::error;
+use tracing::{debug, error};
 
 pool_loader!(CurvePoolLoader);
 
@@ -27,6 +29,14 @@ where
         None
     }
 
+    fn event_signatures(&self) -> Vec<B256> {
+        vec![
+            ICurveStableSwapEvents::TokenExchange::SIGNATURE_HASH,
+            ICurveStableSwapEvents::TokenExchangeUnderlying::SIGNATURE_HASH,
+            ICurveCryptoSwapEvents::TokenExchange::SIGNATURE_HASH,
+        ]
+    }
+
     fn fetch_pool_by_id<'a>(
         &'a self,
         pool_id: PoolId<LoomDataTypesEthereum>,
@@ -49,9 +59,19 @@ where
             let pool_address = pool_id.address()?;
             match CurveProtocol::get_contract_from_code(provider.clone(), pool_address).await {
                 Ok(curve_contract) => {
-                    let curve_pool =
+                    let mut curve_pool =
                         CurvePool::<P, Ethereum>::fetch_pool_data_with_default_encoder(provider.clone(), curve_contract).await?;
 
+                    match CurveProtocol::get_stableswap_params(provider.clone(), pool_address).await {
+                        Ok(stableswap_params) => {
+                            debug!(%pool_address, amp = %stableswap_params.amp, "Curve StableSwap params fetched");
+                            curve_pool = curve_pool.with_stableswap_params(stableswap_params);
+                        }
+                        Err(e) => {
+                            debug!(%pool_address, "Curve StableSwap params not available, using EVM : {}", e);
+                        }
+                    }
+
                     Ok(PoolWrapper::new(Arc::new(curve_pool)))
                 }
                 Err(e) => {
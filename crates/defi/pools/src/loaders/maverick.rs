@@ -1,10 +1,10 @@
 use crate::{pool_loader, MaverickPool};
-use alloy::primitives::Bytes;
+use alloy::primitives::{Bytes, B256};
 use alloy::primitives::Log as EVMLog;
 use alloy::providers::network::Ethereum;
-use alloy::sol_types::SolEventInterface;
+use alloy::sol_types::{SolEvent, SolEventInterface};
 use eyre::{eyre, ErrReport, Result};
-use loom_defi_abi::maverick::IMaverickPool::IMaverickPoolEvents;
+use loom_defi_abi::maverick::IMaverickPool::{self, IMaverickPoolEvents};
 use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
 use loom_types_entities::{PoolClass, PoolId, PoolLoader, PoolWrapper};
 use revm::primitives::Env;
@@ -39,6 +39,14 @@ where
         }
     }
 
+    fn event_signatures(&self) -> Vec<B256> {
+        vec![
+            IMaverickPool::Swap::SIGNATURE_HASH,
+            IMaverickPool::AddLiquidity::SIGNATURE_HASH,
+            IMaverickPool::RemoveLiquidity::SIGNATURE_HASH,
+        ]
+    }
+
     fn fetch_pool_by_id<'a>(
         &'a self,
         pool_id: PoolId<LoomDataTypesEthereum>,
@@ -0,0 +1,122 @@
+use crate::{pool_loader, MaverickV2Pool};
+use alloy::primitives::Log as EVMLog;
+use alloy::primitives::{Bytes, B256, U256};
+use alloy::providers::network::Ethereum;
+use alloy::sol_types::{SolEvent, SolEventInterface};
+use async_stream::stream;
+use eyre::{eyre, ErrReport, Result};
+use futures::Stream;
+use loom_defi_abi::maverick2::IMaverickV2Factory::IMaverickV2FactoryInstance;
+use loom_defi_abi::maverick2::IMaverickV2Pool::{self, IMaverickV2PoolEvents};
+use loom_defi_address_book::FactoryAddress;
+use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
+use loom_types_entities::{PoolClass, PoolId, PoolLoader, PoolWrapper};
+use revm::primitives::Env;
+use revm::DatabaseRef;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tracing::error;
+
+/// Pools fetched from the factory per lookup call
+const LOOKUP_BATCH_SIZE: u64 = 100;
+
+pool_loader!(MaverickV2PoolLoader);
+
+impl<P> PoolLoader<P, Ethereum, LoomDataTypesEthereum> for MaverickV2PoolLoader<P, Ethereum, LoomDataTypesEthereum>
+where
+    P: Provider<Ethereum> + Clone + 'static,
+{
+    fn get_pool_class_by_log(
+        &self,
+        log_entry: &<LoomDataTypesEthereum as LoomDataTypes>::Log,
+    ) -> Option<(PoolId<LoomDataTypesEthereum>, PoolClass)> {
+        let log_entry: Option<EVMLog> = EVMLog::new(log_entry.address(), log_entry.topics().to_vec(), log_entry.data().data.clone());
+        match log_entry {
+            Some(log_entry) => match IMaverickV2PoolEvents::decode_log(&log_entry, false) {
+                Ok(event) => match event.data {
+                    IMaverickV2PoolEvents::PoolSwap(_)
+                    | IMaverickV2PoolEvents::PoolAddLiquidity(_)
+                    | IMaverickV2PoolEvents::PoolRemoveLiquidity(_) => Some((PoolId::Address(log_entry.address), PoolClass::MaverickV2)),
+                    _ => None,
+                },
+                Err(_) => None,
+            },
+            None => None,
+        }
+    }
+
+    fn event_signatures(&self) -> Vec<B256> {
+        vec![
+            IMaverickV2Pool::PoolSwap::SIGNATURE_HASH,
+            IMaverickV2Pool::PoolAddLiquidity::SIGNATURE_HASH,
+            IMaverickV2Pool::PoolRemoveLiquidity::SIGNATURE_HASH,
+        ]
+    }
+
+    fn fetch_pool_by_id<'a>(
+        &'a self,
+        pool_id: PoolId<LoomDataTypesEthereum>,
+    ) -> Pin<Box<dyn Future<Output = Result<PoolWrapper<LoomDataTypesEthereum>>> + Send + 'a>> {
+        Box::pin(async move {
+            if let Some(provider) = self.provider.clone() {
+                self.fetch_pool_by_id_from_provider(pool_id, provider).await
+            } else {
+                Err(eyre!("NO_PROVIDER"))
+            }
+        })
+    }
+
+    fn fetch_pool_by_id_from_provider(
+        &self,
+        pool_id: PoolId<LoomDataTypesEthereum>,
+        provider: P,
+    ) -> Pin<Box<dyn Future<Output = Result<PoolWrapper<LoomDataTypesEthereum>>> + Send>> {
+        Box::pin(
+            async move { Ok(PoolWrapper::new(Arc::new(MaverickV2Pool::fetch_pool_data(provider.clone(), pool_id.address()?).await?))) },
+        )
+    }
+
+    fn fetch_pool_by_id_from_evm(
+        &self,
+        _pool_id: PoolId<LoomDataTypesEthereum>,
+        _db: &dyn DatabaseRef<Error = ErrReport>,
+        _env: Env,
+    ) -> Result<PoolWrapper<LoomDataTypesEthereum>> {
+        // Bin reserves are read with pool calls on the provider
+        Err(eyre!("NOT_IMPLEMENTED"))
+    }
+
+    fn is_code(&self, _code: &Bytes) -> bool {
+        false
+    }
+
+    fn protocol_loader(&self) -> Result<Pin<Box<dyn Stream<Item = (PoolId, PoolClass)> + Send>>> {
+        let Some(client) = self.provider.clone() else {
+            return Err(eyre!("NO_PROVIDER"));
+        };
+
+        Ok(Box::pin(stream! {
+            let factory = IMaverickV2FactoryInstance::new(FactoryAddress::MAVERICK_V2, client.clone());
+            let pool_count: u64 = match factory.poolCount().call().await {
+                Ok(pool_count) => pool_count._poolCount.saturating_to(),
+                Err(e) => {
+                    error!("Error fetching Maverick V2 pool count : {}", e);
+                    return;
+                }
+            };
+
+            for start_index in (0..pool_count).step_by(LOOKUP_BATCH_SIZE as usize) {
+                let end_index = (start_index + LOOKUP_BATCH_SIZE).min(pool_count);
+                match factory.lookup_2(U256::from(start_index), U256::from(end_index)).call().await {
+                    Ok(lookup) => {
+                        for pool_address in lookup.pools {
+                            yield (PoolId::Address(pool_address), PoolClass::MaverickV2)
+                        }
+                    }
+                    Err(e) => error!(start_index, end_index, "Error fetching Maverick V2 pools : {}", e),
+                }
+            }
+        }))
+    }
+}
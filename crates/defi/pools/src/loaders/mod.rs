@@ -1,17 +1,30 @@
+mod aerodrome;
+mod balancer;
 mod curve;
 mod maverick;
+mod maverick2;
 mod uniswap2;
 mod uniswap3;
+mod uniswap4;
+mod vault;
 
-use crate::loaders::curve::CurvePoolLoader;
+use alloy::primitives::Address;
 use alloy::providers::network::Ethereum;
 use alloy::providers::{Network, Provider, RootProvider};
+use std::collections::HashMap;
+use tracing::warn;
 use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
 use loom_types_entities::pool_config::PoolsLoadingConfig;
 use loom_types_entities::{PoolClass, PoolLoader, PoolLoaders};
+pub use aerodrome::AerodromePoolLoader;
+pub use balancer::BalancerV2PoolLoader;
+pub use curve::CurvePoolLoader;
 pub use maverick::MaverickPoolLoader;
+pub use maverick2::MaverickV2PoolLoader;
 pub use uniswap2::UniswapV2PoolLoader;
 pub use uniswap3::UniswapV3PoolLoader;
+pub use uniswap4::UniswapV4PoolLoader;
+pub use vault::VaultPoolLoader;
 
 /// creates  pool loader and imports necessary crates
 #[macro_export]
@@ -117,15 +130,70 @@ where
     where
         P: Provider<Ethereum> + Clone,
     {
-        let pool_loader = PoolLoadersBuilder::<P>::new()
+        Self::default_pool_loaders_with_factory_overrides(provider, config, &HashMap::new())
+    }
+
+    /// Default pool loaders with factories of forked protocols set by name, supported names: `aerodrome`
+    pub fn default_pool_loaders_with_factory_overrides(
+        provider: P,
+        config: PoolsLoadingConfig,
+        factory_overrides: &HashMap<String, Address>,
+    ) -> PoolLoaders<P, Ethereum, LoomDataTypesEthereum>
+    where
+        P: Provider<Ethereum> + Clone,
+    {
+        let mut uniswap3_loader = UniswapV3PoolLoader::with_provider(provider.clone());
+        if let Some(tick_range) = config.tick_preload_range() {
+            uniswap3_loader = uniswap3_loader.with_tick_preload(tick_range);
+        }
+
+        let mut builder = PoolLoadersBuilder::<P>::new()
             .with_provider(provider.clone())
             .with_config(config)
             .add_loader(PoolClass::Maverick, MaverickPoolLoader::with_provider(provider.clone()))
+            .add_loader(PoolClass::MaverickV2, MaverickV2PoolLoader::with_provider(provider.clone()))
             .add_loader(PoolClass::UniswapV2, UniswapV2PoolLoader::with_provider(provider.clone()))
-            .add_loader(PoolClass::UniswapV3, UniswapV3PoolLoader::with_provider(provider.clone()))
+            .add_loader(PoolClass::UniswapV3, uniswap3_loader)
             .add_loader(PoolClass::Curve, CurvePoolLoader::with_provider(provider.clone()))
+            .add_loader(PoolClass::BalancerV2, BalancerV2PoolLoader::with_provider(provider.clone()))
+            .add_loader(PoolClass::UniswapV4, UniswapV4PoolLoader::with_provider(provider.clone()))
+            .add_loader(PoolClass::Erc4626Vault, VaultPoolLoader::with_provider(provider.clone()));
+
+        for (protocol, factory) in factory_overrides.iter() {
+            match protocol.to_lowercase().as_str() {
+                "aerodrome" => {
+                    builder =
+                        builder.add_loader(PoolClass::UniswapV3, AerodromePoolLoader::with_provider(provider.clone()).with_factory(*factory));
+                }
+                _ => warn!("Unknown protocol in factory overrides : {protocol}"),
+            }
+        }
+
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy::sol_types::SolEvent;
+    use loom_defi_abi::aerodrome::IAerodromePool;
+    use loom_defi_abi::curve::ICurveStableSwapEvents;
+    use loom_defi_abi::uniswap3::IUniswapV3Pool;
+
+    #[test]
+    fn test_event_signatures_of_enabled_pool_classes() {
+        let config = PoolsLoadingConfig::new().disable_all().enable(PoolClass::UniswapV2).enable(PoolClass::Curve);
+        let pool_loaders = PoolLoadersBuilder::<RootProvider<Ethereum>>::new()
+            .with_config(config)
+            .add_loader(PoolClass::UniswapV2, UniswapV2PoolLoader::<RootProvider<Ethereum>, Ethereum>::new())
+            .add_loader(PoolClass::UniswapV3, UniswapV3PoolLoader::<RootProvider<Ethereum>, Ethereum>::new())
+            .add_loader(PoolClass::Curve, CurvePoolLoader::<RootProvider<Ethereum>, Ethereum>::new())
             .build();
 
-        pool_loader
+        let signatures = pool_loaders.event_signatures();
+        assert!(signatures.contains(&IAerodromePool::Swap::SIGNATURE_HASH));
+        assert!(signatures.contains(&ICurveStableSwapEvents::TokenExchange::SIGNATURE_HASH));
+        assert!(!signatures.contains(&IUniswapV3Pool::Swap::SIGNATURE_HASH));
     }
 }
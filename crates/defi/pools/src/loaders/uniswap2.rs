@@ -1,12 +1,13 @@
 use crate::protocols::{fetch_uni2_factory, UniswapV2Protocol};
 use crate::{pool_loader, UniswapV2Pool};
-use alloy::primitives::Bytes;
+use alloy::primitives::{Bytes, B256};
 use alloy::primitives::Log as EVMLog;
 use alloy::providers::network::Ethereum;
-use alloy::sol_types::SolEventInterface;
+use alloy::sol_types::{SolEvent, SolEventInterface};
 use eyre::{eyre, ErrReport};
 use futures::Stream;
-use loom_defi_abi::uniswap2::IUniswapV2Pair::IUniswapV2PairEvents;
+use loom_defi_abi::aerodrome::IAerodromePool;
+use loom_defi_abi::uniswap2::IUniswapV2Pair::{self, IUniswapV2PairEvents};
 use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
 use loom_types_entities::{get_protocol_by_factory, PoolClass, PoolId, PoolLoader, PoolProtocol, PoolWrapper};
 use revm::primitives::Env;
@@ -41,6 +42,19 @@ where
         }
     }
 
+    fn event_signatures(&self) -> Vec<B256> {
+        // Aerodrome V2 pools are loaded as UniswapV2 pools, their Mint event has the UniswapV2 signature
+        vec![
+            IUniswapV2Pair::Swap::SIGNATURE_HASH,
+            IUniswapV2Pair::Mint::SIGNATURE_HASH,
+            IUniswapV2Pair::Burn::SIGNATURE_HASH,
+            IUniswapV2Pair::Sync::SIGNATURE_HASH,
+            IAerodromePool::Swap::SIGNATURE_HASH,
+            IAerodromePool::Burn::SIGNATURE_HASH,
+            IAerodromePool::Sync::SIGNATURE_HASH,
+        ]
+    }
+
     fn fetch_pool_by_id<'a>(
         &'a self,
         pool_id: PoolId<LoomDataTypesEthereum>,
@@ -1,22 +1,68 @@
 use crate::protocols::{fetch_uni3_factory, UniswapV3Protocol};
-use crate::{pool_loader, MaverickPool, PancakeV3Pool, UniswapV3Pool};
-use alloy::primitives::Bytes;
+use crate::{MaverickPool, PancakeV3Pool, UniswapV3Pool};
+use alloy::primitives::{Bytes, B256};
 use alloy::primitives::Log as EVMLog;
 use alloy::providers::network::Ethereum;
-use alloy::sol_types::SolEventInterface;
+use alloy::providers::{Network, Provider};
+use alloy::sol_types::{SolEvent, SolEventInterface};
 use eyre::{eyre, ErrReport};
 use futures::Stream;
-use loom_defi_abi::uniswap3::IUniswapV3Pool::IUniswapV3PoolEvents;
+use loom_defi_abi::pancake::IPancakeV3Pool;
+use loom_defi_abi::uniswap3::IUniswapV3Pool::{self, IUniswapV3PoolEvents};
 use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
 use loom_types_entities::{get_protocol_by_factory, PoolClass, PoolId, PoolLoader, PoolProtocol, PoolWrapper};
 use revm::primitives::Env;
 use revm::DatabaseRef;
 use std::future::Future;
+use std::marker::PhantomData;
 use std::pin::Pin;
 use std::sync::Arc;
-use tracing::error;
+use tracing::{error, warn};
 
-pool_loader!(UniswapV3PoolLoader);
+#[derive(Clone)]
+pub struct UniswapV3PoolLoader<P, N, LDT = LoomDataTypesEthereum>
+where
+    N: Network,
+    P: Provider<N> + Clone,
+    LDT: LoomDataTypes,
+{
+    provider: Option<P>,
+    tick_preload: Option<i32>,
+    phantom_data: PhantomData<(P, N, LDT)>,
+}
+
+#[allow(dead_code)]
+impl<P, N, LDT> UniswapV3PoolLoader<P, N, LDT>
+where
+    N: Network,
+    P: Provider<N> + Clone,
+    LDT: LoomDataTypes,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_provider(provder: P) -> Self {
+        Self { provider: Some(provder), tick_preload: None, phantom_data: PhantomData }
+    }
+
+    /// Preload initialized ticks within `current tick ± tick_range` of pools fetched from the provider, swaps of preloaded
+    /// pools are simulated analytically instead of with the quoter
+    pub fn with_tick_preload(self, tick_range: i32) -> Self {
+        Self { tick_preload: Some(tick_range), ..self }
+    }
+}
+
+impl<P, N, LDT> Default for UniswapV3PoolLoader<P, N, LDT>
+where
+    N: Network,
+    P: Provider<N> + Clone,
+    LDT: LoomDataTypes,
+{
+    fn default() -> Self {
+        Self { provider: None, tick_preload: None, phantom_data: PhantomData }
+    }
+}
 
 impl<P> PoolLoader<P, Ethereum, LoomDataTypesEthereum> for UniswapV3PoolLoader<P, Ethereum, LoomDataTypesEthereum>
 where
@@ -42,6 +88,16 @@ where
         }
     }
 
+    fn event_signatures(&self) -> Vec<B256> {
+        vec![
+            IUniswapV3Pool::Swap::SIGNATURE_HASH,
+            IUniswapV3Pool::Mint::SIGNATURE_HASH,
+            IUniswapV3Pool::Burn::SIGNATURE_HASH,
+            IUniswapV3Pool::Initialize::SIGNATURE_HASH,
+            IPancakeV3Pool::Swap::SIGNATURE_HASH,
+        ]
+    }
+
     fn fetch_pool_by_id<'a>(
         &'a self,
         pool_id: PoolId<LoomDataTypesEthereum>,
@@ -72,7 +128,16 @@ where
                     PoolProtocol::Maverick => {
                         Ok(PoolWrapper::new(Arc::new(MaverickPool::fetch_pool_data(provider.clone(), pool_address).await?)))
                     }
-                    _ => Ok(PoolWrapper::new(Arc::new(UniswapV3Pool::fetch_pool_data(provider.clone(), pool_address).await?))),
+                    _ => {
+                        let mut pool = UniswapV3Pool::fetch_pool_data(provider.clone(), pool_address).await?;
+                        if let Some(tick_range) = self.tick_preload {
+                            match pool.fetch_preloaded_ticks(provider.clone(), tick_range).await {
+                                Ok(preloaded_ticks) => pool = pool.with_preloaded_ticks(preloaded_ticks),
+                                Err(e) => warn!("Error preloading ticks of {:#20x}: {}", pool_address, e),
+                            }
+                        }
+                        Ok(PoolWrapper::new(Arc::new(pool)))
+                    }
                 },
                 Err(e) => {
                     error!("Error fetching factory address at {:#20x}: {}", pool_address, e);
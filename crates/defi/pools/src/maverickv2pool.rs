@@ -0,0 +1,532 @@
+use alloy::primitives::{Address, Bytes, U128, U256};
+use alloy::providers::{Network, Provider};
+use alloy::sol_types::SolCall;
+use eyre::{eyre, ErrReport, OptionExt, Result};
+use lazy_static::lazy_static;
+use loom_defi_abi::maverick2::IMaverickV2Factory::IMaverickV2FactoryInstance;
+use loom_defi_abi::maverick2::IMaverickV2Pool::IMaverickV2PoolInstance;
+use loom_defi_abi::maverick2::IMaverickV2Quoter::calculateSwapCall;
+use loom_defi_abi::maverick2::{IMaverickV2Pool, SwapParams};
+use loom_defi_abi::IERC20;
+use loom_defi_address_book::{FactoryAddress, PeripheryAddress};
+use loom_evm_utils::evm::evm_call;
+use loom_types_entities::required_state::RequiredState;
+use loom_types_entities::{Pool, PoolAbiEncoder, PoolClass, PoolId, PoolProtocol, PreswapRequirement, SwapDirection};
+use revm::primitives::Env;
+use revm::DatabaseRef;
+use std::any::Any;
+use tracing::error;
+
+lazy_static! {
+    static ref U256_ONE: U256 = U256::from(1);
+}
+
+/// Ticks on each side of the active tick with cached reserves
+const ADJACENT_TICKS: i32 = 3;
+
+/// Maverick V2 amounts are 18 decimals fixed point, token amounts are converted with the token scale
+const DEFAULT_SCALE: f64 = 1e18;
+
+/// Reserves of a Maverick V2 tick summed over the bins of all kinds, D18 scaled
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MaverickV2TickReserves {
+    pub tick: i32,
+    pub reserve_a: u128,
+    pub reserve_b: u128,
+}
+
+#[derive(Clone)]
+pub struct MaverickV2Pool {
+    address: Address,
+    pub token_a: Address,
+    pub token_b: Address,
+    liquidity_a: U256,
+    liquidity_b: U256,
+    fee_a_in: U256,
+    fee_b_in: U256,
+    token_a_scale: U256,
+    token_b_scale: U256,
+    pub bin_width: u32,
+    pub active_tick: i32,
+    pub active_bin_id: u32,
+    // Reserves of ticks around the active tick at load time, ordered by tick, swap amounts come from the quoter
+    bins: Vec<MaverickV2TickReserves>,
+    encoder: MaverickV2AbiSwapEncoder,
+}
+
+impl MaverickV2Pool {
+    pub fn get_token_a_in(token_address_from: &Address, token_address_to: &Address) -> bool {
+        *token_address_from < *token_address_to
+    }
+
+    /// The furthest tick the swap is allowed to reach, no limit
+    pub fn get_tick_limit(token_a_in: bool) -> i32 {
+        if token_a_in {
+            i32::MAX
+        } else {
+            i32::MIN
+        }
+    }
+
+    pub fn bins(&self) -> &[MaverickV2TickReserves] {
+        &self.bins
+    }
+
+    pub async fn fetch_pool_data<N: Network, P: Provider<N> + Send + Sync + Clone + 'static>(client: P, address: Address) -> Result<Self> {
+        let factory = IMaverickV2FactoryInstance::new(FactoryAddress::MAVERICK_V2, client.clone());
+        if !factory.isFactoryPool(address).call().await?._0 {
+            return Err(eyre!("NOT_MAVERICK_V2_FACTORY_POOL"));
+        }
+
+        let pool = IMaverickV2PoolInstance::new(address, client.clone());
+
+        let token_a: Address = pool.tokenA().call().await?._0;
+        let token_b: Address = pool.tokenB().call().await?._0;
+        let fee_a_in: U256 = pool.fee(true).call().await?._0;
+        let fee_b_in: U256 = pool.fee(false).call().await?._0;
+        let token_a_scale: U256 = pool.tokenAScale().call().await?._0;
+        let token_b_scale: U256 = pool.tokenBScale().call().await?._0;
+        let bin_width: u32 = pool.tickSpacing().call().await?._0.to();
+        let active_tick = pool.getState().call().await?._0.activeTick;
+
+        let mut bins = Vec::new();
+        let mut active_bin_id = 0;
+        for tick in active_tick - ADJACENT_TICKS..=active_tick + ADJACENT_TICKS {
+            let tick_state = pool.getTick(tick).call().await?.tickState;
+            if tick == active_tick {
+                active_bin_id = tick_state.binIdsByTick.iter().copied().find(|bin_id| *bin_id != 0).unwrap_or_default();
+            }
+            bins.push(MaverickV2TickReserves { tick, reserve_a: tick_state.reserveA, reserve_b: tick_state.reserveB });
+        }
+
+        let liquidity_a: U256 = IERC20::IERC20Instance::new(token_a, client.clone()).balanceOf(address).call().await?._0;
+        let liquidity_b: U256 = IERC20::IERC20Instance::new(token_b, client.clone()).balanceOf(address).call().await?._0;
+
+        Ok(MaverickV2Pool {
+            address,
+            token_a,
+            token_b,
+            liquidity_a,
+            liquidity_b,
+            fee_a_in,
+            fee_b_in,
+            token_a_scale,
+            token_b_scale,
+            bin_width,
+            active_tick,
+            active_bin_id,
+            bins,
+            encoder: MaverickV2AbiSwapEncoder::new(address),
+        })
+    }
+
+    fn tick_sqrt_price(&self, tick: i32) -> f64 {
+        1.0001f64.powf(self.bin_width as f64 * tick as f64 / 2.0)
+    }
+
+    fn to_scale(amount: U256, scale: U256) -> f64 {
+        (amount.saturating_to::<u128>() as f64) * DEFAULT_SCALE / (scale.saturating_to::<u128>() as f64)
+    }
+
+    fn from_scale(amount: f64, scale: U256) -> U256 {
+        U256::from((amount * (scale.saturating_to::<u128>() as f64) / DEFAULT_SCALE).floor() as u128)
+    }
+
+    /// Approximates the swap output with the virtual reserves of the active tick, crossing into cached adjacent ticks for
+    /// larger amounts. Fails when the cached ticks do not hold enough liquidity. Reserves are a load time snapshot, use
+    /// `calculate_out_amount` for exact amounts.
+    pub fn approximate_out_amount(&self, token_address_from: &Address, in_amount: U256) -> Result<U256> {
+        let token_a_in = *token_address_from == self.token_a;
+        let (fee, scale_in, scale_out) = if token_a_in {
+            (self.fee_a_in, self.token_a_scale, self.token_b_scale)
+        } else {
+            (self.fee_b_in, self.token_b_scale, self.token_a_scale)
+        };
+
+        let mut remaining = Self::to_scale(in_amount, scale_in) * (1.0 - (fee.saturating_to::<u128>() as f64) / DEFAULT_SCALE);
+        let mut out_amount = 0.0;
+
+        // Swapping token A in moves the price down
+        let ticks: Vec<&MaverickV2TickReserves> = if token_a_in {
+            self.bins.iter().rev().filter(|bin| bin.tick <= self.active_tick).collect()
+        } else {
+            self.bins.iter().filter(|bin| bin.tick >= self.active_tick).collect()
+        };
+
+        for bin in ticks {
+            let reserve_a = bin.reserve_a as f64;
+            let reserve_b = bin.reserve_b as f64;
+            let sqrt_lower = self.tick_sqrt_price(bin.tick);
+            let sqrt_upper = self.tick_sqrt_price(bin.tick + 1);
+
+            // Liquidity solving (A + L / sqrt_upper) * (B + L * sqrt_lower) = L^2
+            let price_ratio = 1.0 - sqrt_lower / sqrt_upper;
+            let b = reserve_a * sqrt_lower + reserve_b / sqrt_upper;
+            let liquidity = (b + (b * b + 4.0 * price_ratio * reserve_a * reserve_b).sqrt()) / (2.0 * price_ratio);
+            if liquidity <= 0.0 {
+                continue;
+            }
+            let virtual_a = reserve_a + liquidity / sqrt_upper;
+            let virtual_b = reserve_b + liquidity * sqrt_lower;
+
+            let (virtual_in, virtual_out, reserve_out, in_to_exhaust) = if token_a_in {
+                (virtual_a, virtual_b, reserve_b, liquidity / sqrt_lower - virtual_a)
+            } else {
+                (virtual_b, virtual_a, reserve_a, liquidity * sqrt_upper - virtual_b)
+            };
+
+            let tick_out = virtual_out - liquidity * liquidity / (virtual_in + remaining);
+            if tick_out < reserve_out {
+                out_amount += tick_out;
+                remaining = 0.0;
+                break;
+            }
+            out_amount += reserve_out;
+            remaining -= in_to_exhaust.max(0.0);
+        }
+
+        if remaining > 0.0 {
+            return Err(eyre!("NOT_ENOUGH_CACHED_LIQUIDITY"));
+        }
+
+        Ok(Self::from_scale(out_amount, scale_out))
+    }
+}
+
+impl Pool for MaverickV2Pool {
+    fn as_any<'a>(&self) -> &dyn Any {
+        self
+    }
+
+    fn get_class(&self) -> PoolClass {
+        PoolClass::MaverickV2
+    }
+
+    fn get_protocol(&self) -> PoolProtocol {
+        PoolProtocol::MaverickV2
+    }
+
+    fn get_address(&self) -> Address {
+        self.address
+    }
+
+    fn get_pool_id(&self) -> PoolId {
+        PoolId::Address(self.address)
+    }
+
+    fn get_fee(&self) -> U256 {
+        self.fee_a_in
+    }
+
+    fn get_tokens(&self) -> Vec<Address> {
+        vec![self.token_a, self.token_b]
+    }
+
+    fn get_swap_directions(&self) -> Vec<SwapDirection> {
+        vec![(self.token_a, self.token_b).into(), (self.token_b, self.token_a).into()]
+    }
+
+    fn calculate_out_amount(
+        &self,
+        state_db: &dyn DatabaseRef<Error = ErrReport>,
+        env: Env,
+        token_address_from: &Address,
+        token_address_to: &Address,
+        in_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        if in_amount >= U256::from(U128::MAX) {
+            error!("IN_AMOUNT_EXCEEDS_MAX {}", self.get_address().to_checksum(None));
+            return Err(eyre!("IN_AMOUNT_EXCEEDS_MAX"));
+        }
+
+        let token_a_in = MaverickV2Pool::get_token_a_in(token_address_from, token_address_to);
+
+        let mut env = env;
+        env.tx.gas_limit = 1_500_000;
+
+        let call_data_vec = calculateSwapCall {
+            pool: self.address,
+            amount: in_amount.to(),
+            tokenAIn: token_a_in,
+            exactOutput: false,
+            tickLimit: MaverickV2Pool::get_tick_limit(token_a_in),
+        }
+        .abi_encode();
+
+        let (value, gas_used) = evm_call(state_db, env, PeripheryAddress::MAVERICK_V2_QUOTER, call_data_vec)?;
+
+        let ret = calculateSwapCall::abi_decode_returns(&value, false)?.amountOut;
+
+        if ret.is_zero() {
+            Err(eyre!("ZERO_OUT_AMOUNT"))
+        } else {
+            Ok((ret.checked_sub(*U256_ONE).ok_or_eyre("SUBTRACTION_OVERFLOWN")?, gas_used))
+        }
+    }
+
+    fn calculate_in_amount(
+        &self,
+        state_db: &dyn DatabaseRef<Error = ErrReport>,
+        env: Env,
+        token_address_from: &Address,
+        token_address_to: &Address,
+        out_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        if out_amount >= U256::from(U128::MAX) {
+            error!("OUT_AMOUNT_EXCEEDS_MAX {}", self.get_address().to_checksum(None));
+            return Err(eyre!("OUT_AMOUNT_EXCEEDS_MAX"));
+        }
+
+        let token_a_in = MaverickV2Pool::get_token_a_in(token_address_from, token_address_to);
+
+        let mut env = env;
+        env.tx.gas_limit = 1_500_000;
+
+        let call_data_vec = calculateSwapCall {
+            pool: self.address,
+            amount: out_amount.to(),
+            tokenAIn: token_a_in,
+            exactOutput: true,
+            tickLimit: MaverickV2Pool::get_tick_limit(token_a_in),
+        }
+        .abi_encode();
+
+        let (value, gas_used) = evm_call(state_db, env, PeripheryAddress::MAVERICK_V2_QUOTER, call_data_vec)?;
+
+        let ret = calculateSwapCall::abi_decode_returns(&value, false)?.amountIn;
+
+        if ret.is_zero() {
+            Err(eyre!("ZERO_IN_AMOUNT"))
+        } else {
+            Ok((ret.checked_add(*U256_ONE).ok_or_eyre("ADD_OVERFLOWN")?, gas_used))
+        }
+    }
+
+    // The multicaller has no maverickV2SwapCallback, the input is transferred to the pool before the swap
+    fn can_flash_swap(&self) -> bool {
+        false
+    }
+
+    fn can_calculate_in_amount(&self) -> bool {
+        true
+    }
+
+    fn get_abi_encoder(&self) -> Option<&dyn PoolAbiEncoder> {
+        Some(&self.encoder)
+    }
+
+    fn get_read_only_cell_vec(&self) -> Vec<U256> {
+        Vec::new()
+    }
+
+    fn get_state_required(&self) -> Result<RequiredState> {
+        let pool_address = self.get_address();
+
+        let mut state_required = RequiredState::new();
+        state_required.add_call(pool_address, IMaverickV2Pool::getStateCall {}.abi_encode());
+
+        for tick in self.active_tick - ADJACENT_TICKS..=self.active_tick + ADJACENT_TICKS {
+            state_required.add_call(pool_address, IMaverickV2Pool::getTickCall { tick }.abi_encode());
+        }
+
+        // Pool slots are collected from the prestate traces of the view and quoter calls, which read exactly the state
+        // and bin storage a swap touches
+        for (token_a_in, liquidity) in [(true, self.liquidity_a), (false, self.liquidity_b)] {
+            let quoter_swap_call = calculateSwapCall {
+                pool: pool_address,
+                amount: (liquidity / U256::from(100)).saturating_to(),
+                tokenAIn: token_a_in,
+                exactOutput: false,
+                tickLimit: MaverickV2Pool::get_tick_limit(token_a_in),
+            }
+            .abi_encode();
+            state_required.add_call(PeripheryAddress::MAVERICK_V2_QUOTER, quoter_swap_call);
+        }
+
+        for token_address in self.get_tokens() {
+            state_required.add_call(token_address, IERC20::balanceOfCall { account: pool_address }.abi_encode());
+        }
+
+        Ok(state_required)
+    }
+
+    fn is_native(&self) -> bool {
+        false
+    }
+
+    fn preswap_requirement(&self) -> PreswapRequirement {
+        PreswapRequirement::Base
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Copy)]
+struct MaverickV2AbiSwapEncoder {
+    pool_address: Address,
+}
+
+impl MaverickV2AbiSwapEncoder {
+    pub fn new(pool_address: Address) -> Self {
+        Self { pool_address }
+    }
+}
+
+impl PoolAbiEncoder for MaverickV2AbiSwapEncoder {
+    fn encode_swap_in_amount_provided(
+        &self,
+        token_from_address: Address,
+        token_to_address: Address,
+        amount: U256,
+        recipient: Address,
+        payload: Bytes,
+    ) -> Result<Bytes> {
+        let token_a_in = MaverickV2Pool::get_token_a_in(&token_from_address, &token_to_address);
+
+        let swap_call = IMaverickV2Pool::swapCall {
+            recipient,
+            params: SwapParams { amount, tokenAIn: token_a_in, exactOutput: false, tickLimit: MaverickV2Pool::get_tick_limit(token_a_in) },
+            data: payload,
+        };
+
+        Ok(Bytes::from(swap_call.abi_encode()))
+    }
+
+    fn encode_swap_out_amount_provided(
+        &self,
+        token_from_address: Address,
+        token_to_address: Address,
+        amount: U256,
+        recipient: Address,
+        payload: Bytes,
+    ) -> Result<Bytes> {
+        let token_a_in = MaverickV2Pool::get_token_a_in(&token_from_address, &token_to_address);
+
+        let swap_call = IMaverickV2Pool::swapCall {
+            recipient,
+            params: SwapParams { amount, tokenAIn: token_a_in, exactOutput: true, tickLimit: MaverickV2Pool::get_tick_limit(token_a_in) },
+            data: payload,
+        };
+
+        Ok(Bytes::from(swap_call.abi_encode()))
+    }
+
+    fn swap_in_amount_offset(&self, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0x24)
+    }
+    fn swap_out_amount_offset(&self, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0x24)
+    }
+    fn swap_out_amount_return_offset(&self, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0x20)
+    }
+    fn swap_in_amount_return_offset(&self, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0x0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::providers::network::primitives::BlockTransactionsKind;
+    use alloy::rpc::types::BlockNumberOrTag;
+    use loom_defi_abi::maverick2::IMaverickV2Quoter::IMaverickV2QuoterInstance;
+    use loom_evm_db::LoomDBType;
+    use loom_evm_utils::evm_env::env_for_block;
+    use loom_node_debug_provider::AnvilDebugProviderFactory;
+    use loom_types_entities::required_state::RequiredStateReader;
+    use loom_types_entities::MarketState;
+    use std::env;
+
+    fn test_pool(bins: Vec<MaverickV2TickReserves>) -> MaverickV2Pool {
+        let address = Address::repeat_byte(1);
+        MaverickV2Pool {
+            address,
+            token_a: Address::repeat_byte(2),
+            token_b: Address::repeat_byte(3),
+            liquidity_a: U256::ZERO,
+            liquidity_b: U256::ZERO,
+            fee_a_in: U256::ZERO,
+            fee_b_in: U256::ZERO,
+            token_a_scale: U256::from(10).pow(U256::from(18)),
+            token_b_scale: U256::from(10).pow(U256::from(18)),
+            bin_width: 10,
+            active_tick: 0,
+            active_bin_id: 1,
+            bins,
+            encoder: MaverickV2AbiSwapEncoder::new(address),
+        }
+    }
+
+    #[test]
+    fn test_approximate_out_amount() {
+        let reserve = 1_000_000_000_000_000_000_000u128;
+        let pool = test_pool(vec![
+            MaverickV2TickReserves { tick: -1, reserve_a: 0, reserve_b: reserve },
+            MaverickV2TickReserves { tick: 0, reserve_a: reserve, reserve_b: reserve },
+            MaverickV2TickReserves { tick: 1, reserve_a: reserve, reserve_b: 0 },
+        ]);
+        let one = U256::from(10).pow(U256::from(18));
+
+        // Price of the active tick is within 0.1% of one
+        for token_from in [pool.token_a, pool.token_b] {
+            let out_amount = pool.approximate_out_amount(&token_from, one).unwrap();
+            assert!(out_amount.abs_diff(one) < one / U256::from(1000), "{out_amount}");
+        }
+
+        // Crossing into the adjacent tick
+        let in_amount = U256::from(reserve) * U256::from(3) / U256::from(2);
+        let out_amount = pool.approximate_out_amount(&pool.token_a, in_amount).unwrap();
+        assert!(out_amount > U256::from(reserve) && out_amount < in_amount);
+
+        assert!(pool.approximate_out_amount(&pool.token_a, U256::from(reserve) * U256::from(10)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pool() -> Result<()> {
+        let node_url = env::var("MAINNET_WS")?;
+
+        let client = AnvilDebugProviderFactory::from_node_on_block(node_url, 20935488).await?;
+
+        let factory = IMaverickV2FactoryInstance::new(FactoryAddress::MAVERICK_V2, client.clone());
+        let pool_address = *factory.lookup_2(U256::ZERO, U256::from(1)).call().await?.pools.first().ok_or_eyre("NO_POOLS")?;
+
+        let pool = MaverickV2Pool::fetch_pool_data(client.clone(), pool_address).await?;
+
+        let state_required = pool.get_state_required()?;
+        let state_update = RequiredStateReader::fetch_calls_and_slots(client.clone(), state_required, None).await?;
+
+        let mut market_state = MarketState::new(LoomDBType::default());
+        market_state.state_db.apply_geth_update(state_update);
+
+        let block_number = client.get_block_number().await?;
+        let block = client.get_block_by_number(BlockNumberOrTag::Number(block_number), BlockTransactionsKind::Hashes).await?.unwrap();
+        let evm_env = env_for_block(block.header.number, block.header.timestamp);
+
+        let quoter = IMaverickV2QuoterInstance::new(PeripheryAddress::MAVERICK_V2_QUOTER, client.clone());
+
+        for (token_from, token_to, liquidity) in
+            [(pool.token_a, pool.token_b, pool.liquidity_a), (pool.token_b, pool.token_a, pool.liquidity_b)]
+        {
+            let amount = liquidity / U256::from(1000);
+            let token_a_in = token_from == pool.token_a;
+
+            let contract_amount_out = quoter
+                .calculateSwap(pool_address, amount.to(), token_a_in, false, MaverickV2Pool::get_tick_limit(token_a_in))
+                .call()
+                .await?
+                .amountOut;
+
+            let (out_amount, gas_used) =
+                pool.calculate_out_amount(&market_state.state_db, evm_env.clone(), &token_from, &token_to, amount)?;
+            assert_eq!(out_amount, contract_amount_out - U256::from(1));
+            assert!(gas_used > 50000);
+
+            // Active tick virtual reserves approximate the quoter within 1%
+            let approximate_out_amount = pool.approximate_out_amount(&token_from, amount)?;
+            let delta = approximate_out_amount.abs_diff(contract_amount_out);
+            assert!(delta * U256::from(100) <= contract_amount_out, "{approximate_out_amount} {contract_amount_out}");
+        }
+
+        Ok(())
+    }
+}
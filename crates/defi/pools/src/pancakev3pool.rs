@@ -288,6 +288,10 @@ impl Pool for PancakeV3Pool {
         true
     }
 
+    fn supports_exact_output(&self) -> bool {
+        true
+    }
+
     fn get_abi_encoder(&self) -> Option<&dyn PoolAbiEncoder> {
         Some(&self.encoder)
     }
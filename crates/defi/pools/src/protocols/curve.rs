@@ -9,10 +9,13 @@ use eyre::{eyre, Report, Result};
 use tracing::{debug, error, trace};
 use tokio::time;
 
+use crate::virtual_impl::CurveStableSwapParams;
+
 use loom_defi_abi::curve::ICurveAddressProvider::ICurveAddressProviderInstance;
 use loom_defi_abi::curve::ICurveCommon::ICurveCommonInstance;
 use loom_defi_abi::curve::ICurveCommonI128::ICurveCommonI128Instance;
 use loom_defi_abi::curve::ICurveFactory::ICurveFactoryInstance;
+use loom_defi_abi::curve::ICurveRegistry::ICurveRegistryInstance;
 use loom_defi_abi::curve::ICurveI128_2::ICurveI128_2Instance;
 use loom_defi_abi::curve::ICurveI128_2_To::{ICurveI128_2_ToCalls, ICurveI128_2_ToInstance};
 use loom_defi_abi::curve::ICurveI128_2_To_Meta::ICurveI128_2_To_MetaInstance;
@@ -29,6 +32,9 @@ use loom_defi_abi::curve::{
     ICurveU256_3_Eth, ICurveU256_3_Eth_To, ICurveU256_3_Eth_To2,
 };
 
+/// Main Curve registry of StableSwap pools
+pub const CURVE_REGISTRY_ADDRESS: Address = address!("90E00ACe148ca3b23Ac1bC8C240C2a7Dd9c2d7f6");
+
 #[derive(Clone, Debug)]
 pub enum CurveContract<P, N>
 where
@@ -590,6 +596,33 @@ where
         }
     }
 
+    /// Fetch StableSwap invariant parameters from the main Curve registry. Fails for pools that are not in the registry
+    pub async fn get_stableswap_params(client: P, pool_address: Address) -> Result<CurveStableSwapParams> {
+        let registry = ICurveRegistryInstance::new(CURVE_REGISTRY_ADDRESS, client);
+
+        let n_coins = match registry.get_n_coins(pool_address).call().await {
+            Ok(x) => x._0[0].to::<usize>(),
+            Err(e) => {
+                debug!("Error getting n_coins from registry {} : {}", pool_address, e);
+                return Err(eyre!("POOL_NOT_IN_REGISTRY"));
+            }
+        };
+        if n_coins == 0 {
+            return Err(eyre!("POOL_NOT_IN_REGISTRY"));
+        }
+
+        let amp = registry.get_A(pool_address).call().await.map_err(|e| eyre!("GET_A_ERROR : {}", e))?._0;
+        let rates = registry.get_rates(pool_address).call().await.map_err(|e| eyre!("GET_RATES_ERROR : {}", e))?._0;
+        let fees = registry.get_fees(pool_address).call().await.map_err(|e| eyre!("GET_FEES_ERROR : {}", e))?._0;
+
+        let rates: Vec<U256> = rates.into_iter().take(n_coins).collect();
+        if amp.is_zero() || rates.iter().any(|rate| rate.is_zero()) {
+            return Err(eyre!("BAD_STABLESWAP_PARAMS"));
+        }
+
+        Ok(CurveStableSwapParams { amp, rates, fee: fees[0] })
+    }
+
     pub async fn get_contract_from_code(client: P, address: Address) -> Result<CurveContract<P, N>> {
         //let sig = ICurveU256_3_EthCalls::Balances(  <ICurveU256_3_Eth<M>>::BalancesCall );
         //let sig = ICurveU256_3_EthCalls::Balances(  BalancesCall{} );
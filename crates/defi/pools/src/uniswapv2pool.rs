@@ -222,6 +222,11 @@ impl Pool for UniswapV2Pool {
         self.fee
     }
 
+    fn calculate_fee_amount(&self, in_amount: U256) -> U256 {
+        // `fee` is the share of the input left after the fee in 1/10000 units
+        in_amount * (U256::from(10000) - self.fee) / U256::from(10000)
+    }
+
     fn get_tokens(&self) -> Vec<Address> {
         vec![self.token0, self.token1]
     }
@@ -515,4 +520,13 @@ mod test {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_calculate_fee_amount() {
+        let pool = UniswapV2Pool::new(POOL_ADDRESSES[0]);
+        assert_eq!(pool.calculate_fee_amount(U256::from(1_000_000)), U256::from(3_000));
+
+        let pool = pool.set_fee(U256::from(9900));
+        assert_eq!(pool.calculate_fee_amount(U256::from(1_000_000)), U256::from(10_000));
+    }
 }
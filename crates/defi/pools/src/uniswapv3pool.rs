@@ -1,10 +1,11 @@
 use std::any::Any;
 use std::fmt::Debug;
 use std::ops::Sub;
+use std::sync::{Arc, RwLock};
 
 use crate::state_readers::UniswapV3QuoterV2StateReader;
 use crate::state_readers::{UniswapV3QuoterV2Encoder, UniswapV3StateReader};
-use crate::virtual_impl::UniswapV3PoolVirtual;
+use crate::virtual_impl::{PreloadedTicks, UniswapV3PoolVirtual};
 use alloy::primitives::{Address, Bytes, I256, U160, U256};
 use alloy::providers::{Network, Provider};
 use alloy::sol_types::{SolCall, SolInterface};
@@ -13,7 +14,7 @@ use lazy_static::lazy_static;
 use loom_defi_abi::uniswap3::IUniswapV3Pool;
 use loom_defi_abi::uniswap3::IUniswapV3Pool::slot0Return;
 use loom_defi_abi::uniswap_periphery::ITickLens;
-use loom_defi_abi::IERC20;
+use loom_defi_abi::{IMulticall3, IERC20};
 use loom_defi_address_book::{FactoryAddress, PeripheryAddress};
 use loom_types_entities::required_state::RequiredState;
 use loom_types_entities::{Pool, PoolAbiEncoder, PoolClass, PoolId, PoolProtocol, PreswapRequirement, SwapDirection};
@@ -69,6 +70,8 @@ pub struct UniswapV3Pool {
     factory: Address,
     protocol: PoolProtocol,
     encoder: UniswapV3AbiSwapEncoder,
+    // Shared by the clones of the pool, so ticks invalidated on the market pool are dropped from the swap paths as well
+    preloaded_ticks: Arc<RwLock<Option<Arc<PreloadedTicks>>>>,
 }
 
 impl UniswapV3Pool {
@@ -85,6 +88,7 @@ impl UniswapV3Pool {
             factory: Address::ZERO,
             protocol: PoolProtocol::UniswapV3Like,
             encoder: UniswapV3AbiSwapEncoder::new(address),
+            preloaded_ticks: Default::default(),
         }
     }
 
@@ -109,6 +113,27 @@ impl UniswapV3Pool {
             factory,
             protocol: PoolProtocol::UniswapV3Like,
             encoder: UniswapV3AbiSwapEncoder::new(address),
+            preloaded_ticks: Default::default(),
+        }
+    }
+
+    pub fn with_preloaded_ticks(self, preloaded_ticks: PreloadedTicks) -> Self {
+        Self { preloaded_ticks: Arc::new(RwLock::new(Some(Arc::new(preloaded_ticks)))), ..self }
+    }
+
+    pub fn preloaded_ticks(&self) -> Option<Arc<PreloadedTicks>> {
+        self.preloaded_ticks.read().ok().and_then(|preloaded_ticks| preloaded_ticks.clone())
+    }
+
+    /// Drop the preloaded ticks after a liquidity change, swaps are quoted with the EVM until they are reloaded
+    pub fn invalidate_preloaded_ticks(&self) -> bool {
+        self.preloaded_ticks.write().is_ok_and(|mut preloaded_ticks| preloaded_ticks.take().is_some())
+    }
+
+    /// Replace the preloaded ticks of the pool and all its clones
+    pub fn set_preloaded_ticks(&self, preloaded_ticks: PreloadedTicks) {
+        if let Ok(mut guard) = self.preloaded_ticks.write() {
+            *guard = Some(Arc::new(preloaded_ticks));
         }
     }
 
@@ -178,6 +203,7 @@ impl UniswapV3Pool {
             factory,
             protocol,
             encoder: UniswapV3AbiSwapEncoder { pool_address: address },
+            preloaded_ticks: Default::default(),
         };
         debug!("fetch_pool_data_evm {:?} {:?} {} {:?} {}", token0, token1, fee, factory, protocol);
 
@@ -214,10 +240,71 @@ impl UniswapV3Pool {
             factory,
             protocol,
             encoder: UniswapV3AbiSwapEncoder::new(address),
+            preloaded_ticks: Default::default(),
         };
 
         Ok(ret)
     }
+
+    /// Fetches initialized ticks within `current tick ± tick_range` with TickLens calls for all bitmap words batched into
+    /// one Multicall3 `eth_call`
+    pub async fn fetch_preloaded_ticks<N: Network, P: Provider<N> + Send + Sync + Clone + 'static>(
+        &self,
+        client: P,
+        tick_range: i32,
+    ) -> Result<PreloadedTicks> {
+        let tick = self.slot0.as_ref().ok_or_eyre("SLOT0_NOT_SET")?.tick;
+        self.fetch_ticks_around(client, tick, tick_range).await
+    }
+
+    /// Preloads the ticks again around the current tick of the pool, e.g. after they were invalidated
+    pub async fn reload_preloaded_ticks<N: Network, P: Provider<N> + Send + Sync + Clone + 'static>(
+        &self,
+        client: P,
+        tick_range: i32,
+    ) -> Result<()> {
+        let slot0: Slot0 = IUniswapV3Pool::IUniswapV3PoolInstance::new(self.address, client.clone()).slot0().call().await?.into();
+        let preloaded_ticks = self.fetch_ticks_around(client, slot0.tick, tick_range).await?;
+        self.set_preloaded_ticks(preloaded_ticks);
+        Ok(())
+    }
+
+    async fn fetch_ticks_around<N: Network, P: Provider<N> + Send + Sync + Clone + 'static>(
+        &self,
+        client: P,
+        tick: i32,
+        tick_range: i32,
+    ) -> Result<PreloadedTicks> {
+        let tick_spacing = self.tick_spacing();
+        if tick_spacing == 0 {
+            return Err(eyre!("BAD_PRICE_STEP"));
+        }
+        let min_word = UniswapV3Pool::get_tick_bitmap_index(tick.saturating_sub(tick_range), tick_spacing);
+        let max_word = UniswapV3Pool::get_tick_bitmap_index(tick.saturating_add(tick_range), tick_spacing);
+
+        let tick_lens = PeripheryAddress::uniswap_v3_tick_lens(client.get_chain_id().await?);
+        let calls: Vec<IMulticall3::Call3> = (min_word..=max_word)
+            .map(|tick_bitmap_index| IMulticall3::Call3 {
+                target: tick_lens,
+                allowFailure: false,
+                callData: ITickLens::getPopulatedTicksInWordCall { pool: self.address, tickBitmapIndex: tick_bitmap_index }
+                    .abi_encode()
+                    .into(),
+            })
+            .collect();
+
+        let multicall = IMulticall3::IMulticall3Instance::new(PeripheryAddress::MULTICALL3, client);
+        let results = multicall.aggregate3(calls).call().await?.returnData;
+
+        let mut ticks: Vec<(i32, i128)> = Vec::new();
+        for result in results {
+            let populated_ticks = ITickLens::getPopulatedTicksInWordCall::abi_decode_returns(&result.returnData, false)?.populatedTicks;
+            ticks.extend(populated_ticks.into_iter().map(|populated_tick| (populated_tick.tick.as_i32(), populated_tick.liquidityNet)));
+        }
+        debug!(address = %self.address, tick, min_word, max_word, ticks = ticks.len(), "Ticks preloaded");
+
+        Ok(PreloadedTicks::new(tick_spacing, min_word, max_word, ticks))
+    }
 }
 
 impl Pool for UniswapV3Pool {
@@ -243,6 +330,10 @@ impl Pool for UniswapV3Pool {
         U256::from(self.fee)
     }
 
+    fn calculate_fee_amount(&self, in_amount: U256) -> U256 {
+        in_amount * U256::from(self.fee) / U256::from(1_000_000)
+    }
+
     fn get_tokens(&self) -> Vec<Address> {
         vec![self.token0, self.token1]
     }
@@ -281,6 +372,13 @@ impl Pool for UniswapV3Pool {
                 };
             }
             (ret_virtual, 150_000)
+        } else if let Some(ret_virtual) = self.preloaded_ticks().and_then(|preloaded_ticks| {
+            // Swaps crossing ticks outside of the preloaded range are quoted with the QuoterV2
+            UniswapV3PoolVirtual::simulate_swap_in_amount_preloaded(&state_db, self, &preloaded_ticks, *token_address_from, in_amount)
+                .inspect_err(|error| debug!(%error, address = %self.address, "Preloaded ticks miss"))
+                .ok()
+        }) {
+            (ret_virtual, 150_000)
         } else {
             let mut env = _env;
             env.tx.gas_limit = 1_000_000;
@@ -335,6 +433,12 @@ impl Pool for UniswapV3Pool {
                 }
             }
             (ret_virtual, 150000)
+        } else if let Some(ret_virtual) = self.preloaded_ticks().and_then(|preloaded_ticks| {
+            UniswapV3PoolVirtual::simulate_swap_out_amount_preloaded(&state_db, self, &preloaded_ticks, *token_address_from, out_amount)
+                .inspect_err(|error| debug!(%error, address = %self.address, "Preloaded ticks miss"))
+                .ok()
+        }) {
+            (ret_virtual, 150000)
         } else {
             let mut env = _env;
             env.tx.gas_limit = 1_000_000;
@@ -365,6 +469,10 @@ impl Pool for UniswapV3Pool {
         true
     }
 
+    fn supports_exact_output(&self) -> bool {
+        true
+    }
+
     fn get_abi_encoder(&self) -> Option<&dyn PoolAbiEncoder> {
         Some(&self.encoder)
     }
@@ -444,7 +552,7 @@ impl Pool for UniswapV3Pool {
 
 #[allow(dead_code)]
 #[derive(Clone, Copy)]
-struct UniswapV3AbiSwapEncoder {
+pub(crate) struct UniswapV3AbiSwapEncoder {
     pool_address: Address,
 }
 
@@ -542,6 +650,27 @@ mod test {
     ];
     const BLOCK_NUMBER: u64 = 20935488u64;
 
+    #[test]
+    fn test_invalidate_preloaded_ticks() {
+        let pool = UniswapV3Pool::new(UniswapV3PoolAddress::USDC_WETH_3000).with_preloaded_ticks(PreloadedTicks::new(60, -1, 1, [(0, 10)]));
+        let path_pool = pool.clone();
+        assert_eq!(path_pool.preloaded_ticks().map(|ticks| ticks.len()), Some(1));
+
+        assert!(pool.invalidate_preloaded_ticks());
+        assert!(path_pool.preloaded_ticks().is_none());
+        assert!(!pool.invalidate_preloaded_ticks());
+
+        // Reloaded ticks are shared with the clones again
+        pool.set_preloaded_ticks(PreloadedTicks::new(60, -1, 1, [(0, 10), (60, -10)]));
+        assert_eq!(path_pool.preloaded_ticks().map(|ticks| ticks.len()), Some(2));
+    }
+
+    #[test]
+    fn test_tick_lens_by_chain() {
+        assert_eq!(PeripheryAddress::uniswap_v3_tick_lens(1), PeripheryAddress::UNISWAP_V3_TICK_LENS);
+        assert_eq!(PeripheryAddress::uniswap_v3_tick_lens(8453), PeripheryAddress::UNISWAP_V3_TICK_LENS_BASE);
+    }
+
     #[tokio::test]
     async fn test_pool_tokens() -> Result<()> {
         let node_url = env::var("MAINNET_WS")?;
@@ -784,4 +913,18 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_calculate_fee_amount() {
+        let pool = UniswapV3Pool::new_with_data(
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+            0,
+            500,
+            None,
+            Address::ZERO,
+        );
+        assert_eq!(pool.calculate_fee_amount(U256::from(1_000_000)), U256::from(500));
+    }
 }
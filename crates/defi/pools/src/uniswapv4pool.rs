@@ -0,0 +1,438 @@
+use std::any::Any;
+
+use crate::db_reader::UniswapV4DBReader;
+use crate::virtual_impl::UniswapV4PoolVirtual;
+use alloy::primitives::aliases::{I24, U24};
+use alloy::primitives::{keccak256, Address, Bytes, B256, I256, U256};
+use alloy::providers::{Network, Provider};
+use alloy::rpc::types::Filter;
+use alloy::sol_types::{SolCall, SolEvent, SolValue};
+use eyre::{eyre, ErrReport, OptionExt, Result};
+use loom_defi_abi::uniswap4::IUniswapV4PoolManagerEvents::Initialize;
+use loom_defi_abi::uniswap4::{IStateView, IUniswapV4PoolManager, IV4Quoter, PoolKey};
+use loom_defi_address_book::{FactoryAddress, PeripheryAddress, TokenAddressEth};
+use loom_defi_uniswap_v3_math::tick_math::{MAX_SQRT_RATIO, MIN_SQRT_RATIO};
+use loom_evm_utils::evm::evm_call;
+use loom_types_entities::required_state::RequiredState;
+use loom_types_entities::{Pool, PoolAbiEncoder, PoolClass, PoolId, PoolProtocol, PreswapRequirement, SwapDirection};
+use revm::primitives::Env;
+use revm::DatabaseRef;
+use tracing::debug;
+
+const Q96: U256 = U256::from_limbs([0, 0x100000000, 0, 0]);
+
+/// Uniswap V4 pool living in the singleton PoolManager.
+/// V4 pools have no own address, `get_address` returns the lower 20 bytes of the pool id to keep pools unique in collections.
+#[derive(Clone)]
+pub struct UniswapV4Pool {
+    pub pool_id: B256,
+    /// Currencies as stored in the pool key, zero address is native ETH
+    pub currency0: Address,
+    pub currency1: Address,
+    pub fee: u32,
+    pub tick_spacing: i32,
+    pub hooks: Address,
+    pub liquidity: u128,
+    pub sqrt_price_x96: U256,
+    pub tick: i32,
+}
+
+impl UniswapV4Pool {
+    pub fn from_pool_key(pool_key: PoolKey) -> Self {
+        let pool_id = keccak256(pool_key.abi_encode());
+        UniswapV4Pool {
+            pool_id,
+            currency0: pool_key.currency0,
+            currency1: pool_key.currency1,
+            fee: pool_key.fee.to(),
+            tick_spacing: pool_key.tickSpacing.as_i32(),
+            hooks: pool_key.hooks,
+            liquidity: 0,
+            sqrt_price_x96: U256::ZERO,
+            tick: 0,
+        }
+    }
+
+    pub fn pool_key(&self) -> Result<PoolKey> {
+        Ok(PoolKey {
+            currency0: self.currency0,
+            currency1: self.currency1,
+            fee: U24::from(self.fee),
+            tickSpacing: I24::try_from(self.tick_spacing)?,
+            hooks: self.hooks,
+        })
+    }
+
+    pub fn hooks(&self) -> Address {
+        self.hooks
+    }
+
+    pub fn has_hooks(&self) -> bool {
+        !self.hooks.is_zero()
+    }
+
+    fn currency_to_token(currency: Address) -> Address {
+        if currency.is_zero() {
+            TokenAddressEth::WETH
+        } else {
+            currency
+        }
+    }
+
+    fn get_zero_for_one(&self, token_address_from: &Address) -> bool {
+        *token_address_from == Self::currency_to_token(self.currency0)
+    }
+
+    pub async fn fetch_pool_data<N: Network, P: Provider<N> + Send + Sync + Clone + 'static>(client: P, pool_id: B256) -> Result<Self> {
+        let filter = Filter::new()
+            .address(FactoryAddress::UNISWAP_V4_POOL_MANAGER_ADDRESS)
+            .event_signature(Initialize::SIGNATURE_HASH)
+            .topic1(pool_id)
+            .from_block(0);
+
+        let logs = client.get_logs(&filter).await?;
+        let log = logs.first().ok_or_eyre("POOL_NOT_INITIALIZED")?;
+        let initialize = Initialize::decode_log_data(log.data(), false)?;
+
+        let mut pool = UniswapV4Pool::from_pool_key(PoolKey {
+            currency0: initialize.currency0,
+            currency1: initialize.currency1,
+            fee: initialize.fee,
+            tickSpacing: initialize.tickSpacing,
+            hooks: initialize.hooks,
+        });
+        if pool.pool_id != pool_id {
+            return Err(eyre!("POOL_ID_MISMATCH"));
+        }
+
+        let state_view = IStateView::IStateViewInstance::new(PeripheryAddress::UNISWAPV4_STATE_VIEW_ADDRESS, client.clone());
+        let slot0 = state_view.getSlot0(pool_id).call().await?;
+        pool.sqrt_price_x96 = U256::from(slot0.sqrtPriceX96);
+        pool.tick = slot0.tick.as_i32();
+        pool.liquidity = state_view.getLiquidity(pool_id).call().await?.liquidity;
+
+        debug!(
+            "fetch_pool_data {:?} {:?} {:?} fee {} spacing {} hooks {:?}",
+            pool_id, pool.currency0, pool.currency1, pool.fee, pool.tick_spacing, pool.hooks
+        );
+
+        Ok(pool)
+    }
+
+    /// PoolManager swap call, negative `amount_specified` is exact input
+    fn encode_swap(&self, token_from_address: &Address, amount_specified: I256) -> Result<Bytes> {
+        let zero_for_one = self.get_zero_for_one(token_from_address);
+        let sqrt_price_limit_x96 = if zero_for_one { MIN_SQRT_RATIO + U256::from(1) } else { MAX_SQRT_RATIO - U256::from(1) };
+        let swap_call = IUniswapV4PoolManager::swapCall {
+            key: self.pool_key()?,
+            params: IUniswapV4PoolManager::SwapParams {
+                zeroForOne: zero_for_one,
+                amountSpecified: amount_specified,
+                sqrtPriceLimitX96: sqrt_price_limit_x96.to(),
+            },
+            hookData: Bytes::new(),
+        };
+        Ok(Bytes::from(swap_call.abi_encode()))
+    }
+
+    fn quote_call_data(&self, zero_for_one: bool, amount: U256, exact_input: bool) -> Result<Vec<u8>> {
+        let params = IV4Quoter::QuoteExactSingleParams {
+            poolKey: self.pool_key()?,
+            zeroForOne: zero_for_one,
+            exactAmount: amount.try_into().map_err(|_| eyre!("AMOUNT_OVERFLOW"))?,
+            hookData: Bytes::new(),
+        };
+        Ok(if exact_input {
+            IV4Quoter::quoteExactInputSingleCall { params }.abi_encode()
+        } else {
+            IV4Quoter::quoteExactOutputSingleCall { params }.abi_encode()
+        })
+    }
+
+    /// Quote with V4Quoter executing the swap in EVM, hooks are executed as well
+    fn quote_evm(
+        &self,
+        state_db: &dyn DatabaseRef<Error = ErrReport>,
+        mut env: Env,
+        zero_for_one: bool,
+        amount: U256,
+        exact_input: bool,
+    ) -> Result<(U256, u64)> {
+        env.tx.gas_limit = 1_000_000;
+        let call_data = self.quote_call_data(zero_for_one, amount, exact_input)?;
+        let (value, gas_used) = evm_call(&state_db, env, PeripheryAddress::UNISWAP_V4_QUOTER, call_data)?;
+
+        let ret = if exact_input {
+            IV4Quoter::quoteExactInputSingleCall::abi_decode_returns(&value, false)
+                .map_err(|_| eyre!("CANNOT_DECODE_EXACT_INPUT_RETURN"))?
+                .amountOut
+        } else {
+            IV4Quoter::quoteExactOutputSingleCall::abi_decode_returns(&value, false)
+                .map_err(|_| eyre!("CANNOT_DECODE_EXACT_OUTPUT_RETURN"))?
+                .amountIn
+        };
+        Ok((ret, gas_used))
+    }
+}
+
+impl Pool for UniswapV4Pool {
+    fn as_any<'a>(&self) -> &dyn Any {
+        self
+    }
+
+    fn get_class(&self) -> PoolClass {
+        PoolClass::UniswapV4
+    }
+
+    fn get_protocol(&self) -> PoolProtocol {
+        PoolProtocol::UniswapV4
+    }
+
+    fn get_address(&self) -> Address {
+        Address::from_slice(&self.pool_id[12..])
+    }
+
+    fn get_pool_id(&self) -> PoolId {
+        PoolId::Bytes32(self.pool_id)
+    }
+
+    fn get_fee(&self) -> U256 {
+        U256::from(self.fee)
+    }
+
+    fn get_tokens(&self) -> Vec<Address> {
+        vec![Self::currency_to_token(self.currency0), Self::currency_to_token(self.currency1)]
+    }
+
+    fn get_swap_directions(&self) -> Vec<SwapDirection> {
+        let tokens = self.get_tokens();
+        vec![(tokens[0], tokens[1]).into(), (tokens[1], tokens[0]).into()]
+    }
+
+    fn calculate_out_amount(
+        &self,
+        state_db: &dyn DatabaseRef<Error = ErrReport>,
+        env: Env,
+        token_address_from: &Address,
+        _token_address_to: &Address,
+        in_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        let (ret, gas_used) = if self.has_hooks() {
+            self.quote_evm(state_db, env, self.get_zero_for_one(token_address_from), in_amount, true)?
+        } else {
+            match UniswapV4PoolVirtual::simulate_swap_in_amount_provider(&state_db, self, *token_address_from, in_amount) {
+                Ok(ret) => (ret, 150_000),
+                Err(_) => self.quote_evm(state_db, env, self.get_zero_for_one(token_address_from), in_amount, true)?,
+            }
+        };
+
+        if ret.is_zero() {
+            Err(eyre!("RETURN_RESULT_IS_ZERO"))
+        } else {
+            Ok((ret.checked_sub(U256::from(1)).ok_or_eyre("SUB_OVERFLOWN")?, gas_used))
+        }
+    }
+
+    fn calculate_in_amount(
+        &self,
+        state_db: &dyn DatabaseRef<Error = ErrReport>,
+        env: Env,
+        token_address_from: &Address,
+        _token_address_to: &Address,
+        out_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        let (ret, gas_used) = self.quote_evm(state_db, env, self.get_zero_for_one(token_address_from), out_amount, false)?;
+
+        if ret.is_zero() {
+            Err(eyre!("RETURN_RESULT_IS_ZERO"))
+        } else {
+            Ok((ret.checked_add(U256::from(1)).ok_or_eyre("ADD_OVERFLOWN")?, gas_used))
+        }
+    }
+
+    fn can_flash_swap(&self) -> bool {
+        false
+    }
+
+    fn can_calculate_in_amount(&self) -> bool {
+        true
+    }
+
+    fn get_abi_encoder(&self) -> Option<&dyn PoolAbiEncoder> {
+        Some(self)
+    }
+
+    fn get_read_only_cell_vec(&self) -> Vec<U256> {
+        Vec::new()
+    }
+
+    fn get_state_required(&self) -> Result<RequiredState> {
+        let mut state_required = RequiredState::new();
+        let state_view = PeripheryAddress::UNISWAPV4_STATE_VIEW_ADDRESS;
+
+        state_required
+            .add_call(state_view, IStateView::getSlot0Call { poolId: self.pool_id }.abi_encode())
+            .add_call(state_view, IStateView::getLiquidityCall { poolId: self.pool_id }.abi_encode());
+
+        if self.tick_spacing > 0 {
+            let tick_bitmap_index = (self.tick / self.tick_spacing) >> 8;
+            for i in -4..=3 {
+                state_required.add_call(
+                    state_view,
+                    IStateView::getTickBitmapCall { poolId: self.pool_id, tick: (tick_bitmap_index + i) as i16 }.abi_encode(),
+                );
+            }
+        }
+
+        // Quotes in both directions load hooks and PoolManager state touched by a swap
+        if !self.sqrt_price_x96.is_zero() {
+            let liquidity = U256::from(self.liquidity);
+            let amount0 = liquidity * Q96 / self.sqrt_price_x96 / U256::from(100);
+            let amount1 = liquidity * self.sqrt_price_x96 / Q96 / U256::from(100);
+            if !amount0.is_zero() {
+                state_required.add_call(PeripheryAddress::UNISWAP_V4_QUOTER, self.quote_call_data(true, amount0, true)?);
+            }
+            if !amount1.is_zero() {
+                state_required.add_call(PeripheryAddress::UNISWAP_V4_QUOTER, self.quote_call_data(false, amount1, true)?);
+            }
+        }
+
+        Ok(state_required)
+    }
+
+    fn is_native(&self) -> bool {
+        self.currency0.is_zero() || self.currency1.is_zero()
+    }
+
+    fn preswap_requirement(&self) -> PreswapRequirement {
+        PreswapRequirement::Callback
+    }
+
+    fn get_pool_manager_cells(&self) -> Vec<(Address, Vec<U256>)> {
+        let state_slot = UniswapV4DBReader::pool_state_slot(self.pool_id);
+        vec![(FactoryAddress::UNISWAP_V4_POOL_MANAGER_ADDRESS, vec![state_slot, state_slot + U256::from(3)])]
+    }
+
+    fn get_hooks(&self) -> Option<Address> {
+        self.has_hooks().then_some(self.hooks)
+    }
+}
+
+// Swaps are executed by the PoolManager inside unlock, the recipient is paid by take
+impl PoolAbiEncoder for UniswapV4Pool {
+    fn encode_swap_in_amount_provided(
+        &self,
+        token_from_address: Address,
+        _token_to_address: Address,
+        amount: U256,
+        _recipient: Address,
+        _payload: Bytes,
+    ) -> Result<Bytes> {
+        self.encode_swap(&token_from_address, I256::ZERO - I256::from_raw(amount))
+    }
+
+    fn encode_swap_out_amount_provided(
+        &self,
+        token_from_address: Address,
+        _token_to_address: Address,
+        amount: U256,
+        _recipient: Address,
+        _payload: Bytes,
+    ) -> Result<Bytes> {
+        self.encode_swap(&token_from_address, I256::from_raw(amount))
+    }
+
+    // params.amountSpecified, the pool key is encoded in place
+    fn swap_in_amount_offset(&self, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0xC4)
+    }
+
+    fn swap_out_amount_offset(&self, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0xC4)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy::primitives::{address, b256};
+    use alloy::providers::network::primitives::BlockTransactionsKind;
+    use alloy::rpc::types::BlockNumberOrTag;
+    use loom_evm_db::{DatabaseLoomExt, LoomDBType};
+    use loom_node_debug_provider::AnvilDebugProviderFactory;
+    use loom_types_entities::required_state::RequiredStateReader;
+    use loom_types_entities::MarketState;
+
+    // ETH/USDC 0.05% hookless pool
+    const POOL_ID: B256 = b256!("21c67e77068de97969ba93d4aab21826d33ca12bb9f565d8496e8fda8a82ca27");
+    const BLOCK_NUMBER: u64 = 21700000u64;
+
+    #[test]
+    fn test_pool_key_roundtrip() {
+        let pool_key = PoolKey {
+            currency0: Address::ZERO,
+            currency1: address!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"),
+            fee: U24::from(500),
+            tickSpacing: I24::try_from(10).unwrap(),
+            hooks: Address::ZERO,
+        };
+        let pool = UniswapV4Pool::from_pool_key(pool_key.clone());
+        assert_eq!(pool.pool_key().unwrap(), pool_key);
+        assert_eq!(pool.get_tokens(), vec![TokenAddressEth::WETH, pool_key.currency1]);
+        assert!(pool.is_native());
+        assert_eq!(pool.get_hooks(), None);
+    }
+
+    #[test]
+    fn test_encode_swap() {
+        let usdc = address!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48");
+        let pool_key = PoolKey {
+            currency0: usdc,
+            currency1: TokenAddressEth::WETH,
+            fee: U24::from(3000),
+            tickSpacing: I24::try_from(60).unwrap(),
+            hooks: Address::ZERO,
+        };
+        let pool = UniswapV4Pool::from_pool_key(pool_key.clone());
+        let encoder = pool.get_abi_encoder().unwrap();
+        let amount = U256::from(1000);
+
+        let call_data = encoder.encode_swap_in_amount_provided(TokenAddressEth::WETH, usdc, amount, Address::ZERO, Bytes::new()).unwrap();
+        let swap_call = IUniswapV4PoolManager::swapCall::abi_decode(&call_data, true).unwrap();
+        assert_eq!(swap_call.key, pool_key);
+        assert!(!swap_call.params.zeroForOne);
+        assert_eq!(swap_call.params.amountSpecified, I256::ZERO - I256::from_raw(amount));
+        assert_eq!(I256::from_raw(U256::from_be_slice(&call_data[0xC4..0xE4])), I256::ZERO - I256::from_raw(amount));
+
+        let call_data = encoder.encode_swap_out_amount_provided(usdc, TokenAddressEth::WETH, amount, Address::ZERO, Bytes::new()).unwrap();
+        let swap_call = IUniswapV4PoolManager::swapCall::abi_decode(&call_data, true).unwrap();
+        assert!(swap_call.params.zeroForOne);
+        assert_eq!(swap_call.params.amountSpecified, I256::from_raw(amount));
+    }
+
+    #[tokio::test]
+    async fn test_calculate_out_amount() -> Result<()> {
+        let node_url = std::env::var("MAINNET_WS")?;
+        let client = AnvilDebugProviderFactory::from_node_on_block(node_url, BLOCK_NUMBER).await?;
+
+        let pool = UniswapV4Pool::fetch_pool_data(client.clone(), POOL_ID).await?;
+        assert_eq!(pool.get_pool_id(), PoolId::Bytes32(POOL_ID));
+
+        let mut market_state = MarketState::new(LoomDBType::new());
+        let state_required = RequiredStateReader::fetch_calls_and_slots(client.clone(), pool.get_state_required()?, None).await?;
+        market_state.state_db.apply_geth_update(state_required);
+
+        let block_header = client.get_block_by_number(BlockNumberOrTag::Latest, BlockTransactionsKind::Hashes).await?.unwrap().header;
+        let mut evm_env = Env::default();
+        evm_env.block.number = U256::from(block_header.number);
+        evm_env.block.timestamp = U256::from(block_header.timestamp);
+
+        let tokens = pool.get_tokens();
+        let in_amount = U256::from(10).pow(U256::from(17));
+        let (out_virtual, _) = pool.calculate_out_amount(&market_state.state_db, evm_env.clone(), &tokens[0], &tokens[1], in_amount)?;
+        let (out_evm, _) = pool.quote_evm(&market_state.state_db, evm_env, true, in_amount, true)?;
+        assert_eq!(out_virtual, out_evm - U256::from(1));
+
+        Ok(())
+    }
+}
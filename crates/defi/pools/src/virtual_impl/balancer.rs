@@ -0,0 +1,462 @@
+use alloy::primitives::{uint, I256, U256};
+use eyre::{eyre, Result};
+
+const ONE: U256 = uint!(1000000000000000000_U256);
+const MAX_POW_RELATIVE_ERROR: U256 = uint!(10000_U256);
+const MAX_IN_RATIO: U256 = uint!(300000000000000000_U256);
+const MAX_OUT_RATIO: U256 = uint!(300000000000000000_U256);
+const AMP_PRECISION: U256 = uint!(1000_U256);
+const MAX_ITERATIONS: usize = 255;
+
+const ONE_18: I256 = I256::from_raw(uint!(1000000000000000000_U256));
+const ONE_20: I256 = I256::from_raw(uint!(100000000000000000000_U256));
+const ONE_36: I256 = I256::from_raw(uint!(1000000000000000000000000000000000000_U256));
+const MAX_NATURAL_EXPONENT: I256 = I256::from_raw(uint!(130000000000000000000_U256));
+const MIN_NATURAL_EXPONENT_ABS: I256 = I256::from_raw(uint!(41000000000000000000_U256));
+const LN_36_LOWER_BOUND: I256 = I256::from_raw(uint!(900000000000000000_U256));
+const LN_36_UPPER_BOUND: I256 = I256::from_raw(uint!(1100000000000000000_U256));
+const MILD_EXPONENT_BOUND: U256 = uint!(289480223093290488558927462521719769633174961664101410098_U256);
+
+// x_n = 2^(7 - n) and a_n = e^x_n, x0 and x1 with 18 decimals and no decimals in a0 and a1, 20 decimals for the rest
+const X: [I256; 12] = [
+    I256::from_raw(uint!(128000000000000000000_U256)),
+    I256::from_raw(uint!(64000000000000000000_U256)),
+    I256::from_raw(uint!(3200000000000000000000_U256)),
+    I256::from_raw(uint!(1600000000000000000000_U256)),
+    I256::from_raw(uint!(800000000000000000000_U256)),
+    I256::from_raw(uint!(400000000000000000000_U256)),
+    I256::from_raw(uint!(200000000000000000000_U256)),
+    I256::from_raw(uint!(100000000000000000000_U256)),
+    I256::from_raw(uint!(50000000000000000000_U256)),
+    I256::from_raw(uint!(25000000000000000000_U256)),
+    I256::from_raw(uint!(12500000000000000000_U256)),
+    I256::from_raw(uint!(6250000000000000000_U256)),
+];
+const A: [I256; 12] = [
+    I256::from_raw(uint!(38877084059945950922200000000000000000000000000000000000_U256)),
+    I256::from_raw(uint!(6235149080811616882910000000_U256)),
+    I256::from_raw(uint!(7896296018268069516100000000000000_U256)),
+    I256::from_raw(uint!(888611052050787263676000000_U256)),
+    I256::from_raw(uint!(298095798704172827474000_U256)),
+    I256::from_raw(uint!(5459815003314423907810_U256)),
+    I256::from_raw(uint!(738905609893065022723_U256)),
+    I256::from_raw(uint!(271828182845904523536_U256)),
+    I256::from_raw(uint!(164872127070012814685_U256)),
+    I256::from_raw(uint!(128402541668774148407_U256)),
+    I256::from_raw(uint!(113314845306682631683_U256)),
+    I256::from_raw(uint!(106449445891785942956_U256)),
+];
+
+/// Swap math of a Balancer V2 pool
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BalancerPoolKind {
+    /// Weighted pool, normalized weights with 1e18 precision
+    Weighted { weights: Vec<U256> },
+    /// Stable pool, amplification parameter multiplied by the 1e3 amp precision
+    Stable { amp: U256 },
+}
+
+/// Balancer V2 pool parameters fetched from the pool contract
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BalancerPoolParams {
+    pub kind: BalancerPoolKind,
+    /// Per token multipliers upscaling balances to 18 decimals, 1e18 precision
+    pub scaling_factors: Vec<U256>,
+    /// Swap fee percentage, 1e18 precision
+    pub fee: U256,
+}
+
+fn int(value: u64) -> I256 {
+    I256::from_raw(U256::from(value))
+}
+
+fn checked_mul(a: U256, b: U256) -> Result<U256> {
+    a.checked_mul(b).ok_or_else(|| eyre!("MUL_OVERFLOW"))
+}
+
+fn checked_sub(a: U256, b: U256) -> Result<U256> {
+    a.checked_sub(b).ok_or_else(|| eyre!("SUB_OVERFLOW"))
+}
+
+fn div_up_raw(a: U256, b: U256) -> Result<U256> {
+    if b.is_zero() {
+        return Err(eyre!("ZERO_DIVISION"));
+    }
+    Ok(if a.is_zero() { U256::ZERO } else { (a - U256::from(1)) / b + U256::from(1) })
+}
+
+/// Port of the Balancer FixedPoint library, 1e18 precision
+pub struct BalancerFixedPoint;
+
+impl BalancerFixedPoint {
+    pub fn mul_down(a: U256, b: U256) -> Result<U256> {
+        Ok(checked_mul(a, b)? / ONE)
+    }
+
+    pub fn mul_up(a: U256, b: U256) -> Result<U256> {
+        let product = checked_mul(a, b)?;
+        Ok(if product.is_zero() { U256::ZERO } else { (product - U256::from(1)) / ONE + U256::from(1) })
+    }
+
+    pub fn div_down(a: U256, b: U256) -> Result<U256> {
+        if b.is_zero() {
+            return Err(eyre!("ZERO_DIVISION"));
+        }
+        Ok(checked_mul(a, ONE)? / b)
+    }
+
+    pub fn div_up(a: U256, b: U256) -> Result<U256> {
+        div_up_raw(checked_mul(a, ONE)?, b)
+    }
+
+    pub fn complement(x: U256) -> U256 {
+        if x < ONE {
+            ONE - x
+        } else {
+            U256::ZERO
+        }
+    }
+
+    /// x^y rounded up by the maximum relative error of `LogExpMath::pow`
+    pub fn pow_up(x: U256, y: U256) -> Result<U256> {
+        if y == ONE {
+            Ok(x)
+        } else if y == ONE * U256::from(2) {
+            Self::mul_up(x, x)
+        } else if y == ONE * U256::from(4) {
+            let square = Self::mul_up(x, x)?;
+            Self::mul_up(square, square)
+        } else {
+            let raw = Self::pow(x, y)?;
+            let max_error = Self::mul_up(raw, MAX_POW_RELATIVE_ERROR)? + U256::from(1);
+            Ok(raw + max_error)
+        }
+    }
+
+    /// LogExpMath x^y as exp(y * ln(x)), both arguments and result with 1e18 precision
+    pub fn pow(x: U256, y: U256) -> Result<U256> {
+        if y.is_zero() {
+            return Ok(ONE);
+        }
+        if x.is_zero() {
+            return Ok(U256::ZERO);
+        }
+        if x.bit(255) {
+            return Err(eyre!("X_OUT_OF_BOUNDS"));
+        }
+        if y >= MILD_EXPONENT_BOUND {
+            return Err(eyre!("Y_OUT_OF_BOUNDS"));
+        }
+        let x = I256::from_raw(x);
+        let y = I256::from_raw(y);
+
+        let logx_times_y = if LN_36_LOWER_BOUND < x && x < LN_36_UPPER_BOUND {
+            let ln_36_x = Self::ln_36(x);
+            // ln_36_x has 36 decimals, split it to avoid overflow when multiplied by y
+            (ln_36_x / ONE_18) * y + ((ln_36_x % ONE_18) * y) / ONE_18
+        } else {
+            Self::ln(x) * y
+        };
+        let logx_times_y = logx_times_y / ONE_18;
+
+        if logx_times_y > MAX_NATURAL_EXPONENT || -logx_times_y > MIN_NATURAL_EXPONENT_ABS {
+            return Err(eyre!("PRODUCT_OUT_OF_BOUNDS"));
+        }
+        Ok(Self::exp(logx_times_y)?.into_raw())
+    }
+
+    /// Natural exponentiation e^x, 1e18 precision
+    pub fn exp(x: I256) -> Result<I256> {
+        if x > MAX_NATURAL_EXPONENT || -x > MIN_NATURAL_EXPONENT_ABS {
+            return Err(eyre!("INVALID_EXPONENT"));
+        }
+        if x.is_negative() {
+            return Ok((ONE_18 * ONE_18) / Self::exp(-x)?);
+        }
+
+        let mut x = x;
+        let first_an = if x >= X[0] {
+            x -= X[0];
+            A[0]
+        } else if x >= X[1] {
+            x -= X[1];
+            A[1]
+        } else {
+            int(1)
+        };
+
+        // 20 decimals from here, x10 and x11 are not needed as the series converges fast enough
+        x *= int(100);
+        let mut product = ONE_20;
+        for (x_n, a_n) in X.iter().zip(A.iter()).take(10).skip(2) {
+            if x >= *x_n {
+                x -= *x_n;
+                product = (product * *a_n) / ONE_20;
+            }
+        }
+
+        let mut series_sum = ONE_20 + x;
+        let mut term = x;
+        for n in 2..=12 {
+            term = ((term * x) / ONE_20) / int(n);
+            series_sum += term;
+        }
+
+        Ok((((product * series_sum) / ONE_20) * first_an) / int(100))
+    }
+
+    /// Natural logarithm, 1e18 precision
+    fn ln(a: I256) -> I256 {
+        if a < ONE_18 {
+            return -Self::ln((ONE_18 * ONE_18) / a);
+        }
+
+        let mut a = a;
+        let mut sum = I256::ZERO;
+        if a >= A[0] * ONE_18 {
+            a /= A[0];
+            sum += X[0];
+        }
+        if a >= A[1] * ONE_18 {
+            a /= A[1];
+            sum += X[1];
+        }
+
+        sum *= int(100);
+        a *= int(100);
+        for (x_n, a_n) in X.iter().zip(A.iter()).skip(2) {
+            if a >= *a_n {
+                a = (a * ONE_20) / *a_n;
+                sum += *x_n;
+            }
+        }
+
+        // ln(a) = 2 * artanh(z), z = (a - 1) / (a + 1)
+        let z = ((a - ONE_20) * ONE_20) / (a + ONE_20);
+        let z_squared = (z * z) / ONE_20;
+        let mut num = z;
+        let mut series_sum = num;
+        for n in [3, 5, 7, 9, 11] {
+            num = (num * z_squared) / ONE_20;
+            series_sum += num / int(n);
+        }
+
+        (sum + series_sum * int(2)) / int(100)
+    }
+
+    /// Natural logarithm with 36 decimals for x close to one
+    fn ln_36(x: I256) -> I256 {
+        let x = x * ONE_18;
+        let z = ((x - ONE_36) * ONE_36) / (x + ONE_36);
+        let z_squared = (z * z) / ONE_36;
+        let mut num = z;
+        let mut series_sum = num;
+        for n in [3, 5, 7, 9, 11, 13, 15] {
+            num = (num * z_squared) / ONE_36;
+            series_sum += num / int(n);
+        }
+        series_sum * int(2)
+    }
+}
+
+/// Weighted and stable math of Balancer V2 pools, port of the WeightedMath and StableMath solidity libraries
+pub struct BalancerPoolVirtual;
+
+impl BalancerPoolVirtual {
+    pub fn weighted_out_given_in(balance_in: U256, weight_in: U256, balance_out: U256, weight_out: U256, amount_in: U256) -> Result<U256> {
+        if amount_in > BalancerFixedPoint::mul_down(balance_in, MAX_IN_RATIO)? {
+            return Err(eyre!("MAX_IN_RATIO"));
+        }
+        let base = BalancerFixedPoint::div_up(balance_in, balance_in + amount_in)?;
+        let exponent = BalancerFixedPoint::div_down(weight_in, weight_out)?;
+        let power = BalancerFixedPoint::pow_up(base, exponent)?;
+        BalancerFixedPoint::mul_down(balance_out, BalancerFixedPoint::complement(power))
+    }
+
+    pub fn weighted_in_given_out(balance_in: U256, weight_in: U256, balance_out: U256, weight_out: U256, amount_out: U256) -> Result<U256> {
+        if amount_out > BalancerFixedPoint::mul_down(balance_out, MAX_OUT_RATIO)? {
+            return Err(eyre!("MAX_OUT_RATIO"));
+        }
+        let base = BalancerFixedPoint::div_up(balance_out, balance_out - amount_out)?;
+        let exponent = BalancerFixedPoint::div_up(weight_out, weight_in)?;
+        let power = BalancerFixedPoint::pow_up(base, exponent)?;
+        BalancerFixedPoint::mul_up(balance_in, checked_sub(power, ONE)?)
+    }
+
+    pub fn stable_invariant(amp: U256, balances: &[U256]) -> Result<U256> {
+        let n = U256::from(balances.len());
+        let sum: U256 = balances.iter().sum();
+        if sum.is_zero() {
+            return Ok(U256::ZERO);
+        }
+
+        let amp_times_total = amp * n;
+        let mut invariant = sum;
+        for _ in 0..MAX_ITERATIONS {
+            let mut d_p = invariant;
+            for balance in balances.iter() {
+                if balance.is_zero() {
+                    return Err(eyre!("ZERO_BALANCE"));
+                }
+                d_p = checked_mul(d_p, invariant)? / (balance * n);
+            }
+            let prev_invariant = invariant;
+            invariant = checked_mul(checked_mul(amp_times_total, sum)? / AMP_PRECISION + d_p * n, invariant)?
+                / (checked_mul(checked_sub(amp_times_total, AMP_PRECISION)?, invariant)? / AMP_PRECISION + (n + U256::from(1)) * d_p);
+            if invariant.abs_diff(prev_invariant) <= U256::from(1) {
+                return Ok(invariant);
+            }
+        }
+        Err(eyre!("STABLE_INVARIANT_DIDNT_CONVERGE"))
+    }
+
+    /// Balance of token `index` keeping the invariant with all other balances unchanged
+    fn stable_balance_given_invariant(amp: U256, balances: &[U256], invariant: U256, index: usize) -> Result<U256> {
+        let n = U256::from(balances.len());
+        let amp_times_total = amp * n;
+
+        let mut sum = balances[0];
+        let mut p_d = balances[0] * n;
+        for balance in balances.iter().skip(1) {
+            p_d = checked_mul(checked_mul(p_d, *balance)?, n)? / invariant;
+            sum += *balance;
+        }
+        sum -= balances[index];
+
+        let inv2 = checked_mul(invariant, invariant)?;
+        let c = checked_mul(div_up_raw(inv2, checked_mul(amp_times_total, p_d)?)? * AMP_PRECISION, balances[index])?;
+        let b = sum + invariant / amp_times_total * AMP_PRECISION;
+
+        let mut token_balance = div_up_raw(inv2 + c, invariant + b)?;
+        for _ in 0..MAX_ITERATIONS {
+            let prev_token_balance = token_balance;
+            token_balance = div_up_raw(checked_mul(token_balance, token_balance)? + c, checked_sub(token_balance * U256::from(2) + b, invariant)?)?;
+            if token_balance.abs_diff(prev_token_balance) <= U256::from(1) {
+                return Ok(token_balance);
+            }
+        }
+        Err(eyre!("STABLE_GET_BALANCE_DIDNT_CONVERGE"))
+    }
+
+    pub fn stable_out_given_in(amp: U256, balances: &[U256], i: usize, j: usize, amount_in: U256) -> Result<U256> {
+        let invariant = Self::stable_invariant(amp, balances)?;
+        let mut balances = balances.to_vec();
+        balances[i] += amount_in;
+        let final_balance_out = Self::stable_balance_given_invariant(amp, &balances, invariant, j)?;
+        checked_sub(checked_sub(balances[j], final_balance_out)?, U256::from(1))
+    }
+
+    pub fn stable_in_given_out(amp: U256, balances: &[U256], i: usize, j: usize, amount_out: U256) -> Result<U256> {
+        let invariant = Self::stable_invariant(amp, balances)?;
+        let mut balances = balances.to_vec();
+        balances[j] = checked_sub(balances[j], amount_out)?;
+        let final_balance_in = Self::stable_balance_given_invariant(amp, &balances, invariant, i)?;
+        Ok(checked_sub(final_balance_in, balances[i])? + U256::from(1))
+    }
+
+    fn check_indexes(params: &BalancerPoolParams, balances: &[U256], i: usize, j: usize) -> Result<()> {
+        if i == j || i >= balances.len() || j >= balances.len() || balances.len() != params.scaling_factors.len() {
+            return Err(eyre!("BAD_TOKEN_INDEX"));
+        }
+        Ok(())
+    }
+
+    fn upscale(balances: &[U256], scaling_factors: &[U256]) -> Result<Vec<U256>> {
+        balances.iter().zip(scaling_factors.iter()).map(|(balance, factor)| BalancerFixedPoint::mul_down(*balance, *factor)).collect()
+    }
+
+    /// Vault swap given in, fee is taken from the amount in before upscaling
+    pub fn calc_out_given_in(params: &BalancerPoolParams, balances: &[U256], i: usize, j: usize, amount_in: U256) -> Result<U256> {
+        Self::check_indexes(params, balances, i, j)?;
+        let amount_in = checked_sub(amount_in, BalancerFixedPoint::mul_up(amount_in, params.fee)?)?;
+        let amount_in = BalancerFixedPoint::mul_down(amount_in, params.scaling_factors[i])?;
+        let balances = Self::upscale(balances, &params.scaling_factors)?;
+
+        let amount_out = match &params.kind {
+            BalancerPoolKind::Weighted { weights } => {
+                Self::weighted_out_given_in(balances[i], weights[i], balances[j], weights[j], amount_in)?
+            }
+            BalancerPoolKind::Stable { amp } => Self::stable_out_given_in(*amp, &balances, i, j, amount_in)?,
+        };
+        BalancerFixedPoint::div_down(amount_out, params.scaling_factors[j])
+    }
+
+    /// Vault swap given out, fee is added to the downscaled amount in
+    pub fn calc_in_given_out(params: &BalancerPoolParams, balances: &[U256], i: usize, j: usize, amount_out: U256) -> Result<U256> {
+        Self::check_indexes(params, balances, i, j)?;
+        let amount_out = BalancerFixedPoint::mul_down(amount_out, params.scaling_factors[j])?;
+        let balances = Self::upscale(balances, &params.scaling_factors)?;
+
+        let amount_in = match &params.kind {
+            BalancerPoolKind::Weighted { weights } => {
+                Self::weighted_in_given_out(balances[i], weights[i], balances[j], weights[j], amount_out)?
+            }
+            BalancerPoolKind::Stable { amp } => Self::stable_in_given_out(*amp, &balances, i, j, amount_out)?,
+        };
+        let amount_in = BalancerFixedPoint::div_up(amount_in, params.scaling_factors[i])?;
+        BalancerFixedPoint::div_up(amount_in, BalancerFixedPoint::complement(params.fee))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn e18(value: u128) -> U256 {
+        U256::from(value) * ONE
+    }
+
+    #[test]
+    fn test_log_exp_math() {
+        assert_eq!(BalancerFixedPoint::exp(ONE_18).unwrap(), I256::from_raw(U256::from(2718281828459045235u128)));
+        assert_eq!(BalancerFixedPoint::pow(e18(2), ONE / U256::from(2)).unwrap(), U256::from(1414213562373095047u128));
+        // ln_36 branch
+        assert_eq!(BalancerFixedPoint::pow(U256::from(1050000000000000000u128), e18(3)).unwrap(), U256::from(1157624999999999999u128));
+    }
+
+    #[test]
+    fn test_weighted_math() {
+        // 80/20 pool with 10M tokens and 5000 WETH
+        let (balance_in, weight_in) = (e18(10_000_000), U256::from(800000000000000000u128));
+        let (balance_out, weight_out) = (e18(5_000), U256::from(200000000000000000u128));
+
+        let out = BalancerPoolVirtual::weighted_out_given_in(balance_in, weight_in, balance_out, weight_out, e18(1000)).unwrap();
+        assert_eq!(out, U256::from(1999500099982500000u128));
+
+        let amount_in = BalancerPoolVirtual::weighted_in_given_out(balance_in, weight_in, balance_out, weight_out, e18(1)).unwrap();
+        assert_eq!(amount_in, U256::from(500062509476530000000u128));
+
+        assert!(BalancerPoolVirtual::weighted_out_given_in(balance_in, weight_in, balance_out, weight_out, e18(3_000_001)).is_err());
+    }
+
+    #[test]
+    fn test_stable_math() {
+        let amp = U256::from(2000 * 1000);
+        let balances = vec![e18(50_000_000), e18(48_000_000), e18(52_000_000)];
+
+        assert_eq!(BalancerPoolVirtual::stable_invariant(amp, &balances).unwrap(), U256::from(149999959955962226168694079u128));
+
+        let out = BalancerPoolVirtual::stable_out_given_in(amp, &balances, 0, 1, e18(1_000_000)).unwrap();
+        assert_eq!(out, U256::from(999968677487736976612105u128));
+
+        let amount_in = BalancerPoolVirtual::stable_in_given_out(amp, &balances, 1, 2, e18(1_000_000)).unwrap();
+        assert_eq!(amount_in, U256::from(999969955266208804502723u128));
+    }
+
+    #[test]
+    fn test_swap_with_fee_and_scaling() {
+        // 50/50 WETH/USDC with 0.3% fee
+        let params = BalancerPoolParams {
+            kind: BalancerPoolKind::Weighted { weights: vec![ONE / U256::from(2), ONE / U256::from(2)] },
+            scaling_factors: vec![ONE, ONE * U256::from(10).pow(U256::from(12))],
+            fee: U256::from(3000000000000000u128),
+        };
+        let balances = vec![e18(1000), U256::from(2_000_000_000_000u128)];
+
+        let out = BalancerPoolVirtual::calc_out_given_in(&params, &balances, 0, 1, e18(1)).unwrap();
+        assert_eq!(out, U256::from(1992013962u128));
+
+        let amount_in = BalancerPoolVirtual::calc_in_given_out(&params, &balances, 0, 1, out).unwrap();
+        assert_eq!(amount_in, U256::from(999999999959897694u128));
+    }
+}
@@ -0,0 +1,176 @@
+use alloy::primitives::U256;
+use eyre::{eyre, Result};
+
+const PRECISION: U256 = U256::from_limbs([1_000_000_000_000_000_000, 0, 0, 0]);
+const FEE_DENOMINATOR: U256 = U256::from_limbs([10_000_000_000, 0, 0, 0]);
+const MAX_ITERATIONS: usize = 255;
+
+/// StableSwap invariant parameters as reported by the Curve registry
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CurveStableSwapParams {
+    /// Amplification coefficient A
+    pub amp: U256,
+    /// Per coin multipliers normalizing balances to 18 decimals, 1e18 precision
+    pub rates: Vec<U256>,
+    /// Swap fee, 1e10 precision
+    pub fee: U256,
+}
+
+/// StableSwap math of the plain Curve pools, port of the 3pool vyper implementation
+pub struct CurvePoolVirtual;
+
+impl CurvePoolVirtual {
+    fn abs_diff_le_one(a: U256, b: U256) -> bool {
+        a.abs_diff(b) <= U256::from(1)
+    }
+
+    pub fn xp(balances: &[U256], rates: &[U256]) -> Result<Vec<U256>> {
+        if balances.len() != rates.len() {
+            return Err(eyre!("BALANCES_RATES_LEN_MISMATCH"));
+        }
+        Ok(balances.iter().zip(rates.iter()).map(|(balance, rate)| balance * rate / PRECISION).collect())
+    }
+
+    pub fn get_d(xp: &[U256], amp: U256) -> Result<U256> {
+        let n = U256::from(xp.len());
+        let s: U256 = xp.iter().sum();
+        if s.is_zero() {
+            return Ok(U256::ZERO);
+        }
+
+        let ann = amp * n;
+        let mut d = s;
+        for _ in 0..MAX_ITERATIONS {
+            let mut d_p = d;
+            for x in xp.iter() {
+                if x.is_zero() {
+                    return Err(eyre!("ZERO_BALANCE"));
+                }
+                d_p = d_p * d / (x * n);
+            }
+            let d_prev = d;
+            d = (ann * s + d_p * n) * d / ((ann - U256::from(1)) * d + (n + U256::from(1)) * d_p);
+            if Self::abs_diff_le_one(d, d_prev) {
+                return Ok(d);
+            }
+        }
+        Err(eyre!("D_NOT_CONVERGED"))
+    }
+
+    /// New balance of coin j when balance of coin i is set to x, all values normalized by rates
+    pub fn get_y(i: usize, j: usize, x: U256, xp: &[U256], amp: U256) -> Result<U256> {
+        if i == j || i >= xp.len() || j >= xp.len() {
+            return Err(eyre!("BAD_COIN_INDEX"));
+        }
+
+        let n = U256::from(xp.len());
+        let d = Self::get_d(xp, amp)?;
+        let ann = amp * n;
+
+        let mut c = d;
+        let mut s = U256::ZERO;
+        for (k, xp_k) in xp.iter().enumerate() {
+            let x_k = if k == i {
+                x
+            } else if k != j {
+                *xp_k
+            } else {
+                continue;
+            };
+            if x_k.is_zero() {
+                return Err(eyre!("ZERO_BALANCE"));
+            }
+            s += x_k;
+            c = c * d / (x_k * n);
+        }
+        c = c * d / (ann * n);
+        let b = s + d / ann;
+
+        let mut y = d;
+        for _ in 0..MAX_ITERATIONS {
+            let y_prev = y;
+            y = (y * y + c) / (U256::from(2) * y + b - d);
+            if Self::abs_diff_le_one(y, y_prev) {
+                return Ok(y);
+            }
+        }
+        Err(eyre!("Y_NOT_CONVERGED"))
+    }
+
+    /// Amount of coin j received for dx of coin i, equivalent of pool `get_dy`
+    pub fn get_dy(i: usize, j: usize, dx: U256, balances: &[U256], params: &CurveStableSwapParams) -> Result<U256> {
+        let xp = Self::xp(balances, &params.rates)?;
+        let rate_i = params.rates.get(i).ok_or_else(|| eyre!("BAD_COIN_INDEX"))?;
+        let rate_j = params.rates.get(j).ok_or_else(|| eyre!("BAD_COIN_INDEX"))?;
+
+        let x = xp[i] + dx * rate_i / PRECISION;
+        let y = Self::get_y(i, j, x, &xp, params.amp)?;
+
+        let dy = xp[j].checked_sub(y + U256::from(1)).ok_or_else(|| eyre!("INSUFFICIENT_LIQUIDITY"))? * PRECISION / rate_j;
+        let fee = params.fee * dy / FEE_DENOMINATOR;
+        Ok(dy - fee)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn three_pool_params() -> CurveStableSwapParams {
+        let e30 = U256::from(10).pow(U256::from(30));
+        CurveStableSwapParams { amp: U256::from(2000), rates: vec![PRECISION, e30, e30], fee: U256::from(1_000_000) }
+    }
+
+    fn three_pool_balances() -> Vec<U256> {
+        vec![
+            U256::from(60_000_000u128 * 10u128.pow(18)),
+            U256::from(55_000_000u128 * 10u128.pow(6)),
+            U256::from(70_000_000u128 * 10u128.pow(6)),
+        ]
+    }
+
+    #[test]
+    fn test_get_d() {
+        let params = three_pool_params();
+        let xp = CurvePoolVirtual::xp(&three_pool_balances(), &params.rates).unwrap();
+        assert_eq!(CurvePoolVirtual::get_d(&xp, params.amp).unwrap(), U256::from(184999532448834190835317672u128));
+    }
+
+    #[test]
+    fn test_get_dy() {
+        let params = three_pool_params();
+        let balances = three_pool_balances();
+
+        // 1M DAI -> USDC
+        let dy = CurvePoolVirtual::get_dy(0, 1, U256::from(1_000_000u128 * 10u128.pow(18)), &balances, &params).unwrap();
+        assert_eq!(dy, U256::from(999843024568u128));
+
+        // 1M USDC -> USDT
+        let dy = CurvePoolVirtual::get_dy(1, 2, U256::from(1_000_000u128 * 10u128.pow(6)), &balances, &params).unwrap();
+        assert_eq!(dy, U256::from(1000013346147u128));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let params = three_pool_params();
+        let mut balances = three_pool_balances();
+
+        let dx = U256::from(100_000u128 * 10u128.pow(18));
+        let dy = CurvePoolVirtual::get_dy(0, 1, dx, &balances, &params).unwrap();
+
+        balances[0] += dx;
+        balances[1] -= dy;
+
+        let dx_back = CurvePoolVirtual::get_dy(1, 0, dy, &balances, &params).unwrap();
+        assert!(dx_back < dx);
+        // Two fees of 0.01% and rounding
+        assert!(dx_back > dx * U256::from(9997) / U256::from(10000));
+    }
+
+    #[test]
+    fn test_bad_index() {
+        let params = three_pool_params();
+        assert!(CurvePoolVirtual::get_dy(0, 0, U256::from(1), &three_pool_balances(), &params).is_err());
+        assert!(CurvePoolVirtual::get_dy(0, 3, U256::from(1), &three_pool_balances(), &params).is_err());
+    }
+}
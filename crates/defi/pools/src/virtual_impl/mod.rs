@@ -1,4 +1,11 @@
+pub use balancer::{BalancerFixedPoint, BalancerPoolKind, BalancerPoolParams, BalancerPoolVirtual};
+pub use curve::{CurvePoolVirtual, CurveStableSwapParams};
+pub use tick_provider::PreloadedTicks;
 pub use uniswapv3::UniswapV3PoolVirtual;
+pub use uniswapv4::UniswapV4PoolVirtual;
 
+mod balancer;
+mod curve;
 pub mod tick_provider;
 mod uniswapv3;
+mod uniswapv4;
@@ -1,7 +1,14 @@
-use crate::db_reader::UniswapV3DBReader;
-use alloy::primitives::{Address, U256};
+use crate::db_reader::{UniswapV3DBReader, UniswapV4DBReader};
+use alloy::primitives::{Address, B256, U256};
+use eyre::eyre;
 use loom_defi_uniswap_v3_math::tick_provider::TickProvider;
 use revm::DatabaseRef;
+use std::collections::BTreeMap;
+
+/// Tick bitmap with net liquidity of initialized ticks, everything a swap simulation needs to cross ticks
+pub trait TickLiquidityProvider: TickProvider {
+    fn get_liquidity_net(&self, tick: i32) -> eyre::Result<i128>;
+}
 
 pub struct TickProviderEVMDB<DB> {
     pub db: DB,
@@ -25,3 +32,110 @@ where
         UniswapV3DBReader::tick_bitmap(&self.db, self.pool_address, tick)
     }
 }
+
+impl<DB> TickLiquidityProvider for TickProviderEVMDB<DB>
+where
+    DB: DatabaseRef,
+{
+    fn get_liquidity_net(&self, tick: i32) -> eyre::Result<i128> {
+        UniswapV3DBReader::ticks_liquidity_net(&self.db, self.pool_address, tick)
+    }
+}
+
+/// Initialized ticks of a Uniswap V3 pool fetched at load time for bitmap words `min_word..=max_word`.
+/// Words outside of the range are unknown and fail the lookup instead of reading as empty.
+#[derive(Clone, Debug, Default)]
+pub struct PreloadedTicks {
+    min_word: i16,
+    max_word: i16,
+    tick_bitmap: BTreeMap<i16, U256>,
+    liquidity_net: BTreeMap<i32, i128>,
+}
+
+impl PreloadedTicks {
+    /// Rebuilds the tick bitmap from `(tick, liquidity_net)` of initialized ticks
+    pub fn new(tick_spacing: u32, min_word: i16, max_word: i16, ticks: impl IntoIterator<Item = (i32, i128)>) -> Self {
+        let mut tick_bitmap: BTreeMap<i16, U256> = BTreeMap::new();
+        let mut liquidity_net: BTreeMap<i32, i128> = BTreeMap::new();
+        for (tick, tick_liquidity_net) in ticks {
+            let compressed = tick / tick_spacing as i32;
+            let word = tick_bitmap.entry((compressed >> 8) as i16).or_default();
+            *word |= U256::from(1) << (compressed & 0xff) as usize;
+            liquidity_net.insert(tick, tick_liquidity_net);
+        }
+        Self { min_word, max_word, tick_bitmap, liquidity_net }
+    }
+
+    pub fn word_range(&self) -> (i16, i16) {
+        (self.min_word, self.max_word)
+    }
+
+    pub fn len(&self) -> usize {
+        self.liquidity_net.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.liquidity_net.is_empty()
+    }
+}
+
+impl TickProvider for PreloadedTicks {
+    fn get_tick(&self, tick: i16) -> eyre::Result<U256> {
+        if tick < self.min_word || tick > self.max_word {
+            return Err(eyre!("TICK_BITMAP_WORD_NOT_PRELOADED"));
+        }
+        Ok(self.tick_bitmap.get(&tick).copied().unwrap_or_default())
+    }
+}
+
+impl TickLiquidityProvider for PreloadedTicks {
+    fn get_liquidity_net(&self, tick: i32) -> eyre::Result<i128> {
+        Ok(self.liquidity_net.get(&tick).copied().unwrap_or_default())
+    }
+}
+
+pub struct TickProviderV4EVMDB<DB> {
+    pub db: DB,
+    pub pool_id: B256,
+}
+
+impl<DB> TickProviderV4EVMDB<DB>
+where
+    DB: DatabaseRef,
+{
+    pub fn new(db: DB, pool_id: B256) -> Self {
+        TickProviderV4EVMDB { db, pool_id }
+    }
+}
+
+impl<DB> TickProvider for TickProviderV4EVMDB<DB>
+where
+    DB: DatabaseRef,
+{
+    fn get_tick(&self, tick: i16) -> eyre::Result<U256> {
+        UniswapV4DBReader::tick_bitmap(&self.db, self.pool_id, tick)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use loom_defi_uniswap_v3_math::tick_bitmap::next_initialized_tick_within_one_word;
+
+    #[test]
+    fn test_preloaded_ticks_bitmap() {
+        let ticks = PreloadedTicks::new(60, -1, 1, [(-120, 10), (-60, 20), (0, -20), (60, -10), (15360, 5)]);
+
+        assert_eq!(ticks.len(), 5);
+        assert_eq!(ticks.get_tick(-1).unwrap(), (U256::from(1) << 254) | (U256::from(1) << 255));
+        assert_eq!(ticks.get_tick(0).unwrap(), U256::from(0b11));
+        assert_eq!(ticks.get_tick(1).unwrap(), U256::from(1));
+        assert!(ticks.get_tick(2).is_err());
+        assert_eq!(ticks.get_liquidity_net(-60).unwrap(), 20);
+        assert_eq!(ticks.get_liquidity_net(120).unwrap(), 0);
+
+        assert_eq!(next_initialized_tick_within_one_word(&ticks, 30, 60, true).unwrap(), (0, true));
+        assert_eq!(next_initialized_tick_within_one_word(&ticks, -1, 60, true).unwrap(), (-60, true));
+        assert_eq!(next_initialized_tick_within_one_word(&ticks, 0, 60, false).unwrap(), (60, true));
+    }
+}
@@ -4,7 +4,7 @@ use loom_defi_uniswap_v3_math::tick_math::{MAX_SQRT_RATIO, MAX_TICK, MIN_SQRT_RA
 use revm::DatabaseRef;
 
 use crate::db_reader::UniswapV3DBReader;
-use crate::virtual_impl::tick_provider::TickProviderEVMDB;
+use crate::virtual_impl::tick_provider::{PreloadedTicks, TickLiquidityProvider, TickProviderEVMDB};
 use crate::UniswapV3Pool;
 use loom_types_entities::Pool;
 
@@ -50,11 +50,11 @@ pub const U256_1: U256 = U256::from_limbs([1, 0, 0, 0]);
 // Others
 
 pub struct CurrentState {
-    amount_specified_remaining: I256,
-    amount_calculated: I256,
-    sqrt_price_x_96: U256,
-    tick: i32,
-    liquidity: u128,
+    pub amount_specified_remaining: I256,
+    pub amount_calculated: I256,
+    pub sqrt_price_x_96: U256,
+    pub tick: i32,
+    pub liquidity: u128,
 }
 
 #[derive(Default)]
@@ -86,6 +86,46 @@ impl UniswapV3PoolVirtual {
         pool: &UniswapV3Pool,
         token_in: Address,
         amount_in: U256,
+    ) -> eyre::Result<U256> {
+        Self::simulate_swap_in_amount(db, pool, &TickProviderEVMDB::new(db, pool.get_address()), token_in, amount_in)
+    }
+
+    /// Swap simulation crossing ticks preloaded with the pool, slot0 and liquidity are still read from `db`
+    pub fn simulate_swap_in_amount_preloaded<DB: DatabaseRef>(
+        db: &DB,
+        pool: &UniswapV3Pool,
+        preloaded_ticks: &PreloadedTicks,
+        token_in: Address,
+        amount_in: U256,
+    ) -> eyre::Result<U256> {
+        Self::simulate_swap_in_amount(db, pool, preloaded_ticks, token_in, amount_in)
+    }
+
+    pub fn simulate_swap_out_amount_provided<DB: DatabaseRef>(
+        db: &DB,
+        pool: &UniswapV3Pool,
+        token_in: Address,
+        amount_out: U256,
+    ) -> eyre::Result<U256> {
+        Self::simulate_swap_out_amount(db, pool, &TickProviderEVMDB::new(db, pool.get_address()), token_in, amount_out)
+    }
+
+    pub fn simulate_swap_out_amount_preloaded<DB: DatabaseRef>(
+        db: &DB,
+        pool: &UniswapV3Pool,
+        preloaded_ticks: &PreloadedTicks,
+        token_in: Address,
+        amount_out: U256,
+    ) -> eyre::Result<U256> {
+        Self::simulate_swap_out_amount(db, pool, preloaded_ticks, token_in, amount_out)
+    }
+
+    fn simulate_swap_in_amount<DB: DatabaseRef, TP: TickLiquidityProvider>(
+        db: &DB,
+        pool: &UniswapV3Pool,
+        tick_provider: &TP,
+        token_in: Address,
+        amount_in: U256,
     ) -> eyre::Result<U256> {
         if amount_in.is_zero() {
             return Ok(U256::ZERO);
@@ -112,8 +152,6 @@ impl UniswapV3PoolVirtual {
             liquidity,                                             //Current available liquidity in the tick range
         };
 
-        let tick_provider = TickProviderEVMDB::new(db, pool_address);
-
         while current_state.amount_specified_remaining != I256::ZERO && current_state.sqrt_price_x_96 != sqrt_price_limit_x_96 {
             // Initialize a new step struct to hold the dynamic state of the pool at each step
             let mut step = StepComputations {
@@ -122,9 +160,13 @@ impl UniswapV3PoolVirtual {
                 ..Default::default()
             };
 
+            // The bitmap word searched for the next tick must be known to the provider, a missing word is read as empty
+            let compressed = current_state.tick.div_euclid(tick_spacing as i32) + if zero_for_one { 0 } else { 1 };
+            tick_provider.get_tick((compressed >> 8) as i16)?;
+
             // Get the next tick from the current tick
             (step.tick_next, step.initialized) = loom_defi_uniswap_v3_math::tick_bitmap::next_initialized_tick_within_one_word(
-                &tick_provider,
+                tick_provider,
                 current_state.tick,
                 tick_spacing as i32,
                 zero_for_one,
@@ -171,8 +213,7 @@ impl UniswapV3PoolVirtual {
             // If the price moved all the way to the next price, recompute the liquidity change for the next iteration
             if current_state.sqrt_price_x_96 == step.sqrt_price_next_x96 {
                 if step.initialized {
-                    let mut liquidity_net: i128 =
-                        UniswapV3DBReader::ticks_liquidity_net(&db, pool_address, step.tick_next).unwrap_or_default();
+                    let mut liquidity_net: i128 = tick_provider.get_liquidity_net(step.tick_next).unwrap_or_default();
 
                     // we are on a tick boundary, and the next tick is initialized, so we must charge a protocol fee
                     if zero_for_one {
@@ -207,9 +248,10 @@ impl UniswapV3PoolVirtual {
         }
     }
 
-    pub fn simulate_swap_out_amount_provided<DB: DatabaseRef>(
+    fn simulate_swap_out_amount<DB: DatabaseRef, TP: TickLiquidityProvider>(
         db: &DB,
         pool: &UniswapV3Pool,
+        tick_provider: &TP,
         token_in: Address,
         amount_out: U256,
     ) -> eyre::Result<U256> {
@@ -246,11 +288,13 @@ impl UniswapV3PoolVirtual {
                 ..Default::default()
             };
 
-            let tick_provider = TickProviderEVMDB::new(&db, pool_address);
+            // The bitmap word searched for the next tick must be known to the provider, a missing word is read as empty
+            let compressed = current_state.tick.div_euclid(tick_spacing as i32) + if zero_for_one { 0 } else { 1 };
+            tick_provider.get_tick((compressed >> 8) as i16)?;
 
             // Get the next tick from the current tick
             (step.tick_next, step.initialized) = loom_defi_uniswap_v3_math::tick_bitmap::next_initialized_tick_within_one_word(
-                &tick_provider,
+                tick_provider,
                 current_state.tick,
                 tick_spacing as i32,
                 zero_for_one,
@@ -296,8 +340,7 @@ impl UniswapV3PoolVirtual {
             // If the price moved all the way to the next price, recompute the liquidity change for the next iteration
             if current_state.sqrt_price_x_96 == step.sqrt_price_next_x96 {
                 if step.initialized {
-                    let mut liquidity_net: i128 =
-                        UniswapV3DBReader::ticks_liquidity_net(db, pool_address, step.tick_next).unwrap_or_default();
+                    let mut liquidity_net: i128 = tick_provider.get_liquidity_net(step.tick_next).unwrap_or_default();
 
                     // we are on a tick boundary, and the next tick is initialized, so we must charge a protocol fee
                     if zero_for_one {
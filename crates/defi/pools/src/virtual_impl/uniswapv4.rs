@@ -0,0 +1,117 @@
+use alloy::primitives::{Address, I256, U256};
+use eyre::eyre;
+use loom_defi_uniswap_v3_math::tick_math::{MAX_SQRT_RATIO, MAX_TICK, MIN_SQRT_RATIO, MIN_TICK};
+use revm::DatabaseRef;
+
+use crate::db_reader::UniswapV4DBReader;
+use crate::virtual_impl::tick_provider::TickProviderV4EVMDB;
+use crate::virtual_impl::uniswapv3::{CurrentState, StepComputations, U256_1};
+use crate::UniswapV4Pool;
+use loom_types_entities::Pool;
+
+/// Swap math of hookless V4 pools, V4 concentrated liquidity is the V3 math over the PoolManager storage
+pub struct UniswapV4PoolVirtual;
+
+impl UniswapV4PoolVirtual {
+    pub fn simulate_swap_in_amount_provider<DB: DatabaseRef>(
+        db: &DB,
+        pool: &UniswapV4Pool,
+        token_in: Address,
+        amount_in: U256,
+    ) -> eyre::Result<U256> {
+        if amount_in.is_zero() {
+            return Ok(U256::ZERO);
+        }
+
+        let zero_for_one = token_in == pool.get_tokens()[0];
+
+        let sqrt_price_limit_x_96 = if zero_for_one { MIN_SQRT_RATIO + U256_1 } else { MAX_SQRT_RATIO - U256_1 };
+
+        let pool_id = pool.pool_id;
+
+        let slot0 = UniswapV4DBReader::slot0(&db, pool_id)?;
+        if slot0.protocol_fee != 0 {
+            // Combined protocol and lp fee is not supported by the V3 swap step
+            return Err(eyre!("PROTOCOL_FEE_NOT_SUPPORTED"));
+        }
+        let liquidity = UniswapV4DBReader::liquidity(&db, pool_id)?;
+
+        let mut current_state = CurrentState {
+            sqrt_price_x_96: slot0.sqrt_price_x96,
+            amount_calculated: I256::ZERO,
+            amount_specified_remaining: I256::from_raw(amount_in),
+            tick: slot0.tick,
+            liquidity,
+        };
+
+        let tick_provider = TickProviderV4EVMDB::new(db, pool_id);
+
+        while current_state.amount_specified_remaining != I256::ZERO && current_state.sqrt_price_x_96 != sqrt_price_limit_x_96 {
+            let mut step = StepComputations { sqrt_price_start_x_96: current_state.sqrt_price_x_96, ..Default::default() };
+
+            (step.tick_next, step.initialized) = loom_defi_uniswap_v3_math::tick_bitmap::next_initialized_tick_within_one_word(
+                &tick_provider,
+                current_state.tick,
+                pool.tick_spacing,
+                zero_for_one,
+            )?;
+
+            step.tick_next = step.tick_next.clamp(MIN_TICK, MAX_TICK);
+
+            step.sqrt_price_next_x96 = loom_defi_uniswap_v3_math::tick_math::get_sqrt_ratio_at_tick(step.tick_next)?;
+
+            let swap_target_sqrt_ratio = if zero_for_one {
+                step.sqrt_price_next_x96.max(sqrt_price_limit_x_96)
+            } else {
+                step.sqrt_price_next_x96.min(sqrt_price_limit_x_96)
+            };
+
+            (current_state.sqrt_price_x_96, step.amount_in, step.amount_out, step.fee_amount) =
+                loom_defi_uniswap_v3_math::swap_math::compute_swap_step(
+                    current_state.sqrt_price_x_96,
+                    swap_target_sqrt_ratio,
+                    current_state.liquidity,
+                    current_state.amount_specified_remaining,
+                    slot0.lp_fee,
+                )?;
+
+            current_state.amount_specified_remaining = current_state
+                .amount_specified_remaining
+                .overflowing_sub(I256::from_raw(step.amount_in.overflowing_add(step.fee_amount).0))
+                .0;
+
+            current_state.amount_calculated -= I256::from_raw(step.amount_out);
+
+            if current_state.sqrt_price_x_96 == step.sqrt_price_next_x96 {
+                if step.initialized {
+                    let mut liquidity_net: i128 = UniswapV4DBReader::ticks_liquidity_net(&db, pool_id, step.tick_next).unwrap_or_default();
+
+                    if zero_for_one {
+                        liquidity_net = -liquidity_net;
+                    }
+
+                    current_state.liquidity = if liquidity_net < 0 {
+                        if current_state.liquidity < (-liquidity_net as u128) {
+                            return Err(eyre!("LIQUIDITY_UNDERFLOW"));
+                        } else {
+                            current_state.liquidity - (-liquidity_net as u128)
+                        }
+                    } else {
+                        current_state.liquidity + (liquidity_net as u128)
+                    };
+                }
+                current_state.tick = if zero_for_one { step.tick_next.wrapping_sub(1) } else { step.tick_next }
+            } else if current_state.sqrt_price_x_96 != step.sqrt_price_start_x_96 {
+                current_state.tick = loom_defi_uniswap_v3_math::tick_math::get_tick_at_sqrt_ratio(current_state.sqrt_price_x_96)?;
+            }
+        }
+
+        if current_state.amount_specified_remaining.is_zero() {
+            let amount_out = (-current_state.amount_calculated).into_raw();
+            tracing::trace!("AmountOut : {amount_out}");
+            Ok(amount_out)
+        } else {
+            Err(eyre!("NOT_ENOUGH_LIQUIDITY"))
+        }
+    }
+}
@@ -1,3 +1,7 @@
 pub use preloader_actor::{preload_market_state, MarketStatePreloadedOneShotActor};
+pub use snapshot_actor::{market_state_snapshot, market_state_snapshot_worker, MarketStateSnapshotActor, DEFAULT_SNAPSHOT_INTERVAL_BLOCKS};
+pub use snapshot_restore_actor::{restore_market_state_snapshot, MarketStateSnapshotRestoreOneShotActor};
 
 mod preloader_actor;
+mod snapshot_actor;
+mod snapshot_restore_actor;
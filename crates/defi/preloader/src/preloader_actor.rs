@@ -12,6 +12,7 @@ use loom_core_actors::{Accessor, Actor, ActorResult, SharedState, WorkerResult};
 use loom_core_actors_macros::Accessor;
 use loom_core_blockchain::{Blockchain, BlockchainState};
 use loom_defi_address_book::TokenAddressEth;
+use loom_evm_db::DatabaseLoomExt;
 use loom_evm_utils::{BalanceCheater, NWETH};
 use loom_types_blockchain::GethStateUpdate;
 use loom_types_entities::{AccountNonceAndBalanceState, MarketState, TxSigners};
@@ -30,6 +31,20 @@ where
     Ok(AccountState { balance, code, nonce, storage: BTreeMap::new() })
 }
 
+/// Account already in the state db, e.g. restored by `MarketStateSnapshotRestoreOneShotActor`, is not fetched again
+fn cached_account_state<DB: DatabaseRef + DatabaseLoomExt>(db: &DB, address: Address) -> Option<AccountState> {
+    if !db.is_account(&address) {
+        return None;
+    }
+    let info = db.basic_ref(address).ok()??;
+    Some(AccountState {
+        balance: Some(info.balance),
+        code: info.code.map(|code| code.original_bytes()).filter(|code| !code.is_empty()),
+        nonce: Some(info.nonce),
+        storage: BTreeMap::new(),
+    })
+}
+
 async fn set_monitor_token_balance(
     account_nonce_balance_state: Option<SharedState<AccountNonceAndBalanceState>>,
     owner: Address,
@@ -67,7 +82,7 @@ pub async fn preload_market_state<P, N, DB>(
 where
     N: Network,
     P: Provider<N> + Send + Sync + Clone + 'static,
-    DB: DatabaseRef + Database + DatabaseCommit + Send + Sync + Clone + 'static,
+    DB: DatabaseRef + Database + DatabaseCommit + DatabaseLoomExt + Send + Sync + Clone + 'static,
 {
     let mut market_state_guard = market_state.write().await;
 
@@ -75,7 +90,10 @@ where
 
     for address in copied_accounts_vec {
         trace!("Loading address : {address}");
-        let acc_state = fetch_account_state(client.clone(), address).await?;
+        let acc_state = match cached_account_state(&market_state_guard.state_db, address) {
+            Some(acc_state) => acc_state,
+            None => fetch_account_state(client.clone(), address).await?,
+        };
 
         set_monitor_token_balance(
             account_nonce_balance_state.clone(),
@@ -110,7 +128,10 @@ where
         } else {
             match state.entry(token) {
                 Entry::Vacant(e) => {
-                    let mut acc_state = fetch_account_state(client.clone(), token).await?;
+                    let mut acc_state = match cached_account_state(&market_state_guard.state_db, token) {
+                        Some(acc_state) => acc_state,
+                        None => fetch_account_state(client.clone(), token).await?,
+                    };
                     acc_state.storage.insert(BalanceCheater::get_balance_cell(token, owner)?.into(), balance.into());
                     e.insert(acc_state);
                 }
@@ -217,7 +238,7 @@ impl<P, N, DB> Actor for MarketStatePreloadedOneShotActor<P, N, DB>
 where
     N: Network,
     P: Provider<N> + Send + Sync + Clone + 'static,
-    DB: DatabaseRef + Database + DatabaseCommit + Send + Sync + Clone + 'static,
+    DB: DatabaseRef + Database + DatabaseCommit + DatabaseLoomExt + Send + Sync + Clone + 'static,
 {
     fn start_and_wait(&self) -> Result<()> {
         let rt = tokio::runtime::Runtime::new()?; // we need a different runtime to wait for the result
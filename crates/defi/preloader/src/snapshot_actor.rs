@@ -0,0 +1,168 @@
+use alloy_primitives::{BlockHash, BlockNumber};
+use chrono::Utc;
+use eyre::{eyre, Result};
+use loom_core_actors::{subscribe, Accessor, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, SharedState, WorkerResult};
+use loom_core_actors_macros::{Accessor, Consumer};
+use loom_core_blockchain::{Blockchain, BlockchainState};
+use loom_evm_db::DatabaseLoomExt;
+use loom_storage_db::{insert_market_state_snapshot, DbPool, MarketStateSnapshot};
+use loom_types_blockchain::GethStateUpdate;
+use loom_types_entities::MarketState;
+use loom_types_events::MarketEvents;
+use revm::{Database, DatabaseCommit, DatabaseRef};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, error, info};
+
+pub const DEFAULT_SNAPSHOT_INTERVAL_BLOCKS: u64 = 100;
+
+/// Snapshot row of the market state accounts at `block_number`
+pub fn market_state_snapshot(
+    chain_id: u64,
+    block_number: BlockNumber,
+    block_hash: BlockHash,
+    state: &GethStateUpdate,
+) -> Result<MarketStateSnapshot> {
+    Ok(MarketStateSnapshot {
+        chain_id: chain_id as i64,
+        block_number: block_number as i64,
+        block_hash: block_hash.to_string(),
+        created_at: Utc::now(),
+        state: serde_json::to_string(state)?,
+    })
+}
+
+pub async fn market_state_snapshot_worker<DB>(
+    db_pool: DbPool,
+    chain_id: u64,
+    interval_blocks: u64,
+    market_state: SharedState<MarketState<DB>>,
+    market_events_rx: Broadcaster<MarketEvents>,
+    cancellation_token: CancellationToken,
+) -> WorkerResult
+where
+    DB: DatabaseRef + Database + DatabaseCommit + DatabaseLoomExt + Send + Sync + Clone + 'static,
+{
+    subscribe!(market_events_rx);
+
+    loop {
+        let market_event_msg: Result<MarketEvents, RecvError> = tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Market state snapshot cancelled".to_string());
+            }
+            msg = market_events_rx.recv() => msg,
+        };
+        match market_event_msg {
+            Ok(MarketEvents::BlockHeaderUpdate { block_number, .. }) => {
+                if block_number % interval_blocks != 0 {
+                    continue;
+                }
+
+                // Only the accounts are copied under the read lock, serialized and written without holding it
+                let (snapshot_block, block_hash, state) = {
+                    let market_state_guard = market_state.read().await;
+                    (market_state_guard.block_number, market_state_guard.block_hash, market_state_guard.state_db.export_accounts())
+                };
+                debug!(block_number, snapshot_block, accounts = state.len(), "Market state snapshot created");
+
+                let db_pool_clone = db_pool.clone();
+                tokio::task::spawn(async move {
+                    let snapshot = match tokio::task::spawn_blocking(move || {
+                        market_state_snapshot(chain_id, snapshot_block, block_hash, &state)
+                    })
+                    .await
+                    {
+                        Ok(Ok(snapshot)) => snapshot,
+                        Ok(Err(e)) => {
+                            error!("Failed to serialize market state snapshot : {}", e);
+                            return;
+                        }
+                        Err(e) => {
+                            error!("Market state snapshot task failed : {}", e);
+                            return;
+                        }
+                    };
+                    match insert_market_state_snapshot(&db_pool_clone, &snapshot).await {
+                        Ok(_) => info!(snapshot_block, "Market state snapshot saved"),
+                        Err(e) => error!("Failed to save market state snapshot : {}", e),
+                    }
+                });
+            }
+            Ok(_) => {}
+            Err(RecvError::Closed) => {
+                error!("Market events channel closed");
+                break Err(eyre!("MARKET_EVENTS_RX_CLOSED"));
+            }
+            Err(RecvError::Lagged(lag)) => {
+                error!("Market events channel lagged by {} messages", lag);
+            }
+        }
+    }
+}
+
+/// Writes the market state to the `market_state_snapshot` table every `interval_blocks` blocks, so a restarted bot can
+/// restore it with `MarketStateSnapshotRestoreOneShotActor` instead of fetching everything again
+#[derive(Accessor, Consumer)]
+pub struct MarketStateSnapshotActor<DB: Clone + Send + Sync + 'static> {
+    db_pool: DbPool,
+    chain_id: u64,
+    interval_blocks: u64,
+    #[accessor]
+    market_state: Option<SharedState<MarketState<DB>>>,
+    #[consumer]
+    market_events_rx: Option<Broadcaster<MarketEvents>>,
+    cancellation_token: CancellationToken,
+}
+
+impl<DB> MarketStateSnapshotActor<DB>
+where
+    DB: DatabaseRef + Database + DatabaseCommit + DatabaseLoomExt + Send + Sync + Clone + 'static,
+{
+    pub fn new(db_pool: DbPool) -> Self {
+        Self {
+            db_pool,
+            chain_id: 1,
+            interval_blocks: DEFAULT_SNAPSHOT_INTERVAL_BLOCKS,
+            market_state: None,
+            market_events_rx: None,
+            cancellation_token: CancellationToken::new(),
+        }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
+    pub fn with_interval_blocks(self, interval_blocks: u64) -> Self {
+        Self { interval_blocks: interval_blocks.max(1), ..self }
+    }
+
+    pub fn on_bc(self, bc: &Blockchain, state: &BlockchainState<DB>) -> Self {
+        Self {
+            chain_id: bc.chain_id(),
+            market_state: Some(state.market_state()),
+            market_events_rx: Some(bc.market_events_channel()),
+            ..self
+        }
+    }
+}
+
+impl<DB> Actor for MarketStateSnapshotActor<DB>
+where
+    DB: DatabaseRef + Database + DatabaseCommit + DatabaseLoomExt + Send + Sync + Clone + 'static,
+{
+    fn start(&self) -> ActorResult {
+        let task = tokio::task::spawn(market_state_snapshot_worker(
+            self.db_pool.clone(),
+            self.chain_id,
+            self.interval_blocks,
+            self.market_state.clone().unwrap(),
+            self.market_events_rx.clone().unwrap(),
+            self.cancellation_token.clone(),
+        ));
+        Ok(vec![task])
+    }
+
+    fn name(&self) -> &'static str {
+        "MarketStateSnapshotActor"
+    }
+}
@@ -0,0 +1,262 @@
+use std::marker::PhantomData;
+
+use alloy_eips::BlockId;
+use alloy_network::primitives::{BlockTransactionsKind, HeaderResponse};
+use alloy_network::{BlockResponse, Network};
+use alloy_primitives::{Address, BlockHash, BlockNumber, B256};
+use alloy_provider::Provider;
+use eyre::{eyre, OptionExt, Result};
+use loom_core_actors::{Accessor, Actor, ActorResult, SharedState};
+use loom_core_actors_macros::Accessor;
+use loom_core_blockchain::{Blockchain, BlockchainState};
+use loom_node_debug_provider::DebugProviderExt;
+use loom_storage_db::{latest_market_state_snapshot, DbPool};
+use loom_types_blockchain::{debug_trace_block, GethStateUpdate};
+use loom_types_entities::MarketState;
+use revm::{Database, DatabaseCommit, DatabaseRef};
+use tracing::{debug, info, warn};
+
+/// Applies a transaction state diff to the accounts and slots of the snapshot. Other accounts and slots are not tracked by
+/// the market state and are skipped, as `BlockHistoryState::apply_update` does for new blocks. Diff mode traces leave cleared
+/// slots out of `post`, slots in `pre` but not in `post` are zeroed.
+fn apply_known_state_diff(state: &mut GethStateUpdate, pre: &GethStateUpdate, post: &GethStateUpdate) {
+    for (address, account_pre) in pre.iter() {
+        let Some(account) = state.get_mut(address) else {
+            continue;
+        };
+        let post_storage = post.get(address).map(|account_post| &account_post.storage);
+        for slot in account_pre.storage.keys() {
+            if post_storage.is_some_and(|post_storage| post_storage.contains_key(slot)) {
+                continue;
+            }
+            if let Some(known_value) = account.storage.get_mut(slot) {
+                *known_value = B256::ZERO;
+            }
+        }
+    }
+
+    for (address, account_diff) in post.iter() {
+        let Some(account) = state.get_mut(address) else {
+            continue;
+        };
+        if account_diff.balance.is_some() {
+            account.balance = account_diff.balance;
+        }
+        if account_diff.nonce.is_some() {
+            account.nonce = account_diff.nonce;
+        }
+        if account_diff.code.is_some() {
+            account.code.clone_from(&account_diff.code);
+        }
+        for (slot, value) in account_diff.storage.iter() {
+            if let Some(known_value) = account.storage.get_mut(slot) {
+                *known_value = *value;
+            }
+        }
+    }
+}
+
+/// Restores the latest market state snapshot of the chain if it is at most `max_age_blocks` behind the node. The blocks
+/// after the snapshot are replayed from their state diffs, so the restored state is at the current block. Returns false
+/// if there is no fresh snapshot or it was reorged out.
+pub async fn restore_market_state_snapshot<P, N, DB>(
+    client: P,
+    db_pool: DbPool,
+    chain_id: u64,
+    max_age_blocks: u64,
+    market_state: SharedState<MarketState<DB>>,
+) -> Result<bool>
+where
+    N: Network,
+    P: Provider<N> + DebugProviderExt<N> + Send + Sync + Clone + 'static,
+    DB: DatabaseRef + Database + DatabaseCommit + Send + Sync + Clone + 'static,
+{
+    let Some(snapshot) = latest_market_state_snapshot(&db_pool, chain_id as i64).await? else {
+        info!(chain_id, "No market state snapshot found");
+        return Ok(false);
+    };
+    let snapshot_block = snapshot.block_number as BlockNumber;
+    let current_block = client.get_block_number().await?;
+    if current_block.saturating_sub(snapshot_block) > max_age_blocks {
+        info!(snapshot_block, current_block, max_age_blocks, "Market state snapshot is too old");
+        return Ok(false);
+    }
+
+    let snapshot_hash: BlockHash = snapshot.block_hash.parse()?;
+    let block = client.get_block_by_number(snapshot_block.into(), BlockTransactionsKind::Hashes).await?.ok_or_eyre("BLOCK_NOT_FOUND")?;
+    if block.header().hash() != snapshot_hash {
+        info!(snapshot_block, %snapshot_hash, "Market state snapshot block is not canonical");
+        return Ok(false);
+    }
+
+    let mut state: GethStateUpdate = serde_json::from_str(&snapshot.state)?;
+    for block_number in snapshot_block + 1..=current_block {
+        let (pre, post) = debug_trace_block(client.clone(), BlockId::Number(block_number.into()), true).await?;
+        for (pre, post) in pre.iter().zip(post.iter()) {
+            apply_known_state_diff(&mut state, pre, post);
+        }
+        debug!(block_number, "Market state snapshot block replayed");
+    }
+    let block_hash = if current_block == snapshot_block {
+        snapshot_hash
+    } else {
+        client
+            .get_block_by_number(current_block.into(), BlockTransactionsKind::Hashes)
+            .await?
+            .ok_or_eyre("BLOCK_NOT_FOUND")?
+            .header()
+            .hash()
+    };
+
+    let accounts = state.len();
+    let restored_accounts: Vec<Address> = state.keys().copied().collect();
+    let mut market_state_guard = market_state.write().await;
+    market_state_guard.apply_geth_update(state);
+    for address in restored_accounts {
+        market_state_guard.config.add_restored(address);
+    }
+    market_state_guard.block_number = current_block;
+    market_state_guard.block_hash = block_hash;
+    info!(snapshot_block, current_block, accounts, "Market state restored from snapshot");
+
+    Ok(true)
+}
+
+/// Restores the market state from the latest snapshot written by `MarketStateSnapshotActor`. Must run before
+/// `MarketStatePreloadedOneShotActor`, which then fetches only the accounts missing in the restored state. Failures are not
+/// fatal, the state is preloaded from the node instead.
+#[derive(Clone, Accessor)]
+pub struct MarketStateSnapshotRestoreOneShotActor<P, N, DB> {
+    client: P,
+    db_pool: DbPool,
+    chain_id: u64,
+    max_age_blocks: u64,
+    #[accessor]
+    market_state: Option<SharedState<MarketState<DB>>>,
+    _n: PhantomData<N>,
+}
+
+impl<P, N, DB> MarketStateSnapshotRestoreOneShotActor<P, N, DB>
+where
+    N: Network,
+    P: Provider<N> + DebugProviderExt<N> + Send + Sync + Clone + 'static,
+    DB: DatabaseRef + Database + DatabaseCommit + Send + Sync + Clone + 'static,
+{
+    pub fn new(client: P, db_pool: DbPool, max_age_blocks: u64) -> Self {
+        Self { client, db_pool, chain_id: 1, max_age_blocks, market_state: None, _n: PhantomData }
+    }
+
+    pub fn on_bc(self, bc: &Blockchain, state: &BlockchainState<DB>) -> Self {
+        Self { chain_id: bc.chain_id(), market_state: Some(state.market_state_commit()), ..self }
+    }
+}
+
+impl<P, N, DB> Actor for MarketStateSnapshotRestoreOneShotActor<P, N, DB>
+where
+    N: Network,
+    P: Provider<N> + DebugProviderExt<N> + Send + Sync + Clone + 'static,
+    DB: DatabaseRef + Database + DatabaseCommit + Send + Sync + Clone + 'static,
+{
+    fn start_and_wait(&self) -> Result<()> {
+        let rt = tokio::runtime::Runtime::new()?; // we need a different runtime to wait for the result
+        let restore_future = restore_market_state_snapshot(
+            self.client.clone(),
+            self.db_pool.clone(),
+            self.chain_id,
+            self.max_age_blocks,
+            self.market_state.clone().unwrap(),
+        );
+        let handler = rt.spawn(async move {
+            match restore_future.await {
+                Ok(true) => Ok("RESTORED".to_string()),
+                Ok(false) => Ok("NO_FRESH_SNAPSHOT".to_string()),
+                Err(e) => {
+                    warn!("Failed to restore market state snapshot, preloading from node : {}", e);
+                    Ok("RESTORE_FAILED".to_string())
+                }
+            }
+        });
+
+        self.wait(Ok(vec![handler]))?;
+        rt.shutdown_background();
+        Ok(())
+    }
+
+    fn start(&self) -> ActorResult {
+        Err(eyre!("NEED_TO_BE_WAITED"))
+    }
+
+    fn name(&self) -> &'static str {
+        "MarketStateSnapshotRestoreOneShotActor"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::market_state_snapshot;
+    use alloy_primitives::U256;
+    use alloy_rpc_types_trace::geth::AccountState;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_apply_known_state_diff() {
+        let known = Address::repeat_byte(1);
+        let unknown = Address::repeat_byte(2);
+        let (known_slot, unknown_slot): (B256, B256) = (U256::from(1).into(), U256::from(2).into());
+        let mut state: GethStateUpdate = BTreeMap::from([(
+            known,
+            AccountState {
+                balance: Some(U256::from(10)),
+                nonce: Some(1),
+                code: None,
+                storage: BTreeMap::from([(known_slot, U256::from(100).into())]),
+            },
+        )]);
+
+        let diff: GethStateUpdate = BTreeMap::from([
+            (
+                known,
+                AccountState {
+                    balance: None,
+                    nonce: Some(2),
+                    code: None,
+                    storage: BTreeMap::from([(known_slot, U256::from(200).into()), (unknown_slot, U256::from(300).into())]),
+                },
+            ),
+            (unknown, AccountState { balance: Some(U256::from(5)), ..Default::default() }),
+        ]);
+        apply_known_state_diff(&mut state, &GethStateUpdate::new(), &diff);
+
+        assert_eq!(state.len(), 1);
+        let account = &state[&known];
+        assert_eq!((account.balance, account.nonce), (Some(U256::from(10)), Some(2)));
+        assert_eq!(account.storage, BTreeMap::from([(known_slot, U256::from(200).into())]));
+
+        // The cleared slot is in pre but not in post
+        let pre: GethStateUpdate = BTreeMap::from([(
+            known,
+            AccountState { storage: BTreeMap::from([(known_slot, U256::from(200).into())]), ..Default::default() },
+        )]);
+        let post: GethStateUpdate = BTreeMap::from([(known, AccountState { nonce: Some(3), ..Default::default() })]);
+        apply_known_state_diff(&mut state, &pre, &post);
+
+        let account = &state[&known];
+        assert_eq!(account.nonce, Some(3));
+        assert_eq!(account.storage, BTreeMap::from([(known_slot, B256::ZERO)]));
+    }
+
+    #[test]
+    fn test_market_state_snapshot_roundtrip() -> Result<()> {
+        let account = Address::repeat_byte(1);
+        let state: GethStateUpdate =
+            BTreeMap::from([(account, AccountState { balance: Some(U256::from(1000)), nonce: Some(7), ..Default::default() })]);
+
+        let snapshot = market_state_snapshot(8453, 100, BlockHash::repeat_byte(2), &state)?;
+        assert_eq!((snapshot.chain_id, snapshot.block_number), (8453, 100));
+        assert_eq!(snapshot.block_hash.parse::<BlockHash>()?, BlockHash::repeat_byte(2));
+        assert_eq!(serde_json::from_str::<GethStateUpdate>(&snapshot.state)?, state);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use alloy_network::Network;
+use alloy_primitives::{address, Address, U256};
+use alloy_provider::Provider;
+use eyre::{eyre, Result};
+use loom_defi_abi::IAggregatorV3;
+
+/// Chainlink USD price feeds on Ethereum mainnet, all of them answer with 8 decimals
+pub struct ChainlinkFeedsEth;
+
+impl ChainlinkFeedsEth {
+    pub const ETH_USD: Address = address!("5f4eC3Df9cbd43714FE2740f5E3616155c5b8419");
+    pub const BTC_USD: Address = address!("F4030086522a5bEEa4988F8cA5B36dbC97BeE88c");
+    pub const USDC_USD: Address = address!("8fFfFfd4AfB6115b954Bd326cbe7B4BA576818f6");
+
+    /// Heartbeats of the feeds, the longest time between two answers
+    pub const ETH_USD_HEARTBEAT_SECS: u64 = 3600;
+    pub const BTC_USD_HEARTBEAT_SECS: u64 = 3600;
+    pub const USDC_USD_HEARTBEAT_SECS: u64 = 86400;
+}
+
+/// Latest answer of the `AggregatorV3Interface` feed, rejected when older than the heartbeat at the timestamp
+pub async fn fetch_chainlink_answer<N: Network, P: Provider<N> + Send + Sync + Clone + 'static>(
+    client: P,
+    feed: Address,
+    timestamp: u64,
+    heartbeat_secs: u64,
+) -> Result<U256> {
+    let aggregator = IAggregatorV3::IAggregatorV3Instance::new(feed, client);
+    let round_data = aggregator.latestRoundData().call().await?;
+    if round_data.answer.is_negative() || round_data.answer.is_zero() {
+        return Err(eyre!("BAD_ORACLE_ANSWER"));
+    }
+    if is_stale_answer(round_data.updatedAt.saturating_to(), timestamp, heartbeat_secs) {
+        return Err(eyre!("STALE_ORACLE_ANSWER"));
+    }
+    Ok(round_data.answer.into_raw())
+}
+
+/// An answer is stale when it was never updated or updated more than the heartbeat before the timestamp
+pub fn is_stale_answer(updated_at: u64, timestamp: u64, heartbeat_secs: u64) -> bool {
+    updated_at == 0 || timestamp.saturating_sub(updated_at) > heartbeat_secs
+}
+
+/// Price of ETH in token units, as set by `Token::set_eth_price`, from USD answers of feeds with the same decimals
+pub fn eth_price_from_usd_answers(eth_usd: U256, token_usd: U256, token_decimals: u8) -> Option<U256> {
+    if token_usd.is_zero() {
+        return None;
+    }
+    eth_usd.checked_mul(U256::from(10).pow(U256::from(token_decimals))).map(|value| value / token_usd)
+}
+
+/// Deviation of the pool price from the oracle price in basis points of the oracle price
+pub fn price_deviation_bps(pool_price: U256, oracle_price: U256) -> u64 {
+    if oracle_price.is_zero() {
+        return u64::MAX;
+    }
+    let delta = if pool_price > oracle_price { pool_price - oracle_price } else { oracle_price - pool_price };
+    delta.saturating_mul(U256::from(10000)).checked_div(oracle_price).map(|bps| bps.saturating_to()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_eth_price_from_usd_answers() {
+        // ETH at $3000 and USDC at $1 with 8 decimals answers, USDC has 6 decimals
+        let eth_usd = U256::from(300_000_000_000u64);
+        let usdc_usd = U256::from(100_000_000u64);
+        assert_eq!(eth_price_from_usd_answers(eth_usd, usdc_usd, 6), Some(U256::from(3_000_000_000u64)));
+
+        // BTC at $60000, WBTC has 8 decimals
+        let btc_usd = U256::from(6_000_000_000_000u64);
+        assert_eq!(eth_price_from_usd_answers(eth_usd, btc_usd, 8), Some(U256::from(5_000_000u64)));
+
+        assert_eq!(eth_price_from_usd_answers(eth_usd, U256::ZERO, 6), None);
+    }
+
+    #[test]
+    fn test_price_deviation_bps() {
+        assert_eq!(price_deviation_bps(U256::from(1050), U256::from(1000)), 500);
+        assert_eq!(price_deviation_bps(U256::from(900), U256::from(1000)), 1000);
+        assert_eq!(price_deviation_bps(U256::from(1000), U256::from(1000)), 0);
+        assert_eq!(price_deviation_bps(U256::from(1000), U256::ZERO), u64::MAX);
+    }
+
+    #[test]
+    fn test_is_stale_answer() {
+        assert!(!is_stale_answer(1_700_000_000, 1_700_003_600, 3600));
+        assert!(is_stale_answer(1_700_000_000, 1_700_003_601, 3600));
+        // Answers updated after the timestamp of a lagging block are fresh
+        assert!(!is_stale_answer(1_700_000_012, 1_700_000_000, 3600));
+        assert!(is_stale_answer(0, 1_700_000_000, 3600));
+    }
+}
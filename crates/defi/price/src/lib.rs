@@ -1,5 +1,7 @@
+mod chainlink;
 mod price_actor;
 mod price_feed;
 
+pub use chainlink::{eth_price_from_usd_answers, fetch_chainlink_answer, price_deviation_bps, ChainlinkFeedsEth};
 pub use price_actor::PriceActor;
 pub use price_feed::PriceFeed;
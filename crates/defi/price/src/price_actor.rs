@@ -1,21 +1,166 @@
 use std::collections::HashMap;
 use std::marker::PhantomData;
+use std::sync::Arc;
 use std::ops::{Div, Mul};
-use std::time::Duration;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use alloy_network::Network;
 use alloy_primitives::{address, Address, U256};
 use alloy_provider::Provider;
-use loom_core_actors::{Accessor, Actor, ActorResult, SharedState, WorkerResult};
-use loom_core_actors_macros::Accessor;
+use eyre::{eyre, Result};
+use futures::stream::{self, StreamExt};
+use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, Producer, SharedState, WorkerResult};
+use loom_core_actors_macros::{Accessor, Consumer, Producer};
 use loom_core_blockchain::Blockchain;
+use loom_defi_abi::IERC20;
 use loom_defi_address_book::TokenAddressEth;
 use loom_defi_pools::protocols::CurveProtocol;
 use loom_defi_pools::CurvePool;
-use loom_types_entities::{Market, Pool};
-use tracing::{debug, error, info};
+use loom_types_entities::{Market, Pool, PoolId, Token};
+use loom_types_events::{HealthEvent, MessageBlockHeader, MessageHealthEvent};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, error, info, warn};
+
+use crate::chainlink::{eth_price_from_usd_answers, fetch_chainlink_answer, price_deviation_bps, ChainlinkFeedsEth};
+
+/// Pool derived prices deviating more from the Chainlink price are replaced by it
+const MAX_ORACLE_DEVIATION_BPS: u64 = 500;
+
+/// Tokens checked against the Chainlink USD feed of the same asset, with the feed heartbeat
+const ORACLE_FEEDS: [(Address, Address, u64); 2] = [
+    (TokenAddressEth::USDC, ChainlinkFeedsEth::USDC_USD, ChainlinkFeedsEth::USDC_USD_HEARTBEAT_SECS),
+    (TokenAddressEth::WBTC, ChainlinkFeedsEth::BTC_USD, ChainlinkFeedsEth::BTC_USD_HEARTBEAT_SECS),
+];
+
+/// Curve pool prices are refreshed every this many blocks
+const CURVE_PRICE_UPDATE_INTERVAL: u64 = 5;
+
+/// Pool TVL is refreshed every this many blocks
+const POOL_TVL_UPDATE_INTERVAL: u64 = 50;
+
+/// Concurrent token balance requests of the pool TVL update
+const POOL_TVL_CONCURRENCY: usize = 16;
+
+/// Sets the USD TVL of the enabled market pools from their token balances. Tokens without an ETH price count as zero.
+async fn update_pool_tvl<N: Network, P: Provider<N> + Send + Sync + Clone + 'static>(
+    client: P,
+    market: &SharedState<Market>,
+) -> Result<()> {
+    let (pools, weth, usdc) = {
+        let market_guard = market.read().await;
+        let pools: Vec<(PoolId, Address, Vec<Arc<Token>>)> = market_guard
+            .pools()
+            .values()
+            .filter(|pool| !market_guard.is_pool_disabled(&pool.get_pool_id()) && !market_guard.is_pool_manager(&pool.get_address()))
+            .map(|pool| {
+                let tokens = pool.get_tokens().iter().map(|token| market_guard.get_token_or_default(token)).collect();
+                (pool.get_pool_id(), pool.get_address(), tokens)
+            })
+            .collect();
+        (pools, market_guard.get_token_or_default(&TokenAddressEth::WETH), market_guard.get_token_or_default(&TokenAddressEth::USDC))
+    };
+    let Some(eth_usd) = usdc.calc_token_value_from_eth(U256::from(10).pow(U256::from(18))).map(|value| usdc.to_float(value)) else {
+        return Err(eyre!("USDC_PRICE_NOT_SET"));
+    };
+
+    let pool_tvls: Vec<(PoolId, f64)> = stream::iter(pools)
+        .map(|(pool_id, pool_address, tokens)| {
+            let client = client.clone();
+            let weth = weth.clone();
+            async move {
+                let mut tvl_eth = 0.0;
+                for token in tokens {
+                    let balance = IERC20::IERC20Instance::new(token.get_address(), client.clone()).balanceOf(pool_address).call().await?._0;
+                    tvl_eth += token.calc_eth_value(balance).map_or(0.0, |value| weth.to_float(value));
+                }
+                Ok::<_, eyre::Report>((pool_id, tvl_eth * eth_usd))
+            }
+        })
+        .buffer_unordered(POOL_TVL_CONCURRENCY)
+        .filter_map(|result| async move { result.map_err(|error| debug!(%error, "Pool TVL not fetched")).ok() })
+        .collect()
+        .await;
+
+    let pools_len = pool_tvls.len();
+    let mut market_guard = market.write().await;
+    for (pool_id, tvl_usd) in pool_tvls {
+        market_guard.set_pool_tvl(pool_id, tvl_usd);
+    }
+    debug!(pools_len, eth_usd, "Pool TVL updated");
+    Ok(())
+}
+
+/// Chainlink USD answers of ETH and of the checked tokens, stale answers are skipped
+async fn fetch_oracle_answers<N: Network, P: Provider<N> + Send + Sync + Clone + 'static>(
+    client: P,
+    timestamp: u64,
+) -> Result<(U256, Vec<(Address, U256)>)> {
+    let eth_usd =
+        fetch_chainlink_answer(client.clone(), ChainlinkFeedsEth::ETH_USD, timestamp, ChainlinkFeedsEth::ETH_USD_HEARTBEAT_SECS).await?;
+
+    let mut token_answers = Vec::new();
+    for (token_address, feed, heartbeat_secs) in ORACLE_FEEDS {
+        match fetch_chainlink_answer(client.clone(), feed, timestamp, heartbeat_secs).await {
+            Ok(token_usd) => token_answers.push((token_address, token_usd)),
+            Err(error) => error!(%error, %feed, "Chainlink feed failed"),
+        }
+    }
+    Ok((eth_usd, token_answers))
+}
+
+/// Compares pool derived ETH prices with Chainlink prices. A missing or deviating price is replaced by the oracle price,
+/// deviations are reported as suspected manipulation.
+async fn check_oracle_prices(
+    market: &SharedState<Market>,
+    eth_usd: U256,
+    token_answers: &[(Address, U256)],
+    health_monitor_tx: Option<&Broadcaster<MessageHealthEvent>>,
+) {
+    for &(token_address, token_usd) in token_answers {
+        let Some(token) = market.read().await.get_token(&token_address) else {
+            continue;
+        };
+        let Some(oracle_price) = eth_price_from_usd_answers(eth_usd, token_usd, token.get_decimals()) else {
+            continue;
+        };
+
+        match token.get_eth_price() {
+            Some(pool_price) => {
+                let deviation_bps = price_deviation_bps(pool_price, oracle_price);
+                if deviation_bps <= MAX_ORACLE_DEVIATION_BPS {
+                    continue;
+                }
+                warn!(token = %token_address, %pool_price, %oracle_price, deviation_bps, "Pool price deviates from oracle price");
+                token.set_eth_price(Some(oracle_price));
+                if let Some(health_monitor_tx) = health_monitor_tx {
+                    let health_event = HealthEvent::PriceManipulationSuspected { token: token_address, pool_price, oracle_price };
+                    if let Err(e) = health_monitor_tx.send(MessageHealthEvent::new(health_event)) {
+                        error!("Failed to send health event : {}", e);
+                    }
+                }
+            }
+            None => {
+                info!("Price of ETH in {token_address:#20x} is {oracle_price} from oracle");
+                token.set_eth_price(Some(oracle_price));
+            }
+        }
+    }
+}
+
+async fn price_worker<N: Network, P: Provider<N> + Send + Sync + Clone + 'static>(
+    client: P,
+    market: SharedState<Market>,
+    block_header_rx: Option<Broadcaster<MessageBlockHeader>>,
+    health_monitor_tx: Option<Broadcaster<MessageHealthEvent>>,
+    once: bool,
+    cancellation_token: CancellationToken,
+) -> WorkerResult {
+    let mut block_header_rx = match (once, block_header_rx) {
+        (true, _) => None,
+        (false, Some(block_header_rx)) => Some(block_header_rx.subscribe()),
+        (false, None) => return Err(eyre!("BLOCK_HEADER_CHANNEL_NOT_SET")),
+    };
 
-async fn price_worker<N: Network, P: Provider<N> + Clone + 'static>(client: P, market: SharedState<Market>, once: bool) -> WorkerResult {
     let curve_tricrypto_usdc = CurveProtocol::new_u256_3_eth_to(client.clone(), address!("7F86Bf177Dd4F3494b841a37e810A34dD56c829B"));
     let curve_tricrypto_usdt = CurveProtocol::new_u256_3_eth_to(client.clone(), address!("f5f5b97624542d72a9e06f04804bf81baa15e2b4"));
 
@@ -41,30 +186,39 @@ async fn price_worker<N: Network, P: Provider<N> + Clone + 'static>(client: P, m
         }
     }
 
-    loop {
-        for (token_address, curve_pool) in coins_hash_map.iter() {
-            debug!("Fetching price of {} at {}", token_address, curve_pool.get_address());
-
-            match curve_pool.fetch_out_amount(TokenAddressEth::WETH, *token_address, weth_amount).await {
-                Ok(out_amount) => {
-                    let price = out_amount.mul(one_ether).div(weth_amount);
-                    info!("Price of ETH in {token_address:#20x} is {price}");
-                    match market.read().await.get_token(token_address) {
-                        Some(tkn) => {
-                            tkn.set_eth_price(Some(price));
-                            debug!("Price is set");
-                        }
-                        _ => {
-                            error!(address=%token_address, "Token not found");
+    let mut timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or_default();
+
+    for update_idx in 0u64.. {
+        if update_idx % CURVE_PRICE_UPDATE_INTERVAL == 0 {
+            for (token_address, curve_pool) in coins_hash_map.iter() {
+                debug!("Fetching price of {} at {}", token_address, curve_pool.get_address());
+
+                match curve_pool.fetch_out_amount(TokenAddressEth::WETH, *token_address, weth_amount).await {
+                    Ok(out_amount) => {
+                        let price = out_amount.mul(one_ether).div(weth_amount);
+                        info!("Price of ETH in {token_address:#20x} is {price}");
+                        match market.read().await.get_token(token_address) {
+                            Some(tkn) => {
+                                tkn.set_eth_price(Some(price));
+                                debug!("Price is set");
+                            }
+                            _ => {
+                                error!(address=%token_address, "Token not found");
+                            }
                         }
                     }
-                }
-                Err(error) => {
-                    error!(%error, "fetch_out_amount")
+                    Err(error) => {
+                        error!(%error, "fetch_out_amount")
+                    }
                 }
             }
         }
 
+        match fetch_oracle_answers(client.clone(), timestamp).await {
+            Ok((eth_usd, token_answers)) => check_oracle_prices(&market, eth_usd, &token_answers, health_monitor_tx.as_ref()).await,
+            Err(error) => error!(%error, "Oracle price check failed"),
+        }
+
         let usdt_price = market.read().await.get_token_or_default(&TokenAddressEth::USDT).get_eth_price();
         let usdc_price = market.read().await.get_token_or_default(&TokenAddressEth::USDC).get_eth_price();
 
@@ -85,21 +239,44 @@ async fn price_worker<N: Network, P: Provider<N> + Clone + 'static>(client: P, m
                 }
             }
         }
-        if once {
-            break;
+
+        if update_idx % POOL_TVL_UPDATE_INTERVAL == 0 {
+            if let Err(error) = update_pool_tvl(client.clone(), &market).await {
+                error!(%error, "Pool TVL update failed");
+            }
         }
 
-        let _ = tokio::time::sleep(Duration::new(60, 0)).await;
+        let Some(block_header_rx) = block_header_rx.as_mut() else {
+            break;
+        };
+        timestamp = loop {
+            let msg = tokio::select! {
+                _ = cancellation_token.cancelled() => {
+                    return Ok("PriceWorker cancelled".to_string());
+                }
+                msg = block_header_rx.recv() => msg,
+            };
+            match msg {
+                Ok(block_header) => break block_header.inner.header.timestamp,
+                Err(RecvError::Lagged(lag)) => debug!(lag, "Block header channel lagged"),
+                Err(RecvError::Closed) => return Err(eyre!("BLOCK_HEADER_CHANNEL_CLOSED")),
+            }
+        };
     }
     Ok("PriceWorker finished".to_string())
 }
 
-#[derive(Accessor)]
+#[derive(Accessor, Consumer, Producer)]
 pub struct PriceActor<P, N> {
     client: P,
     only_once: bool,
     #[accessor]
     market: Option<SharedState<Market>>,
+    #[consumer]
+    block_header_rx: Option<Broadcaster<MessageBlockHeader>>,
+    #[producer]
+    health_monitor_tx: Option<Broadcaster<MessageHealthEvent>>,
+    cancellation_token: CancellationToken,
     _n: PhantomData<N>,
 }
 
@@ -109,15 +286,32 @@ where
     P: Provider<N> + Send + Sync + Clone + 'static,
 {
     pub fn new(client: P) -> Self {
-        Self { client, only_once: false, market: None, _n: PhantomData }
+        Self {
+            client,
+            only_once: false,
+            market: None,
+            block_header_rx: None,
+            health_monitor_tx: None,
+            cancellation_token: CancellationToken::new(),
+            _n: PhantomData,
+        }
     }
 
     pub fn only_once(self) -> Self {
         Self { only_once: true, ..self }
     }
 
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
     pub fn on_bc(self, bc: &Blockchain) -> Self {
-        Self { market: Some(bc.market()), ..self }
+        Self {
+            market: Some(bc.market()),
+            block_header_rx: Some(bc.new_block_headers_channel()),
+            health_monitor_tx: Some(bc.health_monitor_channel()),
+            ..self
+        }
     }
 }
 
@@ -127,7 +321,14 @@ where
     P: Provider<N> + Send + Sync + Clone + 'static,
 {
     fn start(&self) -> ActorResult {
-        let task = tokio::task::spawn(price_worker(self.client.clone(), self.market.clone().unwrap(), self.only_once));
+        let task = tokio::task::spawn(price_worker(
+            self.client.clone(),
+            self.market.clone().unwrap(),
+            self.block_header_rx.clone(),
+            self.health_monitor_tx.clone(),
+            self.only_once,
+            self.cancellation_token.clone(),
+        ));
         Ok(vec![task])
     }
 
@@ -135,3 +336,42 @@ where
         "PriceActor"
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_check_oracle_prices() {
+        let mut market = Market::default();
+        market.add_token(Token::new_with_data(TokenAddressEth::USDC, Some("USDC".to_string()), None, Some(6), true, false));
+        market.add_token(Token::new_with_data(TokenAddressEth::WBTC, Some("WBTC".to_string()), None, Some(8), true, false));
+        let market = SharedState::new(market);
+
+        // Pool price 10% below the oracle price of ETH at $3000 and USDC at $1, WBTC has no pool price
+        let usdc = market.read().await.get_token(&TokenAddressEth::USDC).unwrap();
+        usdc.set_eth_price(Some(U256::from(2_700_000_000u64)));
+        let eth_usd = U256::from(300_000_000_000u64);
+        let token_answers =
+            [(TokenAddressEth::USDC, U256::from(100_000_000u64)), (TokenAddressEth::WBTC, U256::from(6_000_000_000_000u64))];
+
+        let health_monitor_tx: Broadcaster<MessageHealthEvent> = Broadcaster::new(10);
+        let mut health_monitor_rx = health_monitor_tx.subscribe();
+        check_oracle_prices(&market, eth_usd, &token_answers, Some(&health_monitor_tx)).await;
+
+        assert_eq!(usdc.get_eth_price(), Some(U256::from(3_000_000_000u64)));
+        let wbtc = market.read().await.get_token(&TokenAddressEth::WBTC).unwrap();
+        assert_eq!(wbtc.get_eth_price(), Some(U256::from(5_000_000u64)));
+
+        match health_monitor_rx.try_recv().unwrap().inner {
+            HealthEvent::PriceManipulationSuspected { token, pool_price, oracle_price } => {
+                assert_eq!(token, TokenAddressEth::USDC);
+                assert_eq!(pool_price, U256::from(2_700_000_000u64));
+                assert_eq!(oracle_price, U256::from(3_000_000_000u64));
+            }
+            _ => panic!("PriceManipulationSuspected expected"),
+        }
+        // A missing pool price is set without a health event
+        assert!(health_monitor_rx.try_recv().is_err());
+    }
+}
@@ -1,9 +1,12 @@
 use crate::fast_cache_db::FastDbAccount;
 use alloy::primitives::map::HashMap;
 use alloy::primitives::{Address, U256};
+use alloy::rpc::types::trace::geth::AccountState;
 use eyre::ErrReport;
 use revm::primitives::AccountInfo;
 use revm::DatabaseRef;
+use std::collections::BTreeMap;
+use std::sync::Arc;
 
 pub trait DatabaseLoomExt {
     fn with_ext_db(&mut self, db: impl DatabaseRef<Error = ErrReport> + Send + Sync + 'static);
@@ -25,5 +28,19 @@ pub trait DatabaseLoomExt {
 
     fn replace_account_storage(&mut self, address: Address, storage: HashMap<U256, U256>) -> eyre::Result<()>;
 
+    /// Apply geth state update vec changing only touched accounts and slots
+    fn apply_state_diff_in_place(&mut self, update: &[BTreeMap<Address, AccountState>]) -> eyre::Result<()>;
+
+    /// Export all cached accounts and slots as a geth state update
+    fn export_accounts(&self) -> BTreeMap<Address, AccountState>;
+
+    /// Accounts and slots with a different value in `self` as a geth state update on top of `other`
+    fn diff(&self, other: &Self) -> Vec<BTreeMap<Address, AccountState>>;
+
     fn maintain(self) -> Self;
+
+    /// Empty read-write layer on top of `base`. Reads fall through to `base`, writes stay in the new layer.
+    fn new_layer(base: Arc<Self>) -> Self
+    where
+        Self: Sized;
 }
@@ -16,7 +16,7 @@ use revm::db::{AccountState as DBAccountState, EmptyDBTyped};
 use revm::primitives::{Account, AccountInfo, Bytecode};
 use revm::{Database, DatabaseCommit, DatabaseRef};
 use std::collections::hash_map::Entry;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{Debug, Formatter};
 use std::sync::Arc;
 use tracing::{error, trace};
@@ -454,6 +454,152 @@ impl LoomDB {
         }
         self
     }
+
+    /// Apply geth state diff to the read-write layer, only touched accounts are copied from the read-only layer
+    pub fn apply_state_diff_in_place(&mut self, update_vec: &[BTreeMap<Address, GethAccountState>]) -> Result<()> {
+        for update_record in update_vec {
+            for (address, acc_state) in update_record {
+                trace!(%address, code = acc_state.code.is_some(), storage = acc_state.storage.len(), "apply_state_diff_in_place");
+                if address.is_zero() {
+                    continue;
+                }
+
+                let code = acc_state.code.as_ref().map(|code| {
+                    let bytecode = Bytecode::new_raw(code.clone());
+                    (bytecode.hash_slow(), bytecode)
+                });
+                if let Some((code_hash, bytecode)) = &code {
+                    if !bytecode.is_empty() {
+                        self.contracts.entry(*code_hash).or_insert_with(|| bytecode.clone());
+                    }
+                }
+
+                let account = self.load_ro_rw_ext_account(*address)?;
+                if let Some((code_hash, bytecode)) = code {
+                    account.info.code_hash = code_hash;
+                    account.info.code = Some(bytecode);
+                }
+                if let Some(nonce) = acc_state.nonce {
+                    account.info.nonce = nonce;
+                }
+                if let Some(balance) = acc_state.balance {
+                    account.info.balance = balance;
+                }
+                if !account.account_state.is_storage_cleared() {
+                    account.account_state = DBAccountState::Touched;
+                }
+                account.storage.extend(acc_state.storage.iter().map(|(slot, value)| ((*slot).into(), (*value).into())));
+            }
+        }
+        Ok(())
+    }
+
+    /// Accounts and slots of the read-only and read-write layers as a geth state update, read-write values override read-only ones
+    pub fn export_accounts(&self) -> BTreeMap<Address, GethAccountState> {
+        let mut state = self.read_only_db.as_ref().map(|db| db.export_accounts()).unwrap_or_default();
+
+        for (address, account) in self.accounts.iter() {
+            // Cached lookups of empty addresses
+            if matches!(account.account_state, DBAccountState::NotExisting) && account.storage.is_empty() {
+                continue;
+            }
+            let code =
+                account.info.code.clone().or_else(|| self.code_by_hash_ref(account.info.code_hash).ok()).filter(|code| !code.is_empty());
+
+            let entry = state.entry(*address).or_default();
+            entry.balance = Some(account.info.balance);
+            entry.nonce = Some(account.info.nonce);
+            if let Some(code) = code {
+                entry.code = Some(code.original_bytes());
+            }
+            if account.account_state.is_storage_cleared() {
+                entry.storage.clear();
+            }
+            entry.storage.extend(account.storage.iter().map(|(slot, value)| ((*slot).into(), (*value).into())));
+        }
+        state
+    }
+
+    /// Same as `export_accounts` for a single account
+    pub fn export_account(&self, address: &Address) -> Option<GethAccountState> {
+        let mut state = self.read_only_db.as_ref().and_then(|db| db.export_account(address));
+
+        if let Some(account) = self.accounts.get(address) {
+            if matches!(account.account_state, DBAccountState::NotExisting) && account.storage.is_empty() {
+                return state;
+            }
+            let code =
+                account.info.code.clone().or_else(|| self.code_by_hash_ref(account.info.code_hash).ok()).filter(|code| !code.is_empty());
+
+            let entry = state.get_or_insert_with(GethAccountState::default);
+            entry.balance = Some(account.info.balance);
+            entry.nonce = Some(account.info.nonce);
+            if let Some(code) = code {
+                entry.code = Some(code.original_bytes());
+            }
+            if account.account_state.is_storage_cleared() {
+                entry.storage.clear();
+            }
+            entry.storage.extend(account.storage.iter().map(|(slot, value)| ((*slot).into(), (*value).into())));
+        }
+        state
+    }
+
+    /// Accounts and slots with a different value in `self`, as a single geth state update applying on top of `other`. Read-only
+    /// layers shared by both are not compared.
+    pub fn diff(&self, other: &LoomDB) -> Vec<BTreeMap<Address, GethAccountState>> {
+        let mut addresses = BTreeSet::new();
+        self.collect_unshared_addresses(other, &mut addresses);
+        other.collect_unshared_addresses(self, &mut addresses);
+
+        let mut update = BTreeMap::new();
+        for address in addresses {
+            let Some(account) = self.export_account(&address) else {
+                continue;
+            };
+            let other_account = other.export_account(&address).unwrap_or_default();
+
+            let account_diff = GethAccountState {
+                balance: account.balance.filter(|balance| other_account.balance != Some(*balance)),
+                nonce: account.nonce.filter(|nonce| other_account.nonce != Some(*nonce)),
+                code: account.code.filter(|code| other_account.code.as_ref() != Some(code)),
+                storage: account.storage.into_iter().filter(|(slot, value)| other_account.storage.get(slot) != Some(value)).collect(),
+            };
+            if account_diff != GethAccountState::default() {
+                update.insert(address, account_diff);
+            }
+        }
+
+        if update.is_empty() {
+            vec![]
+        } else {
+            vec![update]
+        }
+    }
+
+    /// Addresses of the layers of `self` above the first read-only layer shared with `other`
+    fn collect_unshared_addresses(&self, other: &LoomDB, addresses: &mut BTreeSet<Address>) {
+        addresses.extend(self.accounts.keys().copied());
+        let mut layer = self.read_only_db.as_ref();
+        while let Some(db) = layer {
+            if other.has_read_only_layer(db) {
+                break;
+            }
+            addresses.extend(db.accounts.keys().copied());
+            layer = db.read_only_db.as_ref();
+        }
+    }
+
+    fn has_read_only_layer(&self, layer: &Arc<LoomDB>) -> bool {
+        let mut current = self.read_only_db.as_ref();
+        while let Some(db) = current {
+            if Arc::ptr_eq(db, layer) {
+                return true;
+            }
+            current = db.read_only_db.as_ref();
+        }
+        false
+    }
 }
 
 impl DatabaseLoomExt for LoomDB {
@@ -505,9 +651,25 @@ impl DatabaseLoomExt for LoomDB {
         self.replace_account_storage(address, storage)
     }
 
+    fn apply_state_diff_in_place(&mut self, update: &[BTreeMap<Address, GethAccountState>]) -> Result<()> {
+        self.apply_state_diff_in_place(update)
+    }
+
+    fn export_accounts(&self) -> BTreeMap<Address, GethAccountState> {
+        self.export_accounts()
+    }
+
+    fn diff(&self, other: &Self) -> Vec<BTreeMap<Address, GethAccountState>> {
+        self.diff(other)
+    }
+
     fn maintain(self) -> Self {
         self.merge_all()
     }
+
+    fn new_layer(base: Arc<Self>) -> Self {
+        LoomDB { read_only_db: Some(base), ..LoomDB::new() }
+    }
 }
 
 impl DatabaseRef for LoomDB {
@@ -681,6 +843,7 @@ mod test {
     use super::GethAccountState;
     use crate::alloydb::AlloyDB;
     use crate::loom_db::LoomDB;
+    use crate::DatabaseLoomExt;
     use alloy::eips::BlockNumberOrTag;
     use alloy::primitives::map::HashMap;
     use alloy::primitives::{Address, Bytes, B256, I256, U256};
@@ -690,6 +853,7 @@ mod test {
     use revm::primitives::{AccountInfo, Bytecode, KECCAK_EMPTY};
     use revm::{Database, DatabaseRef};
     use std::collections::BTreeMap;
+    use std::sync::Arc;
 
     #[test]
     fn test_new_with_provider() {
@@ -838,6 +1002,130 @@ mod test {
         assert_eq!(new_state.accounts.len(), 1);
     }
 
+    #[test]
+    fn test_apply_state_diff_in_place() {
+        let account = Address::with_last_byte(42);
+        let other_account = Address::with_last_byte(43);
+        let nonce = 42;
+        let mut init_state = LoomDB::new();
+        init_state.insert_account_info(account, AccountInfo { nonce, ..Default::default() });
+        init_state.insert_account_info(other_account, AccountInfo { nonce, ..Default::default() });
+
+        let (key0, value0) = (U256::from(123), U256::from(456));
+        let (key1, value1) = (U256::from(789), U256::from(999));
+        init_state.insert_account_storage(account, key0, value0).unwrap();
+        init_state.insert_account_storage(account, key1, value1).unwrap();
+
+        let mut new_state = LoomDB::new().with_ro_db(Some(init_state));
+
+        let update_record = GethAccountState {
+            balance: Some(U256::from(1000)),
+            code: Some(Bytes::from(vec![1, 2, 3])),
+            nonce: Some(nonce + 1),
+            storage: [(B256::from(I256::try_from(123).unwrap()), B256::from(I256::try_from(333).unwrap()))].into(),
+        };
+        let update: Vec<BTreeMap<Address, GethAccountState>> =
+            vec![[(account, update_record), (Address::ZERO, GethAccountState::default())].into()];
+
+        new_state.apply_state_diff_in_place(&update).unwrap();
+
+        assert_eq!(new_state.accounts.len(), 1);
+        assert_eq!(new_state.basic(account).unwrap().unwrap().code, Some(Bytecode::new_raw(Bytes::from(vec![1, 2, 3]))));
+        assert_eq!(new_state.basic(account).unwrap().unwrap().nonce, nonce + 1);
+        assert_eq!(new_state.basic(account).unwrap().unwrap().balance, U256::from(1000));
+        assert_eq!(new_state.storage_ref(account, key0).unwrap(), U256::from(333));
+        assert_eq!(new_state.storage_ref(account, key1).unwrap(), value1);
+        assert_eq!(new_state.basic_ref(other_account).unwrap().unwrap().nonce, nonce);
+
+        let read_only_db = new_state.read_only_db();
+        assert_eq!(read_only_db.basic_ref(account).unwrap().unwrap().nonce, nonce);
+        assert_eq!(read_only_db.storage_ref(account, key0).unwrap(), value0);
+    }
+
+    #[test]
+    fn test_export_accounts() {
+        let account = Address::with_last_byte(42);
+        let other_account = Address::with_last_byte(43);
+        let mut init_state = LoomDB::new();
+        init_state.insert_account_info(other_account, AccountInfo { nonce: 1, ..Default::default() });
+        init_state.insert_account_storage(account, U256::from(1), U256::from(10)).unwrap();
+        init_state.insert_account_storage(account, U256::from(2), U256::from(20)).unwrap();
+
+        let mut new_state = LoomDB::new().with_ro_db(Some(init_state));
+        let code = Bytecode::new_raw(Bytes::from(vec![1, 2, 3]));
+        new_state.insert_account_info(account, AccountInfo { nonce: 2, code: Some(code.clone()), ..Default::default() });
+        new_state.insert_account_storage(account, U256::from(2), U256::from(30)).unwrap();
+
+        let exported = new_state.export_accounts();
+        assert_eq!(exported.len(), 2);
+
+        let mut restored_state = LoomDB::new();
+        restored_state.apply_geth_update(exported);
+        assert_eq!(restored_state.basic_ref(account).unwrap().unwrap().nonce, 2);
+        assert_eq!(restored_state.basic_ref(account).unwrap().unwrap().code, Some(code));
+        assert_eq!(restored_state.storage_ref(account, U256::from(1)).unwrap(), U256::from(10));
+        assert_eq!(restored_state.storage_ref(account, U256::from(2)).unwrap(), U256::from(30));
+        assert_eq!(restored_state.basic_ref(other_account).unwrap().unwrap().nonce, 1);
+    }
+
+    #[test]
+    fn test_diff() {
+        let account = Address::with_last_byte(42);
+        let other_account = Address::with_last_byte(43);
+        let mut init_state = LoomDB::new();
+        init_state.insert_account_info(other_account, AccountInfo { nonce: 1, ..Default::default() });
+        init_state.insert_account_storage(account, U256::from(1), U256::from(10)).unwrap();
+        init_state.insert_account_storage(account, U256::from(2), U256::from(20)).unwrap();
+
+        let state = LoomDB::new().with_ro_db(Some(init_state));
+        let mut new_state = state.clone();
+        new_state.insert_account_info(other_account, AccountInfo { nonce: 1, ..Default::default() });
+        new_state.insert_account_storage(account, U256::from(1), U256::from(10)).unwrap();
+        new_state.insert_account_storage(account, U256::from(2), U256::from(30)).unwrap();
+        new_state.insert_account_storage(account, U256::from(3), U256::from(40)).unwrap();
+
+        let diff = new_state.diff(&state);
+        assert_eq!(diff.len(), 1);
+        assert_eq!(diff[0].len(), 1);
+        let account_diff = diff[0].get(&account).unwrap();
+        assert_eq!(account_diff.nonce, None);
+        assert_eq!(
+            account_diff.storage,
+            BTreeMap::from([(U256::from(2).into(), U256::from(30).into()), (U256::from(3).into(), U256::from(40).into())])
+        );
+
+        let mut restored_state = state.clone();
+        restored_state.apply_geth_update_vec(diff);
+        assert_eq!(restored_state.storage_ref(account, U256::from(2)).unwrap(), U256::from(30));
+        assert_eq!(restored_state.storage_ref(account, U256::from(3)).unwrap(), U256::from(40));
+
+        assert!(state.diff(&state).is_empty());
+    }
+
+    #[test]
+    fn test_diff_read_only_layers() {
+        let account = Address::with_last_byte(42);
+        let mut init_state = LoomDB::new();
+        init_state.insert_account_info(account, AccountInfo { nonce: 1, ..Default::default() });
+        init_state.insert_account_storage(account, U256::from(1), U256::from(10)).unwrap();
+
+        // The account is only in the read-only layer of `state`, the other database does not share it
+        let state = LoomDB::new().with_ro_db(Some(init_state));
+        let diff = state.diff(&LoomDB::new());
+        assert_eq!(diff.len(), 1);
+        let account_diff = diff[0].get(&account).unwrap();
+        assert_eq!(account_diff.nonce, Some(1));
+        assert_eq!(account_diff.storage, BTreeMap::from([(U256::from(1).into(), U256::from(10).into())]));
+
+        // Layers over the shared read-only layer only compare their own accounts
+        let base = Arc::new(state);
+        let mut new_state = LoomDB::new_layer(base.clone());
+        new_state.insert_account_storage(account, U256::from(1), U256::from(20)).unwrap();
+        let diff = new_state.diff(&LoomDB::new_layer(base.clone()));
+        assert_eq!(diff[0].get(&account).unwrap().storage, BTreeMap::from([(U256::from(1).into(), U256::from(20).into())]));
+        assert!(LoomDB::new_layer(base.clone()).diff(&LoomDB::new_layer(base)).is_empty());
+    }
+
     #[test]
     fn test_merge() {
         let account = Address::with_last_byte(42);
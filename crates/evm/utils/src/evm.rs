@@ -1,5 +1,6 @@
 use crate::evm_env::evm_env_from_tx;
 use alloy::eips::BlockNumHash;
+use alloy::primitives::Log as EVMLog;
 use alloy::primitives::TxHash;
 use alloy::rpc::types::trace::geth::AccountState;
 use alloy::rpc::types::Log;
@@ -10,8 +11,11 @@ use alloy::{
 use eyre::eyre;
 use lazy_static::lazy_static;
 use loom_types_blockchain::GethStateUpdate;
-use revm::primitives::{Account, Env, ExecutionResult, HaltReason, Output, ResultAndState, TransactTo, CANCUN};
-use revm::{Database, DatabaseCommit, DatabaseRef, Evm};
+use revm::interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome};
+use revm::primitives::{
+    Account, AuthorizationList, Env, EvmState, ExecutionResult, HaltReason, Output, ResultAndState, TransactTo, CANCUN, PRAGUE,
+};
+use revm::{inspector_handle_register, Database, DatabaseCommit, DatabaseRef, Evm, EvmContext, Inspector};
 use std::collections::BTreeMap;
 use std::fmt::Debug;
 use thiserror::Error;
@@ -75,6 +79,91 @@ where
 }
 
 pub fn evm_access_list<DB: DatabaseRef>(state_db: DB, env: &Env, tx: &TransactionRequest) -> eyre::Result<(u64, AccessList)> {
+    let (gas_used, access_list, _) = evm_access_list_with_rebate(state_db, env, tx, None)?;
+    Ok((gas_used, access_list))
+}
+
+/// Same as `evm_access_list`, additionally returns how much ETH `rebate_contract` sent to the sender or the receiver of the transaction
+pub fn evm_access_list_with_rebate<DB: DatabaseRef>(
+    state_db: DB,
+    env: &Env,
+    tx: &TransactionRequest,
+    rebate_contract: Option<Address>,
+) -> eyre::Result<(u64, AccessList, U256)> {
+    let (gas_used, access_list, rebate, _, _) = evm_access_list_with_state(state_db, env, tx, rebate_contract)?;
+    Ok((gas_used, access_list, rebate))
+}
+
+/// Sums ETH sent by `from` to `recipients` in calls that are not reverted
+struct ValueInflowInspector {
+    from: Option<Address>,
+    recipients: [Address; 2],
+    // Inflow of the open frames, added to the parent frame when the frame succeeds
+    frames: Vec<U256>,
+    inflow: U256,
+}
+
+impl ValueInflowInspector {
+    fn new(from: Option<Address>, recipients: [Address; 2]) -> Self {
+        Self { from, recipients, frames: Vec::new(), inflow: U256::ZERO }
+    }
+
+    fn frame_end(&mut self, success: bool) {
+        let frame_inflow = self.frames.pop().unwrap_or_default();
+        if success {
+            match self.frames.last_mut() {
+                Some(parent_inflow) => *parent_inflow += frame_inflow,
+                None => self.inflow += frame_inflow,
+            }
+        }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for ValueInflowInspector {
+    fn call(&mut self, _context: &mut EvmContext<DB>, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        let inflow = match inputs.transfer_value() {
+            Some(value) if Some(inputs.caller) == self.from && self.recipients.contains(&inputs.target_address) => value,
+            _ => U256::ZERO,
+        };
+        self.frames.push(inflow);
+        None
+    }
+
+    fn call_end(&mut self, _context: &mut EvmContext<DB>, _inputs: &CallInputs, outcome: CallOutcome) -> CallOutcome {
+        self.frame_end(outcome.result.is_ok());
+        outcome
+    }
+
+    fn create(&mut self, _context: &mut EvmContext<DB>, _inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        self.frames.push(U256::ZERO);
+        None
+    }
+
+    fn create_end(&mut self, _context: &mut EvmContext<DB>, _inputs: &CreateInputs, outcome: CreateOutcome) -> CreateOutcome {
+        self.frame_end(outcome.result.is_ok());
+        outcome
+    }
+}
+
+/// Code of `account` is an EIP-7702 delegation designator
+pub fn is_eip7702_delegated<DB: DatabaseRef>(state_db: &DB, account: Address) -> bool {
+    let Ok(Some(account_info)) = state_db.basic_ref(account) else {
+        return false;
+    };
+    match account_info.code {
+        Some(code) => code.is_eip7702(),
+        None => state_db.code_by_hash_ref(account_info.code_hash).is_ok_and(|code| code.is_eip7702()),
+    }
+}
+
+/// Same as `evm_access_list_with_rebate`, additionally returns the accounts and slots loaded by the transaction with their
+/// values after it and the logs of the transaction
+pub fn evm_access_list_with_state<DB: DatabaseRef>(
+    state_db: DB,
+    env: &Env,
+    tx: &TransactionRequest,
+    rebate_contract: Option<Address>,
+) -> eyre::Result<(u64, AccessList, U256, EvmState, Vec<EVMLog>)> {
     let mut env = env.clone();
 
     let tx_to = tx.to.unwrap_or_default().to().map_or(Address::ZERO, |x| *x);
@@ -91,21 +180,41 @@ pub fn evm_access_list<DB: DatabaseRef>(state_db: DB, env: &Env, tx: &Transactio
 
     env.block.coinbase = *COINBASE;
 
-    let mut evm = Evm::builder().with_ref_db(state_db).with_spec_id(CANCUN).with_env(Box::new(env)).build();
+    // EIP-7702 authorizations and the code of delegated accounts are applied by Prague only
+    let spec_id = match &tx.authorization_list {
+        Some(authorization_list) => {
+            env.tx.authorization_list = Some(AuthorizationList::Signed(authorization_list.clone()));
+            PRAGUE
+        }
+        None if is_eip7702_delegated(&state_db, tx_to) => PRAGUE,
+        None => CANCUN,
+    };
+
+    let rebate_inspector = ValueInflowInspector::new(rebate_contract, [env.tx.caller, tx_to]);
+
+    let mut evm = Evm::builder()
+        .with_ref_db(state_db)
+        .with_spec_id(spec_id)
+        .with_env(Box::new(env))
+        .with_external_context(rebate_inspector)
+        .append_handler_register(inspector_handle_register)
+        .build();
 
     let ref_tx = evm.transact().map_err(|_| EvmError::TransactError)?;
     let execution_result = ref_tx.result;
     match execution_result {
-        ExecutionResult::Success { output, gas_used, reason, .. } => {
+        ExecutionResult::Success { output, gas_used, reason, logs, .. } => {
             debug!(gas_used, ?reason, ?output, "AccessList");
             let mut acl = AccessList::default();
 
-            for (addr, acc) in ref_tx.state {
+            let rebate = evm.context.external.inflow;
+
+            for (addr, acc) in ref_tx.state.iter() {
                 let storage_keys: Vec<B256> = acc.storage.keys().map(|x| (*x).into()).collect();
-                acl.0.push(AccessListItem { address: addr, storage_keys });
+                acl.0.push(AccessListItem { address: *addr, storage_keys });
             }
 
-            Ok((gas_used, acl))
+            Ok((gas_used, acl, rebate, ref_tx.state, logs))
         }
         ExecutionResult::Revert { output, gas_used } => Err(eyre!(EvmError::Reverted(revert_bytes_to_string(&output), gas_used))),
         ExecutionResult::Halt { reason, gas_used } => Err(eyre!(EvmError::Halted(reason, gas_used))),
@@ -1,4 +1,6 @@
-use alloy::primitives::{Address, U256};
+use alloy::primitives::{Address, Log, U256};
+use alloy::sol_types::SolEvent;
+use loom_defi_abi::IWETH;
 use loom_defi_address_book::TokenAddressEth;
 use std::ops::{Add, Mul};
 
@@ -64,4 +66,13 @@ impl NWETH {
     pub fn address() -> Address {
         Self::ADDRESS
     }
+
+    /// Sum of the WETH `Withdrawal` events of `account` in the logs. The multicaller unwraps the profit of a swap starting
+    /// in WETH to pay the tips and the owner, so this is the profit the swap transaction realized.
+    pub fn withdrawn_by<'a>(logs: impl IntoIterator<Item = &'a Log>, account: Address) -> U256 {
+        logs.into_iter()
+            .filter_map(|log| IWETH::Withdrawal::decode_log(log, false).ok())
+            .filter(|withdrawal| withdrawal.data.src == account)
+            .fold(U256::ZERO, |total, withdrawal| total.saturating_add(withdrawal.data.wad))
+    }
 }
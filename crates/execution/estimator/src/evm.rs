@@ -2,34 +2,100 @@ use alloy_consensus::TxEnvelope;
 use alloy_eips::eip2718::Encodable2718;
 use alloy_eips::BlockNumberOrTag;
 use alloy_network::{Ethereum, Network};
-use alloy_primitives::{Bytes, TxKind, U256};
+use alloy_primitives::{Address, Bytes, TxKind, U256};
+#[cfg(feature = "debug-traces")]
+use alloy_primitives::{keccak256, B256};
 use alloy_provider::Provider;
 use alloy_rpc_types::{TransactionInput, TransactionRequest};
 use eyre::{eyre, Result};
 use influxdb::{Timestamp, WriteQuery};
 use std::marker::PhantomData;
+use std::sync::Arc;
 use tokio::sync::broadcast::error::RecvError;
 use tracing::{debug, error, info, trace};
 
 use loom_core_blockchain::{Blockchain, Strategy};
 use loom_evm_utils::NWETH;
-use loom_types_entities::{EstimationError, Swap, SwapEncoder};
+use loom_types_entities::tips::Tips;
+use loom_types_entities::{Eip7702SwapEncoder, EstimationError, Swap, SwapEncoder};
 
-use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, Consumer, Producer, WorkerResult};
-use loom_core_actors_macros::{Consumer, Producer};
+use loom_core_actors::{
+    subscribe, Accessor, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, Producer, SharedState, WorkerResult,
+};
+use loom_core_actors_macros::{Accessor, Consumer, Producer};
 use loom_evm_db::{AlloyDB, DatabaseLoomExt};
-use loom_evm_utils::evm::evm_access_list;
+use loom_evm_utils::evm::evm_access_list_with_state;
 use loom_evm_utils::evm_env::env_for_block;
-use loom_types_events::{HealthEvent, MessageHealthEvent, MessageSwapCompose, SwapComposeData, SwapComposeMessage, TxComposeData, TxState};
+#[cfg(feature = "debug-traces")]
+use loom_evm_utils::evm_trace::{evm_trace_call, EvmTraceError};
+#[cfg(feature = "debug-traces")]
+use loom_types_events::TraceFrame;
+use loom_types_events::{
+    HealthEvent, MessageHealthEvent, MessageSwapCompose, SwapComposeData, SwapComposeMessage, SwapTraces, TxComposeData, TxState,
+    EVM_ESTIMATOR_SOURCE,
+};
+#[cfg(feature = "debug-traces")]
+use revm::primitives::Env;
 use revm::DatabaseRef;
 
+/// Traces the swap call with revm's tracing inspector. Reverted and halted calls are traced too.
+#[cfg(feature = "debug-traces")]
+fn trace_swap_call<DB: DatabaseRef>(
+    db: DB,
+    evm_env: &Env,
+    caller: Address,
+    to: Address,
+    call_value: Option<U256>,
+    call_data: &Bytes,
+) -> Option<Vec<TraceFrame>> {
+    let mut env = evm_env.clone();
+    env.tx.caller = caller;
+    env.tx.value = call_value.unwrap_or_default();
+
+    match evm_trace_call(db, env, to, call_data.to_vec()) {
+        Ok((_, _, trace)) | Err(EvmTraceError::Reverted(_, _, trace)) | Err(EvmTraceError::Halted(_, _, trace)) => Some(trace),
+        Err(error) => {
+            error!(%error, "evm_trace_call");
+            None
+        }
+    }
+}
+
+/// Encodes the swap, swaps with `use_eip7702` are encoded for the sender EOA delegated to the encoder contract
+fn encode_swap<E, DB>(
+    swap_encoder: &E,
+    estimate_request: &SwapComposeData<DB>,
+    gas_cost: Option<U256>,
+    sender_address: Address,
+) -> Result<(Address, Option<U256>, Bytes, Vec<Tips>)>
+where
+    E: SwapEncoder + Clone,
+{
+    let (swap, tips_pct, next_block_number, eth_balance) = (
+        estimate_request.swap.clone(),
+        estimate_request.tips_pct,
+        Some(estimate_request.tx_compose.next_block_number),
+        Some(estimate_request.tx_compose.eth_balance),
+    );
+    if estimate_request.tx_compose.use_eip7702 {
+        Eip7702SwapEncoder::new(swap_encoder.clone()).encode(swap, tips_pct, next_block_number, gas_cost, Some(sender_address), eth_balance)
+    } else {
+        swap_encoder.encode(swap, tips_pct, next_block_number, gas_cost, Some(sender_address), eth_balance)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(not(feature = "debug-traces"), allow(unused_variables))]
 async fn estimator_task<N, DB>(
     client: Option<impl Provider<N> + 'static>,
-    swap_encoder: impl SwapEncoder,
+    swap_encoder: impl SwapEncoder + Clone,
     estimate_request: SwapComposeData<DB>,
     compose_channel_tx: Broadcaster<MessageSwapCompose<DB>>,
     health_monitor_channel_tx: Option<Broadcaster<MessageHealthEvent>>,
     influxdb_write_channel_tx: Option<Broadcaster<WriteQuery>>,
+    swap_traces: Option<SharedState<SwapTraces>>,
+    gas_rebate_contract: Option<Address>,
+    chain_id: u64,
 ) -> Result<()>
 where
     N: Network,
@@ -48,30 +114,7 @@ where
     let tx_signer = estimate_request.tx_compose.signer.clone().ok_or(eyre!("NO_SIGNER"))?;
     let gas_price = estimate_request.tx_compose.priority_gas_fee + estimate_request.tx_compose.next_block_base_fee;
 
-    let (to, call_value, call_data, _) = swap_encoder.encode(
-        estimate_request.swap.clone(),
-        estimate_request.tips_pct,
-        Some(estimate_request.tx_compose.next_block_number),
-        None,
-        Some(tx_signer.address()),
-        Some(estimate_request.tx_compose.eth_balance),
-    )?;
-
-    let tx_request = TransactionRequest {
-        transaction_type: Some(2),
-        chain_id: Some(1),
-        from: Some(tx_signer.address()),
-        to: Some(TxKind::Call(to)),
-        gas: Some(estimate_request.tx_compose.gas),
-        value: call_value,
-        input: TransactionInput::new(call_data.clone()),
-        nonce: Some(estimate_request.tx_compose.nonce),
-        max_priority_fee_per_gas: Some(estimate_request.tx_compose.priority_gas_fee as u128),
-        max_fee_per_gas: Some(
-            estimate_request.tx_compose.next_block_base_fee as u128 + estimate_request.tx_compose.priority_gas_fee as u128,
-        ),
-        ..TransactionRequest::default()
-    };
+    let (to, call_value, call_data, _) = encode_swap(&swap_encoder, &estimate_request, None, tx_signer.address())?;
 
     let Some(mut db) = estimate_request.poststate else {
         error!("StateDB is None");
@@ -87,10 +130,39 @@ where
         }
     }
 
-    let evm_env = env_for_block(estimate_request.tx_compose.next_block_number, estimate_request.tx_compose.next_block_timestamp);
+    let mut evm_env = env_for_block(estimate_request.tx_compose.next_block_number, estimate_request.tx_compose.next_block_timestamp);
+    evm_env.cfg.chain_id = chain_id;
+
+    // The router requests the authorization until the EOA is delegated to the encoder contract.
+    // The sender nonce is increased before the authorization is applied.
+    let (transaction_type, authorization_list) = if estimate_request.tx_compose.eip7702_authorize {
+        let authorization =
+            Eip7702SwapEncoder::new(swap_encoder.clone()).authorization(evm_env.cfg.chain_id, estimate_request.tx_compose.nonce + 1);
+        (4, Some(vec![tx_signer.sign_authorization(authorization)?]))
+    } else {
+        (2, None)
+    };
 
-    let (gas_used, access_list) = match evm_access_list(&db, &evm_env, &tx_request) {
-        Ok((gas_used, access_list)) => {
+    let tx_request = TransactionRequest {
+        transaction_type: Some(transaction_type),
+        chain_id: Some(evm_env.cfg.chain_id),
+        from: Some(tx_signer.address()),
+        to: Some(TxKind::Call(to)),
+        gas: Some(estimate_request.tx_compose.gas),
+        value: call_value,
+        input: TransactionInput::new(call_data.clone()),
+        nonce: Some(estimate_request.tx_compose.nonce),
+        max_priority_fee_per_gas: Some(estimate_request.tx_compose.priority_gas_fee as u128),
+        max_fee_per_gas: Some(
+            estimate_request.tx_compose.next_block_base_fee as u128 + estimate_request.tx_compose.priority_gas_fee as u128,
+        ),
+        authorization_list: authorization_list.clone(),
+        ..TransactionRequest::default()
+    };
+
+    let access_list_result = evm_access_list_with_state(&db, &evm_env, &tx_request, gas_rebate_contract);
+    let (gas_used, access_list, gas_rebate, tx_state, logs) = match access_list_result {
+        Ok((gas_used, access_list, gas_rebate, tx_state, logs)) => {
             let pool_id_vec = estimate_request.swap.get_pool_id_vec();
 
             tokio::task::spawn(async move {
@@ -108,7 +180,7 @@ where
                 }
             });
 
-            (gas_used, access_list)
+            (gas_used, access_list, gas_rebate, tx_state, logs)
         }
         Err(e) => {
             trace!(
@@ -120,6 +192,10 @@ where
             // simulation has failed but this could be caused by a token / pool with unsupported fee issue
             trace!("evm_access_list error calldata : {} {}", to, call_data);
 
+            if let Err(error) = compose_channel_tx.send(MessageSwapCompose::rejected(estimate_request.clone())) {
+                error!(%error, "compose_channel_tx.send");
+            }
+
             if let Some(health_monitor_channel_tx) = &health_monitor_channel_tx {
                 if let Swap::BackrunSwapLine(swap_line) = estimate_request.swap {
                     if let Err(e) =
@@ -143,25 +219,38 @@ where
         return Err(eyre!("TRANSACTION_ESTIMATED_INCORRECTLY"));
     }
 
-    let gas_cost = U256::from(gas_used as u128 * gas_price as u128);
+    // The swap is written into its own layer over the estimate state, diffing it against an empty layer over the same state
+    // visits only the accounts changed by the swap
+    let base_db = Arc::new(db);
+    let mut swap_poststate = DB::new_layer(base_db.clone());
+    for (address, account) in tx_state.into_iter().filter(|(_, account)| account.is_touched()) {
+        for (slot, value) in account.changed_storage_slots() {
+            swap_poststate.insert_account_storage(address, *slot, value.present_value())?;
+        }
+        swap_poststate.insert_account_info(address, account.info);
+    }
+    let poststate_update = swap_poststate.diff(&DB::new_layer(base_db.clone()));
+    drop(swap_poststate);
+    let db = Arc::unwrap_or_clone(base_db);
+
+    // Profit unwrapped by the swap contract, swaps starting in other tokens keep it in the contract
+    let simulated_profit_eth =
+        estimate_request.swap.get_first_token().filter(|token| token.is_weth()).map(|_| NWETH::withdrawn_by(&logs, to));
+
+    // ETH paid back by the rebate contract during simulation offsets the gas cost
+    let gas_cost = U256::from(gas_used as u128 * gas_price as u128).saturating_sub(gas_rebate);
 
     debug!(
-        "Swap encode swap={}, tips_pct={:?}, next_block_number={}, gas_cost={}, signer={}",
+        "Swap encode swap={}, tips_pct={:?}, next_block_number={}, gas_cost={}, gas_rebate={}, signer={}",
         estimate_request.swap,
         estimate_request.tips_pct,
         estimate_request.tx_compose.next_block_number,
         gas_cost,
+        gas_rebate,
         tx_signer.address()
     );
 
-    let (to, call_value, call_data, tips_vec) = match swap_encoder.encode(
-        estimate_request.swap.clone(),
-        estimate_request.tips_pct,
-        Some(estimate_request.tx_compose.next_block_number),
-        Some(gas_cost),
-        Some(tx_signer.address()),
-        Some(estimate_request.tx_compose.eth_balance),
-    ) {
+    let (to, call_value, call_data, tips_vec) = match encode_swap(&swap_encoder, &estimate_request, Some(gas_cost), tx_signer.address()) {
         Ok((to, call_value, call_data, tips_vec)) => (to, call_value, call_data, tips_vec),
         Err(error) => {
             error!(%error, %swap, "swap_encoder.encode");
@@ -169,9 +258,20 @@ where
         }
     };
 
+    // Swap id of the trace is the hash of the final calldata
+    #[cfg(feature = "debug-traces")]
+    let (swap_id, simulation_trace): (B256, Option<Vec<TraceFrame>>) =
+        (keccak256(&call_data), trace_swap_call(&db, &evm_env, tx_signer.address(), to, call_value, &call_data));
+
+    #[cfg(feature = "debug-traces")]
+    if let (Some(swap_traces), Some(simulation_trace)) = (&swap_traces, &simulation_trace) {
+        swap_traces.write().await.insert(swap_id, simulation_trace.clone());
+        debug!(%swap_id, %swap, "Simulation trace stored");
+    }
+
     let tx_request = TransactionRequest {
-        transaction_type: Some(2),
-        chain_id: Some(1),
+        transaction_type: Some(transaction_type),
+        chain_id: Some(evm_env.cfg.chain_id),
         from: Some(tx_signer.address()),
         to: Some(TxKind::Call(to)),
         gas: Some((gas_used * 1500) / 1000),
@@ -183,6 +283,7 @@ where
         max_fee_per_gas: Some(
             estimate_request.tx_compose.priority_gas_fee as u128 + estimate_request.tx_compose.next_block_base_fee as u128,
         ),
+        authorization_list,
         ..TransactionRequest::default()
     };
 
@@ -205,12 +306,19 @@ where
         None => profit_eth_f64,
     };
 
-    let sign_request = MessageSwapCompose::ready(SwapComposeData {
-        tx_compose: TxComposeData { tx_bundle: Some(tx_with_state), ..estimate_request.tx_compose },
-        poststate: Some(db),
-        tips: Some(total_tips + gas_cost),
-        ..estimate_request
-    });
+    let sign_request = MessageSwapCompose::ready_with_source(
+        SwapComposeData {
+            tx_compose: TxComposeData { gas: gas_used, tx_bundle: Some(tx_with_state), ..estimate_request.tx_compose },
+            poststate: Some(db),
+            poststate_update: Some(poststate_update),
+            tips: Some(total_tips + gas_cost),
+            simulated_profit_eth,
+            #[cfg(feature = "debug-traces")]
+            simulation_trace,
+            ..estimate_request
+        },
+        EVM_ESTIMATOR_SOURCE,
+    );
 
     let result = match compose_channel_tx.send(sign_request) {
         Err(error) => {
@@ -235,6 +343,7 @@ where
     result
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn estimator_worker<N, DB>(
     client: Option<impl Provider<N> + Clone + 'static>,
     encoder: impl SwapEncoder + Send + Sync + Clone + 'static,
@@ -242,6 +351,10 @@ async fn estimator_worker<N, DB>(
     compose_channel_tx: Broadcaster<MessageSwapCompose<DB>>,
     health_monitor_channel_tx: Option<Broadcaster<MessageHealthEvent>>,
     influxdb_write_channel_tx: Option<Broadcaster<WriteQuery>>,
+    swap_traces: Option<SharedState<SwapTraces>>,
+    gas_rebate_contract: Option<Address>,
+    chain_id: u64,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult
 where
     N: Network,
@@ -251,6 +364,9 @@ where
 
     loop {
         tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("EVM estimator cancelled".to_string());
+            }
             msg = compose_channel_rx.recv() => {
                 let compose_request_msg : Result<MessageSwapCompose<DB>, RecvError> = msg;
                 match compose_request_msg {
@@ -261,17 +377,24 @@ where
                             let client_cloned = client.clone();
                             let influxdb_channel_tx_cloned = influxdb_write_channel_tx.clone();
                             let health_monitor_channel_tx_cloned = health_monitor_channel_tx.clone();
+                            let swap_traces_cloned = swap_traces.clone();
                             tokio::task::spawn(
                                 async move {
                                 if let Err(e) = estimator_task(
                                         client_cloned,
                                         encoder_cloned,
                                         estimate_request.clone(),
-                                        compose_channel_tx_cloned,
+                                        compose_channel_tx_cloned.clone(),
                                         health_monitor_channel_tx_cloned,
                                         influxdb_channel_tx_cloned,
+                                        swap_traces_cloned,
+                                        gas_rebate_contract,
+                                        chain_id,
                                 ).await {
                                         error!("Error in EVM estimator_task: {:?}", e);
+                                        if let Err(error) = compose_channel_tx_cloned.send(MessageSwapCompose::rejected(estimate_request)) {
+                                            error!(%error, "compose_channel_tx.send");
+                                        }
                                     }
                                 }
                             );
@@ -284,10 +407,12 @@ where
     }
 }
 
-#[derive(Consumer, Producer)]
+#[derive(Accessor, Consumer, Producer)]
 pub struct EvmEstimatorActor<P, N, E, DB: Clone + Send + Sync + 'static> {
     encoder: E,
     client: Option<P>,
+    #[accessor]
+    swap_traces: Option<SharedState<SwapTraces>>,
     #[consumer]
     compose_channel_rx: Option<Broadcaster<MessageSwapCompose<DB>>>,
     #[producer]
@@ -296,7 +421,10 @@ pub struct EvmEstimatorActor<P, N, E, DB: Clone + Send + Sync + 'static> {
     health_monitor_channel_tx: Option<Broadcaster<MessageHealthEvent>>,
     #[producer]
     influxdb_write_channel_tx: Option<Broadcaster<WriteQuery>>,
+    gas_rebate_contract: Option<Address>,
+    chain_id: u64,
     _n: PhantomData<N>,
+    cancellation_token: CancellationToken,
 }
 
 impl<P, N, E, DB> EvmEstimatorActor<P, N, E, DB>
@@ -310,10 +438,14 @@ where
         Self {
             encoder,
             client: None,
+            swap_traces: None,
             compose_channel_tx: None,
             compose_channel_rx: None,
             health_monitor_channel_tx: None,
             influxdb_write_channel_tx: None,
+            gas_rebate_contract: None,
+            chain_id: 1,
+            cancellation_token: CancellationToken::new(),
             _n: PhantomData::<N>,
         }
     }
@@ -322,20 +454,35 @@ where
         Self {
             encoder,
             client,
+            swap_traces: None,
             compose_channel_tx: None,
             compose_channel_rx: None,
             health_monitor_channel_tx: None,
             influxdb_write_channel_tx: None,
+            gas_rebate_contract: None,
+            chain_id: 1,
+            cancellation_token: CancellationToken::new(),
             _n: PhantomData::<N>,
         }
     }
 
+    /// Account ETH sent by `rebate_contract` to the signer or the multicaller during simulation as a gas rebate
+    pub fn with_gas_rebate(self, rebate_contract: Address) -> Self {
+        Self { gas_rebate_contract: Some(rebate_contract), ..self }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
     pub fn on_bc(self, bc: &Blockchain, strategy: &Strategy<DB>) -> Self {
         Self {
             compose_channel_tx: Some(strategy.swap_compose_channel()),
             compose_channel_rx: Some(strategy.swap_compose_channel()),
             health_monitor_channel_tx: Some(bc.health_monitor_channel()),
             influxdb_write_channel_tx: Some(bc.influxdb_write_channel()),
+            swap_traces: Some(bc.swap_traces()),
+            chain_id: bc.chain_id(),
             ..self
         }
     }
@@ -356,6 +503,10 @@ where
             self.compose_channel_tx.clone().unwrap(),
             self.health_monitor_channel_tx.clone(),
             self.influxdb_write_channel_tx.clone(),
+            self.swap_traces.clone(),
+            self.gas_rebate_contract,
+            self.chain_id,
+            self.cancellation_token.clone(),
         ));
         Ok(vec![task])
     }
@@ -3,6 +3,7 @@ use std::sync::Arc;
 
 use alloy_consensus::TxEnvelope;
 use alloy_eips::eip2718::Encodable2718;
+use alloy_eips::eip2930::AccessList;
 use alloy_network::Ethereum;
 use alloy_primitives::{Bytes, TxKind, U256};
 use alloy_provider::Provider;
@@ -16,10 +17,21 @@ use loom_evm_utils::NWETH;
 use loom_types_entities::{Swap, SwapEncoder};
 
 use loom_broadcast_flashbots::Flashbots;
-use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, Consumer, Producer, WorkerResult};
+use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, Producer, WorkerResult};
 use loom_core_actors_macros::{Consumer, Producer};
 use loom_types_blockchain::LoomTx;
-use loom_types_events::{MessageSwapCompose, SwapComposeData, SwapComposeMessage, TxComposeData, TxState};
+use loom_types_events::{MessageSwapCompose, SwapComposeData, SwapComposeMessage, TxComposeData, TxState, GETH_ESTIMATOR_SOURCE};
+
+/// Access list from `eth_createAccessList` and the gas used by the transaction with it attached. Every listed address and
+/// slot costs 2400 and 1900 gas upfront, so the list only pays off when the warm access discounts are larger
+async fn create_access_list<P: Provider<Ethereum>>(provider: &P, tx_request: &TransactionRequest) -> Result<(AccessList, u64)> {
+    let result = provider.create_access_list(tx_request).await?;
+    if let Some(error) = result.error {
+        error!("eth_createAccessList error : {}", error);
+        return Err(eyre!("CREATE_ACCESS_LIST_FAILED"));
+    }
+    Ok((result.access_list, result.gas_used.saturating_to()))
+}
 
 async fn estimator_task<P: Provider<Ethereum> + Send + Sync + Clone + 'static, DB: DatabaseRef + Send + Sync + Clone>(
     estimate_request: SwapComposeData<DB>,
@@ -110,12 +122,33 @@ async fn estimator_task<P: Provider<Ethereum> + Send + Sync + Clone + 'static, D
                     return Err(eyre!("TX_SIMULATION_REVERT"));
                 }
 
-                let gas = tx_sim_result.gas_used.to();
+                let mut gas = tx_sim_result.gas_used.to();
+
+                // Profit unwrapped by the swap contract, swaps starting in other tokens keep it in the contract
+                let simulated_profit_eth =
+                    token_in.is_weth().then(|| NWETH::withdrawn_by(tx_sim_result.logs.iter().flatten().map(|log| &log.inner), to));
 
                 if let Some(access_list) = tx_sim_result.access_list.clone() {
                     let swap = estimate_request.swap.clone();
 
-                    tx_request.access_list = Some(access_list.clone());
+                    let mut access_list = Some(access_list);
+                    if estimate_request.tx_compose.use_access_list {
+                        // Keep the access list only when the transaction gets cheaper with it
+                        access_list = match create_access_list(client.provider(), &tx_request).await {
+                            Ok((node_access_list, access_list_gas)) if access_list_gas < gas => {
+                                debug!(gas, access_list_gas, "Access list reduces gas used");
+                                gas = access_list_gas;
+                                Some(node_access_list)
+                            }
+                            Ok(_) => None,
+                            Err(e) => {
+                                debug!("Access list creation failed : {}", e);
+                                None
+                            }
+                        };
+                    }
+
+                    tx_request.access_list = access_list.clone();
                     let gas_cost = U256::from(gas * gas_price);
                     if gas_cost < profit_eth {
                         let (to, call_value, call_data, tips_vec) = match estimate_request.swap {
@@ -139,7 +172,7 @@ async fn estimator_task<P: Provider<Ethereum> + Send + Sync + Clone + 'static, D
                             value: call_value,
                             input: TransactionInput::new(call_data),
                             nonce: Some(estimate_request.tx_compose.nonce),
-                            access_list: Some(access_list),
+                            access_list,
                             max_priority_fee_per_gas: Some(estimate_request.tx_compose.priority_gas_fee as u128),
                             max_fee_per_gas: Some(estimate_request.tx_compose.next_block_base_fee as u128), // TODO: Why not prio + base fee?
                             ..TransactionRequest::default()
@@ -152,11 +185,15 @@ async fn estimator_task<P: Provider<Ethereum> + Send + Sync + Clone + 'static, D
 
                         let total_tips = tips_vec.into_iter().map(|v| v.tips).sum();
 
-                        let sign_request = MessageSwapCompose::ready(SwapComposeData {
-                            tx_compose: TxComposeData { gas, ..estimate_request.tx_compose },
-                            tips: Some(total_tips + gas_cost),
-                            ..estimate_request
-                        });
+                        let sign_request = MessageSwapCompose::ready_with_source(
+                            SwapComposeData {
+                                tx_compose: TxComposeData { gas, ..estimate_request.tx_compose },
+                                tips: Some(total_tips + gas_cost),
+                                simulated_profit_eth,
+                                ..estimate_request
+                            },
+                            GETH_ESTIMATOR_SOURCE,
+                        );
 
                         match compose_channel_tx.send(sign_request) {
                             Ok(_) => {
@@ -202,11 +239,15 @@ async fn estimator_worker<P: Provider<Ethereum> + Send + Sync + Clone + 'static,
     encoder: impl SwapEncoder + Send + Sync + Clone + 'static,
     compose_channel_rx: Broadcaster<MessageSwapCompose<DB>>,
     compose_channel_tx: Broadcaster<MessageSwapCompose<DB>>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult {
     subscribe!(compose_channel_rx);
 
     loop {
         tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Geth estimator cancelled".to_string());
+            }
             msg = compose_channel_rx.recv() => {
                 let compose_request_msg : Result<MessageSwapCompose<DB>, RecvError> = msg;
                 match compose_request_msg {
@@ -220,9 +261,12 @@ async fn estimator_worker<P: Provider<Ethereum> + Send + Sync + Clone + 'static,
                                     estimate_request.clone(),
                                     client_cloned,
                                     encoder_cloned,
-                                    compose_channel_tx_cloned,
+                                    compose_channel_tx_cloned.clone(),
                                 ).await {
                                         error!("Error in Geth estimator_task: {:?}", e);
+                                        if let Err(error) = compose_channel_tx_cloned.send(MessageSwapCompose::rejected(estimate_request)) {
+                                            error!(%error, "compose_channel_tx.send");
+                                        }
                                     }
                                 }
                             );
@@ -243,6 +287,7 @@ pub struct GethEstimatorActor<P, E, DB: Clone + Send + Sync + 'static> {
     compose_channel_rx: Option<Broadcaster<MessageSwapCompose<DB>>>,
     #[producer]
     compose_channel_tx: Option<Broadcaster<MessageSwapCompose<DB>>>,
+    cancellation_token: CancellationToken,
 }
 
 impl<P, E, DB> GethEstimatorActor<P, E, DB>
@@ -252,7 +297,11 @@ where
     DB: DatabaseRef + Send + Sync + Clone,
 {
     pub fn new(client: Arc<Flashbots<P>>, encoder: E) -> Self {
-        Self { client, encoder, compose_channel_tx: None, compose_channel_rx: None }
+        Self { client, encoder, compose_channel_tx: None, compose_channel_rx: None, cancellation_token: CancellationToken::new() }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
     }
 
     pub fn on_bc(self, strategy: &Strategy<DB>) -> Self {
@@ -276,6 +325,7 @@ where
             self.encoder.clone(),
             self.compose_channel_rx.clone().unwrap(),
             self.compose_channel_tx.clone().unwrap(),
+            self.cancellation_token.clone(),
         ));
         Ok(vec![task])
     }
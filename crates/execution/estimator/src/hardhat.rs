@@ -8,7 +8,7 @@ use revm::DatabaseRef;
 use tokio::sync::broadcast::error::RecvError;
 use tracing::{error, info};
 
-use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, Consumer, Producer, WorkerResult};
+use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, Producer, WorkerResult};
 use loom_core_actors_macros::{Consumer, Producer};
 use loom_node_debug_provider::DebugProviderExt;
 use loom_types_entities::SwapEncoder;
@@ -18,11 +18,15 @@ async fn estimator_worker<DB: DatabaseRef + Send + Sync + Clone>(
     swap_encoder: impl SwapEncoder,
     compose_channel_rx: Broadcaster<MessageSwapCompose<DB>>,
     compose_channel_tx: Broadcaster<MessageSwapCompose<DB>>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult {
     subscribe!(compose_channel_rx);
 
     loop {
         tokio::select! {
+                    _ = cancellation_token.cancelled() => {
+                        break Ok("Hardhat estimator cancelled".to_string());
+                    }
                     msg = compose_channel_rx.recv() => {
                         let compose_request_msg : Result<MessageSwapCompose<DB>, RecvError> = msg;
                         match compose_request_msg {
@@ -110,6 +114,7 @@ pub struct HardhatEstimatorActor<P, E, DB: Send + Sync + Clone + 'static> {
     compose_channel_rx: Option<Broadcaster<MessageSwapCompose<DB>>>,
     #[producer]
     compose_channel_tx: Option<Broadcaster<MessageSwapCompose<DB>>>,
+    cancellation_token: CancellationToken,
 }
 
 impl<P, E, DB> HardhatEstimatorActor<P, E, DB>
@@ -119,7 +124,11 @@ where
     DB: DatabaseRef + Send + Sync + Clone,
 {
     pub fn new(client: P, encoder: E) -> Self {
-        Self { client, encoder, compose_channel_tx: None, compose_channel_rx: None }
+        Self { client, encoder, compose_channel_tx: None, compose_channel_rx: None, cancellation_token: CancellationToken::new() }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
     }
 }
 
@@ -134,6 +143,7 @@ where
             self.encoder.clone(),
             self.compose_channel_rx.clone().unwrap(),
             self.compose_channel_tx.clone().unwrap(),
+            self.cancellation_token.clone(),
         ));
         Ok(vec![task])
     }
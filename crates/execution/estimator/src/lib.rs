@@ -1,7 +1,9 @@
 mod evm;
 mod geth;
 mod hardhat;
+mod supervisor;
 
 pub use evm::EvmEstimatorActor;
 pub use geth::GethEstimatorActor;
 pub use hardhat::HardhatEstimatorActor;
+pub use supervisor::EstimatorSupervisorActor;
@@ -0,0 +1,253 @@
+use std::collections::VecDeque;
+
+use eyre::eyre;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, error, info, warn};
+
+use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, WorkerResult};
+use loom_core_actors_macros::Consumer;
+use loom_core_blockchain::{Blockchain, Strategy};
+use loom_evm_db::DatabaseLoomExt;
+use loom_types_events::{HealthEvent, MarketEvents, MessageHealthEvent, MessageSwapCompose, SwapComposeMessage};
+use revm::DatabaseRef;
+
+pub const DEFAULT_MAX_DIVERGENCES: usize = 3;
+pub const DEFAULT_DIVERGENCE_WINDOW_BLOCKS: u64 = 10;
+
+/// Switches to the fallback estimator after `max_divergences` estimator divergences within `window_blocks` blocks
+/// and back to the primary estimator once no divergence is left in the window
+struct EstimatorFailover {
+    max_divergences: usize,
+    window_blocks: u64,
+    divergence_blocks: VecDeque<u64>,
+    use_fallback: bool,
+}
+
+impl EstimatorFailover {
+    fn new(max_divergences: usize, window_blocks: u64) -> Self {
+        Self { max_divergences: max_divergences.max(1), window_blocks, divergence_blocks: VecDeque::new(), use_fallback: false }
+    }
+
+    fn prune(&mut self, block_number: u64) {
+        while self.divergence_blocks.front().is_some_and(|block| block + self.window_blocks <= block_number) {
+            self.divergence_blocks.pop_front();
+        }
+    }
+
+    /// Returns true if the fallback estimator was activated
+    fn record_divergence(&mut self, block_number: u64) -> bool {
+        self.divergence_blocks.push_back(block_number);
+        self.prune(block_number);
+        if !self.use_fallback && self.divergence_blocks.len() >= self.max_divergences {
+            self.use_fallback = true;
+            return true;
+        }
+        false
+    }
+
+    /// Returns true if the primary estimator was restored
+    fn on_block(&mut self, block_number: u64) -> bool {
+        self.prune(block_number);
+        if self.use_fallback && self.divergence_blocks.is_empty() {
+            self.use_fallback = false;
+            return true;
+        }
+        false
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn estimator_supervisor_worker<DB: Clone + Send + Sync + 'static>(
+    compose_channel_rx: Broadcaster<MessageSwapCompose<DB>>,
+    market_events_rx: Broadcaster<MarketEvents>,
+    health_monitor_channel_rx: Broadcaster<MessageHealthEvent>,
+    primary_channel_tx: Broadcaster<MessageSwapCompose<DB>>,
+    fallback_channel_tx: Broadcaster<MessageSwapCompose<DB>>,
+    max_divergences: usize,
+    divergence_window_blocks: u64,
+    cancellation_token: CancellationToken,
+) -> WorkerResult {
+    subscribe!(compose_channel_rx);
+    subscribe!(market_events_rx);
+    subscribe!(health_monitor_channel_rx);
+
+    let mut failover = EstimatorFailover::new(max_divergences, divergence_window_blocks);
+    let mut block_number = 0;
+
+    loop {
+        tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Estimator supervisor cancelled".to_string());
+            }
+            msg = market_events_rx.recv() => {
+                let market_event_msg: Result<MarketEvents, RecvError> = msg;
+                match market_event_msg {
+                    Ok(MarketEvents::BlockHeaderUpdate { block_number: header_block_number, .. }) => {
+                        block_number = header_block_number;
+                        if failover.on_block(block_number) {
+                            info!(block_number, "Estimator divergences stopped, switched back to primary estimator");
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Closed) => {
+                        error!("Market events channel closed");
+                        break Err(eyre!("MARKET_EVENTS_RX_CLOSED"));
+                    }
+                    Err(RecvError::Lagged(lag)) => {
+                        error!("Market events channel lagged by {} messages", lag);
+                    }
+                }
+            }
+            msg = health_monitor_channel_rx.recv() => {
+                let health_event_msg: Result<MessageHealthEvent, RecvError> = msg;
+                match health_event_msg {
+                    Ok(health_event) => {
+                        if let HealthEvent::EstimatorDivergence { tx_hash, estimated_gas, gas_used } = health_event.inner {
+                            debug!(%tx_hash, estimated_gas, gas_used, block_number, "Estimator divergence");
+                            if failover.record_divergence(block_number) {
+                                warn!(block_number, max_divergences, divergence_window_blocks, "Switched to fallback estimator");
+                            }
+                        }
+                    }
+                    Err(RecvError::Closed) => {
+                        error!("Health monitor channel closed");
+                        break Err(eyre!("HEALTH_MONITOR_RX_CLOSED"));
+                    }
+                    Err(RecvError::Lagged(lag)) => {
+                        error!("Health monitor channel lagged by {} messages", lag);
+                    }
+                }
+            }
+            msg = compose_channel_rx.recv() => {
+                let compose_request_msg: Result<MessageSwapCompose<DB>, RecvError> = msg;
+                match compose_request_msg {
+                    Ok(compose_request) => {
+                        if !matches!(compose_request.inner, SwapComposeMessage::Estimate(_)) {
+                            continue;
+                        }
+                        let estimator_channel_tx = if failover.use_fallback { &fallback_channel_tx } else { &primary_channel_tx };
+                        if let Err(error) = estimator_channel_tx.send(compose_request) {
+                            error!(%error, "estimator_channel_tx.send");
+                        }
+                    }
+                    Err(RecvError::Closed) => {
+                        error!("Swap compose channel closed");
+                        break Err(eyre!("SWAP_COMPOSE_RX_CLOSED"));
+                    }
+                    Err(RecvError::Lagged(lag)) => {
+                        error!("Swap compose channel lagged by {} messages", lag);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Routes swap estimate requests to the primary estimator, usually `EvmEstimatorActor`, and to the fallback estimator,
+/// usually `GethEstimatorActor`, while the primary estimator diverges from gas used on-chain.
+/// Divergences are reported by `StuffingTxMonitorActor` with `HealthEvent::EstimatorDivergence`.
+#[derive(Consumer)]
+pub struct EstimatorSupervisorActor<DB: Clone + Send + Sync + 'static> {
+    primary_estimator: Box<dyn Actor>,
+    fallback_estimator: Box<dyn Actor>,
+    primary_channel: Broadcaster<MessageSwapCompose<DB>>,
+    fallback_channel: Broadcaster<MessageSwapCompose<DB>>,
+    max_divergences: usize,
+    divergence_window_blocks: u64,
+    #[consumer]
+    compose_channel_rx: Option<Broadcaster<MessageSwapCompose<DB>>>,
+    #[consumer]
+    market_events_rx: Option<Broadcaster<MarketEvents>>,
+    #[consumer]
+    health_monitor_channel_rx: Option<Broadcaster<MessageHealthEvent>>,
+    cancellation_token: CancellationToken,
+}
+
+impl<DB: DatabaseRef + DatabaseLoomExt + Send + Sync + Clone + 'static> EstimatorSupervisorActor<DB> {
+    /// Estimators must have their producers set, estimate requests are consumed from the supervisor channels
+    pub fn new<PE, FE>(mut primary_estimator: PE, mut fallback_estimator: FE) -> Self
+    where
+        PE: Actor + Consumer<MessageSwapCompose<DB>>,
+        FE: Actor + Consumer<MessageSwapCompose<DB>>,
+    {
+        let primary_channel = Broadcaster::new(100);
+        let fallback_channel = Broadcaster::new(100);
+        primary_estimator.consume(primary_channel.clone());
+        fallback_estimator.consume(fallback_channel.clone());
+
+        Self {
+            primary_estimator: Box::new(primary_estimator),
+            fallback_estimator: Box::new(fallback_estimator),
+            primary_channel,
+            fallback_channel,
+            max_divergences: DEFAULT_MAX_DIVERGENCES,
+            divergence_window_blocks: DEFAULT_DIVERGENCE_WINDOW_BLOCKS,
+            compose_channel_rx: None,
+            market_events_rx: None,
+            health_monitor_channel_rx: None,
+            cancellation_token: CancellationToken::new(),
+        }
+    }
+
+    pub fn with_divergence_limit(self, max_divergences: usize, divergence_window_blocks: u64) -> Self {
+        Self { max_divergences, divergence_window_blocks, ..self }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
+    pub fn on_bc(self, bc: &Blockchain, strategy: &Strategy<DB>) -> Self {
+        Self {
+            compose_channel_rx: Some(strategy.swap_compose_channel()),
+            market_events_rx: Some(bc.market_events_channel()),
+            health_monitor_channel_rx: Some(bc.health_monitor_channel()),
+            ..self
+        }
+    }
+}
+
+impl<DB: DatabaseRef + DatabaseLoomExt + Send + Sync + Clone + 'static> Actor for EstimatorSupervisorActor<DB> {
+    fn start(&self) -> ActorResult {
+        let mut tasks = self.primary_estimator.start()?;
+        tasks.extend(self.fallback_estimator.start()?);
+
+        tasks.push(tokio::task::spawn(estimator_supervisor_worker(
+            self.compose_channel_rx.clone().unwrap(),
+            self.market_events_rx.clone().unwrap(),
+            self.health_monitor_channel_rx.clone().unwrap(),
+            self.primary_channel.clone(),
+            self.fallback_channel.clone(),
+            self.max_divergences,
+            self.divergence_window_blocks,
+            self.cancellation_token.clone(),
+        )));
+        Ok(tasks)
+    }
+
+    fn name(&self) -> &'static str {
+        "EstimatorSupervisorActor"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_estimator_failover() {
+        let mut failover = EstimatorFailover::new(2, 10);
+        assert!(!failover.record_divergence(100));
+        // First divergence is out of the window
+        assert!(!failover.record_divergence(110));
+        assert!(!failover.use_fallback);
+        assert!(failover.record_divergence(115));
+        assert!(failover.use_fallback);
+        assert!(!failover.record_divergence(116));
+
+        assert!(!failover.on_block(125));
+        assert!(failover.use_fallback);
+        assert!(failover.on_block(126));
+        assert!(!failover.use_fallback);
+    }
+}
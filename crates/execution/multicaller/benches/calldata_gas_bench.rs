@@ -0,0 +1,51 @@
+use alloy_primitives::utils::parse_units;
+use alloy_primitives::{Address, U256};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use loom_defi_address_book::TokenAddressEth;
+use loom_defi_pools::UniswapV2Pool;
+use loom_execution_multicaller::MulticallerSwapEncoder;
+use loom_types_entities::{PoolWrapper, Swap, SwapAmountType, SwapLine, SwapPath, Token};
+
+// WETH -> token 1 -> ... -> WETH on UniswapV2 pools
+fn swap_with_hops(hops: usize) -> Swap {
+    let mut token_addresses = vec![TokenAddressEth::WETH];
+    token_addresses.extend((1..hops).map(|i| Address::repeat_byte(i as u8)));
+    token_addresses.push(TokenAddressEth::WETH);
+
+    let tokens: Vec<Token> = token_addresses.iter().map(|address| Token::new(*address)).collect();
+    let pools: Vec<PoolWrapper> = token_addresses
+        .windows(2)
+        .enumerate()
+        .map(|(i, pair)| {
+            let (token0, token1) = if pair[0] < pair[1] { (pair[0], pair[1]) } else { (pair[1], pair[0]) };
+            let liquidity = U256::from(10).pow(U256::from(24));
+            UniswapV2Pool::new_with_data(Address::repeat_byte(0x80 + i as u8), token0, token1, Address::ZERO, liquidity, liquidity).into()
+        })
+        .collect();
+
+    let amount_in: U256 = parse_units("1", "ether").unwrap().get_absolute();
+    let amount_out: U256 = parse_units("1.01", "ether").unwrap().get_absolute();
+    Swap::BackrunSwapLine(SwapLine {
+        path: SwapPath::new(tokens, pools),
+        amount_in: SwapAmountType::Set(amount_in),
+        amount_out: SwapAmountType::Set(amount_out),
+        ..SwapLine::default()
+    })
+}
+
+pub fn bench_calldata_gas(c: &mut Criterion) {
+    let mut group = c.benchmark_group("calldata_gas");
+    let encoder = MulticallerSwapEncoder::default();
+
+    for hops in [3, 5] {
+        let swap = swap_with_hops(hops);
+        group.bench_function(format!("estimate_calldata_gas_cost_{}_hops", hops), |b| {
+            b.iter(|| encoder.estimate_calldata_gas_cost(black_box(&swap)).expect("Failed to encode swap"))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_calldata_gas);
+criterion_main!(benches);
@@ -9,6 +9,10 @@ use crate::{SwapLineEncoder, SwapStepEncoder, DEFAULT_VIRTUAL_ADDRESS};
 use loom_types_blockchain::MulticallerCalls;
 use loom_types_entities::Swap;
 
+// Transaction data gas per byte since EIP-2028
+const CALLDATA_ZERO_BYTE_GAS: u64 = 4;
+const CALLDATA_NONZERO_BYTE_GAS: u64 = 16;
+
 pub trait MulticallerEncoder {
     fn encode_calls(&self, calls: MulticallerCalls) -> Result<(Address, Bytes)>;
     fn add_internal_calls(&self, opcodes: MulticallerCalls, inside_opcodes: MulticallerCalls) -> Result<MulticallerCalls>;
@@ -19,11 +23,12 @@ pub trait MulticallerEncoder {
 pub struct MulticallerSwapEncoder {
     pub multicaller_address: Address,
     pub swap_step_encoder: SwapStepEncoder,
+    pub emergency_stop: Option<Address>,
 }
 
 impl MulticallerSwapEncoder {
     pub fn new(multicaller_address: Address, swap_step_encoder: SwapStepEncoder) -> Self {
-        Self { multicaller_address, swap_step_encoder }
+        Self { multicaller_address, swap_step_encoder, emergency_stop: None }
     }
 
     pub fn default_with_address(multicaller_address: Address) -> Self {
@@ -34,12 +39,27 @@ impl MulticallerSwapEncoder {
 
         let swap_step_encoder = SwapStepEncoder::new(multicaller_address, swap_line_encoder);
 
-        Self { multicaller_address, swap_step_encoder }
+        Self { multicaller_address, swap_step_encoder, emergency_stop: None }
+    }
+
+    /// Prepend a check of the emergency stop contract to every encoded swap, the multicaller reverts when the contract is stopped
+    pub fn with_emergency_stop(self, stop_contract: Address) -> Self {
+        Self { emergency_stop: Some(stop_contract), ..self }
     }
 
     pub fn get_contract_address(&self) -> Address {
         self.multicaller_address
     }
+
+    /// Gas paid for the multicaller calldata of `swap`, it is charged on top of the execution gas
+    pub fn estimate_calldata_gas_cost(&self, swap: &Swap) -> Result<u64> {
+        let (_, call_data) = self.encode_calls(self.make_calls(swap)?)?;
+        Ok(calldata_gas_cost(&call_data))
+    }
+}
+
+fn calldata_gas_cost(data: &[u8]) -> u64 {
+    data.iter().map(|byte| if *byte == 0 { CALLDATA_ZERO_BYTE_GAS } else { CALLDATA_NONZERO_BYTE_GAS }).sum()
 }
 
 impl MulticallerEncoder for MulticallerSwapEncoder {
@@ -58,6 +78,9 @@ impl MulticallerEncoder for MulticallerSwapEncoder {
                 self.swap_step_encoder.encode_swap_steps(&swap_step_0, &swap_step_1)
             }
             Swap::BackrunSwapSteps((swap_step_0, swap_step_1)) => self.swap_step_encoder.encode_swap_steps(swap_step_0, swap_step_1),
+            Swap::FlashLoanSwapLine { provider, asset, amount, inner } => {
+                self.swap_step_encoder.encode_flash_loan_swap_line(provider, *asset, *amount, inner)
+            }
             Swap::Multiple(swap_vec) => {
                 if swap_vec.len() == 1 {
                     self.make_calls(&swap_vec[0])
@@ -82,3 +105,54 @@ impl Default for MulticallerSwapEncoder {
         MulticallerSwapEncoder::default_with_address(DEFAULT_VIRTUAL_ADDRESS)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::U256;
+    use alloy_sol_types::SolCall;
+    use loom_defi_abi::balancer::IVault;
+    use loom_defi_address_book::TokenAddressEth;
+    use loom_defi_pools::UniswapV2Pool;
+    use loom_types_entities::{FlashLoanProvider, PoolWrapper, SwapAmountType, SwapLine, SwapPath, Token};
+
+    #[test]
+    fn test_calldata_gas_cost() {
+        assert_eq!(calldata_gas_cost(&[]), 0);
+        assert_eq!(calldata_gas_cost(&[0, 0, 1, 0xff]), 2 * CALLDATA_ZERO_BYTE_GAS + 2 * CALLDATA_NONZERO_BYTE_GAS);
+    }
+
+    #[test]
+    fn test_balancer_flash_loan_swap_line() {
+        let token = Address::repeat_byte(1);
+        let (token0, token1) = if TokenAddressEth::WETH < token { (TokenAddressEth::WETH, token) } else { (token, TokenAddressEth::WETH) };
+        let liquidity = U256::from(10).pow(U256::from(24));
+        let pools: Vec<PoolWrapper> = vec![
+            UniswapV2Pool::new_with_data(Address::repeat_byte(0x80), token0, token1, Address::ZERO, liquidity, liquidity).into(),
+            UniswapV2Pool::new_with_data(Address::repeat_byte(0x81), token0, token1, Address::ZERO, liquidity, liquidity).into(),
+        ];
+        let tokens = vec![Token::new(TokenAddressEth::WETH), Token::new(token), Token::new(TokenAddressEth::WETH)];
+        let amount_in = U256::from(10).pow(U256::from(18));
+        let swap_line = SwapLine {
+            path: SwapPath::new(tokens, pools),
+            amount_in: SwapAmountType::Set(amount_in),
+            amount_out: SwapAmountType::Set(amount_in + U256::from(1000)),
+            ..SwapLine::default()
+        };
+
+        let encoder = MulticallerSwapEncoder::default_with_address(Address::repeat_byte(0x55));
+        let swap = Swap::new_flash_loan(FlashLoanProvider::BalancerV2, swap_line).unwrap();
+        let calls = encoder.make_calls(&swap).unwrap();
+
+        assert_eq!(calls.len(), 1);
+        let flash_call = calls.get(0).unwrap();
+        assert_eq!(flash_call.to, "0xBA12222222228d8Ba445958a75a0704d566BF2C8".parse::<Address>().unwrap());
+        assert_eq!(flash_call.call_data[..4], IVault::flashLoanCall::SELECTOR);
+
+        let flash_loan = IVault::flashLoanCall::abi_decode(&flash_call.call_data, false).unwrap();
+        assert_eq!(flash_loan.recipient, Address::repeat_byte(0x55));
+        assert_eq!(flash_loan.tokens, vec![TokenAddressEth::WETH]);
+        assert_eq!(flash_loan.amounts, vec![amount_in]);
+        assert!(!flash_loan.userData.is_empty());
+    }
+}
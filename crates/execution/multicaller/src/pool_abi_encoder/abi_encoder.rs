@@ -1,6 +1,7 @@
 use crate::pool_abi_encoder::pools::{
-    CurveProtocolAbiEncoder, MaverickProtocolAbiEncoder, PancakeV3ProtocolAbiEncoder, UniswapV2ProtocolAbiEncoder,
-    UniswapV3ProtocolAbiEncoder,
+    BalancerV2ProtocolAbiEncoder, CurveProtocolAbiEncoder, Erc4626VaultProtocolAbiEncoder, MaverickProtocolAbiEncoder,
+    MaverickV2ProtocolAbiEncoder, PancakeV3ProtocolAbiEncoder, UniswapV2ProtocolAbiEncoder, UniswapV3ProtocolAbiEncoder,
+    UniswapV4ProtocolAbiEncoder,
 };
 use crate::pool_abi_encoder::ProtocolAbiSwapEncoderTrait;
 use alloy_primitives::{Address, Bytes, U256};
@@ -20,8 +21,12 @@ impl Default for ProtocolABIEncoderV2 {
             (PoolClass::UniswapV3, Arc::new(UniswapV3ProtocolAbiEncoder) as Arc<dyn ProtocolAbiSwapEncoderTrait>),
             (PoolClass::UniswapV2, Arc::new(UniswapV2ProtocolAbiEncoder) as Arc<dyn ProtocolAbiSwapEncoderTrait>),
             (PoolClass::Maverick, Arc::new(MaverickProtocolAbiEncoder) as Arc<dyn ProtocolAbiSwapEncoderTrait>),
+            (PoolClass::MaverickV2, Arc::new(MaverickV2ProtocolAbiEncoder) as Arc<dyn ProtocolAbiSwapEncoderTrait>),
             (PoolClass::PancakeV3, Arc::new(PancakeV3ProtocolAbiEncoder) as Arc<dyn ProtocolAbiSwapEncoderTrait>),
             (PoolClass::Curve, Arc::new(CurveProtocolAbiEncoder) as Arc<dyn ProtocolAbiSwapEncoderTrait>),
+            (PoolClass::Erc4626Vault, Arc::new(Erc4626VaultProtocolAbiEncoder) as Arc<dyn ProtocolAbiSwapEncoderTrait>),
+            (PoolClass::BalancerV2, Arc::new(BalancerV2ProtocolAbiEncoder) as Arc<dyn ProtocolAbiSwapEncoderTrait>),
+            (PoolClass::UniswapV4, Arc::new(UniswapV4ProtocolAbiEncoder) as Arc<dyn ProtocolAbiSwapEncoderTrait>),
         ]
         .into_iter()
         .collect();
@@ -115,7 +120,7 @@ mod tests {
     #[test]
     fn test_default() {
         let abi_encoder_v2 = ProtocolABIEncoderV2::default();
-        assert_eq!(abi_encoder_v2.pool_classes.len(), 5);
+        assert_eq!(abi_encoder_v2.pool_classes.len(), 9);
     }
 
     #[test]
@@ -6,6 +6,9 @@ mod abi_encoder;
 
 mod pools;
 
+#[cfg(test)]
+pub(crate) use pools::MaverickV2ProtocolAbiEncoder;
+
 pub trait ProtocolAbiSwapEncoderTrait: Send + Sync + 'static {
     fn encode_swap_in_amount_provided(
         &self,
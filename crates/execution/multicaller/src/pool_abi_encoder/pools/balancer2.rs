@@ -0,0 +1,101 @@
+use crate::pool_abi_encoder::ProtocolAbiSwapEncoderTrait;
+use alloy_primitives::{Address, Bytes, B256, U256};
+use alloy_sol_types::SolCall;
+use eyre::eyre;
+use loom_defi_abi::balancer::IVault;
+use loom_types_entities::{Pool, PoolId};
+
+/// Single swaps through the Balancer V2 vault, the recipient is also the sender the vault pulls the input from
+pub struct BalancerV2ProtocolAbiEncoder;
+
+impl BalancerV2ProtocolAbiEncoder {
+    fn get_pool_id(pool: &dyn Pool) -> eyre::Result<B256> {
+        match pool.get_pool_id() {
+            PoolId::Bytes32(pool_id) => Ok(pool_id),
+            PoolId::Address(_) => Err(eyre!("NOT_BALANCER_POOL_ID")),
+        }
+    }
+
+    fn encode_swap(
+        pool: &dyn Pool,
+        token_from_address: Address,
+        token_to_address: Address,
+        amount: U256,
+        kind: IVault::SwapKind,
+        recipient: Address,
+    ) -> eyre::Result<Bytes> {
+        // No limit on the calculated amount, the profit is checked by the multicaller
+        let limit = match kind {
+            IVault::SwapKind::GIVEN_OUT => U256::MAX,
+            _ => U256::ZERO,
+        };
+
+        let swap_call = IVault::swapCall {
+            singleSwap: IVault::SingleSwap {
+                poolId: Self::get_pool_id(pool)?,
+                kind,
+                assetIn: token_from_address,
+                assetOut: token_to_address,
+                amount,
+                userData: Bytes::new(),
+            },
+            funds: IVault::FundManagement { sender: recipient, fromInternalBalance: false, recipient, toInternalBalance: false },
+            limit,
+            deadline: U256::MAX,
+        };
+
+        Ok(Bytes::from(swap_call.abi_encode()))
+    }
+}
+
+impl ProtocolAbiSwapEncoderTrait for BalancerV2ProtocolAbiEncoder {
+    fn encode_swap_in_amount_provided(
+        &self,
+        pool: &dyn Pool,
+        token_from_address: Address,
+        token_to_address: Address,
+        amount: U256,
+        recipient: Address,
+        _payload: Bytes,
+    ) -> eyre::Result<Bytes> {
+        Self::encode_swap(pool, token_from_address, token_to_address, amount, IVault::SwapKind::GIVEN_IN, recipient)
+    }
+
+    fn encode_swap_out_amount_provided(
+        &self,
+        pool: &dyn Pool,
+        token_from_address: Address,
+        token_to_address: Address,
+        amount: U256,
+        recipient: Address,
+        _payload: Bytes,
+    ) -> eyre::Result<Bytes> {
+        Self::encode_swap(pool, token_from_address, token_to_address, amount, IVault::SwapKind::GIVEN_OUT, recipient)
+    }
+
+    // singleSwap.amount, the single swap struct is encoded after the funds, limit and deadline
+    fn swap_in_amount_offset(&self, _pool: &dyn Pool, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0x164)
+    }
+
+    fn swap_out_amount_offset(&self, _pool: &dyn Pool, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0x164)
+    }
+
+    // swap returns the calculated amount only
+    fn swap_out_amount_return_offset(&self, _pool: &dyn Pool, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0x0)
+    }
+
+    fn swap_in_amount_return_offset(&self, _pool: &dyn Pool, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0x0)
+    }
+
+    fn swap_out_amount_return_script(&self, _pool: &dyn Pool, _token_from_address: Address, _token_to_address: Address) -> Option<Bytes> {
+        None
+    }
+
+    fn swap_in_amount_return_script(&self, _pool: &dyn Pool, _token_from_address: Address, _token_to_address: Address) -> Option<Bytes> {
+        None
+    }
+}
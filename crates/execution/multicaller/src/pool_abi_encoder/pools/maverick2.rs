@@ -0,0 +1,86 @@
+use crate::pool_abi_encoder::ProtocolAbiSwapEncoderTrait;
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_sol_types::SolCall;
+use loom_defi_abi::maverick2::{IMaverickV2Pool, SwapParams};
+use loom_types_entities::Pool;
+
+/// Swaps are funded by transferring the input to the pool before the call, an empty `data` skips the swap callback
+pub struct MaverickV2ProtocolAbiEncoder;
+
+impl MaverickV2ProtocolAbiEncoder {
+    pub fn get_token_a_in(token_address_from: &Address, token_address_to: &Address) -> bool {
+        token_address_from < token_address_to
+    }
+
+    /// The furthest tick the swap is allowed to reach, no limit
+    pub fn get_tick_limit(token_a_in: bool) -> i32 {
+        if token_a_in {
+            i32::MAX
+        } else {
+            i32::MIN
+        }
+    }
+
+    fn encode_swap(token_from_address: Address, token_to_address: Address, amount: U256, exact_output: bool, recipient: Address) -> Bytes {
+        let token_a_in = Self::get_token_a_in(&token_from_address, &token_to_address);
+
+        let swap_call = IMaverickV2Pool::swapCall {
+            recipient,
+            params: SwapParams { amount, tokenAIn: token_a_in, exactOutput: exact_output, tickLimit: Self::get_tick_limit(token_a_in) },
+            data: Bytes::new(),
+        };
+
+        Bytes::from(swap_call.abi_encode())
+    }
+}
+
+impl ProtocolAbiSwapEncoderTrait for MaverickV2ProtocolAbiEncoder {
+    fn encode_swap_in_amount_provided(
+        &self,
+        _pool: &dyn Pool,
+        token_from_address: Address,
+        token_to_address: Address,
+        amount: U256,
+        recipient: Address,
+        _payload: Bytes,
+    ) -> eyre::Result<Bytes> {
+        Ok(Self::encode_swap(token_from_address, token_to_address, amount, false, recipient))
+    }
+
+    fn encode_swap_out_amount_provided(
+        &self,
+        _pool: &dyn Pool,
+        token_from_address: Address,
+        token_to_address: Address,
+        amount: U256,
+        recipient: Address,
+        _payload: Bytes,
+    ) -> eyre::Result<Bytes> {
+        Ok(Self::encode_swap(token_from_address, token_to_address, amount, true, recipient))
+    }
+
+    fn swap_in_amount_offset(&self, _pool: &dyn Pool, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0x24)
+    }
+
+    fn swap_out_amount_offset(&self, _pool: &dyn Pool, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0x24)
+    }
+
+    // swap returns (amountIn, amountOut)
+    fn swap_out_amount_return_offset(&self, _pool: &dyn Pool, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0x0)
+    }
+
+    fn swap_in_amount_return_offset(&self, _pool: &dyn Pool, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0x20)
+    }
+
+    fn swap_out_amount_return_script(&self, _pool: &dyn Pool, _token_from_address: Address, _token_to_address: Address) -> Option<Bytes> {
+        None
+    }
+
+    fn swap_in_amount_return_script(&self, _pool: &dyn Pool, _token_from_address: Address, _token_to_address: Address) -> Option<Bytes> {
+        None
+    }
+}
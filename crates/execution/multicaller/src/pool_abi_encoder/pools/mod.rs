@@ -1,10 +1,18 @@
+pub use balancer2::BalancerV2ProtocolAbiEncoder;
 pub use curve::CurveProtocolAbiEncoder;
+pub use erc4626::Erc4626VaultProtocolAbiEncoder;
 pub use maverick::MaverickProtocolAbiEncoder;
+pub use maverick2::MaverickV2ProtocolAbiEncoder;
 pub use pancake3::PancakeV3ProtocolAbiEncoder;
 pub use uniswapv2::UniswapV2ProtocolAbiEncoder;
 pub use uniswapv3::UniswapV3ProtocolAbiEncoder;
+pub use uniswapv4::UniswapV4ProtocolAbiEncoder;
+mod balancer2;
 mod curve;
+mod erc4626;
 mod maverick;
+mod maverick2;
 mod pancake3;
 mod uniswapv2;
 mod uniswapv3;
+mod uniswapv4;
@@ -0,0 +1,188 @@
+use crate::opcodes_helpers::OpcodesHelpers;
+use crate::pool_abi_encoder::ProtocolAbiSwapEncoderTrait;
+use crate::pool_opcodes_encoder::swap_opcodes_encoders::MulticallerOpcodesPayload;
+use crate::pool_opcodes_encoder::SwapOpcodesEncoderTrait;
+use alloy_primitives::{Address, Bytes, U256};
+use eyre::{eyre, OptionExt};
+use loom_defi_abi::AbiEncoderHelper;
+use loom_types_blockchain::{MulticallerCall, MulticallerCalls};
+use loom_types_entities::{Pool, PreswapRequirement, SwapAmountType};
+use tracing::trace;
+
+/// Balancer V2 swaps through the vault, the multicaller approves the vault and the output is sent back to the multicaller
+pub struct BalancerV2SwapOpcodesEncoder;
+
+impl SwapOpcodesEncoderTrait for BalancerV2SwapOpcodesEncoder {
+    fn encode_swap_in_amount_provided(
+        &self,
+        swap_opcodes: &mut MulticallerCalls,
+        abi_encoder: &dyn ProtocolAbiSwapEncoderTrait,
+        token_from_address: Address,
+        token_to_address: Address,
+        amount_in: SwapAmountType,
+        cur_pool: &dyn Pool,
+        next_pool: Option<&dyn Pool>,
+        _payload: MulticallerOpcodesPayload,
+        multicaller_address: Address,
+    ) -> eyre::Result<()> {
+        // Balances of the pool are held by the vault
+        let vault_address = cur_pool.get_state_addresses().first().copied().ok_or_eyre("NO_VAULT_ADDRESS")?;
+
+        trace!(
+            "balancer v2 approve and swap for pool={:?} vault={:?}, amount={:?} from {} to {}",
+            cur_pool.get_address(),
+            vault_address,
+            amount_in,
+            token_from_address,
+            token_to_address
+        );
+
+        let approve_opcode = MulticallerCall::new_call(
+            token_from_address,
+            &AbiEncoderHelper::encode_erc20_approve(vault_address, amount_in.unwrap_or_default()),
+        );
+
+        let mut swap_opcode = MulticallerCall::new_call(
+            vault_address,
+            &abi_encoder.encode_swap_in_amount_provided(
+                cur_pool,
+                token_from_address,
+                token_to_address,
+                amount_in.unwrap_or_default(),
+                multicaller_address,
+                Bytes::new(),
+            )?,
+        );
+        swap_opcode.set_return_stack(
+            true,
+            0,
+            abi_encoder.swap_in_amount_return_offset(cur_pool, token_from_address, token_to_address).ok_or_eyre("NO_OFFSET")?,
+            0x20,
+        );
+        let swap_offset = abi_encoder.swap_in_amount_offset(cur_pool, token_from_address, token_to_address).ok_or_eyre("NO_OFFSET")?;
+
+        swap_opcodes.merge(OpcodesHelpers::build_multiple_stack(
+            amount_in,
+            vec![(approve_opcode, 0x24, 0x20), (swap_opcode, swap_offset, 0x20)],
+            Some(token_from_address),
+        )?);
+
+        if let Some(PreswapRequirement::Transfer(next_pool_address)) = next_pool.map(|next_pool| next_pool.preswap_requirement()) {
+            let mut transfer_opcode =
+                MulticallerCall::new_call(token_to_address, &AbiEncoderHelper::encode_erc20_transfer(next_pool_address, U256::ZERO));
+            transfer_opcode.set_call_stack(true, 0, 0x24, 0x20);
+            swap_opcodes.add(transfer_opcode);
+        }
+
+        Ok(())
+    }
+
+    fn encode_swap_out_amount_provided(
+        &self,
+        _swap_opcodes: &mut MulticallerCalls,
+        _abi_encoder: &dyn ProtocolAbiSwapEncoderTrait,
+        _token_from_address: Address,
+        _token_to_address: Address,
+        _amount_out: SwapAmountType,
+        _cur_pool: &dyn Pool,
+        _next_pool: Option<&dyn Pool>,
+        _payload: MulticallerOpcodesPayload,
+        _multicaller_address: Address,
+    ) -> eyre::Result<()> {
+        Err(eyre!("NOT_IMPLEMENTED"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pool_abi_encoder::ProtocolABIEncoderV2;
+    use alloy_primitives::B256;
+    use alloy_sol_types::SolCall;
+    use loom_defi_abi::balancer::IVault;
+    use loom_defi_address_book::FactoryAddress;
+    use loom_defi_pools::{BalancerPool, BalancerPoolKind, BalancerPoolParams, UniswapV2Pool};
+
+    fn balancer_pool(token_a: Address, token_b: Address) -> BalancerPool {
+        let params = BalancerPoolParams {
+            kind: BalancerPoolKind::Weighted { weights: vec![U256::from(5) * U256::from(10).pow(U256::from(17)); 2] },
+            scaling_factors: vec![U256::from(10).pow(U256::from(18)); 2],
+            fee: U256::from(3) * U256::from(10).pow(U256::from(15)),
+        };
+        BalancerPool::new(Address::repeat_byte(10), B256::repeat_byte(7), vec![token_a, token_b], vec![U256::ZERO; 2], params)
+    }
+
+    #[test]
+    fn test_encode_swap_in_amount_provided() {
+        let (token_a, token_b) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let pool = balancer_pool(token_a, token_b);
+        let multicaller_address = Address::repeat_byte(0x55);
+        let amount_in = U256::from(1000);
+
+        let mut swap_opcodes = MulticallerCalls::new();
+        BalancerV2SwapOpcodesEncoder
+            .encode_swap_in_amount_provided(
+                &mut swap_opcodes,
+                &ProtocolABIEncoderV2::default(),
+                token_a,
+                token_b,
+                SwapAmountType::Set(amount_in),
+                &pool,
+                None,
+                MulticallerOpcodesPayload::Empty,
+                multicaller_address,
+            )
+            .unwrap();
+
+        let calls = swap_opcodes.opcodes_vec;
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].to, token_a);
+        assert_eq!(calls[0].call_data, AbiEncoderHelper::encode_erc20_approve(FactoryAddress::BALANCER_V2_VAULT, amount_in));
+
+        assert_eq!(calls[1].to, FactoryAddress::BALANCER_V2_VAULT);
+        let swap_call = IVault::swapCall::abi_decode(&calls[1].call_data, true).unwrap();
+        assert_eq!(swap_call.singleSwap.poolId, B256::repeat_byte(7));
+        assert_eq!(swap_call.singleSwap.kind, IVault::SwapKind::GIVEN_IN);
+        assert_eq!((swap_call.singleSwap.assetIn, swap_call.singleSwap.assetOut), (token_a, token_b));
+        assert_eq!(swap_call.singleSwap.amount, amount_in);
+        assert_eq!((swap_call.funds.sender, swap_call.funds.recipient), (multicaller_address, multicaller_address));
+        assert_eq!(swap_call.limit, U256::ZERO);
+
+        // The amount offset used for stack amounts points to singleSwap.amount
+        assert_eq!(U256::from_be_slice(&calls[1].call_data[0x164..0x184]), amount_in);
+    }
+
+    #[test]
+    fn test_encode_swap_in_amount_from_stack() {
+        let (token_a, token_b) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let pool = balancer_pool(token_a, token_b);
+        let next_pool = UniswapV2Pool::new(Address::repeat_byte(11));
+
+        let mut swap_opcodes = MulticallerCalls::new();
+        BalancerV2SwapOpcodesEncoder
+            .encode_swap_in_amount_provided(
+                &mut swap_opcodes,
+                &ProtocolABIEncoderV2::default(),
+                token_b,
+                token_a,
+                SwapAmountType::RelativeStack(0),
+                &pool,
+                Some(&next_pool),
+                MulticallerOpcodesPayload::Empty,
+                Address::repeat_byte(0x55),
+            )
+            .unwrap();
+
+        // The approval and the swap read the output of the previous swap, the swap output is transferred to the next pool
+        let calls = swap_opcodes.opcodes_vec;
+        assert_eq!(calls.len(), 3);
+        let approve_stack = calls[0].call_stack.clone().unwrap();
+        let swap_stack = calls[1].call_stack.clone().unwrap();
+        assert_eq!((approve_stack.stack_offset, approve_stack.data_offset), (0, 0x24));
+        assert_eq!((swap_stack.stack_offset, swap_stack.data_offset), (0, 0x164));
+        assert!(calls[0].return_stack.is_none());
+        assert_eq!(calls[1].return_stack.clone().unwrap().data_offset, 0x0);
+        assert_eq!(calls[2].to, token_a);
+        assert_eq!(calls[2].call_data, AbiEncoderHelper::encode_erc20_transfer(next_pool.get_address(), U256::ZERO));
+    }
+}
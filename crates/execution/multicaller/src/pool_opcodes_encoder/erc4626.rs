@@ -0,0 +1,168 @@
+use crate::opcodes_helpers::OpcodesHelpers;
+use crate::pool_abi_encoder::ProtocolAbiSwapEncoderTrait;
+use crate::pool_opcodes_encoder::swap_opcodes_encoders::MulticallerOpcodesPayload;
+use crate::pool_opcodes_encoder::SwapOpcodesEncoderTrait;
+use alloy_primitives::{Address, Bytes, U256};
+use eyre::{eyre, OptionExt};
+use loom_defi_abi::AbiEncoderHelper;
+use loom_types_blockchain::{MulticallerCall, MulticallerCalls};
+use loom_types_entities::{Pool, PreswapRequirement, SwapAmountType};
+use tracing::trace;
+
+/// ERC-4626 vault swaps, the multicaller approves and deposits the assets or redeems its shares
+pub struct Erc4626VaultSwapOpcodesEncoder;
+
+impl SwapOpcodesEncoderTrait for Erc4626VaultSwapOpcodesEncoder {
+    fn encode_swap_in_amount_provided(
+        &self,
+        swap_opcodes: &mut MulticallerCalls,
+        abi_encoder: &dyn ProtocolAbiSwapEncoderTrait,
+        token_from_address: Address,
+        token_to_address: Address,
+        amount_in: SwapAmountType,
+        cur_pool: &dyn Pool,
+        next_pool: Option<&dyn Pool>,
+        _payload: MulticallerOpcodesPayload,
+        multicaller_address: Address,
+    ) -> eyre::Result<()> {
+        let vault_address = cur_pool.get_address();
+        let is_deposit = token_to_address == vault_address;
+
+        trace!(
+            "erc4626 vault deposit={} for vault={:?}, amount={:?} from {} to {}",
+            is_deposit,
+            vault_address,
+            amount_in,
+            token_from_address,
+            token_to_address
+        );
+
+        let mut swap_opcode = MulticallerCall::new_call(
+            vault_address,
+            &abi_encoder.encode_swap_in_amount_provided(
+                cur_pool,
+                token_from_address,
+                token_to_address,
+                amount_in.unwrap_or_default(),
+                multicaller_address,
+                Bytes::new(),
+            )?,
+        );
+        swap_opcode.set_return_stack(
+            true,
+            0,
+            abi_encoder.swap_in_amount_return_offset(cur_pool, token_from_address, token_to_address).ok_or_eyre("NO_OFFSET")?,
+            0x20,
+        );
+        let swap_offset = abi_encoder.swap_in_amount_offset(cur_pool, token_from_address, token_to_address).ok_or_eyre("NO_OFFSET")?;
+
+        let mut opcodes = Vec::new();
+        if is_deposit {
+            let approve_opcode = MulticallerCall::new_call(
+                token_from_address,
+                &AbiEncoderHelper::encode_erc20_approve(vault_address, amount_in.unwrap_or_default()),
+            );
+            opcodes.push((approve_opcode, 0x24, 0x20));
+        }
+        opcodes.push((swap_opcode, swap_offset, 0x20));
+
+        swap_opcodes.merge(OpcodesHelpers::build_multiple_stack(amount_in, opcodes, Some(token_from_address))?);
+
+        if let Some(PreswapRequirement::Transfer(next_pool_address)) = next_pool.map(|next_pool| next_pool.preswap_requirement()) {
+            let mut transfer_opcode =
+                MulticallerCall::new_call(token_to_address, &AbiEncoderHelper::encode_erc20_transfer(next_pool_address, U256::ZERO));
+            transfer_opcode.set_call_stack(true, 0, 0x24, 0x20);
+            swap_opcodes.add(transfer_opcode);
+        }
+
+        Ok(())
+    }
+
+    fn encode_swap_out_amount_provided(
+        &self,
+        _swap_opcodes: &mut MulticallerCalls,
+        _abi_encoder: &dyn ProtocolAbiSwapEncoderTrait,
+        _token_from_address: Address,
+        _token_to_address: Address,
+        _amount_out: SwapAmountType,
+        _cur_pool: &dyn Pool,
+        _next_pool: Option<&dyn Pool>,
+        _payload: MulticallerOpcodesPayload,
+        _multicaller_address: Address,
+    ) -> eyre::Result<()> {
+        Err(eyre!("NOT_IMPLEMENTED"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pool_abi_encoder::ProtocolABIEncoderV2;
+    use alloy_sol_types::SolCall;
+    use loom_defi_abi::IERC4626;
+    use loom_defi_pools::{Erc4626VaultPool, UniswapV2Pool};
+
+    #[test]
+    fn test_encode_deposit() {
+        let (share_token, asset_token) = (Address::repeat_byte(10), Address::repeat_byte(1));
+        let pool = Erc4626VaultPool::new(share_token, asset_token, U256::from(10).pow(U256::from(18)));
+        let multicaller_address = Address::repeat_byte(0x55);
+        let amount_in = U256::from(1000);
+
+        let mut swap_opcodes = MulticallerCalls::new();
+        Erc4626VaultSwapOpcodesEncoder
+            .encode_swap_in_amount_provided(
+                &mut swap_opcodes,
+                &ProtocolABIEncoderV2::default(),
+                asset_token,
+                share_token,
+                SwapAmountType::Set(amount_in),
+                &pool,
+                None,
+                MulticallerOpcodesPayload::Empty,
+                multicaller_address,
+            )
+            .unwrap();
+
+        let calls = swap_opcodes.opcodes_vec;
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].to, asset_token);
+        assert_eq!(calls[0].call_data, AbiEncoderHelper::encode_erc20_approve(share_token, amount_in));
+        assert_eq!(calls[1].to, share_token);
+        let deposit_call = IERC4626::depositCall::abi_decode(&calls[1].call_data, true).unwrap();
+        assert_eq!((deposit_call.assets, deposit_call.receiver), (amount_in, multicaller_address));
+        assert_eq!(calls[1].return_stack.clone().unwrap().data_offset, 0x0);
+    }
+
+    #[test]
+    fn test_encode_redeem_from_stack() {
+        let (share_token, asset_token) = (Address::repeat_byte(10), Address::repeat_byte(1));
+        let pool = Erc4626VaultPool::new(share_token, asset_token, U256::from(10).pow(U256::from(18)));
+        let next_pool = UniswapV2Pool::new(Address::repeat_byte(11));
+        let multicaller_address = Address::repeat_byte(0x55);
+
+        let mut swap_opcodes = MulticallerCalls::new();
+        Erc4626VaultSwapOpcodesEncoder
+            .encode_swap_in_amount_provided(
+                &mut swap_opcodes,
+                &ProtocolABIEncoderV2::default(),
+                share_token,
+                asset_token,
+                SwapAmountType::RelativeStack(0),
+                &pool,
+                Some(&next_pool),
+                MulticallerOpcodesPayload::Empty,
+                multicaller_address,
+            )
+            .unwrap();
+
+        // No approval to redeem, the redeemed assets are transferred to the next pool
+        let calls = swap_opcodes.opcodes_vec;
+        assert_eq!(calls.len(), 2);
+        let redeem_call = IERC4626::redeemCall::abi_decode(&calls[0].call_data, true).unwrap();
+        assert_eq!((redeem_call.receiver, redeem_call.owner), (multicaller_address, multicaller_address));
+        assert_eq!(calls[0].call_stack.clone().unwrap().data_offset, 0x04);
+        assert_eq!(calls[1].to, asset_token);
+        assert_eq!(calls[1].call_data, AbiEncoderHelper::encode_erc20_transfer(next_pool.get_address(), U256::ZERO));
+    }
+}
@@ -0,0 +1,164 @@
+use crate::opcodes_helpers::OpcodesHelpers;
+use crate::pool_abi_encoder::ProtocolAbiSwapEncoderTrait;
+use crate::pool_opcodes_encoder::swap_opcodes_encoders::MulticallerOpcodesPayload;
+use crate::pool_opcodes_encoder::SwapOpcodesEncoderTrait;
+use alloy_primitives::{Address, U256};
+use eyre::{eyre, OptionExt};
+use loom_defi_abi::AbiEncoderHelper;
+use loom_types_blockchain::{MulticallerCall, MulticallerCalls};
+use loom_types_entities::{Pool, SwapAmountType};
+use tracing::trace;
+
+/// Maverick V2 swaps without the callback, the multicaller transfers the input to the pool right before the swap call
+pub struct MaverickV2SwapOpcodesEncoder;
+
+impl SwapOpcodesEncoderTrait for MaverickV2SwapOpcodesEncoder {
+    fn encode_swap_in_amount_provided(
+        &self,
+        swap_opcodes: &mut MulticallerCalls,
+        abi_encoder: &dyn ProtocolAbiSwapEncoderTrait,
+        token_from_address: Address,
+        token_to_address: Address,
+        amount_in: SwapAmountType,
+        cur_pool: &dyn Pool,
+        next_pool: Option<&dyn Pool>,
+        _payload: MulticallerOpcodesPayload,
+        multicaller_address: Address,
+    ) -> eyre::Result<()> {
+        let swap_to = next_pool.and_then(|next_pool| next_pool.preswap_requirement().address()).unwrap_or(multicaller_address);
+
+        trace!(
+            "maverick v2 transfer and swap for pool={:?}, amount={:?} from {} to {}",
+            cur_pool.get_address(),
+            amount_in,
+            token_from_address,
+            token_to_address
+        );
+
+        let transfer_opcode = MulticallerCall::new_call(
+            token_from_address,
+            &AbiEncoderHelper::encode_erc20_transfer(cur_pool.get_address(), amount_in.unwrap_or_default()),
+        );
+
+        let mut swap_opcode = MulticallerCall::new_call(
+            cur_pool.get_address(),
+            &abi_encoder.encode_swap_in_amount_provided(
+                cur_pool,
+                token_from_address,
+                token_to_address,
+                amount_in.unwrap_or_default(),
+                swap_to,
+                Default::default(),
+            )?,
+        );
+
+        swap_opcode.set_return_stack(
+            true,
+            0,
+            abi_encoder.swap_in_amount_return_offset(cur_pool, token_from_address, token_to_address).ok_or_eyre("NO_OFFSET")?,
+            0x20,
+        );
+
+        let swap_offset = abi_encoder.swap_in_amount_offset(cur_pool, token_from_address, token_to_address).ok_or_eyre("NO_OFFSET")?;
+
+        // Both calls take the input amount from the same stack value, the transfer does not push to the stack
+        swap_opcodes.merge(OpcodesHelpers::build_multiple_stack(
+            amount_in,
+            vec![(transfer_opcode, 0x24, 0x20), (swap_opcode, swap_offset, 0x20)],
+            Some(token_from_address),
+        )?);
+
+        Ok(())
+    }
+
+    fn encode_swap_out_amount_provided(
+        &self,
+        _swap_opcodes: &mut MulticallerCalls,
+        _abi_encoder: &dyn ProtocolAbiSwapEncoderTrait,
+        _token_from_address: Address,
+        _token_to_address: Address,
+        _amount_out: SwapAmountType,
+        _cur_pool: &dyn Pool,
+        _next_pool: Option<&dyn Pool>,
+        _payload: MulticallerOpcodesPayload,
+        _multicaller_address: Address,
+    ) -> eyre::Result<()> {
+        Err(eyre!("NOT_IMPLEMENTED"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pool_abi_encoder::MaverickV2ProtocolAbiEncoder;
+    use alloy_sol_types::SolCall;
+    use loom_defi_abi::maverick2::IMaverickV2Pool;
+    use loom_types_entities::MockPool;
+
+    #[test]
+    fn test_encode_swap_in_amount_provided() {
+        let (token_a, token_b) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let pool = MockPool::new(token_a, token_b, Address::repeat_byte(10));
+        let multicaller_address = Address::repeat_byte(0x55);
+        let amount_in = U256::from(1000);
+
+        let mut swap_opcodes = MulticallerCalls::new();
+        MaverickV2SwapOpcodesEncoder
+            .encode_swap_in_amount_provided(
+                &mut swap_opcodes,
+                &MaverickV2ProtocolAbiEncoder,
+                token_a,
+                token_b,
+                SwapAmountType::Set(amount_in),
+                &pool,
+                None,
+                MulticallerOpcodesPayload::Empty,
+                multicaller_address,
+            )
+            .unwrap();
+
+        let calls = swap_opcodes.opcodes_vec;
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].to, token_a);
+        assert_eq!(calls[0].call_data, AbiEncoderHelper::encode_erc20_transfer(pool.get_address(), amount_in));
+
+        assert_eq!(calls[1].to, pool.get_address());
+        let swap_call = IMaverickV2Pool::swapCall::abi_decode(&calls[1].call_data, true).unwrap();
+        assert_eq!(swap_call.recipient, multicaller_address);
+        assert_eq!(swap_call.params.amount, amount_in);
+        assert!(swap_call.params.tokenAIn);
+        assert!(!swap_call.params.exactOutput);
+        assert!(swap_call.data.is_empty());
+    }
+
+    #[test]
+    fn test_encode_swap_in_amount_from_stack() {
+        let (token_a, token_b) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let pool = MockPool::new(token_a, token_b, Address::repeat_byte(10));
+
+        let mut swap_opcodes = MulticallerCalls::new();
+        MaverickV2SwapOpcodesEncoder
+            .encode_swap_in_amount_provided(
+                &mut swap_opcodes,
+                &MaverickV2ProtocolAbiEncoder,
+                token_b,
+                token_a,
+                SwapAmountType::RelativeStack(0),
+                &pool,
+                None,
+                MulticallerOpcodesPayload::Empty,
+                Address::repeat_byte(0x55),
+            )
+            .unwrap();
+
+        // The transfer and the swap read the output of the previous swap, the amount out of the swap is pushed to the stack
+        let calls = swap_opcodes.opcodes_vec;
+        let transfer_stack = calls[0].call_stack.clone().unwrap();
+        let swap_stack = calls[1].call_stack.clone().unwrap();
+        assert_eq!((transfer_stack.stack_offset, transfer_stack.data_offset), (0, 0x24));
+        assert_eq!((swap_stack.stack_offset, swap_stack.data_offset), (0, 0x24));
+        assert!(calls[0].return_stack.is_none());
+        assert_eq!(calls[1].return_stack.clone().unwrap().data_offset, 0x20);
+        assert!(!IMaverickV2Pool::swapCall::abi_decode(&calls[1].call_data, true).unwrap().params.tokenAIn);
+    }
+}
@@ -1,20 +1,28 @@
 use crate::pool_abi_encoder::ProtocolAbiSwapEncoderTrait;
 pub use crate::pool_opcodes_encoder::swap_opcodes_encoders::MulticallerOpcodesPayload;
 use alloy_primitives::Address;
+pub use balancer2::BalancerV2SwapOpcodesEncoder;
 pub use curve::CurveSwapOpcodesEncoder;
+pub use erc4626::Erc4626VaultSwapOpcodesEncoder;
 use eyre::{eyre, Result};
 use loom_types_blockchain::MulticallerCalls;
 use loom_types_entities::{Pool, SwapAmountType};
+pub use maverick2::MaverickV2SwapOpcodesEncoder;
 pub use steth::StEthSwapEncoder;
 pub use swap_opcodes_encoders::ProtocolSwapOpcodesEncoderV2;
 pub use uniswap2::UniswapV2SwapOpcodesEncoder;
 pub use uniswap3::UniswapV3SwapOpcodesEncoder;
+pub use uniswap4::UniswapV4SwapOpcodesEncoder;
 pub use wsteth::WstEthSwapEncoder;
 
+mod balancer2;
 mod curve;
+mod erc4626;
+mod maverick2;
 mod steth;
 mod uniswap2;
 mod uniswap3;
+mod uniswap4;
 mod wsteth;
 
 mod swap_opcodes_encoders;
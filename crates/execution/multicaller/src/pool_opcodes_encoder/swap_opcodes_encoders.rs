@@ -1,6 +1,7 @@
 use crate::pool_abi_encoder::ProtocolAbiSwapEncoderTrait;
 use crate::pool_opcodes_encoder::{
-    CurveSwapOpcodesEncoder, SwapOpcodesEncoderTrait, UniswapV2SwapOpcodesEncoder, UniswapV3SwapOpcodesEncoder,
+    BalancerV2SwapOpcodesEncoder, CurveSwapOpcodesEncoder, Erc4626VaultSwapOpcodesEncoder, MaverickV2SwapOpcodesEncoder,
+    SwapOpcodesEncoderTrait, UniswapV2SwapOpcodesEncoder, UniswapV3SwapOpcodesEncoder, UniswapV4SwapOpcodesEncoder,
 };
 use crate::{OpcodesEncoder, OpcodesEncoderV2};
 use alloy_primitives::{Address, Bytes};
@@ -57,9 +58,13 @@ impl Default for ProtocolSwapOpcodesEncoderV2 {
 
         pool_classes.insert(PoolClass::UniswapV2, uni2_opcodes_encoder.clone());
         pool_classes.insert(PoolClass::Maverick, uni3_opcodes_encoder.clone());
+        pool_classes.insert(PoolClass::MaverickV2, Arc::new(MaverickV2SwapOpcodesEncoder));
         pool_classes.insert(PoolClass::UniswapV3, uni3_opcodes_encoder.clone());
         pool_classes.insert(PoolClass::PancakeV3, uni3_opcodes_encoder.clone());
         pool_classes.insert(PoolClass::Curve, curve_opcodes_encoder.clone());
+        pool_classes.insert(PoolClass::Erc4626Vault, Arc::new(Erc4626VaultSwapOpcodesEncoder));
+        pool_classes.insert(PoolClass::BalancerV2, Arc::new(BalancerV2SwapOpcodesEncoder));
+        pool_classes.insert(PoolClass::UniswapV4, Arc::new(UniswapV4SwapOpcodesEncoder));
 
         Self { pool_classes }
     }
@@ -0,0 +1,312 @@
+use crate::opcodes_helpers::OpcodesHelpers;
+use crate::pool_abi_encoder::ProtocolAbiSwapEncoderTrait;
+use crate::pool_opcodes_encoder::swap_opcodes_encoders::MulticallerOpcodesPayload;
+use crate::pool_opcodes_encoder::SwapOpcodesEncoderTrait;
+use crate::{OpcodesEncoder, OpcodesEncoderV2};
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
+use alloy_sol_types::{SolCall, SolValue};
+use eyre::{eyre, OptionExt};
+use loom_defi_abi::uniswap4::IUniswapV4PoolManager;
+use loom_defi_abi::AbiEncoderHelper;
+use loom_defi_address_book::FactoryAddress;
+use loom_types_blockchain::{MulticallerCall, MulticallerCalls};
+use loom_types_entities::{Pool, PreswapRequirement, SwapAmountType};
+use tracing::trace;
+
+// unlock data follows the selector, offset and length, the swap call data follows the packed opcode header and address
+const UNLOCK_SWAP_OFFSET: u32 = 0x44 + 0x20;
+// unlock returns the bytes returned by the multicaller callback, the top of the callback stack
+const UNLOCK_RETURN_OFFSET: u32 = 0x40;
+
+/// Uniswap V4 swaps through the PoolManager, the input is synced and transferred before unlock.
+/// In the unlock callback the multicaller swaps, settles the input and takes the output delta read from the PoolManager transient storage.
+pub struct UniswapV4SwapOpcodesEncoder;
+
+impl UniswapV4SwapOpcodesEncoder {
+    // CurrencyDelta slot of the multicaller for the currency
+    fn currency_delta_slot(target: Address, currency: Address) -> B256 {
+        keccak256((target, currency).abi_encode())
+    }
+}
+
+impl SwapOpcodesEncoderTrait for UniswapV4SwapOpcodesEncoder {
+    fn encode_swap_in_amount_provided(
+        &self,
+        swap_opcodes: &mut MulticallerCalls,
+        abi_encoder: &dyn ProtocolAbiSwapEncoderTrait,
+        token_from_address: Address,
+        token_to_address: Address,
+        amount_in: SwapAmountType,
+        cur_pool: &dyn Pool,
+        next_pool: Option<&dyn Pool>,
+        _payload: MulticallerOpcodesPayload,
+        multicaller_address: Address,
+    ) -> eyre::Result<()> {
+        if cur_pool.is_native() {
+            return Err(eyre!("NATIVE_CURRENCY_NOT_SUPPORTED"));
+        }
+        // The amount on the stack is negated for the exact input swap
+        if matches!(amount_in, SwapAmountType::Stack0 | SwapAmountType::RelativeStack(1..)) {
+            return Err(eyre!("UNSUPPORTED_AMOUNT_STACK"));
+        }
+
+        let pool_manager = FactoryAddress::UNISWAP_V4_POOL_MANAGER_ADDRESS;
+
+        let swap_to: Address = match next_pool.map(|next_pool| next_pool.preswap_requirement()) {
+            Some(PreswapRequirement::Transfer(next_funds_to)) => next_funds_to,
+            _ => multicaller_address,
+        };
+
+        trace!(
+            "uniswap v4 swap for pool={:?}, amount={:?} from {} to {} swap_to={}",
+            cur_pool.get_address(),
+            amount_in,
+            token_from_address,
+            token_to_address,
+            swap_to
+        );
+
+        let mut unlock_opcodes = MulticallerCalls::new();
+        unlock_opcodes
+            .add(MulticallerCall::new_call(
+                pool_manager,
+                &abi_encoder.encode_swap_in_amount_provided(
+                    cur_pool,
+                    token_from_address,
+                    token_to_address,
+                    amount_in.unwrap_or_default(),
+                    swap_to,
+                    Bytes::new(),
+                )?,
+            ))
+            .add(MulticallerCall::new_call(pool_manager, &Bytes::from(IUniswapV4PoolManager::settleCall {}.abi_encode())));
+
+        let mut delta_opcode = MulticallerCall::new_static_call(
+            pool_manager,
+            &Bytes::from(
+                IUniswapV4PoolManager::exttloadCall { slot: Self::currency_delta_slot(multicaller_address, token_to_address) }.abi_encode(),
+            ),
+        );
+        delta_opcode.set_return_stack(true, 0, 0x0, 0x20);
+        unlock_opcodes.add(delta_opcode);
+
+        let mut take_opcode = MulticallerCall::new_call(
+            pool_manager,
+            &Bytes::from(IUniswapV4PoolManager::takeCall { currency: token_to_address, to: swap_to, amount: U256::ZERO }.abi_encode()),
+        );
+        take_opcode.set_call_stack(true, 0, 0x44, 0x20);
+        unlock_opcodes.add(take_opcode);
+
+        let sync_opcode = MulticallerCall::new_call(
+            pool_manager,
+            &Bytes::from(IUniswapV4PoolManager::syncCall { currency: token_from_address }.abi_encode()),
+        );
+        swap_opcodes.add(sync_opcode);
+
+        let transfer_opcode = MulticallerCall::new_call(
+            token_from_address,
+            &AbiEncoderHelper::encode_erc20_transfer(pool_manager, amount_in.unwrap_or_default()),
+        );
+        swap_opcodes.merge(OpcodesHelpers::build_multiple_stack(amount_in, vec![(transfer_opcode, 0x24, 0x20)], Some(token_from_address))?);
+
+        let mut unlock_opcode = MulticallerCall::new_call(
+            pool_manager,
+            &Bytes::from(IUniswapV4PoolManager::unlockCall { data: OpcodesEncoderV2::pack_do_calls_data(&unlock_opcodes)? }.abi_encode()),
+        );
+        if !matches!(amount_in, SwapAmountType::Set(_)) {
+            swap_opcodes.add(MulticallerCall::new_calculation_call(&Bytes::from(vec![0x8, 0x2A, 0x00])));
+            let swap_offset = abi_encoder.swap_in_amount_offset(cur_pool, token_from_address, token_to_address).ok_or_eyre("NO_OFFSET")?;
+            unlock_opcode.set_call_stack(true, 0, UNLOCK_SWAP_OFFSET + swap_offset, 0x20);
+        }
+        unlock_opcode.set_return_stack(true, 0, UNLOCK_RETURN_OFFSET, 0x20);
+        swap_opcodes.add(unlock_opcode);
+
+        Ok(())
+    }
+
+    fn encode_swap_out_amount_provided(
+        &self,
+        _swap_opcodes: &mut MulticallerCalls,
+        _abi_encoder: &dyn ProtocolAbiSwapEncoderTrait,
+        _token_from_address: Address,
+        _token_to_address: Address,
+        _amount_out: SwapAmountType,
+        _cur_pool: &dyn Pool,
+        _next_pool: Option<&dyn Pool>,
+        _payload: MulticallerOpcodesPayload,
+        _multicaller_address: Address,
+    ) -> eyre::Result<()> {
+        Err(eyre!("NOT_IMPLEMENTED"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pool_abi_encoder::ProtocolABIEncoderV2;
+    use crate::MulticallerDeployer;
+    use alloy_primitives::aliases::{I24, U24};
+    use alloy_primitives::I256;
+    use alloy_provider::Provider;
+    use alloy_rpc_types::{Filter, TransactionInput, TransactionRequest};
+    use alloy_sol_types::SolEvent;
+    use loom_defi_abi::uniswap4::IUniswapV4PoolManagerEvents::Initialize;
+    use loom_defi_abi::uniswap4::{IV4Quoter, PoolKey};
+    use loom_defi_address_book::{PeripheryAddress, TokenAddressEth};
+    use loom_defi_pools::{UniswapV2Pool, UniswapV4Pool};
+    use loom_evm_utils::{BalanceCheater, NWETH};
+    use loom_node_debug_provider::AnvilDebugProviderFactory;
+
+    const POOL_MANAGER_DEPLOY_BLOCK: u64 = 21688329u64;
+    const BLOCK_NUMBER: u64 = 21700000u64;
+
+    fn uniswap_v4_pool(token_a: Address, token_b: Address) -> UniswapV4Pool {
+        UniswapV4Pool::from_pool_key(PoolKey {
+            currency0: token_a,
+            currency1: token_b,
+            fee: U24::from(3000),
+            tickSpacing: I24::try_from(60).unwrap(),
+            hooks: Address::ZERO,
+        })
+    }
+
+    fn encode_swap(
+        amount_in: SwapAmountType,
+        pool: &UniswapV4Pool,
+        token_from: Address,
+        token_to: Address,
+        next_pool: Option<&dyn Pool>,
+        multicaller_address: Address,
+    ) -> eyre::Result<MulticallerCalls> {
+        let mut swap_opcodes = MulticallerCalls::new();
+        UniswapV4SwapOpcodesEncoder.encode_swap_in_amount_provided(
+            &mut swap_opcodes,
+            &ProtocolABIEncoderV2::default(),
+            token_from,
+            token_to,
+            amount_in,
+            pool,
+            next_pool,
+            MulticallerOpcodesPayload::Empty,
+            multicaller_address,
+        )?;
+        Ok(swap_opcodes)
+    }
+
+    #[test]
+    fn test_encode_swap_in_amount_provided() {
+        let (token_a, token_b) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let pool = uniswap_v4_pool(token_a, token_b);
+        let multicaller_address = Address::repeat_byte(0x55);
+        let amount_in = U256::from(1000);
+        let pool_manager = FactoryAddress::UNISWAP_V4_POOL_MANAGER_ADDRESS;
+
+        let calls = encode_swap(SwapAmountType::Set(amount_in), &pool, token_a, token_b, None, multicaller_address).unwrap().opcodes_vec;
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls[0].to, pool_manager);
+        assert_eq!(IUniswapV4PoolManager::syncCall::abi_decode(&calls[0].call_data, true).unwrap().currency, token_a);
+        assert_eq!(calls[1].to, token_a);
+        assert_eq!(calls[1].call_data, AbiEncoderHelper::encode_erc20_transfer(pool_manager, amount_in));
+
+        assert_eq!(calls[2].to, pool_manager);
+        assert!(calls[2].call_stack.is_none());
+        assert_eq!(calls[2].return_stack.clone().unwrap().data_offset, UNLOCK_RETURN_OFFSET);
+        let unlock_data = IUniswapV4PoolManager::unlockCall::abi_decode(&calls[2].call_data, true).unwrap().data;
+
+        // The swap is the first call in the callback and is an exact input swap
+        let swap_call = IUniswapV4PoolManager::swapCall::abi_decode(&unlock_data[0x20..], false).unwrap();
+        assert_eq!(swap_call.key, pool.pool_key().unwrap());
+        assert!(swap_call.params.zeroForOne);
+        assert_eq!(swap_call.params.amountSpecified, I256::ZERO - I256::from_raw(amount_in));
+        assert_eq!(I256::from_raw(U256::from_be_slice(&calls[2].call_data[0x128..0x148])), I256::ZERO - I256::from_raw(amount_in));
+
+        // The output delta is taken by the multicaller
+        let take_call = IUniswapV4PoolManager::takeCall::abi_decode(&unlock_data[unlock_data.len() - 0x64..], true).unwrap();
+        assert_eq!((take_call.currency, take_call.to), (token_b, multicaller_address));
+    }
+
+    #[test]
+    fn test_encode_swap_in_amount_from_stack() {
+        let (token_a, token_b) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let pool = uniswap_v4_pool(token_a, token_b);
+        let next_pool = UniswapV2Pool::new(Address::repeat_byte(11));
+
+        let calls = encode_swap(SwapAmountType::RelativeStack(0), &pool, token_b, token_a, Some(&next_pool), Address::repeat_byte(0x55))
+            .unwrap()
+            .opcodes_vec;
+
+        // The transfer reads the amount, the negated amount is written to the swap in unlock data
+        assert_eq!(calls.len(), 4);
+        let transfer_stack = calls[1].call_stack.clone().unwrap();
+        assert_eq!((transfer_stack.stack_offset, transfer_stack.data_offset), (0, 0x24));
+        assert_eq!(calls[2].call_data, Bytes::from(vec![0x8, 0x2A, 0x00]));
+        let unlock_stack = calls[3].call_stack.clone().unwrap();
+        assert_eq!((unlock_stack.stack_offset, unlock_stack.data_offset), (0, 0x128));
+
+        let unlock_data = IUniswapV4PoolManager::unlockCall::abi_decode(&calls[3].call_data, true).unwrap().data;
+        let take_call = IUniswapV4PoolManager::takeCall::abi_decode(&unlock_data[unlock_data.len() - 0x64..], true).unwrap();
+        assert_eq!((take_call.currency, take_call.to), (token_a, next_pool.get_address()));
+
+        assert!(encode_swap(SwapAmountType::RelativeStack(1), &pool, token_b, token_a, None, Address::repeat_byte(0x55)).is_err());
+        let native_pool = uniswap_v4_pool(Address::ZERO, token_b);
+        assert!(encode_swap(SwapAmountType::RelativeStack(0), &native_pool, token_b, TokenAddressEth::WETH, None, Address::ZERO).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_swap_on_fork() -> eyre::Result<()> {
+        let node_url = std::env::var("MAINNET_WS")?;
+        let client = AnvilDebugProviderFactory::from_node_on_block(node_url, BLOCK_NUMBER).await?;
+
+        let multicaller_address = MulticallerDeployer::new()
+            .set_code(client.clone(), Address::repeat_byte(0x78))
+            .await?
+            .address()
+            .ok_or_eyre("MULTICALLER_NOT_DEPLOYED")?;
+        BalanceCheater::set_anvil_token_balance_float(client.clone(), NWETH::ADDRESS, multicaller_address, 1.0).await?;
+
+        // First hookless WETH pool with liquidity
+        let filter = Filter::new()
+            .address(FactoryAddress::UNISWAP_V4_POOL_MANAGER_ADDRESS)
+            .event_signature(Initialize::SIGNATURE_HASH)
+            .from_block(POOL_MANAGER_DEPLOY_BLOCK)
+            .to_block(BLOCK_NUMBER);
+        let mut pool = None;
+        for log in client.get_logs(&filter).await? {
+            let initialize = Initialize::decode_log_data(log.data(), false)?;
+            if !initialize.hooks.is_zero() || (initialize.currency0 != NWETH::ADDRESS && initialize.currency1 != NWETH::ADDRESS) {
+                continue;
+            }
+            let candidate = UniswapV4Pool::fetch_pool_data(client.clone(), initialize.id).await?;
+            if candidate.liquidity > 0 {
+                pool = Some(candidate);
+                break;
+            }
+        }
+        let pool = pool.ok_or_eyre("POOL_NOT_FOUND")?;
+        let token_to = pool.get_tokens().into_iter().find(|token| *token != NWETH::ADDRESS).ok_or_eyre("TOKEN_NOT_FOUND")?;
+        let amount_in = NWETH::from_float(0.0001);
+
+        let swap_opcodes = encode_swap(SwapAmountType::Set(amount_in), &pool, NWETH::ADDRESS, token_to, None, multicaller_address)?;
+        let tx_request = TransactionRequest::default()
+            .to(multicaller_address)
+            .from(Address::repeat_byte(0x12))
+            .input(TransactionInput::new(OpcodesEncoderV2::pack_do_calls(&swap_opcodes)?));
+        let result = client.call(&tx_request).await?;
+        let amount_out = U256::from_be_slice(&result[0x40..0x60]);
+
+        let quoter = IV4Quoter::IV4QuoterInstance::new(PeripheryAddress::UNISWAP_V4_QUOTER, client.clone());
+        let quote = quoter
+            .quoteExactInputSingle(IV4Quoter::QuoteExactSingleParams {
+                poolKey: pool.pool_key()?,
+                zeroForOne: pool.currency0 == NWETH::ADDRESS,
+                exactAmount: amount_in.to(),
+                hookData: Bytes::new(),
+            })
+            .call()
+            .await?;
+        assert!(!amount_out.is_zero());
+        assert_eq!(amount_out, quote.amountOut);
+
+        Ok(())
+    }
+}
@@ -1,8 +1,10 @@
 use crate::MulticallerSwapEncoder;
 use alloy_primitives::{Address, BlockNumber, Bytes, U256};
+use alloy_sol_types::SolCall;
 use eyre::{eyre, OptionExt, Result};
+use loom_defi_abi::IEmergencyStop;
 use loom_types_blockchain::LoomDataTypesEthereum;
-use loom_types_blockchain::MulticallerCalls;
+use loom_types_blockchain::{MulticallerCall, MulticallerCalls};
 use loom_types_entities::tips::{tips_and_value_for_swap_type, Tips};
 use loom_types_entities::{Swap, SwapEncoder, SwapStep};
 use tracing::{debug, error, trace};
@@ -38,7 +40,7 @@ impl SwapEncoder for MulticallerSwapEncoder {
                 }
                 ret
             }
-            Swap::ExchangeSwapLine(_) => vec![],
+            Swap::ExchangeSwapLine(_) | Swap::FlashLoanSwapLine { .. } => vec![],
             Swap::None => {
                 vec![]
             }
@@ -62,6 +64,10 @@ impl SwapEncoder for MulticallerSwapEncoder {
                         }
                     }
                 }
+                Swap::FlashLoanSwapLine { provider, asset, amount, inner } => {
+                    trace!("START: flash loan swap line");
+                    self.swap_step_encoder.encode_flash_loan_swap_line(provider, *asset, *amount, inner)?
+                }
                 _ => return Err(eyre!("NO_SWAP_STEPS")),
             }
         } else if swap_vec.len() == 1 {
@@ -96,6 +102,12 @@ impl SwapEncoder for MulticallerSwapEncoder {
                 vec![]
             };
 
+        if let Some(stop_contract) = self.emergency_stop {
+            // Static calls cannot branch on the returned bool, requireNotStopped reverts the whole multicall instead
+            let call_data: Bytes = IEmergencyStop::requireNotStoppedCall {}.abi_encode().into();
+            swap_opcodes.insert(MulticallerCall::new_static_call(stop_contract, &call_data));
+        }
+
         let (to, call_data) = self.swap_step_encoder.to_call_data(&swap_opcodes)?;
 
         Ok((to, None, call_data, tips_vec))
@@ -1,5 +1,5 @@
 use alloy_primitives::{Address, Bytes, U256};
-use eyre::Result;
+use eyre::{OptionExt, Result};
 use lazy_static::lazy_static;
 use tracing::trace;
 
@@ -8,7 +8,7 @@ use crate::SwapLineEncoder;
 use loom_defi_abi::AbiEncoderHelper;
 use loom_types_blockchain::LoomDataTypesEthereum;
 use loom_types_blockchain::{MulticallerCall, MulticallerCalls};
-use loom_types_entities::{SwapAmountType, SwapStep};
+use loom_types_entities::{FlashLoanProvider, SwapAmountType, SwapLine, SwapStep};
 
 lazy_static! {
     static ref BALANCER_VAULT_ADDRESS: Address = "0xBA12222222228d8Ba445958a75a0704d566BF2C8".parse().unwrap();
@@ -61,8 +61,8 @@ impl SwapStepEncoder {
 
         let mut steps = steps.clone();
 
-        let token = first_swap.first_token().unwrap();
-        let in_amount = first_swap.get_in_amount().unwrap();
+        let token = first_swap.first_token().ok_or_eyre("NO_FIRST_TOKEN")?;
+        let in_amount = first_swap.get_in_amount()?;
 
         for (swap_idx, swap) in steps.iter_mut().enumerate() {
             if swap_idx > 0 {
@@ -92,6 +92,31 @@ impl SwapStepEncoder {
         Ok(flash_opcodes)
     }
 
+    /// Borrow `amount` of `asset` from `provider` and run the swap line in the flash loan callback of the multicaller.
+    /// The multicaller repays the loan with the fee at the end of the callback.
+    pub fn encode_flash_loan_swap_line(
+        &self,
+        provider: &FlashLoanProvider,
+        asset: Address,
+        amount: U256,
+        swap_line: &SwapLine<LoomDataTypesEthereum>,
+    ) -> Result<MulticallerCalls> {
+        let swap_opcodes = self.swap_line_encoder.encode_swap_line_in_amount(swap_line, None)?;
+        let inside_call_bytes = OpcodesEncoderV2::pack_do_calls_data(&swap_opcodes)?;
+
+        let (lender, flash_call_data) = match provider {
+            FlashLoanProvider::BalancerV2 => (
+                *BALANCER_VAULT_ADDRESS,
+                AbiEncoderHelper::encode_balancer_flashloan(asset, amount, inside_call_bytes, self.multicaller_address),
+            ),
+        };
+
+        let mut flash_opcodes = MulticallerCalls::new();
+        flash_opcodes.add(MulticallerCall::new_call(lender, &flash_call_data));
+
+        Ok(flash_opcodes)
+    }
+
     pub fn encode_in_amount(
         &self,
         flash_step: SwapStep<LoomDataTypesEthereum>,
@@ -1,19 +1,56 @@
 use async_trait::async_trait;
 use eyre::eyre;
 use influxdb::{Client, ReadQuery, WriteQuery};
-use loom_core_actors::{Actor, ActorResult, Broadcaster, Consumer, WorkerResult};
+use loom_core_actors::{Actor, ActorResult, Broadcaster, CancellationToken, Consumer, WorkerResult};
 use loom_core_actors_macros::Consumer;
 use loom_core_blockchain::Blockchain;
 use std::collections::HashMap;
 use std::time::Duration;
-use tokio::time::timeout;
+use tokio::time::{interval, sleep, timeout, MissedTickBehavior};
 use tracing::{error, info, warn};
 
+pub const DEFAULT_INFLUXDB_BATCH_SIZE: usize = 500;
+pub const DEFAULT_INFLUXDB_FLUSH_INTERVAL_MS: u64 = 1000;
+
+const INFLUXDB_WRITE_TIMEOUT_MS: u64 = 2000;
+const INFLUXDB_WRITE_MAX_RETRIES: u32 = 3;
+const INFLUXDB_WRITE_RETRY_BASE_DELAY_MS: u64 = 100;
+
+/// Write the batch in one request, failed batches are retried with exponential backoff and dropped after the last retry
+async fn write_batch_with_retry(client: Client, batch: Vec<WriteQuery>) {
+    let batch_len = batch.len();
+    for attempt in 0..=INFLUXDB_WRITE_MAX_RETRIES {
+        let error = match timeout(Duration::from_millis(INFLUXDB_WRITE_TIMEOUT_MS), client.query(batch.clone())).await {
+            Ok(Ok(_)) => return,
+            Ok(Err(e)) => format!("{:?}", e),
+            Err(elapsed) => elapsed.to_string(),
+        };
+        if attempt < INFLUXDB_WRITE_MAX_RETRIES {
+            let delay = Duration::from_millis(INFLUXDB_WRITE_RETRY_BASE_DELAY_MS * 2u64.pow(attempt));
+            warn!(batch_len, attempt, %error, "InfluxDB batch write failed, retrying in {:?}", delay);
+            sleep(delay).await;
+        } else {
+            error!(batch_len, %error, "InfluxDB batch write failed, dropping batch");
+        }
+    }
+}
+
+fn flush(client: &Client, buffer: &mut Vec<WriteQuery>) {
+    if buffer.is_empty() {
+        return;
+    }
+    let batch = std::mem::take(buffer);
+    tokio::task::spawn(write_batch_with_retry(client.clone(), batch));
+}
+
 pub async fn start_influxdb_worker(
     url: String,
     database: String,
     tags: HashMap<String, String>,
+    batch_size: usize,
+    flush_interval: Duration,
     event_receiver: Broadcaster<WriteQuery>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult {
     let client = Client::new(url, database.clone());
     let create_db_stmt = format!("CREATE DATABASE {}", database);
@@ -23,37 +60,44 @@ pub async fn start_influxdb_worker(
         Err(e) => info!("Database creation failed or already exists: {:?}", e),
     }
     let mut event_receiver = event_receiver.subscribe();
+
+    let batch_size = batch_size.max(1);
+    let mut buffer: Vec<WriteQuery> = Vec::with_capacity(batch_size);
+    let mut flush_interval = interval(flush_interval);
+    flush_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
     loop {
-        let event_result = event_receiver.recv().await;
-        match event_result {
-            Ok(mut event) => {
-                for (key, value) in tags.iter() {
-                    event = event.add_tag(key, value.clone());
-                }
-                let client_clone = client.clone();
-                tokio::task::spawn(async move {
-                    match timeout(Duration::from_millis(2000), client_clone.query(event)).await {
-                        Ok(inner_result) => {
-                            if let Err(e) = inner_result {
-                                error!("InfluxDB Write failed: {:?}", e);
-                            }
+        tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("InfluxDB writer cancelled".to_string());
+            }
+            event_result = event_receiver.recv() => {
+                match event_result {
+                    Ok(mut event) => {
+                        for (key, value) in tags.iter() {
+                            event = event.add_tag(key, value.clone());
                         }
-                        Err(elapsed) => {
-                            error!("InfluxDB Query timed out: {}", elapsed);
+                        buffer.push(event);
+                        if buffer.len() >= batch_size {
+                            flush(&client, &mut buffer);
                         }
                     }
-                });
-            }
-            Err(e) => match e {
-                tokio::sync::broadcast::error::RecvError::Closed => {
-                    error!("InfluxDB channel closed");
-                    return Err(eyre!("INFLUXDB_CHANNEL_CLOSED"));
-                }
-                tokio::sync::broadcast::error::RecvError::Lagged(lagged) => {
-                    warn!("InfluxDB lagged: {:?}", lagged);
-                    continue;
+                    Err(e) => match e {
+                        tokio::sync::broadcast::error::RecvError::Closed => {
+                            error!("InfluxDB channel closed");
+                            flush(&client, &mut buffer);
+                            return Err(eyre!("INFLUXDB_CHANNEL_CLOSED"));
+                        }
+                        tokio::sync::broadcast::error::RecvError::Lagged(lagged) => {
+                            warn!("InfluxDB lagged: {:?}", lagged);
+                            continue;
+                        }
+                    },
                 }
-            },
+            }
+            _ = flush_interval.tick() => {
+                flush(&client, &mut buffer);
+            }
         }
     }
 }
@@ -63,13 +107,38 @@ pub struct InfluxDbWriterActor {
     url: String,
     database: String,
     tags: HashMap<String, String>,
+    batch_size: usize,
+    flush_interval: Duration,
     #[consumer]
     influxdb_write_channel_rx: Option<Broadcaster<WriteQuery>>,
+    cancellation_token: CancellationToken,
 }
 
 impl InfluxDbWriterActor {
     pub fn new(url: String, database: String, tags: HashMap<String, String>) -> Self {
-        Self { url, database, tags, influxdb_write_channel_rx: None }
+        Self {
+            url,
+            database,
+            tags,
+            batch_size: DEFAULT_INFLUXDB_BATCH_SIZE,
+            flush_interval: Duration::from_millis(DEFAULT_INFLUXDB_FLUSH_INTERVAL_MS),
+            influxdb_write_channel_rx: None,
+            cancellation_token: CancellationToken::new(),
+        }
+    }
+
+    /// Writes are buffered and flushed when `batch_size` writes are collected or every `flush_interval_ms`.
+    /// Defaults are used for unset values.
+    pub fn with_batch_config(self, batch_size: Option<usize>, flush_interval_ms: Option<u64>) -> Self {
+        Self {
+            batch_size: batch_size.unwrap_or(DEFAULT_INFLUXDB_BATCH_SIZE),
+            flush_interval: Duration::from_millis(flush_interval_ms.unwrap_or(DEFAULT_INFLUXDB_FLUSH_INTERVAL_MS)),
+            ..self
+        }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
     }
 
     pub fn on_bc(self, bc: &Blockchain) -> Self {
@@ -91,7 +160,10 @@ impl Actor for InfluxDbWriterActor {
             self.url.clone(),
             self.database.clone(),
             self.tags.clone(),
+            self.batch_size,
+            self.flush_interval,
             influxdb_write_channel_rx.clone(),
+            self.cancellation_token.clone(),
         ));
         Ok(vec![task])
     }
@@ -1,3 +1,3 @@
 mod influxdb_actor;
 
-pub use influxdb_actor::InfluxDbWriterActor;
+pub use influxdb_actor::{InfluxDbWriterActor, DEFAULT_INFLUXDB_BATCH_SIZE, DEFAULT_INFLUXDB_FLUSH_INTERVAL_MS};
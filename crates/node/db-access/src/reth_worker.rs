@@ -20,7 +20,7 @@ use std::path::Path;
 use std::sync::Arc;
 use tracing::{debug, error, info, trace};
 
-use loom_core_actors::{Actor, ActorResult, Broadcaster, Producer, WorkerResult};
+use loom_core_actors::{Actor, ActorResult, Broadcaster, CancellationToken, Producer, WorkerResult};
 use loom_core_actors_macros::Producer;
 use loom_core_blockchain::Blockchain;
 use loom_evm_utils::reth_types::append_all_matching_block_logs;
@@ -38,6 +38,7 @@ pub async fn reth_node_worker<P>(
     new_block_with_tx_channel: Option<Broadcaster<MessageBlock>>,
     new_block_logs_channel: Option<Broadcaster<MessageBlockLogs>>,
     new_block_state_update_channel: Option<Broadcaster<MessageBlockStateUpdate>>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult
 where
     P: Provider<Ethereum> + Send + Sync + Clone + 'static,
@@ -60,6 +61,9 @@ where
 
     loop {
         tokio::select! {
+        _ = cancellation_token.cancelled() => {
+            break Ok("Reth node worker cancelled".to_string());
+        }
         block_msg = stream.next() => {
             let Some(block_header) = block_msg else {
                     continue
@@ -249,6 +253,7 @@ pub fn reth_node_worker_starter<P>(
     new_block_with_tx_channel: Option<Broadcaster<MessageBlock>>,
     new_block_logs_channel: Option<Broadcaster<MessageBlockLogs>>,
     new_block_state_update_channel: Option<Broadcaster<MessageBlockStateUpdate>>,
+    cancellation_token: CancellationToken,
 ) -> ActorResult
 where
     P: Provider<Ethereum> + Send + Sync + Clone + 'static,
@@ -260,6 +265,7 @@ where
         new_block_with_tx_channel,
         new_block_logs_channel,
         new_block_state_update_channel,
+        cancellation_token,
     ));
     Ok(vec![handler])
 }
@@ -278,6 +284,7 @@ pub struct RethDbAccessBlockActor<P> {
     block_logs_channel: Option<Broadcaster<MessageBlockLogs>>,
     #[producer]
     block_state_update_channel: Option<Broadcaster<MessageBlockStateUpdate>>,
+    cancellation_token: CancellationToken,
 }
 
 impl<P> RethDbAccessBlockActor<P>
@@ -297,9 +304,14 @@ where
             block_with_tx_channel: None,
             block_logs_channel: None,
             block_state_update_channel: None,
+            cancellation_token: CancellationToken::new(),
         }
     }
 
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
     pub fn on_bc(self, bc: &Blockchain) -> Self {
         Self {
             block_header_channel: if self.config.block_header { Some(bc.new_block_headers_channel()) } else { None },
@@ -323,6 +335,7 @@ where
             self.block_with_tx_channel.clone(),
             self.block_logs_channel.clone(),
             self.block_state_update_channel.clone(),
+            self.cancellation_token.clone(),
         )
     }
     fn name(&self) -> &'static str {
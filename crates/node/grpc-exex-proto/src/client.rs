@@ -5,7 +5,7 @@ use alloy_rpc_types::{
 };
 use alloy_rpc_types_trace::geth::AccountState;
 use async_stream::stream;
-use eyre::{eyre, Result};
+use eyre::Result;
 use reth::primitives::{RecoveredBlock, SealedHeader, TransactionSigned};
 use reth::revm::db::states::StorageSlot;
 use reth::revm::db::{BundleAccount, StorageWithOriginalValues};
@@ -40,7 +40,7 @@ impl ExExClient {
             Ok(stream) => stream.into_inner(),
             Err(e) => {
                 error!(error=?e, "subscribe header");
-                return Err(eyre!("ERROR"));
+                return Err(e.into());
             }
         };
 
@@ -75,7 +75,7 @@ impl ExExClient {
             Ok(stream) => stream.into_inner(),
             Err(e) => {
                 error!(error=?e, "subscribe header");
-                return Err(eyre!("ERROR"));
+                return Err(e.into());
             }
         };
         Ok(stream! {
@@ -111,7 +111,7 @@ impl ExExClient {
             Ok(stream) => stream.into_inner(),
             Err(e) => {
                 error!(error=?e, "subscribe header");
-                return Err(eyre!("ERROR"));
+                return Err(e.into());
             }
         };
 
@@ -159,7 +159,7 @@ impl ExExClient {
             Ok(stream) => stream.into_inner(),
             Err(e) => {
                 error!(error=?e, "subscribe receipts");
-                return Err(eyre!("ERROR"));
+                return Err(e.into());
             }
         };
         Ok(stream! {
@@ -208,7 +208,7 @@ impl ExExClient {
             Ok(stream) => stream.into_inner(),
             Err(e) => {
                 error!(error=?e, "subscribe receipts");
-                return Err(eyre!("ERROR"));
+                return Err(e.into());
             }
         };
         Ok(stream! {
@@ -270,7 +270,7 @@ impl ExExClient {
             Ok(stream) => stream.into_inner(),
             Err(e) => {
                 error!(error=?e, "subscribe exex");
-                return Err(eyre!("ERROR"));
+                return Err(e.into());
             }
         };
 
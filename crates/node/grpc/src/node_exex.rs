@@ -1,8 +1,10 @@
 use crate::node_exex_worker::node_exex_grpc_worker;
-use loom_core_actors::{Actor, ActorResult, Broadcaster, Producer};
+use loom_core_actors::{Actor, ActorResult, Broadcaster, CancellationToken, Producer};
 use loom_core_actors_macros::Producer;
 use loom_core_blockchain::Blockchain;
-use loom_types_events::{MessageBlock, MessageBlockHeader, MessageBlockLogs, MessageBlockStateUpdate, MessageMempoolDataUpdate};
+use loom_types_events::{
+    MarketEvents, MessageBlock, MessageBlockHeader, MessageBlockLogs, MessageBlockStateUpdate, MessageMempoolDataUpdate,
+};
 use std::any::type_name;
 
 #[derive(Producer)]
@@ -18,6 +20,9 @@ pub struct NodeExExGrpcActor {
     block_state_update_channel: Option<Broadcaster<MessageBlockStateUpdate>>,
     #[producer]
     mempool_update_channel: Option<Broadcaster<MessageMempoolDataUpdate>>,
+    #[producer]
+    market_events_channel: Option<Broadcaster<MarketEvents>>,
+    cancellation_token: CancellationToken,
 }
 
 impl NodeExExGrpcActor {
@@ -29,9 +34,15 @@ impl NodeExExGrpcActor {
             block_logs_channel: None,
             block_state_update_channel: None,
             mempool_update_channel: None,
+            market_events_channel: None,
+            cancellation_token: CancellationToken::new(),
         }
     }
 
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
     pub fn on_bc(self, bc: &Blockchain) -> Self {
         Self {
             block_header_channel: Some(bc.new_block_headers_channel()),
@@ -39,6 +50,7 @@ impl NodeExExGrpcActor {
             block_logs_channel: Some(bc.new_block_logs_channel()),
             block_state_update_channel: Some(bc.new_block_state_update_channel()),
             mempool_update_channel: Some(bc.new_mempool_tx_channel()),
+            market_events_channel: Some(bc.market_events_channel()),
             ..self
         }
     }
@@ -53,6 +65,8 @@ impl Actor for NodeExExGrpcActor {
             self.block_logs_channel.clone().unwrap(),
             self.block_state_update_channel.clone().unwrap(),
             self.mempool_update_channel.clone().unwrap(),
+            self.market_events_channel.clone(),
+            self.cancellation_token.clone(),
         ));
         Ok(vec![handler])
     }
@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use alloy_eips::BlockNumHash;
 use alloy_primitives::{map::HashMap, Address, U256};
@@ -11,14 +12,15 @@ use reth_rpc::eth::EthTxBuilder;
 use revm::db::states::StorageSlot;
 use revm::db::{BundleAccount, StorageWithOriginalValues};
 use tokio::select;
-use tracing::{error, info};
+use tonic::{Code, Status};
+use tracing::{error, info, warn};
 
-use loom_core_actors::{Broadcaster, WorkerResult};
+use loom_core_actors::{Broadcaster, CancellationToken, WorkerResult};
 use loom_evm_utils::reth_types::append_all_matching_block_logs_sealed;
 use loom_node_grpc_exex_proto::ExExClient;
 use loom_types_blockchain::{GethStateUpdate, MempoolTx};
 use loom_types_events::{
-    BlockHeader, BlockLogs, BlockStateUpdate, BlockUpdate, Message, MessageBlock, MessageBlockHeader, MessageBlockLogs,
+    BlockHeader, BlockLogs, BlockStateUpdate, BlockUpdate, MarketEvents, Message, MessageBlock, MessageBlockHeader, MessageBlockLogs,
     MessageBlockStateUpdate, MessageMempoolDataUpdate, NodeMempoolDataUpdate,
 };
 
@@ -138,6 +140,27 @@ fn get_current_chain(notification: ExExNotification) -> Option<Arc<Chain>> {
     }
 }
 
+const RECONNECT_BASE_DELAY: Duration = Duration::from_millis(100);
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Delay before reconnection attempt `attempt`, `min(2^attempt * 100ms, 30s)`
+fn reconnect_delay(attempt: u32) -> Duration {
+    RECONNECT_BASE_DELAY.saturating_mul(2u32.saturating_pow(attempt)).min(RECONNECT_MAX_DELAY)
+}
+
+/// Only `UNAVAILABLE` and `DEADLINE_EXCEEDED` statuses are retried, transport errors are reported by tonic as `UNAVAILABLE`
+fn is_reconnectable(error: &eyre::Report) -> bool {
+    match error.downcast_ref::<Status>() {
+        Some(status) => matches!(status.code(), Code::Unavailable | Code::DeadlineExceeded),
+        None => error.downcast_ref::<tonic::transport::Error>().is_some(),
+    }
+}
+
+fn stream_closed(stream: &str) -> eyre::Report {
+    Status::unavailable(format!("{stream} stream closed")).into()
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn node_exex_grpc_worker(
     url: Option<String>,
     block_header_channel: Broadcaster<MessageBlockHeader>,
@@ -145,8 +168,83 @@ pub async fn node_exex_grpc_worker(
     logs_channel: Broadcaster<MessageBlockLogs>,
     state_update_channel: Broadcaster<MessageBlockStateUpdate>,
     mempool_channel: Broadcaster<MessageMempoolDataUpdate>,
+    market_events_channel: Option<Broadcaster<MarketEvents>>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult {
-    let client = ExExClient::connect(url.unwrap_or("http://[::1]:10000".to_string())).await?;
+    let url = url.unwrap_or("http://[::1]:10000".to_string());
+    let mut attempt: u32 = 0;
+    let mut disconnected = false;
+
+    loop {
+        let on_subscribed = || {
+            if disconnected {
+                info!("ExEx gRPC reconnected url={} attempts={}", url, attempt);
+                if let Some(market_events_channel) = &market_events_channel {
+                    if let Err(e) = market_events_channel.send(MarketEvents::ExExReconnected) {
+                        error!("market_events_channel.send error : {}", e)
+                    }
+                }
+            }
+            disconnected = false;
+            attempt = 0;
+        };
+
+        let session = node_exex_grpc_session(
+            &url,
+            &block_header_channel,
+            &block_with_tx_channel,
+            &logs_channel,
+            &state_update_channel,
+            &mempool_channel,
+            on_subscribed,
+        );
+        let error = tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("ExEx gRPC worker cancelled".to_string());
+            }
+            result = session => match result {
+                Ok(()) => stream_closed("exex"),
+                Err(e) => e,
+            },
+        };
+
+        if !is_reconnectable(&error) {
+            error!("ExEx gRPC failed url={} error={}", url, error);
+            return Err(error);
+        }
+
+        if !disconnected {
+            disconnected = true;
+            if let Some(market_events_channel) = &market_events_channel {
+                if let Err(e) = market_events_channel.send(MarketEvents::ExExDisconnected) {
+                    error!("market_events_channel.send error : {}", e)
+                }
+            }
+        }
+
+        let delay = reconnect_delay(attempt);
+        warn!("ExEx gRPC disconnected url={} error={} attempt={} retry_in={:?}", url, error, attempt, delay);
+        attempt = attempt.saturating_add(1);
+        tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("ExEx gRPC worker cancelled".to_string());
+            }
+            _ = tokio::time::sleep(delay) => {}
+        }
+    }
+}
+
+/// Connects to the ExEx server and forwards the subscribed streams until one of them fails or is closed
+async fn node_exex_grpc_session(
+    url: &str,
+    block_header_channel: &Broadcaster<MessageBlockHeader>,
+    block_with_tx_channel: &Broadcaster<MessageBlock>,
+    logs_channel: &Broadcaster<MessageBlockLogs>,
+    state_update_channel: &Broadcaster<MessageBlockStateUpdate>,
+    mempool_channel: &Broadcaster<MessageMempoolDataUpdate>,
+    on_subscribed: impl FnOnce(),
+) -> eyre::Result<()> {
+    let client = ExExClient::connect(url.to_string()).await?;
 
     let stream_header = client.subscribe_header().await?;
     pin_mut!(stream_header);
@@ -163,76 +261,94 @@ pub async fn node_exex_grpc_worker(
     let stream_tx = client.subscribe_mempool_tx().await?;
     pin_mut!(stream_tx);
 
+    on_subscribed();
+
     loop {
         select! {
 
             header = stream_header.next() => {
-                if let Some(header) = header {
-                    if let Err(e) = block_header_channel.send(
-                        MessageBlockHeader::new_with_time(BlockHeader::new( header)))
-                    {
-                        error!("block_header_channel.send error : {}", e)
-                    }
+                let Some(header) = header else { return Err(stream_closed("header")) };
+                if let Err(e) = block_header_channel.send(
+                    MessageBlockHeader::new_with_time(BlockHeader::new( header)))
+                {
+                    error!("block_header_channel.send error : {}", e)
                 }
             }
 
             block = stream_block.next() => {
-                if let Some(block) = block {
-                    if let Err(e) = block_with_tx_channel.send(
-                        Message::new_with_time( BlockUpdate{block})
-                    ) {
-                        error!("block_with_tx_channel.send error : {}", e)
-                    }
+                let Some(block) = block else { return Err(stream_closed("block")) };
+                if let Err(e) = block_with_tx_channel.send(
+                    Message::new_with_time( BlockUpdate{block})
+                ) {
+                    error!("block_with_tx_channel.send error : {}", e)
                 }
             }
 
             logs = stream_logs.next() => {
-                if let Some((block_header, logs)) = logs {
-                    let block_logs = BlockLogs {block_header, logs};
-                    if let Err(e) = logs_channel.send(
-                        Message::new_with_time(block_logs)
-                    ) {
-                        error!("block_with_tx_channel.send error : {}", e)
-                    }
+                let Some((block_header, logs)) = logs else { return Err(stream_closed("logs")) };
+                let block_logs = BlockLogs {block_header, logs};
+                if let Err(e) = logs_channel.send(
+                    Message::new_with_time(block_logs)
+                ) {
+                    error!("block_with_tx_channel.send error : {}", e)
                 }
             }
 
             state_update = stream_state.next() => {
-                if let Some((block_header, state_update)) = state_update {
-                    let block_state_update = BlockStateUpdate{
-                        block_header,
-                        state_update : vec![state_update],
-                    };
-                    if let Err(e) = state_update_channel.send(
-                        Message::new_with_time(block_state_update)
-                    ) {
-                        error!("block_with_tx_channel.send error : {}", e)
-                    }
+                let Some((block_header, state_update)) = state_update else { return Err(stream_closed("state update")) };
+                let block_state_update = BlockStateUpdate{
+                    block_header,
+                    state_update : vec![state_update],
+                };
+                if let Err(e) = state_update_channel.send(
+                    Message::new_with_time(block_state_update)
+                ) {
+                    error!("block_with_tx_channel.send error : {}", e)
                 }
             }
             pending_tx = stream_tx.next() => {
-                if let Some(tx) = pending_tx {
-                    let tx_hash = *tx.inner.tx_hash();
-
-                    let mempool_tx = MempoolTx{
-                        source: "exex".to_string(),
-                        tx_hash,
-                        time: Utc::now(),
-                        tx: Some(tx),
-                        logs: None,
-                        mined: None,
-                        failed: None,
-                        state_update: None,
-                        pre_state: None,
-                    };
-                    let data_update = NodeMempoolDataUpdate{ tx_hash, mempool_tx};
-
-                    if let Err(e) = mempool_channel.send(Message::new_with_source(data_update, "exex".to_string())) {
-                        error!("mempool_channel.send error : {}", e)
-                    }
-
+                let Some(tx) = pending_tx else { return Err(stream_closed("mempool tx")) };
+                let tx_hash = *tx.inner.tx_hash();
+
+                let mempool_tx = MempoolTx{
+                    source: "exex".to_string(),
+                    tx_hash,
+                    time: Utc::now(),
+                    tx: Some(tx),
+                    logs: None,
+                    mined: None,
+                    failed: None,
+                    state_update: None,
+                    pre_state: None,
+                };
+                let data_update = NodeMempoolDataUpdate{ tx_hash, mempool_tx};
+
+                if let Err(e) = mempool_channel.send(Message::new_with_source(data_update, "exex".to_string())) {
+                    error!("mempool_channel.send error : {}", e)
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_reconnect_delay() {
+        assert_eq!(reconnect_delay(0), Duration::from_millis(100));
+        assert_eq!(reconnect_delay(3), Duration::from_millis(800));
+        assert_eq!(reconnect_delay(9), Duration::from_secs(30));
+        assert_eq!(reconnect_delay(u32::MAX), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_is_reconnectable() {
+        assert!(is_reconnectable(&Status::unavailable("restart").into()));
+        assert!(is_reconnectable(&Status::deadline_exceeded("timeout").into()));
+        assert!(is_reconnectable(&stream_closed("header")));
+        assert!(!is_reconnectable(&Status::permission_denied("denied").into()));
+        assert!(!is_reconnectable(&eyre::eyre!("DECODE_ERROR")));
+    }
+}
@@ -0,0 +1,234 @@
+use alloy_json_rpc::PubSubItem;
+use alloy_pubsub::{ConnectionHandle, ConnectionInterface, PubSubConnect};
+use alloy_transport::{TransportErrorKind, TransportResult};
+use eyre::{eyre, Result};
+use serde::de::DeserializeOwned;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::UnixStream;
+use tracing::{debug, error};
+
+const READ_CHUNK_SIZE: usize = 64 << 10;
+
+/// IPC connection to a unix socket rejecting messages larger than `max_message_size_bytes`. The IPC transport of alloy
+/// buffers messages of any size
+#[derive(Clone, Debug)]
+pub struct BoundedIpcConnect {
+    path: String,
+    max_message_size_bytes: usize,
+}
+
+impl BoundedIpcConnect {
+    pub fn new(path: String, max_message_size_bytes: usize) -> Self {
+        Self { path, max_message_size_bytes }
+    }
+}
+
+impl PubSubConnect for BoundedIpcConnect {
+    fn is_local(&self) -> bool {
+        true
+    }
+
+    async fn connect(&self) -> TransportResult<ConnectionHandle> {
+        let stream = UnixStream::connect(&self.path).await.map_err(TransportErrorKind::custom)?;
+        let (reader, writer) = stream.into_split();
+        let (handle, interface) = ConnectionHandle::new();
+        tokio::spawn(bounded_ipc_backend(reader, writer, interface, self.max_message_size_bytes));
+        Ok(handle)
+    }
+}
+
+/// Buffer of the bytes read from the socket. Bytes are scanned once for the end of the top level JSON value, a message is
+/// deserialized only when it is complete
+#[derive(Debug, Default)]
+struct JsonMessageBuffer {
+    buf: Vec<u8>,
+    // start of the current message in `buf`, the bytes before belong to taken messages
+    start: usize,
+    // end of the scanned bytes of the current message
+    scan_offset: usize,
+    // nesting depth of objects and arrays at `scan_offset`
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+}
+
+impl JsonMessageBuffer {
+    fn extend(&mut self, data: &[u8]) {
+        self.buf.drain(..self.start);
+        self.scan_offset -= self.start;
+        self.start = 0;
+        self.buf.extend_from_slice(data);
+    }
+
+    /// Takes the first complete JSON message. Returns `None` while the message is incomplete and an error when it grows
+    /// beyond `max_message_size_bytes`
+    fn take_message<T: DeserializeOwned>(&mut self, max_message_size_bytes: usize) -> Result<Option<T>> {
+        while self.scan_offset < self.buf.len() {
+            let byte = self.buf[self.scan_offset];
+            self.scan_offset += 1;
+
+            if self.in_string {
+                if self.escaped {
+                    self.escaped = false;
+                } else if byte == b'\\' {
+                    self.escaped = true;
+                } else if byte == b'"' {
+                    self.in_string = false;
+                }
+                continue;
+            }
+
+            match byte {
+                b'{' | b'[' => self.depth += 1,
+                _ if self.depth == 0 && byte.is_ascii_whitespace() => {
+                    // Whitespace between messages
+                    self.start = self.scan_offset;
+                }
+                _ if self.depth == 0 => return Err(eyre!("Invalid IPC message")),
+                b'"' => self.in_string = true,
+                b'}' | b']' => {
+                    self.depth -= 1;
+                    if self.depth == 0 {
+                        let len = self.scan_offset - self.start;
+                        if len > max_message_size_bytes {
+                            return Err(eyre!("IPC message of {len} bytes exceeds limit of {max_message_size_bytes} bytes"));
+                        }
+                        let item = serde_json::from_slice(&self.buf[self.start..self.scan_offset])?;
+                        self.start = self.scan_offset;
+                        return Ok(Some(item));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if self.scan_offset - self.start > max_message_size_bytes {
+            Err(eyre!("IPC message exceeds limit of {max_message_size_bytes} bytes"))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+async fn bounded_ipc_backend(
+    mut reader: OwnedReadHalf,
+    mut writer: OwnedWriteHalf,
+    mut interface: ConnectionInterface,
+    max_message_size_bytes: usize,
+) {
+    let mut buf = JsonMessageBuffer::default();
+    let mut chunk = vec![0u8; READ_CHUNK_SIZE];
+
+    let errored = loop {
+        tokio::select! {
+            biased;
+            item = interface.recv_from_frontend() => {
+                match item {
+                    Some(msg) => {
+                        if let Err(e) = writer.write_all(msg.get().as_bytes()).await {
+                            error!("IPC write error : {}", e);
+                            break true;
+                        }
+                    }
+                    None => {
+                        debug!("IPC frontend dropped");
+                        break false;
+                    }
+                }
+            }
+            read = reader.read(&mut chunk) => {
+                match read {
+                    Ok(0) => {
+                        error!("IPC socket closed");
+                        break true;
+                    }
+                    Ok(n) => {
+                        buf.extend(&chunk[..n]);
+                        let mut failed = false;
+                        loop {
+                            match buf.take_message::<PubSubItem>(max_message_size_bytes) {
+                                Ok(Some(item)) => {
+                                    if interface.send_to_frontend(item).is_err() {
+                                        debug!("IPC frontend dropped");
+                                        return;
+                                    }
+                                }
+                                Ok(None) => break,
+                                Err(e) => {
+                                    error!("IPC read error : {}", e);
+                                    failed = true;
+                                    break;
+                                }
+                            }
+                        }
+                        if failed {
+                            break true;
+                        }
+                    }
+                    Err(e) => {
+                        error!("IPC read error : {}", e);
+                        break true;
+                    }
+                }
+            }
+        }
+    };
+
+    if errored {
+        interface.close_with_error();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::Value;
+
+    fn json_message_buffer(data: &[u8]) -> JsonMessageBuffer {
+        let mut buf = JsonMessageBuffer::default();
+        buf.extend(data);
+        buf
+    }
+
+    #[test]
+    fn test_take_json_message() {
+        let mut buf = json_message_buffer(br#"{"id":1} {"id":"#);
+        let message = buf.take_message::<Value>(1024).unwrap();
+        assert_eq!(message, Some(serde_json::json!({"id":1})));
+
+        // Second message is incomplete
+        assert_eq!(buf.take_message::<Value>(1024).unwrap(), None);
+        buf.extend(b"2}\n");
+        assert_eq!(buf.take_message::<Value>(1024).unwrap(), Some(serde_json::json!({"id":2})));
+
+        assert_eq!(buf.take_message::<Value>(1024).unwrap(), None);
+        buf.extend(b"");
+        assert!(buf.buf.is_empty());
+    }
+
+    #[test]
+    fn test_take_json_message_in_chunks() {
+        let message = br#" {"result":["}{",{"a":"\"]"}],"id":[1]}"#;
+        let mut buf = JsonMessageBuffer::default();
+        for byte in message.iter() {
+            assert_eq!(buf.take_message::<Value>(1024).unwrap(), None);
+            // Bytes of the incomplete message are scanned once
+            assert_eq!(buf.scan_offset, buf.buf.len());
+            buf.extend(&[*byte]);
+        }
+        assert_eq!(buf.take_message::<Value>(1024).unwrap(), Some(serde_json::json!({"result":["}{",{"a":"\"]"}],"id":[1]})));
+    }
+
+    #[test]
+    fn test_take_json_message_limit() {
+        let mut complete = json_message_buffer(br#"{"result":"0x0123456789"}"#);
+        assert!(complete.take_message::<Value>(16).is_err());
+
+        let mut incomplete = json_message_buffer(br#"{"result":"0x0123456789"#);
+        assert!(incomplete.take_message::<Value>(16).is_err());
+
+        let mut invalid = json_message_buffer(b"}");
+        assert!(invalid.take_message::<Value>(16).is_err());
+    }
+}
@@ -1,12 +1,20 @@
+pub use loom_node_actor_config::DEFAULT_MAX_MESSAGE_SIZE_BYTES;
 pub use node_block_actor::NodeBlockActor;
 pub use node_mempool_actor::NodeMempoolActor;
+pub use resolved_ws::ResolvedWsConnect;
+pub use robust_subscription_manager::{
+    is_io_error, robust_block_subscription_worker, websocket_config, ConnectionStatus, RobustSubscriptionManager, SubscriptionTransport,
+};
 pub use wait_for_node_sync_actor::WaitForNodeSyncOneShotBlockingActor;
 
+mod bounded_ipc;
 mod node_block_actor;
 mod node_block_hash_worker;
 mod node_block_logs_worker;
 mod node_block_state_worker;
 mod node_block_with_tx_worker;
 mod node_mempool_actor;
+mod resolved_ws;
+mod robust_subscription_manager;
 
 mod wait_for_node_sync_actor;
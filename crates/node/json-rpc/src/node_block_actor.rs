@@ -1,12 +1,15 @@
 use alloy_network::Ethereum;
 use alloy_provider::Provider;
+use reqwest::dns::Resolve;
+use std::sync::Arc;
 use tokio::task::JoinHandle;
 
-use crate::node_block_hash_worker::new_node_block_header_worker;
+use crate::node_block_hash_worker::{new_node_block_header_poll_worker, new_node_block_header_worker};
 use crate::node_block_logs_worker::new_node_block_logs_worker;
 use crate::node_block_state_worker::new_node_block_state_worker;
 use crate::node_block_with_tx_worker::new_block_with_tx_worker;
-use loom_core_actors::{Actor, ActorResult, Broadcaster, Producer, WorkerResult};
+use crate::robust_subscription_manager::robust_block_subscription_worker;
+use loom_core_actors::{Actor, ActorResult, Broadcaster, CancellationToken, Producer, WorkerResult};
 use loom_core_actors_macros::Producer;
 use loom_core_blockchain::Blockchain;
 use loom_node_actor_config::NodeBlockActorConfig;
@@ -14,12 +17,16 @@ use loom_node_debug_provider::DebugProviderExt;
 use loom_types_blockchain::LoomDataTypesEthereum;
 use loom_types_events::{MessageBlock, MessageBlockHeader, MessageBlockLogs, MessageBlockStateUpdate};
 
+#[allow(clippy::too_many_arguments)]
 pub fn new_node_block_workers_starter<P>(
     client: P,
     new_block_headers_channel: Option<Broadcaster<MessageBlockHeader>>,
     new_block_with_tx_channel: Option<Broadcaster<MessageBlock>>,
     new_block_logs_channel: Option<Broadcaster<MessageBlockLogs>>,
     new_block_state_update_channel: Option<Broadcaster<MessageBlockStateUpdate>>,
+    config: &NodeBlockActorConfig,
+    dns_resolver: Option<Arc<dyn Resolve>>,
+    cancellation_token: CancellationToken,
 ) -> ActorResult
 where
     P: Provider<Ethereum> + DebugProviderExt + Send + Sync + Clone + 'static,
@@ -28,19 +35,61 @@ where
     let mut tasks: Vec<JoinHandle<WorkerResult>> = Vec::new();
 
     if let Some(channel) = new_block_with_tx_channel {
-        tasks.push(tokio::task::spawn(new_block_with_tx_worker(client.clone(), new_header_internal_channel.clone(), channel)));
+        tasks.push(tokio::task::spawn(new_block_with_tx_worker(
+            client.clone(),
+            new_header_internal_channel.clone(),
+            channel,
+            cancellation_token.clone(),
+        )));
     }
 
     if let Some(channel) = new_block_headers_channel {
-        tasks.push(tokio::task::spawn(new_node_block_header_worker(client.clone(), new_header_internal_channel.clone(), channel)));
+        if let (true, Some(url)) = (config.use_subscription, config.subscription_url.clone()) {
+            // Reconnects to the url when the subscription fails
+            tasks.push(tokio::task::spawn(robust_block_subscription_worker(
+                url,
+                vec![],
+                config.max_message_size_bytes,
+                dns_resolver,
+                new_header_internal_channel.clone(),
+                channel,
+                cancellation_token.clone(),
+            )));
+        } else if config.use_subscription {
+            tasks.push(tokio::task::spawn(new_node_block_header_worker(
+                client.clone(),
+                new_header_internal_channel.clone(),
+                channel,
+                cancellation_token.clone(),
+            )));
+        } else {
+            tasks.push(tokio::task::spawn(new_node_block_header_poll_worker(
+                client.clone(),
+                new_header_internal_channel.clone(),
+                channel,
+                cancellation_token.clone(),
+            )));
+        }
     }
 
     if let Some(channel) = new_block_logs_channel {
-        tasks.push(tokio::task::spawn(new_node_block_logs_worker(client.clone(), new_header_internal_channel.clone(), channel)));
+        tasks.push(tokio::task::spawn(new_node_block_logs_worker(
+            client.clone(),
+            new_header_internal_channel.clone(),
+            channel,
+            config.log_subscription_prefilter,
+            config.log_signatures.clone(),
+            cancellation_token.clone(),
+        )));
     }
 
     if let Some(channel) = new_block_state_update_channel {
-        tasks.push(tokio::task::spawn(new_node_block_state_worker(client.clone(), new_header_internal_channel.clone(), channel)));
+        tasks.push(tokio::task::spawn(new_node_block_state_worker(
+            client.clone(),
+            new_header_internal_channel.clone(),
+            channel,
+            cancellation_token,
+        )));
     }
 
     Ok(tasks)
@@ -50,6 +99,7 @@ where
 pub struct NodeBlockActor<P> {
     client: P,
     config: NodeBlockActorConfig,
+    dns_resolver: Option<Arc<dyn Resolve>>,
     #[producer]
     block_header_channel: Option<Broadcaster<MessageBlockHeader>>,
     #[producer]
@@ -58,6 +108,7 @@ pub struct NodeBlockActor<P> {
     block_logs_channel: Option<Broadcaster<MessageBlockLogs>>,
     #[producer]
     block_state_update_channel: Option<Broadcaster<MessageBlockStateUpdate>>,
+    cancellation_token: CancellationToken,
 }
 
 impl<P> NodeBlockActor<P>
@@ -72,19 +123,36 @@ where
         NodeBlockActor {
             client,
             config,
+            dns_resolver: None,
             block_header_channel: None,
             block_with_tx_channel: None,
             block_logs_channel: None,
             block_state_update_channel: None,
+            cancellation_token: CancellationToken::new(),
         }
     }
 
+    /// Resolver of the WebSocket host of the subscription url
+    pub fn with_dns_resolver(self, dns_resolver: Arc<dyn Resolve>) -> Self {
+        Self { dns_resolver: Some(dns_resolver), ..self }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
     pub fn on_bc(self, bc: &Blockchain<LoomDataTypesEthereum>) -> Self {
+        let mut config = self.config.clone();
+        if bc.log_subscription_prefilter() {
+            config = config.with_log_subscription_prefilter();
+        }
+
         Self {
             block_header_channel: if self.config.block_header { Some(bc.new_block_headers_channel()) } else { None },
             block_with_tx_channel: if self.config.block_with_tx { Some(bc.new_block_with_tx_channel()) } else { None },
             block_logs_channel: if self.config.block_logs { Some(bc.new_block_logs_channel()) } else { None },
             block_state_update_channel: if self.config.block_state_update { Some(bc.new_block_state_update_channel()) } else { None },
+            config,
             ..self
         }
     }
@@ -101,6 +169,9 @@ where
             self.block_with_tx_channel.clone(),
             self.block_logs_channel.clone(),
             self.block_state_update_channel.clone(),
+            &self.config,
+            self.dns_resolver.clone(),
+            self.cancellation_token.clone(),
         )
     }
     fn name(&self) -> &'static str {
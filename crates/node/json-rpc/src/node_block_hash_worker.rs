@@ -1,16 +1,20 @@
 use std::collections::HashMap;
+use std::time::Duration;
 
 use alloy_network::Ethereum;
 use alloy_primitives::BlockHash;
 use alloy_provider::Provider;
 use alloy_pubsub::PubSubConnect;
-use alloy_rpc_types::Header;
+use alloy_rpc_types::{BlockNumberOrTag, BlockTransactionsKind, Header};
 use chrono::Utc;
 use eyre::Result;
 use futures::StreamExt;
-use loom_core_actors::{run_sync, Broadcaster, WorkerResult};
+use loom_core_actors::{run_sync, Broadcaster, CancellationToken, WorkerResult};
 use loom_types_events::{BlockHeader, MessageBlockHeader};
-use tracing::{error, info};
+use tracing::{debug, error, info};
+
+/// Interval of `eth_blockNumber` requests of the polling header worker
+pub const BLOCK_POLL_INTERVAL: Duration = Duration::from_millis(250);
 
 #[allow(dead_code)]
 pub async fn new_node_block_hash_worker<P: Provider + PubSubConnect>(client: P, sender: Broadcaster<Header>) -> Result<()> {
@@ -42,6 +46,7 @@ pub async fn new_node_block_header_worker<P>(
     client: P,
     new_block_header_channel: Broadcaster<Header>,
     block_header_channel: Broadcaster<MessageBlockHeader>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult
 where
     P: Provider<Ethereum> + Send + Sync + Clone + 'static,
@@ -54,6 +59,9 @@ where
 
     loop {
         tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Node block header worker cancelled".to_string());
+            }
             block_msg = stream.next() => {
                 if let Some(block_header) = block_msg {
                     let block_hash = block_header.hash;
@@ -72,3 +80,61 @@ where
         }
     }
 }
+
+/// Polls `eth_blockNumber` and fetches headers of new blocks with `eth_getBlockByNumber`, for transports without
+/// subscription support
+pub async fn new_node_block_header_poll_worker<P>(
+    client: P,
+    new_block_header_channel: Broadcaster<Header>,
+    block_header_channel: Broadcaster<MessageBlockHeader>,
+    cancellation_token: CancellationToken,
+) -> WorkerResult
+where
+    P: Provider<Ethereum> + Send + Sync + Clone + 'static,
+{
+    info!("Starting node block header poll worker");
+    let mut last_block_number = client.get_block_number().await?.saturating_sub(1);
+    let mut interval = tokio::time::interval(BLOCK_POLL_INTERVAL);
+
+    loop {
+        tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Node block header poll worker cancelled".to_string());
+            }
+            _ = interval.tick() => {}
+        }
+        let block_number = match client.get_block_number().await {
+            Ok(block_number) => block_number,
+            Err(e) => {
+                error!("Failed to get block number : {}", e);
+                continue;
+            }
+        };
+
+        // Blocks missed between two polls are fetched in order
+        while last_block_number < block_number {
+            let next_block_number = last_block_number + 1;
+            let block_header =
+                match client.get_block_by_number(BlockNumberOrTag::Number(next_block_number), BlockTransactionsKind::Hashes).await {
+                    Ok(Some(block)) => block.header,
+                    Ok(None) => {
+                        debug!(next_block_number, "Block is not available yet");
+                        break;
+                    }
+                    Err(e) => {
+                        error!("Failed to get block {} : {}", next_block_number, e);
+                        break;
+                    }
+                };
+            last_block_number = next_block_number;
+            info!("Block hash received: {:?}", block_header.hash);
+
+            if let Err(e) = new_block_header_channel.send(block_header.clone()) {
+                error!("Block hash broadcaster error  {}", e);
+            }
+            if let Err(e) = block_header_channel.send(MessageBlockHeader::new_with_time(BlockHeader::new(block_header))) {
+                error!("Block header broadcaster error {}", e);
+            }
+        }
+    }
+}
@@ -1,29 +1,51 @@
 use alloy_network::{primitives::HeaderResponse, Network};
 use std::time::Duration;
 
+use alloy_primitives::{BlockHash, B256};
 use alloy_provider::Provider;
 use alloy_rpc_types::{Filter, Header};
 use tokio::sync::broadcast::{Receiver, error::RecvError};
 use tracing::{debug, error, warn};
 
-use loom_core_actors::{subscribe, Broadcaster, WorkerResult};
+use loom_core_actors::{subscribe, Broadcaster, CancellationToken, WorkerResult};
 use loom_types_events::{BlockLogs, Message, MessageBlockLogs};
 
+/// Logs of the block, only the events with the given signatures when there are any
+fn block_logs_filter(block_hash: BlockHash, log_signatures: &[B256]) -> Filter {
+    let filter = Filter::new().at_block_hash(block_hash);
+    if log_signatures.is_empty() {
+        filter
+    } else {
+        filter.event_signature(log_signatures.to_vec())
+    }
+}
+
 pub async fn new_node_block_logs_worker<N: Network, P: Provider<N> + Send + Sync + 'static>(
     client: P,
     block_header_receiver: Broadcaster<Header>,
     sender: Broadcaster<MessageBlockLogs>,
+    log_subscription_prefilter: bool,
+    log_signatures: Vec<B256>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult {
+    let log_signatures = if log_subscription_prefilter { log_signatures } else { Vec::new() };
+    if log_subscription_prefilter && log_signatures.is_empty() {
+        warn!("No log signatures for the log subscription prefilter, all logs are requested");
+    }
     // Subscribe to the block header channel with enhanced error handling
     let mut receiver = block_header_receiver.subscribe();
     
     // Keep-alive mechanism - periodically check channel health
     let sender_clone = sender.clone();
     let block_header_receiver_clone = block_header_receiver.clone();
+    let keep_alive_cancellation_token = cancellation_token.clone();
     tokio::spawn(async move {
         let mut interval = tokio::time::interval(Duration::from_secs(30));
         loop {
-            interval.tick().await;
+            tokio::select! {
+                _ = keep_alive_cancellation_token.cancelled() => break,
+                _ = interval.tick() => {}
+            }
             if !sender_clone.is_healthy() {
                 warn!("BlockLogs sender channel appears unhealthy, checking status");
                 // Attempt to send a keep-alive message or reconnect if needed
@@ -38,7 +60,13 @@ pub async fn new_node_block_logs_worker<N: Network, P: Provider<N> + Send + Sync
 
     loop {
         // Attempt to receive a message with error handling
-        let block_header = match receiver.recv().await {
+        let block_header = tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Node block logs worker cancelled".to_string());
+            }
+            block_header = receiver.recv() => block_header,
+        };
+        let block_header = match block_header {
             Ok(header) => header,
             Err(e) => {
                 error!("Error receiving block header: {}", e);
@@ -62,7 +90,7 @@ pub async fn new_node_block_logs_worker<N: Network, P: Provider<N> + Send + Sync
 
         let (block_number, block_hash) = (block_header.number, block_header.hash);
         debug!("BlockLogs header received {} {}", block_number, block_hash);
-        let filter = Filter::new().at_block_hash(block_header.hash());
+        let filter = block_logs_filter(block_header.hash(), &log_signatures);
 
         let mut err_counter = 0;
         let max_retries = 5; // Increased from 3 to 5 for more resilience
@@ -126,3 +154,23 @@ pub async fn new_node_block_logs_worker_reth<N: Network, P: Provider<N> + Send +
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::keccak256;
+
+    #[test]
+    fn test_block_logs_filter() {
+        let block_hash = BlockHash::repeat_byte(1);
+        let pool_created = keccak256("PoolCreated(address,address,int24,address)");
+
+        let filter = block_logs_filter(block_hash, &[pool_created]);
+        assert_eq!(filter.get_block_hash(), Some(block_hash));
+        assert!(filter.topics[0].matches(&pool_created));
+        assert!(!filter.topics[0].matches(&B256::repeat_byte(2)));
+
+        let filter = block_logs_filter(block_hash, &[]);
+        assert!(filter.topics[0].is_empty());
+    }
+}
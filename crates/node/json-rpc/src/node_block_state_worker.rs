@@ -7,7 +7,7 @@ use std::time::Duration;
 use tokio::sync::broadcast::error::RecvError;
 use tracing::{debug, error, info, warn};
 
-use loom_core_actors::{subscribe, Broadcaster, WorkerResult};
+use loom_core_actors::{subscribe, Broadcaster, CancellationToken, WorkerResult};
 use loom_node_debug_provider::DebugProviderExt;
 use loom_types_blockchain::{debug_trace_block, fetch_block_trace_chunked};
 use loom_types_events::{BlockStateUpdate, Message, MessageBlockStateUpdate};
@@ -20,6 +20,7 @@ pub async fn new_node_block_state_worker<P>(
     client: P,
     block_header_receiver: Broadcaster<Header>,
     sender: Broadcaster<MessageBlockStateUpdate>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult
 where
     P: Provider<Ethereum> + DebugProviderExt<Ethereum> + Send + Sync + Clone + 'static,
@@ -30,10 +31,14 @@ where
     // Keep-alive mechanism - periodically check channel health
     let sender_clone = sender.clone();
     let block_header_receiver_clone = block_header_receiver.clone();
+    let keep_alive_cancellation_token = cancellation_token.clone();
     tokio::spawn(async move {
         let mut interval = tokio::time::interval(Duration::from_secs(30));
         loop {
-            interval.tick().await;
+            tokio::select! {
+                _ = keep_alive_cancellation_token.cancelled() => break,
+                _ = interval.tick() => {}
+            }
             if !sender_clone.is_healthy() {
                 warn!("BlockState sender channel appears unhealthy, checking status");
                 // Attempt to send a keep-alive message or reconnect if needed
@@ -47,7 +52,13 @@ where
 
     loop {
         // Attempt to receive a message with enhanced error handling
-        let block_header = match receiver.recv().await {
+        let block_header = tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Node block state worker cancelled".to_string());
+            }
+            block_header = receiver.recv() => block_header,
+        };
+        let block_header = match block_header {
             Ok(header) => header,
             Err(e) => {
                 error!("Error receiving block header in state worker: {}", e);
@@ -1,7 +1,7 @@
 use alloy_network::{primitives::HeaderResponse, Ethereum};
 use alloy_provider::Provider;
 use alloy_rpc_types::{BlockId, BlockTransactionsKind, Header};
-use loom_core_actors::{subscribe, Broadcaster, WorkerResult};
+use loom_core_actors::{subscribe, Broadcaster, CancellationToken, WorkerResult};
 use loom_types_blockchain::fetch_block_with_transactions_chunked;
 use loom_types_events::{BlockUpdate, Message, MessageBlock};
 use std::time::Duration;
@@ -31,6 +31,7 @@ pub async fn new_block_with_tx_worker<P>(
     client: P,
     block_header_receiver: Broadcaster<Header>,
     sender: Broadcaster<MessageBlock>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult
 where
     P: Provider<Ethereum> + Send + Sync + Clone + 'static,
@@ -39,7 +40,13 @@ where
     subscribe!(block_header_receiver);
 
     loop {
-        if let Ok(block_header) = block_header_receiver.recv().await {
+        let block_header = tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Node block with tx worker cancelled".to_string());
+            }
+            block_header = block_header_receiver.recv() => block_header,
+        };
+        if let Ok(block_header) = block_header {
             let (block_number, block_hash) = (block_header.number, block_header.hash);
             info!("BlockWithTx header received {} {}", block_number, block_hash);
 
@@ -2,24 +2,35 @@ use alloy_network::{Ethereum, TransactionResponse};
 use alloy_primitives::TxHash;
 use alloy_provider::Provider;
 use futures::StreamExt;
-use tracing::error;
+use tracing::{debug, error};
 
-use loom_core_actors::{Actor, ActorResult, Broadcaster, Producer, WorkerResult};
+use loom_core_actors::{Actor, ActorResult, Broadcaster, CancellationToken, Producer, WorkerResult};
 use loom_core_actors_macros::*;
 use loom_core_blockchain::Blockchain;
+use loom_node_actor_config::MempoolActorConfig;
 use loom_types_blockchain::LoomDataTypesEthereum;
 use loom_types_blockchain::MempoolTx;
 use loom_types_events::{MessageMempoolDataUpdate, NodeMempoolDataUpdate};
 
 /// Worker listens for new transactions in the node mempool and broadcasts [`MessageMempoolDataUpdate`].
-pub async fn new_node_mempool_worker<P>(client: P, name: String, mempool_tx: Broadcaster<MessageMempoolDataUpdate>) -> WorkerResult
+pub async fn new_node_mempool_worker<P>(
+    client: P,
+    name: String,
+    mempool_tx: Broadcaster<MessageMempoolDataUpdate>,
+    cancellation_token: CancellationToken,
+) -> WorkerResult
 where
     P: Provider<Ethereum> + Send + Sync + 'static,
 {
     let mempool_subscription = client.subscribe_full_pending_transactions().await?;
     let mut stream = mempool_subscription.into_stream();
 
-    while let Some(tx) = stream.next().await {
+    loop {
+        let tx = tokio::select! {
+            _ = cancellation_token.cancelled() => break,
+            tx = stream.next() => tx,
+        };
+        let Some(tx) = tx else { break };
         let tx_hash: TxHash = tx.tx_hash();
         let update_msg: MessageMempoolDataUpdate = MessageMempoolDataUpdate::new_with_source(
             NodeMempoolDataUpdate { tx_hash, mempool_tx: MempoolTx { tx: Some(tx), ..MempoolTx::default() } },
@@ -33,12 +44,56 @@ where
     Ok(name)
 }
 
+/// Worker for nodes without full pending transaction subscription, transactions are fetched by the subscribed hashes.
+pub async fn new_node_mempool_hash_worker<P>(
+    client: P,
+    name: String,
+    mempool_tx: Broadcaster<MessageMempoolDataUpdate>,
+    cancellation_token: CancellationToken,
+) -> WorkerResult
+where
+    P: Provider<Ethereum> + Send + Sync + Clone + 'static,
+{
+    let mempool_subscription = client.subscribe_pending_transactions().await?;
+    let mut stream = mempool_subscription.into_stream();
+
+    loop {
+        let tx_hash = tokio::select! {
+            _ = cancellation_token.cancelled() => break,
+            tx_hash = stream.next() => tx_hash,
+        };
+        let Some(tx_hash) = tx_hash else { break };
+        let client = client.clone();
+        let name = name.clone();
+        let mempool_tx = mempool_tx.clone();
+        // Fetched concurrently, a slow response must not delay the following transactions
+        tokio::task::spawn(async move {
+            match client.get_transaction_by_hash(tx_hash).await {
+                Ok(Some(tx)) => {
+                    let update_msg: MessageMempoolDataUpdate = MessageMempoolDataUpdate::new_with_source(
+                        NodeMempoolDataUpdate { tx_hash, mempool_tx: MempoolTx { tx: Some(tx), ..MempoolTx::default() } },
+                        name,
+                    );
+                    if let Err(e) = mempool_tx.send(update_msg) {
+                        error!("mempool_tx.send error : {}", e);
+                    }
+                }
+                Ok(None) => debug!(%tx_hash, "Pending transaction not found"),
+                Err(e) => error!(%tx_hash, "get_transaction_by_hash error : {}", e),
+            }
+        });
+    }
+    Ok(name)
+}
+
 #[derive(Producer)]
 pub struct NodeMempoolActor<P> {
     name: &'static str,
     client: P,
+    config: MempoolActorConfig,
     #[producer]
     mempool_tx: Option<Broadcaster<MessageMempoolDataUpdate>>,
+    cancellation_token: CancellationToken,
 }
 
 impl<P> NodeMempoolActor<P>
@@ -46,13 +101,27 @@ where
     P: Provider<Ethereum> + Send + Sync + Clone + 'static,
 {
     pub fn new(client: P) -> NodeMempoolActor<P> {
-        NodeMempoolActor { client, name: "NodeMempoolActor", mempool_tx: None }
+        NodeMempoolActor {
+            client,
+            name: "NodeMempoolActor",
+            config: MempoolActorConfig::default(),
+            mempool_tx: None,
+            cancellation_token: CancellationToken::new(),
+        }
     }
 
     pub fn with_name(self, name: String) -> Self {
         Self { name: Box::leak(name.into_boxed_str()), ..self }
     }
 
+    pub fn with_config(self, config: MempoolActorConfig) -> Self {
+        Self { config, ..self }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
     fn get_name(&self) -> &'static str {
         self.name
     }
@@ -67,8 +136,21 @@ where
     P: Provider<Ethereum> + Send + Sync + Clone + 'static,
 {
     fn start(&self) -> ActorResult {
-        let task =
-            tokio::task::spawn(new_node_mempool_worker(self.client.clone(), self.name.to_string(), self.mempool_tx.clone().unwrap()));
+        let task = if self.config.full_tx_subscription {
+            tokio::task::spawn(new_node_mempool_worker(
+                self.client.clone(),
+                self.name.to_string(),
+                self.mempool_tx.clone().unwrap(),
+                self.cancellation_token.clone(),
+            ))
+        } else {
+            tokio::task::spawn(new_node_mempool_hash_worker(
+                self.client.clone(),
+                self.name.to_string(),
+                self.mempool_tx.clone().unwrap(),
+                self.cancellation_token.clone(),
+            ))
+        };
         Ok(vec![task])
     }
 
@@ -0,0 +1,126 @@
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use alloy_json_rpc::PubSubItem;
+use alloy_pubsub::{ConnectionHandle, ConnectionInterface, PubSubConnect};
+use alloy_transport::{TransportErrorKind, TransportResult};
+use alloy_transport_ws::WebSocketConfig;
+use futures::{SinkExt, StreamExt};
+use reqwest::dns::{Name, Resolve};
+use tokio::net::TcpStream;
+use tokio::time::{interval_at, Instant};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tracing::{debug, error};
+use url::Url;
+
+// Ping interval of an idle connection
+const KEEPALIVE: Duration = Duration::from_secs(10);
+
+/// WebSocket connection with the host resolved by `resolver` instead of the system resolver used by `WsConnect`. TLS is
+/// negotiated with the host name of the url
+#[derive(Clone)]
+pub struct ResolvedWsConnect {
+    url: String,
+    config: WebSocketConfig,
+    resolver: Arc<dyn Resolve>,
+}
+
+impl ResolvedWsConnect {
+    pub fn new(url: String, config: WebSocketConfig, resolver: Arc<dyn Resolve>) -> Self {
+        Self { url, config, resolver }
+    }
+
+    async fn resolve(&self) -> TransportResult<Vec<SocketAddr>> {
+        let url = Url::parse(&self.url).map_err(TransportErrorKind::custom)?;
+        let host = url.host_str().ok_or_else(|| TransportErrorKind::custom_str("WS url has no host"))?;
+        let port = url.port_or_known_default().ok_or_else(|| TransportErrorKind::custom_str("WS url has no port"))?;
+        let name = Name::from_str(host.trim_start_matches('[').trim_end_matches(']')).map_err(TransportErrorKind::custom)?;
+        let addrs = self.resolver.resolve(name).await.map_err(|e| TransportErrorKind::custom_str(&e.to_string()))?;
+        Ok(addrs.map(|addr| SocketAddr::new(addr.ip(), port)).collect())
+    }
+}
+
+impl PubSubConnect for ResolvedWsConnect {
+    fn is_local(&self) -> bool {
+        alloy_transport::utils::guess_local_url(&self.url)
+    }
+
+    async fn connect(&self) -> TransportResult<ConnectionHandle> {
+        let addrs = self.resolve().await?;
+        let stream = TcpStream::connect(addrs.as_slice()).await.map_err(TransportErrorKind::custom)?;
+        let (socket, _) = tokio_tungstenite::client_async_tls_with_config(self.url.as_str(), stream, Some(self.config), None)
+            .await
+            .map_err(TransportErrorKind::custom)?;
+
+        let (handle, interface) = ConnectionHandle::new();
+        tokio::spawn(resolved_ws_backend(socket, interface));
+        Ok(handle)
+    }
+}
+
+async fn resolved_ws_backend(mut socket: WebSocketStream<MaybeTlsStream<TcpStream>>, mut interface: ConnectionInterface) {
+    let mut keepalive = interval_at(Instant::now() + KEEPALIVE, KEEPALIVE);
+
+    let errored = loop {
+        tokio::select! {
+            biased;
+            item = interface.recv_from_frontend() => {
+                match item {
+                    Some(msg) => {
+                        keepalive.reset();
+                        if let Err(e) = socket.send(Message::Text(msg.get().to_owned().into())).await {
+                            error!("WS write error : {}", e);
+                            break true;
+                        }
+                    }
+                    None => {
+                        debug!("WS frontend dropped");
+                        break false;
+                    }
+                }
+            }
+            _ = keepalive.tick() => {
+                if let Err(e) = socket.send(Message::Ping(Default::default())).await {
+                    error!("WS keepalive error : {}", e);
+                    break true;
+                }
+            }
+            msg = socket.next() => {
+                match msg {
+                    Some(Ok(Message::Text(text))) => match serde_json::from_str::<PubSubItem>(text.as_str()) {
+                        Ok(item) => {
+                            if interface.send_to_frontend(item).is_err() {
+                                debug!("WS frontend dropped");
+                                return;
+                            }
+                        }
+                        Err(e) => {
+                            error!("WS message error : {}", e);
+                            break true;
+                        }
+                    },
+                    Some(Ok(Message::Binary(_))) => {
+                        error!("WS binary message received, expected text");
+                        break true;
+                    }
+                    Some(Ok(Message::Close(_))) | None => {
+                        error!("WS server has gone away");
+                        break true;
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => {
+                        error!("WS read error : {}", e);
+                        break true;
+                    }
+                }
+            }
+        }
+    };
+
+    if errored {
+        interface.close_with_error();
+    }
+}
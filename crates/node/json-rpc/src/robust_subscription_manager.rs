@@ -1,16 +1,62 @@
+use crate::bounded_ipc::BoundedIpcConnect;
+use crate::resolved_ws::ResolvedWsConnect;
 use alloy_network::Ethereum;
-use alloy_provider::{Provider, ProviderBuilder, WsConnect};
+use alloy_primitives::BlockHash;
+use alloy_provider::{Provider, RootProvider};
+use alloy_rpc_client::{ClientBuilder, RpcClient};
 use alloy_rpc_types::Header;
-use eyre::{eyre, Result};
+use alloy_transport::{RpcError, TransportError, TransportErrorKind};
+use alloy_transport_ws::{WebSocketConfig, WsConnect};
+use chrono::Utc;
+use eyre::{eyre, Result, WrapErr};
+use futures::StreamExt;
+use loom_core_actors::{Broadcaster, CancellationToken, WorkerResult};
+use loom_node_actor_config::DEFAULT_MAX_MESSAGE_SIZE_BYTES;
+use loom_types_events::{BlockHeader, MessageBlockHeader};
+use reqwest::dns::Resolve;
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::broadcast;
 use tokio::time::{interval, sleep, timeout};
 use tracing::{debug, error, info, warn};
-use url::Url;
-use futures_util::StreamExt;
 
-/// Robust subscription manager with automatic reconnection and health monitoring
+/// Transport of a node endpoint, `ws://` and `wss://` urls are WebSocket, anything else is an IPC socket path
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubscriptionTransport {
+    Ws,
+    Ipc,
+}
+
+impl SubscriptionTransport {
+    pub fn from_url(url: &str) -> Self {
+        if url.starts_with("ws://") || url.starts_with("wss://") {
+            Self::Ws
+        } else {
+            Self::Ipc
+        }
+    }
+}
+
+/// IPC transport reports a missing or closed socket as an IO error
+pub fn is_io_error(err: &TransportError) -> bool {
+    match err {
+        RpcError::Transport(TransportErrorKind::BackendGone) => true,
+        RpcError::Transport(TransportErrorKind::Custom(e)) => e.downcast_ref::<std::io::Error>().is_some(),
+        _ => false,
+    }
+}
+
+/// WebSocket config accepting messages and frames up to `max_message_size_bytes`
+// WebSocketConfig is non exhaustive in newer tungstenite versions, so fields are assigned
+#[allow(clippy::field_reassign_with_default)]
+pub fn websocket_config(max_message_size_bytes: usize) -> WebSocketConfig {
+    let mut config = WebSocketConfig::default();
+    config.max_message_size = Some(max_message_size_bytes);
+    config.max_frame_size = Some(max_message_size_bytes);
+    config
+}
+
+/// Robust subscription manager with automatic reconnection and health monitoring for WebSocket and IPC endpoints
 pub struct RobustSubscriptionManager {
     primary_url: String,
     backup_urls: Vec<String>,
@@ -20,6 +66,8 @@ pub struct RobustSubscriptionManager {
     reconnect_delay: Duration,
     health_check_interval: Duration,
     connection_timeout: Duration,
+    max_message_size_bytes: usize,
+    dns_resolver: Option<Arc<dyn Resolve>>,
 }
 
 impl RobustSubscriptionManager {
@@ -33,42 +81,86 @@ impl RobustSubscriptionManager {
             reconnect_delay: Duration::from_secs(2),
             health_check_interval: Duration::from_secs(30),
             connection_timeout: Duration::from_secs(10),
+            max_message_size_bytes: DEFAULT_MAX_MESSAGE_SIZE_BYTES,
+            dns_resolver: None,
         }
     }
 
-    /// Start robust block header subscription with automatic reconnection
+    pub fn with_max_reconnect_attempts(self, max_reconnect_attempts: usize) -> Self {
+        Self { max_reconnect_attempts, ..self }
+    }
+
+    /// Largest message accepted from a WebSocket or IPC endpoint
+    pub fn with_max_message_size_bytes(self, max_message_size_bytes: usize) -> Self {
+        Self { max_message_size_bytes, ..self }
+    }
+
+    /// Resolver of the WebSocket hosts, the system resolver is used without it. IPC sockets are local paths.
+    pub fn with_dns_resolver(self, dns_resolver: Option<Arc<dyn Resolve>>) -> Self {
+        Self { dns_resolver, ..self }
+    }
+
+    /// Connect a client to the current url, failed connections are retried with the same backoff and url rotation
+    /// as subscriptions
+    pub async fn connect_robust_client(&mut self) -> Result<RpcClient> {
+        loop {
+            let current_url = self.get_current_url();
+            let err = match timeout(self.connection_timeout, self.connect_client(&current_url)).await {
+                Ok(Ok(client)) => {
+                    self.reset_reconnection_state();
+                    return Ok(client);
+                }
+                Ok(Err(e)) => e,
+                Err(_) => eyre!("Connection timeout"),
+            };
+            error!("Connection to {} failed: {:#}", current_url, err);
+
+            if !Self::should_reconnect(&current_url, &err) {
+                return Err(err);
+            }
+            if self.reconnect_attempts >= self.max_reconnect_attempts {
+                error!("Max reconnection attempts reached, giving up");
+                return Err(eyre!("Max reconnection attempts reached"));
+            }
+            self.wait_before_reconnect().await;
+        }
+    }
+
+    /// Start robust block header subscription with automatic reconnection. Headers received again after a reconnect are
+    /// sent once
     pub async fn start_robust_block_subscription(
         &mut self,
-        sender: broadcast::Sender<Header>,
+        new_block_header_channel: Broadcaster<Header>,
+        block_header_channel: Broadcaster<MessageBlockHeader>,
     ) -> Result<()> {
         info!("Starting robust block subscription manager");
-        
+        let mut block_processed: HashMap<BlockHash, chrono::DateTime<Utc>> = HashMap::new();
+
         loop {
             let current_url = self.get_current_url();
             info!("Attempting connection to: {}", current_url);
-            
-            match self.try_connect_and_subscribe(&current_url, sender.clone()).await {
+
+            match self.try_connect_and_subscribe(&current_url, &new_block_header_channel, &block_header_channel, &mut block_processed).await
+            {
                 Ok(_) => {
                     info!("Block subscription ended normally");
                     self.reset_reconnection_state();
                     break;
                 }
                 Err(e) => {
-                    error!("Block subscription failed: {}", e);
+                    error!("Block subscription failed: {:#}", e);
+                    
+                    if !Self::should_reconnect(&current_url, &e) {
+                        error!("Block subscription error is not recoverable, giving up");
+                        return Err(e);
+                    }
                     
                     if self.reconnect_attempts >= self.max_reconnect_attempts {
                         error!("Max reconnection attempts reached, giving up");
                         return Err(eyre!("Max reconnection attempts reached"));
                     }
                     
-                    self.reconnect_attempts += 1;
-                    self.switch_to_next_url();
-                    
-                    // Exponential backoff with jitter
-                    let delay = self.calculate_backoff_delay();
-                    warn!("Waiting {} seconds before reconnection attempt {} of {}", 
-                          delay.as_secs(), self.reconnect_attempts, self.max_reconnect_attempts);
-                    sleep(delay).await;
+                    self.wait_before_reconnect().await;
                 }
             }
         }
@@ -76,34 +168,59 @@ impl RobustSubscriptionManager {
         Ok(())
     }
 
+    /// WebSocket failures are always retried. IPC failures are retried on IO errors of the transport and on ended or stale
+    /// subscriptions, other IPC errors like a node without subscriptions support are not.
+    fn should_reconnect(url: &str, err: &eyre::Report) -> bool {
+        match SubscriptionTransport::from_url(url) {
+            SubscriptionTransport::Ws => true,
+            SubscriptionTransport::Ipc => err.downcast_ref::<TransportError>().is_none_or(is_io_error),
+        }
+    }
+
+    /// Switch to the next url and wait with exponential backoff
+    async fn wait_before_reconnect(&mut self) {
+        self.reconnect_attempts += 1;
+        self.switch_to_next_url();
+        
+        // Exponential backoff with jitter
+        let delay = self.calculate_backoff_delay();
+        warn!("Waiting {} seconds before reconnection attempt {} of {}", 
+              delay.as_secs(), self.reconnect_attempts, self.max_reconnect_attempts);
+        sleep(delay).await;
+    }
+
     /// Try to connect and subscribe to block headers
     async fn try_connect_and_subscribe(
-        &self,
+        &mut self,
         url: &str,
-        sender: broadcast::Sender<Header>,
+        new_block_header_channel: &Broadcaster<Header>,
+        block_header_channel: &Broadcaster<MessageBlockHeader>,
+        block_processed: &mut HashMap<BlockHash, chrono::DateTime<Utc>>,
     ) -> Result<()> {
-        info!("Connecting to WebSocket: {}", url);
+        info!("Connecting to {:?} endpoint: {}", SubscriptionTransport::from_url(url), url);
         
         // Create provider with timeout
-        let provider = timeout(
+        let client = timeout(
             self.connection_timeout,
-            self.create_provider(url)
+            self.connect_client(url)
         ).await
-        .map_err(|_| eyre!("Connection timeout"))?
-        .map_err(|e| eyre!("Failed to create provider: {}", e))?;
+        .map_err(|_| eyre!("Connection timeout"))??;
+        let provider = RootProvider::<Ethereum>::new(client);
         
         info!("Successfully connected, starting block subscription");
         
-        // Create subscription with timeout
+        // Create subscription with timeout, transport errors are kept to tell IPC IO errors apart
         let sub = timeout(
             self.connection_timeout,
             provider.subscribe_blocks()
         ).await
         .map_err(|_| eyre!("Subscription timeout"))?
-        .map_err(|e| eyre!("Failed to create block subscription: {}", e))?;
+        .wrap_err("Failed to create block subscription")?;
         
         let mut stream = sub.into_stream();
-        
+        // Attempts count from the last working subscription
+        self.reconnect_attempts = 0;
+
         // Health check interval
         let mut health_check = interval(self.health_check_interval);
         let mut last_block_time = std::time::Instant::now();
@@ -116,32 +233,24 @@ impl RobustSubscriptionManager {
                 // Handle incoming blocks
                 block_result = stream.next() => {
                     match block_result {
-                        Some(Ok(block)) => {
+                        Some(block) => {
                             last_block_time = std::time::Instant::now();
                             block_count += 1;
                             
                             debug!("Received block #{} (hash: {}, total: {})", 
                                    block.number, block.hash, block_count);
                             
-                            // Send to subscribers
-                            match sender.send(block) {
-                                Ok(subscriber_count) => {
-                                    if subscriber_count == 0 {
-                                        warn!("No subscribers for block updates");
-                                    } else {
-                                        debug!("Block sent to {} subscribers", subscriber_count);
-                                    }
+                            if let std::collections::hash_map::Entry::Vacant(e) = block_processed.entry(block.hash) {
+                                e.insert(Utc::now());
+                                if let Err(e) = new_block_header_channel.send(block.clone()) {
+                                    error!("Block hash broadcaster error  {}", e);
                                 }
-                                Err(e) => {
-                                    warn!("Failed to send block to subscribers: {}", e);
-                                    // Continue anyway - subscribers might reconnect
+                                if let Err(e) = block_header_channel.send(MessageBlockHeader::new_with_time(BlockHeader::new(block))) {
+                                    error!("Block header broadcaster error {}", e);
                                 }
+                                block_processed.retain(|_, &mut v| v > Utc::now() - chrono::TimeDelta::minutes(10));
                             }
                         }
-                        Some(Err(e)) => {
-                            error!("Error in block stream: {}", e);
-                            return Err(eyre!("Block stream error: {}", e));
-                        }
                         None => {
                             warn!("Block stream ended unexpectedly");
                             return Err(eyre!("Block stream ended"));
@@ -166,15 +275,24 @@ impl RobustSubscriptionManager {
         }
     }
 
-    /// Create a new provider from URL
-    async fn create_provider(&self, url: &str) -> Result<impl Provider<Ethereum> + Clone> {
-        let ws = WsConnect::new(url);
-        let provider = ProviderBuilder::new()
-            .on_ws(ws)
-            .await
-            .map_err(|e| eyre!("Failed to create WebSocket provider: {}", e))?;
+    /// Create a new WebSocket or IPC client from URL
+    async fn connect_client(&self, url: &str) -> Result<RpcClient> {
+        let client = match SubscriptionTransport::from_url(url) {
+            SubscriptionTransport::Ws => {
+                let config = websocket_config(self.max_message_size_bytes);
+                match &self.dns_resolver {
+                    Some(dns_resolver) => {
+                        ClientBuilder::default().pubsub(ResolvedWsConnect::new(url.to_string(), config, dns_resolver.clone())).await
+                    }
+                    None => ClientBuilder::default().ws(WsConnect { url: url.to_string(), auth: None, config: Some(config) }).await,
+                }
+            }
+            SubscriptionTransport::Ipc => {
+                ClientBuilder::default().pubsub(BoundedIpcConnect::new(url.to_string(), self.max_message_size_bytes)).await
+            }
+        };
         
-        Ok(provider)
+        client.wrap_err_with(|| format!("Failed to connect to {url}"))
     }
 
     /// Get the current URL to use
@@ -234,36 +352,116 @@ pub struct ConnectionStatus {
     pub is_connected: bool,
 }
 
-/// Enhanced block subscription worker using the robust manager
+/// Block header worker using the robust manager, subscription failures are retried with url rotation and the manager is
+/// restarted with fresh state after `max_reconnect_attempts`
 pub async fn robust_block_subscription_worker(
     primary_url: String,
     backup_urls: Vec<String>,
-    sender: broadcast::Sender<Header>,
-) -> Result<()> {
-    let mut manager = RobustSubscriptionManager::new(primary_url, backup_urls);
-    
+    max_message_size_bytes: usize,
+    dns_resolver: Option<Arc<dyn Resolve>>,
+    new_block_header_channel: Broadcaster<Header>,
+    block_header_channel: Broadcaster<MessageBlockHeader>,
+    cancellation_token: CancellationToken,
+) -> WorkerResult {
+    let new_manager = || {
+        RobustSubscriptionManager::new(primary_url.clone(), backup_urls.clone())
+            .with_max_message_size_bytes(max_message_size_bytes)
+            .with_dns_resolver(dns_resolver.clone())
+    };
+    let mut manager = new_manager();
+
     loop {
-        match manager.start_robust_block_subscription(sender.clone()).await {
+        let subscription_result = tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                info!("Block subscription cancelled");
+                break;
+            }
+            subscription_result = manager.start_robust_block_subscription(new_block_header_channel.clone(), block_header_channel.clone()) => {
+                subscription_result
+            }
+        };
+        match subscription_result {
             Ok(_) => {
                 info!("Block subscription completed successfully");
                 break;
             }
             Err(e) => {
                 error!("Block subscription failed permanently: {}", e);
-                
+
                 // Wait before trying to restart the entire subscription system
-                sleep(Duration::from_secs(60)).await;
-                
-                // Create a new manager to reset all state
-                manager = RobustSubscriptionManager::new(
-                    manager.primary_url.clone(),
-                    manager.backup_urls.clone()
-                );
-                
+                tokio::select! {
+                    _ = cancellation_token.cancelled() => break,
+                    _ = sleep(Duration::from_secs(60)) => {}
+                }
+
+                manager = new_manager();
                 warn!("Restarting entire subscription system with fresh state");
             }
         }
     }
-    
-    Ok(())
-}
\ No newline at end of file
+
+    Ok("robust_block_subscription_worker".to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_subscription_transport_from_url() {
+        assert_eq!(SubscriptionTransport::from_url("ws://localhost:8546"), SubscriptionTransport::Ws);
+        assert_eq!(SubscriptionTransport::from_url("wss://node.example"), SubscriptionTransport::Ws);
+        assert_eq!(SubscriptionTransport::from_url("/tmp/reth.ipc"), SubscriptionTransport::Ipc);
+    }
+
+    #[test]
+    fn test_should_reconnect() {
+        let io_error: TransportError = TransportErrorKind::custom(std::io::Error::from(std::io::ErrorKind::NotFound));
+        let backend_gone: TransportError = TransportErrorKind::backend_gone();
+        let unsupported: TransportError = TransportErrorKind::pubsub_unavailable();
+
+        assert!(is_io_error(&io_error));
+        assert!(is_io_error(&backend_gone));
+        assert!(!is_io_error(&unsupported));
+
+        let ipc = "/tmp/reth.ipc";
+        assert!(RobustSubscriptionManager::should_reconnect(ipc, &eyre::Report::from(io_error)));
+        assert!(RobustSubscriptionManager::should_reconnect(ipc, &eyre!("Block stream ended")));
+        assert!(!RobustSubscriptionManager::should_reconnect(ipc, &eyre::Report::from(unsupported)));
+
+        let pubsub_unavailable: TransportError = TransportErrorKind::pubsub_unavailable();
+        assert!(RobustSubscriptionManager::should_reconnect("ws://localhost:8546", &eyre::Report::from(pubsub_unavailable)));
+    }
+
+    #[test]
+    fn test_url_rotation() {
+        let mut manager = RobustSubscriptionManager::new("primary".to_string(), vec!["backup1".to_string(), "backup2".to_string()]);
+        assert_eq!(manager.get_current_url(), "primary");
+        manager.switch_to_next_url();
+        assert_eq!(manager.get_current_url(), "backup1");
+        manager.switch_to_next_url();
+        assert_eq!(manager.get_current_url(), "backup2");
+        manager.switch_to_next_url();
+        assert_eq!(manager.get_current_url(), "primary");
+
+        manager.switch_to_next_url();
+        manager.reconnect_attempts = 3;
+        manager.reset_reconnection_state();
+        assert_eq!(manager.get_current_url(), "primary");
+        assert!(manager.get_connection_status().is_connected);
+    }
+
+    #[test]
+    fn test_backoff_delay() {
+        let mut manager = RobustSubscriptionManager::new("primary".to_string(), vec![]);
+        let delay = manager.calculate_backoff_delay();
+        assert!(delay >= Duration::from_secs(2) && delay <= Duration::from_secs(2 + 2 / 4));
+
+        manager.reconnect_attempts = 3;
+        let delay = manager.calculate_backoff_delay();
+        assert!(delay >= Duration::from_secs(16) && delay <= Duration::from_secs(20));
+
+        manager.reconnect_attempts = 100;
+        assert!(manager.calculate_backoff_delay() <= Duration::from_secs(300));
+    }
+}
@@ -1,18 +1,55 @@
+use alloy_primitives::B256;
+
 #[derive(Debug, Clone)]
 pub struct NodeBlockActorConfig {
     pub block_header: bool,
     pub block_with_tx: bool,
     pub block_logs: bool,
     pub block_state_update: bool,
+    /// Request only pool event logs from the node instead of all logs of the block
+    pub log_subscription_prefilter: bool,
+    /// Receive new block headers with `eth_subscribe newHeads`, otherwise poll `eth_blockNumber`. Transports without
+    /// subscription support have to poll
+    pub use_subscription: bool,
+    /// Event signatures requested in prefilter mode, pool events of the enabled pool classes and pool creation events of
+    /// configured factories. All logs are requested without them
+    pub log_signatures: Vec<B256>,
+    /// WebSocket or IPC url the header subscription reconnects to when it fails
+    pub subscription_url: Option<String>,
+    /// Largest message accepted by the reconnecting header subscription
+    pub max_message_size_bytes: usize,
 }
 
+/// Default message size limit of WebSocket and IPC connections, the WebSocket limit of tungstenite
+pub const DEFAULT_MAX_MESSAGE_SIZE_BYTES: usize = 64 << 20;
+
 impl NodeBlockActorConfig {
     pub fn all_disabled() -> Self {
-        Self { block_header: false, block_with_tx: false, block_logs: false, block_state_update: false }
+        Self {
+            block_header: false,
+            block_with_tx: false,
+            block_logs: false,
+            block_state_update: false,
+            log_subscription_prefilter: false,
+            use_subscription: true,
+            log_signatures: Vec::new(),
+            subscription_url: None,
+            max_message_size_bytes: DEFAULT_MAX_MESSAGE_SIZE_BYTES,
+        }
     }
 
     pub fn all_enabled() -> Self {
-        Self { block_header: true, block_with_tx: true, block_logs: true, block_state_update: true }
+        Self {
+            block_header: true,
+            block_with_tx: true,
+            block_logs: true,
+            block_state_update: true,
+            log_subscription_prefilter: false,
+            use_subscription: true,
+            log_signatures: Vec::new(),
+            subscription_url: None,
+            max_message_size_bytes: DEFAULT_MAX_MESSAGE_SIZE_BYTES,
+        }
     }
 
     pub fn with_block_header(mut self) -> Self {
@@ -34,4 +71,44 @@ impl NodeBlockActorConfig {
         self.block_state_update = true;
         self
     }
+
+    pub fn with_log_subscription_prefilter(mut self) -> Self {
+        self.log_subscription_prefilter = true;
+        self
+    }
+
+    pub fn with_use_subscription(mut self, use_subscription: bool) -> Self {
+        self.use_subscription = use_subscription;
+        self
+    }
+
+    pub fn with_log_signatures(mut self, log_signatures: Vec<B256>) -> Self {
+        self.log_signatures = log_signatures;
+        self
+    }
+
+    pub fn with_subscription_url(mut self, subscription_url: String, max_message_size_bytes: usize) -> Self {
+        self.subscription_url = Some(subscription_url);
+        self.max_message_size_bytes = max_message_size_bytes;
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MempoolActorConfig {
+    /// Subscribe to full pending transactions, otherwise to hashes and fetch each transaction with `eth_getTransactionByHash`
+    pub full_tx_subscription: bool,
+}
+
+impl Default for MempoolActorConfig {
+    fn default() -> Self {
+        Self { full_tx_subscription: true }
+    }
+}
+
+impl MempoolActorConfig {
+    pub fn with_full_tx_subscription(mut self, full_tx_subscription: bool) -> Self {
+        self.full_tx_subscription = full_tx_subscription;
+        self
+    }
 }
@@ -8,7 +8,7 @@ use alloy_network::{Ethereum, Network};
 use alloy_primitives::BlockNumber;
 use alloy_provider::Provider;
 use eyre::ErrReport;
-use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
+use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, Producer, SharedState, WorkerResult};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
 use loom_core_blockchain::{Blockchain, BlockchainState};
 use loom_evm_db::DatabaseLoomExt;
@@ -38,6 +38,7 @@ pub struct NodeBlockPlayerActor<P, N, DB: Send + Sync + Clone + 'static> {
     block_logs_channel: Option<Broadcaster<MessageBlockLogs>>,
     #[producer]
     block_state_update_channel: Option<Broadcaster<MessageBlockStateUpdate>>,
+    cancellation_token: CancellationToken,
     _n: PhantomData<N>,
 }
 
@@ -59,10 +60,15 @@ where
             block_with_tx_channel: None,
             block_logs_channel: None,
             block_state_update_channel: None,
+            cancellation_token: CancellationToken::new(),
             _n: PhantomData,
         }
     }
 
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
     pub fn on_bc(self, bc: &Blockchain, state: &BlockchainState<DB>) -> Self {
         Self {
             mempool: Some(bc.mempool()),
@@ -87,7 +93,7 @@ where
         let mut handles: Vec<JoinHandle<WorkerResult>> = Vec::new();
         if let Some(mempool) = self.mempool.clone() {
             if let Some(compose_channel) = self.compose_channel.clone() {
-                let handle = tokio::task::spawn(replayer_compose_worker(mempool, compose_channel));
+                let handle = tokio::task::spawn(replayer_compose_worker(mempool, compose_channel, self.cancellation_token.clone()));
                 handles.push(handle);
             }
         }
@@ -102,6 +108,7 @@ where
             self.block_with_tx_channel.clone(),
             self.block_logs_channel.clone(),
             self.block_state_update_channel.clone(),
+            self.cancellation_token.clone(),
         ));
         handles.push(handle);
         Ok(handles)
@@ -1,15 +1,22 @@
-use loom_core_actors::{Broadcaster, SharedState, WorkerResult};
+use loom_core_actors::{Broadcaster, CancellationToken, SharedState, WorkerResult};
 use loom_evm_utils::reth_types::decode_into_transaction;
 use loom_types_blockchain::Mempool;
 use loom_types_events::{MessageTxCompose, RlpState, TxComposeMessageType};
 use tokio::select;
 use tracing::{error, info};
 
-pub(crate) async fn replayer_compose_worker(mempool: SharedState<Mempool>, compose_channel: Broadcaster<MessageTxCompose>) -> WorkerResult {
+pub(crate) async fn replayer_compose_worker(
+    mempool: SharedState<Mempool>,
+    compose_channel: Broadcaster<MessageTxCompose>,
+    cancellation_token: CancellationToken,
+) -> WorkerResult {
     let mut compose_channel_rx = compose_channel.subscribe();
 
     loop {
         select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Replayer compose worker cancelled".to_string());
+            }
             msg = compose_channel_rx.recv() => {
                 if let Ok(msg) = msg {
                     if let TxComposeMessageType::Broadcast(broadcast_msg) = msg.inner {
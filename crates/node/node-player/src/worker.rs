@@ -5,7 +5,7 @@ use alloy_network::Ethereum;
 use alloy_primitives::BlockNumber;
 use alloy_provider::Provider;
 use alloy_rpc_types::{BlockTransactions, BlockTransactionsKind, Filter};
-use loom_core_actors::{Broadcaster, SharedState, WorkerResult};
+use loom_core_actors::{Broadcaster, CancellationToken, SharedState, WorkerResult};
 use loom_evm_db::DatabaseLoomExt;
 use loom_node_debug_provider::DebugProviderExt;
 use loom_types_blockchain::{debug_trace_block, Mempool};
@@ -31,6 +31,7 @@ pub async fn node_player_worker<P, DB>(
     new_block_with_tx_channel: Option<Broadcaster<MessageBlock>>,
     new_block_logs_channel: Option<Broadcaster<MessageBlockLogs>>,
     new_block_state_update_channel: Option<Broadcaster<MessageBlockStateUpdate>>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult
 where
     P: Provider<Ethereum> + DebugProviderExt<Ethereum> + Send + Sync + Clone + 'static,
@@ -179,7 +180,12 @@ where
             }
         }
 
-        tokio::time::sleep(Duration::from_millis(1000)).await;
+        tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                return Ok("Node block player worker cancelled".to_string());
+            }
+            _ = tokio::time::sleep(Duration::from_millis(1000)) => {}
+        }
     }
 
     Ok("Node block player worker finished".to_string())
@@ -1,4 +1,6 @@
+use alloy_primitives::TxHash;
 use serde::Serialize;
+use utoipa::PartialSchema;
 use utoipa::ToSchema;
 
 #[derive(Debug, Serialize)]
@@ -14,3 +16,20 @@ pub struct BlockHeader {
     pub base_fee_per_gas: Option<u64>,
     pub next_block_base_fee: u64,
 }
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConfirmedBundle {
+    #[schema(schema_with = String::schema)]
+    pub tx_hash: TxHash,
+    pub block_number: u64,
+    /// Index of the transaction in the block
+    pub position: usize,
+    pub gas_used: u64,
+    /// WETH balance change of the multicaller or of the EIP-7702 EOA net of the gas cost in wei, negative for losing bundles
+    pub realized_profit_wei: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BundlesResponse {
+    pub bundles: Vec<ConfirmedBundle>,
+}
@@ -0,0 +1,14 @@
+use alloy_primitives::Address;
+use serde::Serialize;
+use utoipa::PartialSchema;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EmergencyStopResponse {
+    #[schema(schema_with = String::schema)]
+    pub stop_contract: Address,
+    #[schema(schema_with = String::schema)]
+    pub operator: Address,
+    pub nonce: u64,
+    pub target_block: u64,
+}
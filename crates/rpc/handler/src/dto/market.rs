@@ -0,0 +1,68 @@
+use crate::dto::pool::PoolProtocol;
+use alloy_primitives::{Address, U256};
+use serde::de::value::StringDeserializer;
+use serde::{Deserialize, Deserializer, Serialize};
+use utoipa::PartialSchema;
+use utoipa::{IntoParams, ToSchema};
+
+/// Protocol names are accepted in any case, e.g. `uniswap_v3`
+fn deserialize_protocol<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<PoolProtocol>, D::Error> {
+    let Some(protocol) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    PoolProtocol::deserialize(StringDeserializer::<D::Error>::new(protocol.to_uppercase())).map(Some)
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct PoolStateFilter {
+    #[serde(default, deserialize_with = "deserialize_protocol")]
+    #[param(value_type = Option<String>)]
+    pub protocol: Option<PoolProtocol>,
+    /// Pools with unknown liquidity are excluded when set
+    pub min_liquidity_usd: Option<f64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PoolStateResponse {
+    pub pools: Vec<PoolState>,
+    pub total: usize,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PoolState {
+    #[schema(schema_with = String::schema)]
+    pub address: Address,
+    pub protocol: PoolProtocol,
+    #[schema(schema_with = String::schema)]
+    pub token0: Option<Address>,
+    #[schema(schema_with = String::schema)]
+    pub token1: Option<Address>,
+    #[schema(schema_with = String::schema)]
+    pub fee: U256,
+    /// Token balances held by the pool
+    #[schema(schema_with = String::schema)]
+    pub reserve0: Option<U256>,
+    #[schema(schema_with = String::schema)]
+    pub reserve1: Option<U256>,
+    pub liquidity_usd: Option<f64>,
+    pub disabled: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TokensResponse {
+    pub tokens: Vec<TokenInfo>,
+    pub total: usize,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TokenInfo {
+    #[schema(schema_with = String::schema)]
+    pub address: Address,
+    pub symbol: String,
+    pub decimals: u8,
+    pub basic: bool,
+    pub middle: bool,
+    /// Price of one token in ETH
+    pub price_eth: Option<f64>,
+    pub price_usd: Option<f64>,
+}
@@ -1,5 +1,10 @@
 pub mod block;
+pub mod emergency_stop;
 pub mod flashbots;
+pub mod market;
 pub mod pagination;
+pub mod pnl;
 pub mod pool;
 pub mod quote;
+pub mod status;
+pub mod strategy;
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+const fn _periods_default() -> i64 {
+    30
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct PnlQuery {
+    /// Number of latest periods to return
+    #[serde(default = "_periods_default")]
+    pub periods: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PnlPeriod {
+    /// Unix timestamp of the period start
+    pub period_start: i64,
+    pub txs: i64,
+    /// Realized profit net of gas cost
+    pub profit_eth: f64,
+    pub profit_usd: Option<f64>,
+    pub gas_cost: f64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PnlResponse {
+    pub periods: Vec<PnlPeriod>,
+}
@@ -1,4 +1,4 @@
-use alloy_primitives::{Address, U256};
+use alloy_primitives::{Address, TxHash, U256};
 
 use serde::{Deserialize, Serialize};
 use utoipa::openapi::schema::SchemaType;
@@ -24,6 +24,12 @@ pub struct PoolDetailsResponse {
     pub tokens: Vec<Address>,
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PendingSwapsResponse {
+    #[schema(schema_with = array_of_strings)]
+    pub tx_hashes: Vec<TxHash>,
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct Pool {
     #[schema(schema_with = String::schema)]
@@ -56,6 +62,7 @@ pub enum PoolClass {
     RocketPool,
     BalancerV1,
     BalancerV2,
+    Erc4626Vault,
     Custom(u64),
 }
 impl From<loom_types_entities::PoolClass> for PoolClass {
@@ -74,6 +81,7 @@ impl From<loom_types_entities::PoolClass> for PoolClass {
             loom_types_entities::PoolClass::RocketPool => PoolClass::RocketPool,
             loom_types_entities::PoolClass::BalancerV1 => PoolClass::BalancerV1,
             loom_types_entities::PoolClass::BalancerV2 => PoolClass::BalancerV2,
+            loom_types_entities::PoolClass::Erc4626Vault => PoolClass::Erc4626Vault,
             loom_types_entities::PoolClass::Custom(id) => PoolClass::Custom(id),
         }
     }
@@ -98,6 +106,7 @@ pub enum PoolProtocol {
     UniswapV3Like,
     UniswapV4,
     PancakeV3,
+    AerodromeCL,
     Integral,
     Maverick,
     MaverickV2,
@@ -109,6 +118,7 @@ pub enum PoolProtocol {
     AntFarm,
     BalancerV1,
     BalancerV2,
+    Erc4626Vault,
     Custom(u64),
 }
 
@@ -131,6 +141,7 @@ impl From<loom_types_entities::PoolProtocol> for PoolProtocol {
             loom_types_entities::PoolProtocol::UniswapV3Like => PoolProtocol::UniswapV3Like,
             loom_types_entities::PoolProtocol::UniswapV4 => PoolProtocol::UniswapV4,
             loom_types_entities::PoolProtocol::PancakeV3 => PoolProtocol::PancakeV3,
+            loom_types_entities::PoolProtocol::AerodromeCL => PoolProtocol::AerodromeCL,
             loom_types_entities::PoolProtocol::Integral => PoolProtocol::Integral,
             loom_types_entities::PoolProtocol::Maverick => PoolProtocol::Maverick,
             loom_types_entities::PoolProtocol::MaverickV2 => PoolProtocol::MaverickV2,
@@ -142,6 +153,7 @@ impl From<loom_types_entities::PoolProtocol> for PoolProtocol {
             loom_types_entities::PoolProtocol::AntFarm => PoolProtocol::AntFarm,
             loom_types_entities::PoolProtocol::BalancerV1 => PoolProtocol::BalancerV1,
             loom_types_entities::PoolProtocol::BalancerV2 => PoolProtocol::BalancerV2,
+            loom_types_entities::PoolProtocol::Erc4626Vault => PoolProtocol::Erc4626Vault,
             loom_types_entities::PoolProtocol::Custom(id) => PoolProtocol::Custom(id),
         }
     }
@@ -166,6 +178,7 @@ impl From<&PoolProtocol> for loom_types_entities::PoolProtocol {
             PoolProtocol::UniswapV3Like => loom_types_entities::PoolProtocol::UniswapV3Like,
             PoolProtocol::UniswapV4 => loom_types_entities::PoolProtocol::UniswapV4,
             PoolProtocol::PancakeV3 => loom_types_entities::PoolProtocol::PancakeV3,
+            PoolProtocol::AerodromeCL => loom_types_entities::PoolProtocol::AerodromeCL,
             PoolProtocol::Integral => loom_types_entities::PoolProtocol::Integral,
             PoolProtocol::Maverick => loom_types_entities::PoolProtocol::Maverick,
             PoolProtocol::MaverickV2 => loom_types_entities::PoolProtocol::MaverickV2,
@@ -177,6 +190,7 @@ impl From<&PoolProtocol> for loom_types_entities::PoolProtocol {
             PoolProtocol::AntFarm => loom_types_entities::PoolProtocol::AntFarm,
             PoolProtocol::BalancerV1 => loom_types_entities::PoolProtocol::BalancerV1,
             PoolProtocol::BalancerV2 => loom_types_entities::PoolProtocol::BalancerV2,
+            PoolProtocol::Erc4626Vault => loom_types_entities::PoolProtocol::Erc4626Vault,
             PoolProtocol::Custom(id) => loom_types_entities::PoolProtocol::Custom(*id),
         }
     }
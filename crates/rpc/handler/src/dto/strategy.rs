@@ -0,0 +1,14 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LatestArbResponse {
+    /// Block the swap is composed for
+    pub block_number: u64,
+    pub swap: String,
+    pub profit_eth: f64,
+    pub tips_eth: Option<f64>,
+    pub gas: u64,
+    /// Source of the opportunity, e.g. the backrun transaction
+    pub origin: Option<String>,
+}
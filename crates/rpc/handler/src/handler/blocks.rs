@@ -1,4 +1,4 @@
-use crate::dto::block::BlockHeader;
+use crate::dto::block::{BlockHeader, BundlesResponse, ConfirmedBundle};
 use axum::extract::State;
 use axum::http::StatusCode;
 use axum::Json;
@@ -35,3 +35,36 @@ pub async fn latest_block<DB: DatabaseRef + DatabaseCommit + Send + Sync + Clone
         }
     }
 }
+
+/// Get confirmed bundles
+///
+/// Latest own bundles found in mined blocks, newest first
+#[utoipa::path(
+    get,
+    path = "bundles",
+    tag = "block",
+    tags = [],
+    responses(
+    (status = 200, description = "Confirmed bundles", body = BundlesResponse),
+    )
+)]
+pub async fn confirmed_bundles<DB: DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'static>(
+    State(app_state): State<AppState<DB>>,
+) -> Result<Json<BundlesResponse>, (StatusCode, String)> {
+    let bundles = app_state
+        .bc
+        .bundle_history()
+        .read()
+        .await
+        .bundles()
+        .rev()
+        .map(|bundle| ConfirmedBundle {
+            tx_hash: bundle.tx_hash,
+            block_number: bundle.block_number,
+            position: bundle.position,
+            gas_used: bundle.gas_used,
+            realized_profit_wei: bundle.realized_profit_wei.to_string(),
+        })
+        .collect();
+    Ok(Json(BundlesResponse { bundles }))
+}
@@ -0,0 +1,146 @@
+use crate::dto::emergency_stop::EmergencyStopResponse;
+use alloy_consensus::Transaction as _;
+use alloy_primitives::{Bytes, TxKind};
+use alloy_rpc_types::{Transaction, TransactionInput, TransactionRequest};
+use alloy_sol_types::SolCall;
+use axum::extract::State;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::Json;
+use loom_defi_abi::IEmergencyStop;
+use loom_rpc_state::AppState;
+use loom_types_events::{MessageTxCompose, TxComposeData, TxState};
+use revm::{DatabaseCommit, DatabaseRef};
+use tracing::{error, warn};
+
+const EMERGENCY_STOP_GAS: u64 = 100_000;
+// Floor of the priority fee when the latest block has no transactions paying a tip
+const EMERGENCY_STOP_MIN_PRIORITY_FEE: u64 = 1_000_000_000;
+// Emergency stop transactions replace each other, so they share the reserved nonce
+const EMERGENCY_STOP_NONCE_GROUP: u64 = u64::MAX;
+
+/// `Authorization: Bearer <token>` header matches `auth_token`, compared in constant time
+fn is_authorized(headers: &HeaderMap, auth_token: &str) -> bool {
+    let Some(token) =
+        headers.get(header::AUTHORIZATION).and_then(|value| value.to_str().ok()).and_then(|value| value.strip_prefix("Bearer "))
+    else {
+        return false;
+    };
+    token.len() == auth_token.len() && token.bytes().zip(auth_token.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// Highest priority fee paid in the latest block, so the stop is ordered at the top of the next block
+fn top_priority_fee(txs: Option<&Vec<Transaction>>, base_fee: u64) -> u64 {
+    txs.into_iter()
+        .flatten()
+        .filter_map(|tx| tx.effective_tip_per_gas(base_fee))
+        .max()
+        .map(|tip| tip.min(u64::MAX as u128) as u64)
+        .unwrap_or_default()
+        .max(EMERGENCY_STOP_MIN_PRIORITY_FEE)
+}
+
+/// Send `stop()` to the emergency stop contract from the operator EOA. Signed by TxSignersActor and broadcast to relays
+pub async fn emergency_stop<DB: DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'static>(
+    State(app_state): State<AppState<DB>>,
+    headers: HeaderMap,
+) -> Result<Json<EmergencyStopResponse>, (StatusCode, String)> {
+    let Some(emergency_stop) = app_state.emergency_stop.clone() else {
+        return Err((StatusCode::NOT_FOUND, "Emergency stop is not configured".to_string()));
+    };
+    if !is_authorized(&headers, &emergency_stop.auth_token) {
+        warn!("Unauthorized emergency stop request");
+        return Err((StatusCode::UNAUTHORIZED, "Unauthorized".to_string()));
+    }
+    let operator = emergency_stop.signer.address();
+
+    let (block_header, priority_fee) = {
+        let latest_block = app_state.bc.latest_block();
+        let latest_block_guard = latest_block.read().await;
+        let Some(block_header) = latest_block_guard.block_header.clone() else {
+            return Err((StatusCode::INTERNAL_SERVER_ERROR, "No block header found".to_string()));
+        };
+        let priority_fee = top_priority_fee(latest_block_guard.txs(), block_header.base_fee_per_gas.unwrap_or_default());
+        (block_header, priority_fee)
+    };
+
+    // The nonce is reserved, so transactions composed concurrently for the operator do not reuse it
+    let nonce = {
+        let nonce_and_balance = app_state.bc.nonce_and_balance();
+        let mut nonce_and_balance_guard = nonce_and_balance.write().await;
+        if !nonce_and_balance_guard.is_monitored(&operator) {
+            return Err((StatusCode::SERVICE_UNAVAILABLE, format!("Operator {} nonce is not monitored", operator)));
+        }
+        nonce_and_balance_guard.reserve_nonce(operator, EMERGENCY_STOP_NONCE_GROUP)
+    };
+
+    let next_block_base_fee = app_state.bc.chain_parameters().calc_next_block_base_fee(
+        block_header.gas_used,
+        block_header.gas_limit,
+        block_header.base_fee_per_gas.unwrap_or_default(),
+    );
+
+    let call_data: Bytes = IEmergencyStop::stopCall {}.abi_encode().into();
+    let tx_request = TransactionRequest {
+        transaction_type: Some(2),
+        chain_id: Some(app_state.bc.chain_id()),
+        from: Some(operator),
+        to: Some(TxKind::Call(emergency_stop.stop_contract)),
+        gas: Some(EMERGENCY_STOP_GAS),
+        input: TransactionInput::new(call_data),
+        nonce: Some(nonce),
+        max_priority_fee_per_gas: Some(priority_fee as u128),
+        max_fee_per_gas: Some(priority_fee as u128 + next_block_base_fee as u128 * 2),
+        ..TransactionRequest::default()
+    };
+
+    let target_block = block_header.number + 1;
+    let tx_compose = TxComposeData {
+        eoa: Some(operator),
+        signer: Some(emergency_stop.signer.clone()),
+        nonce,
+        gas: EMERGENCY_STOP_GAS,
+        priority_gas_fee: priority_fee,
+        next_block_number: target_block,
+        next_block_timestamp: block_header.timestamp + app_state.bc.expected_block_time().as_secs(),
+        next_block_base_fee,
+        tx_bundle: Some(vec![TxState::SignatureRequired(tx_request)]),
+        origin: Some("emergency_stop".to_string()),
+        ..TxComposeData::default()
+    };
+
+    if let Err(e) = app_state.bc.tx_compose_channel().send(MessageTxCompose::sign(tx_compose)) {
+        error!("tx_compose_channel.send error {}", e);
+        app_state.bc.nonce_and_balance().write().await.release_nonce(operator, nonce);
+        return Err((StatusCode::INTERNAL_SERVER_ERROR, format!("Error: {}", e)));
+    }
+
+    warn!(stop_contract = %emergency_stop.stop_contract, %operator, nonce, priority_fee, target_block, "Emergency stop sent");
+
+    Ok(Json(EmergencyStopResponse { stop_contract: emergency_stop.stop_contract, operator, nonce, target_block }))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_is_authorized() {
+        let mut headers = HeaderMap::new();
+        assert!(!is_authorized(&headers, "secret"));
+
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Bearer secret"));
+        assert!(is_authorized(&headers, "secret"));
+        assert!(!is_authorized(&headers, "secret2"));
+        assert!(!is_authorized(&headers, "secreT"));
+
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("secret"));
+        assert!(!is_authorized(&headers, "secret"));
+    }
+
+    #[test]
+    fn test_top_priority_fee_floor() {
+        assert_eq!(top_priority_fee(None, 10_000_000_000), EMERGENCY_STOP_MIN_PRIORITY_FEE);
+        assert_eq!(top_priority_fee(Some(&vec![]), 10_000_000_000), EMERGENCY_STOP_MIN_PRIORITY_FEE);
+    }
+}
@@ -0,0 +1,165 @@
+use crate::dto::market::{PoolState, PoolStateFilter, PoolStateResponse, TokenInfo, TokensResponse};
+use crate::dto::pagination::Pagination;
+use crate::dto::pool::PoolProtocol;
+use alloy_primitives::{Address, U256};
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use eyre::ErrReport;
+use loom_defi_address_book::TokenAddressEth;
+use loom_defi_pools::state_readers::ERC20StateReader;
+use loom_evm_utils::NWETH;
+use loom_rpc_state::AppState;
+use loom_types_entities::{Market, PoolId, PoolWrapper, Token};
+use revm::primitives::Env;
+use revm::{DatabaseCommit, DatabaseRef};
+
+/// Price of one ETH in USD from the USDC token price
+fn eth_usd_price(market: &Market) -> Option<f64> {
+    let usdc = market.get_token(&TokenAddressEth::USDC)?;
+    usdc.calc_token_value_from_eth(NWETH::from_float(1.0)).map(|value| usdc.to_float(value))
+}
+
+fn pool_state<DB: DatabaseRef>(market: &Market, state_db: &DB, pool: &PoolWrapper, eth_usd: Option<f64>) -> PoolState {
+    let tokens = pool.get_tokens();
+    // Balances are only known for pools holding their tokens at the pool address
+    let reserves: Vec<Option<U256>> = match pool.get_pool_id() {
+        PoolId::Address(pool_address) => {
+            tokens.iter().map(|token| ERC20StateReader::balance_of(state_db, Env::default(), *token, pool_address).ok()).collect()
+        }
+        PoolId::Bytes32(_) => vec![None; tokens.len()],
+    };
+
+    let liquidity_eth: Option<f64> = tokens
+        .iter()
+        .zip(reserves.iter())
+        .map(|(token, reserve)| {
+            let token = market.get_token(token)?;
+            token.calc_eth_value((*reserve)?).map(NWETH::to_float)
+        })
+        .sum();
+
+    PoolState {
+        address: pool.get_address(),
+        protocol: PoolProtocol::from(pool.get_protocol()),
+        token0: tokens.first().cloned(),
+        token1: tokens.get(1).cloned(),
+        fee: pool.get_fee(),
+        reserve0: reserves.first().cloned().flatten(),
+        reserve1: reserves.get(1).cloned().flatten(),
+        liquidity_usd: liquidity_eth.zip(eth_usd).map(|(liquidity_eth, eth_usd)| liquidity_eth * eth_usd),
+        disabled: market.is_pool_disabled(&pool.get_pool_id()),
+    }
+}
+
+/// Get pools state
+///
+/// Get tracked pools with their reserves from the current market state
+#[utoipa::path(
+    get,
+    path = "/pools",
+    tag = "market",
+    tags = [],
+    params(
+        Pagination, PoolStateFilter
+    ),
+    responses(
+    (status = 200, description = "Tracked pools with reserves", body = PoolStateResponse),
+    )
+)]
+pub async fn market_pools<DB: DatabaseRef<Error = ErrReport> + DatabaseCommit + Send + Sync + Clone + 'static>(
+    State(app_state): State<AppState<DB>>,
+    pagination: Query<Pagination>,
+    filter: Query<PoolStateFilter>,
+) -> Result<Json<PoolStateResponse>, (StatusCode, String)> {
+    let market_state = app_state.state.market_state();
+    let market_state_guard = market_state.read().await;
+    let state_db = &market_state_guard.state_db;
+    let market = app_state.bc.market();
+    let market_guard = market.read().await;
+    let eth_usd = eth_usd_price(&market_guard);
+
+    let mut pools: Vec<&PoolWrapper> = market_guard
+        .pools()
+        .values()
+        .filter(|pool| match &filter.protocol {
+            None => true,
+            Some(protocol) => pool.get_protocol() == protocol.into(),
+        })
+        .collect();
+    pools.sort_by_key(|pool| pool.get_address());
+
+    // Reserves are read only for the requested page unless pools are filtered by liquidity
+    let (pools, total) = match filter.min_liquidity_usd {
+        Some(min_liquidity_usd) => {
+            let pools: Vec<PoolState> = pools
+                .into_iter()
+                .map(|pool| pool_state(&market_guard, state_db, pool, eth_usd))
+                .filter(|pool| pool.liquidity_usd.is_some_and(|liquidity_usd| liquidity_usd >= min_liquidity_usd))
+                .collect();
+            let total = pools.len();
+            (pools.into_iter().skip(pagination.start()).take(pagination.limit).collect(), total)
+        }
+        None => {
+            let total = pools.len();
+            let pools = pools
+                .into_iter()
+                .skip(pagination.start())
+                .take(pagination.limit)
+                .map(|pool| pool_state(&market_guard, state_db, pool, eth_usd))
+                .collect();
+            (pools, total)
+        }
+    };
+
+    Ok(Json(PoolStateResponse { pools, total }))
+}
+
+fn token_info(token: &Token, eth_usd: Option<f64>) -> TokenInfo {
+    let price_eth = token.calc_eth_value(token.get_exp()).map(NWETH::to_float);
+    TokenInfo {
+        address: token.get_address(),
+        symbol: token.get_symbol(),
+        decimals: token.get_decimals(),
+        basic: token.is_basic(),
+        middle: token.is_middle(),
+        price_eth,
+        price_usd: price_eth.zip(eth_usd).map(|(price_eth, eth_usd)| price_eth * eth_usd),
+    }
+}
+
+/// Get tokens
+///
+/// Get known tokens with their prices
+#[utoipa::path(
+    get,
+    path = "/tokens",
+    tag = "market",
+    tags = [],
+    params(
+        Pagination
+    ),
+    responses(
+    (status = 200, description = "Known tokens with prices", body = TokensResponse),
+    )
+)]
+pub async fn market_tokens<DB: DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'static>(
+    State(app_state): State<AppState<DB>>,
+    pagination: Query<Pagination>,
+) -> Result<Json<TokensResponse>, (StatusCode, String)> {
+    let market = app_state.bc.market();
+    let market_guard = market.read().await;
+    let eth_usd = eth_usd_price(&market_guard);
+
+    let mut addresses: Vec<&Address> = market_guard.tokens().keys().collect();
+    addresses.sort();
+    let tokens = addresses
+        .iter()
+        .skip(pagination.start())
+        .take(pagination.limit)
+        .filter_map(|address| market_guard.get_token(address))
+        .map(|token| token_info(&token, eth_usd))
+        .collect();
+
+    Ok(Json(TokensResponse { tokens, total: addresses.len() }))
+}
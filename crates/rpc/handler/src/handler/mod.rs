@@ -1,4 +1,10 @@
 pub mod blocks;
+pub mod emergency_stop;
 pub mod flashbots;
+pub mod market;
+pub mod pnl;
 pub mod pools;
+pub mod status;
+pub mod strategy;
+pub mod traces;
 pub mod ws;
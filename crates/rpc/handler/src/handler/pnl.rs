@@ -0,0 +1,77 @@
+use crate::dto::pnl::{PnlPeriod, PnlQuery, PnlResponse};
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use loom_rpc_state::AppState;
+use loom_storage_db::{pnl_by_period, PnlPeriod as LedgerPeriod};
+use revm::{DatabaseCommit, DatabaseRef};
+use tracing::error;
+
+async fn pnl_response<DB: DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'static>(
+    app_state: AppState<DB>,
+    period: LedgerPeriod,
+    periods: i64,
+) -> Result<Json<PnlResponse>, (StatusCode, String)> {
+    match pnl_by_period(&app_state.db, period, periods).await {
+        Ok(summaries) => Ok(Json(PnlResponse {
+            periods: summaries
+                .into_iter()
+                .map(|summary| PnlPeriod {
+                    period_start: summary.period_start.timestamp(),
+                    txs: summary.txs,
+                    profit_eth: summary.profit_eth,
+                    profit_usd: summary.profit_usd,
+                    gas_cost: summary.gas_cost,
+                })
+                .collect(),
+        })),
+        Err(e) => {
+            error!("pnl_by_period error {}", e);
+            Err((StatusCode::INTERNAL_SERVER_ERROR, format!("Error: {}", e)))
+        }
+    }
+}
+
+/// Get daily profit
+///
+/// Realized profit of landed transactions per day, newest first
+#[utoipa::path(
+    get,
+    path = "/daily",
+    tag = "pnl",
+    tags = [],
+    params(
+        PnlQuery
+    ),
+    responses(
+    (status = 200, description = "Profit per day", body = PnlResponse),
+    )
+)]
+pub async fn pnl_daily<DB: DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'static>(
+    State(app_state): State<AppState<DB>>,
+    query: Query<PnlQuery>,
+) -> Result<Json<PnlResponse>, (StatusCode, String)> {
+    pnl_response(app_state, LedgerPeriod::Day, query.periods).await
+}
+
+/// Get weekly profit
+///
+/// Realized profit of landed transactions per week, newest first
+#[utoipa::path(
+    get,
+    path = "/weekly",
+    tag = "pnl",
+    tags = [],
+    params(
+        PnlQuery
+    ),
+    responses(
+    (status = 200, description = "Profit per week", body = PnlResponse),
+    )
+)]
+pub async fn pnl_weekly<DB: DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'static>(
+    State(app_state): State<AppState<DB>>,
+    query: Query<PnlQuery>,
+) -> Result<Json<PnlResponse>, (StatusCode, String)> {
+    pnl_response(app_state, LedgerPeriod::Week, query.periods).await
+}
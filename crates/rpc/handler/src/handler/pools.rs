@@ -1,7 +1,7 @@
 use crate::dto::pagination::Pagination;
-use crate::dto::pool::{MarketStats, Pool, PoolClass, PoolDetailsResponse, PoolProtocol, PoolResponse};
+use crate::dto::pool::{MarketStats, PendingSwapsResponse, Pool, PoolClass, PoolDetailsResponse, PoolProtocol, PoolResponse};
 use crate::dto::quote::{Filter, QuoteRequest, QuoteResponse};
-use alloy_primitives::Address;
+use alloy_primitives::{Address, B256};
 use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use axum::Json;
@@ -108,6 +108,35 @@ pub async fn pool<DB: DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'stat
     }
 }
 
+/// Get pending swaps of a pool
+///
+/// Get hashes of pending transactions swapping on a pool
+#[utoipa::path(
+    get,
+    path = "/pools/{address}/pending_swaps",
+    tag = "market",
+    tags = [],
+    params(
+        ("address" = String, Path, description = "Address of the pool or 32 byte id of a UniswapV4 pool"),
+    ),
+    responses(
+    (status = 200, description = "Pending swaps response", body = PendingSwapsResponse),
+    (status = 400, description = "Malformed pool id"),
+    )
+)]
+pub async fn pool_pending_swaps<DB: DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'static>(
+    State(app_state): State<AppState<DB>>,
+    Path(address): Path<String>,
+) -> Result<Json<PendingSwapsResponse>, (StatusCode, String)> {
+    let pool_id = match Address::from_str(&address) {
+        Ok(address) => PoolId::Address(address),
+        Err(_) => PoolId::Bytes32(B256::from_str(&address).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?),
+    };
+
+    let tx_hashes = app_state.bc.mempool().read().await.get_pending_swaps_for_pool(&pool_id).into_iter().map(|tx| tx.tx_hash).collect();
+    Ok(Json(PendingSwapsResponse { tx_hashes }))
+}
+
 /// Market statistics
 ///
 /// Get the latest market statistics
@@ -0,0 +1,86 @@
+use crate::dto::status::HealthResponse;
+use axum::extract::State;
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+use axum::Json;
+use loom_evm_utils::NWETH;
+use loom_rpc_state::AppState;
+use revm::{DatabaseCommit, DatabaseRef};
+use std::fmt::Write;
+
+fn write_gauge(metrics: &mut String, name: &str, help: &str, value: impl std::fmt::Display) {
+    let _ = writeln!(metrics, "# HELP {name} {help}");
+    let _ = writeln!(metrics, "# TYPE {name} gauge");
+    let _ = writeln!(metrics, "{name} {value}");
+}
+
+/// Health check
+///
+/// Service is healthy once a block header was received
+#[utoipa::path(
+    get,
+    path = "health",
+    tag = "status",
+    tags = [],
+    responses(
+    (status = 200, description = "Healthy", body = HealthResponse),
+    (status = 503, description = "No block received yet", body = HealthResponse),
+    )
+)]
+pub async fn health<DB: DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'static>(
+    State(app_state): State<AppState<DB>>,
+) -> (StatusCode, Json<HealthResponse>) {
+    let latest_block = app_state.bc.latest_block().read().await.block_header.as_ref().map(|header| header.number);
+    match latest_block {
+        Some(_) => (StatusCode::OK, Json(HealthResponse { status: "ok".to_string(), latest_block })),
+        None => (StatusCode::SERVICE_UNAVAILABLE, Json(HealthResponse { status: "syncing".to_string(), latest_block })),
+    }
+}
+
+/// Metrics
+///
+/// Live market and strategy gauges in the Prometheus text format
+#[utoipa::path(
+    get,
+    path = "metrics",
+    tag = "status",
+    tags = [],
+    responses(
+    (status = 200, description = "Prometheus metrics", body = String),
+    )
+)]
+pub async fn metrics<DB: DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'static>(
+    State(app_state): State<AppState<DB>>,
+) -> impl IntoResponse {
+    let mut metrics = String::new();
+
+    if let Some(header) = app_state.bc.latest_block().read().await.block_header.as_ref() {
+        write_gauge(&mut metrics, "loom_latest_block_number", "Number of the latest block", header.number);
+    }
+    {
+        let market = app_state.bc.market();
+        let market_guard = market.read().await;
+        write_gauge(&mut metrics, "loom_market_pools", "Pools in the market", market_guard.pools().len());
+        write_gauge(&mut metrics, "loom_market_disabled_pools", "Disabled pools in the market", market_guard.disabled_pools_count());
+        write_gauge(&mut metrics, "loom_market_tokens", "Tokens in the market", market_guard.tokens().len());
+        write_gauge(&mut metrics, "loom_market_swap_paths", "Swap paths in the market", market_guard.swap_paths().len());
+    }
+    if let Some(latest_arb) = app_state.latest_arb.read().await.as_ref() {
+        write_gauge(&mut metrics, "loom_latest_arb_block_number", "Block of the latest composed arb", latest_arb.block_number);
+        write_gauge(
+            &mut metrics,
+            "loom_latest_arb_profit_eth",
+            "Profit of the latest composed arb in ETH",
+            NWETH::to_float(latest_arb.profit_eth),
+        );
+    }
+    #[cfg(feature = "prometheus")]
+    if let Some(registry) = app_state.prometheus_registry.as_ref() {
+        match prometheus::TextEncoder::new().encode_to_string(&registry.gather()) {
+            Ok(exported) => metrics.push_str(&exported),
+            Err(e) => tracing::error!("Failed to encode prometheus metrics : {}", e),
+        }
+    }
+
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], metrics)
+}
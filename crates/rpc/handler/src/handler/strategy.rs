@@ -0,0 +1,36 @@
+use crate::dto::strategy::LatestArbResponse;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::Json;
+use loom_evm_utils::NWETH;
+use loom_rpc_state::AppState;
+use revm::{DatabaseCommit, DatabaseRef};
+
+/// Get latest arb
+///
+/// Latest swap composed by the strategy and ready for broadcast
+#[utoipa::path(
+    get,
+    path = "/latest_arb",
+    tag = "strategy",
+    tags = [],
+    responses(
+    (status = 200, description = "Latest arb", body = LatestArbResponse),
+    (status = 404, description = "No arb found yet"),
+    )
+)]
+pub async fn latest_arb<DB: DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'static>(
+    State(app_state): State<AppState<DB>>,
+) -> Result<Json<LatestArbResponse>, (StatusCode, String)> {
+    match app_state.latest_arb.read().await.as_ref() {
+        Some(latest_arb) => Ok(Json(LatestArbResponse {
+            block_number: latest_arb.block_number,
+            swap: latest_arb.swap.clone(),
+            profit_eth: NWETH::to_float(latest_arb.profit_eth),
+            tips_eth: latest_arb.tips.map(NWETH::to_float),
+            gas: latest_arb.gas,
+            origin: latest_arb.origin.clone(),
+        })),
+        None => Err((StatusCode::NOT_FOUND, "No arb found yet".to_string())),
+    }
+}
@@ -0,0 +1,37 @@
+use alloy_primitives::B256;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use loom_rpc_state::AppState;
+use loom_types_events::TraceFrame;
+use revm::{DatabaseCommit, DatabaseRef};
+use std::str::FromStr;
+
+/// Get swap simulation trace
+///
+/// EVM execution trace of a simulated swap. Traces are captured only by estimators built with the `debug-traces` feature.
+#[utoipa::path(
+    get,
+    path = "/{swap_id}",
+    tag = "traces",
+    tags = [],
+    params(
+        ("swap_id" = String, Path, description = "Keccak hash of the swap calldata"),
+    ),
+    responses(
+    (status = 200, description = "Parity call traces of the simulated swap transaction"),
+    (status = 400, description = "Invalid swap id"),
+    (status = 404, description = "Trace not found"),
+    )
+)]
+pub async fn swap_trace<DB: DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'static>(
+    State(app_state): State<AppState<DB>>,
+    Path(swap_id): Path<String>,
+) -> Result<Json<Vec<TraceFrame>>, (StatusCode, String)> {
+    let swap_id = B256::from_str(&swap_id).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    match app_state.bc.swap_traces().read().await.get(&swap_id) {
+        None => Err((StatusCode::NOT_FOUND, "Trace not found".to_string())),
+        Some(trace) => Ok(Json(trace.clone())),
+    }
+}
@@ -1,5 +1,8 @@
-use crate::dto::block::BlockHeader;
+use crate::dto::block::{BlockHeader, BundlesResponse, ConfirmedBundle};
+use crate::dto::market::{PoolState, PoolStateResponse, TokenInfo, TokensResponse};
+use crate::dto::pnl::{PnlPeriod, PnlResponse};
 use crate::dto::pool::MarketStats;
+use crate::dto::pool::PendingSwapsResponse;
 use crate::dto::pool::Pool;
 use crate::dto::pool::PoolClass;
 use crate::dto::pool::PoolDetailsResponse;
@@ -7,39 +10,115 @@ use crate::dto::pool::PoolProtocol;
 use crate::dto::pool::PoolResponse;
 use crate::dto::quote::QuoteRequest;
 use crate::dto::quote::QuoteResponse;
+use crate::dto::status::HealthResponse;
+use crate::dto::strategy::LatestArbResponse;
+use crate::handler::blocks::__path_confirmed_bundles;
 use crate::handler::blocks::__path_latest_block;
+use crate::handler::market::__path_market_pools;
+use crate::handler::market::__path_market_tokens;
+use crate::handler::pnl::__path_pnl_daily;
+use crate::handler::pnl::__path_pnl_weekly;
 use crate::handler::pools::__path_market_stats;
 use crate::handler::pools::__path_pool;
+use crate::handler::pools::__path_pool_pending_swaps;
 use crate::handler::pools::__path_pool_quote;
 use crate::handler::pools::__path_pools;
+use crate::handler::status::__path_health;
+use crate::handler::status::__path_metrics;
+use crate::handler::strategy::__path_latest_arb;
+use crate::handler::traces::__path_swap_trace;
 use utoipa::OpenApi;
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(latest_block),
+    paths(latest_block, confirmed_bundles),
     tags(
         (name = "block", description = "Blockchain")
     ),
-    components(schemas(BlockHeader))
+    components(schemas(BlockHeader, BundlesResponse, ConfirmedBundle))
 )]
 pub struct BlockApi;
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(pool, pools, pool_quote, market_stats),
+    paths(pool, pools, pool_quote, pool_pending_swaps, market_stats),
     tags(
         (name = "market", description = "Market")
     ),
-    components(schemas(PoolResponse, PoolDetailsResponse, Pool, PoolClass, PoolProtocol, MarketStats, QuoteRequest, QuoteResponse))
+    components(schemas(
+        PoolResponse,
+        PoolDetailsResponse,
+        Pool,
+        PoolClass,
+        PoolProtocol,
+        MarketStats,
+        QuoteRequest,
+        QuoteResponse,
+        PendingSwapsResponse
+    ))
 )]
 pub struct MarketApi;
 
+#[derive(OpenApi)]
+#[openapi(
+    paths(market_pools, market_tokens),
+    tags(
+        (name = "market", description = "Market")
+    ),
+    components(schemas(PoolStateResponse, PoolState, TokensResponse, TokenInfo, PoolProtocol))
+)]
+pub struct MarketStateApi;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(pnl_daily, pnl_weekly),
+    tags(
+        (name = "pnl", description = "Profit and loss")
+    ),
+    components(schemas(PnlResponse, PnlPeriod))
+)]
+pub struct PnlApi;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(swap_trace),
+    tags(
+        (name = "traces", description = "Swap simulation traces")
+    )
+)]
+pub struct TracesApi;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(latest_arb),
+    tags(
+        (name = "strategy", description = "Strategy")
+    ),
+    components(schemas(LatestArbResponse))
+)]
+pub struct StrategyApi;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(health, metrics),
+    tags(
+        (name = "status", description = "Service status")
+    ),
+    components(schemas(HealthResponse))
+)]
+pub struct StatusApi;
+
 #[allow(dead_code)]
 #[derive(OpenApi)]
 #[openapi(
     nest(
         (path = "/api/v1/block/", api = BlockApi),
-        (path = "/api/v1/markets", api = MarketApi)
+        (path = "/api/v1/markets", api = MarketApi),
+        (path = "/api/v1/market", api = MarketStateApi),
+        (path = "/api/v1/pnl", api = PnlApi),
+        (path = "/api/v1/strategy", api = StrategyApi),
+        (path = "/api/v1/traces", api = TracesApi),
+        (path = "/", api = StatusApi)
     )
 )]
 pub struct ApiDoc;
@@ -1,6 +1,12 @@
-use crate::handler::blocks::latest_block;
+use crate::handler::blocks::{confirmed_bundles, latest_block};
+use crate::handler::emergency_stop::emergency_stop;
 use crate::handler::flashbots::flashbots;
-use crate::handler::pools::{market_stats, pool, pool_quote, pools};
+use crate::handler::market::{market_pools, market_tokens};
+use crate::handler::pnl::{pnl_daily, pnl_weekly};
+use crate::handler::pools::{market_stats, pool, pool_pending_swaps, pool_quote, pools};
+use crate::handler::status::{health, metrics};
+use crate::handler::strategy::latest_arb;
+use crate::handler::traces::swap_trace;
 use crate::handler::ws::ws_handler;
 //use crate::openapi::ApiDoc;
 use axum::routing::{get, post};
@@ -18,21 +24,41 @@ pub fn router<DB: DatabaseRef<Error = ErrReport> + DatabaseCommit + Sync + Send
             Router::new()
                 .nest("/block", router_block()) // rename to node
                 .nest("/markets", router_market())
-                .nest("/flashbots", Router::new().route("/", post(flashbots))),
+                .nest("/market", router_market_state())
+                .nest("/flashbots", Router::new().route("/", post(flashbots)))
+                .nest("/pnl", router_pnl())
+                .nest("/strategy", Router::new().route("/latest_arb", get(latest_arb)))
+                .nest("/traces", router_traces()),
         )
+        .route("/health", get(health))
+        .route("/metrics", get(metrics))
+        .route("/emergency-stop", post(emergency_stop))
         .route("/ws", get(ws_handler))
         //.merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
         .with_state(app_state)
 }
 
 pub fn router_block<DB: DatabaseRef + DatabaseCommit + Sync + Send + Clone + 'static>() -> Router<AppState<DB>> {
-    Router::new().route("/latest_block", get(latest_block))
+    Router::new().route("/latest_block", get(latest_block)).route("/bundles", get(confirmed_bundles))
+}
+
+pub fn router_market_state<DB: DatabaseRef<Error = ErrReport> + DatabaseCommit + Sync + Send + Clone + 'static>() -> Router<AppState<DB>> {
+    Router::new().route("/pools", get(market_pools)).route("/tokens", get(market_tokens))
+}
+
+pub fn router_pnl<DB: DatabaseRef + DatabaseCommit + Sync + Send + Clone + 'static>() -> Router<AppState<DB>> {
+    Router::new().route("/daily", get(pnl_daily)).route("/weekly", get(pnl_weekly))
+}
+
+pub fn router_traces<DB: DatabaseRef + DatabaseCommit + Sync + Send + Clone + 'static>() -> Router<AppState<DB>> {
+    Router::new().route("/:swap_id", get(swap_trace))
 }
 
 pub fn router_market<DB: DatabaseRef<Error = ErrReport> + DatabaseCommit + Sync + Send + Clone + 'static>() -> Router<AppState<DB>> {
     Router::new()
         .route("/pools/:address", get(pool))
         .route("/pools/:address/quote", post(pool_quote))
+        .route("/pools/:address/pending_swaps", get(pool_pending_swaps))
         .route("/pools", get(pools))
         .route("/", get(market_stats))
 }
@@ -1,17 +1,54 @@
 use crate::router::router;
+use alloy_primitives::Address;
 use axum::Router;
 use eyre::ErrReport;
-use loom_core_actors::{Actor, ActorResult, WorkerResult};
+use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, Consumer, SharedState, WorkerResult};
 use loom_core_actors_macros::Consumer;
-use loom_core_blockchain::{Blockchain, BlockchainState};
-use loom_rpc_state::AppState;
+use loom_core_blockchain::{Blockchain, BlockchainState, Strategy};
+use loom_rpc_state::{AppState, EmergencyStop, LatestArb};
 use loom_storage_db::DbPool;
+use loom_types_blockchain::LoomDataTypesEthereum;
+use loom_types_entities::LoomTxSigner;
+use loom_types_events::{MessageSwapCompose, SwapComposeMessage};
 use revm::{DatabaseCommit, DatabaseRef};
 use std::net::SocketAddr;
+use std::sync::Arc;
 use tokio::net::TcpListener;
+use tokio::sync::broadcast::error::RecvError;
 use tokio_util::sync::CancellationToken;
 use tower_http::trace::{DefaultMakeSpan, TraceLayer};
-use tracing::info;
+use tracing::{error, info};
+
+/// Keeps the latest swap ready for broadcast for `/api/v1/strategy/latest_arb`
+pub async fn latest_arb_worker<DB: Clone + Send + Sync + 'static>(
+    swap_compose_rx: Broadcaster<MessageSwapCompose<DB>>,
+    latest_arb: SharedState<Option<LatestArb>>,
+) -> WorkerResult {
+    subscribe!(swap_compose_rx);
+
+    loop {
+        match swap_compose_rx.recv().await {
+            Ok(msg) => {
+                if let SwapComposeMessage::Ready(data) = msg.inner {
+                    *latest_arb.write().await = Some(LatestArb {
+                        block_number: data.tx_compose.next_block_number,
+                        swap: data.swap.to_string(),
+                        profit_eth: data.swap.abs_profit_eth(),
+                        tips: data.tips,
+                        gas: data.tx_compose.gas,
+                        origin: data.origin.clone(),
+                    });
+                }
+            }
+            Err(RecvError::Lagged(lag)) => {
+                error!("Swap compose channel lagged by {} messages", lag);
+            }
+            Err(RecvError::Closed) => {
+                break Ok("Swap compose channel closed".to_string());
+            }
+        }
+    }
+}
 
 pub async fn start_web_server_worker<S, DB>(
     host: String,
@@ -19,6 +56,9 @@ pub async fn start_web_server_worker<S, DB>(
     bc: Blockchain,
     state: BlockchainState<DB>,
     db_pool: DbPool,
+    emergency_stop: Option<EmergencyStop>,
+    latest_arb: SharedState<Option<LatestArb>>,
+    #[cfg(feature = "prometheus")] prometheus_registry: Option<prometheus::Registry>,
     shutdown_token: CancellationToken,
 ) -> WorkerResult
 where
@@ -26,7 +66,15 @@ where
     S: Clone + Send + Sync + 'static,
     Router: From<Router<S>>,
 {
-    let app_state = AppState { db: db_pool, bc, state };
+    let app_state = AppState {
+        db: db_pool,
+        bc,
+        state,
+        emergency_stop,
+        latest_arb,
+        #[cfg(feature = "prometheus")]
+        prometheus_registry,
+    };
     let router = router(app_state);
     let router = router.merge(extra_router);
 
@@ -51,8 +99,14 @@ pub struct WebServerActor<S, DB: Clone + Send + Sync + 'static> {
     extra_router: Router<S>,
     shutdown_token: CancellationToken,
     db_pool: DbPool,
+    emergency_stop: Option<EmergencyStop>,
+    latest_arb: SharedState<Option<LatestArb>>,
+    #[cfg(feature = "prometheus")]
+    prometheus_registry: Option<prometheus::Registry>,
     bc: Option<Blockchain>,
     state: Option<BlockchainState<DB>>,
+    #[consumer]
+    swap_compose_rx: Option<Broadcaster<MessageSwapCompose<DB>>>,
 }
 
 impl<S, DB> WebServerActor<S, DB>
@@ -62,12 +116,46 @@ where
     Router: From<Router<S>>,
 {
     pub fn new(host: String, extra_router: Router<S>, db_pool: DbPool, shutdown_token: CancellationToken) -> Self {
-        Self { host, extra_router, shutdown_token, db_pool, bc: None, state: None }
+        Self {
+            host,
+            extra_router,
+            shutdown_token,
+            db_pool,
+            emergency_stop: None,
+            latest_arb: SharedState::new(None),
+            #[cfg(feature = "prometheus")]
+            prometheus_registry: None,
+            bc: None,
+            state: None,
+            swap_compose_rx: None,
+        }
     }
 
     pub fn on_bc(self, bc: &Blockchain, state: &BlockchainState<DB>) -> Self {
         Self { bc: Some(bc.clone()), state: Some(state.clone()), ..self }
     }
+
+    /// Serve the latest swap of the strategy ready for broadcast
+    pub fn on_strategy(self, strategy: &Strategy<DB>) -> Self {
+        Self { swap_compose_rx: Some(strategy.swap_compose_channel()), ..self }
+    }
+
+    /// Enable `POST /emergency-stop`, calls `stop()` of the stop contract signed by the operator EOA. Requests must send
+    /// `auth_token` as `Authorization: Bearer <auth_token>`.
+    pub fn with_emergency_stop(
+        self,
+        stop_contract: Address,
+        signer: Arc<dyn LoomTxSigner<LoomDataTypesEthereum>>,
+        auth_token: String,
+    ) -> Self {
+        Self { emergency_stop: Some(EmergencyStop { stop_contract, signer, auth_token }), ..self }
+    }
+
+    /// Append the metrics of `registry` to `GET /metrics`
+    #[cfg(feature = "prometheus")]
+    pub fn with_prometheus_registry(self, registry: prometheus::Registry) -> Self {
+        Self { prometheus_registry: Some(registry), ..self }
+    }
 }
 
 impl<S, DB> Actor for WebServerActor<S, DB>
@@ -77,15 +165,22 @@ where
     DB: DatabaseRef<Error = ErrReport> + DatabaseCommit + Send + Sync + Clone + Default + 'static,
 {
     fn start(&self) -> ActorResult {
-        let task = tokio::spawn(start_web_server_worker(
+        let mut tasks = vec![tokio::spawn(start_web_server_worker(
             self.host.clone(),
             self.extra_router.clone(),
             self.bc.clone().unwrap(),
             self.state.clone().unwrap(),
             self.db_pool.clone(),
+            self.emergency_stop.clone(),
+            self.latest_arb.clone(),
+            #[cfg(feature = "prometheus")]
+            self.prometheus_registry.clone(),
             self.shutdown_token.clone(),
-        ));
-        Ok(vec![task])
+        ))];
+        if let Some(swap_compose_rx) = self.swap_compose_rx.clone() {
+            tasks.push(tokio::spawn(latest_arb_worker(swap_compose_rx, self.latest_arb.clone())));
+        }
+        Ok(tasks)
     }
 
     fn name(&self) -> &'static str {
@@ -1,10 +1,40 @@
+use alloy_primitives::{Address, U256};
+use loom_core_actors::SharedState;
 use loom_core_blockchain::{Blockchain, BlockchainState};
 use loom_storage_db::DbPool;
+use loom_types_blockchain::LoomDataTypesEthereum;
+use loom_types_entities::LoomTxSigner;
 use revm::{DatabaseCommit, DatabaseRef};
+use std::sync::Arc;
+
+/// On-chain kill switch controlled by the operator EOA
+#[derive(Clone)]
+pub struct EmergencyStop {
+    pub stop_contract: Address,
+    pub signer: Arc<dyn LoomTxSigner<LoomDataTypesEthereum>>,
+    /// Bearer token required by `POST /emergency-stop`
+    pub auth_token: String,
+}
+
+/// Latest swap composed by the strategy and ready for broadcast
+#[derive(Clone, Debug)]
+pub struct LatestArb {
+    pub block_number: u64,
+    pub swap: String,
+    pub profit_eth: U256,
+    pub tips: Option<U256>,
+    pub gas: u64,
+    pub origin: Option<String>,
+}
 
 #[derive(Clone)]
 pub struct AppState<DB: DatabaseRef + DatabaseCommit + Clone + Send + Sync + 'static> {
     pub db: DbPool,
     pub bc: Blockchain,
     pub state: BlockchainState<DB>,
+    pub emergency_stop: Option<EmergencyStop>,
+    pub latest_arb: SharedState<Option<LatestArb>>,
+    /// Metrics appended to `GET /metrics`
+    #[cfg(feature = "prometheus")]
+    pub prometheus_registry: Option<prometheus::Registry>,
 }
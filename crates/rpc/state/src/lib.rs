@@ -1,3 +1,3 @@
-pub use app_state::AppState;
+pub use app_state::{AppState, EmergencyStop, LatestArb};
 
 mod app_state;
@@ -0,0 +1 @@
+DROP TABLE pnl_ledger;
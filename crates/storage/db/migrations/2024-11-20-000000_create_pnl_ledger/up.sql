@@ -0,0 +1,14 @@
+CREATE TABLE pnl_ledger
+(
+    id           BIGSERIAL PRIMARY KEY,
+    timestamp    TIMESTAMPTZ      NOT NULL,
+    strategy     TEXT             NOT NULL,
+    tx_hash      TEXT             NOT NULL UNIQUE,
+    path         TEXT             NOT NULL,
+    profit_eth   DOUBLE PRECISION NOT NULL,
+    profit_usd   DOUBLE PRECISION,
+    gas_cost     DOUBLE PRECISION NOT NULL,
+    block_number BIGINT           NOT NULL
+);
+
+CREATE INDEX pnl_ledger_timestamp_idx ON pnl_ledger (timestamp);
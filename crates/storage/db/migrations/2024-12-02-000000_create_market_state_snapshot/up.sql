@@ -0,0 +1,9 @@
+CREATE TABLE market_state_snapshot
+(
+    chain_id     BIGINT      NOT NULL,
+    block_number BIGINT      NOT NULL,
+    block_hash   TEXT        NOT NULL,
+    created_at   TIMESTAMPTZ NOT NULL,
+    state        TEXT        NOT NULL,
+    PRIMARY KEY (chain_id, block_number)
+);
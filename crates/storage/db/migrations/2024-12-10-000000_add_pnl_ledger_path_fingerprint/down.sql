@@ -0,0 +1,2 @@
+ALTER TABLE pnl_ledger
+    DROP COLUMN path_fingerprint;
@@ -0,0 +1,2 @@
+ALTER TABLE pnl_ledger
+    ADD COLUMN path_fingerprint TEXT;
@@ -1,3 +1,8 @@
+pub use market_state_snapshot::{insert_market_state_snapshot, latest_market_state_snapshot, MarketStateSnapshot, SnapshotError};
+pub use pnl_ledger::{insert_pnl_entry, pnl_by_period, LedgerError, PnlLedgerEntry, PnlPeriod, PnlPeriodSummary};
 pub use pool::{init_db_pool, DbPool};
 
+mod market_state_snapshot;
+mod pnl_ledger;
 mod pool;
+mod schema;
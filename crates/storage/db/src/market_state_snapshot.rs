@@ -0,0 +1,63 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use thiserror::Error;
+
+use crate::schema::market_state_snapshot;
+use crate::DbPool;
+
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    #[error("Failed to get connection: {0}")]
+    ConnectionError(#[from] bb8::RunError<diesel_async::pooled_connection::PoolError>),
+    #[error("Query failed: {0}")]
+    QueryError(#[from] diesel::result::Error),
+}
+
+/// Market state database of the chain at `block_number`, `state` is the json encoded geth state update of all cached accounts
+#[derive(Clone, Debug, Insertable, Queryable, Selectable)]
+#[diesel(table_name = market_state_snapshot)]
+pub struct MarketStateSnapshot {
+    pub chain_id: i64,
+    pub block_number: i64,
+    pub block_hash: String,
+    pub created_at: DateTime<Utc>,
+    pub state: String,
+}
+
+/// Writes the snapshot and removes snapshots of older blocks of the chain, a snapshot of an already stored block is replaced
+pub async fn insert_market_state_snapshot(db_pool: &DbPool, snapshot: &MarketStateSnapshot) -> Result<(), SnapshotError> {
+    let mut conn = db_pool.get().await?;
+    diesel::insert_into(market_state_snapshot::table)
+        .values(snapshot)
+        .on_conflict((market_state_snapshot::chain_id, market_state_snapshot::block_number))
+        .do_update()
+        .set((
+            market_state_snapshot::block_hash.eq(&snapshot.block_hash),
+            market_state_snapshot::created_at.eq(snapshot.created_at),
+            market_state_snapshot::state.eq(&snapshot.state),
+        ))
+        .execute(&mut conn)
+        .await?;
+    diesel::delete(
+        market_state_snapshot::table
+            .filter(market_state_snapshot::chain_id.eq(snapshot.chain_id))
+            .filter(market_state_snapshot::block_number.lt(snapshot.block_number)),
+    )
+    .execute(&mut conn)
+    .await?;
+    Ok(())
+}
+
+/// Snapshot of the latest block of the chain, if any
+pub async fn latest_market_state_snapshot(db_pool: &DbPool, chain_id: i64) -> Result<Option<MarketStateSnapshot>, SnapshotError> {
+    let mut conn = db_pool.get().await?;
+    let snapshot = market_state_snapshot::table
+        .filter(market_state_snapshot::chain_id.eq(chain_id))
+        .order(market_state_snapshot::block_number.desc())
+        .select(MarketStateSnapshot::as_select())
+        .first::<MarketStateSnapshot>(&mut conn)
+        .await
+        .optional()?;
+    Ok(snapshot)
+}
@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Double, Nullable, Text, Timestamptz};
+use diesel_async::RunQueryDsl;
+use thiserror::Error;
+
+use crate::schema::pnl_ledger;
+use crate::DbPool;
+
+#[derive(Debug, Error)]
+pub enum LedgerError {
+    #[error("Failed to get connection: {0}")]
+    ConnectionError(#[from] bb8::RunError<diesel_async::pooled_connection::PoolError>),
+    #[error("Query failed: {0}")]
+    QueryError(#[from] diesel::result::Error),
+}
+
+/// Realized profit of a landed transaction
+#[derive(Clone, Debug, Insertable)]
+#[diesel(table_name = pnl_ledger)]
+pub struct PnlLedgerEntry {
+    pub timestamp: DateTime<Utc>,
+    pub strategy: String,
+    pub tx_hash: String,
+    pub path: String,
+    pub profit_eth: f64,
+    pub profit_usd: Option<f64>,
+    pub gas_cost: f64,
+    pub block_number: i64,
+    /// Hex encoded `SwapLine::path_fingerprint`, set for single swap line transactions
+    pub path_fingerprint: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum PnlPeriod {
+    Day,
+    Week,
+}
+
+impl PnlPeriod {
+    /// Postgres `date_trunc` field
+    fn as_str(&self) -> &'static str {
+        match self {
+            PnlPeriod::Day => "day",
+            PnlPeriod::Week => "week",
+        }
+    }
+}
+
+/// Ledger totals of a day or a week. `profit_eth` is net of gas cost, `profit_usd` sums entries with a known USD value only.
+#[derive(Clone, Debug, QueryableByName)]
+pub struct PnlPeriodSummary {
+    #[diesel(sql_type = Timestamptz)]
+    pub period_start: DateTime<Utc>,
+    #[diesel(sql_type = BigInt)]
+    pub txs: i64,
+    #[diesel(sql_type = Double)]
+    pub profit_eth: f64,
+    #[diesel(sql_type = Nullable<Double>)]
+    pub profit_usd: Option<f64>,
+    #[diesel(sql_type = Double)]
+    pub gas_cost: f64,
+}
+
+/// Adds an entry to the ledger, an entry of an already recorded transaction is ignored
+pub async fn insert_pnl_entry(db_pool: &DbPool, entry: &PnlLedgerEntry) -> Result<(), LedgerError> {
+    let mut conn = db_pool.get().await?;
+    diesel::insert_into(pnl_ledger::table).values(entry).on_conflict(pnl_ledger::tx_hash).do_nothing().execute(&mut conn).await?;
+    Ok(())
+}
+
+/// Ledger totals of the latest `limit` periods, newest first
+pub async fn pnl_by_period(db_pool: &DbPool, period: PnlPeriod, limit: i64) -> Result<Vec<PnlPeriodSummary>, LedgerError> {
+    let mut conn = db_pool.get().await?;
+    let summaries = diesel::sql_query(
+        "SELECT date_trunc($1, timestamp) AS period_start, COUNT(*) AS txs, SUM(profit_eth) AS profit_eth, \
+         SUM(profit_usd) AS profit_usd, SUM(gas_cost) AS gas_cost \
+         FROM pnl_ledger GROUP BY period_start ORDER BY period_start DESC LIMIT $2",
+    )
+    .bind::<Text, _>(period.as_str())
+    .bind::<BigInt, _>(limit)
+    .load::<PnlPeriodSummary>(&mut conn)
+    .await?;
+    Ok(summaries)
+}
@@ -68,8 +68,13 @@ pub fn bench_swap_calculator(c: &mut Criterion) {
     println!("SwapLine: {}", swap_line);
     group.bench_function("calculate", |b| {
         b.iter(|| {
-            SwapCalculator::calculate(black_box(&mut swap_line.clone()), black_box(&state_db), black_box(Env::default()))
-                .expect("Failed to calculate swap");
+            SwapCalculator::calculate(
+                black_box(&mut swap_line.clone()),
+                black_box(&state_db),
+                black_box(Env::default()),
+                SwapCalculator::calldata_gas_cost,
+            )
+            .expect("Failed to calculate swap");
         })
     });
 
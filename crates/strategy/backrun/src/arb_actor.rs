@@ -1,6 +1,7 @@
 use std::marker::PhantomData;
 
 use alloy_network::Network;
+use alloy_primitives::Address;
 use alloy_provider::Provider;
 use eyre::ErrReport;
 use influxdb::WriteQuery;
@@ -8,16 +9,19 @@ use revm::{Database, DatabaseCommit, DatabaseRef};
 use tokio::task::JoinHandle;
 use tracing::info;
 
-use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
+use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, Producer, SharedState, WorkerResult};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
+use loom_evm_db::DatabaseLoomExt;
 use loom_node_debug_provider::DebugProviderExt;
 use loom_types_blockchain::Mempool;
-use loom_types_entities::{BlockHistory, LatestBlock, Market, MarketState};
-use loom_types_events::{MarketEvents, MempoolEvents, MessageHealthEvent, MessageSwapCompose};
+use loom_types_entities::{AccountNonceAndBalanceState, BlockHistory, LatestBlock, Market, MarketState};
+use loom_types_events::{LoomTask, MarketEvents, MempoolEvents, MessageHealthEvent, MessageSwapCompose};
 
 use super::{PendingTxStateChangeProcessorActor, StateChangeArbSearcherActor};
 use crate::block_state_change_processor::BlockStateChangeProcessorActor;
 use crate::BackrunConfig;
+use crate::GasAuctionState;
+use crate::LandingProbabilityEstimator;
 use crate::rate_limited_client::RateLimitedClient;
 
 #[derive(Accessor, Consumer, Producer)]
@@ -26,6 +30,7 @@ pub struct StateChangeArbActor<P, N, DB: Clone + Send + Sync + 'static> {
     client: P,
     use_blocks: bool,
     use_mempool: bool,
+    multicaller_address: Option<Address>,
     #[accessor]
     market: Option<SharedState<Market>>,
     #[accessor]
@@ -36,16 +41,25 @@ pub struct StateChangeArbActor<P, N, DB: Clone + Send + Sync + 'static> {
     market_state: Option<SharedState<MarketState<DB>>>,
     #[accessor]
     block_history: Option<SharedState<BlockHistory<DB>>>,
+    #[accessor]
+    gas_auction_state: Option<SharedState<GasAuctionState>>,
+    #[accessor]
+    landing_probability: Option<SharedState<LandingProbabilityEstimator>>,
+    #[accessor]
+    nonce_and_balance: Option<SharedState<AccountNonceAndBalanceState>>,
     #[consumer]
     mempool_events_tx: Option<Broadcaster<MempoolEvents>>,
     #[consumer]
     market_events_tx: Option<Broadcaster<MarketEvents>>,
+    #[consumer]
+    tasks_rx: Option<Broadcaster<LoomTask>>,
     #[producer]
     compose_channel_tx: Option<Broadcaster<MessageSwapCompose<DB>>>,
     #[producer]
     pool_health_monitor_tx: Option<Broadcaster<MessageHealthEvent>>,
     #[producer]
     influxdb_write_channel_tx: Option<Broadcaster<WriteQuery>>,
+    cancellation_token: CancellationToken,
 
     _n: PhantomData<N>,
 }
@@ -62,32 +76,73 @@ where
             client,
             use_blocks,
             use_mempool,
+            multicaller_address: None,
             market: None,
             mempool: None,
             latest_block: None,
             block_history: None,
             market_state: None,
+            gas_auction_state: None,
+            landing_probability: None,
+            nonce_and_balance: None,
             mempool_events_tx: None,
             market_events_tx: None,
+            tasks_rx: None,
             compose_channel_tx: None,
             pool_health_monitor_tx: None,
             influxdb_write_channel_tx: None,
+            cancellation_token: CancellationToken::new(),
             _n: PhantomData,
         }
     }
+
+    pub fn with_multicaller_address(self, multicaller_address: Address) -> Self {
+        Self { multicaller_address: Some(multicaller_address), ..self }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
 }
 
 impl<P, N, DB> Actor for StateChangeArbActor<P, N, DB>
 where
     N: Network,
     P: Provider<N> + DebugProviderExt<N> + Send + Sync + Clone + 'static,
-    DB: DatabaseRef<Error = ErrReport> + Database<Error = ErrReport> + DatabaseCommit + Send + Sync + Clone + Default + 'static,
+    DB: DatabaseRef<Error = ErrReport>
+        + Database<Error = ErrReport>
+        + DatabaseCommit
+        + DatabaseLoomExt
+        + Send
+        + Sync
+        + Clone
+        + Default
+        + 'static,
 {
     fn start(&self) -> ActorResult {
         let searcher_pool_update_channel = Broadcaster::new(100);
         let mut tasks: Vec<JoinHandle<WorkerResult>> = Vec::new();
 
-        let mut state_update_searcher = StateChangeArbSearcherActor::new(self.backrun_config.clone());
+        let mut state_update_searcher =
+            StateChangeArbSearcherActor::new(self.backrun_config.clone()).with_cancellation_token(self.cancellation_token.clone());
+        if let Some(multicaller_address) = self.multicaller_address {
+            state_update_searcher = state_update_searcher.with_multicaller_address(multicaller_address);
+        }
+        if let Some(gas_auction_state) = &self.gas_auction_state {
+            state_update_searcher.access(gas_auction_state.clone());
+        }
+        if let Some(landing_probability) = &self.landing_probability {
+            state_update_searcher.access(landing_probability.clone());
+        }
+        if let Some(nonce_and_balance) = &self.nonce_and_balance {
+            state_update_searcher.access(nonce_and_balance.clone());
+        }
+        if let Some(market_events_tx) = &self.market_events_tx {
+            state_update_searcher.consume(market_events_tx.clone());
+        }
+        if let Some(tasks_rx) = &self.tasks_rx {
+            state_update_searcher.consume(tasks_rx.clone());
+        }
 
         // Check required fields before unwrap
         let market = match &self.market {
@@ -163,7 +218,8 @@ where
 
             let rate_limit_rps = self.backrun_config.rate_limit_rps.unwrap_or(0);
             let client = RateLimitedClient::new(self.client.clone(), rate_limit_rps);
-            let mut pending_tx_state_processor = PendingTxStateChangeProcessorActor::new(client);
+            let mut pending_tx_state_processor =
+                PendingTxStateChangeProcessorActor::new(client).with_cancellation_token(self.cancellation_token.clone());
             match pending_tx_state_processor
                 .access(mempool)
                 .access(latest_block)
@@ -200,11 +256,12 @@ where
                 }
             };
 
-            let mut block_state_processor = BlockStateChangeProcessorActor::new();
+            let mut block_state_processor = BlockStateChangeProcessorActor::new().with_cancellation_token(self.cancellation_token.clone());
             match block_state_processor
                 .access(market)
                 .access(block_history)
-                .consume(market_events_tx)
+                .consume(market_events_tx.clone())
+                .produce(market_events_tx)
                 .produce(searcher_pool_update_channel.clone())
                 .start()
             {
@@ -1,6 +1,12 @@
+use alloy_network::Network;
 use alloy_primitives::{Address, U256};
+use alloy_provider::Provider;
+use eyre::Result;
 use loom_types_entities::strategy_config::StrategyConfig;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Clone, Deserialize, Debug)]
 pub struct BackrunConfigSection {
@@ -15,6 +21,7 @@ pub struct BaseNetworkConfig {
     pub flash_loan_fee_bps: Option<u64>, // Basis points (e.g., 30 = 0.3%)
     pub max_capital_usd: Option<u64>,    // Maximum capital in USD
     pub gas_boost_percent: Option<u64>,  // Percentage to boost gas price by
+    pub bribe_pct: Option<u8>,           // Percentage of profit after base fee paid to the block builder
     pub private_tx_enabled: Option<bool>, // Whether to use private transactions
     pub mev_blocker_enabled: Option<bool>, // Whether to use MEV blocker
 }
@@ -28,6 +35,7 @@ impl Default for BaseNetworkConfig {
             flash_loan_fee_bps: Some(30), // 0.3% flash loan fee
             max_capital_usd: Some(100_000), // $100,000 USD
             gas_boost_percent: Some(10), // 10% gas boost
+            bribe_pct: Some(50), // 50% of profit to the block builder
             private_tx_enabled: Some(false), // Private transactions disabled by default
             mev_blocker_enabled: Some(false), // MEV blocker disabled by default
         }
@@ -37,6 +45,12 @@ impl Default for BaseNetworkConfig {
 #[derive(Clone, Deserialize, Debug)]
 pub struct BackrunConfig {
     eoa: Option<Address>,
+    // EOAs assigned round-robin to composed transactions, a signer key is needed for each of them
+    #[serde(default)]
+    eoa_rotation: Vec<Address>,
+    // Rotation position shared by the clones of the config handed to the searcher tasks
+    #[serde(skip)]
+    eoa_rotation_idx: Arc<AtomicUsize>,
     smart: bool,
     chain_id: Option<u64>,
     base_config: Option<BaseNetworkConfig>,
@@ -44,6 +58,58 @@ pub struct BackrunConfig {
     max_path_length: Option<usize>,
     private_tx_url: Option<String>, // URL for private transaction service
     pub rate_limit_rps: Option<u32>,
+    #[serde(default = "default_max_concurrent_searches")]
+    max_concurrent_searches: usize, // Searches running EVM simulations at the same time
+    #[serde(default = "default_bundle_simulation_timeout_ms")]
+    bundle_simulation_timeout_ms: u64, // Calculation of a single swap path is abandoned after this time
+    #[serde(default)]
+    enable_partial_fill_probing: bool, // Bundle a half size probing swap before the full size one
+    #[serde(default)]
+    use_flash_loans: bool, // Borrow the input of swaps exceeding the multicaller balance from the Balancer vault
+    #[serde(default)]
+    enable_eip7702: bool, // Call swaps on the EOA delegated to the multicaller, requires Pectra
+    #[serde(default)]
+    use_access_list: bool, // Attach the eth_createAccessList access list when it lowers gas used
+    // Priority fee of the backrun over the highest priority fee of the stuffing transactions. Higher multipliers make
+    // landing more likely but cut the net profit, the premium is paid on all gas of the bundle
+    #[serde(default = "default_stuffing_tx_gas_multiplier", deserialize_with = "deserialize_stuffing_tx_gas_multiplier")]
+    stuffing_tx_gas_multiplier: f64,
+    #[serde(default = "default_min_landing_probability")]
+    min_landing_probability: f32, // Swaps with a lower estimated bundle landing probability are not routed
+}
+
+const DEFAULT_MAX_CONCURRENT_SEARCHES: usize = 8;
+
+const DEFAULT_BUNDLE_SIMULATION_TIMEOUT_MS: u64 = 100;
+
+fn default_max_concurrent_searches() -> usize {
+    DEFAULT_MAX_CONCURRENT_SEARCHES
+}
+
+fn default_bundle_simulation_timeout_ms() -> u64 {
+    DEFAULT_BUNDLE_SIMULATION_TIMEOUT_MS
+}
+
+const DEFAULT_STUFFING_TX_GAS_MULTIPLIER: f64 = 1.1;
+
+fn default_stuffing_tx_gas_multiplier() -> f64 {
+    DEFAULT_STUFFING_TX_GAS_MULTIPLIER
+}
+
+const DEFAULT_MIN_LANDING_PROBABILITY: f32 = 0.05;
+
+fn default_min_landing_probability() -> f32 {
+    DEFAULT_MIN_LANDING_PROBABILITY
+}
+
+fn deserialize_stuffing_tx_gas_multiplier<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    let multiplier = f64::deserialize(deserializer)?;
+    if !multiplier.is_finite() || multiplier < 1.0 {
+        return Err(serde::de::Error::custom(format!(
+            "stuffing_tx_gas_multiplier must be a finite number of at least 1.0, got {multiplier}"
+        )));
+    }
+    Ok(multiplier)
 }
 
 impl StrategyConfig for BackrunConfig {
@@ -57,9 +123,29 @@ impl BackrunConfig {
         self.smart
     }
 
+    pub fn eoa_rotation(&self) -> &[Address] {
+        &self.eoa_rotation
+    }
+
+    pub fn with_eoa_rotation(self, eoa_rotation: Vec<Address>) -> Self {
+        Self { eoa_rotation, eoa_rotation_idx: Arc::new(AtomicUsize::new(0)), ..self }
+    }
+
+    /// Next EOA of the rotation, falls back to `eoa` when no rotation is configured. Clones of the config advance the same
+    /// rotation.
+    pub fn next_eoa(&self) -> Option<Address> {
+        if self.eoa_rotation.is_empty() {
+            return self.eoa;
+        }
+        let idx = self.eoa_rotation_idx.fetch_add(1, Ordering::Relaxed);
+        Some(self.eoa_rotation[idx % self.eoa_rotation.len()])
+    }
+
     pub fn new_dumb() -> Self {
         Self { 
             eoa: None, 
+            eoa_rotation: Vec::new(),
+            eoa_rotation_idx: Arc::new(AtomicUsize::new(0)),
             smart: false,
             chain_id: Some(8453), // Default to Base Network
             base_config: None,
@@ -67,6 +153,14 @@ impl BackrunConfig {
             max_path_length: Some(4),
             private_tx_url: None,
             rate_limit_rps: None,
+            max_concurrent_searches: DEFAULT_MAX_CONCURRENT_SEARCHES,
+            bundle_simulation_timeout_ms: DEFAULT_BUNDLE_SIMULATION_TIMEOUT_MS,
+            enable_partial_fill_probing: false,
+            use_flash_loans: false,
+            enable_eip7702: false,
+            use_access_list: false,
+            stuffing_tx_gas_multiplier: DEFAULT_STUFFING_TX_GAS_MULTIPLIER,
+            min_landing_probability: DEFAULT_MIN_LANDING_PROBABILITY,
         }
     }
     
@@ -78,6 +172,20 @@ impl BackrunConfig {
         self.chain_id.unwrap_or(1) // Default to Ethereum mainnet
     }
     
+    /// Check that the node behind `client` serves the configured chain.
+    /// Panics on mismatch so a misconfigured bot never starts loading pools.
+    pub async fn chain_id_validation<P, N>(&self, client: &P) -> Result<()>
+    where
+        N: Network,
+        P: Provider<N>,
+    {
+        let node_chain_id = client.get_chain_id().await?;
+        if node_chain_id != self.chain_id() {
+            panic!("Chain id mismatch: backrun config expects chain_id={} but the node reports chain_id={}", self.chain_id(), node_chain_id);
+        }
+        Ok(())
+    }
+
     pub fn with_chain_id(mut self, chain_id: u64) -> Self {
         self.chain_id = Some(chain_id);
         self
@@ -87,12 +195,10 @@ impl BackrunConfig {
         self.base_config.clone().unwrap_or_default()
     }
     
-    pub fn min_profit_wei(&self) -> U256 {
-        if self.is_base_network() {
-            self.base_config().min_profit_wei.unwrap_or(U256::from(1_000_000_000_000_000u64))
-        } else {
-            U256::from(1_000_000_000_000_000u64) // Default 0.001 ETH
-        }
+    /// Configured Base threshold, otherwise the threshold of the chain from `Blockchain::min_profit_wei`, 0.001 ETH by default
+    pub fn min_profit_wei(&self, chain_min_profit_wei: Option<U256>) -> U256 {
+        let configured = self.base_config.as_ref().and_then(|base_config| base_config.min_profit_wei).filter(|_| self.is_base_network());
+        configured.or(chain_min_profit_wei).unwrap_or(U256::from(1_000_000_000_000_000u64))
     }
     
     pub fn flash_loan_fee_bps(&self) -> u64 {
@@ -111,11 +217,53 @@ impl BackrunConfig {
         self.max_path_length.unwrap_or(4) // Default to 4 hops
     }
     
+    pub fn max_concurrent_searches(&self) -> usize {
+        self.max_concurrent_searches.max(1)
+    }
+
+    pub fn bundle_simulation_timeout(&self) -> Duration {
+        Duration::from_millis(self.bundle_simulation_timeout_ms.max(1))
+    }
+
+    pub fn enable_partial_fill_probing(&self) -> bool {
+        self.enable_partial_fill_probing
+    }
+
+    pub fn use_flash_loans(&self) -> bool {
+        self.use_flash_loans
+    }
+
+    pub fn enable_eip7702(&self) -> bool {
+        self.enable_eip7702
+    }
+
+    pub fn use_access_list(&self) -> bool {
+        self.use_access_list
+    }
+
+    pub fn stuffing_tx_gas_multiplier(&self) -> f64 {
+        self.stuffing_tx_gas_multiplier
+    }
+
+    pub fn min_landing_probability(&self) -> f32 {
+        self.min_landing_probability
+    }
+
+    /// Priority fee raised to `stuffing_tx_gas_multiplier` times the highest priority fee of the stuffing transactions
+    pub fn apply_stuffing_tx_gas_multiplier(&self, priority_fee: u64, stuffing_priority_fee: u64) -> u64 {
+        let min_priority_fee = (stuffing_priority_fee as f64 * self.stuffing_tx_gas_multiplier) as u64;
+        priority_fee.max(min_priority_fee)
+    }
+    
     // Gas optimization methods
     pub fn gas_boost_percent(&self) -> u64 {
         self.base_config().gas_boost_percent.unwrap_or(10) // Default 10%
     }
     
+    pub fn bribe_pct(&self) -> u8 {
+        self.base_config().bribe_pct.unwrap_or(50) // Default 50%
+    }
+    
     pub fn calculate_gas_price(&self, base_gas_price: U256) -> U256 {
         let boost_percent = self.gas_boost_percent();
         let boost_multiplier = 100 + boost_percent;
@@ -153,6 +301,8 @@ impl Default for BackrunConfig {
     fn default() -> Self {
         Self { 
             eoa: None, 
+            eoa_rotation: Vec::new(),
+            eoa_rotation_idx: Arc::new(AtomicUsize::new(0)),
             smart: true,
             chain_id: Some(8453), // Default to Base Network
             base_config: None,
@@ -160,6 +310,88 @@ impl Default for BackrunConfig {
             max_path_length: Some(4),
             private_tx_url: None,
             rate_limit_rps: None,
+            max_concurrent_searches: DEFAULT_MAX_CONCURRENT_SEARCHES,
+            bundle_simulation_timeout_ms: DEFAULT_BUNDLE_SIMULATION_TIMEOUT_MS,
+            enable_partial_fill_probing: false,
+            use_flash_loans: false,
+            enable_eip7702: false,
+            use_access_list: false,
+            stuffing_tx_gas_multiplier: DEFAULT_STUFFING_TX_GAS_MULTIPLIER,
+            min_landing_probability: DEFAULT_MIN_LANDING_PROBABILITY,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use loom_types_entities::AccountNonceAndBalanceState;
+    use serde::de::IntoDeserializer;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_eoa_rotation_unique_nonces() {
+        let eoa_rotation = vec![Address::repeat_byte(1), Address::repeat_byte(2), Address::repeat_byte(3)];
+        let backrun_config = BackrunConfig::default().with_eoa_rotation(eoa_rotation.clone());
+        let mut nonce_and_balance = AccountNonceAndBalanceState::new();
+        for eoa in backrun_config.eoa_rotation() {
+            nonce_and_balance.add_account(*eoa).set_nonce(10);
         }
+
+        let bundles: Vec<(Address, u64)> = (0..7)
+            .map(|group| {
+                let eoa = backrun_config.next_eoa().unwrap();
+                (eoa, nonce_and_balance.reserve_nonce(eoa, group))
+            })
+            .collect();
+
+        assert_eq!(bundles.iter().collect::<HashSet<_>>().len(), bundles.len());
+        assert_eq!(bundles[..3].iter().map(|(eoa, _)| *eoa).collect::<Vec<_>>(), eoa_rotation);
+        assert_eq!(bundles[..3].iter().map(|(_, nonce)| *nonce).collect::<Vec<_>>(), vec![10, 10, 10]);
+        assert_eq!(bundles[6], (Address::repeat_byte(1), 12));
+
+        assert_eq!(BackrunConfig::default().next_eoa(), None);
+    }
+
+    #[test]
+    fn test_eoa_rotation_shared_by_clones() {
+        let eoa_rotation = vec![Address::repeat_byte(1), Address::repeat_byte(2), Address::repeat_byte(3)];
+        let backrun_config = BackrunConfig::default().with_eoa_rotation(eoa_rotation.clone());
+
+        // Every searcher task composes with its own clone of the config
+        let eoas: Vec<Address> = (0..4).map(|_| backrun_config.clone().next_eoa().unwrap()).collect();
+        assert_eq!(eoas, vec![eoa_rotation[0], eoa_rotation[1], eoa_rotation[2], eoa_rotation[0]]);
+    }
+
+    #[test]
+    fn test_stuffing_tx_gas_multiplier() {
+        let backrun_config: BackrunConfig = serde_json::from_str(r#"{"smart":true}"#).unwrap();
+        assert_eq!(backrun_config.stuffing_tx_gas_multiplier(), DEFAULT_STUFFING_TX_GAS_MULTIPLIER);
+        assert_eq!(backrun_config.apply_stuffing_tx_gas_multiplier(100, 1000), 1100);
+        assert_eq!(backrun_config.apply_stuffing_tx_gas_multiplier(2000, 1000), 2000);
+
+        let backrun_config: BackrunConfig = serde_json::from_str(r#"{"smart":true,"stuffing_tx_gas_multiplier":1.5}"#).unwrap();
+        assert_eq!(backrun_config.apply_stuffing_tx_gas_multiplier(100, 1000), 1500);
+
+        assert!(serde_json::from_str::<BackrunConfig>(r#"{"smart":true,"stuffing_tx_gas_multiplier":0.9}"#).is_err());
+
+        let deserialize = |multiplier: f64| -> Result<f64, serde::de::value::Error> {
+            deserialize_stuffing_tx_gas_multiplier(multiplier.into_deserializer())
+        };
+        assert!(deserialize(f64::INFINITY).is_err());
+        assert!(deserialize(f64::NAN).is_err());
+        assert_eq!(deserialize(2.0).unwrap(), 2.0);
+    }
+
+    #[test]
+    fn test_min_profit_wei() {
+        let chain_min_profit_wei = Some(U256::from(1_000_000_000_000u64));
+        let backrun_config = BackrunConfig::default();
+        assert_eq!(backrun_config.min_profit_wei(None), U256::from(1_000_000_000_000_000u64));
+        assert_eq!(backrun_config.min_profit_wei(chain_min_profit_wei), U256::from(1_000_000_000_000u64));
+
+        let backrun_config: BackrunConfig =
+            serde_json::from_str(r#"{"smart":true,"chain_id":8453,"base_config":{"min_profit_wei":"0x2540be400"}}"#).unwrap();
+        assert_eq!(backrun_config.min_profit_wei(chain_min_profit_wei), U256::from(10_000_000_000u64));
     }
 }
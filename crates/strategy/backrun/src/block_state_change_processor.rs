@@ -1,27 +1,55 @@
 use super::affected_pools_state::get_affected_pools_from_state_update;
-use eyre::eyre;
-use loom_core_actors::{run_sync, subscribe, Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
+use super::pool_swap_volume::get_pool_swap_volumes_from_logs;
+use alloy_primitives::U256;
+use eyre::{eyre, ErrReport};
+use loom_core_actors::{
+    run_sync, subscribe, Accessor, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, Producer, SharedState, WorkerResult,
+};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
 use loom_core_blockchain::{Blockchain, BlockchainState, Strategy};
 use loom_types_blockchain::ChainParameters;
 use loom_types_blockchain::LoomDataTypesEthereum;
-use loom_types_entities::{BlockHistory, Market};
+use loom_types_entities::{BlockHistory, Market, PoolWrapper};
 use loom_types_events::{MarketEvents, StateUpdateEvent};
+use revm::primitives::Env;
 use revm::DatabaseRef;
 use tokio::sync::broadcast::error::RecvError;
 use tracing::error;
 
-pub async fn block_state_change_worker<DB: DatabaseRef + Send + Sync + Clone + 'static>(
+// Swaps on a pool between two PoolStatsUpdate events
+const DEFAULT_POOL_STATS_UPDATE_INTERVAL: u64 = 100;
+
+// Amount of the second pool token for one unit of the first one after the pool fee, probed with a thousandth of a unit
+fn pool_spot_price(market: &Market, pool: &PoolWrapper, state_db: &dyn DatabaseRef<Error = ErrReport>) -> Option<f64> {
+    let tokens = pool.get_tokens();
+    let (token_from, token_to) = (market.get_token_or_default(tokens.first()?), market.get_token_or_default(tokens.get(1)?));
+    let in_amount = U256::from(10).pow(U256::from(token_from.get_decimals())) / U256::from(1000);
+    let (out_amount, _) =
+        pool.calculate_out_amount(state_db, Env::default(), &token_from.get_address(), &token_to.get_address(), in_amount).ok()?;
+    Some(token_to.to_float(out_amount) / token_from.to_float(in_amount))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn block_state_change_worker<DB: DatabaseRef<Error = ErrReport> + Send + Sync + Clone + 'static>(
     chain_parameters: ChainParameters,
+    pool_stats_update_interval: u64,
     market: SharedState<Market>,
     block_history: SharedState<BlockHistory<DB>>,
     market_events_rx: Broadcaster<MarketEvents>,
+    market_events_tx: Broadcaster<MarketEvents>,
     state_updates_broadcaster: Broadcaster<StateUpdateEvent<DB, LoomDataTypesEthereum>>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult {
     subscribe!(market_events_rx);
 
     loop {
-        let market_event = match market_events_rx.recv().await {
+        let market_event = tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Block state change processor cancelled".to_string());
+            }
+            market_event = market_events_rx.recv() => market_event,
+        };
+        let market_event = match market_event {
             Ok(market_event) => market_event,
             Err(e) => match e {
                 RecvError::Closed => {
@@ -61,6 +89,38 @@ pub async fn block_state_change_worker<DB: DatabaseRef + Send + Sync + Clone + '
             continue;
         };
 
+        let pool_spot_prices: Vec<_> = {
+            let market_guard = market.read().await;
+            affected_pools.keys().map(|pool| (pool.get_pool_id(), pool_spot_price(&market_guard, pool, &block_state_entry))).collect()
+        };
+
+        {
+            let mut market_guard = market.write().await;
+            // A pool failing to calculate drops its stale spot price
+            for (pool_id, spot_price) in pool_spot_prices {
+                market_guard.set_pool_spot_price(pool_id, spot_price.unwrap_or_default());
+            }
+            let pool_volumes = get_pool_swap_volumes_from_logs(&market_guard, block_history_entry.logs.as_deref().unwrap_or_default());
+            for (pool_id, pool_volume) in pool_volumes {
+                let swap_count =
+                    market_guard.record_pool_swaps(pool_id, pool_volume.swap_count, pool_volume.volume_eth, block_history_entry.number());
+                market_guard.record_pool_window_activity(
+                    pool_id,
+                    block_history_entry.timestamp(),
+                    pool_volume.volume_eth,
+                    pool_volume.competing_swap_count,
+                );
+                // A block can carry several swaps, emit once the count crosses a multiple of the interval
+                let prev_swap_count = swap_count - pool_volume.swap_count;
+                if pool_stats_update_interval > 0 && swap_count / pool_stats_update_interval > prev_swap_count / pool_stats_update_interval
+                {
+                    if let Some(stats) = market_guard.get_pool_stats(&pool_id) {
+                        run_sync!(market_events_tx.send(MarketEvents::PoolStatsUpdate { pool_id, stats }));
+                    }
+                }
+            }
+        }
+
         let next_block_number = block_history_entry.number() + 1;
         let next_block_timestamp = block_history_entry.timestamp() + 12;
         let next_base_fee = chain_parameters.calc_next_block_base_fee_from_header(&block_history_entry.header);
@@ -85,6 +145,7 @@ pub async fn block_state_change_worker<DB: DatabaseRef + Send + Sync + Clone + '
 #[derive(Accessor, Consumer, Producer)]
 pub struct BlockStateChangeProcessorActor<DB: Clone + Send + Sync + 'static> {
     chain_parameters: ChainParameters,
+    pool_stats_update_interval: u64,
     #[accessor]
     market: Option<SharedState<Market>>,
     #[accessor]
@@ -92,27 +153,44 @@ pub struct BlockStateChangeProcessorActor<DB: Clone + Send + Sync + 'static> {
     #[consumer]
     market_events_rx: Option<Broadcaster<MarketEvents>>,
     #[producer]
+    market_events_tx: Option<Broadcaster<MarketEvents>>,
+    #[producer]
     state_updates_tx: Option<Broadcaster<StateUpdateEvent<DB>>>,
+    cancellation_token: CancellationToken,
 }
 
 impl<DB: DatabaseRef + Send + Sync + Clone + 'static> BlockStateChangeProcessorActor<DB> {
     pub fn new() -> BlockStateChangeProcessorActor<DB> {
         BlockStateChangeProcessorActor {
             chain_parameters: ChainParameters::ethereum(),
+            pool_stats_update_interval: DEFAULT_POOL_STATS_UPDATE_INTERVAL,
             market: None,
             block_history: None,
             market_events_rx: None,
+            market_events_tx: None,
             state_updates_tx: None,
+            cancellation_token: CancellationToken::new(),
         }
     }
 
+    /// Emit `MarketEvents::PoolStatsUpdate` for a pool every `interval` swaps, zero disables the events
+    pub fn with_pool_stats_update_interval(self, interval: u64) -> Self {
+        Self { pool_stats_update_interval: interval, ..self }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
     pub fn on_bc(self, bc: &Blockchain, state: &BlockchainState<DB>, strategy: &Strategy<DB>) -> Self {
         Self {
             chain_parameters: bc.chain_parameters(),
             market: Some(bc.market()),
             market_events_rx: Some(bc.market_events_channel()),
+            market_events_tx: Some(bc.market_events_channel()),
             state_updates_tx: Some(strategy.state_update_channel()),
             block_history: Some(state.block_history()),
+            ..self
         }
     }
 }
@@ -123,14 +201,17 @@ impl<DB: DatabaseRef + Send + Sync + Clone + 'static> Default for BlockStateChan
     }
 }
 
-impl<DB: DatabaseRef + Send + Sync + Clone + 'static> Actor for BlockStateChangeProcessorActor<DB> {
+impl<DB: DatabaseRef<Error = ErrReport> + Send + Sync + Clone + 'static> Actor for BlockStateChangeProcessorActor<DB> {
     fn start(&self) -> ActorResult {
         let task = tokio::task::spawn(block_state_change_worker(
             self.chain_parameters.clone(),
+            self.pool_stats_update_interval,
             self.market.clone().unwrap(),
             self.block_history.clone().unwrap(),
             self.market_events_rx.clone().unwrap(),
+            self.market_events_tx.clone().unwrap(),
             self.state_updates_tx.clone().unwrap(),
+            self.cancellation_token.clone(),
         ));
         Ok(vec![task])
     }
@@ -2,34 +2,91 @@ use alloy_primitives::{Address, U256};
 use eyre::{eyre, Result};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::{debug, error, info};
+use tokio::sync::{RwLock, RwLockReadGuard};
+use tracing::{debug, error, info, warn};
 
-use loom_types_entities::{Market, PoolWrapper, Token};
+use loom_core_actors::Broadcaster;
+use loom_types_entities::{AccountNonceAndBalanceState, Market, PnlHistory, PoolWrapper, SwapPath, Token};
+use loom_types_events::{HealthEvent, Message, MessageHealthEvent};
 use std::collections::HashSet;
 
+/// Concurrent trades the balance should cover before `HealthEvent::LowBalance` is sent
+const MIN_BALANCE_TRADES: u64 = 3;
+
 /// CapitalManager handles dynamic capital allocation for arbitrage trades
 pub struct CapitalManager {
     /// Maximum capital in USD (with 6 decimals)
-    max_capital_usd: U256,
+    max_capital_usd: RwLock<U256>,
     /// Token prices in USD (with 6 decimals)
     prices: RwLock<HashMap<Address, U256>>,
     /// Pool liquidity estimates
     pool_liquidity: RwLock<HashMap<String, U256>>,
     /// ETH price in USD (with 6 decimals)
     eth_usd_price: RwLock<U256>,
+    /// Simulation success history per swap path
+    pnl_history: RwLock<PnlHistory>,
+    /// Low balance events are sent here
+    health_monitor_tx: Option<Broadcaster<MessageHealthEvent>>,
 }
 
 impl CapitalManager {
     /// Create a new capital manager
     pub fn new(max_capital_usd: u64) -> Self {
         Self {
-            max_capital_usd: U256::from(max_capital_usd * 1_000_000), // Convert to 6 decimals
+            max_capital_usd: RwLock::new(U256::from(max_capital_usd * 1_000_000)), // Convert to 6 decimals
             prices: RwLock::new(HashMap::new()),
             pool_liquidity: RwLock::new(HashMap::new()),
             eth_usd_price: RwLock::new(U256::from(2000 * 1_000_000)), // Default ETH price: $2000 with 6 decimals
+            pnl_history: RwLock::new(PnlHistory::new()),
+            health_monitor_tx: None,
         }
     }
+
+    pub fn with_health_monitor_channel(self, health_monitor_tx: Broadcaster<MessageHealthEvent>) -> Self {
+        Self { health_monitor_tx: Some(health_monitor_tx), ..self }
+    }
+
+    /// Clamp `amount` of `token` to the balance of `account`, the multicaller holds the swap capital. `HealthEvent::LowBalance`
+    /// is sent when the balance covers fewer than 3 concurrent trades of `amount`.
+    pub fn rebalance_check(
+        &self,
+        token: &Token,
+        amount: U256,
+        account: Address,
+        balance_state: &AccountNonceAndBalanceState,
+    ) -> Result<U256> {
+        let balance = balance_state.get_account(&account).map(|account| account.get_balance(&token.get_address())).unwrap_or_default();
+
+        if balance < amount.saturating_mul(U256::from(MIN_BALANCE_TRADES)) {
+            warn!("Low {} balance {} for trades of {}", token.get_symbol(), token.to_float(balance), token.to_float(amount));
+            if let Some(health_monitor_tx) = &self.health_monitor_tx {
+                let health_event = HealthEvent::LowBalance { token: token.get_address(), balance, amount };
+                if let Err(e) = health_monitor_tx.send(Message::new(health_event)) {
+                    error!("health_monitor_tx.send : {}", e);
+                }
+            }
+        }
+
+        if balance.is_zero() {
+            return Err(eyre!("NO_BALANCE"));
+        }
+        Ok(amount.min(balance))
+    }
+
+    /// Record the outcome of a path simulation
+    pub async fn record_simulation(&self, swap_path: &SwapPath, success: bool) {
+        self.pnl_history.write().await.record_simulation(swap_path.get_hash(), success);
+    }
+
+    /// Simulation history to score paths by `SwapPath::score_by_historical_success_rate`, held while scoring all paths of a search
+    pub async fn read_pnl_history(&self) -> RwLockReadGuard<'_, PnlHistory> {
+        self.pnl_history.read().await
+    }
+
+    /// Snapshot of the simulation history
+    pub async fn pnl_history(&self) -> PnlHistory {
+        self.pnl_history.read().await.clone()
+    }
     
     /// Update the ETH price in USD
     pub async fn update_eth_price(&self, price_usd: u64) {
@@ -39,8 +96,8 @@ impl CapitalManager {
     }
     
     /// Set the maximum capital in USD
-    pub fn set_max_capital_usd(&mut self, max_capital_usd: u64) {
-        self.max_capital_usd = U256::from(max_capital_usd * 1_000_000); // Convert to 6 decimals
+    pub async fn set_max_capital_usd(&self, max_capital_usd: u64) {
+        *self.max_capital_usd.write().await = U256::from(max_capital_usd * 1_000_000); // Convert to 6 decimals
     }
     
     /// Update the price of a token
@@ -77,6 +134,8 @@ impl CapitalManager {
         
         // Calculate the maximum amount based on USD limit
         let max_from_usd = self.max_capital_usd
+            .read()
+            .await
             .checked_mul(U256::from(10).pow(U256::from(token.get_decimals())))
             .ok_or_else(|| eyre!("Overflow in max_from_usd calculation"))?
             .checked_div(token_price)
@@ -265,4 +324,32 @@ impl CapitalManager {
         
         Ok(min_liquidity)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::utils::parse_ether;
+    use loom_defi_address_book::TokenAddressEth;
+
+    #[test]
+    fn test_rebalance_check_reads_account_balance() {
+        let capital_manager = CapitalManager::new(100_000);
+        let weth = Token::new_with_data(TokenAddressEth::WETH, Some("WETH".to_string()), None, Some(18), true, false);
+        let multicaller = Address::repeat_byte(0x11);
+        let eoa = Address::repeat_byte(0x22);
+
+        let mut balance_state = AccountNonceAndBalanceState::new();
+        balance_state.add_account(multicaller).set_balance(TokenAddressEth::WETH, parse_ether("2").unwrap());
+        balance_state.add_account(eoa).set_balance(TokenAddressEth::WETH, parse_ether("10").unwrap());
+
+        // Amounts above the multicaller balance are clamped even though the EOA holds more
+        let amount = capital_manager.rebalance_check(&weth, parse_ether("5").unwrap(), multicaller, &balance_state).unwrap();
+        assert_eq!(amount, parse_ether("2").unwrap());
+
+        let amount = capital_manager.rebalance_check(&weth, parse_ether("0.5").unwrap(), multicaller, &balance_state).unwrap();
+        assert_eq!(amount, parse_ether("0.5").unwrap());
+
+        assert!(capital_manager.rebalance_check(&weth, parse_ether("1").unwrap(), Address::repeat_byte(0x33), &balance_state).is_err());
+    }
+}
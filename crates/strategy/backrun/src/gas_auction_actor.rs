@@ -0,0 +1,290 @@
+use std::collections::BTreeSet;
+
+use alloy_consensus::Transaction;
+use alloy_primitives::{Address, BlockNumber};
+use eyre::eyre;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, error, info};
+
+use loom_core_actors::{
+    subscribe, Accessor, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, Producer, SharedState, WorkerResult,
+};
+use loom_core_actors_macros::{Accessor, Consumer, Producer};
+use loom_core_blockchain::Blockchain;
+use loom_types_entities::LatestBlock;
+use loom_types_events::{HealthEvent, MarketEvents, MessageHealthEvent, MessageTxCompose, TxComposeMessageType};
+
+const MIN_TIP_MULTIPLIER: f64 = 1.0;
+const MAX_TIP_MULTIPLIER: f64 = 3.0;
+// Raise the tip quickly when outbid and lower it slowly when landing
+const MISSED_BUNDLE_FACTOR: f64 = 1.1;
+const LANDED_BUNDLE_FACTOR: f64 = 0.98;
+// Raise the tip by up to this factor when a bundle competes with pending transactions, scaled by its miss probability
+const CONFLICT_BUNDLE_FACTOR: f64 = 1.1;
+
+/// Running tip multiplier adjusted by the outcome of our bundles
+#[derive(Clone, Debug)]
+pub struct GasAuctionState {
+    effective_tip_multiplier: f64,
+    pending_bundle_blocks: BTreeSet<BlockNumber>,
+}
+
+impl Default for GasAuctionState {
+    fn default() -> Self {
+        Self { effective_tip_multiplier: MIN_TIP_MULTIPLIER, pending_bundle_blocks: BTreeSet::new() }
+    }
+}
+
+impl GasAuctionState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn effective_tip_multiplier(&self) -> f64 {
+        self.effective_tip_multiplier
+    }
+
+    pub fn apply(&self, priority_gas_fee: u64) -> u64 {
+        (priority_gas_fee as f64 * self.effective_tip_multiplier) as u64
+    }
+
+    pub fn add_bundle(&mut self, block_number: BlockNumber) {
+        self.pending_bundle_blocks.insert(block_number);
+    }
+
+    /// Settle the bundles sent for `block_number`, returns None if no bundle targeted the block
+    pub fn settle_block(&mut self, block_number: BlockNumber, landed: bool) -> Option<f64> {
+        let had_bundle = self.pending_bundle_blocks.contains(&block_number);
+        self.pending_bundle_blocks = self.pending_bundle_blocks.split_off(&(block_number + 1));
+        if !had_bundle {
+            return None;
+        }
+
+        let factor = if landed { LANDED_BUNDLE_FACTOR } else { MISSED_BUNDLE_FACTOR };
+        self.effective_tip_multiplier = (self.effective_tip_multiplier * factor).clamp(MIN_TIP_MULTIPLIER, MAX_TIP_MULTIPLIER);
+        Some(self.effective_tip_multiplier)
+    }
+
+    /// Raise the tip after a bundle conflicted with pending transactions, returns the new multiplier
+    pub fn record_conflicts(&mut self, inclusion_probability: f64) -> f64 {
+        let factor = 1.0 + (CONFLICT_BUNDLE_FACTOR - 1.0) * (1.0 - inclusion_probability.clamp(0.0, 1.0));
+        self.effective_tip_multiplier = (self.effective_tip_multiplier * factor).clamp(MIN_TIP_MULTIPLIER, MAX_TIP_MULTIPLIER);
+        self.effective_tip_multiplier
+    }
+}
+
+pub async fn gas_auction_worker(
+    multicaller_address: Address,
+    latest_block: SharedState<LatestBlock>,
+    gas_auction_state: SharedState<GasAuctionState>,
+    market_events_rx: Broadcaster<MarketEvents>,
+    tx_compose_channel_rx: Broadcaster<MessageTxCompose>,
+    health_monitor_channel_rx: Broadcaster<MessageHealthEvent>,
+    cancellation_token: CancellationToken,
+) -> WorkerResult {
+    subscribe!(market_events_rx);
+    subscribe!(tx_compose_channel_rx);
+    subscribe!(health_monitor_channel_rx);
+
+    loop {
+        tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Gas auction cancelled".to_string());
+            }
+            msg = market_events_rx.recv() => {
+                let market_event_msg: Result<MarketEvents, RecvError> = msg;
+                match market_event_msg {
+                    Ok(MarketEvents::BlockTxUpdate { block_number, .. }) => {
+                        let landed = latest_block
+                            .read()
+                            .await
+                            .txs()
+                            .is_some_and(|txs| txs.iter().any(|tx| tx.to() == Some(multicaller_address)));
+
+                        if let Some(multiplier) = gas_auction_state.write().await.settle_block(block_number, landed) {
+                            info!(block_number, landed, multiplier, "Gas auction tip multiplier updated");
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Closed) => {
+                        error!("Market events channel closed");
+                        break Err(eyre!("MARKET_EVENTS_RX_CLOSED"));
+                    }
+                    Err(RecvError::Lagged(lag)) => {
+                        error!("Market events channel lagged by {} messages", lag);
+                    }
+                }
+            }
+            msg = tx_compose_channel_rx.recv() => {
+                let tx_compose_msg: Result<MessageTxCompose, RecvError> = msg;
+                match tx_compose_msg {
+                    Ok(tx_compose) => {
+                        if let TxComposeMessageType::Broadcast(tx_compose_data) = tx_compose.inner {
+                            debug!(block_number = tx_compose_data.next_block_number, "Gas auction bundle sent");
+                            gas_auction_state.write().await.add_bundle(tx_compose_data.next_block_number);
+                        }
+                    }
+                    Err(RecvError::Closed) => {
+                        error!("Tx compose channel closed");
+                        break Err(eyre!("TX_COMPOSE_RX_CLOSED"));
+                    }
+                    Err(RecvError::Lagged(lag)) => {
+                        error!("Tx compose channel lagged by {} messages", lag);
+                    }
+                }
+            }
+            msg = health_monitor_channel_rx.recv() => {
+                let health_event_msg: Result<MessageHealthEvent, RecvError> = msg;
+                match health_event_msg {
+                    Ok(health_event) => {
+                        if let HealthEvent::BundleConflicts { block_number, conflicts, inclusion_probability } = health_event.inner {
+                            let multiplier = gas_auction_state.write().await.record_conflicts(inclusion_probability);
+                            info!(block_number, conflicts, inclusion_probability, multiplier, "Gas auction tip multiplier raised on conflicts");
+                        }
+                    }
+                    Err(RecvError::Closed) => {
+                        error!("Health monitor channel closed");
+                        break Err(eyre!("HEALTH_MONITOR_RX_CLOSED"));
+                    }
+                    Err(RecvError::Lagged(lag)) => {
+                        error!("Health monitor channel lagged by {} messages", lag);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Accessor, Consumer, Producer)]
+pub struct GasAuctionActor {
+    multicaller_address: Address,
+    #[accessor]
+    latest_block: Option<SharedState<LatestBlock>>,
+    #[accessor]
+    gas_auction_state: Option<SharedState<GasAuctionState>>,
+    #[consumer]
+    market_events_rx: Option<Broadcaster<MarketEvents>>,
+    #[consumer]
+    tx_compose_channel_rx: Option<Broadcaster<MessageTxCompose>>,
+    #[consumer]
+    health_monitor_channel_rx: Option<Broadcaster<MessageHealthEvent>>,
+    cancellation_token: CancellationToken,
+}
+
+impl GasAuctionActor {
+    pub fn new(multicaller_address: Address) -> Self {
+        Self {
+            multicaller_address,
+            latest_block: None,
+            gas_auction_state: None,
+            market_events_rx: None,
+            tx_compose_channel_rx: None,
+            health_monitor_channel_rx: None,
+            cancellation_token: CancellationToken::new(),
+        }
+    }
+
+    pub fn on_bc(self, bc: &Blockchain, gas_auction_state: SharedState<GasAuctionState>) -> Self {
+        Self {
+            latest_block: Some(bc.latest_block()),
+            gas_auction_state: Some(gas_auction_state),
+            market_events_rx: Some(bc.market_events_channel()),
+            tx_compose_channel_rx: Some(bc.tx_compose_channel()),
+            health_monitor_channel_rx: Some(bc.health_monitor_channel()),
+            ..self
+        }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+}
+
+impl Actor for GasAuctionActor {
+    fn start(&self) -> ActorResult {
+        let task = tokio::task::spawn(gas_auction_worker(
+            self.multicaller_address,
+            self.latest_block.clone().unwrap(),
+            self.gas_auction_state.clone().unwrap(),
+            self.market_events_rx.clone().unwrap(),
+            self.tx_compose_channel_rx.clone().unwrap(),
+            self.health_monitor_channel_rx.clone().unwrap(),
+            self.cancellation_token.clone(),
+        ));
+        Ok(vec![task])
+    }
+
+    fn name(&self) -> &'static str {
+        "GasAuctionActor"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_apply() {
+        let mut state = GasAuctionState::new();
+        assert_eq!(state.apply(1_000_000_000), 1_000_000_000);
+
+        state.add_bundle(10);
+        state.settle_block(10, false);
+        assert_eq!(state.apply(1_000_000_000), 1_100_000_000);
+        assert_eq!(state.apply(0), 0);
+    }
+
+    #[test]
+    fn test_settle_block() {
+        let mut state = GasAuctionState::new();
+        assert_eq!(state.settle_block(10, false), None);
+
+        state.add_bundle(10);
+        state.add_bundle(12);
+        let multiplier = state.settle_block(10, false).unwrap();
+        assert!((multiplier - 1.1).abs() < 1e-9);
+
+        // Bundle for block 12 is still pending
+        assert_eq!(state.settle_block(11, false), None);
+        let multiplier = state.settle_block(12, true).unwrap();
+        assert!((multiplier - 1.1 * 0.98).abs() < 1e-9);
+        assert_eq!(state.settle_block(12, true), None);
+
+        // Bundles for past blocks are dropped when a later block is settled
+        state.add_bundle(13);
+        assert_eq!(state.settle_block(14, false), None);
+        assert_eq!(state.settle_block(13, false), None);
+        assert!((state.effective_tip_multiplier() - 1.1 * 0.98).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_record_conflicts() {
+        let mut state = GasAuctionState::new();
+        assert_eq!(state.record_conflicts(1.0), MIN_TIP_MULTIPLIER);
+
+        // One conflicting tx halves the inclusion probability and raises the tip by half of the full factor
+        assert!((state.record_conflicts(0.5) - 1.05).abs() < 1e-9);
+        assert!((state.record_conflicts(0.0) - 1.05 * 1.1).abs() < 1e-9);
+
+        for _ in 0..100 {
+            state.record_conflicts(0.0);
+        }
+        assert_eq!(state.effective_tip_multiplier(), MAX_TIP_MULTIPLIER);
+    }
+
+    #[test]
+    fn test_settle_block_clamp() {
+        let mut state = GasAuctionState::new();
+        for block_number in 0..100 {
+            state.add_bundle(block_number);
+            state.settle_block(block_number, false);
+        }
+        assert_eq!(state.effective_tip_multiplier(), MAX_TIP_MULTIPLIER);
+
+        for block_number in 100..200 {
+            state.add_bundle(block_number);
+            state.settle_block(block_number, true);
+        }
+        assert_eq!(state.effective_tip_multiplier(), MIN_TIP_MULTIPLIER);
+    }
+}
@@ -0,0 +1,286 @@
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+use alloy_primitives::{keccak256, BlockNumber, TxHash};
+use eyre::eyre;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, error, info};
+
+use loom_core_actors::{subscribe, Accessor, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, SharedState, WorkerResult};
+use loom_core_actors_macros::{Accessor, Consumer};
+use loom_core_blockchain::Blockchain;
+use loom_types_entities::LatestBlock;
+use loom_types_events::{MarketEvents, MessageTxCompose, RlpState, TxComposeMessageType};
+
+// Number of latest settled bundles the landing rates are computed over
+const LANDING_WINDOW_BUNDLES: usize = 1000;
+// Upper bounds of the priority_fee / base_fee ratio buckets, ratios above the last bound fall into an extra bucket
+const LANDING_RATIO_BUCKETS: [f64; 8] = [0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.0, 5.0];
+const LANDING_BUCKETS_LEN: usize = LANDING_RATIO_BUCKETS.len() + 1;
+// Settled bundles a bucket needs before its landing rate is reported to the searcher
+const LANDING_MIN_BUCKET_BUNDLES: u32 = 50;
+
+/// Empirical landing rate of our bundles by their priority fee to base fee ratio
+#[derive(Clone, Debug, Default)]
+pub struct LandingProbabilityEstimator {
+    // (bucket, landed) of the settled bundles, oldest first
+    window: VecDeque<(usize, bool)>,
+    landed: [u32; LANDING_BUCKETS_LEN],
+    total: [u32; LANDING_BUCKETS_LEN],
+    // block -> bucket and backrun tx hashes of the bundles sent for the block
+    pending_bundles: BTreeMap<BlockNumber, Vec<(usize, Vec<TxHash>)>>,
+}
+
+impl LandingProbabilityEstimator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn bucket(priority_fee: u64, base_fee: u64) -> usize {
+        let ratio = if base_fee == 0 { f64::INFINITY } else { priority_fee as f64 / base_fee as f64 };
+        LANDING_RATIO_BUCKETS.iter().position(|bound| ratio < *bound).unwrap_or(LANDING_RATIO_BUCKETS.len())
+    }
+
+    /// Landing probability of a bundle paying `priority_fee` over `base_fee`. The rate is smoothed with one landed and one
+    /// missed bundle, so buckets without history estimate 0.5
+    pub fn estimate(&self, priority_fee: u64, base_fee: u64) -> f32 {
+        let bucket = Self::bucket(priority_fee, base_fee);
+        (self.landed[bucket] + 1) as f32 / (self.total[bucket] + 2) as f32
+    }
+
+    /// Landing probability of a bundle paying `priority_fee` over `base_fee`, `None` until enough bundles of its ratio bucket
+    /// settled for the rate to be meaningful
+    pub fn estimate_sampled(&self, priority_fee: u64, base_fee: u64) -> Option<f32> {
+        let bucket = Self::bucket(priority_fee, base_fee);
+        (self.total[bucket] >= LANDING_MIN_BUCKET_BUNDLES).then(|| self.estimate(priority_fee, base_fee))
+    }
+
+    /// Record the outcome of a bundle, the oldest bundle leaves the window when it is full
+    pub fn record(&mut self, priority_fee: u64, base_fee: u64, landed: bool) {
+        self.record_bucket(Self::bucket(priority_fee, base_fee), landed);
+    }
+
+    fn record_bucket(&mut self, bucket: usize, landed: bool) {
+        self.window.push_back((bucket, landed));
+        self.total[bucket] += 1;
+        if landed {
+            self.landed[bucket] += 1;
+        }
+        if self.window.len() > LANDING_WINDOW_BUNDLES {
+            if let Some((bucket, landed)) = self.window.pop_front() {
+                self.total[bucket] -= 1;
+                if landed {
+                    self.landed[bucket] -= 1;
+                }
+            }
+        }
+    }
+
+    pub fn add_bundle(&mut self, block_number: BlockNumber, priority_fee: u64, base_fee: u64, tx_hashes: Vec<TxHash>) {
+        self.pending_bundles.entry(block_number).or_default().push((Self::bucket(priority_fee, base_fee), tx_hashes));
+    }
+
+    /// Record the bundles sent for `block_number`, a bundle landed when one of its transactions is in `block_tx_hashes`.
+    /// Returns the number of settled and landed bundles. Bundles of older blocks that were not settled are dropped
+    pub fn settle_block(&mut self, block_number: BlockNumber, block_tx_hashes: &HashSet<TxHash>) -> (usize, usize) {
+        let newer_bundles = self.pending_bundles.split_off(&(block_number + 1));
+        let settled_bundles = std::mem::replace(&mut self.pending_bundles, newer_bundles).remove(&block_number).unwrap_or_default();
+        let mut landed_bundles = 0;
+        for (bucket, tx_hashes) in settled_bundles.iter() {
+            let landed = tx_hashes.iter().any(|tx_hash| block_tx_hashes.contains(tx_hash));
+            if landed {
+                landed_bundles += 1;
+            }
+            self.record_bucket(*bucket, landed);
+        }
+        (settled_bundles.len(), landed_bundles)
+    }
+}
+
+pub async fn landing_probability_worker(
+    latest_block: SharedState<LatestBlock>,
+    landing_probability: SharedState<LandingProbabilityEstimator>,
+    market_events_rx: Broadcaster<MarketEvents>,
+    tx_compose_channel_rx: Broadcaster<MessageTxCompose>,
+    cancellation_token: CancellationToken,
+) -> WorkerResult {
+    subscribe!(market_events_rx);
+    subscribe!(tx_compose_channel_rx);
+
+    loop {
+        tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Landing probability cancelled".to_string());
+            }
+            msg = market_events_rx.recv() => {
+                let market_event_msg: Result<MarketEvents, RecvError> = msg;
+                match market_event_msg {
+                    Ok(MarketEvents::BlockTxUpdate { block_number, .. }) => {
+                        // Bundles are matched by our transaction hashes, EIP-7702 bundles call the EOA and not the multicaller
+                        let block_tx_hashes: HashSet<TxHash> = latest_block
+                            .read()
+                            .await
+                            .txs()
+                            .map(|txs| txs.iter().map(|tx| *tx.inner.tx_hash()).collect())
+                            .unwrap_or_default();
+
+                        let (settled, landed) = landing_probability.write().await.settle_block(block_number, &block_tx_hashes);
+                        if settled > 0 {
+                            info!(block_number, landed, settled, "Bundle landing outcome recorded");
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Closed) => {
+                        error!("Market events channel closed");
+                        break Err(eyre!("MARKET_EVENTS_RX_CLOSED"));
+                    }
+                    Err(RecvError::Lagged(lag)) => {
+                        error!("Market events channel lagged by {} messages", lag);
+                    }
+                }
+            }
+            msg = tx_compose_channel_rx.recv() => {
+                let tx_compose_msg: Result<MessageTxCompose, RecvError> = msg;
+                match tx_compose_msg {
+                    Ok(tx_compose) => {
+                        if let TxComposeMessageType::Broadcast(tx_compose_data) = tx_compose.inner {
+                            debug!(block_number = tx_compose_data.next_block_number, "Landing probability bundle sent");
+                            let tx_hashes = tx_compose_data
+                                .rlp_bundle
+                                .iter()
+                                .flatten()
+                                .filter_map(|rlp_state| match rlp_state {
+                                    RlpState::Backrun(rlp) => Some(keccak256(rlp)),
+                                    _ => None,
+                                })
+                                .collect();
+                            landing_probability.write().await.add_bundle(
+                                tx_compose_data.next_block_number,
+                                tx_compose_data.priority_gas_fee,
+                                tx_compose_data.next_block_base_fee,
+                                tx_hashes,
+                            );
+                        }
+                    }
+                    Err(RecvError::Closed) => {
+                        error!("Tx compose channel closed");
+                        break Err(eyre!("TX_COMPOSE_RX_CLOSED"));
+                    }
+                    Err(RecvError::Lagged(lag)) => {
+                        error!("Tx compose channel lagged by {} messages", lag);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Tracks which of our bundles land to estimate the landing probability of new ones
+#[derive(Accessor, Consumer, Default)]
+pub struct LandingProbabilityActor {
+    #[accessor]
+    latest_block: Option<SharedState<LatestBlock>>,
+    #[accessor]
+    landing_probability: Option<SharedState<LandingProbabilityEstimator>>,
+    #[consumer]
+    market_events_rx: Option<Broadcaster<MarketEvents>>,
+    #[consumer]
+    tx_compose_channel_rx: Option<Broadcaster<MessageTxCompose>>,
+    cancellation_token: CancellationToken,
+}
+
+impl LandingProbabilityActor {
+    pub fn new() -> Self {
+        Self {
+            latest_block: None,
+            landing_probability: None,
+            market_events_rx: None,
+            tx_compose_channel_rx: None,
+            cancellation_token: CancellationToken::new(),
+        }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
+    pub fn on_bc(self, bc: &Blockchain, landing_probability: SharedState<LandingProbabilityEstimator>) -> Self {
+        Self {
+            latest_block: Some(bc.latest_block()),
+            landing_probability: Some(landing_probability),
+            market_events_rx: Some(bc.market_events_channel()),
+            tx_compose_channel_rx: Some(bc.tx_compose_channel()),
+            ..self
+        }
+    }
+}
+
+impl Actor for LandingProbabilityActor {
+    fn start(&self) -> ActorResult {
+        let task = tokio::task::spawn(landing_probability_worker(
+            self.latest_block.clone().unwrap(),
+            self.landing_probability.clone().unwrap(),
+            self.market_events_rx.clone().unwrap(),
+            self.tx_compose_channel_rx.clone().unwrap(),
+            self.cancellation_token.clone(),
+        ));
+        Ok(vec![task])
+    }
+
+    fn name(&self) -> &'static str {
+        "LandingProbabilityActor"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_landing_probability_estimate() {
+        let mut estimator = LandingProbabilityEstimator::new();
+        assert_eq!(estimator.estimate(100, 1000), 0.5);
+
+        for i in 0..8 {
+            let (landed_hash, missed_hash) = (TxHash::repeat_byte(i as u8 + 1), TxHash::repeat_byte(0xff));
+            estimator.add_bundle(i, 100, 1000, vec![landed_hash]);
+            estimator.add_bundle(i, 3000, 1000, vec![landed_hash]);
+            estimator.add_bundle(i, 10, 1000, vec![missed_hash]);
+            let block_tx_hashes = if i % 4 == 0 { HashSet::from([landed_hash]) } else { HashSet::new() };
+            assert_eq!(estimator.settle_block(i, &block_tx_hashes), (3, if i % 4 == 0 { 2 } else { 0 }));
+        }
+        // 2 of 8 landed in both buckets, bundles without a transaction in the block never land
+        assert_eq!(estimator.estimate(120, 1000), 0.3);
+        assert_eq!(estimator.estimate(2500, 1000), 0.3);
+        assert_eq!(estimator.estimate(10, 1000), 0.1);
+
+        // Bundles of blocks settled late are dropped
+        estimator.add_bundle(10, 100, 1000, vec![TxHash::ZERO]);
+        assert_eq!(estimator.settle_block(11, &HashSet::from([TxHash::ZERO])), (0, 0));
+    }
+
+    #[test]
+    fn test_landing_probability_estimate_sampled() {
+        let mut estimator = LandingProbabilityEstimator::new();
+        for _ in 0..LANDING_MIN_BUCKET_BUNDLES - 1 {
+            estimator.record(100, 1000, false);
+        }
+        assert_eq!(estimator.estimate_sampled(100, 1000), None);
+
+        estimator.record(100, 1000, false);
+        assert_eq!(estimator.estimate_sampled(100, 1000), Some(1.0 / (LANDING_MIN_BUCKET_BUNDLES + 2) as f32));
+        assert_eq!(estimator.estimate_sampled(3000, 1000), None);
+    }
+
+    #[test]
+    fn test_landing_probability_window() {
+        let mut estimator = LandingProbabilityEstimator::new();
+        for _ in 0..LANDING_WINDOW_BUNDLES {
+            estimator.record(100, 1000, false);
+        }
+        for _ in 0..LANDING_WINDOW_BUNDLES {
+            estimator.record(100, 1000, true);
+        }
+        assert_eq!(estimator.window.len(), LANDING_WINDOW_BUNDLES);
+        assert_eq!(estimator.estimate(100, 1000), 1001.0 / 1002.0);
+    }
+}
@@ -2,6 +2,9 @@ pub use arb_actor::StateChangeArbActor;
 pub use backrun_config::{BackrunConfig, BackrunConfigSection};
 pub use block_state_change_processor::BlockStateChangeProcessorActor;
 pub use capital_manager::CapitalManager;
+pub use gas_auction_actor::{GasAuctionActor, GasAuctionState};
+pub use landing_probability_actor::{LandingProbabilityActor, LandingProbabilityEstimator};
+pub use path_scoring_actor::PathScoringActor;
 pub use pending_tx_state_change_processor::PendingTxStateChangeProcessorActor;
 pub use state_change_arb_searcher::StateChangeArbSearcherActor;
 pub use swap_calculator::SwapCalculator;
@@ -9,6 +12,9 @@ pub use profit_calculator::{ProfitCalculator, MultiCurrencyProfit};
 
 mod block_state_change_processor;
 mod capital_manager;
+mod gas_auction_actor;
+mod landing_probability_actor;
+mod path_scoring_actor;
 mod pending_tx_state_change_processor;
 mod state_change_arb_searcher;
 mod profit_calculator;
@@ -18,5 +24,10 @@ mod affected_pools_logs;
 mod affected_pools_state;
 mod arb_actor;
 mod backrun_config;
+#[cfg(feature = "perf-metrics")]
+mod perf_metrics;
+mod pool_swap_volume;
 mod swap_calculator;
 mod rate_limited_client;
+mod simulation_cache;
+mod simulation_timeout;
@@ -0,0 +1,233 @@
+use alloy_primitives::BlockNumber;
+use eyre::eyre;
+use revm::DatabaseRef;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, error};
+
+use loom_core_actors::{
+    subscribe, Accessor, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, Producer, SharedState, WorkerResult,
+};
+use loom_core_actors_macros::{Accessor, Consumer, Producer};
+use loom_core_blockchain::{Blockchain, Strategy};
+use loom_types_entities::{Market, Swap, SwapPath};
+use loom_types_events::{MarketEvents, MessageSwapCompose, SwapComposeMessage};
+
+// Pool volume in ETH at which the volume component reaches one half
+const VOLUME_SCORE_HALF_ETH: f64 = 100.0;
+// Blocks since the last profitable trade at which the recency component reaches one half, about one day
+const RECENCY_SCORE_HALF_BLOCKS: f64 = 7200.0;
+// Trade size used to estimate the price impact against pool TVL
+const PRICE_IMPACT_REFERENCE_TRADE_USD: f64 = 10_000.0;
+// Blocks without a profitable swap after which the path score halves, about one hour
+const PATH_SCORE_HALF_LIFE_BLOCKS: u64 = 300;
+
+const VOLUME_SCORE_WEIGHT: f64 = 0.4;
+const RECENCY_SCORE_WEIGHT: f64 = 0.3;
+const PRICE_IMPACT_SCORE_WEIGHT: f64 = 0.3;
+
+/// Paths scoring above this are searched beyond the per pool path limit of the searcher. Volume and price impact alone reach
+/// at most `VOLUME_SCORE_WEIGHT + PRICE_IMPACT_SCORE_WEIGHT`, so only recently profitable paths pass.
+pub(crate) const PRIORITY_PATH_SCORE: f64 = VOLUME_SCORE_WEIGHT + PRICE_IMPACT_SCORE_WEIGHT;
+
+/// Score in [0, 1] of a swap path by the lowest swap volume of its pools, blocks since the last profitable trade on the path
+/// and the estimated price impact of a reference trade. Paths never traded profitably get no recency component.
+fn compute_path_score(market: &Market, swap_path: &SwapPath, blocks_since_profit: Option<u64>) -> f64 {
+    if swap_path.pools.is_empty() {
+        return 0.0;
+    }
+
+    let min_volume_eth = swap_path
+        .pools
+        .iter()
+        .map(|pool| {
+            market.get_pool_stats(&pool.get_pool_id()).map_or(0.0, |stats| stats.volume_eth.saturating_to::<u128>() as f64 / 1e18)
+        })
+        .fold(f64::INFINITY, f64::min);
+    let volume_score = min_volume_eth / (min_volume_eth + VOLUME_SCORE_HALF_ETH);
+
+    let recency_score = blocks_since_profit.map_or(0.0, |blocks| RECENCY_SCORE_HALF_BLOCKS / (RECENCY_SCORE_HALF_BLOCKS + blocks as f64));
+
+    // Pools without metrics have unknown TVL and are not counted
+    let price_impact: f64 = swap_path
+        .pools
+        .iter()
+        .filter_map(|pool| market.get_pool_metrics(&pool.get_pool_id()))
+        .map(|metrics| PRICE_IMPACT_REFERENCE_TRADE_USD / (metrics.tvl_usd.max(0.0) + PRICE_IMPACT_REFERENCE_TRADE_USD))
+        .sum();
+    let price_impact_score = (1.0 - price_impact).max(0.0);
+
+    let score = VOLUME_SCORE_WEIGHT * volume_score + RECENCY_SCORE_WEIGHT * recency_score + PRICE_IMPACT_SCORE_WEIGHT * price_impact_score;
+    score.clamp(0.0, 1.0)
+}
+
+/// Recompute and store scores of the swap paths with indexes `path_idx_vec`
+fn update_path_scores(market: &mut Market, path_idx_vec: &[usize], block_number: BlockNumber) -> usize {
+    let mut updated = 0;
+    for path_idx in path_idx_vec {
+        let Some(swap_path) = market.swap_paths().get_path_by_idx(*path_idx) else {
+            continue;
+        };
+        let blocks_since_profit =
+            market.get_path_last_profit_block(swap_path).map(|profit_block| block_number.saturating_sub(profit_block));
+        let score = compute_path_score(market, swap_path, blocks_since_profit);
+        if market.update_path_score(*path_idx, score) {
+            updated += 1;
+        }
+    }
+    updated
+}
+
+pub async fn path_scoring_worker<DB: Send + Sync + Clone + 'static>(
+    market: SharedState<Market>,
+    market_events_rx: Broadcaster<MarketEvents>,
+    swap_compose_channel_rx: Broadcaster<MessageSwapCompose<DB>>,
+    cancellation_token: CancellationToken,
+) -> WorkerResult {
+    subscribe!(market_events_rx);
+    subscribe!(swap_compose_channel_rx);
+
+    let mut block_number: BlockNumber = 0;
+
+    loop {
+        tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Path scoring cancelled".to_string());
+            }
+            msg = market_events_rx.recv() => {
+                let market_event_msg: Result<MarketEvents, RecvError> = msg;
+                match market_event_msg {
+                    Ok(MarketEvents::BlockHeaderUpdate { block_number: header_block_number, .. }) => {
+                        block_number = header_block_number;
+                        let mut market_guard = market.write().await;
+                        market_guard.set_block_number(block_number);
+                        let decayed = market_guard.decay_path_scores(block_number, PATH_SCORE_HALF_LIFE_BLOCKS);
+                        debug!(block_number, decayed, "Swap path scores decayed");
+                    }
+                    Ok(MarketEvents::PoolStatsUpdate { pool_id, .. }) => {
+                        let mut market_guard = market.write().await;
+                        let path_idx_vec = market_guard.pool_swap_paths_idx_vec(&pool_id).unwrap_or_default();
+                        let updated = update_path_scores(&mut market_guard, &path_idx_vec, block_number);
+                        debug!(%pool_id, updated, "Swap path scores updated");
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Closed) => {
+                        error!("Market events channel closed");
+                        break Err(eyre!("MARKET_EVENTS_RX_CLOSED"));
+                    }
+                    Err(RecvError::Lagged(lag)) => {
+                        error!("Market events channel lagged by {} messages", lag);
+                    }
+                }
+            }
+            msg = swap_compose_channel_rx.recv() => {
+                let swap_compose_msg: Result<MessageSwapCompose<DB>, RecvError> = msg;
+                match swap_compose_msg {
+                    Ok(swap_compose) => {
+                        let SwapComposeMessage::Ready(swap_compose_data) = swap_compose.inner else {
+                            continue;
+                        };
+                        let Swap::BackrunSwapLine(swap_line) = &swap_compose_data.swap else {
+                            continue;
+                        };
+                        let path_hash = swap_line.path.get_hash();
+                        let mut market_guard = market.write().await;
+                        market_guard.record_path_profit(&swap_line.path, block_number);
+                        let path_idx = market_guard.swap_paths().path_hash_map.get(&path_hash).cloned();
+                        if let Some(path_idx) = path_idx {
+                            update_path_scores(&mut market_guard, &[path_idx], block_number);
+                        }
+                    }
+                    Err(RecvError::Closed) => {
+                        error!("Swap compose channel closed");
+                        break Err(eyre!("SWAP_COMPOSE_RX_CLOSED"));
+                    }
+                    Err(RecvError::Lagged(lag)) => {
+                        error!("Swap compose channel lagged by {} messages", lag);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Recomputes `SwapPath::score` from pool volume, blocks since the last profitable trade and price impact.
+/// Scores of the pool paths are updated on `MarketEvents::PoolStatsUpdate`, the path score is updated when a profitable swap
+/// on the path is ready. Profitable swaps are recorded in the market profitability history, which also feeds the hit rate of
+/// `Market::get_arbitrage_score`. Scores decay on every block header.
+#[derive(Accessor, Consumer, Producer)]
+pub struct PathScoringActor<DB: Clone + Send + Sync + 'static> {
+    #[accessor]
+    market: Option<SharedState<Market>>,
+    #[consumer]
+    market_events_rx: Option<Broadcaster<MarketEvents>>,
+    #[consumer]
+    swap_compose_channel_rx: Option<Broadcaster<MessageSwapCompose<DB>>>,
+    cancellation_token: CancellationToken,
+}
+
+impl<DB: DatabaseRef + Send + Sync + Clone + 'static> PathScoringActor<DB> {
+    pub fn new() -> Self {
+        Self { market: None, market_events_rx: None, swap_compose_channel_rx: None, cancellation_token: CancellationToken::new() }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
+    pub fn on_bc(self, bc: &Blockchain, strategy: &Strategy<DB>) -> Self {
+        Self {
+            market: Some(bc.market()),
+            market_events_rx: Some(bc.market_events_channel()),
+            swap_compose_channel_rx: Some(strategy.swap_compose_channel()),
+            ..self
+        }
+    }
+}
+
+impl<DB: DatabaseRef + Send + Sync + Clone + 'static> Default for PathScoringActor<DB> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<DB: DatabaseRef + Send + Sync + Clone + 'static> Actor for PathScoringActor<DB> {
+    fn start(&self) -> ActorResult {
+        let task = tokio::task::spawn(path_scoring_worker(
+            self.market.clone().unwrap(),
+            self.market_events_rx.clone().unwrap(),
+            self.swap_compose_channel_rx.clone().unwrap(),
+            self.cancellation_token.clone(),
+        ));
+        Ok(vec![task])
+    }
+
+    fn name(&self) -> &'static str {
+        "PathScoringActor"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Address, U256};
+    use loom_types_entities::{MockPool, PoolId, Token};
+
+    #[test]
+    fn test_priority_path_score() {
+        let mut market = Market::default();
+        let (token0, token1) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let pool = MockPool::new(token0, token1, Address::repeat_byte(10));
+        let swap_path = SwapPath::new(vec![Token::new(token0), Token::new(token1)], vec![pool]);
+
+        assert!((compute_path_score(&market, &swap_path, None) - 0.3).abs() < 1e-9);
+
+        market.set_pool_tvl(PoolId::Address(Address::repeat_byte(10)), 100_000_000.0);
+        market.record_pool_swaps(PoolId::Address(Address::repeat_byte(10)), 1, U256::from(10).pow(U256::from(22)), 1);
+        let never_profitable_score = compute_path_score(&market, &swap_path, None);
+        assert!(never_profitable_score > 0.69 && never_profitable_score < PRIORITY_PATH_SCORE);
+
+        assert!(compute_path_score(&market, &swap_path, Some(0)) > PRIORITY_PATH_SCORE);
+        assert!(compute_path_score(&market, &swap_path, Some(100)) > PRIORITY_PATH_SCORE);
+        assert!(compute_path_score(&market, &swap_path, Some(1_000_000)) < PRIORITY_PATH_SCORE);
+    }
+}
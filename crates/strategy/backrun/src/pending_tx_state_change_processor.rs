@@ -11,13 +11,15 @@ use lazy_static::lazy_static;
 use revm::primitives::bitvec::macros::internal::funty::Fundamental;
 use revm::{Database, DatabaseCommit, DatabaseRef};
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, error, warn};
 
-use loom_core_actors::{subscribe, Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
+use loom_core_actors::{
+    subscribe, Accessor, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, Producer, SharedState, WorkerResult,
+};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
 use loom_core_blockchain::{Blockchain, BlockchainState, Strategy};
 use loom_node_debug_provider::DebugProviderExt;
@@ -29,6 +31,9 @@ use loom_types_events::{MarketEvents, MempoolEvents, StateUpdateEvent};
 use super::affected_pools_code::{get_affected_pools_from_code, is_pool_code};
 use super::affected_pools_state::get_affected_pools_from_state_update;
 
+// Pending txs re-traced against the state of every new block, the highest gas price first
+const PENDING_TXS_RETRACE_COUNT: usize = 50;
+
 lazy_static! {
     static ref COINBASE: Address = "0x1f9090aaE28b8a3dCeaDf281B0F12828e676c326".parse().unwrap();
 }
@@ -43,6 +48,7 @@ pub async fn pending_tx_state_change_task<P, N, DB>(
     latest_block: SharedState<LatestBlock>,
     market_state: SharedState<MarketState<DB>>,
     affecting_tx: Arc<RwLock<HashMap<TxHash, bool>>>,
+    sandwich_victims: Arc<RwLock<HashSet<TxHash>>>,
     cur_block_number: BlockNumber,
     cur_block_time: u64,
     cur_next_base_fee: u64,
@@ -160,6 +166,15 @@ where
     debug!(%tx_hash, %source, pools = affected_pools.len(), accounts = accounts_len, storage = storage_len, "Mempool affected pools");
 
     affecting_tx.write().await.insert(tx_hash, !affected_pools.is_empty());
+    if !affected_pools.is_empty() {
+        mempool.write().await.add_tx_pools(tx_hash, affected_pools.keys().map(|pool| pool.get_pool_id()));
+    }
+
+    // The sandwich is usually detected while the victim is traced
+    if sandwich_victims.read().await.contains(&tx_hash) {
+        debug!(%tx_hash, "Skipping sandwich victim");
+        return Ok(());
+    }
 
     // Improved handling for Latest header possibly being empty
     let latest_header_opt = latest_block.read().await.block_header.clone();
@@ -206,6 +221,9 @@ where
                 };
 
                 debug!("Mempool code pools {} {} update len : {}", tx_hash, source, affected_pools.len());
+                if !affected_pools.is_empty() {
+                    mempool.write().await.add_tx_pools(tx_hash, affected_pools.keys().map(|pool| pool.get_pool_id()));
+                }
 
                 if let Some(latest_header) = latest_block.read().await.block_header.clone() {
                     let block_number = latest_header.number.as_u64() + 1;
@@ -253,6 +271,7 @@ pub async fn pending_tx_state_change_worker<P, N, DB>(
     mempool_events_rx: Broadcaster<MempoolEvents>,
     market_events_rx: Broadcaster<MarketEvents>,
     state_updates_broadcaster: Broadcaster<StateUpdateEvent<DB>>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult
 where
     N: Network,
@@ -267,9 +286,14 @@ where
     let mut cur_block_number: Option<BlockNumber> = None;
     let mut cur_block_time: Option<u64> = None;
     let mut cur_state_override: StateOverride = StateOverride::default();
+    // Sandwich victims are not backrun
+    let sandwich_victims: Arc<RwLock<HashSet<TxHash>>> = Arc::new(RwLock::new(HashSet::new()));
 
     loop {
         tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Pending tx state change processor cancelled".to_string());
+            }
             msg = market_events_rx.recv() => {
                 if let Ok(msg) = msg {
                     let market_event_msg : MarketEvents = msg;
@@ -278,6 +302,7 @@ where
                         cur_block_number = Some( block_number.as_u64() + 1);
                         cur_block_time = Some(timestamp + 12 );
                         cur_next_base_fee = next_base_fee;
+                        sandwich_victims.write().await.clear();
 
                         for _counter in 0..5  {
                             if let Ok(msg) = market_events_rx.recv().await {
@@ -288,17 +313,51 @@ where
                                 }
                             }
                         }
+
+                        // Pending txs were traced on the previous block, the ones most likely to be included are re-traced first
+                        let top_tx_hashes: Vec<TxHash> = mempool
+                            .read()
+                            .await
+                            .get_top_gas_price_txs(PENDING_TXS_RETRACE_COUNT)
+                            .into_iter()
+                            .map(|mempool_tx| mempool_tx.tx_hash)
+                            .collect();
+                        for tx_hash in top_tx_hashes {
+                            tokio::task::spawn(
+                                pending_tx_state_change_task(
+                                    client.clone(),
+                                    tx_hash,
+                                    market.clone(),
+                                    mempool.clone(),
+                                    latest_block.clone(),
+                                    market_state.clone(),
+                                    affecting_tx.clone(),
+                                    sandwich_victims.clone(),
+                                    cur_block_number.unwrap_or_default(),
+                                    cur_block_time.unwrap_or_default(),
+                                    cur_next_base_fee,
+                                    cur_state_override.clone(),
+                                    state_updates_broadcaster.clone(),
+                                )
+                            );
+                        }
                     }
                 }
             }
             msg = mempool_events_rx.recv() => {
                 if let Ok(msg) = msg {
                     let mempool_event_msg : MempoolEvents = msg;
-                    if let MempoolEvents::MempoolActualTxUpdate{ tx_hash }  = mempool_event_msg {
+                    if let MempoolEvents::SandwichDetected{ victim_tx, .. } = mempool_event_msg {
+                        sandwich_victims.write().await.insert(victim_tx);
+                    } else if let MempoolEvents::MempoolActualTxUpdate{ tx_hash }  = mempool_event_msg {
                         if cur_block_number.is_none() {
                             warn!("Did not received block header update yet!");
                             continue;
                         }
+                        if sandwich_victims.read().await.contains(&tx_hash) {
+                            debug!(%tx_hash, "Skipping sandwich victim");
+                            continue;
+                        }
 
                         tokio::task::spawn(
                             pending_tx_state_change_task(
@@ -309,6 +368,7 @@ where
                                 latest_block.clone(),
                                 market_state.clone(),
                                 affecting_tx.clone(),
+                                sandwich_victims.clone(),
                                 cur_block_number.unwrap_or_default(),
                                 cur_block_time.unwrap_or_default(),
                                 cur_next_base_fee,
@@ -340,6 +400,7 @@ pub struct PendingTxStateChangeProcessorActor<P, N, DB: Clone + Send + Sync + 's
     mempool_events_rx: Option<Broadcaster<MempoolEvents>>,
     #[producer]
     state_updates_tx: Option<Broadcaster<StateUpdateEvent<DB>>>,
+    cancellation_token: CancellationToken,
     _n: PhantomData<N>,
 }
 
@@ -359,10 +420,15 @@ where
             market_events_rx: None,
             mempool_events_rx: None,
             state_updates_tx: None,
+            cancellation_token: CancellationToken::new(),
             _n: PhantomData,
         }
     }
 
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
     pub fn on_bc(self, bc: &Blockchain, state: &BlockchainState<DB>, strategy: &Strategy<DB>) -> Self {
         Self {
             market: Some(bc.market()),
@@ -393,6 +459,7 @@ where
             self.mempool_events_rx.clone().unwrap(),
             self.market_events_rx.clone().unwrap(),
             self.state_updates_tx.clone().unwrap(),
+            self.cancellation_token.clone(),
         ));
         Ok(vec![task])
     }
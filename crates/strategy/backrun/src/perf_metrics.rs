@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use eyre::Result;
+use influxdb::{Timestamp, WriteQuery};
+use loom_core_actors::Broadcaster;
+use loom_types_entities::{Market, PoolId, PoolWrapper, SwapDirection, SwapPath};
+use tracing::error;
+
+/// Histogram is exported and reset every `FLUSH_INTERVAL_CALLS` calls
+const FLUSH_INTERVAL_CALLS: u64 = 1000;
+
+/// Upper bounds of the latency buckets in microseconds, the last bucket is unbounded
+const LATENCY_BUCKETS_US: [u64; 9] = [50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 50_000];
+
+struct SlowestCall {
+    pool_id: PoolId,
+    path_count: usize,
+    latency_us: u64,
+}
+
+/// Latency histogram of `Market::build_swap_path_vec` calls with the slowest call of the interval
+struct LatencyHistogram {
+    bucket_counts: [u64; LATENCY_BUCKETS_US.len() + 1],
+    count: u64,
+    total_latency_us: u64,
+    slowest: Option<SlowestCall>,
+}
+
+impl LatencyHistogram {
+    const fn new() -> Self {
+        Self { bucket_counts: [0; LATENCY_BUCKETS_US.len() + 1], count: 0, total_latency_us: 0, slowest: None }
+    }
+
+    /// Returns the write query of the histogram and resets it after `FLUSH_INTERVAL_CALLS` calls
+    fn record(&mut self, pool_id: PoolId, path_count: usize, latency_us: u64) -> Option<WriteQuery> {
+        let bucket = LATENCY_BUCKETS_US.iter().position(|upper_bound| latency_us <= *upper_bound).unwrap_or(LATENCY_BUCKETS_US.len());
+        self.bucket_counts[bucket] += 1;
+        self.count += 1;
+        self.total_latency_us += latency_us;
+        if self.slowest.as_ref().is_none_or(|slowest| latency_us > slowest.latency_us) {
+            self.slowest = Some(SlowestCall { pool_id, path_count, latency_us });
+        }
+
+        if self.count < FLUSH_INTERVAL_CALLS {
+            return None;
+        }
+        let write_query = self.to_write_query();
+        *self = Self::new();
+        Some(write_query)
+    }
+
+    fn to_write_query(&self) -> WriteQuery {
+        let mut write_query = WriteQuery::new(Timestamp::from(chrono::Utc::now()), "build_swap_path_vec_latency")
+            .add_field("count", self.count)
+            .add_field("mean_latency_us", self.total_latency_us / self.count.max(1));
+
+        for (upper_bound, bucket_count) in LATENCY_BUCKETS_US.iter().zip(self.bucket_counts.iter()) {
+            write_query = write_query.add_field(format!("le_{upper_bound}_us"), *bucket_count);
+        }
+        write_query = write_query.add_field("le_inf_us", self.bucket_counts[LATENCY_BUCKETS_US.len()]);
+
+        if let Some(slowest) = &self.slowest {
+            write_query = write_query
+                .add_tag("pool_id", slowest.pool_id.to_string())
+                .add_field("path_count_returned", slowest.path_count as u64)
+                .add_field("latency_us", slowest.latency_us);
+        }
+        write_query
+    }
+}
+
+static BUILD_SWAP_PATH_VEC_LATENCY: Mutex<LatencyHistogram> = Mutex::new(LatencyHistogram::new());
+
+/// `Market::build_swap_path_vec` with the call latency recorded in a histogram exported to influxdb every
+/// `FLUSH_INTERVAL_CALLS` calls
+pub(crate) fn build_swap_path_vec_instrumented(
+    market: &Market,
+    directions: &BTreeMap<PoolWrapper, Vec<SwapDirection>>,
+    influxdb_write_channel_tx: &Broadcaster<WriteQuery>,
+) -> Result<Vec<SwapPath>> {
+    let start_time = Instant::now();
+    let swap_paths = market.build_swap_path_vec(directions);
+    let latency_us = start_time.elapsed().as_micros() as u64;
+
+    let Some(pool_id) = directions.keys().next().map(|pool| pool.get_pool_id()) else {
+        return swap_paths;
+    };
+    let path_count = swap_paths.as_ref().map_or(0, |swap_paths| swap_paths.len());
+
+    let write_query = match BUILD_SWAP_PATH_VEC_LATENCY.lock() {
+        Ok(mut histogram) => histogram.record(pool_id, path_count, latency_us),
+        Err(e) => {
+            error!("BUILD_SWAP_PATH_VEC_LATENCY lock error : {}", e);
+            None
+        }
+    };
+    if let Some(write_query) = write_query {
+        if let Err(e) = influxdb_write_channel_tx.send(write_query) {
+            error!("Failed to send build_swap_path_vec latency to influxdb: {:?}", e);
+        }
+    }
+
+    swap_paths
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::Address;
+
+    #[test]
+    fn test_latency_histogram_flush() {
+        let mut histogram = LatencyHistogram::new();
+        let (fast_pool, slow_pool) = (PoolId::Address(Address::repeat_byte(1)), PoolId::Address(Address::repeat_byte(2)));
+
+        for _ in 0..FLUSH_INTERVAL_CALLS - 2 {
+            assert!(histogram.record(fast_pool, 3, 40).is_none());
+        }
+        assert!(histogram.record(slow_pool, 120, 75_000).is_none());
+        assert_eq!(histogram.bucket_counts[0], FLUSH_INTERVAL_CALLS - 2);
+        assert_eq!(histogram.bucket_counts[LATENCY_BUCKETS_US.len()], 1);
+        assert_eq!(histogram.slowest.as_ref().map(|slowest| (slowest.pool_id, slowest.path_count)), Some((slow_pool, 120)));
+
+        assert!(histogram.record(fast_pool, 3, 300).is_some());
+        assert_eq!(histogram.count, 0);
+        assert!(histogram.slowest.is_none());
+    }
+}
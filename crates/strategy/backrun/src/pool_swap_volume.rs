@@ -0,0 +1,125 @@
+use std::collections::{HashMap, HashSet};
+
+use alloy_primitives::{TxHash, U256};
+use alloy_rpc_types::Log;
+use alloy_sol_types::SolEvent;
+use loom_defi_abi::uniswap2::IUniswapV2Pair;
+use loom_defi_abi::uniswap3::IUniswapV3Pool;
+use loom_types_entities::{Market, PoolId};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PoolSwapVolume {
+    pub swap_count: u64,
+    pub volume_eth: U256,
+    /// Transactions swapping on the pool and on at least one other known pool, counted as arbitrages competing for the pool
+    pub competing_swap_count: u64,
+}
+
+/// Swap count and volume in ETH of known pools from UniswapV2 and UniswapV3 like swap logs.
+/// Amounts are valued with the first pool token that has an ETH price, pools without priced tokens get zero volume.
+pub fn get_pool_swap_volumes_from_logs(market: &Market, logs: &[Log]) -> HashMap<PoolId, PoolSwapVolume> {
+    let mut volumes: HashMap<PoolId, PoolSwapVolume> = HashMap::new();
+    // transaction -> known pools it swapped on
+    let mut tx_pools: HashMap<TxHash, HashSet<PoolId>> = HashMap::new();
+
+    for log in logs.iter() {
+        let pool_id = PoolId::Address(log.address());
+        let Some(pool) = market.get_pool(&pool_id) else {
+            continue;
+        };
+        let Some(topic0) = log.topic0() else {
+            continue;
+        };
+
+        let amounts = if *topic0 == IUniswapV2Pair::Swap::SIGNATURE_HASH {
+            IUniswapV2Pair::Swap::decode_log(&log.inner, false)
+                .ok()
+                .map(|event| (event.data.amount0In + event.data.amount0Out, event.data.amount1In + event.data.amount1Out))
+        } else if *topic0 == IUniswapV3Pool::Swap::SIGNATURE_HASH {
+            IUniswapV3Pool::Swap::decode_log(&log.inner, false)
+                .ok()
+                .map(|event| (event.data.amount0.unsigned_abs(), event.data.amount1.unsigned_abs()))
+        } else {
+            None
+        };
+        let Some((amount0, amount1)) = amounts else {
+            continue;
+        };
+
+        let tokens = pool.get_tokens();
+        if tokens.len() < 2 {
+            continue;
+        }
+        let volume_eth = market
+            .get_token(&tokens[0])
+            .and_then(|token| token.calc_eth_value(amount0))
+            .or_else(|| market.get_token(&tokens[1]).and_then(|token| token.calc_eth_value(amount1)))
+            .unwrap_or_default();
+
+        let volume_entry = volumes.entry(pool_id).or_default();
+        volume_entry.swap_count += 1;
+        volume_entry.volume_eth = volume_entry.volume_eth.saturating_add(volume_eth);
+
+        if let Some(tx_hash) = log.transaction_hash {
+            tx_pools.entry(tx_hash).or_default().insert(pool_id);
+        }
+    }
+
+    for pools in tx_pools.into_values().filter(|pools| pools.len() > 1) {
+        for pool_id in pools {
+            if let Some(volume_entry) = volumes.get_mut(&pool_id) {
+                volume_entry.competing_swap_count += 1;
+            }
+        }
+    }
+
+    volumes
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::Address;
+    use loom_types_entities::{MockPool, Token};
+
+    fn swap_log(address: Address, amount0_in: u64, amount0_out: u64, tx_hash: TxHash) -> Log {
+        let event = IUniswapV2Pair::Swap {
+            sender: Address::ZERO,
+            amount0In: U256::from(amount0_in) * U256::from(10).pow(U256::from(18)),
+            amount1In: U256::ZERO,
+            amount0Out: U256::from(amount0_out) * U256::from(10).pow(U256::from(18)),
+            amount1Out: U256::ZERO,
+            to: Address::ZERO,
+        };
+        Log { inner: alloy_primitives::Log { address, data: event.encode_log_data() }, transaction_hash: Some(tx_hash), ..Log::default() }
+    }
+
+    #[test]
+    fn test_get_pool_swap_volumes_from_logs() {
+        let (token0, token1, pool_address) = (Address::repeat_byte(1), Address::repeat_byte(2), Address::repeat_byte(10));
+        let other_pool_address = Address::repeat_byte(12);
+        let mut market = Market::default();
+        market.add_pool(MockPool::new(token0, token1, pool_address)).unwrap();
+        market.add_pool(MockPool::new(token1, token0, other_pool_address)).unwrap();
+        let token = Token::new_with_data(token0, None, None, Some(18), false, false);
+        token.set_eth_price(Some(U256::from(2) * U256::from(10).pow(U256::from(18))));
+        market.add_token(token);
+
+        let (tx0, tx1) = (TxHash::repeat_byte(1), TxHash::repeat_byte(2));
+        let logs = vec![
+            swap_log(pool_address, 4, 0, tx0),
+            swap_log(pool_address, 0, 2, tx1),
+            swap_log(Address::repeat_byte(11), 4, 0, tx0),
+            swap_log(other_pool_address, 0, 0, tx1),
+        ];
+
+        let volumes = get_pool_swap_volumes_from_logs(&market, &logs);
+        assert_eq!(volumes.len(), 2);
+        // tx1 swaps on both known pools, the unknown pool of tx0 is not counted
+        assert_eq!(
+            volumes[&PoolId::Address(pool_address)],
+            PoolSwapVolume { swap_count: 2, volume_eth: U256::from(3) * U256::from(10).pow(U256::from(18)), competing_swap_count: 1 }
+        );
+        assert_eq!(volumes[&PoolId::Address(other_pool_address)].competing_swap_count, 1);
+    }
+}
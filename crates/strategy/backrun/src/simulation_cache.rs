@@ -0,0 +1,195 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use alloy_primitives::{Address, U256};
+use loom_types_blockchain::GethStateUpdate;
+use loom_types_entities::{SwapAmountType, SwapError, SwapLine, SwapPath};
+
+pub const DEFAULT_SIMULATION_CACHE_SIZE: usize = 10_000;
+
+/// Hashes of the storage and code changes of each account of `state_update`. Balances and nonces change with every
+/// transaction and are left out
+pub fn account_state_hashes(state_update: &[GethStateUpdate]) -> HashMap<Address, u64> {
+    let mut hashes: HashMap<Address, u64> = HashMap::new();
+    for update in state_update.iter() {
+        for (address, account) in update.iter() {
+            let entry = hashes.entry(*address).or_default();
+            let mut h = DefaultHasher::new();
+            entry.hash(&mut h);
+            account.code.hash(&mut h);
+            account.storage.hash(&mut h);
+            *entry = h.finish();
+        }
+    }
+    hashes
+}
+
+/// Hash of the post-state of the pools of `swap_path` built on top of the state of `block_number`, paths of pools
+/// untouched by two state updates get the same hash
+pub fn path_state_hash(block_number: u64, account_hashes: &HashMap<Address, u64>, swap_path: &SwapPath) -> u64 {
+    let mut h = DefaultHasher::new();
+    block_number.hash(&mut h);
+    for pool in swap_path.pools.iter() {
+        for address in std::iter::once(pool.get_address()).chain(pool.get_state_addresses()) {
+            account_hashes.get(&address).hash(&mut h);
+        }
+    }
+    h.finish()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SimulationCacheKey {
+    path_fingerprint: [u8; 32],
+    amount_in: Option<U256>,
+    state_hash: u64,
+}
+
+impl SimulationCacheKey {
+    pub fn new(swap_line: &SwapLine, state_hash: u64) -> Self {
+        let amount_in = match swap_line.amount_in {
+            SwapAmountType::Set(amount) => Some(amount),
+            _ => None,
+        };
+        Self { path_fingerprint: swap_line.path_fingerprint(), amount_in, state_hash }
+    }
+}
+
+/// LRU cache of `SwapCalculator::calculate` results for the current block
+pub struct SimulationCache {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<SimulationCacheKey, (Result<SwapLine, SwapError>, u64)>,
+    // last access tick -> key
+    lru: BTreeMap<u64, SimulationCacheKey>,
+}
+
+impl Default for SimulationCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_SIMULATION_CACHE_SIZE)
+    }
+}
+
+impl SimulationCache {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity: capacity.max(1), tick: 0, entries: HashMap::new(), lru: BTreeMap::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    pub fn get(&mut self, key: &SimulationCacheKey) -> Option<Result<SwapLine, SwapError>> {
+        let tick = self.next_tick();
+        let (result, last_tick) = self.entries.get_mut(key)?;
+        self.lru.remove(last_tick);
+        self.lru.insert(tick, *key);
+        *last_tick = tick;
+        Some(result.clone())
+    }
+
+    pub fn insert(&mut self, key: SimulationCacheKey, result: Result<SwapLine, SwapError>) {
+        let tick = self.next_tick();
+        if let Some((_, last_tick)) = self.entries.insert(key, (result, tick)) {
+            self.lru.remove(&last_tick);
+        }
+        self.lru.insert(tick, key);
+
+        while self.entries.len() > self.capacity {
+            let Some((_, oldest_key)) = self.lru.pop_first() else {
+                break;
+            };
+            self.entries.remove(&oldest_key);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.lru.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::B256;
+    use alloy_rpc_types_trace::geth::AccountState;
+    use loom_types_entities::{MockPool, Token};
+    use std::collections::BTreeMap;
+
+    fn swap_path(pool_address: Address) -> SwapPath {
+        let token0 = Token::new(Address::repeat_byte(1));
+        let token1 = Token::new(Address::repeat_byte(2));
+        let pool = MockPool::new(token0.get_address(), token1.get_address(), pool_address);
+        SwapPath::new(vec![token0.clone(), token1, token0], vec![pool.clone(), pool])
+    }
+
+    fn state_update(address: Address, nonce: u64, slot_value: u8) -> GethStateUpdate {
+        let storage = BTreeMap::from([(B256::ZERO, B256::repeat_byte(slot_value))]);
+        let account = AccountState { balance: Some(U256::from(nonce)), nonce: Some(nonce), code: None, storage };
+        BTreeMap::from([(address, account)])
+    }
+
+    fn cache_key(n: u64) -> SimulationCacheKey {
+        SimulationCacheKey { path_fingerprint: [0u8; 32], amount_in: Some(U256::from(n)), state_hash: n }
+    }
+
+    #[test]
+    fn test_path_state_hash() {
+        let pool_address = Address::repeat_byte(10);
+        let sender = Address::repeat_byte(20);
+        let path = swap_path(pool_address);
+
+        let pool_state = account_state_hashes(&[state_update(pool_address, 0, 1), state_update(sender, 1, 0)]);
+        let hash = path_state_hash(100, &pool_state, &path);
+
+        // Another sender with other balance and nonce changes
+        let other_sender_state = account_state_hashes(&[state_update(pool_address, 5, 1), state_update(Address::repeat_byte(21), 7, 3)]);
+        assert_eq!(path_state_hash(100, &other_sender_state, &path), hash);
+
+        let other_pool_state = account_state_hashes(&[state_update(pool_address, 0, 2), state_update(sender, 1, 0)]);
+        assert_ne!(path_state_hash(100, &other_pool_state, &path), hash);
+
+        assert_ne!(path_state_hash(101, &pool_state, &path), hash);
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let mut cache = SimulationCache::new(2);
+        cache.insert(cache_key(1), Ok(SwapLine::default()));
+        cache.insert(cache_key(2), Ok(SwapLine::default()));
+
+        // Key 1 is used, so key 2 is the least recently used
+        assert!(cache.get(&cache_key(1)).is_some());
+        cache.insert(cache_key(3), Ok(SwapLine::default()));
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&cache_key(2)).is_none());
+        assert!(cache.get(&cache_key(1)).is_some());
+        assert!(cache.get(&cache_key(3)).is_some());
+    }
+
+    #[test]
+    fn test_insert_existing_key() {
+        let mut cache = SimulationCache::new(2);
+        cache.insert(cache_key(1), Ok(SwapLine::default()));
+        cache.insert(cache_key(2), Ok(SwapLine::default()));
+        cache.insert(cache_key(1), Ok(SwapLine::default()));
+        cache.insert(cache_key(3), Ok(SwapLine::default()));
+
+        // Reinserted key 1 is newer than key 2
+        assert!(cache.get(&cache_key(2)).is_none());
+        assert!(cache.get(&cache_key(1)).is_some());
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,170 @@
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{sync_channel, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::Duration;
+
+use eyre::ErrReport;
+use loom_types_entities::{SwapError, SwapLine};
+use revm::primitives::Env;
+use revm::DatabaseRef;
+
+use crate::SwapCalculator;
+
+/// Runs `SwapCalculator::calculate` on the global rayon pool and waits at most `timeout` for the result. The searcher
+/// iterates paths on its own thread pool, so waiting here never starves the calculation. A calculation not started
+/// when the timeout expires is skipped, a running one can not be interrupted and its result is dropped.
+pub fn calculate_with_timeout<DB>(swap_line: &SwapLine, state_db: Arc<DB>, env: Env, timeout: Duration) -> Result<SwapLine, SwapError>
+where
+    DB: DatabaseRef<Error = ErrReport> + Send + Sync + 'static,
+{
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let (result_tx, result_rx) = sync_channel(1);
+
+    let task_cancelled = cancelled.clone();
+    let mut task_swap_line = swap_line.clone();
+    rayon::spawn(move || {
+        if task_cancelled.load(Ordering::Relaxed) {
+            return;
+        }
+        // Panics in spawned jobs abort the process
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+            SwapCalculator::calculate(&mut task_swap_line, state_db.as_ref(), env, SwapCalculator::calldata_gas_cost).map(|_| ())
+        }))
+        .unwrap_or_else(|_| Err(task_swap_line.to_error("CALCULATION_PANICKED".to_string())));
+        let _ = result_tx.send(result.map(|_| task_swap_line));
+    });
+
+    match result_rx.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => {
+            cancelled.store(true, Ordering::Relaxed);
+            Err(swap_line.to_error(<SwapError>::TIMEOUT.to_string()))
+        }
+        Err(RecvTimeoutError::Disconnected) => Err(swap_line.to_error("CALCULATION_DROPPED".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::{Address, U256};
+    use eyre::{eyre, Result};
+    use loom_evm_db::LoomDBType;
+    use loom_types_entities::required_state::RequiredState;
+    use loom_types_entities::{
+        Pool, PoolAbiEncoder, PoolClass, PoolId, PoolProtocol, PoolWrapper, PreswapRequirement, SwapDirection, SwapPath, Token,
+    };
+    use std::any::Any;
+    use std::time::Instant;
+
+    const POOL_SLEEP: Duration = Duration::from_millis(200);
+
+    #[derive(Clone)]
+    struct SleepingPool {
+        address: Address,
+        tokens: Vec<Address>,
+    }
+
+    impl Pool for SleepingPool {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn get_class(&self) -> PoolClass {
+            PoolClass::UniswapV2
+        }
+
+        fn get_protocol(&self) -> PoolProtocol {
+            PoolProtocol::UniswapV2
+        }
+
+        fn get_address(&self) -> Address {
+            self.address
+        }
+
+        fn get_pool_id(&self) -> PoolId {
+            PoolId::Address(self.address)
+        }
+
+        fn get_fee(&self) -> U256 {
+            U256::ZERO
+        }
+
+        fn get_tokens(&self) -> Vec<Address> {
+            self.tokens.clone()
+        }
+
+        fn get_swap_directions(&self) -> Vec<SwapDirection> {
+            vec![(self.tokens[0], self.tokens[1]).into(), (self.tokens[1], self.tokens[0]).into()]
+        }
+
+        fn calculate_out_amount(
+            &self,
+            _state: &dyn DatabaseRef<Error = ErrReport>,
+            _env: Env,
+            _token_address_from: &Address,
+            _token_address_to: &Address,
+            _in_amount: U256,
+        ) -> Result<(U256, u64), ErrReport> {
+            std::thread::sleep(POOL_SLEEP);
+            Err(eyre!("SLEPT"))
+        }
+
+        fn calculate_in_amount(
+            &self,
+            _state: &dyn DatabaseRef<Error = ErrReport>,
+            _env: Env,
+            _token_address_from: &Address,
+            _token_address_to: &Address,
+            _out_amount: U256,
+        ) -> Result<(U256, u64), ErrReport> {
+            std::thread::sleep(POOL_SLEEP);
+            Err(eyre!("SLEPT"))
+        }
+
+        fn can_flash_swap(&self) -> bool {
+            false
+        }
+
+        fn can_calculate_in_amount(&self) -> bool {
+            true
+        }
+
+        fn get_abi_encoder(&self) -> Option<&dyn PoolAbiEncoder> {
+            None
+        }
+
+        fn get_read_only_cell_vec(&self) -> Vec<U256> {
+            Vec::new()
+        }
+
+        fn get_state_required(&self) -> Result<RequiredState> {
+            Ok(RequiredState::new())
+        }
+
+        fn is_native(&self) -> bool {
+            false
+        }
+
+        fn preswap_requirement(&self) -> PreswapRequirement {
+            PreswapRequirement::Base
+        }
+    }
+
+    #[test]
+    fn test_calculate_with_timeout() {
+        let (token_a, token_b) = (Token::new(Address::repeat_byte(1)), Token::new(Address::repeat_byte(2)));
+        token_a.set_eth_price(Some(U256::from(10).pow(U256::from(18))));
+        let pool = SleepingPool { address: Address::repeat_byte(3), tokens: vec![token_a.get_address(), token_b.get_address()] };
+        let swap_line: SwapLine = SwapPath::new(vec![token_a, token_b], vec![PoolWrapper::new(Arc::new(pool))]).into();
+
+        let start_time = Instant::now();
+        let result = calculate_with_timeout(&swap_line, Arc::new(LoomDBType::default()), Env::default(), Duration::from_millis(50));
+        assert!(start_time.elapsed() < POOL_SLEEP);
+
+        let swap_error = result.unwrap_err();
+        assert!(swap_error.is_timeout());
+        assert_eq!(swap_error.pool, PoolId::Address(Address::repeat_byte(3)));
+    }
+}
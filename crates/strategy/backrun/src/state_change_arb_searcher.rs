@@ -1,46 +1,188 @@
 use std::collections::{BTreeMap, HashSet};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
-use alloy_primitives::U256;
+use alloy_consensus::Transaction;
+use alloy_primitives::{Address, U256};
 #[cfg(not(debug_assertions))]
 use chrono::TimeDelta;
 use eyre::{eyre, ErrReport, Result};
 use influxdb::{Timestamp, WriteQuery};
 use rayon::prelude::*;
 use rayon::{ThreadPool, ThreadPoolBuilder};
+use revm::primitives::Env;
 use revm::{DatabaseCommit, DatabaseRef};
 use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{watch, Semaphore};
 #[cfg(not(debug_assertions))]
 use tracing::warn;
 use tracing::{debug, error, info, trace};
 
 use crate::BackrunConfig;
-use crate::profit_calculator::ProfitCalculator;
+use crate::CapitalManager;
+use crate::GasAuctionState;
+use crate::LandingProbabilityEstimator;
 use crate::SwapCalculator;
-use loom_core_actors::{subscribe, Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
+use crate::path_scoring_actor::PRIORITY_PATH_SCORE;
+use crate::profit_calculator::ProfitCalculator;
+use crate::simulation_cache::{account_state_hashes, path_state_hash, SimulationCache, SimulationCacheKey};
+use crate::simulation_timeout::calculate_with_timeout;
+use loom_core_actors::{
+    subscribe, Accessor, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, Producer, SharedState, WorkerResult,
+};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
 use loom_core_blockchain::{Blockchain, Strategy};
-use loom_evm_db::DatabaseHelpers;
-use loom_types_entities::strategy_config::StrategyConfig;
-use loom_types_entities::{Market, PoolWrapper, Swap, SwapDirection, SwapError, SwapLine, SwapPath};
+use loom_evm_db::DatabaseLoomExt;
+use loom_types_entities::{
+    AccountNonceAndBalanceState, CalculationResult, FlashLoanProvider, Market, PoolWrapper, Swap, SwapAmountType, SwapDirection, SwapError,
+    SwapLine, SwapPath,
+};
 use loom_types_events::{
-    BestTxSwapCompose, HealthEvent, Message, MessageHealthEvent, MessageSwapCompose, StateUpdateEvent, SwapComposeData, SwapComposeMessage,
-    TxComposeData,
+    BestTxSwapCompose, HealthEvent, LoomTask, MarketEvents, Message, MessageHealthEvent, MessageSwapCompose, StateUpdateEvent,
+    SwapComposeData, SwapComposeMessage, TxComposeData,
 };
 
-async fn state_change_arb_searcher_task<DB: DatabaseRef<Error = ErrReport> + DatabaseCommit + Send + Sync + Clone + Default + 'static>(
+// Share of the input amount swapped by the probing swap bundled before the full size one
+const PARTIAL_FILL_PROBE_RATIO: f64 = 0.5;
+
+/// Depth score in [0, 1] as an integer sort key
+fn depth_score_key(score: f64) -> u64 {
+    (score * 1_000_000.0) as u64
+}
+
+/// Output of `swap_line` executed on the state after `probe` swapped through the same pools. Each pool prices the hop on its
+/// reserves after the probe hop, as its output for the inputs of both hops less the output of the probe hop.
+fn calculate_swap_line_out<DB: DatabaseRef<Error = ErrReport>>(
+    swap_line: &SwapLine,
+    probe: &SwapLine,
+    db: &DB,
+    env: Env,
+) -> Result<(U256, u64, Vec<CalculationResult>), SwapError> {
+    let mut amount_in = swap_line.amount_in.unwrap_or_default();
+    let mut gas_used = 0;
+    let mut calculation_results = Vec::with_capacity(swap_line.pools().len());
+
+    for (i, pool) in swap_line.pools().iter().enumerate() {
+        let Some(probe_result) = probe.calculation_results.get(i) else {
+            return Err(swap_line.to_error("PROBE_NOT_CALCULATED".to_string()));
+        };
+        let token_from = swap_line.tokens()[i].get_address();
+        let token_to = swap_line.tokens()[i + 1].get_address();
+        let (amount_out, gas) = pool
+            .calculate_out_amount(db, env.clone(), &token_from, &token_to, probe_result.amount_in + amount_in)
+            .map_err(|e| swap_line.to_error(e.to_string()))?;
+        let amount_out = amount_out.saturating_sub(probe_result.amount_out);
+        if amount_out.is_zero() {
+            return Err(swap_line.to_error("ZERO_OUT_AMOUNT".to_string()));
+        }
+        calculation_results.push(CalculationResult::new(amount_in, amount_out));
+        gas_used += gas;
+        amount_in = amount_out;
+    }
+    Ok((amount_in, gas_used, calculation_results))
+}
+
+/// Split the calculated swap line into a probing swap with `PARTIAL_FILL_PROBE_RATIO` of the input amount and the rest of it,
+/// together they spend the input amount of the swap line. The probe is calculated on `db`, the rest by `calculate_swap_line_out`
+/// on the post-probe state. Splits with an unprofitable rest are rejected as the bundle would earn less than the probe profit.
+fn partial_fill_probe<DB: DatabaseRef<Error = ErrReport>>(
+    swap_line: &SwapLine,
+    db: &DB,
+    env: Env,
+) -> Result<(SwapLine, SwapLine), SwapError> {
+    let (mut probe, mut rest) = swap_line.split_at_ratio(PARTIAL_FILL_PROBE_RATIO);
+    let (amount_out, gas_used, calculation_results) =
+        probe.calculate_with_in_amount(db, env.clone(), probe.amount_in.unwrap_or_default())?;
+    probe.amount_out = SwapAmountType::Set(amount_out);
+    probe.gas_used = Some(gas_used);
+    probe.calculation_results = calculation_results;
+
+    let (rest_amount_out, rest_gas_used, rest_calculation_results) = calculate_swap_line_out(&rest, &probe, db, env)?;
+    if rest_amount_out <= rest.amount_in.unwrap_or_default() {
+        return Err(swap_line.to_error("PROBE_REST_NOT_PROFITABLE".to_string()));
+    }
+    rest.amount_out = SwapAmountType::Set(rest_amount_out);
+    rest.gas_used = Some(rest_gas_used);
+    rest.calculation_results = rest_calculation_results;
+    Ok((probe, rest))
+}
+
+/// Swap of the swap line funded by the multicaller balance of the input token. Lines exceeding the balance borrow the input
+/// from the Balancer vault with `use_flash_loans`, as do lines whose balance can not be checked. Otherwise the input amount
+/// is clamped to the balance and the line is calculated again on `db`. Lines are forwarded as is when the multicaller
+/// balance is not monitored and dropped when nothing is left.
+fn fund_swap_line<DB: DatabaseRef<Error = ErrReport>>(
+    capital_manager: &CapitalManager,
+    balance_state: &AccountNonceAndBalanceState,
+    multicaller_address: Address,
+    use_flash_loans: bool,
+    swap_line: SwapLine,
+    db: &DB,
+    env: Env,
+) -> Option<Swap> {
+    if !balance_state.is_monitored(&multicaller_address) {
+        return Some(Swap::BackrunSwapLine(swap_line));
+    }
+    let (Some(token), SwapAmountType::Set(amount_in)) = (swap_line.get_first_token(), swap_line.amount_in) else {
+        return Some(Swap::BackrunSwapLine(swap_line));
+    };
+    let balance = match capital_manager.rebalance_check(token, amount_in, multicaller_address, balance_state) {
+        Ok(amount) if amount >= amount_in => return Some(Swap::BackrunSwapLine(swap_line)),
+        Ok(amount) => Some(amount),
+        Err(e) => {
+            debug!(%swap_line, "Swap balance check failed : {}", e);
+            None
+        }
+    };
+
+    if use_flash_loans {
+        match SwapCalculator::calculate_flash_loan(&swap_line, FlashLoanProvider::BalancerV2) {
+            Ok(swap) => return Some(swap),
+            Err(e) => debug!(%swap_line, "Flash loan swap rejected : {:?}", e),
+        }
+    }
+
+    let amount = balance?;
+    debug!(%swap_line, balance = %amount, "Swap amount clamped to balance");
+    let mut clamped = swap_line.clone();
+    match clamped.calculate_with_in_amount(db, env, amount) {
+        Ok((amount_out, gas_used, calculation_results)) => {
+            clamped.amount_in = SwapAmountType::Set(amount);
+            clamped.amount_out = SwapAmountType::Set(amount_out);
+            clamped.gas_used = Some(gas_used);
+            clamped.calculation_results = calculation_results;
+            (!clamped.abs_profit_eth().is_zero()).then_some(Swap::BackrunSwapLine(clamped))
+        }
+        Err(e) => {
+            debug!(%swap_line, "Clamped swap calculation failed : {:?}", e);
+            None
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn state_change_arb_searcher_task<
+    DB: DatabaseRef<Error = ErrReport> + DatabaseCommit + DatabaseLoomExt + Send + Sync + Clone + Default + 'static,
+>(
     thread_pool: Arc<ThreadPool>,
+    simulation_cache: Arc<Mutex<SimulationCache>>,
     backrun_config: BackrunConfig,
+    chain_min_profit_wei: Option<U256>,
     state_update_event: StateUpdateEvent<DB>,
     market: SharedState<Market>,
+    gas_auction_state: Option<SharedState<GasAuctionState>>,
+    landing_probability: Option<SharedState<LandingProbabilityEstimator>>,
+    capital_manager: Arc<CapitalManager>,
+    nonce_and_balance: Option<SharedState<AccountNonceAndBalanceState>>,
+    multicaller_address: Option<Address>,
     swap_request_tx: Broadcaster<MessageSwapCompose<DB>>,
     pool_health_monitor_tx: Broadcaster<MessageHealthEvent>,
     influxdb_write_channel_tx: Broadcaster<WriteQuery>,
 ) -> Result<()> {
     debug!("Message received {} stuffing : {:?}", state_update_event.origin, state_update_event.stuffing_tx_hash());
 
-    let mut db = state_update_event.market_state().clone();
-    DatabaseHelpers::apply_geth_state_update_vec(&mut db, state_update_event.state_update().clone());
+    // The state update is applied to a new read-write layer, the market state of the event is shared and not copied
+    let mut db = DB::new_layer(state_update_event.shared_market_state());
+    db.apply_state_diff_in_place(state_update_event.state_update())?;
 
     let start_time_utc = chrono::Utc::now();
 
@@ -49,18 +191,29 @@ async fn state_change_arb_searcher_task<DB: DatabaseRef<Error = ErrReport> + Dat
 
     let market_guard_read = market.read().await;
     debug!(elapsed = start_time.elapsed().as_micros(), "market_guard market.read acquired");
+    let pnl_history = capital_manager.read_pnl_history().await;
 
     for (pool, v) in state_update_event.directions().iter() {
         let pool_paths: Vec<SwapPath> = match market_guard_read.get_pool_paths(&pool.get_pool_id()) {
-            Some(paths) => {
+            Some(mut paths) => {
+                // Deepest and most traded paths first
+                paths.sort_by_cached_key(|swap_path| {
+                    std::cmp::Reverse(depth_score_key(market_guard_read.compute_arbitrage_depth_score(swap_path)))
+                });
+
+                // Scores of paths failing their simulations are lowered by the success rate
+                let path_scores: Vec<f64> =
+                    paths.iter().map(|swap_path| swap_path.score_by_historical_success_rate(&pnl_history)).collect();
+
                 let pool_paths = paths
                     .into_iter()
+                    .zip(path_scores)
                     .enumerate()
-                    .filter(|(idx, swap_path)| {
-                        *idx < 100 || swap_path.score.unwrap_or_default() > 0.97
+                    .filter(|(idx, (_, path_score))| {
+                        *idx < 100 || *path_score > PRIORITY_PATH_SCORE
                         //&& !swap_path.pools.iter().any(|pool| market_guard_read.is_pool_disabled(&pool.get_pool_id()))
                     })
-                    .map(|(_, swap_path)| swap_path)
+                    .map(|(_, (swap_path, _))| swap_path)
                     .collect::<Vec<_>>();
 
                 // let pool_paths = pool_paths
@@ -76,7 +229,12 @@ async fn state_change_arb_searcher_task<DB: DatabaseRef<Error = ErrReport> + Dat
             None => {
                 let mut pool_direction: BTreeMap<PoolWrapper, Vec<SwapDirection>> = BTreeMap::new();
                 pool_direction.insert(pool.clone(), v.clone());
-                market_guard_read.build_swap_path_vec(&pool_direction).unwrap_or_default()
+                #[cfg(feature = "perf-metrics")]
+                let swap_paths =
+                    crate::perf_metrics::build_swap_path_vec_instrumented(&market_guard_read, &pool_direction, &influxdb_write_channel_tx);
+                #[cfg(not(feature = "perf-metrics"))]
+                let swap_paths = market_guard_read.build_swap_path_vec(&pool_direction);
+                swap_paths.unwrap_or_default()
             }
         };
 
@@ -84,11 +242,14 @@ async fn state_change_arb_searcher_task<DB: DatabaseRef<Error = ErrReport> + Dat
             swap_path_set.insert(pool_path);
         }
     }
+    let mut swap_path_vec: Vec<SwapPath> = swap_path_set.into_iter().collect();
+    swap_path_vec
+        .sort_by_cached_key(|swap_path| std::cmp::Reverse(depth_score_key(market_guard_read.compute_arbitrage_depth_score(swap_path))));
+
+    drop(pnl_history);
     drop(market_guard_read);
     debug!(elapsed = start_time.elapsed().as_micros(), "market_guard market.read released");
 
-    let swap_path_vec: Vec<SwapPath> = swap_path_set.into_iter().collect();
-
     if swap_path_vec.is_empty() {
         debug!(
             request=?state_update_event.stuffing_txs_hashes().first().unwrap_or_default(),
@@ -101,24 +262,49 @@ async fn state_change_arb_searcher_task<DB: DatabaseRef<Error = ErrReport> + Dat
     info!("Calculation started: swap_path_vec_len={} elapsed={}", swap_path_vec.len(), start_time.elapsed().as_micros());
 
     let env = state_update_event.evm_env();
+    let next_block_number = state_update_event.next_block_number;
+    let account_hashes = Arc::new(account_state_hashes(state_update_event.state_update()));
 
     let channel_len = swap_path_vec.len();
     let (swap_path_tx, mut swap_line_rx) = tokio::sync::mpsc::channel(channel_len);
 
-    let market_state_clone = db.clone();
+    let market_state_clone = Arc::new(db.clone());
     let swap_path_vec_len = swap_path_vec.len();
+    let simulation_timeout = backrun_config.bundle_simulation_timeout();
 
     // Clone backrun_config before moving it into the async block
     let backrun_config_clone = backrun_config.clone();
-    
+
+    let simulation_outcomes: Arc<Mutex<Vec<(SwapPath, bool)>>> = Arc::new(Mutex::new(Vec::with_capacity(swap_path_vec_len)));
+    let simulation_outcomes_clone = simulation_outcomes.clone();
+
     tokio::task::spawn(async move {
         thread_pool.install(|| {
             swap_path_vec.into_par_iter().for_each_with((&swap_path_tx, &market_state_clone, &env), |req, item| {
                 let mut mut_item: SwapLine = SwapLine { path: item, ..Default::default() };
                 //#[cfg(not(debug_assertions))]
                 //let start_time = chrono::Local::now();
-                // Use enhanced SwapCalculator with dynamic capital allocation
-                let calc_result = SwapCalculator::calculate(&mut mut_item, req.1, req.2.clone());
+                // Paths touched by several pending transactions with the same post-state are calculated once
+                let state_hash = path_state_hash(next_block_number, &account_hashes, &mut_item.path);
+                let cache_key = SimulationCacheKey::new(&mut_item, state_hash);
+                let cached_result = simulation_cache.lock().ok().and_then(|mut cache| cache.get(&cache_key));
+                let calc_result = match cached_result {
+                    Some(cached_result) => cached_result.map(|swap_line| mut_item = swap_line),
+                    None => {
+                        // Use enhanced SwapCalculator with dynamic capital allocation, slow pools are reported with a timeout error
+                        let calc_result = calculate_with_timeout(&mut_item, req.1.clone(), req.2.clone(), simulation_timeout)
+                            .map(|swap_line| mut_item = swap_line);
+                        if let Ok(mut cache) = simulation_cache.lock() {
+                            cache.insert(cache_key, calc_result.clone().map(|_| mut_item.clone()));
+                        }
+                        // Paths simulated without a profitable amount did not fail, profitability is not recorded
+                        let simulated = calc_result.as_ref().map_or_else(|e| e.is_no_profit(), |_| true);
+                        if let Ok(mut simulation_outcomes) = simulation_outcomes_clone.lock() {
+                            simulation_outcomes.push((mut_item.path.clone(), simulated));
+                        }
+                        calc_result
+                    }
+                };
                 //#[cfg(not(debug_assertions))]
                 //let took_time = chrono::Local::now() - start_time;
 
@@ -135,8 +321,7 @@ async fn state_change_arb_searcher_task<DB: DatabaseRef<Error = ErrReport> + Dat
                         if let Ok(profit) = mut_item.profit() {
                             // Calculate realistic minimum profit threshold
                             let gas_cost = U256::from(state_update_event.next_base_fee) * U256::from(300_000); // Estimated gas usage
-                            let flash_loan_fee = mut_item.abs_profit_eth() / U256::from(10000) * U256::from(5); // 0.05% fee
-                            let min_profit_threshold = gas_cost + flash_loan_fee + backrun_config_clone.min_profit_wei();
+                            let min_profit_threshold = gas_cost + backrun_config_clone.min_profit_wei(chain_min_profit_wei);
                             
                             // Check if profit is positive and exceeds the realistic minimum threshold
                             if profit.is_positive() && mut_item.abs_profit_eth() > min_profit_threshold {
@@ -190,28 +375,78 @@ async fn state_change_arb_searcher_task<DB: DatabaseRef<Error = ErrReport> + Dat
             Ok(swap_line) => {
                 // Clone backrun_config for use in this scope
                 let backrun_config_clone = backrun_config.clone();
-                
-                // Calculate optimized gas price with dynamic boost based on profit
-                let base_gas_price = U256::from(state_update_event.next_base_fee);
-                let eth_profit = swap_line.abs_profit_eth();
-                
-                // Dynamic gas boost: higher profit = more aggressive bidding
-                let profit_ratio = eth_profit / (base_gas_price * U256::from(300_000)); // profit vs gas cost ratio
-                let dynamic_boost = if profit_ratio > U256::from(10) {
-                    50 // 50% boost for very profitable trades
-                } else if profit_ratio > U256::from(5) {
-                    30 // 30% boost for profitable trades
-                } else {
-                    backrun_config_clone.gas_boost_percent() // Default boost
+
+                let swap = match (&nonce_and_balance, multicaller_address) {
+                    (Some(nonce_and_balance), Some(multicaller_address)) => {
+                        let balance_state = nonce_and_balance.read().await;
+                        match fund_swap_line(
+                            &capital_manager,
+                            &balance_state,
+                            multicaller_address,
+                            backrun_config_clone.use_flash_loans(),
+                            swap_line,
+                            &db,
+                            state_update_event.evm_env(),
+                        ) {
+                            Some(swap) => swap,
+                            None => {
+                                answers += 1;
+                                continue;
+                            }
+                        }
+                    }
+                    _ => Swap::BackrunSwapLine(swap_line),
+                };
+
+                // Bid a share of the profit left after the base fee cost as the priority fee
+                let mut gas_estimate = match swap.pre_estimate_gas() {
+                    0 => 300000,
+                    gas => gas,
+                };
+                let eth_profit = swap.abs_profit_eth();
+
+                // The probing swap lands in the same bundle right before the full size swap, flash loan swaps are not probed
+                let swap = match swap {
+                    Swap::BackrunSwapLine(swap_line) if backrun_config_clone.enable_partial_fill_probing() => {
+                        match partial_fill_probe(&swap_line, &db, state_update_event.evm_env()) {
+                            Ok((probe, rest)) => {
+                                gas_estimate += probe.gas_used.unwrap_or_default();
+                                Swap::Multiple(vec![Swap::BackrunSwapLine(probe), Swap::BackrunSwapLine(rest)])
+                            }
+                            Err(e) => {
+                                debug!("Partial fill probe calculation failed : {:?}", e);
+                                Swap::BackrunSwapLine(swap_line)
+                            }
+                        }
+                    }
+                    swap => swap,
+                };
+                let bribe_pct = backrun_config_clone.bribe_pct();
+                let priority_fee =
+                    SwapLine::estimated_miner_bribe(eth_profit, gas_estimate, state_update_event.next_base_fee, bribe_pct);
+                let priority_fee = match &gas_auction_state {
+                    Some(gas_auction_state) => gas_auction_state.read().await.apply(priority_fee),
+                    None => priority_fee,
+                };
+                // Tip the stuffing txs actually pay at the next base fee, legacy and EIP-2930 txs tip their gas price above it
+                let stuffing_priority_fee = state_update_event
+                    .stuffing_txs
+                    .iter()
+                    .filter_map(|tx| tx.effective_tip_per_gas(state_update_event.next_base_fee))
+                    .max()
+                    .unwrap_or_default() as u64;
+                let priority_fee = backrun_config_clone.apply_stuffing_tx_gas_multiplier(priority_fee, stuffing_priority_fee);
+
+                // The swap is only annotated, the router applies the landing probability cutoff
+                let estimated_landing_probability = match &landing_probability {
+                    Some(landing_probability) => {
+                        landing_probability.read().await.estimate_sampled(priority_fee, state_update_event.next_base_fee)
+                    }
+                    None => None,
                 };
                 
-                let optimized_gas_price = base_gas_price * (U256::from(100 + dynamic_boost)) / U256::from(100);
-                
-                // Calculate priority fee (the part above base fee)
-                let priority_fee = u64::try_from(optimized_gas_price.saturating_sub(base_gas_price)).unwrap_or(0);
-                
-                info!("Dynamic gas pricing: profit_ratio={}, boost={}%, gas_price={}", 
-                      profit_ratio, dynamic_boost, optimized_gas_price);
+                info!("Miner bribe gas pricing: profit={}, gas={}, bribe={}%, priority_fee={}", 
+                      eth_profit, gas_estimate, bribe_pct, priority_fee);
                 
                 // Determine if we should use private transactions
                 let use_private_tx = backrun_config_clone.private_tx_enabled();
@@ -222,11 +457,10 @@ async fn state_change_arb_searcher_task<DB: DatabaseRef<Error = ErrReport> + Dat
                 
                 // Log gas optimization info
                 debug!(
-                    "Gas optimization: base_fee={}, optimized={}, priority_fee={}, boost={}%, private_tx={}, mev_blocker={}",
-                    base_gas_price,
-                    optimized_gas_price,
+                    "Gas optimization: base_fee={}, priority_fee={}, bribe={}%, private_tx={}, mev_blocker={}",
+                    state_update_event.next_base_fee,
                     priority_fee,
-                    backrun_config_clone.gas_boost_percent(),
+                    bribe_pct,
                     use_private_tx,
                     use_mev_blocker
                 );
@@ -245,46 +479,40 @@ async fn state_change_arb_searcher_task<DB: DatabaseRef<Error = ErrReport> + Dat
                 
                 let prepare_request = SwapComposeMessage::Prepare(SwapComposeData {
                     tx_compose: TxComposeData {
-                        eoa: backrun_config_clone.eoa(),
+                        eoa: backrun_config.next_eoa(),
                         next_block_number: state_update_event.next_block_number,
                         next_block_timestamp: state_update_event.next_block_timestamp,
                         next_block_base_fee: state_update_event.next_base_fee,
-                        gas: swap_line.gas_used.unwrap_or(300000),
+                        gas: gas_estimate,
                         priority_gas_fee: priority_fee,
                         stuffing_txs: state_update_event.stuffing_txs.clone(),
                         stuffing_txs_hashes: state_update_event.stuffing_txs_hashes.clone(),
+                        use_eip7702: backrun_config_clone.enable_eip7702(),
+                        use_access_list: backrun_config_clone.use_access_list(),
 origin: Some(state_update_event.origin.clone() + &mev_info),
                         ..TxComposeData::default()
                     },
-                    swap: Swap::BackrunSwapLine(swap_line),
+                    swap,
                     tips_pct: Some(state_update_event.tips_pct),
+                    estimated_landing_probability,
                     poststate: Some(db.clone()),
                     poststate_update: Some(state_update_event.state_update().clone()),
                     ..SwapComposeData::default()
                 });
 
                 if !backrun_config_clone.smart() || best_answers.check(&prepare_request) {
-                    // Calculate profit in multiple currencies
-                    if let Swap::BackrunSwapLine(ref swap_line) = prepare_request.swap {
-                        let eth_profit = swap_line.abs_profit_eth();
-                        
-                        // Get the chain ID from the backrun config
-                        let chain_id = Some(backrun_config_clone.chain_id());
-                        
-                        // Spawn a task to calculate and log multi-currency profits
-                        // This won't block the main execution flow
-                    let db_clone = db.clone();
-                    tokio::spawn(async move {
-                        match ProfitCalculator::calculate_multi_currency_profit(eth_profit, &db_clone, chain_id).await {
-                            Ok(multi_profit) => {
-                                // Log profits in multiple currencies
-                                multi_profit.log_profits();
-                            },
-                            Err(e) => {
-                                error!("Failed to calculate multi-currency profit: {}", e);
+                    // Multi currency profit is logged from a separate task, it does not block the search
+                    if let Some(token_in) = prepare_request.swap.get_first_token().cloned() {
+                        let eth_profit = prepare_request.swap.abs_profit_eth();
+                        let chain_id = backrun_config_clone.chain_id();
+                        let market = market.clone();
+                        tokio::spawn(async move {
+                            let market_guard = market.read().await;
+                            match ProfitCalculator::calculate_multi_currency_profit(eth_profit, &market_guard, &token_in, chain_id) {
+                                Ok(multi_profit) => multi_profit.log_profits(),
+                                Err(e) => debug!("Failed to calculate multi currency profit : {}", e),
                             }
-                        }
-                    });
+                        });
                     }
                     
                     if let Err(e) = swap_request_tx_clone.send(Message::new(prepare_request)) {
@@ -304,6 +532,12 @@ origin: Some(state_update_event.origin.clone() + &mev_info),
         answers += 1;
     }
 
+    // Cached results were recorded by the search that calculated them
+    let simulation_outcomes = simulation_outcomes.lock().map(|mut outcomes| std::mem::take(&mut *outcomes)).unwrap_or_default();
+    for (swap_path, success) in simulation_outcomes.iter() {
+        capital_manager.record_simulation(swap_path, *success).await;
+    }
+
     let stuffing_tx_hash = state_update_event.stuffing_tx_hash();
     let elapsed = start_time.elapsed().as_micros();
     info!(
@@ -329,15 +563,50 @@ origin: Some(state_update_event.origin.clone() + &mev_info),
     Ok(())
 }
 
+/// Number of running searches out of `max_concurrent_searches`
+fn send_concurrent_searches_gauge(
+    semaphore: &Semaphore,
+    max_concurrent_searches: usize,
+    influxdb_write_channel_tx: &Broadcaster<WriteQuery>,
+) {
+    let running = max_concurrent_searches.saturating_sub(semaphore.available_permits());
+    let write_query = WriteQuery::new(Timestamp::from(chrono::Utc::now()), "concurrent_searches_gauge")
+        .add_field("running", running as u64)
+        .add_field("max", max_concurrent_searches as u64);
+    if let Err(e) = influxdb_write_channel_tx.send(write_query) {
+        error!("Failed to send concurrent searches to influxdb: {:?}", e);
+    }
+}
+
+/// Nonces and balances of all rotated EOAs are tracked, so every EOA gets its own nonce sequence
+async fn monitor_eoa_rotation(backrun_config: &BackrunConfig, nonce_and_balance: &Option<SharedState<AccountNonceAndBalanceState>>) {
+    let Some(nonce_and_balance) = nonce_and_balance else {
+        return;
+    };
+    let mut nonce_and_balance_guard = nonce_and_balance.write().await;
+    for eoa in backrun_config.eoa_rotation() {
+        nonce_and_balance_guard.add_account(*eoa);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn state_change_arb_searcher_worker<
-    DB: DatabaseRef<Error = ErrReport> + DatabaseCommit + Send + Sync + Clone + Default + 'static,
+    DB: DatabaseRef<Error = ErrReport> + DatabaseCommit + DatabaseLoomExt + Send + Sync + Clone + Default + 'static,
 >(
     backrun_config: BackrunConfig,
+    chain_min_profit_wei: Option<U256>,
     market: SharedState<Market>,
+    gas_auction_state: Option<SharedState<GasAuctionState>>,
+    landing_probability: Option<SharedState<LandingProbabilityEstimator>>,
+    nonce_and_balance: Option<SharedState<AccountNonceAndBalanceState>>,
+    multicaller_address: Option<Address>,
+    market_events_rx: Option<Broadcaster<MarketEvents>>,
+    tasks_rx: Option<Broadcaster<LoomTask>>,
     search_request_rx: Broadcaster<StateUpdateEvent<DB>>,
     swap_request_tx: Broadcaster<MessageSwapCompose<DB>>,
     pool_health_monitor_tx: Broadcaster<MessageHealthEvent>,
     influxdb_write_channel_tx: Broadcaster<WriteQuery>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult {
     subscribe!(search_request_rx);
 
@@ -347,23 +616,75 @@ pub async fn state_change_arb_searcher_worker<
     let tasks = std::cmp::max(2, cpus);
     info!("Starting state arb searcher cpus={cpus}, tasks={tasks}");
     let thread_pool = Arc::new(ThreadPoolBuilder::new().num_threads(tasks).build()?);
+    let simulation_cache = Arc::new(Mutex::new(SimulationCache::default()));
+    // Bounds EVM simulations running at the same time, searches of new state updates wait for a finished search
+    let max_concurrent_searches = backrun_config.max_concurrent_searches();
+    let search_semaphore = Arc::new(Semaphore::new(max_concurrent_searches));
+    let capital_manager =
+        Arc::new(CapitalManager::new(backrun_config.max_capital_usd()).with_health_monitor_channel(pool_health_monitor_tx.clone()));
+    let mut market_events_rx = market_events_rx.map(|market_events_rx| market_events_rx.subscribe());
+    let mut tasks_rx = tasks_rx.map(|tasks_rx| tasks_rx.subscribe());
+    // Reloaded configs apply to searches started afterwards. The number of concurrent searches is fixed at startup.
+    monitor_eoa_rotation(&backrun_config, &nonce_and_balance).await;
+    let (backrun_config_tx, backrun_config_rx) = watch::channel(backrun_config);
 
     loop {
         tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("State change arb searcher cancelled".to_string());
+            }
                 msg = search_request_rx.recv() => {
                 let pool_update_msg : Result<StateUpdateEvent<DB>, RecvError> = msg;
                 if let Ok(msg) = pool_update_msg {
-                    tokio::task::spawn(
-                        state_change_arb_searcher_task(
-                            thread_pool.clone(),
-                            backrun_config.clone(),
-                            msg,
-                            market.clone(),
-                            swap_request_tx.clone(),
-                            pool_health_monitor_tx.clone(),
-                            influxdb_write_channel_tx.clone(),
-                        )
+                    let search_task = state_change_arb_searcher_task(
+                        thread_pool.clone(),
+                        simulation_cache.clone(),
+                        backrun_config_rx.borrow().clone(),
+                        chain_min_profit_wei,
+                        msg,
+                        market.clone(),
+                        gas_auction_state.clone(),
+                        landing_probability.clone(),
+                        capital_manager.clone(),
+                        nonce_and_balance.clone(),
+                        multicaller_address,
+                        swap_request_tx.clone(),
+                        pool_health_monitor_tx.clone(),
+                        influxdb_write_channel_tx.clone(),
                     );
+                    let search_semaphore = search_semaphore.clone();
+                    let influxdb_write_channel_tx = influxdb_write_channel_tx.clone();
+                    // The permit is acquired by the spawned task, so waiting for a finished search does not block the event loop
+                    tokio::task::spawn(async move {
+                        let permit = search_semaphore.clone().acquire_owned().await?;
+                        send_concurrent_searches_gauge(&search_semaphore, max_concurrent_searches, &influxdb_write_channel_tx);
+                        let result = search_task.await;
+                        drop(permit);
+                        send_concurrent_searches_gauge(&search_semaphore, max_concurrent_searches, &influxdb_write_channel_tx);
+                        result
+                    });
+                }
+            }
+            msg = async { market_events_rx.as_mut().unwrap().recv().await }, if market_events_rx.is_some() => {
+                let market_event_msg : Result<MarketEvents, RecvError> = msg;
+                if let Ok(MarketEvents::BlockHeaderUpdate { block_number, .. }) = market_event_msg {
+                    if let Ok(mut cache) = simulation_cache.lock() {
+                        debug!(block_number, entries = cache.len(), "Simulation cache cleared");
+                        cache.clear();
+                    }
+                }
+            }
+            msg = async { tasks_rx.as_mut().unwrap().recv().await }, if tasks_rx.is_some() => {
+                let task_msg : Result<LoomTask, RecvError> = msg;
+                if let Some(backrun_config) = task_msg.ok().and_then(|task| task.reloaded_config::<BackrunConfig>()) {
+                    info!(
+                        max_capital_usd = backrun_config.max_capital_usd(),
+                        gas_boost_percent = backrun_config.gas_boost_percent(),
+                        "Backrun config reloaded"
+                    );
+                    capital_manager.set_max_capital_usd(backrun_config.max_capital_usd()).await;
+                    monitor_eoa_rotation(&backrun_config, &nonce_and_balance).await;
+                    backrun_config_tx.send_replace(backrun_config);
                 }
             }
         }
@@ -373,8 +694,20 @@ pub async fn state_change_arb_searcher_worker<
 #[derive(Accessor, Consumer, Producer)]
 pub struct StateChangeArbSearcherActor<DB: Clone + Send + Sync + 'static> {
     backrun_config: BackrunConfig,
+    chain_min_profit_wei: Option<U256>,
+    multicaller_address: Option<Address>,
     #[accessor]
     market: Option<SharedState<Market>>,
+    #[accessor]
+    gas_auction_state: Option<SharedState<GasAuctionState>>,
+    #[accessor]
+    landing_probability: Option<SharedState<LandingProbabilityEstimator>>,
+    #[accessor]
+    nonce_and_balance: Option<SharedState<AccountNonceAndBalanceState>>,
+    #[consumer]
+    market_events_rx: Option<Broadcaster<MarketEvents>>,
+    #[consumer]
+    tasks_rx: Option<Broadcaster<LoomTask>>,
     #[consumer]
     state_update_rx: Option<Broadcaster<StateUpdateEvent<DB>>>,
     #[producer]
@@ -383,23 +716,45 @@ pub struct StateChangeArbSearcherActor<DB: Clone + Send + Sync + 'static> {
     pool_health_monitor_tx: Option<Broadcaster<MessageHealthEvent>>,
     #[producer]
     influxdb_write_channel_tx: Option<Broadcaster<WriteQuery>>,
+    cancellation_token: CancellationToken,
 }
 
 impl<DB: DatabaseRef<Error = ErrReport> + Send + Sync + Clone + 'static> StateChangeArbSearcherActor<DB> {
     pub fn new(backrun_config: BackrunConfig) -> StateChangeArbSearcherActor<DB> {
         StateChangeArbSearcherActor {
             backrun_config,
+            chain_min_profit_wei: None,
+            multicaller_address: None,
             market: None,
+            gas_auction_state: None,
+            landing_probability: None,
+            nonce_and_balance: None,
+            market_events_rx: None,
+            tasks_rx: None,
             state_update_rx: None,
             compose_tx: None,
             pool_health_monitor_tx: None,
             influxdb_write_channel_tx: None,
+            cancellation_token: CancellationToken::new(),
         }
     }
 
+    /// Swap amounts are clamped to the balance of the multicaller holding the swap capital
+    pub fn with_multicaller_address(self, multicaller_address: Address) -> Self {
+        Self { multicaller_address: Some(multicaller_address), ..self }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
     pub fn on_bc(self, bc: &Blockchain, strategy: &Strategy<DB>) -> Self {
         Self {
+            chain_min_profit_wei: bc.min_profit_wei(),
             market: Some(bc.market()),
+            nonce_and_balance: Some(bc.nonce_and_balance()),
+            market_events_rx: Some(bc.market_events_channel()),
+            tasks_rx: Some(bc.tasks_channel()),
             pool_health_monitor_tx: Some(bc.health_monitor_channel()),
             compose_tx: Some(strategy.swap_compose_channel()),
             state_update_rx: Some(strategy.state_update_channel()),
@@ -409,17 +764,25 @@ impl<DB: DatabaseRef<Error = ErrReport> + Send + Sync + Clone + 'static> StateCh
     }
 }
 
-impl<DB: DatabaseRef<Error = ErrReport> + DatabaseCommit + Send + Sync + Clone + Default + 'static> Actor
+impl<DB: DatabaseRef<Error = ErrReport> + DatabaseCommit + DatabaseLoomExt + Send + Sync + Clone + Default + 'static> Actor
     for StateChangeArbSearcherActor<DB>
 {
     fn start(&self) -> ActorResult {
         let task = tokio::task::spawn(state_change_arb_searcher_worker(
             self.backrun_config.clone(),
+            self.chain_min_profit_wei,
             self.market.clone().unwrap(),
+            self.gas_auction_state.clone(),
+            self.landing_probability.clone(),
+            self.nonce_and_balance.clone(),
+            self.multicaller_address,
+            self.market_events_rx.clone(),
+            self.tasks_rx.clone(),
             self.state_update_rx.clone().unwrap(),
             self.compose_tx.clone().unwrap(),
             self.pool_health_monitor_tx.clone().unwrap(),
             self.influxdb_write_channel_tx.clone().unwrap(),
+            self.cancellation_token.clone(),
         ));
         Ok(vec![task])
     }
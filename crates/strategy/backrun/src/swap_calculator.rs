@@ -3,8 +3,9 @@ use alloy_primitives::utils::parse_units;
 use alloy_primitives::U256;
 use eyre::{eyre, ErrReport, Result};
 use lazy_static::lazy_static;
+use loom_execution_multicaller::MulticallerSwapEncoder;
 use loom_types_blockchain::LoomDataTypes;
-use loom_types_entities::{PoolWrapper, SwapError, SwapLine};
+use loom_types_entities::{FlashLoanProvider, PoolWrapper, Swap, SwapAmountType, SwapError, SwapLine};
 use revm::primitives::Env;
 use revm::DatabaseRef;
 use tracing::debug;
@@ -50,18 +51,40 @@ lazy_static! {
     
     // Gas cost estimation (21000 base + ~200000 for complex swaps)
     static ref ESTIMATED_GAS_COST: U256 = U256::from(250000);
+
+    // Encoder of the multicaller calldata the calldata gas is estimated with
+    static ref CALLDATA_GAS_ENCODER: MulticallerSwapEncoder = MulticallerSwapEncoder::default();
+}
+
+/// Golden-section iterations in `optimize_input_amount`, two initial evaluations plus one per iteration
+const GOLDEN_SECTION_ITERATIONS: usize = 12;
+
+/// `(sqrt(5) - 1) / 2` scaled by `GOLDEN_RATIO_DENOMINATOR`
+const GOLDEN_RATIO_NUMERATOR: u64 = 618_033_988_749;
+const GOLDEN_RATIO_DENOMINATOR: u64 = 1_000_000_000_000;
+
+/// Part of the interval `width` at the inverse golden ratio
+fn golden_section(width: U256) -> U256 {
+    width * U256::from(GOLDEN_RATIO_NUMERATOR) / U256::from(GOLDEN_RATIO_DENOMINATOR)
 }
 
 pub struct SwapCalculator {}
 
 impl SwapCalculator {
-    /// Calculate the optimal input amount and profit for a swap path with enhanced profitability checks
+    /// Calculate the optimal input amount and profit for a swap path with enhanced profitability checks.
+    /// Calldata gas, as estimated by `calldata_gas_cost` on the line of each test amount, is paid on top of the execution gas.
     #[inline]
-    pub fn calculate<'a, DB: DatabaseRef<Error = ErrReport>, LDT: LoomDataTypes>(
+    pub fn calculate<'a, DB, LDT, F>(
         path: &'a mut SwapLine<LDT>,
         state: &'a DB,
         env: Env,
-    ) -> Result<&'a mut SwapLine<LDT>, SwapError<LDT>> {
+        calldata_gas_cost: F,
+    ) -> Result<&'a mut SwapLine<LDT>, SwapError<LDT>>
+    where
+        DB: DatabaseRef<Error = ErrReport>,
+        LDT: LoomDataTypes,
+        F: Fn(&SwapLine<LDT>) -> Result<u64, SwapError<LDT>>,
+    {
         let first_token = path.get_first_token().unwrap();
         
         // Start with multiple test amounts to find the best range
@@ -72,6 +95,18 @@ impl SwapCalculator {
             parse_units("5.0", "ether").unwrap().into(),
         ];
         
+        // Hooks can change swap results arbitrarily, such paths are evaluated only with EVM quoted test amounts
+        let has_hooks = path.pools().iter().any(|pool| pool.get_hooks().is_some());
+
+        // Last pool takes exactOutput, compute the input for target outputs instead of optimizing the input
+        if !has_hooks && path.get_last_pool().is_some_and(|pool| pool.supports_exact_output()) {
+            if let Some(best) = Self::calculate_with_exact_output(path, state, env.clone(), &test_amounts, &calldata_gas_cost) {
+                *path = best;
+                debug!("Found profitable exact output path with profit: {} ETH", path.abs_profit_eth());
+                return Ok(path);
+            }
+        }
+        
         let mut best_path: Option<SwapLine<LDT>> = None;
         let mut best_profit = U256::ZERO;
         
@@ -82,14 +117,17 @@ impl SwapCalculator {
                 // Test this amount
                 if let Ok(_) = path_clone.optimize_with_in_amount(state, env.clone(), amount_in) {
                     let profit = path_clone.abs_profit_eth();
+                    let calldata_gas = calldata_gas_cost(&path_clone)?;
                     
                     // Check if this is profitable after costs
-                    if Self::is_profitable_after_costs(profit, test_eth_amount.into(), &env) {
+                    if Self::is_profitable_after_costs(profit, calldata_gas, &env) {
                         if profit > best_profit {
                             best_profit = profit;
                             
                             // Try to optimize around this amount
-                            if let Ok(_) = Self::optimize_input_amount(&mut path_clone, state, env.clone(), amount_in) {
+                            if has_hooks {
+                                best_path = Some(path_clone);
+                            } else if let Ok(_) = Self::optimize_input_amount(&mut path_clone, state, env.clone(), amount_in) {
                                 let optimized_profit = path_clone.abs_profit_eth();
                                 if optimized_profit > profit {
                                     best_path = Some(path_clone);
@@ -113,32 +151,106 @@ impl SwapCalculator {
             debug!("Found profitable path with profit: {} ETH", best_profit);
             Ok(path)
         } else {
-            Err(path.to_error("NO_PROFITABLE_AMOUNT_FOUND".to_string()))
+            Err(path.to_error(<SwapError<LDT>>::NO_PROFIT.to_string()))
+        }
+    }
+    
+    /// Borrow the input amount of a swap line calculated with `calculate` from `provider`.
+    /// The flash loan fee is subtracted from the profit of the returned swap, swap lines not covering the fee or swapping
+    /// through a pool locked by the lender are rejected.
+    pub fn calculate_flash_loan<LDT: LoomDataTypes>(
+        path: &SwapLine<LDT>,
+        provider: FlashLoanProvider,
+    ) -> Result<Swap<LDT>, SwapError<LDT>> {
+        if path.pools().iter().any(|pool| provider.is_pool_locked(pool)) {
+            return Err(path.to_error("FLASH_LOAN_POOL_ON_PATH".to_string()));
+        }
+
+        let flash_loan_fee = provider.fee(path.amount_in.unwrap_or_default());
+        if path.abs_profit() <= flash_loan_fee {
+            return Err(path.to_error("FLASH_LOAN_FEE_EXCEEDS_PROFIT".to_string()));
+        }
+
+        let swap = Swap::new_flash_loan(provider, path.clone()).ok_or_else(|| path.to_error("NO_FIRST_TOKEN".to_string()))?;
+        debug!("Flash loan swap {} fee: {} profit: {} ETH", swap, flash_loan_fee, swap.abs_profit_eth());
+        Ok(swap)
+    }
+
+    /// Find the most profitable target output, the required input is calculated backwards through the path
+    fn calculate_with_exact_output<DB, LDT, F>(
+        path: &SwapLine<LDT>,
+        state: &DB,
+        env: Env,
+        test_amounts: &[U256],
+        calldata_gas_cost: &F,
+    ) -> Option<SwapLine<LDT>>
+    where
+        DB: DatabaseRef<Error = ErrReport>,
+        LDT: LoomDataTypes,
+        F: Fn(&SwapLine<LDT>) -> Result<u64, SwapError<LDT>>,
+    {
+        let first_token = path.get_first_token()?;
+
+        let mut best_path: Option<SwapLine<LDT>> = None;
+        let mut best_profit = U256::ZERO;
+
+        for test_eth_amount in test_amounts {
+            let Some(out_amount) = first_token.calc_token_value_from_eth(*test_eth_amount) else {
+                continue;
+            };
+            let Ok((in_amount, _, _)) = path.calculate_with_out_amount(state, env.clone(), out_amount) else {
+                continue;
+            };
+            if in_amount >= out_amount {
+                continue;
+            }
+
+            let Ok((out_amount, gas_used, calculation_results)) = path.calculate_with_in_amount(state, env.clone(), in_amount) else {
+                continue;
+            };
+
+            let mut path_clone = path.clone();
+            path_clone.amount_in = SwapAmountType::Set(in_amount);
+            path_clone.amount_out = SwapAmountType::Set(out_amount);
+            path_clone.gas_used = Some(gas_used);
+            path_clone.calculation_results = calculation_results;
+
+            let profit = path_clone.abs_profit_eth();
+            let calldata_gas = calldata_gas_cost(&path_clone).ok()?;
+            if profit > best_profit && Self::is_profitable_after_costs(profit, calldata_gas, &env) {
+                best_profit = profit;
+                best_path = Some(path_clone);
+            }
         }
+
+        best_path
     }
     
-    /// Check if a trade is profitable after accounting for gas costs and fees
+    /// Calldata gas of the multicaller transaction executing `path` with its input amount set, swap lines the multicaller can
+    /// not encode are rejected
+    pub fn calldata_gas_cost(path: &SwapLine) -> Result<u64, SwapError> {
+        CALLDATA_GAS_ENCODER
+            .estimate_calldata_gas_cost(&Swap::BackrunSwapLine(path.clone()))
+            .map_err(|error| path.to_error(format!("CALLDATA_NOT_ENCODED: {error}")))
+    }
+
+    /// Check if a trade is profitable after accounting for gas costs. Pool fees are already deducted from `profit` by the
+    /// pool output amounts, so they are not charged again.
     #[inline]
-    fn is_profitable_after_costs(profit: U256, input_amount: U256, env: &Env) -> bool {
-        // Calculate gas cost in ETH
+    fn is_profitable_after_costs(profit: U256, calldata_gas: u64, env: &Env) -> bool {
+        // Calculate gas cost in ETH, calldata gas is paid on top of the execution gas
         // alloy U256 has no unwrap_or; fallback manually
         let gas_price: U256 = if env.tx.gas_price.is_zero() { U256::from(20_000_000_000u64) } else { env.tx.gas_price }; // 20 gwei default
-        let gas_cost_wei = gas_price * *ESTIMATED_GAS_COST;
-        
-        // Calculate flash loan fee
-        let flash_loan_fee = Self::calculate_flash_loan_fee(input_amount);
-        
-        // Total costs
-        let total_costs = gas_cost_wei + flash_loan_fee;
+        let gas_cost_wei = gas_price * (*ESTIMATED_GAS_COST + U256::from(calldata_gas));
         
+        // Flash loan fees are charged on flash loan swaps only in `calculate_flash_loan`
         // Profit must exceed costs plus minimum threshold
-        let required_profit = total_costs + *MIN_PROFIT_THRESHOLD;
+        let required_profit = gas_cost_wei + *MIN_PROFIT_THRESHOLD;
         
         let is_profitable = profit > required_profit;
         
         if is_profitable {
-            debug!("Trade is profitable: profit={} ETH, costs={} ETH, net={} ETH", 
-                   profit, total_costs, profit.saturating_sub(total_costs));
+            debug!("Trade is profitable: profit={} ETH, costs={} ETH, net={} ETH", profit, gas_cost_wei, profit.saturating_sub(gas_cost_wei));
         } else {
             debug!("Trade not profitable: profit={} ETH, required={} ETH", profit, required_profit);
         }
@@ -153,7 +265,9 @@ impl SwapCalculator {
         input_amount * *FLASH_LOAN_FEE_NUMERATOR / *FLASH_LOAN_FEE_DENOMINATOR
     } // kept private for internal use
     
-    /// Optimize the input amount using binary search to find the most profitable amount
+    /// Optimize the input amount using golden-section search to find the most profitable amount.
+    /// Profit is assumed to be unimodal on `[initial_amount, max_amount]`, each iteration reuses one of the previous points
+    /// and needs a single new evaluation.
     #[inline]
     pub fn optimize_input_amount<'a, DB: DatabaseRef<Error = ErrReport>, LDT: LoomDataTypes>(
         path: &'a mut SwapLine<LDT>,
@@ -161,92 +275,73 @@ impl SwapCalculator {
         env: Env,
         initial_amount: U256,
     ) -> Result<&'a mut SwapLine<LDT>, SwapError<LDT>> {
-        // This token is used in estimate_max_amount_from_liquidity
-        let _first_token = path.get_first_token().unwrap();
-        
         // Estimate the maximum amount based on pool liquidity
         let max_amount = Self::estimate_max_amount_from_liquidity(path);
-        
-        // Use binary search to find the optimal input amount
+
+        let profit_for = |amount: U256| -> U256 {
+            let mut path_clone = path.clone();
+            match path_clone.optimize_with_in_amount(state, env.clone(), amount) {
+                Ok(_) => path_clone.abs_profit_eth(),
+                Err(_) => U256::ZERO,
+            }
+        };
+
         let mut low = initial_amount;
         let mut high = max_amount;
         let mut best_amount = initial_amount;
         let mut best_profit = U256::ZERO;
-        
-        // Number of iterations for binary search
-        let iterations = 8;
-        
-        for _ in 0..iterations {
-            if high <= low {
-                break;
-            }
-            
-            // Try three points: low, mid, high
-            let mid = low + (high - low) / U256::from(2);
-            
-            // Calculate profit for each point
-            let mut path_low = path.clone();
-            let mut path_mid = path.clone();
-            let mut path_high = path.clone();
-            
-            let low_result = path_low.optimize_with_in_amount(state, env.clone(), low);
-            let mid_result = path_mid.optimize_with_in_amount(state, env.clone(), mid);
-            let high_result = path_high.optimize_with_in_amount(state, env.clone(), high);
-            
-            // Get profits
-            let profit_low = if low_result.is_ok() {
-                path_low.abs_profit_eth()
-            } else {
-                U256::ZERO
-            };
-            
-            let profit_mid = if mid_result.is_ok() {
-                path_mid.abs_profit_eth()
-            } else {
-                U256::ZERO
-            };
-            
-            let profit_high = if high_result.is_ok() {
-                path_high.abs_profit_eth()
-            } else {
-                U256::ZERO
-            };
-            
-            // Update best profit
-            if profit_low > best_profit {
-                best_profit = profit_low;
-                best_amount = low;
-            }
-            
-            if profit_mid > best_profit {
-                best_profit = profit_mid;
-                best_amount = mid;
+
+        if high > low {
+            let mut x1 = high - golden_section(high - low);
+            let mut x2 = low + golden_section(high - low);
+            let mut profit_1 = profit_for(x1);
+            let mut profit_2 = profit_for(x2);
+
+            for _ in 0..GOLDEN_SECTION_ITERATIONS {
+                if profit_1 > best_profit {
+                    best_profit = profit_1;
+                    best_amount = x1;
+                }
+                if profit_2 > best_profit {
+                    best_profit = profit_2;
+                    best_amount = x2;
+                }
+                if x2 <= x1 {
+                    break;
+                }
+
+                if profit_1 >= profit_2 {
+                    // Peak is in [low, x2]
+                    high = x2;
+                    x2 = x1;
+                    profit_2 = profit_1;
+                    x1 = high - golden_section(high - low);
+                    profit_1 = profit_for(x1);
+                } else {
+                    // Peak is in [x1, high]
+                    low = x1;
+                    x1 = x2;
+                    profit_1 = profit_2;
+                    x2 = low + golden_section(high - low);
+                    profit_2 = profit_for(x2);
+                }
             }
-            
-            if profit_high > best_profit {
-                best_profit = profit_high;
-                best_amount = high;
+
+            if profit_1 > best_profit {
+                best_profit = profit_1;
+                best_amount = x1;
             }
-            
-            // Narrow search range based on where the highest profit is
-            if profit_mid > profit_low && profit_mid > profit_high {
-                // Peak is in the middle, narrow to both sides
-                low = low + (mid - low) / U256::from(2);
-                high = mid + (high - mid) / U256::from(2);
-            } else if profit_low > profit_mid {
-                // Peak is toward the lower end
-                high = mid;
-            } else {
-                // Peak is toward the higher end
-                low = mid;
+            if profit_2 > best_profit {
+                best_profit = profit_2;
+                best_amount = x2;
             }
         }
-        
+
         // Use the best amount found
         debug!("Optimized input amount: {} with profit: {}", best_amount, best_profit);
         path.optimize_with_in_amount(state, env, best_amount)
     }
-    
+
     /// Estimate the maximum amount based on pool liquidity
     /// This ensures we don't try to use more capital than the pools can handle
     #[inline]
@@ -312,3 +407,53 @@ impl SwapCalculator {
         repayment_amount + min_profit_percentage
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::Address;
+    use loom_defi_address_book::TokenAddressEth;
+    use loom_evm_db::LoomDBType;
+    use loom_types_entities::{MockPool, SwapPath, Token};
+    use std::sync::Arc;
+
+    // WETH -> token -> WETH doubling the input, the mock pools can not flash swap so the multicaller borrows from Balancer
+    fn non_flash_swap_line() -> SwapLine {
+        let (weth, token) = (Token::new(TokenAddressEth::WETH), Token::new(Address::repeat_byte(1)));
+        weth.set_eth_price(Some(U256::from(10).pow(U256::from(18))));
+        let pools = vec![
+            PoolWrapper::new(Arc::new(MockPool::new(weth.get_address(), token.get_address(), Address::repeat_byte(10)).with_rate(2, 1))),
+            PoolWrapper::new(Arc::new(MockPool::new(token.get_address(), weth.get_address(), Address::repeat_byte(11)).with_rate(1, 1))),
+        ];
+        SwapPath::new(vec![weth.clone(), token, weth], pools).into()
+    }
+
+    #[test]
+    fn test_calldata_gas_cost_non_flash_swap() {
+        let mut swap_line = non_flash_swap_line();
+        assert!(!swap_line.pools().iter().any(|pool| pool.can_flash_swap()));
+
+        // Input amount is not set before the calculation
+        assert!(SwapCalculator::calldata_gas_cost(&swap_line).is_err());
+
+        SwapCalculator::calculate(&mut swap_line, &LoomDBType::default(), Env::default(), SwapCalculator::calldata_gas_cost).unwrap();
+        assert!(swap_line.amount_in.is_set());
+        assert!(SwapCalculator::calldata_gas_cost(&swap_line).unwrap() > 0);
+    }
+
+    #[test]
+    fn test_is_profitable_after_costs() {
+        let mut env = Env::default();
+        let costs = U256::from(20_000_000_000u64) * *ESTIMATED_GAS_COST + *MIN_PROFIT_THRESHOLD;
+
+        // 20 gwei gas price without a price in the env
+        assert!(SwapCalculator::is_profitable_after_costs(costs + U256::from(1), 0, &env));
+        assert!(!SwapCalculator::is_profitable_after_costs(costs, 0, &env));
+
+        // Calldata gas is paid on top of the execution gas
+        assert!(!SwapCalculator::is_profitable_after_costs(costs + U256::from(1), 1, &env));
+
+        env.tx.gas_price = U256::from(1_000_000_000u64);
+        assert!(SwapCalculator::is_profitable_after_costs(costs, 0, &env));
+    }
+}
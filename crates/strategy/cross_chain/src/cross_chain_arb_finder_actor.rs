@@ -0,0 +1,163 @@
+use std::collections::HashSet;
+
+use eyre::eyre;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, error, info, warn};
+
+use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, CancellationToken, SharedState, WorkerResult};
+use loom_core_blockchain::Blockchain;
+use loom_types_entities::Market;
+use loom_types_events::{HealthEvent, MarketEvents, MessageHealthEvent};
+
+use crate::{find_price_discrepancies, CrossChainToken};
+
+/// Compares token prices of both chains and alerts on discrepancies not alerted before. A discrepancy is alerted again
+/// once it closed and reopened.
+async fn check_price_discrepancies(
+    tokens: &[CrossChainToken],
+    chain_ids: (u64, u64),
+    markets: &(SharedState<Market>, SharedState<Market>),
+    min_delta_bps: u64,
+    alerted: &mut HashSet<String>,
+    health_monitor_channel_tx: &Broadcaster<MessageHealthEvent>,
+) {
+    let discrepancies = {
+        let market_a = markets.0.read().await;
+        let market_b = markets.1.read().await;
+        find_price_discrepancies(tokens, chain_ids, &market_a, &market_b, min_delta_bps)
+    };
+
+    let open: HashSet<String> = discrepancies.iter().map(|discrepancy| discrepancy.symbol.clone()).collect();
+    alerted.retain(|symbol| open.contains(symbol));
+
+    for discrepancy in discrepancies {
+        if !alerted.insert(discrepancy.symbol.clone()) {
+            continue;
+        }
+        warn!(
+            symbol = discrepancy.symbol,
+            chain_id = chain_ids.0,
+            other_chain_id = chain_ids.1,
+            price = discrepancy.price_a,
+            other_price = discrepancy.price_b,
+            delta_bps = discrepancy.delta_bps,
+            "Cross chain price discrepancy"
+        );
+        let health_event = HealthEvent::CrossChainPriceDiscrepancy {
+            symbol: discrepancy.symbol,
+            chain_id: chain_ids.0,
+            other_chain_id: chain_ids.1,
+            price: discrepancy.price_a,
+            other_price: discrepancy.price_b,
+            delta_bps: discrepancy.delta_bps,
+        };
+        if let Err(e) = health_monitor_channel_tx.send(MessageHealthEvent::new(health_event)) {
+            error!("health_monitor_channel_tx.send : {}", e);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn cross_chain_arb_finder_worker(
+    tokens: Vec<CrossChainToken>,
+    chain_ids: (u64, u64),
+    markets: (SharedState<Market>, SharedState<Market>),
+    min_delta_bps: u64,
+    market_events_rx: Broadcaster<MarketEvents>,
+    other_market_events_rx: Broadcaster<MarketEvents>,
+    health_monitor_channel_tx: Broadcaster<MessageHealthEvent>,
+    cancellation_token: CancellationToken,
+) -> WorkerResult {
+    subscribe!(market_events_rx);
+    subscribe!(other_market_events_rx);
+
+    info!(chain_id = chain_ids.0, other_chain_id = chain_ids.1, min_delta_bps, "Cross chain arb finder started");
+
+    let mut alerted: HashSet<String> = HashSet::new();
+
+    loop {
+        let market_event_msg: Result<MarketEvents, RecvError> = tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Cross chain arb finder cancelled".to_string());
+            }
+            msg = market_events_rx.recv() => msg,
+            msg = other_market_events_rx.recv() => msg,
+        };
+        match market_event_msg {
+            Ok(MarketEvents::BlockHeaderUpdate { .. }) => {
+                check_price_discrepancies(&tokens, chain_ids, &markets, min_delta_bps, &mut alerted, &health_monitor_channel_tx).await;
+            }
+            Ok(_) => {}
+            Err(RecvError::Closed) => {
+                error!("Market events channel closed");
+                break Err(eyre!("MARKET_EVENTS_RX_CLOSED"));
+            }
+            Err(RecvError::Lagged(lag)) => {
+                debug!("Market events channel lagged by {} messages", lag);
+            }
+        }
+    }
+}
+
+/// Watches the assets mapped by `tokens` on two chains and alerts through the health monitor channel of the first chain
+/// when their ETH prices differ by at least `min_delta_bps`. Alerting only, no swaps are composed.
+pub struct CrossChainArbFinderActor {
+    tokens: Vec<CrossChainToken>,
+    min_delta_bps: u64,
+    chain_ids: Option<(u64, u64)>,
+    markets: Option<(SharedState<Market>, SharedState<Market>)>,
+    market_events_rx: Option<Broadcaster<MarketEvents>>,
+    other_market_events_rx: Option<Broadcaster<MarketEvents>>,
+    health_monitor_channel_tx: Option<Broadcaster<MessageHealthEvent>>,
+    cancellation_token: CancellationToken,
+}
+
+impl CrossChainArbFinderActor {
+    pub fn new(tokens: Vec<CrossChainToken>, min_delta_bps: u64) -> Self {
+        Self {
+            tokens,
+            min_delta_bps,
+            chain_ids: None,
+            markets: None,
+            market_events_rx: None,
+            other_market_events_rx: None,
+            health_monitor_channel_tx: None,
+            cancellation_token: CancellationToken::new(),
+        }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
+    pub fn on_bc(self, bc: &Blockchain, other_bc: &Blockchain) -> Self {
+        Self {
+            chain_ids: Some((bc.chain_id(), other_bc.chain_id())),
+            markets: Some((bc.market(), other_bc.market())),
+            market_events_rx: Some(bc.market_events_channel()),
+            other_market_events_rx: Some(other_bc.market_events_channel()),
+            health_monitor_channel_tx: Some(bc.health_monitor_channel()),
+            ..self
+        }
+    }
+}
+
+impl Actor for CrossChainArbFinderActor {
+    fn start(&self) -> ActorResult {
+        let task = tokio::task::spawn(cross_chain_arb_finder_worker(
+            self.tokens.clone(),
+            self.chain_ids.unwrap(),
+            self.markets.clone().unwrap(),
+            self.min_delta_bps,
+            self.market_events_rx.clone().unwrap(),
+            self.other_market_events_rx.clone().unwrap(),
+            self.health_monitor_channel_tx.clone().unwrap(),
+            self.cancellation_token.clone(),
+        ));
+        Ok(vec![task])
+    }
+
+    fn name(&self) -> &'static str {
+        "CrossChainArbFinderActor"
+    }
+}
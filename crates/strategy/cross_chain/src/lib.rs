@@ -0,0 +1,5 @@
+mod cross_chain_arb_finder_actor;
+mod price_discrepancy;
+
+pub use cross_chain_arb_finder_actor::CrossChainArbFinderActor;
+pub use price_discrepancy::{find_price_discrepancies, CrossChainToken, PriceDiscrepancy};
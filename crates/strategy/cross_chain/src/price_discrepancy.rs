@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use alloy_primitives::Address;
+use loom_types_entities::Market;
+
+/// Same asset priced differently on two chains. Prices are in tokens per ETH of the chain.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PriceDiscrepancy {
+    pub symbol: String,
+    pub price_a: f64,
+    pub price_b: f64,
+    pub delta_bps: u64,
+}
+
+/// Addresses of the same asset on several chains. Symbols differ between bridged and native deployments, so assets are
+/// matched by this mapping only.
+#[derive(Clone, Debug, Default)]
+pub struct CrossChainToken {
+    symbol: String,
+    addresses: HashMap<u64, Address>,
+}
+
+impl CrossChainToken {
+    pub fn new(symbol: impl Into<String>) -> Self {
+        Self { symbol: symbol.into(), addresses: HashMap::new() }
+    }
+
+    pub fn with_address(mut self, chain_id: u64, address: Address) -> Self {
+        self.addresses.insert(chain_id, address);
+        self
+    }
+
+    pub fn get_symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn get_address(&self, chain_id: u64) -> Option<Address> {
+        self.addresses.get(&chain_id).copied()
+    }
+}
+
+fn eth_price(market: &Market, address: Option<Address>) -> Option<f64> {
+    let token = market.get_token(&address?)?;
+    Some(token.to_float(token.get_eth_price()?))
+}
+
+/// Mapped tokens priced in both markets with ETH prices differing by at least `min_delta_bps`, sorted by symbol
+pub fn find_price_discrepancies(
+    tokens: &[CrossChainToken],
+    chain_ids: (u64, u64),
+    market_a: &Market,
+    market_b: &Market,
+    min_delta_bps: u64,
+) -> Vec<PriceDiscrepancy> {
+    let mut ret: Vec<PriceDiscrepancy> = tokens
+        .iter()
+        .filter_map(|token| {
+            let price_a = eth_price(market_a, token.get_address(chain_ids.0))?;
+            let price_b = eth_price(market_b, token.get_address(chain_ids.1))?;
+            let min_price = price_a.min(price_b);
+            if min_price <= 0.0 {
+                return None;
+            }
+            let delta_bps = ((price_a - price_b).abs() / min_price * 10000.0).round() as u64;
+            (delta_bps >= min_delta_bps).then(|| PriceDiscrepancy { symbol: token.symbol.clone(), price_a, price_b, delta_bps })
+        })
+        .collect();
+    ret.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+    use loom_types_entities::Token;
+
+    fn token(address: u8, symbol: Option<&str>, decimals: u8, eth_price: Option<u64>) -> Token {
+        let token = Token::new_with_data(Address::repeat_byte(address), symbol.map(String::from), None, Some(decimals), false, false);
+        token.set_eth_price(eth_price.map(|price| U256::from(price) * U256::from(10).pow(U256::from(decimals))));
+        token
+    }
+
+    #[test]
+    fn test_find_price_discrepancies() {
+        let mut market_a = Market::default();
+        market_a.add_token(token(1, Some("USDC"), 6, Some(2500)));
+        market_a.add_token(token(2, Some("DAI"), 18, Some(2500)));
+        market_a.add_token(token(3, Some("LINK"), 18, Some(150)));
+        market_a.add_token(token(4, Some("WBTC"), 8, Some(1)));
+
+        // Bridged and native USDC share the symbol, only the mapped native one is compared
+        let mut market_b = Market::default();
+        market_b.add_token(token(11, Some("USDC"), 6, Some(2550)));
+        market_b.add_token(token(21, Some("USDC"), 6, Some(3000)));
+        market_b.add_token(token(12, Some("DAI"), 18, Some(2501)));
+        market_b.add_token(token(13, Some("LINK"), 18, None));
+        market_b.add_token(token(14, Some("WBTC"), 8, Some(2)));
+
+        let tokens = vec![
+            CrossChainToken::new("USDC").with_address(1, Address::repeat_byte(1)).with_address(8453, Address::repeat_byte(11)),
+            CrossChainToken::new("DAI").with_address(1, Address::repeat_byte(2)).with_address(8453, Address::repeat_byte(12)),
+            CrossChainToken::new("LINK").with_address(1, Address::repeat_byte(3)).with_address(8453, Address::repeat_byte(13)),
+            CrossChainToken::new("WBTC").with_address(1, Address::repeat_byte(4)),
+        ];
+
+        let discrepancies = find_price_discrepancies(&tokens, (1, 8453), &market_a, &market_b, 50);
+        assert_eq!(discrepancies, vec![PriceDiscrepancy { symbol: "USDC".to_string(), price_a: 2500.0, price_b: 2550.0, delta_bps: 200 }]);
+    }
+}
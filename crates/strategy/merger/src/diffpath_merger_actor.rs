@@ -1,4 +1,4 @@
-use alloy_primitives::{Address, U256};
+use alloy_primitives::{Address, I256, U256};
 use eyre::{eyre, ErrReport, Result};
 use revm::primitives::Env;
 use revm::DatabaseRef;
@@ -9,16 +9,86 @@ use tokio::sync::broadcast::error::RecvError;
 
 use tracing::{debug, error, info};
 use tracing::Level;
-use loom_core_actors::{Broadcaster, SharedState, WorkerResult, Actor, ActorResult, Consumer, Producer, Accessor};
+use loom_core_actors::{Broadcaster, SharedState, WorkerResult, Actor, ActorResult, CancellationToken, Consumer, Producer, Accessor};
 
 use loom_core_blockchain::{Blockchain, Strategy};
-use loom_types_entities::{LatestBlock, Swap, SwapStep};
+use loom_types_blockchain::LoomDataTypesEthereum;
+use loom_types_entities::{LatestBlock, Swap, SwapAmountType, SwapLine, SwapStep};
 use loom_types_events::{MarketEvents, MessageSwapCompose, SwapComposeData, SwapComposeMessage};
 
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 const COINBASE: Address = Address::new([0x1f, 0x90, 0x90, 0xaa, 0xE2, 0x8b, 0x8a, 0x3d, 0xCe, 0xaD, 0xf2, 0x81, 0xB0, 0xF1, 0x28, 0x28, 0xe6, 0x76, 0xc3, 0x26]);
 
+/// Net token flow of a swap line, the input amount is spent and the output amount is received.
+/// Intermediate tokens of the path net to zero and are omitted. Returns None if the amounts are not set.
+fn token_net_flows(swap_line: &SwapLine) -> Option<BTreeMap<Address, I256>> {
+    let (SwapAmountType::Set(amount_in), SwapAmountType::Set(amount_out)) = (swap_line.amount_in, swap_line.amount_out) else {
+        return None;
+    };
+    let token_in = swap_line.get_first_token()?.get_address();
+    let token_out = swap_line.get_last_token()?.get_address();
+
+    let mut flows: BTreeMap<Address, I256> = BTreeMap::new();
+    *flows.entry(token_in).or_default() -= I256::try_from(amount_in).ok()?;
+    *flows.entry(token_out).or_default() += I256::try_from(amount_out).ok()?;
+    flows.retain(|_, flow| !flow.is_zero());
+    Some(flows)
+}
+
+/// Two flows cancel each other if they touch the same tokens and every token spent by one line is received by the other
+fn is_complementary(flows_0: &BTreeMap<Address, I256>, flows_1: &BTreeMap<Address, I256>) -> bool {
+    flows_0.len() > 1
+        && flows_0.len() == flows_1.len()
+        && flows_0.iter().all(|(token, flow_0)| flows_1.get(token).is_some_and(|flow_1| flow_0.is_negative() != flow_1.is_negative()))
+}
+
+/// Two lines share state if they swap through the same pool contract, e.g. the same pool or pools of one UniswapV4 pool manager.
+/// The amounts of the second line were then computed on a state the first line has already changed.
+fn shares_pool_state(swap_line_0: &SwapLine, swap_line_1: &SwapLine) -> bool {
+    swap_line_0.pools().iter().any(|pool_0| swap_line_1.pools().iter().any(|pool_1| pool_0.get_address() == pool_1.get_address()))
+}
+
+/// Simulates both lines with their input amounts on `state`. The merged swap is kept only if the simulated outputs cover
+/// every token it spends and some token is left as profit.
+fn is_merged_swap_profitable<DB: DatabaseRef<Error = ErrReport>>(
+    state: &DB,
+    env: Env,
+    swap_line_0: &SwapLine,
+    swap_line_1: &SwapLine,
+) -> Result<bool> {
+    let mut flows: BTreeMap<Address, I256> = BTreeMap::new();
+    for swap_line in [swap_line_0, swap_line_1] {
+        let (Some(token_in), Some(token_out)) = (swap_line.get_first_token(), swap_line.get_last_token()) else {
+            return Err(eyre!("EMPTY_SWAP_LINE"));
+        };
+        let amount_in = swap_line.amount_in.unwrap();
+        let (amount_out, _, _) = swap_line.calculate_with_in_amount(state, env.clone(), amount_in).map_err(|e| eyre!(e.msg))?;
+        *flows.entry(token_in.get_address()).or_default() -= I256::try_from(amount_in)?;
+        *flows.entry(token_out.get_address()).or_default() += I256::try_from(amount_out)?;
+    }
+    Ok(flows.values().all(|flow| !flow.is_negative()) && flows.values().any(|flow| flow.is_positive()))
+}
+
+/// Combine two complementary legs of an arb cycle, e.g. A->B via pool1 and B->A via pool2, into swap steps
+fn merge_complementary_swap_lines(
+    swap_line_0: &SwapLine,
+    swap_line_1: &SwapLine,
+    multicaller: Address,
+) -> Option<(SwapStep<LoomDataTypesEthereum>, SwapStep<LoomDataTypesEthereum>)> {
+    let flows_0 = token_net_flows(swap_line_0)?;
+    let flows_1 = token_net_flows(swap_line_1)?;
+    if !is_complementary(&flows_0, &flows_1) || shares_pool_state(swap_line_0, swap_line_1) {
+        return None;
+    }
+
+    let mut swap_step_0 = SwapStep::new(multicaller);
+    swap_step_0.add(swap_line_0.clone());
+    let mut swap_step_1 = SwapStep::new(multicaller);
+    swap_step_1.add(swap_line_1.clone());
+    Some((swap_step_0, swap_step_1))
+}
+
 async fn arb_swap_steps_optimizer_task<DB: DatabaseRef + Send + Sync + Clone>(
     compose_channel_tx: Broadcaster<MessageSwapCompose<DB>>,
     state_db: &(dyn DatabaseRef<Error = ErrReport> + Send + Sync + 'static),
@@ -64,6 +134,7 @@ async fn diff_path_merger_worker<DB: DatabaseRef<Error = ErrReport> + Send + Syn
     market_events_rx: Broadcaster<MarketEvents>,
     compose_channel_rx: Broadcaster<MessageSwapCompose<DB>>,
     compose_channel_tx: Broadcaster<MessageSwapCompose<DB>>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult {
     let mut market_events_rx_receiver = market_events_rx.subscribe();
     let mut compose_channel_rx_receiver = compose_channel_rx.subscribe();
@@ -71,6 +142,9 @@ async fn diff_path_merger_worker<DB: DatabaseRef<Error = ErrReport> + Send + Syn
 
     loop {
         tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Diff path merger cancelled".to_string());
+            }
             msg = market_events_rx_receiver.recv() => {
                 let msg : Result<MarketEvents, RecvError> = msg;
                 match msg {
@@ -112,6 +186,16 @@ async fn diff_path_merger_worker<DB: DatabaseRef<Error = ErrReport> + Send + Syn
                             ("swap", &format!("{:?}", compose_data.swap)),
                         ]);
 
+                        let latest_block_guard = latest_block.read().await;
+                        let Some(block_header) = latest_block_guard.block_header.clone() else {
+                            continue;
+                        };
+                        drop(latest_block_guard);
+
+                        let mut evm_env = Env::default();
+                        evm_env.block.number = U256::from(block_header.number + 1);
+                        evm_env.block.timestamp = U256::from(block_header.timestamp + 12);
+
                         for req in ready_requests.iter() {
 
                             let req_swap = match &req.swap {
@@ -123,24 +207,48 @@ async fn diff_path_merger_worker<DB: DatabaseRef<Error = ErrReport> + Send + Syn
                                 continue
                             };
 
+                            if let Some((sp0, sp1)) = merge_complementary_swap_lines(req_swap, swap_path, COINBASE) {
+                                let Some(poststate) = &compose_data.poststate else {
+                                    continue;
+                                };
+                                match is_merged_swap_profitable(poststate, evm_env.clone(), req_swap, swap_path) {
+                                    Ok(true) => {}
+                                    Ok(false) => {
+                                        json_log(Level::DEBUG, "Merged swap lines not profitable", &[]);
+                                        continue;
+                                    }
+                                    Err(e) => {
+                                        json_log(Level::DEBUG, "Merged swap lines simulation error", &[("error", &format!("{}", e))]);
+                                        continue;
+                                    }
+                                }
+                                json_log(Level::DEBUG, "Complementary swap lines merged", &[
+                                    ("sp0", &format!("{}", sp0)),
+                                    ("sp1", &format!("{}", sp1)),
+                                ]);
+                                let request = MessageSwapCompose::prepare(SwapComposeData {
+                                    origin: Some("diffpath_merger".to_string()),
+                                    tips_pct: None,
+                                    swap: Swap::BackrunSwapSteps((sp0, sp1)),
+                                    ..compose_data.clone()
+                                });
+                                if let Err(e) = compose_channel_tx.send(request) {
+                                    json_log(Level::ERROR, "Compose channel send error", &[("error", &format!("{:?}", e))]);
+                                }
+                                break;
+                            }
+
                         match SwapStep::merge_swap_paths( req_swap.clone(), swap_path.clone(), COINBASE ){
                             Ok((sp0, sp1)) => {
-                                let latest_block_guard = latest_block.read().await;
-                                let block_header = latest_block_guard.block_header.clone().unwrap();
-                                drop(latest_block_guard);
-
                                 let request = SwapComposeData{
                                     swap : Swap::BackrunSwapSteps((sp0,sp1)),
                                     ..compose_data.clone()
                                 };
 
-                                let mut evm_env = Env::default();
-                                evm_env.block.number = U256::from(block_header.number + 1);
-                                evm_env.block.timestamp = U256::from(block_header.timestamp + 12);
-
                                 if let Some(db) = compose_data.poststate.clone() {
                                     let db_clone = db.clone();
                                     let compose_channel_clone = compose_channel_tx.clone();
+                                    let evm_env = evm_env.clone();
                                     tokio::task::spawn( async move {
                                             arb_swap_steps_optimizer_task(
                                             compose_channel_clone,
@@ -184,6 +292,7 @@ pub struct DiffPathMergerActor<DB: Send + Sync + Clone + 'static> {
 
     #[producer]
     compose_channel_tx: Option<Broadcaster<MessageSwapCompose<DB>>>,
+    cancellation_token: CancellationToken,
 }
 
 impl<DB> DiffPathMergerActor<DB>
@@ -196,8 +305,14 @@ where
             market_events: None,
             compose_channel_rx: None,
             compose_channel_tx: None,
+            cancellation_token: CancellationToken::new(),
         }
     }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
     pub fn on_bc(self, bc: &Blockchain, strategy: &Strategy<DB>) -> Self {
         Self {
             latest_block: Some(bc.latest_block()),
@@ -228,6 +343,7 @@ where
             market_events,
             compose_channel_rx,
             compose_channel_tx,
+            self.cancellation_token.clone(),
         ));
         Ok(vec![task])
     }
@@ -236,4 +352,65 @@ where
         "DiffPathMergerActor"
     }
 }
-//</create_file>
\ No newline at end of file
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use loom_types_entities::{MockPool, SwapPath, Token};
+
+    fn swap_line(token_from: Address, token_to: Address, pool_address: Address, amount_in: u64, amount_out: u64) -> SwapLine {
+        SwapLine {
+            path: SwapPath::new(vec![Token::new(token_from), Token::new(token_to)], vec![MockPool::new(token_from, token_to, pool_address)]),
+            amount_in: SwapAmountType::Set(U256::from(amount_in)),
+            amount_out: SwapAmountType::Set(U256::from(amount_out)),
+            ..SwapLine::default()
+        }
+    }
+
+    #[test]
+    fn test_token_net_flows() {
+        let (token_a, token_b) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let flows = token_net_flows(&swap_line(token_a, token_b, Address::repeat_byte(10), 100, 250)).unwrap();
+        assert_eq!(flows.get(&token_a), Some(&I256::try_from(-100).unwrap()));
+        assert_eq!(flows.get(&token_b), Some(&I256::try_from(250).unwrap()));
+
+        let mut not_set = swap_line(token_a, token_b, Address::repeat_byte(10), 100, 250);
+        not_set.amount_out = SwapAmountType::NotSet;
+        assert!(token_net_flows(&not_set).is_none());
+    }
+
+    #[test]
+    fn test_merge_complementary_swap_lines() {
+        let (token_a, token_b) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let a_to_b = swap_line(token_a, token_b, Address::repeat_byte(10), 100, 250);
+        let b_to_a = swap_line(token_b, token_a, Address::repeat_byte(11), 250, 110);
+
+        let (sp0, sp1) = merge_complementary_swap_lines(&a_to_b, &b_to_a, COINBASE).unwrap();
+        assert_eq!(sp0.first_token().unwrap().get_address(), token_a);
+        assert_eq!(sp0.last_token().unwrap().get_address(), token_b);
+        assert_eq!(sp1.first_token().unwrap().get_address(), token_b);
+        assert_eq!(sp1.last_token().unwrap().get_address(), token_a);
+    }
+
+    #[test]
+    fn test_reject_non_complementary_swap_lines() {
+        let (token_a, token_b, token_c) = (Address::repeat_byte(1), Address::repeat_byte(2), Address::repeat_byte(3));
+        let a_to_b = swap_line(token_a, token_b, Address::repeat_byte(10), 100, 250);
+
+        // same direction
+        let a_to_b_other_pool = swap_line(token_a, token_b, Address::repeat_byte(11), 100, 240);
+        assert!(merge_complementary_swap_lines(&a_to_b, &a_to_b_other_pool, COINBASE).is_none());
+
+        // different tokens
+        let b_to_c = swap_line(token_b, token_c, Address::repeat_byte(12), 250, 50);
+        assert!(merge_complementary_swap_lines(&a_to_b, &b_to_c, COINBASE).is_none());
+
+        // same pool in both directions
+        let b_to_a_same_pool = swap_line(token_b, token_a, Address::repeat_byte(10), 250, 110);
+        assert!(merge_complementary_swap_lines(&a_to_b, &b_to_a_same_pool, COINBASE).is_none());
+
+        // cycles only have the profit token flow
+        let a_to_a = swap_line(token_a, token_a, Address::repeat_byte(13), 100, 110);
+        assert!(merge_complementary_swap_lines(&a_to_a, &a_to_a, COINBASE).is_none());
+    }
+}
\ No newline at end of file
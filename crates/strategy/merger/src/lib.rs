@@ -1,8 +1,10 @@
 mod diffpath_merger_actor;
+mod merger_config;
 mod samepath_merger_actor;
 mod swappath_merger_actor;
 mod utils;
 
 pub use diffpath_merger_actor::DiffPathMergerActor;
-pub use samepath_merger_actor::SamePathMergerActor;
-pub use swappath_merger_actor::ArbSwapPathMergerActor;
+pub use merger_config::MergerConfig;
+pub use samepath_merger_actor::{SamePathMergerActor, DEFAULT_PROFIT_IMPROVEMENT_THRESHOLD_BPS};
+pub use swappath_merger_actor::{ArbSwapPathMergerActor, DEFAULT_MAX_READY_REQUESTS};
@@ -0,0 +1,19 @@
+use serde::Deserialize;
+
+use crate::samepath_merger_actor::DEFAULT_PROFIT_IMPROVEMENT_THRESHOLD_BPS;
+use crate::swappath_merger_actor::DEFAULT_MAX_READY_REQUESTS;
+
+#[derive(Clone, Deserialize, Debug)]
+#[serde(default)]
+pub struct MergerConfig {
+    /// Same path candidates replace the stored one only if their profit is higher by at least this number of basis points
+    pub profit_improvement_threshold_bps: u16,
+    /// Ready requests kept by the swap path merger, the least profitable ones are dropped
+    pub max_ready_requests: usize,
+}
+
+impl Default for MergerConfig {
+    fn default() -> Self {
+        Self { profit_improvement_threshold_bps: DEFAULT_PROFIT_IMPROVEMENT_THRESHOLD_BPS, max_ready_requests: DEFAULT_MAX_READY_REQUESTS }
+    }
+}
@@ -21,7 +21,7 @@ use tracing::{debug, info, trace};
 // use crate::json_logger::json_log; // removed duplicate/incorrect import
 use tracing::Level;
 
-use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, SharedState, WorkerResult, Consumer, Producer, Accessor};
+use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, CancellationToken, SharedState, WorkerResult, Consumer, Producer, Accessor};
 use loom_core_blockchain::{Blockchain, BlockchainState, Strategy};
 use loom_evm_db::DatabaseHelpers;
 use loom_evm_utils::evm::evm_transact;
@@ -34,14 +34,52 @@ use loom_types_entities::SwapStep;
 
 const COINBASE: Address = Address::new([0x1f, 0x90, 0x90, 0xaa, 0xE2, 0x8b, 0x8a, 0x3d, 0xCe, 0xaD, 0xf2, 0x81, 0xB0, 0xF1, 0x28, 0x28, 0xe6, 0x76, 0xc3, 0x26]);
 
+pub const DEFAULT_PROFIT_IMPROVEMENT_THRESHOLD_BPS: u16 = 50;
+
+fn canonical_path_key<DB: Clone + 'static>(request: &SwapComposeData<DB>) -> Option<u64> {
+    if let Swap::BackrunSwapLine(swap_line) = &request.swap {
+        Some(swap_line.path.canonical_path_key())
+    } else {
+        None
+    }
+}
+
+/// Returns true if `new_profit` is higher than `old_profit` by at least `threshold_bps` basis points.
+fn exceeds_profit_improvement_threshold(new_profit: U256, old_profit: U256, threshold_bps: u16) -> bool {
+    new_profit > old_profit
+        && new_profit.saturating_mul(U256::from(10000)) >= old_profit.saturating_mul(U256::from(10000 + threshold_bps as u64))
+}
+
+/// Keep only the most profitable request per canonical path for the stuffing transaction.
+/// Returns false if a request for the same path is already stored and the new one does not improve its profit
+/// by at least `threshold_bps` basis points.
+fn insert_best_request<DB: Clone + 'static>(
+    requests: &mut Vec<SwapComposeData<DB>>,
+    request: &SwapComposeData<DB>,
+    threshold_bps: u16,
+) -> bool {
+    let path_key = canonical_path_key(request);
+    match requests.iter_mut().find(|stored| path_key.is_some() && canonical_path_key(stored) == path_key) {
+        Some(stored) => {
+            if exceeds_profit_improvement_threshold(request.swap.abs_profit_eth(), stored.swap.abs_profit_eth(), threshold_bps) {
+                *stored = request.clone();
+                true
+            } else {
+                false
+            }
+        }
+        None => {
+            requests.push(request.clone());
+            true
+        }
+    }
+}
 
 fn get_merge_list<'a, DB: Clone + 'static>(
     request: &SwapComposeData<DB>,
     swap_paths: &'a HashMap<TxHash, Vec<SwapComposeData<DB>>>,
 ) -> Vec<&'a SwapComposeData<DB>> {
-    let swap_line = if let Swap::BackrunSwapLine(swap_line) = &request.swap {
-        swap_line
-    } else {
+    let Some(path_key) = canonical_path_key(request) else {
         return Vec::new();
     };
 
@@ -51,7 +89,7 @@ fn get_merge_list<'a, DB: Clone + 'static>(
         .iter()
         .filter_map(|(k, v)| {
             if *k != swap_stuffing_hash {
-                v.iter().find(|a| if let Swap::BackrunSwapLine(a_line) = &a.swap { a_line.path == swap_line.path } else { false })
+                v.iter().find(|a| canonical_path_key(a) == Some(path_key))
             } else {
                 None
             }
@@ -237,6 +275,7 @@ where
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn same_path_merger_worker<
     N: Network,
     P: Provider<N> + DebugProviderExt<N> + Send + Sync + Clone + 'static,
@@ -248,6 +287,8 @@ async fn same_path_merger_worker<
     market_events_rx: Broadcaster<MarketEvents>,
     compose_channel_rx: Broadcaster<MessageSwapCompose<DB>>,
     compose_channel_tx: Broadcaster<MessageSwapCompose<DB>>,
+    profit_improvement_threshold_bps: u16,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult {
     let mut market_events_rx_receiver = market_events_rx.subscribe();
     let mut compose_channel_rx_receiver = compose_channel_rx.subscribe();
@@ -263,6 +304,9 @@ async fn same_path_merger_worker<
 
     loop {
         tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Same path merger cancelled".to_string());
+            }
             msg = market_events_rx_receiver.recv() => {
                 if let Ok(msg) = msg {
                     let market_event_msg : MarketEvents = msg;
@@ -306,6 +350,13 @@ async fn same_path_merger_worker<
                                 if let Swap::BackrunSwapLine( _swap_line ) = &sign_request.swap {
                                     let stuffing_tx_hash = sign_request.first_stuffing_hash();
 
+                                    // Same path with similar or higher profit was already merged for this transaction
+                                    let requests = swap_paths.entry(stuffing_tx_hash).or_default();
+                                    if !insert_best_request(requests, sign_request, profit_improvement_threshold_bps) {
+                                        trace!("Skipping duplicate path for {stuffing_tx_hash:?}");
+                                        continue;
+                                    }
+
                                     let requests_vec = get_merge_list(sign_request, &swap_paths);
                                     if !requests_vec.is_empty() {
 
@@ -339,9 +390,6 @@ async fn same_path_merger_worker<
                                         );
                                     }
 
-                                    let e = swap_paths.entry(stuffing_tx_hash).or_default();
-                                    e.push( sign_request.clone() );
-
                                 }
                             }
                         }
@@ -368,6 +416,8 @@ pub struct SamePathMergerActor<P, N, DB: Send + Sync + Clone + 'static> {
     compose_channel_rx: Option<Broadcaster<MessageSwapCompose<DB>>>,
     #[producer]
     compose_channel_tx: Option<Broadcaster<MessageSwapCompose<DB>>>,
+    profit_improvement_threshold_bps: u16,
+    cancellation_token: CancellationToken,
     _n: PhantomData<N>,
 }
 
@@ -385,10 +435,21 @@ where
             market_events: None,
             compose_channel_rx: None,
             compose_channel_tx: None,
+            profit_improvement_threshold_bps: DEFAULT_PROFIT_IMPROVEMENT_THRESHOLD_BPS,
+            cancellation_token: CancellationToken::new(),
             _n: PhantomData,
         }
     }
 
+    /// Replace a stored candidate for the same path only if the new one is more profitable by at least `threshold_bps` basis points
+    pub fn with_profit_improvement_threshold(self, threshold_bps: u16) -> Self {
+        Self { profit_improvement_threshold_bps: threshold_bps, ..self }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
     pub fn on_bc(self, bc: &Blockchain, state: &BlockchainState<DB>, strategy: &Strategy<DB>) -> Self {
         Self {
             market_state: Some(state.market_state_commit()),
@@ -426,6 +487,8 @@ where
             market_events,
             compose_channel_rx,
             compose_channel_tx,
+            self.profit_improvement_threshold_bps,
+            self.cancellation_token.clone(),
         ));
         Ok(vec![task])
     }
@@ -434,3 +497,18 @@ where
         "SamePathMergerActor"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exceeds_profit_improvement_threshold() {
+        let old_profit = U256::from(10000);
+        assert!(!exceeds_profit_improvement_threshold(U256::from(10049), old_profit, 50));
+        assert!(exceeds_profit_improvement_threshold(U256::from(10050), old_profit, 50));
+        assert!(!exceeds_profit_improvement_threshold(old_profit, old_profit, 0));
+        assert!(exceeds_profit_improvement_threshold(U256::from(10001), old_profit, 0));
+        assert!(exceeds_profit_improvement_threshold(U256::from(1), U256::ZERO, 50));
+    }
+}
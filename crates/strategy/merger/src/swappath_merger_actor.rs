@@ -1,18 +1,42 @@
+use std::time::Duration;
+
 use alloy_primitives::{Address, U256};
 use eyre::{eyre, ErrReport, Result};
+use influxdb::{Timestamp, WriteQuery};
 use revm::primitives::Env;
 use revm::DatabaseRef;
 use tokio::sync::broadcast::error::RecvError;
+use tokio::time::Instant;
 use tracing::{debug, error, info};
 use super::utils::json_log;
 use loom_core_actors_macros::{Consumer, Producer, Accessor};
 use tracing::Level;
 
-use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, SharedState, WorkerResult, Consumer, Producer, Accessor};
+use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, CancellationToken, SharedState, WorkerResult, Consumer, Producer, Accessor};
 use loom_core_blockchain::{Blockchain, Strategy};
 use loom_types_entities::{LatestBlock, Swap, SwapStep};
 use loom_types_events::{MarketEvents, MessageSwapCompose, SwapComposeData, SwapComposeMessage};
 
+/// Ready requests older than this are not merged if the next block header update is delayed
+pub const DEFAULT_READY_REQUEST_MAX_AGE_MS: u64 = 12000;
+
+/// Ready requests kept for merging, each of them holds a clone of the post state DB
+pub const DEFAULT_MAX_READY_REQUESTS: usize = 50;
+
+/// Remove requests stored before `now - max_age`, returns the number of removed requests
+fn discard_stale_requests<T>(ready_requests: &mut Vec<(Instant, T)>, now: Instant, max_age: Duration) -> usize {
+    let len = ready_requests.len();
+    ready_requests.retain(|(received_at, _)| now.saturating_duration_since(*received_at) <= max_age);
+    len - ready_requests.len()
+}
+
+/// Keep the first `max_ready_requests` requests of the list sorted by profit, returns the number of dropped requests
+fn truncate_ready_requests<T>(ready_requests: &mut Vec<T>, max_ready_requests: usize) -> usize {
+    let len = ready_requests.len();
+    ready_requests.truncate(max_ready_requests);
+    len - ready_requests.len()
+}
+
 async fn arb_swap_steps_optimizer_task<DB: DatabaseRef + Send + Sync + Clone>(
     compose_channel_tx: Broadcaster<MessageSwapCompose<DB>>,
     state_db: &(dyn DatabaseRef<Error = ErrReport> + Send + Sync + 'static),
@@ -53,19 +77,28 @@ async fn arb_swap_steps_optimizer_task<DB: DatabaseRef + Send + Sync + Clone>(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn arb_swap_path_merger_worker<DB: DatabaseRef<Error = ErrReport> + Send + Sync + Clone + 'static>(
     multicaller_address: Address,
     latest_block: SharedState<LatestBlock>,
     market_events_rx: Broadcaster<MarketEvents>,
     compose_channel_rx: Broadcaster<MessageSwapCompose<DB>>,
     compose_channel_tx: Broadcaster<MessageSwapCompose<DB>>,
+    influxdb_write_channel_tx: Option<Broadcaster<WriteQuery>>,
+    max_age_ms: u64,
+    max_ready_requests: usize,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult {
     let mut market_events_rx_receiver = market_events_rx.subscribe();
     let mut compose_channel_rx_receiver = compose_channel_rx.subscribe();
-    let mut ready_requests: Vec<SwapComposeData<DB>> = Vec::new();
+    let mut ready_requests: Vec<(Instant, SwapComposeData<DB>)> = Vec::new();
+    let max_age = Duration::from_millis(max_age_ms);
 
     loop {
         tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Swap path merger cancelled".to_string());
+            }
             msg = market_events_rx_receiver.recv() => {
                 let msg : Result<MarketEvents, RecvError> = msg;
                 match msg {
@@ -107,7 +140,21 @@ async fn arb_swap_path_merger_worker<DB: DatabaseRef<Error = ErrReport> + Send +
                             ("swap", &format!("{:?}", compose_data.swap)),
                         ]);
 
-                        for req in ready_requests.iter() {
+                        let stale_requests_discarded = discard_stale_requests(&mut ready_requests, Instant::now(), max_age);
+                        if stale_requests_discarded > 0 {
+                            json_log(Level::DEBUG, "Stale ready requests discarded", &[
+                                ("count", &format!("{}", stale_requests_discarded)),
+                            ]);
+                            if let Some(influxdb_write_channel_tx) = &influxdb_write_channel_tx {
+                                let write_query = WriteQuery::new(Timestamp::from(chrono::Utc::now()), "swap_path_merger")
+                                    .add_field("stale_requests_discarded", stale_requests_discarded as u64);
+                                if let Err(e) = influxdb_write_channel_tx.send(write_query) {
+                                    error!("Failed to send stale_requests_discarded to influxdb: {:?}", e);
+                                }
+                            }
+                        }
+
+                        for (_, req) in ready_requests.iter() {
 
                             let req_swap = match &req.swap {
                                 Swap::BackrunSwapLine(path)=>path,
@@ -155,8 +202,22 @@ async fn arb_swap_path_merger_worker<DB: DatabaseRef<Error = ErrReport> + Send +
                                 }
                             }
                         }
-                        ready_requests.push(compose_data.clone());
-                        ready_requests.sort_by(|r0,r1| r1.swap.abs_profit().cmp(&r0.swap.abs_profit())  )
+                        ready_requests.push((Instant::now(), compose_data.clone()));
+                        ready_requests.sort_by(|(_, r0),(_, r1)| r1.swap.abs_profit().cmp(&r0.swap.abs_profit())  );
+
+                        let requests_dropped_capacity = truncate_ready_requests(&mut ready_requests, max_ready_requests);
+                        if requests_dropped_capacity > 0 {
+                            json_log(Level::DEBUG, "Least profitable ready requests dropped", &[
+                                ("count", &format!("{}", requests_dropped_capacity)),
+                            ]);
+                            if let Some(influxdb_write_channel_tx) = &influxdb_write_channel_tx {
+                                let write_query = WriteQuery::new(Timestamp::from(chrono::Utc::now()), "swap_path_merger")
+                                    .add_field("requests_dropped_capacity", requests_dropped_capacity as u64);
+                                if let Err(e) = influxdb_write_channel_tx.send(write_query) {
+                                    error!("Failed to send requests_dropped_capacity to influxdb: {:?}", e);
+                                }
+                            }
+                        }
 
                     }
                     Err(e)=>{
@@ -179,6 +240,11 @@ pub struct ArbSwapPathMergerActor<DB: Send + Sync + Clone + 'static> {
     compose_channel_rx: Option<Broadcaster<MessageSwapCompose<DB>>>,
     #[producer]
     compose_channel_tx: Option<Broadcaster<MessageSwapCompose<DB>>>,
+    #[producer]
+    influxdb_write_channel_tx: Option<Broadcaster<WriteQuery>>,
+    max_age_ms: u64,
+    max_ready_requests: usize,
+    cancellation_token: CancellationToken,
 }
 
 impl<DB> ArbSwapPathMergerActor<DB>
@@ -192,14 +258,34 @@ where
             market_events: None,
             compose_channel_rx: None,
             compose_channel_tx: None,
+            influxdb_write_channel_tx: None,
+            max_age_ms: DEFAULT_READY_REQUEST_MAX_AGE_MS,
+            max_ready_requests: DEFAULT_MAX_READY_REQUESTS,
+            cancellation_token: CancellationToken::new(),
         }
     }
+
+    /// Ready requests older than `max_age_ms` are discarded before looking for merge partners
+    pub fn with_max_age_ms(self, max_age_ms: u64) -> Self {
+        Self { max_age_ms, ..self }
+    }
+
+    /// Only the `max_ready_requests` most profitable ready requests are kept for merging
+    pub fn with_max_ready_requests(self, max_ready_requests: usize) -> Self {
+        Self { max_ready_requests: max_ready_requests.max(1), ..self }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+
     pub fn on_bc(self, bc: &Blockchain, strategy: &Strategy<DB>) -> Self {
         Self {
             latest_block: Some(bc.latest_block()),
             market_events: Some(bc.market_events_channel()),
             compose_channel_tx: Some(strategy.swap_compose_channel()),
             compose_channel_rx: Some(strategy.swap_compose_channel()),
+            influxdb_write_channel_tx: Some(bc.influxdb_write_channel()),
             ..self
         }
     }
@@ -225,6 +311,10 @@ where
             market_events,
             compose_channel_rx,
             compose_channel_tx,
+            self.influxdb_write_channel_tx.clone(),
+            self.max_age_ms,
+            self.max_ready_requests,
+            self.cancellation_token.clone(),
         ));
         Ok(vec![task])
     }
@@ -236,11 +326,39 @@ where
 
 #[cfg(test)]
 mod test {
+    use super::{discard_stale_requests, truncate_ready_requests};
     use alloy_primitives::{Address, U256};
     use loom_evm_db::LoomDB;
     use loom_types_entities::{Swap, SwapAmountType, SwapLine, SwapPath, Token};
     use loom_types_events::SwapComposeData;
     use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    #[test]
+    pub fn test_discard_stale_requests() {
+        let now = Instant::now();
+        let max_age = Duration::from_millis(12000);
+        let received_at = |age_ms: u64| now.checked_sub(Duration::from_millis(age_ms));
+        // Instant can not go before the process start on some platforms
+        let (Some(stale_at), Some(fresh_at)) = (received_at(13000), received_at(12000)) else {
+            return;
+        };
+        let mut ready_requests = vec![(stale_at, 0), (fresh_at, 1), (now, 2)];
+
+        assert_eq!(discard_stale_requests(&mut ready_requests, now, max_age), 1);
+        assert_eq!(ready_requests.iter().map(|(_, id)| *id).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(discard_stale_requests(&mut ready_requests, now, max_age), 0);
+    }
+
+    #[test]
+    pub fn test_truncate_ready_requests() {
+        let mut ready_requests = vec![30, 20, 10];
+
+        assert_eq!(truncate_ready_requests(&mut ready_requests, 2), 1);
+        assert_eq!(ready_requests, vec![30, 20]);
+        assert_eq!(truncate_ready_requests(&mut ready_requests, 2), 0);
+    }
 
     #[test]
     pub fn test_sort() {
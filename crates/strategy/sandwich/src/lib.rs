@@ -0,0 +1,5 @@
+mod sandwich_detector;
+mod sandwich_detector_actor;
+
+pub use sandwich_detector::{PendingSwap, SandwichDetector};
+pub use sandwich_detector_actor::SandwichDetectorActor;
@@ -0,0 +1,93 @@
+use std::collections::{HashMap, HashSet};
+
+use alloy_primitives::{Address, TxHash};
+
+/// Pending swap of a mempool transaction on a pool
+#[derive(Clone, Debug)]
+pub struct PendingSwap {
+    pub tx_hash: TxHash,
+    pub sender: Address,
+    pub nonce: u64,
+    pub priority_fee: u128,
+    pub zero_for_one: bool,
+}
+
+/// Tracks pending swaps per pool and finds sandwiches: a front tx and a back tx from the same sender with consecutive nonces
+/// swapping in opposite directions, and a victim tx from another sender swapping in the front tx direction with
+/// a priority fee between the back and front fees.
+#[derive(Default)]
+pub struct SandwichDetector {
+    pool_swaps: HashMap<Address, Vec<PendingSwap>>,
+    reported: HashSet<(TxHash, TxHash)>,
+}
+
+impl SandwichDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a pending swap on `pool` and returns newly detected `(victim_tx, front_tx)` pairs on the pool
+    pub fn add_swap(&mut self, pool: Address, swap: PendingSwap) -> Vec<(TxHash, TxHash)> {
+        let swaps = self.pool_swaps.entry(pool).or_default();
+        if swaps.iter().any(|s| s.tx_hash == swap.tx_hash) {
+            return Vec::new();
+        }
+        swaps.push(swap);
+
+        let mut detected = Vec::new();
+        for front in swaps.iter() {
+            for back in
+                swaps.iter().filter(|s| s.sender == front.sender && s.nonce == front.nonce + 1 && s.zero_for_one != front.zero_for_one)
+            {
+                for victim in swaps.iter().filter(|s| {
+                    s.sender != front.sender
+                        && s.zero_for_one == front.zero_for_one
+                        && s.priority_fee <= front.priority_fee
+                        && s.priority_fee >= back.priority_fee
+                }) {
+                    if self.reported.insert((victim.tx_hash, front.tx_hash)) {
+                        detected.push((victim.tx_hash, front.tx_hash));
+                    }
+                }
+            }
+        }
+        detected
+    }
+
+    pub fn clear(&mut self) {
+        self.pool_swaps.clear();
+        self.reported.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn swap(tx: u8, sender: Address, nonce: u64, priority_fee: u128, zero_for_one: bool) -> PendingSwap {
+        PendingSwap { tx_hash: TxHash::repeat_byte(tx), sender, nonce, priority_fee, zero_for_one }
+    }
+
+    #[test]
+    fn test_detect_sandwich() {
+        let pool = Address::repeat_byte(0x10);
+        let attacker = Address::repeat_byte(0x01);
+        let user = Address::repeat_byte(0x02);
+
+        let mut detector = SandwichDetector::new();
+        assert!(detector.add_swap(pool, swap(1, user, 5, 20, true)).is_empty());
+        assert!(detector.add_swap(pool, swap(2, attacker, 7, 30, true)).is_empty());
+        let detected = detector.add_swap(pool, swap(3, attacker, 8, 10, false));
+        assert_eq!(detected, vec![(TxHash::repeat_byte(1), TxHash::repeat_byte(2))]);
+
+        // Already reported
+        assert!(detector.add_swap(pool, swap(3, attacker, 8, 10, false)).is_empty());
+        // Opposite direction is not a victim
+        assert!(detector.add_swap(pool, swap(4, user, 6, 20, false)).is_empty());
+        // Same swaps on another pool are not related
+        assert!(detector.add_swap(Address::repeat_byte(0x11), swap(5, user, 7, 20, true)).is_empty());
+
+        detector.clear();
+        assert!(detector.add_swap(pool, swap(3, attacker, 8, 10, false)).is_empty());
+    }
+}
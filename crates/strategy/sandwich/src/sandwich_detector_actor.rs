@@ -0,0 +1,167 @@
+use alloy_consensus::Transaction as _;
+use alloy_network::TransactionResponse;
+use alloy_primitives::{Address, TxHash};
+use alloy_rpc_types::Log;
+use alloy_sol_types::SolEvent;
+use eyre::eyre;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, error, info};
+
+use loom_core_actors::{
+    subscribe, Accessor, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, Producer, SharedState, WorkerResult,
+};
+use loom_core_actors_macros::{Accessor, Consumer, Producer};
+use loom_core_blockchain::Blockchain;
+use loom_defi_abi::uniswap2::IUniswapV2Pair;
+use loom_defi_abi::uniswap3::IUniswapV3Pool;
+use loom_types_blockchain::Mempool;
+use loom_types_events::{MarketEvents, MempoolEvents};
+
+use crate::{PendingSwap, SandwichDetector};
+
+/// Pool and direction of UniswapV2 and UniswapV3 like swap logs
+fn get_swap_directions(logs: &[Log]) -> Vec<(Address, bool)> {
+    let mut ret: Vec<(Address, bool)> = Vec::new();
+    for log in logs.iter() {
+        let Some(topic0) = log.topic0() else {
+            continue;
+        };
+        let zero_for_one = if *topic0 == IUniswapV2Pair::Swap::SIGNATURE_HASH {
+            IUniswapV2Pair::Swap::decode_log(&log.inner, false).ok().map(|event| !event.data.amount0In.is_zero())
+        } else if *topic0 == IUniswapV3Pool::Swap::SIGNATURE_HASH {
+            IUniswapV3Pool::Swap::decode_log(&log.inner, false).ok().map(|event| event.data.amount0.is_positive())
+        } else {
+            None
+        };
+        // First swap of the transaction on a pool defines the direction
+        if let Some(zero_for_one) = zero_for_one {
+            if !ret.iter().any(|(pool, _)| *pool == log.address()) {
+                ret.push((log.address(), zero_for_one));
+            }
+        }
+    }
+    ret
+}
+
+pub async fn sandwich_detector_worker(
+    mempool: SharedState<Mempool>,
+    market_events_rx: Broadcaster<MarketEvents>,
+    mempool_events_rx: Broadcaster<MempoolEvents>,
+    mempool_events_tx: Broadcaster<MempoolEvents>,
+    cancellation_token: CancellationToken,
+) -> WorkerResult {
+    subscribe!(market_events_rx);
+    subscribe!(mempool_events_rx);
+
+    let mut detector = SandwichDetector::new();
+
+    loop {
+        tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Sandwich detector cancelled".to_string());
+            }
+            msg = market_events_rx.recv() => {
+                let market_event_msg: Result<MarketEvents, RecvError> = msg;
+                match market_event_msg {
+                    Ok(MarketEvents::BlockHeaderUpdate { .. }) => {
+                        detector.clear();
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Closed) => {
+                        error!("Market events channel closed");
+                        break Err(eyre!("MARKET_EVENTS_RX_CLOSED"));
+                    }
+                    Err(RecvError::Lagged(lag)) => {
+                        error!("Market events channel lagged by {} messages", lag);
+                    }
+                }
+            }
+            msg = mempool_events_rx.recv() => {
+                let mempool_event_msg: Result<MempoolEvents, RecvError> = msg;
+                match mempool_event_msg {
+                    Ok(MempoolEvents::MempoolLogUpdate { tx_hash }) => {
+                        let Some(mempool_tx) = mempool.read().await.get_tx_by_hash(&tx_hash).cloned() else {
+                            continue;
+                        };
+                        let (Some(tx), Some(logs)) = (mempool_tx.tx, mempool_tx.logs) else {
+                            continue;
+                        };
+                        let sender = TransactionResponse::from(&tx);
+                        let nonce = tx.nonce();
+                        let priority_fee = tx.max_priority_fee_per_gas().unwrap_or_else(|| tx.max_fee_per_gas());
+
+                        for (pool, zero_for_one) in get_swap_directions(&logs) {
+                            let swap = PendingSwap { tx_hash, sender, nonce, priority_fee, zero_for_one };
+                            for (victim_tx, front_tx) in detector.add_swap(pool, swap) {
+                                info!(%pool, %victim_tx, %front_tx, "Sandwich detected");
+                                if let Err(e) = mempool_events_tx.send(MempoolEvents::SandwichDetected { victim_tx, front_tx }) {
+                                    error!("mempool_events_tx.send : {}", e);
+                                }
+                            }
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Closed) => {
+                        error!("Mempool events channel closed");
+                        break Err(eyre!("MEMPOOL_EVENTS_RX_CLOSED"));
+                    }
+                    Err(RecvError::Lagged(lag)) => {
+                        debug!("Mempool events channel lagged by {} messages", lag);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Detects sandwich attacks in the mempool from pending swap logs and emits `MempoolEvents::SandwichDetected`
+/// for every victim transaction
+#[derive(Accessor, Consumer, Producer, Default)]
+pub struct SandwichDetectorActor {
+    #[accessor]
+    mempool: Option<SharedState<Mempool>>,
+    #[consumer]
+    market_events_rx: Option<Broadcaster<MarketEvents>>,
+    #[consumer]
+    mempool_events_rx: Option<Broadcaster<MempoolEvents>>,
+    #[producer]
+    mempool_events_tx: Option<Broadcaster<MempoolEvents>>,
+    cancellation_token: CancellationToken,
+}
+
+impl SandwichDetectorActor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn on_bc(self, bc: &Blockchain) -> Self {
+        Self {
+            mempool: Some(bc.mempool()),
+            market_events_rx: Some(bc.market_events_channel()),
+            mempool_events_rx: Some(bc.mempool_events_channel()),
+            mempool_events_tx: Some(bc.mempool_events_channel()),
+            ..self
+        }
+    }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
+}
+
+impl Actor for SandwichDetectorActor {
+    fn start(&self) -> ActorResult {
+        let task = tokio::task::spawn(sandwich_detector_worker(
+            self.mempool.clone().unwrap(),
+            self.market_events_rx.clone().unwrap(),
+            self.mempool_events_rx.clone().unwrap(),
+            self.mempool_events_tx.clone().unwrap(),
+            self.cancellation_token.clone(),
+        ));
+        Ok(vec![task])
+    }
+
+    fn name(&self) -> &'static str {
+        "SandwichDetectorActor"
+    }
+}
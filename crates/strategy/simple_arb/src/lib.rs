@@ -3,27 +3,41 @@ use eyre::{eyre, ErrReport, Result};
 use revm::DatabaseRef;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use std::pin::Pin;
 use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::watch;
 #[macro_use]
 extern crate tracing;
 
-use loom_core_actors::{subscribe, Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
+pub use simple_arb_config::SimpleArbConfig;
+
+mod simple_arb_config;
+
+use loom_core_actors::{
+    subscribe, Accessor, Actor, ActorResult, Broadcaster, CancellationToken, Consumer, Producer, SharedState, WorkerResult,
+};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
 use loom_core_blockchain::{Blockchain, Strategy};
-use loom_types_entities::{Market, PoolWrapper, Swap, SwapLine, SwapPath, Token};
-use loom_types_events::{MarketEvents, MessageSwapCompose, SwapComposeData};
+use loom_types_entities::{Market, PoolId, PoolWrapper, Swap, SwapLine, SwapPath, Token};
+use loom_types_events::{LoomTask, MarketEvents, MessageSwapCompose, SwapComposeData};
 
 // Simple arbitrage path finder that looks for cycles of length 3
 pub async fn simple_arb_finder_worker<DB: DatabaseRef<Error = ErrReport> + Send + Sync + Clone + 'static>(
+    config: SimpleArbConfig,
     market: SharedState<Market>,
     market_events_rx: Broadcaster<MarketEvents>,
+    tasks_rx: Option<Broadcaster<LoomTask>>,
     compose_channel_tx: Broadcaster<MessageSwapCompose<DB>>,
+    cancellation_token: CancellationToken,
 ) -> WorkerResult {
     subscribe!(market_events_rx);
+    let mut tasks_rx = tasks_rx.map(|tasks_rx| tasks_rx.subscribe());
+    let (config_tx, config_rx) = watch::channel(config);
 
     loop {
         tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break Ok("Simple arb finder cancelled".to_string());
+            }
             msg = market_events_rx.recv() => {
                 let msg: Result<MarketEvents, RecvError> = msg;
                 match msg {
@@ -31,7 +45,8 @@ pub async fn simple_arb_finder_worker<DB: DatabaseRef<Error = ErrReport> + Send
                         match event {
                             MarketEvents::BlockHeaderUpdate{..} => {
                                 // Find arbitrage opportunities on new block
-                                if let Err(e) = find_arbitrage_paths(market.clone(), compose_channel_tx.clone()).await {
+                                let config = config_rx.borrow().clone();
+                                if let Err(e) = find_arbitrage_paths(&config, market.clone(), compose_channel_tx.clone()).await {
                                     error!("Error finding arbitrage paths: {}", e);
                                 }
                             },
@@ -43,11 +58,29 @@ pub async fn simple_arb_finder_worker<DB: DatabaseRef<Error = ErrReport> + Send
                     }
                 }
             }
+            msg = async { tasks_rx.as_mut().unwrap().recv().await }, if tasks_rx.is_some() => {
+                let task_msg: Result<LoomTask, RecvError> = msg;
+                if let Some(config) = task_msg.ok().and_then(|task| task.reloaded_config::<SimpleArbConfig>()) {
+                    info!("Simple arb config reloaded: {:?}", config);
+                    config_tx.send_replace(config);
+                }
+            }
         }
     }
 }
 
+/// Pools with a TVL, as set by the price actor, of at least `min_liquidity_usd`. All pools if the threshold is zero.
+fn liquid_pools(market: &Market, min_liquidity_usd: f64) -> HashSet<PoolId> {
+    market
+        .pools()
+        .keys()
+        .filter(|pool_id| min_liquidity_usd <= 0.0 || market.get_pool_metrics(pool_id).is_some_and(|m| m.tvl_usd >= min_liquidity_usd))
+        .cloned()
+        .collect()
+}
+
 async fn find_arbitrage_paths<DB: DatabaseRef<Error = ErrReport> + Send + Sync + Clone + 'static>(
+    config: &SimpleArbConfig,
     market: SharedState<Market>,
     compose_channel_tx: Broadcaster<MessageSwapCompose<DB>>,
 ) -> Result<()> {
@@ -58,17 +91,19 @@ async fn find_arbitrage_paths<DB: DatabaseRef<Error = ErrReport> + Send + Sync +
     
     // Focus on main tokens for efficiency
     let main_tokens: Vec<Arc<Token>> = tokens.into_iter()
-        .filter(|t| t.is_basic())
+        .filter(|t| t.is_basic() && config.is_token_allowed(&t.get_address()))
         .collect();
     
     if main_tokens.is_empty() {
         return Err(eyre!("No main tokens found"));
     }
     
-    // Maximum path length (2-4 hops for efficiency and higher profit margins)
-    let max_path_length = 3;
+    // Prune shallow pools once so the search never expands them
+    let liquid_pools = liquid_pools(&market_guard, config.min_liquidity_usd as f64);
+    debug!("Simple arb search over {} of {} pools", liquid_pools.len(), market_guard.pools().len());
     
     // For each main token, find paths that start and end with it
+    let mut scored_paths: Vec<(f64, SwapPath)> = Vec::new();
     for start_token in main_tokens.iter() {
         let start_address = start_token.get_address();
         
@@ -80,36 +115,71 @@ async fn find_arbitrage_paths<DB: DatabaseRef<Error = ErrReport> + Send + Sync +
             vec![start_token.clone()], 
             vec![], 
             HashSet::new(),
-            max_path_length,
-            &compose_channel_tx
-        ).await?;
+            config,
+            &liquid_pools,
+            &mut scored_paths
+        );
+    }
+    drop(market_guard);
+
+    // Send the most promising cycles first
+    scored_paths.sort_by(|a, b| b.0.total_cmp(&a.0));
+    debug!("Simple arb found {} cycles above score {}", scored_paths.len(), config.min_arbitrage_score);
+
+    for (_, path) in scored_paths {
+        // Create a swap line
+        let swap_line = SwapLine {
+            path,
+            ..Default::default()
+        };
+        
+        // Send to the compose channel for further processing
+        let compose_data = SwapComposeData {
+            swap: Swap::BackrunSwapLine(swap_line),
+            origin: Some("enhanced_arb_finder".to_string()),
+            ..Default::default()
+        };
+        
+        let compose_message = MessageSwapCompose::prepare(compose_data);
+        if let Err(e) = compose_channel_tx.send(compose_message) {
+            error!("Failed to send compose message: {}", e);
+        }
     }
     
     Ok(())
 }
 
-/// DFS to find all cycles with variable length
-async fn find_cycles<DB: DatabaseRef<Error = ErrReport> + Send + Sync + Clone + 'static>(
+/// DFS to find all cycles with variable length, cycles scoring at least `min_arbitrage_score` are collected with their score
+fn find_cycles(
     market: &Market,
     start_token: Arc<Token>,
     current_token_address: Address,
     current_path: Vec<Arc<Token>>,
     current_pools: Vec<Arc<PoolWrapper>>,
     visited_tokens: HashSet<Address>,
-    max_depth: usize,
-    compose_channel_tx: &Broadcaster<MessageSwapCompose<DB>>,
-) -> Result<()> {
+    config: &SimpleArbConfig,
+    liquid_pools: &HashSet<PoolId>,
+    scored_paths: &mut Vec<(f64, SwapPath)>,
+) {
     // If we've reached max depth, stop
-    if current_path.len() > max_depth {
-        return Ok(());
+    if current_path.len() > config.max_path_length {
+        return;
     }
     
-    // Get all pools that contain this token
-    let pools = match market.get_token_pools(&current_token_address) {
-        Some(pool_ids) => pool_ids.iter()
-            .filter_map(|pool_id| market.get_pool(pool_id))
-            .collect::<Vec<_>>(),
-        None => Vec::new(),
+    // Get all pools that contain this token, restricted to the configured protocols
+    let pools: Vec<PoolWrapper> = match &config.protocols {
+        Some(protocols) => protocols
+            .iter()
+            .flat_map(|protocol| market.get_token_pools_by_protocol(&current_token_address, *protocol))
+            .filter(|pool| liquid_pools.contains(&pool.get_pool_id()))
+            .collect(),
+        None => match market.get_token_pools(&current_token_address) {
+            Some(pool_ids) => pool_ids.iter()
+                .filter(|pool_id| liquid_pools.contains(pool_id))
+                .filter_map(|pool_id| market.get_pool(pool_id).cloned())
+                .collect(),
+            None => Vec::new(),
+        },
     };
     
     for pool in pools {
@@ -136,32 +206,23 @@ async fn find_cycles<DB: DatabaseRef<Error = ErrReport> + Send + Sync + Clone +
             complete_pools.push(Arc::new(pool.clone()));
             
             // Create the path
-            let path = SwapPath {
+            let mut path = SwapPath {
                 tokens: complete_path,
                 pools: complete_pools.into_iter().map(|p| (*p).clone()).collect(),
                 disabled: false,
                 disabled_pool: Vec::new(),
-                score: Some(1.0),
+                score: None,
             };
             
-            // Create a swap line
-            let swap_line = SwapLine {
-                path,
-                ..Default::default()
-            };
-            
-            // Send to the compose channel for further processing
-            let compose_data = SwapComposeData {
-                swap: Swap::BackrunSwapLine(swap_line),
-                origin: Some("enhanced_arb_finder".to_string()),
-                ..Default::default()
-            };
-            
-            let compose_message = MessageSwapCompose::prepare(compose_data);
-            if let Err(e) = compose_channel_tx.send(compose_message) {
-                error!("Failed to send compose message: {}", e);
+            // Skip cycles unlikely to be profitable
+            let score = market.get_arbitrage_score(&path);
+            if score < config.min_arbitrage_score {
+                trace!("Cycle skipped with score {}", score);
+                continue;
             }
-        } else if !visited_tokens.contains(&other_token_address) {
+            path.score = Some(score);
+            scored_paths.push((score, path));
+        } else if !visited_tokens.contains(&other_token_address) && config.is_token_allowed(&other_token_address) {
             // Continue the search with the new token
             let other_token = match market.get_token(&other_token_address) {
                 Some(token) => token,
@@ -177,45 +238,56 @@ async fn find_cycles<DB: DatabaseRef<Error = ErrReport> + Send + Sync + Clone +
             let mut new_visited = visited_tokens.clone();
             new_visited.insert(other_token_address);
             
-            Box::pin(find_cycles(
+            find_cycles(
                 market,
                 start_token.clone(),
                 other_token_address,
                 new_path,
                 new_pools,
                 new_visited,
-                max_depth,
-                compose_channel_tx
-            )).await?;
+                config,
+                liquid_pools,
+                scored_paths
+            );
         }
     }
-    
-    Ok(())
 }
 
 #[derive(Accessor, Consumer, Producer)]
 pub struct SimpleArbFinderActor<DB: Clone + Send + Sync + 'static> {
+    config: SimpleArbConfig,
     #[accessor]
     market: Option<SharedState<Market>>,
     #[consumer]
     market_events: Option<Broadcaster<MarketEvents>>,
+    #[consumer]
+    tasks_rx: Option<Broadcaster<LoomTask>>,
     #[producer]
     compose_channel_tx: Option<Broadcaster<MessageSwapCompose<DB>>>,
+    cancellation_token: CancellationToken,
 }
 
 impl<DB: Clone + Send + Sync + 'static> SimpleArbFinderActor<DB> {
-    pub fn new() -> Self {
+    pub fn new(config: SimpleArbConfig) -> Self {
         Self {
+            config,
             market: None,
             market_events: None,
+            tasks_rx: None,
             compose_channel_tx: None,
+            cancellation_token: CancellationToken::new(),
         }
     }
+
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self { cancellation_token, ..self }
+    }
     
     pub fn on_bc(self, bc: &Blockchain, strategy: &Strategy<DB>) -> Self {
         Self {
             market: Some(bc.market()),
             market_events: Some(bc.market_events_channel()),
+            tasks_rx: Some(bc.tasks_channel()),
             compose_channel_tx: Some(strategy.swap_compose_channel()),
             ..self
         }
@@ -228,9 +300,12 @@ where
 {
     fn start(&self) -> ActorResult {
         let task = tokio::task::spawn(simple_arb_finder_worker(
+            self.config.clone(),
             self.market.clone().unwrap(),
             self.market_events.clone().unwrap(),
+            self.tasks_rx.clone(),
             self.compose_channel_tx.clone().unwrap(),
+            self.cancellation_token.clone(),
         ));
         
         Ok(vec![task])
@@ -239,4 +314,71 @@ where
     fn name(&self) -> &'static str {
         "SimpleArbFinderActor"
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use loom_types_entities::MockPool;
+
+    #[test]
+    fn test_liquid_pools() {
+        let mut market = Market::default();
+        let (token0, token1) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let (deep_pool, shallow_pool, unpriced_pool) = (Address::repeat_byte(10), Address::repeat_byte(11), Address::repeat_byte(12));
+        for pool_address in [deep_pool, shallow_pool, unpriced_pool] {
+            market.add_pool(MockPool::new(token0, token1, pool_address)).unwrap();
+        }
+        market.set_pool_tvl(PoolId::Address(deep_pool), 2_000_000.0);
+        market.set_pool_tvl(PoolId::Address(shallow_pool), 10_000.0);
+
+        assert_eq!(liquid_pools(&market, 1_000_000.0), HashSet::from([PoolId::Address(deep_pool)]));
+        assert_eq!(liquid_pools(&market, 10_000.0), HashSet::from([PoolId::Address(deep_pool), PoolId::Address(shallow_pool)]));
+        assert_eq!(liquid_pools(&market, 0.0).len(), 3);
+    }
+
+    #[test]
+    fn test_find_cycles_prunes_low_score() {
+        let mut market = Market::default();
+        let (token0, token1, token2) = (Address::repeat_byte(1), Address::repeat_byte(2), Address::repeat_byte(3));
+        for token in [token0, token1, token2] {
+            market.add_token(Token::new(token));
+        }
+        let pool_addresses = [Address::repeat_byte(10), Address::repeat_byte(11), Address::repeat_byte(12)];
+        market.add_pool(MockPool::new(token0, token1, pool_addresses[0])).unwrap();
+        market.add_pool(MockPool::new(token1, token2, pool_addresses[1])).unwrap();
+        market.add_pool(MockPool::new(token0, token2, pool_addresses[2])).unwrap();
+
+        let config = SimpleArbConfig::default();
+        let find = |market: &Market| {
+            let start_token = market.get_token(&token0).unwrap();
+            let mut scored_paths = Vec::new();
+            find_cycles(
+                market,
+                start_token.clone(),
+                token0,
+                vec![start_token],
+                vec![],
+                HashSet::new(),
+                &config,
+                &liquid_pools(market, 0.0),
+                &mut scored_paths,
+            );
+            scored_paths
+        };
+
+        // both directions of the cycle score below the threshold without TVL, hit rate or spot prices
+        assert!(find(&market).is_empty());
+
+        // 1 token0 -> 2 token1 -> 4 token2 -> 1.01 token0, the reverse direction returns less than it takes
+        market.set_pool_spot_price(PoolId::Address(pool_addresses[0]), 2.0);
+        market.set_pool_spot_price(PoolId::Address(pool_addresses[1]), 2.0);
+        market.set_pool_spot_price(PoolId::Address(pool_addresses[2]), 4.0 / 1.01);
+        let scored_paths = find(&market);
+        assert_eq!(scored_paths.len(), 1);
+        let (score, path) = &scored_paths[0];
+        assert!(*score >= config.min_arbitrage_score);
+        assert_eq!(path.score, Some(*score));
+        assert_eq!(path.tokens.iter().map(|token| token.get_address()).collect::<Vec<_>>(), vec![token0, token1, token2, token0]);
+    }
+}
@@ -0,0 +1,31 @@
+use alloy_primitives::Address;
+use loom_types_entities::PoolProtocol;
+use serde::Deserialize;
+use std::collections::HashSet;
+
+#[derive(Clone, Deserialize, Debug)]
+#[serde(default)]
+pub struct SimpleArbConfig {
+    /// Maximum number of tokens in a cycle before closing it back to the start token
+    pub max_path_length: usize,
+    /// When set, only these tokens are visited during the search
+    pub token_allowlist: Option<HashSet<Address>>,
+    /// Pools with a TVL below this threshold are pruned before the search
+    pub min_liquidity_usd: u64,
+    /// Cycles with a `Market::get_arbitrage_score` below this threshold are skipped
+    pub min_arbitrage_score: f64,
+    /// When set, only pools of these protocols are used in cycles
+    pub protocols: Option<HashSet<PoolProtocol>>,
+}
+
+impl Default for SimpleArbConfig {
+    fn default() -> Self {
+        Self { max_path_length: 3, token_allowlist: None, min_liquidity_usd: 0, min_arbitrage_score: 0.1, protocols: None }
+    }
+}
+
+impl SimpleArbConfig {
+    pub fn is_token_allowed(&self, address: &Address) -> bool {
+        self.token_allowlist.as_ref().map_or(true, |allowlist| allowlist.contains(address))
+    }
+}
@@ -8,6 +8,7 @@ pub use loom_data_types_ethereum::LoomDataTypesEthereum;
 pub use mempool::Mempool;
 pub use mempool_tx::MempoolTx;
 pub use opcodes::*;
+pub use pool_id::PoolId;
 pub use state_update::{
     debug_log_geth_state_update, debug_trace_block, debug_trace_call_diff, debug_trace_call_post_state, debug_trace_call_pre_state,
     debug_trace_transaction, get_touched_addresses, GethStateUpdate, GethStateUpdateVec, TRACING_CALL_OPTS, TRACING_OPTS,
@@ -23,4 +24,5 @@ mod mempool;
 mod mempool_tx;
 mod new_block;
 mod opcodes;
+mod pool_id;
 mod state_update;
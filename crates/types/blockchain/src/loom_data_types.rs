@@ -38,8 +38,10 @@ pub trait LoomDataTypes: Debug + Clone + Send + Sync {
     type Log: Default + Debug + Clone + Send + Sync;
     type StateUpdate: Default + Debug + Clone + Send + Sync;
     type BlockHash: Eq + Copy + Hash + Default + Display + Debug + Clone + Send + Sync;
-    type TxHash: Eq + Copy + Hash + Default + Display + Debug + Clone + Send + Sync;
-    type Address: Eq + Copy + Hash + Ord + Default + Display + Debug + Clone + Send + Sync;
+    type TxHash: Eq + Copy + Hash + Ord + Default + Display + Debug + Clone + Send + Sync;
+    type Address: Eq + Copy + Hash + Ord + Default + Display + Debug + Clone + Send + Sync + AsRef<[u8]>;
     const WETH: Self::Address;
     fn is_weth(address: &Self::Address) -> bool;
+    /// Native token or the wrapped native token of a supported chain, equivalent for path finding
+    fn is_native_or_wrapped(address: &Self::Address) -> bool;
 }
@@ -4,6 +4,7 @@ use alloy_eips::eip2718::Encodable2718;
 use alloy_primitives::{hex, Address, BlockHash, TxHash};
 use alloy_provider::network::TransactionResponse;
 use alloy_rpc_types_eth::{Block as EthBlock, Header, Log, Transaction, TransactionReceipt, TransactionRequest};
+use loom_defi_address_book::{TokenAddressArbitrum, TokenAddressBase, TokenAddressEth};
 
 #[derive(Clone, Debug, Default)]
 pub struct LoomDataTypesEthereum {
@@ -29,6 +30,13 @@ impl LoomDataTypes for LoomDataTypesEthereum {
     fn is_weth(address: &Self::Address) -> bool {
         address.eq(&Self::WETH)
     }
+
+    fn is_native_or_wrapped(address: &Self::Address) -> bool {
+        TokenAddressEth::is_eth_or_sentinel(address)
+            || TokenAddressEth::is_weth(address)
+            || TokenAddressBase::is_weth(address)
+            || TokenAddressArbitrum::is_weth(address)
+    }
 }
 
 impl LoomTx<LoomDataTypesEthereum> for Transaction {
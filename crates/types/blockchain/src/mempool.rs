@@ -1,21 +1,39 @@
 use crate::loom_data_types::LoomTx;
-use crate::{AccountNonceAndTransactions, FetchState, GethStateUpdate, MempoolTx};
+use crate::{AccountNonceAndTransactions, FetchState, GethStateUpdate, MempoolTx, PoolId};
 use crate::{LoomDataTypes, LoomDataTypesEthereum};
 use alloy_primitives::map::HashMap;
 use alloy_primitives::BlockNumber;
 use chrono::{DateTime, Utc};
 use eyre::{eyre, Result};
 use std::collections::hash_map::Entry;
+use std::collections::BTreeSet;
+
+// Max number of pending transactions kept in the gas price index, the cheapest ones are dropped first
+const MEMPOOL_GAS_PRICE_INDEX_MAX_LEN: usize = 10_000;
 
 #[derive(Clone, Debug, Default)]
 pub struct Mempool<LDT: LoomDataTypes = LoomDataTypesEthereum> {
     pub txs: HashMap<LDT::TxHash, MempoolTx<LDT>>,
     accounts: HashMap<LDT::Address, AccountNonceAndTransactions>,
+    // Pending transactions by the pools they swap on, and the pools of each indexed transaction for removal
+    pool_tx_index: HashMap<PoolId<LDT>, Vec<LDT::TxHash>>,
+    tx_pool_index: HashMap<LDT::TxHash, Vec<PoolId<LDT>>>,
+    // Pending transactions ordered by gas price. An ordered set instead of a heap, so removed and mined transactions
+    // are dropped without rebuilding it
+    gas_price_index: BTreeSet<(u128, LDT::TxHash)>,
+    tx_gas_price: HashMap<LDT::TxHash, u128>,
 }
 
 impl<LDT: LoomDataTypes> Mempool<LDT> {
     pub fn new() -> Mempool<LoomDataTypesEthereum> {
-        Mempool { txs: HashMap::default(), accounts: HashMap::default() }
+        Mempool {
+            txs: HashMap::default(),
+            accounts: HashMap::default(),
+            pool_tx_index: HashMap::default(),
+            tx_pool_index: HashMap::default(),
+            gas_price_index: BTreeSet::default(),
+            tx_gas_price: HashMap::default(),
+        }
     }
 
     pub fn len(&self) -> usize {
@@ -32,11 +50,39 @@ impl<LDT: LoomDataTypes> Mempool<LDT> {
 
     pub fn add_tx(&mut self, tx: LDT::Transaction) -> &mut Self {
         let tx_hash: LDT::TxHash = tx.tx_hash();
+        let gas_price = tx.gas_price();
         let entry = self.txs.entry(tx_hash).or_default();
         entry.tx = Some(tx);
+        if entry.mined.is_none() {
+            self.index_gas_price(tx_hash, gas_price);
+        }
         self
     }
 
+    fn index_gas_price(&mut self, tx_hash: LDT::TxHash, gas_price: u128) {
+        if let Some(old_gas_price) = self.tx_gas_price.insert(tx_hash, gas_price) {
+            self.gas_price_index.remove(&(old_gas_price, tx_hash));
+        }
+        self.gas_price_index.insert((gas_price, tx_hash));
+        if self.gas_price_index.len() > MEMPOOL_GAS_PRICE_INDEX_MAX_LEN {
+            if let Some((_, cheapest_tx_hash)) = self.gas_price_index.pop_first() {
+                self.tx_gas_price.remove(&cheapest_tx_hash);
+            }
+        }
+    }
+
+    fn unindex_gas_price(&mut self, tx_hash: &LDT::TxHash) {
+        if let Some(gas_price) = self.tx_gas_price.remove(tx_hash) {
+            self.gas_price_index.remove(&(gas_price, *tx_hash));
+        }
+    }
+
+    /// Up to `n` pending transactions with the highest gas price, most expensive first. They are the most likely to be included
+    /// in the next block
+    pub fn get_top_gas_price_txs(&self, n: usize) -> Vec<MempoolTx<LDT>> {
+        self.gas_price_index.iter().rev().filter_map(|(_, tx_hash)| self.txs.get(tx_hash)).take(n).cloned().collect()
+    }
+
     pub fn add_tx_logs(&mut self, tx_hash: LDT::TxHash, logs: Vec<LDT::Log>) -> &mut Self {
         let entry = self.txs.entry(tx_hash).or_default();
         entry.logs = Some(logs);
@@ -49,6 +95,45 @@ impl<LDT: LoomDataTypes> Mempool<LDT> {
         self
     }
 
+    /// Index a decoded transaction by the pools it swaps on
+    pub fn add_tx_pools(&mut self, tx_hash: LDT::TxHash, pools: impl IntoIterator<Item = PoolId<LDT>>) -> &mut Self {
+        for pool in pools {
+            let pool_txs = self.pool_tx_index.entry(pool).or_default();
+            if !pool_txs.contains(&tx_hash) {
+                pool_txs.push(tx_hash);
+                self.tx_pool_index.entry(tx_hash).or_default().push(pool);
+            }
+        }
+        self
+    }
+
+    /// Pending transactions swapping on `pool`, found without re-decoding the mempool
+    pub fn get_pending_swaps_for_pool(&self, pool: &PoolId<LDT>) -> Vec<MempoolTx<LDT>> {
+        self.pool_tx_index
+            .get(pool)
+            .map(|tx_hashes| {
+                tx_hashes
+                    .iter()
+                    .filter_map(|tx_hash| self.txs.get(tx_hash))
+                    .filter(|mempool_tx| mempool_tx.mined.is_none())
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Drop the pool index entries of a transaction removed from the mempool
+    fn unindex_tx_pools(&mut self, tx_hash: &LDT::TxHash) {
+        for pool in self.tx_pool_index.remove(tx_hash).unwrap_or_default() {
+            if let Entry::Occupied(mut e) = self.pool_tx_index.entry(pool) {
+                e.get_mut().retain(|pool_tx_hash| pool_tx_hash != tx_hash);
+                if e.get().is_empty() {
+                    e.remove();
+                }
+            }
+        }
+    }
+
     pub fn filter_by_gas_price(&self, gas_price: u128) -> Vec<&MempoolTx<LDT>> {
         self.txs
             .values()
@@ -88,6 +173,10 @@ impl<LDT: LoomDataTypes> Mempool<LDT> {
     pub fn clean(&mut self) {
         self.txs = Default::default();
         self.accounts = Default::default();
+        self.pool_tx_index = Default::default();
+        self.tx_pool_index = Default::default();
+        self.gas_price_index = Default::default();
+        self.tx_gas_price = Default::default();
     }
 
     pub fn clean_txs(&mut self, max_block_number: BlockNumber, max_time: DateTime<Utc>) {
@@ -97,11 +186,20 @@ impl<LDT: LoomDataTypes> Mempool<LDT> {
             .into_iter()
             .filter(|(_, v)| v.mined.unwrap_or(max_block_number + 1) > max_block_number && v.time > max_time)
             .collect();
+        let removed_txs: Vec<LDT::TxHash> = self.tx_pool_index.keys().filter(|tx_hash| !self.txs.contains_key(*tx_hash)).cloned().collect();
+        for tx_hash in removed_txs {
+            self.unindex_tx_pools(&tx_hash);
+        }
+
+        let txs = &self.txs;
+        self.tx_gas_price.retain(|tx_hash, _| txs.contains_key(tx_hash));
+        self.gas_price_index.retain(|(_, tx_hash)| txs.contains_key(tx_hash));
     }
 
     pub fn set_mined(&mut self, tx_hash: LDT::TxHash, block_number: BlockNumber) -> &mut Self {
         let entry = self.txs.entry(tx_hash).or_default();
         entry.mined = Some(block_number);
+        self.unindex_gas_price(&tx_hash);
         self
     }
 
@@ -130,7 +228,117 @@ impl<LDT: LoomDataTypes> Mempool<LDT> {
         Err(eyre!("NOT_IMPLEMENTED"))
     }
 
+    /// Pending transactions from the same sender with the same nonce as one of the bundle transactions.
+    /// Only one of them can be included, so each candidate competes with our bundle.
+    pub fn get_bundle_conflict_candidates(&self, our_bundle_txs: &[LDT::TxHash]) -> Vec<LDT::TxHash> {
+        let bundle_from_nonce: Vec<(LDT::Address, u64)> = our_bundle_txs
+            .iter()
+            .filter_map(|tx_hash| self.txs.get(tx_hash).and_then(|mempool_tx| mempool_tx.tx.as_ref()))
+            .map(|tx| (tx.from(), tx.nonce()))
+            .collect();
+
+        if bundle_from_nonce.is_empty() {
+            return Vec::new();
+        }
+
+        self.txs
+            .iter()
+            .filter(|(tx_hash, mempool_tx)| mempool_tx.mined.is_none() && !our_bundle_txs.contains(tx_hash))
+            .filter_map(|(tx_hash, mempool_tx)| {
+                let tx = mempool_tx.tx.as_ref()?;
+                bundle_from_nonce.contains(&(tx.from(), tx.nonce())).then_some(*tx_hash)
+            })
+            .collect()
+    }
+
     pub fn remove_tx(&mut self, tx_hash: &LDT::TxHash) -> Option<MempoolTx<LDT>> {
-        self.txs.remove(tx_hash)
+        let mempool_tx = self.txs.remove(tx_hash);
+        if mempool_tx.is_some() {
+            self.unindex_tx_pools(tx_hash);
+            self.unindex_gas_price(tx_hash);
+        }
+        mempool_tx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::{Signed, TxEip1559, TxEnvelope};
+    use alloy_primitives::{Address, PrimitiveSignature, TxHash, B256, U256};
+    use alloy_rpc_types_eth::Transaction;
+
+    fn test_tx(tx_hash: TxHash, max_fee_per_gas: u128) -> Transaction {
+        let tx = TxEip1559 { max_fee_per_gas, ..TxEip1559::default() };
+        let signature = PrimitiveSignature::new(U256::from(1), U256::from(1), false);
+        let inner = TxEnvelope::Eip1559(Signed::new_unchecked(tx, signature, tx_hash));
+        Transaction { inner, block_hash: None, block_number: None, transaction_index: None, effective_gas_price: None, from: Address::ZERO }
+    }
+
+    #[test]
+    fn test_pending_swaps_for_pool() {
+        let pool = PoolId::Address(Address::repeat_byte(0x01));
+        let other_pool = PoolId::Address(Address::repeat_byte(0x02));
+        // UniswapV4 pools share the pool manager address
+        let v4_pool = PoolId::Bytes32(B256::repeat_byte(0x03));
+        let other_v4_pool = PoolId::Bytes32(B256::repeat_byte(0x04));
+        let tx1 = TxHash::repeat_byte(0x11);
+        let tx2 = TxHash::repeat_byte(0x12);
+
+        let mut mempool = Mempool::<LoomDataTypesEthereum>::new();
+        for tx_hash in [tx1, tx2] {
+            mempool.txs.insert(tx_hash, MempoolTx::new_with_hash(tx_hash));
+        }
+        mempool.add_tx_pools(tx1, [pool, other_pool, v4_pool]).add_tx_pools(tx2, [pool, other_v4_pool]).add_tx_pools(tx2, [pool]);
+
+        let tx_hashes: Vec<TxHash> = mempool.get_pending_swaps_for_pool(&pool).iter().map(|tx| tx.tx_hash).collect();
+        assert_eq!(tx_hashes, vec![tx1, tx2]);
+        let tx_hashes: Vec<TxHash> = mempool.get_pending_swaps_for_pool(&v4_pool).iter().map(|tx| tx.tx_hash).collect();
+        assert_eq!(tx_hashes, vec![tx1]);
+        assert_eq!(mempool.tx_pool_index[&tx2].len(), 2);
+
+        // Mined and removed transactions are not pending
+        mempool.set_mined(tx2, 100);
+        mempool.remove_tx(&tx1);
+        assert!(mempool.get_pending_swaps_for_pool(&pool).is_empty());
+        assert!(mempool.get_pending_swaps_for_pool(&other_pool).is_empty());
+        assert!(mempool.get_pending_swaps_for_pool(&v4_pool).is_empty());
+        assert_eq!(mempool.pool_tx_index.len(), 2);
+        assert_eq!(mempool.tx_pool_index.len(), 1);
+    }
+
+    #[test]
+    fn test_top_gas_price_txs() {
+        let tx1 = TxHash::repeat_byte(0x11);
+        let tx2 = TxHash::repeat_byte(0x12);
+        let tx3 = TxHash::repeat_byte(0x13);
+
+        let mut mempool = Mempool::<LoomDataTypesEthereum>::new();
+        mempool.add_tx(test_tx(tx1, 10)).add_tx(test_tx(tx2, 30)).add_tx(test_tx(tx3, 20));
+
+        let tx_hashes: Vec<TxHash> = mempool.get_top_gas_price_txs(2).iter().map(|tx| tx.tx_hash).collect();
+        assert_eq!(tx_hashes, vec![tx2, tx3]);
+
+        // Mined and removed transactions are not pending
+        mempool.set_mined(tx2, 100);
+        mempool.remove_tx(&tx3);
+        let tx_hashes: Vec<TxHash> = mempool.get_top_gas_price_txs(2).iter().map(|tx| tx.tx_hash).collect();
+        assert_eq!(tx_hashes, vec![tx1]);
+    }
+
+    #[test]
+    fn test_gas_price_index_max_len() {
+        let mut mempool = Mempool::<LoomDataTypesEthereum>::new();
+        for i in 0..=MEMPOOL_GAS_PRICE_INDEX_MAX_LEN {
+            mempool.index_gas_price(TxHash::left_padding_from(&i.to_be_bytes()), i as u128);
+        }
+        assert_eq!(mempool.gas_price_index.len(), MEMPOOL_GAS_PRICE_INDEX_MAX_LEN);
+        assert_eq!(mempool.tx_gas_price.len(), MEMPOOL_GAS_PRICE_INDEX_MAX_LEN);
+        assert!(!mempool.tx_gas_price.contains_key(&TxHash::ZERO));
+
+        // Repricing a transaction replaces its index entry
+        mempool.index_gas_price(TxHash::left_padding_from(&1usize.to_be_bytes()), 1_000_000);
+        assert_eq!(mempool.gas_price_index.last(), Some(&(1_000_000, TxHash::left_padding_from(&1usize.to_be_bytes()))));
+        assert_eq!(mempool.gas_price_index.len(), MEMPOOL_GAS_PRICE_INDEX_MAX_LEN);
     }
 }
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use alloy_primitives::{Address, U256};
 use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
@@ -7,11 +7,15 @@ use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
 pub struct AccountNonceAndBalances<LDT: LoomDataTypes = LoomDataTypesEthereum> {
     nonce: u64,
     balance: HashMap<LDT::Address, U256>,
+    // Nonces handed out to composed transactions that are not mined yet, with the number of composes holding each
+    reserved_nonces: BTreeMap<u64, usize>,
+    // Nonce shared by the alternative composes of a group
+    nonce_groups: HashMap<u64, u64>,
 }
 
 impl<LDT: LoomDataTypes> AccountNonceAndBalances<LDT> {
     pub fn new() -> Self {
-        Self { nonce: 0, balance: HashMap::new() }
+        Self { nonce: 0, balance: HashMap::new(), reserved_nonces: BTreeMap::new(), nonce_groups: HashMap::new() }
     }
 
     pub fn get_nonce(&self) -> u64 {
@@ -20,9 +24,47 @@ impl<LDT: LoomDataTypes> AccountNonceAndBalances<LDT> {
 
     pub fn set_nonce(&mut self, nonce: u64) -> &mut Self {
         self.nonce = nonce;
+        self.reserved_nonces = self.reserved_nonces.split_off(&nonce);
+        self.nonce_groups.retain(|_, group_nonce| *group_nonce >= nonce);
         self
     }
 
+    /// Reserves the lowest nonce starting from the account nonce that is not reserved yet. Alternative composes of the same
+    /// `group` share their nonce, at most one of them can land.
+    pub fn reserve_nonce(&mut self, group: u64) -> u64 {
+        if let Some(&nonce) = self.nonce_groups.get(&group) {
+            if let Some(holders) = self.reserved_nonces.get_mut(&nonce) {
+                *holders += 1;
+                return nonce;
+            }
+        }
+        let mut nonce = self.nonce;
+        while self.reserved_nonces.contains_key(&nonce) {
+            nonce += 1;
+        }
+        self.reserved_nonces.insert(nonce, 1);
+        self.nonce_groups.insert(group, nonce);
+        nonce
+    }
+
+    /// Returns an unused nonce, it will be reserved again by the next `reserve_nonce` once no compose holds it
+    pub fn release_nonce(&mut self, nonce: u64) -> bool {
+        let Some(holders) = self.reserved_nonces.get_mut(&nonce) else {
+            return false;
+        };
+        *holders -= 1;
+        if *holders == 0 {
+            self.reserved_nonces.remove(&nonce);
+            self.nonce_groups.retain(|_, group_nonce| *group_nonce != nonce);
+        }
+        true
+    }
+
+    pub fn clear_reserved_nonces(&mut self) {
+        self.reserved_nonces.clear();
+        self.nonce_groups.clear();
+    }
+
     pub fn set_balance(&mut self, token: LDT::Address, balance: U256) -> &mut Self {
         let entry = self.balance.entry(token).or_default();
         *entry = balance;
@@ -86,4 +128,66 @@ impl AccountNonceAndBalanceState {
     pub fn get_entry_or_default(&mut self, account: Address) -> &mut AccountNonceAndBalances {
         self.accounts.entry(account).or_default()
     }
+
+    /// Reserves the next nonce of the account, so transactions composed concurrently get different nonces unless they are
+    /// alternatives of the same `group`
+    pub fn reserve_nonce(&mut self, account: Address, group: u64) -> u64 {
+        self.get_entry_or_default(account).reserve_nonce(group)
+    }
+
+    pub fn release_nonce(&mut self, account: Address, nonce: u64) -> bool {
+        self.get_mut_account(&account).is_some_and(|entry| entry.release_nonce(nonce))
+    }
+
+    /// Drops reservations of all accounts, composed transactions target the next block only
+    pub fn clear_reserved_nonces(&mut self) {
+        self.accounts.values_mut().for_each(|entry| entry.clear_reserved_nonces());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reserve_nonce() {
+        let account = Address::repeat_byte(1);
+        let mut state = AccountNonceAndBalanceState::new();
+        state.add_account(account).set_nonce(10);
+
+        assert_eq!(state.reserve_nonce(account, 1), 10);
+        assert_eq!(state.reserve_nonce(account, 2), 11);
+        assert_eq!(state.reserve_nonce(account, 3), 12);
+
+        assert!(state.release_nonce(account, 11));
+        assert!(!state.release_nonce(account, 11));
+        assert_eq!(state.reserve_nonce(account, 4), 11);
+        assert_eq!(state.reserve_nonce(account, 5), 13);
+
+        // Mined nonces are not reserved anymore
+        state.get_mut_account(&account).unwrap().set_nonce(12);
+        assert_eq!(state.reserve_nonce(account, 6), 14);
+
+        state.clear_reserved_nonces();
+        assert_eq!(state.reserve_nonce(account, 1), 12);
+    }
+
+    #[test]
+    fn test_reserve_nonce_alternatives() {
+        let account = Address::repeat_byte(1);
+        let mut state = AccountNonceAndBalanceState::new();
+        state.add_account(account).set_nonce(10);
+
+        assert_eq!(state.reserve_nonce(account, 1), 10);
+        assert_eq!(state.reserve_nonce(account, 1), 10);
+        assert_eq!(state.reserve_nonce(account, 2), 11);
+
+        // The nonce stays reserved until every alternative released it
+        assert!(state.release_nonce(account, 10));
+        assert_eq!(state.reserve_nonce(account, 3), 12);
+        assert!(state.release_nonce(account, 10));
+        assert!(!state.release_nonce(account, 10));
+        assert_eq!(state.reserve_nonce(account, 4), 10);
+        assert_eq!(state.reserve_nonce(account, 1), 13);
+    }
 }
@@ -13,6 +13,23 @@ use loom_node_debug_provider::DebugProviderExt;
 use loom_types_blockchain::{debug_trace_block, GethStateUpdateVec};
 use tracing::{debug, error};
 
+// Account address, balance and nonce
+const ACCOUNT_STATE_SIZE: usize = 20 + 32 + 8;
+// Storage slot key and value
+const STORAGE_SLOT_SIZE: usize = 32 + 32;
+
+/// State update size limit of the block histories of a blockchain state
+pub const DEFAULT_MAX_STATE_SIZE_BYTES: usize = 512 * 1024 * 1024;
+
+/// Estimated size of a state update as it is serialized, code and storage dominate it
+pub fn state_update_size_bytes(state_update: &GethStateUpdateVec) -> usize {
+    state_update
+        .iter()
+        .flat_map(|update| update.values())
+        .map(|account| ACCOUNT_STATE_SIZE + account.code.as_ref().map_or(0, |code| code.len()) + account.storage.len() * STORAGE_SLOT_SIZE)
+        .sum()
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct BlockHistoryEntry {
     pub header: Header,
@@ -55,6 +72,7 @@ impl BlockHistoryEntry {
 #[derive(Debug, Clone)]
 pub struct BlockHistory<S> {
     depth: usize,
+    max_state_size_bytes: usize,
     pub latest_block_number: u64,
     block_states: HashMap<BlockHash, S>,
     block_entries: HashMap<BlockHash, BlockHistoryEntry>,
@@ -66,8 +84,14 @@ where
     S: BlockHistoryState,
 {
     pub fn new(depth: usize) -> BlockHistory<S> {
+        Self::new_with_limits(depth, usize::MAX)
+    }
+
+    /// History that drops state updates of the oldest blocks when their total size exceeds `max_state_size_bytes`
+    pub fn new_with_limits(depth: usize, max_state_size_bytes: usize) -> BlockHistory<S> {
         BlockHistory::<S> {
             depth,
+            max_state_size_bytes,
             latest_block_number: 0,
             block_states: Default::default(),
             block_entries: Default::default(),
@@ -127,6 +151,50 @@ impl<S> BlockHistory<S> {
         }
     }
 
+    /// Latest block that `old_tip` and `new_tip` both descend from, found by walking parents of blocks in the history
+    pub fn find_common_ancestor(&self, old_tip: &BlockHash, new_tip: &BlockHash) -> Option<&BlockHistoryEntry> {
+        let mut old_entry = self.block_entries.get(old_tip)?;
+        let mut new_entry = self.block_entries.get(new_tip)?;
+
+        while old_entry.hash() != new_entry.hash() {
+            if old_entry.number() >= new_entry.number() {
+                old_entry = self.block_entries.get(&old_entry.parent_hash())?;
+            }
+            if new_entry.number() > old_entry.number() {
+                new_entry = self.block_entries.get(&new_entry.parent_hash())?;
+            }
+        }
+        Some(old_entry)
+    }
+
+    /// Drops blocks of the branch ending at `old_tip` down to the common ancestor with `new_tip` and makes the branch of `new_tip`
+    /// canonical. Returns the number of dropped blocks and the common ancestor hash, None if the ancestor is not in the history.
+    pub fn rollback_to_common_ancestor(&mut self, old_tip: &BlockHash, new_tip: &BlockHash) -> Option<(usize, BlockHash)> {
+        let ancestor = self.find_common_ancestor(old_tip, new_tip)?;
+        let (ancestor_number, ancestor_hash) = (ancestor.number(), ancestor.hash());
+
+        let mut block_hash = *new_tip;
+        while block_hash != ancestor_hash {
+            let entry = self.block_entries.get(&block_hash)?;
+            let (number, parent_hash) = (entry.number(), entry.parent_hash());
+            self.block_numbers.insert(number, block_hash);
+            block_hash = parent_hash;
+        }
+
+        let mut depth = 0;
+        let mut block_hash = *old_tip;
+        while block_hash != ancestor_hash {
+            let entry = self.block_entries.remove(&block_hash)?;
+            self.block_states.remove(&block_hash);
+            self.block_numbers.retain(|_, hash| *hash != block_hash);
+            block_hash = entry.parent_hash();
+            depth += 1;
+        }
+
+        debug!(depth, ancestor_number, %ancestor_hash, %old_tip, %new_tip, "Block history rolled back");
+        Some((depth, ancestor_hash))
+    }
+
     pub fn add_block_header(&mut self, block_header: Header) -> Result<bool> {
         let block_hash = block_header.hash;
         let block_number = block_header.number;
@@ -244,6 +312,47 @@ impl<S> BlockHistory<S> {
     pub fn contains_block(&self, block_hash: &BlockHash) -> bool {
         self.block_entries.contains_key(block_hash)
     }
+
+    pub fn max_state_size_bytes(&self) -> usize {
+        self.max_state_size_bytes
+    }
+
+    pub fn state_size_bytes(&self) -> usize {
+        self.block_entries.values().filter_map(|entry| entry.state_update.as_ref()).map(state_update_size_bytes).sum()
+    }
+
+    /// Evicts state updates starting from the oldest block until the total size fits the limit.
+    /// State update of the latest block is never evicted. Returns the number of evicted state updates.
+    pub fn prune_if_needed(&mut self) -> usize {
+        if self.max_state_size_bytes == usize::MAX {
+            return 0;
+        }
+
+        let mut state_size = self.state_size_bytes();
+        let mut pruned = 0;
+        while state_size > self.max_state_size_bytes {
+            let Some(oldest_block_hash) = self
+                .block_entries
+                .values()
+                .filter(|entry| entry.state_update.is_some() && entry.number() < self.latest_block_number)
+                .min_by_key(|entry| entry.number())
+                .map(|entry| entry.hash())
+            else {
+                break;
+            };
+
+            let Some(state_update) = self.block_entries.get_mut(&oldest_block_hash).and_then(|entry| entry.state_update.take()) else {
+                break;
+            };
+            state_size -= state_update_size_bytes(&state_update);
+            pruned += 1;
+        }
+
+        if pruned > 0 {
+            debug!(pruned, state_size, max_state_size = self.max_state_size_bytes, "Block history state updates pruned");
+        }
+        pruned
+    }
 }
 
 pub struct BlockHistoryManager<P, D> {
@@ -343,13 +452,17 @@ where
         block_entries.insert(block_hash, block_entry);
         block_states.insert(block_hash, current_state);
 
-        BlockHistory { depth, latest_block_number, block_states, block_entries, block_numbers }
+        BlockHistory { depth, max_state_size_bytes: usize::MAX, latest_block_number, block_states, block_entries, block_numbers }
     }
 
     pub fn new(client: P) -> Self {
         Self { client, _td: PhantomData }
     }
 
+    pub fn client(&self) -> &P {
+        &self.client
+    }
+
     pub async fn fetch_entry_data(&self, entry: &mut BlockHistoryEntry) -> Result<()>
     where
         P: Provider<Ethereum> + DebugProviderExt<Ethereum> + Send + Sync + Clone + 'static,
@@ -549,6 +662,72 @@ mod test {
         assert_eq!(block_history.block_numbers[&4], header_4_1.hash);
     }
 
+    #[test]
+    fn test_rollback_to_common_ancestor() {
+        let mut block_history = BlockHistory::<LoomDBType>::new(10);
+
+        let header_1_0 = create_header(1, U256::from(1).into());
+        let header_2_0 = create_next_header(&header_1_0, 0);
+        let header_3_0 = create_next_header(&header_2_0, 0);
+        let header_2_1 = create_next_header(&header_1_0, 1);
+        let header_3_1 = create_next_header(&header_2_1, 0);
+
+        for header in [&header_1_0, &header_2_0, &header_3_0] {
+            block_history.add_block_header(header.clone()).unwrap();
+            block_history.add_db(header.hash, LoomDBType::default()).unwrap();
+        }
+        block_history.add_block_header(header_2_1.clone()).unwrap();
+        block_history.add_block_header(header_3_1.clone()).unwrap();
+
+        assert_eq!(block_history.find_common_ancestor(&header_3_0.hash, &header_3_1.hash).unwrap().hash(), header_1_0.hash);
+
+        // Blocks 2 and 3 are replaced
+        assert_eq!(block_history.rollback_to_common_ancestor(&header_3_0.hash, &header_3_1.hash), Some((2, header_1_0.hash)));
+
+        assert_eq!(block_history.len(), 3);
+        assert!(!block_history.contains_block(&header_2_0.hash));
+        assert!(!block_history.contains_block(&header_3_0.hash));
+        assert!(block_history.get_block_state(&header_2_0.hash).is_none());
+        assert!(block_history.get_block_state(&header_3_0.hash).is_none());
+        assert!(block_history.get_block_state(&header_1_0.hash).is_some());
+        assert_eq!(block_history.get_block_hash_for_block_number(1), Some(header_1_0.hash));
+        assert_eq!(block_history.get_block_hash_for_block_number(2), Some(header_2_1.hash));
+        assert_eq!(block_history.get_block_hash_for_block_number(3), Some(header_3_1.hash));
+
+        // Unknown old tip
+        assert_eq!(block_history.rollback_to_common_ancestor(&header_3_0.hash, &header_3_1.hash), None);
+    }
+
+    #[test]
+    fn test_prune_state_updates() {
+        let state_update = vec![geth_state_update_add_account(
+            GethStateUpdate::default(),
+            Address::repeat_byte(1),
+            account_state_with_nonce_and_balance(1, U256::from(2)),
+        )];
+        let update_size = state_update_size_bytes(&state_update);
+        let max_state_size = update_size * 10;
+
+        let mut block_history = BlockHistory::<LoomDBType>::new_with_limits(1000, max_state_size);
+
+        for block_number in 1..=100 {
+            let header = create_header(block_number, U256::from(block_number).into());
+            block_history.add_block_header(header.clone()).unwrap();
+            block_history.add_state_diff(header.hash, state_update.clone()).unwrap();
+            block_history.prune_if_needed();
+            assert!(block_history.state_size_bytes() <= max_state_size);
+        }
+
+        assert_eq!(block_history.len(), 100);
+        assert_eq!(block_history.state_size_bytes(), max_state_size);
+
+        // The latest blocks keep their state updates
+        let latest_block_hash = block_history.get_block_hash_for_block_number(block_history.latest_block_number).unwrap();
+        assert!(block_history.get_block_history_entry(&latest_block_hash).unwrap().state_update.is_some());
+        let oldest_block_hash = block_history.get_block_hash_for_block_number(1).unwrap();
+        assert!(block_history.get_block_history_entry(&oldest_block_hash).unwrap().state_update.is_none());
+    }
+
     #[tokio::test]
     async fn test_with_anvil() -> Result<()> {
         let anvil = Anvil::new().try_spawn()?;
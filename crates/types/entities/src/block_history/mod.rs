@@ -1,4 +1,4 @@
-pub use block_history_impl::{BlockHistory, BlockHistoryEntry, BlockHistoryManager};
+pub use block_history_impl::{state_update_size_bytes, BlockHistory, BlockHistoryEntry, BlockHistoryManager, DEFAULT_MAX_STATE_SIZE_BYTES};
 pub use block_history_state::BlockHistoryState;
 
 mod block_history_impl;
@@ -0,0 +1,72 @@
+use std::collections::VecDeque;
+
+use alloy_primitives::{BlockNumber, TxHash, I256};
+
+/// Confirmed bundles kept in `BundleHistory`
+const BUNDLE_HISTORY_CAPACITY: usize = 100;
+
+/// Own backrun transaction found in a mined block
+#[derive(Clone, Debug)]
+pub struct ConfirmedBundle {
+    pub tx_hash: TxHash,
+    pub block_number: BlockNumber,
+    /// Index of the transaction in the block
+    pub position: usize,
+    pub gas_used: u64,
+    /// WETH balance change of the multicaller or of the EIP-7702 EOA net of the gas cost, negative for losing bundles
+    pub realized_profit_wei: I256,
+}
+
+/// Latest confirmed bundles, the oldest one is dropped when `BUNDLE_HISTORY_CAPACITY` is reached
+#[derive(Clone, Debug, Default)]
+pub struct BundleHistory {
+    bundles: VecDeque<ConfirmedBundle>,
+}
+
+impl BundleHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, bundle: ConfirmedBundle) {
+        if self.bundles.len() >= BUNDLE_HISTORY_CAPACITY {
+            self.bundles.pop_front();
+        }
+        self.bundles.push_back(bundle);
+    }
+
+    /// Confirmed bundles from the oldest to the latest
+    pub fn bundles(&self) -> impl DoubleEndedIterator<Item = &ConfirmedBundle> {
+        self.bundles.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.bundles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bundles.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bundle_history_capacity() {
+        let mut history = BundleHistory::new();
+        for block_number in 0..BUNDLE_HISTORY_CAPACITY as u64 + 5 {
+            history.add(ConfirmedBundle {
+                tx_hash: TxHash::ZERO,
+                block_number,
+                position: 0,
+                gas_used: 150_000,
+                realized_profit_wei: I256::ZERO,
+            });
+        }
+        assert_eq!(history.len(), BUNDLE_HISTORY_CAPACITY);
+        assert_eq!(history.bundles().next().map(|bundle| bundle.block_number), Some(5));
+        assert_eq!(history.bundles().last().map(|bundle| bundle.block_number), Some(BUNDLE_HISTORY_CAPACITY as u64 + 4));
+    }
+}
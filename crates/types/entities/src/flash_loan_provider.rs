@@ -0,0 +1,69 @@
+use std::fmt::{Display, Formatter};
+
+use crate::{PoolClass, PoolWrapper};
+use alloy_primitives::U256;
+use loom_types_blockchain::LoomDataTypes;
+
+const FEE_PIPS_DENOMINATOR: u64 = 1_000_000;
+
+/// Lender of the borrowed capital of a `Swap::FlashLoanSwapLine`, only lenders with a callback implemented by the multicaller
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FlashLoanProvider {
+    /// Balancer V2 vault `flashLoan` without fee, the multicaller repays the vault at the end of `receiveFlashLoan`
+    BalancerV2,
+}
+
+impl FlashLoanProvider {
+    /// Fee in pips, 1/1_000_000 of the borrowed amount
+    pub fn fee_pips(&self) -> u64 {
+        match self {
+            Self::BalancerV2 => 0,
+        }
+    }
+
+    /// Fee for borrowing `amount`, rounded up as the lenders do
+    pub fn fee(&self, amount: U256) -> U256 {
+        let fee = amount.saturating_mul(U256::from(self.fee_pips()));
+        let denominator = U256::from(FEE_PIPS_DENOMINATOR);
+        if fee.is_zero() {
+            U256::ZERO
+        } else {
+            (fee - U256::from(1)) / denominator + U256::from(1)
+        }
+    }
+
+    /// Pools that can not be swapped inside the flash loan callback, the Balancer vault is locked against reentrancy
+    /// until `flashLoan` returns
+    pub fn is_pool_locked<LDT: LoomDataTypes>(&self, pool: &PoolWrapper<LDT>) -> bool {
+        match self {
+            Self::BalancerV2 => pool.get_class() == PoolClass::BalancerV2,
+        }
+    }
+}
+
+impl Display for FlashLoanProvider {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BalancerV2 => write!(f, "BalancerV2"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MockPool;
+    use alloy_primitives::Address;
+
+    #[test]
+    fn test_flash_loan_fee() {
+        assert_eq!(FlashLoanProvider::BalancerV2.fee(U256::from(1_000_000)), U256::ZERO);
+        assert_eq!(FlashLoanProvider::BalancerV2.fee(U256::ZERO), U256::ZERO);
+    }
+
+    #[test]
+    fn test_is_pool_locked() {
+        let pool: PoolWrapper = MockPool::new(Address::repeat_byte(1), Address::repeat_byte(2), Address::repeat_byte(10)).into();
+        assert!(!FlashLoanProvider::BalancerV2.is_pool_locked(&pool));
+    }
+}
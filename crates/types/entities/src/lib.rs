@@ -3,29 +3,36 @@
 extern crate core;
 
 pub use account_nonce_balance::{AccountNonceAndBalanceState, AccountNonceAndBalances};
-pub use block_history::{BlockHistory, BlockHistoryEntry, BlockHistoryManager, BlockHistoryState};
+pub use block_history::{BlockHistory, BlockHistoryEntry, BlockHistoryManager, BlockHistoryState, DEFAULT_MAX_STATE_SIZE_BYTES};
+pub use bundle_history::{BundleHistory, ConfirmedBundle};
 pub use calculation_result::CalculationResult;
 pub use datafetcher::{DataFetcher, FetchState};
+pub use flash_loan_provider::FlashLoanProvider;
 pub use keystore::KeyStore;
 pub use latest_block::LatestBlock;
 pub use market::Market;
 pub use market_state::MarketState;
 pub use mock_pool::MockPool;
+pub use pnl_history::{PathSimulationStats, PnlHistory};
 pub use pool::{get_protocol_by_factory, Pool, PoolAbiEncoder, PoolClass, PoolProtocol, PoolWrapper, PreswapRequirement};
 pub use pool_id::PoolId;
 pub use pool_loader::{PoolLoader, PoolLoaders};
+pub use pool_metrics::PoolMetrics;
+pub use pool_stats::PoolStats;
 pub use signers::{LoomTxSigner, TxSignerEth, TxSigners};
 pub use swap::Swap;
 pub use swap_direction::SwapDirection;
-pub use swap_encoder::SwapEncoder;
+pub use swap_encoder::{Eip7702SwapEncoder, SwapEncoder};
 pub use swap_error::{EstimationError, SwapError};
 pub use swap_line::{SwapAmountType, SwapLine};
 pub use swap_path::{SwapPath, SwapPaths};
 pub use swap_path_builder::build_swap_path_vec;
+pub use swap_path_cache::SwapPathsCache;
 pub use swap_step::SwapStep;
 pub use token::{Token, TokenWrapper};
 
 mod block_history;
+mod bundle_history;
 mod latest_block;
 mod market;
 mod market_state;
@@ -37,6 +44,7 @@ mod token;
 pub mod account_nonce_balance;
 pub mod required_state;
 mod swap_path_builder;
+mod swap_path_cache;
 mod swap_step;
 
 mod signers;
@@ -47,13 +55,17 @@ pub mod private;
 
 mod calculation_result;
 mod datafetcher;
+mod flash_loan_provider;
 mod mock_pool;
 pub mod strategy_config;
 
 mod mock_pool_generic;
+mod pnl_history;
 pub mod pool_config;
 mod pool_id;
 mod pool_loader;
+mod pool_metrics;
+mod pool_stats;
 mod swap;
 mod swap_direction;
 mod swap_encoder;
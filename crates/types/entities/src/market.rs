@@ -2,15 +2,18 @@
 
 use alloy_primitives::map::HashMap;
 use alloy_primitives::U256;
-use eyre::{eyre, OptionExt, Result};
-use std::collections::BTreeMap;
+use eyre::{eyre, ErrReport, OptionExt, Result};
+use revm::primitives::Env;
+use revm::DatabaseRef;
+use std::collections::{BTreeMap, VecDeque};
 use std::fmt::Display;
+use std::path::Path;
 use std::sync::Arc;
 use tracing::debug;
 
 use crate::{build_swap_path_vec, PoolId, SwapDirection};
-use crate::{PoolClass, PoolWrapper, Token};
-use crate::{SwapPath, SwapPaths};
+use crate::{PoolClass, PoolMetrics, PoolProtocol, PoolStats, PoolWrapper, Token};
+use crate::{SwapPath, SwapPaths, SwapPathsCache};
 use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
 
 /// The market struct contains all the pools and tokens.
@@ -34,10 +37,61 @@ pub struct Market<LDT: LoomDataTypes = LoomDataTypesEthereum> {
     token_token_pools: HashMap<LDT::Address, HashMap<LDT::Address, Vec<PoolId<LDT>>>>,
     // token -> pool
     token_pools: HashMap<LDT::Address, Vec<PoolId<LDT>>>,
+    // (protocol, token) -> pool
+    protocol_token_pools: HashMap<(PoolProtocol, LDT::Address), Vec<PoolId<LDT>>>,
     // swap_paths
     swap_paths: SwapPaths<LDT>,
+    // pool_address -> tvl and reliability metrics
+    pool_metrics: HashMap<PoolId<LDT>, PoolMetrics>,
+    // pool_address -> number of swaps seen
+    pool_swap_count: HashMap<PoolId<LDT>, u64>,
+    // pool_address -> swap volume in ETH
+    pool_volume_eth: HashMap<PoolId<LDT>, U256>,
+    // pool_address -> block of the last swap
+    pool_last_block: HashMap<PoolId<LDT>, u64>,
+    // pool_address -> (block timestamp, swap volume in ETH, competing arbitrage transactions) of the blocks in the depth score
+    // window, oldest first
+    pool_window_activity: HashMap<PoolId<LDT>, VecDeque<(u64, U256, u64)>>,
+    // latest block timestamp of the pool activity
+    pool_activity_timestamp: u64,
+    // pool_address -> spot price of the second pool token in the first one
+    pool_spot_prices: HashMap<PoolId<LDT>, f64>,
+    // SwapPath::fingerprint -> blocks with a profitable swap on the path, oldest first. The last profitable block is
+    // kept after it leaves the hit rate window
+    path_profitable_blocks: HashMap<[u8; 32], VecDeque<u64>>,
+    // latest block seen by the profitability history
+    block_number: u64,
+    // block the path scores were last decayed at
+    path_scores_decayed_block: u64,
 }
 
+// Window of the swap volume and competition of the depth score, 24h
+const DEPTH_SCORE_WINDOW_SECS: u64 = 86_400;
+// TVL, window swap volume and competing arbitrage transactions at which the score component reaches one half
+const DEPTH_SCORE_TVL_HALF_USD: f64 = 1_000_000.0;
+const DEPTH_SCORE_VOLUME_HALF_ETH: f64 = 100.0;
+const DEPTH_SCORE_COMPETITION_HALF_TXS: f64 = 10.0;
+
+const DEPTH_SCORE_TVL_WEIGHT: f64 = 0.4;
+const DEPTH_SCORE_VOLUME_WEIGHT: f64 = 0.3;
+const DEPTH_SCORE_COMPETITION_WEIGHT: f64 = 0.2;
+const DEPTH_SCORE_RELIABILITY_WEIGHT: f64 = 0.1;
+
+// Number of blocks the hit rate of a path is computed over
+const ARBITRAGE_SCORE_HIT_RATE_BLOCKS: u64 = 100;
+// Shortest cycle, gets the full length component
+const ARBITRAGE_SCORE_MIN_POOLS: f64 = 2.0;
+// TVL at which the logarithmic TVL component of a pool reaches one
+const ARBITRAGE_SCORE_MAX_TVL_USD: f64 = 1_000_000_000.0;
+// Geometric mean deviation of the cycle rates at which the deviation component reaches one half
+const ARBITRAGE_SCORE_DEVIATION_HALF: f64 = 0.005;
+
+// A path without TVL, hit rate or deviation data scores below 0.1 whatever its length
+const ARBITRAGE_SCORE_LENGTH_WEIGHT: f64 = 0.08;
+const ARBITRAGE_SCORE_TVL_WEIGHT: f64 = 0.22;
+const ARBITRAGE_SCORE_HIT_RATE_WEIGHT: f64 = 0.3;
+const ARBITRAGE_SCORE_DEVIATION_WEIGHT: f64 = 0.4;
+
 impl<LDT: LoomDataTypes> Display for Market<LDT> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let token_token_len = self.token_tokens.values().map(|inner| inner.len()).sum::<usize>();
@@ -117,11 +171,66 @@ impl<LDT: LoomDataTypes> Market<LDT> {
             self.token_pools.entry(*swap_direction.from()).or_default().push(pool_address);
         }
 
+        let protocol = pool_contract.get_protocol();
+        for token_address in pool_contract.get_tokens() {
+            let protocol_pools = self.protocol_token_pools.entry((protocol, token_address)).or_default();
+            if !protocol_pools.contains(&pool_address) {
+                protocol_pools.push(pool_address);
+            }
+        }
+
         self.pools.insert(pool_address, pool_contract);
 
         Ok(())
     }
 
+    /// Remove the pool from the market and disable all swap paths containing it, returns false if the pool is unknown.
+    /// The pool stays in the disabled pools list.
+    pub fn remove_pool(&mut self, pool_id: &PoolId<LDT>) -> bool {
+        if !self.disable_pool(*pool_id) {
+            return false;
+        }
+        let Some(pool_contract) = self.pools.remove(pool_id) else {
+            return false;
+        };
+
+        debug!("Removing pool {:?}", pool_id);
+
+        for swap_direction in pool_contract.get_swap_directions().into_iter() {
+            if let Some(token_pools) = self.token_token_pools.get_mut(swap_direction.from()).and_then(|m| m.get_mut(swap_direction.to())) {
+                token_pools.retain(|id| id != pool_id);
+            }
+            if let Some(token_tokens) = self.token_tokens.get_mut(swap_direction.from()) {
+                if let Some(idx) = token_tokens.iter().position(|token| token == swap_direction.to()) {
+                    token_tokens.remove(idx);
+                }
+            }
+            if let Some(token_pools) = self.token_pools.get_mut(swap_direction.from()) {
+                token_pools.retain(|id| id != pool_id);
+            }
+        }
+
+        let protocol = pool_contract.get_protocol();
+        for token_address in pool_contract.get_tokens() {
+            if let Some(protocol_pools) = self.protocol_token_pools.get_mut(&(protocol, token_address)) {
+                protocol_pools.retain(|id| id != pool_id);
+            }
+        }
+
+        for cells in self.pools_manager_cells.values_mut() {
+            cells.retain(|_, id| id != pool_id);
+        }
+
+        self.pool_metrics.remove(pool_id);
+        self.pool_swap_count.remove(pool_id);
+        self.pool_volume_eth.remove(pool_id);
+        self.pool_last_block.remove(pool_id);
+        self.pool_window_activity.remove(pool_id);
+        self.pool_spot_prices.remove(pool_id);
+
+        true
+    }
+
     /// Add a swap path to the market.
     pub fn add_paths(&mut self, paths: Vec<SwapPath<LDT>>) -> Vec<usize> {
         paths.into_iter().filter_map(|path| self.swap_paths.add(path)).collect()
@@ -217,11 +326,47 @@ impl<LDT: LoomDataTypes> Market<LDT> {
         self.swap_paths.disable_pool_paths(&address, &token_from, &token_to, disabled);
     }
 
+    /// Disable all swap directions of the pool, returns false if the pool is unknown.
+    pub fn disable_pool(&mut self, pool_id: PoolId<LDT>) -> bool {
+        self.set_pool_disabled_all_directions(pool_id, true)
+    }
+
+    /// Enable all swap directions of the pool, returns false if the pool is unknown.
+    pub fn enable_pool(&mut self, pool_id: PoolId<LDT>) -> bool {
+        self.set_pool_disabled_all_directions(pool_id, false)
+    }
+
+    fn set_pool_disabled_all_directions(&mut self, pool_id: PoolId<LDT>, disabled: bool) -> bool {
+        let Some(pool) = self.pools.get(&pool_id) else {
+            return false;
+        };
+        for swap_direction in pool.get_swap_directions() {
+            self.swap_paths.disable_pool_paths(&pool_id, swap_direction.from(), swap_direction.to(), disabled);
+        }
+        if disabled {
+            self.pools_disabled.insert(pool_id, true);
+        } else {
+            self.pools_disabled.remove(&pool_id);
+        }
+        true
+    }
+
     /// Set path status to ok or not ok.
     pub fn set_path_disabled(&mut self, swap_path: &SwapPath<LDT>, disabled: bool) -> bool {
         self.swap_paths.disable_path(swap_path, disabled)
     }
 
+    /// Set the score of the swap path with index `path_idx`, returns false if the path does not exist.
+    pub fn update_path_score(&mut self, path_idx: usize, score: f64) -> bool {
+        match self.swap_paths.get_path_by_idx_mut(path_idx) {
+            Some(swap_path) => {
+                swap_path.score = Some(score);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Check if the pool is ok.
     #[inline]
     pub fn is_pool_disabled(&self, address: &PoolId<LDT>) -> bool {
@@ -245,6 +390,14 @@ impl<LDT: LoomDataTypes> Market<LDT> {
         self.token_pools.get(token_address)
     }
 
+    /// Get all pools of the protocol for a token.
+    pub fn get_token_pools_by_protocol(&self, token_address: &LDT::Address, protocol: PoolProtocol) -> Vec<PoolWrapper<LDT>> {
+        self.protocol_token_pools
+            .get(&(protocol, *token_address))
+            .map(|pool_ids| pool_ids.iter().filter_map(|pool_id| self.pools.get(pool_id).cloned()).collect())
+            .unwrap_or_default()
+    }
+
     /// Get all pool addresses as reference that allow to swap `token_address`.
     pub fn get_token_pools_len(&self, token_address: &LDT::Address) -> usize {
         self.token_pools.get(token_address).map_or(0, |t| t.len())
@@ -273,6 +426,11 @@ impl<LDT: LoomDataTypes> Market<LDT> {
         self.pools_disabled.len()
     }
 
+    /// Ids of the disabled and removed pools.
+    pub fn get_disabled_pools(&self) -> Vec<PoolId<LDT>> {
+        self.pools_disabled.iter().filter(|(_, &is_disabled)| is_disabled).map(|(pool_id, _)| *pool_id).collect()
+    }
+
     pub fn add_pool_manager_cell(&mut self, pool_manager_address: LDT::Address, pool_id: PoolId<LDT>, cell: U256) {
         let pool_manager_entry = self.pools_manager_cells.entry(pool_manager_address).or_default();
         pool_manager_entry.insert(cell, pool_id);
@@ -282,11 +440,320 @@ impl<LDT: LoomDataTypes> Market<LDT> {
         self.pools_manager_cells.contains_key(pool_manager_address)
     }
 
+    pub fn get_pool_metrics(&self, pool_id: &PoolId<LDT>) -> Option<&PoolMetrics> {
+        self.pool_metrics.get(pool_id)
+    }
+
+    pub fn set_pool_tvl(&mut self, pool_id: PoolId<LDT>, tvl_usd: f64) {
+        self.pool_metrics.entry(pool_id).or_default().tvl_usd = if tvl_usd.is_finite() { tvl_usd.max(0.0) } else { 0.0 };
+    }
+
+    pub fn set_pool_reliability(&mut self, pool_id: PoolId<LDT>, reliability: f64) {
+        self.pool_metrics.entry(pool_id).or_default().reliability = reliability.clamp(0.0, 1.0);
+    }
+
+    /// Count `swap_count` swaps of a block on the pool and add their volume, returns the updated swap count
+    pub fn record_pool_swaps(&mut self, pool_id: PoolId<LDT>, swap_count: u64, volume_eth: U256, block_number: u64) -> u64 {
+        let volume_entry = self.pool_volume_eth.entry(pool_id).or_default();
+        *volume_entry = volume_entry.saturating_add(volume_eth);
+        let last_block_entry = self.pool_last_block.entry(pool_id).or_default();
+        *last_block_entry = (*last_block_entry).max(block_number);
+        let swap_count_entry = self.pool_swap_count.entry(pool_id).or_default();
+        *swap_count_entry += swap_count;
+        *swap_count_entry
+    }
+
+    pub fn get_pool_stats(&self, pool_id: &PoolId<LDT>) -> Option<PoolStats> {
+        let swap_count = *self.pool_swap_count.get(pool_id)?;
+        Some(PoolStats {
+            swap_count,
+            volume_eth: self.pool_volume_eth.get(pool_id).cloned().unwrap_or_default(),
+            last_block: self.pool_last_block.get(pool_id).cloned().unwrap_or_default(),
+        })
+    }
+
+    /// Record the swap volume and the competing arbitrage transactions on the pool in the block with `timestamp`. Blocks
+    /// older than the depth score window are dropped.
+    pub fn record_pool_window_activity(&mut self, pool_id: PoolId<LDT>, timestamp: u64, volume_eth: U256, competing_swap_count: u64) {
+        self.pool_activity_timestamp = self.pool_activity_timestamp.max(timestamp);
+        let window_start = self.pool_activity_timestamp.saturating_sub(DEPTH_SCORE_WINDOW_SECS);
+        let activity = self.pool_window_activity.entry(pool_id).or_default();
+        activity.push_back((timestamp, volume_eth, competing_swap_count));
+        while activity.front().is_some_and(|(timestamp, _, _)| *timestamp <= window_start) {
+            activity.pop_front();
+        }
+    }
+
+    /// Swap volume in ETH and competing arbitrage transactions of the pool in the depth score window
+    fn get_pool_window_activity(&self, pool_id: &PoolId<LDT>) -> (f64, u64) {
+        let window_start = self.pool_activity_timestamp.saturating_sub(DEPTH_SCORE_WINDOW_SECS);
+        let Some(activity) = self.pool_window_activity.get(pool_id) else {
+            return (0.0, 0);
+        };
+        activity.iter().filter(|(timestamp, _, _)| *timestamp > window_start).fold(
+            (0.0, 0),
+            |(volume_eth, competing_txs), (_, block_volume_eth, block_competing_txs)| {
+                (volume_eth + block_volume_eth.saturating_to::<u128>() as f64 / 1e18, competing_txs + block_competing_txs)
+            },
+        )
+    }
+
+    /// Score in [0, 1] combining minimum pool TVL, average 24h swap volume per pool, inverse of the most competing arbitrage
+    /// transactions on a pool in 24h and minimum pool reliability. Pools without metrics count as zero TVL and full reliability.
+    pub fn compute_arbitrage_depth_score(&self, path: &SwapPath<LDT>) -> f64 {
+        if path.pools.is_empty() {
+            return 0.0;
+        }
+        let default_metrics = PoolMetrics::default();
+        let metrics: Vec<&PoolMetrics> =
+            path.pools.iter().map(|pool| self.pool_metrics.get(&pool.get_pool_id()).unwrap_or(&default_metrics)).collect();
+        let activity: Vec<(f64, u64)> = path.pools.iter().map(|pool| self.get_pool_window_activity(&pool.get_pool_id())).collect();
+
+        let min_tvl = metrics.iter().map(|m| m.tvl_usd.max(0.0)).fold(f64::INFINITY, f64::min);
+        let avg_volume_eth = activity.iter().map(|(volume_eth, _)| volume_eth).sum::<f64>() / path.pools.len() as f64;
+        let max_competing_txs = activity.iter().map(|(_, competing_swap_count)| *competing_swap_count).max().unwrap_or_default() as f64;
+        let min_reliability = metrics.iter().map(|m| m.reliability.clamp(0.0, 1.0)).fold(1.0, f64::min);
+
+        let tvl_score = min_tvl / (min_tvl + DEPTH_SCORE_TVL_HALF_USD);
+        let volume_score = avg_volume_eth / (avg_volume_eth + DEPTH_SCORE_VOLUME_HALF_ETH);
+        let competition_score = DEPTH_SCORE_COMPETITION_HALF_TXS / (max_competing_txs + DEPTH_SCORE_COMPETITION_HALF_TXS);
+
+        let score = DEPTH_SCORE_TVL_WEIGHT * tvl_score
+            + DEPTH_SCORE_VOLUME_WEIGHT * volume_score
+            + DEPTH_SCORE_COMPETITION_WEIGHT * competition_score
+            + DEPTH_SCORE_RELIABILITY_WEIGHT * min_reliability;
+        score.clamp(0.0, 1.0)
+    }
+
+    /// Set the spot price of the pool as the amount of the second pool token for one unit of the first one, decimals adjusted
+    pub fn set_pool_spot_price(&mut self, pool_id: PoolId<LDT>, price: f64) {
+        if price.is_finite() && price > 0.0 {
+            self.pool_spot_prices.insert(pool_id, price);
+        } else {
+            self.pool_spot_prices.remove(&pool_id);
+        }
+    }
+
+    pub fn get_pool_spot_price(&self, pool_id: &PoolId<LDT>) -> Option<f64> {
+        self.pool_spot_prices.get(pool_id).cloned()
+    }
+
+    /// Advance the block of the path profitability history, profits older than `ARBITRAGE_SCORE_HIT_RATE_BLOCKS` are dropped
+    /// except the last one of each path
+    pub fn set_block_number(&mut self, block_number: u64) {
+        if block_number <= self.block_number {
+            return;
+        }
+        self.block_number = block_number;
+        let min_block = self.block_number.saturating_sub(ARBITRAGE_SCORE_HIT_RATE_BLOCKS - 1);
+        for blocks in self.path_profitable_blocks.values_mut() {
+            while blocks.len() > 1 && blocks.front().is_some_and(|block| *block < min_block) {
+                blocks.pop_front();
+            }
+        }
+    }
+
+    /// Record a profitable swap on the path at `block_number`, several profits in one block count once
+    pub fn record_path_profit(&mut self, path: &SwapPath<LDT>, block_number: u64) {
+        self.set_block_number(block_number);
+        let blocks = self.path_profitable_blocks.entry(path.fingerprint()).or_default();
+        if blocks.back().map_or(true, |last_block| *last_block < block_number) {
+            blocks.push_back(block_number);
+        }
+    }
+
+    /// Share of the last `ARBITRAGE_SCORE_HIT_RATE_BLOCKS` blocks with a profitable swap on the path
+    pub fn get_path_hit_rate(&self, path: &SwapPath<LDT>) -> f64 {
+        let min_block = self.block_number.saturating_sub(ARBITRAGE_SCORE_HIT_RATE_BLOCKS - 1);
+        let profitable_blocks = self
+            .path_profitable_blocks
+            .get(&path.fingerprint())
+            .map_or(0, |blocks| blocks.iter().filter(|block| **block >= min_block).count());
+        profitable_blocks as f64 / ARBITRAGE_SCORE_HIT_RATE_BLOCKS as f64
+    }
+
+    /// Amount of the last path token for one unit of the first one at the pool spot prices, decimals adjusted.
+    /// None if a spot price of any pool is unknown.
+    pub fn get_path_spot_output(&self, path: &SwapPath<LDT>) -> Option<f64> {
+        if path.pools.is_empty() || path.tokens.len() != path.pools.len() + 1 {
+            return None;
+        }
+        let mut log_rate_sum = 0.0;
+        for (pool, token_from) in path.pools.iter().zip(path.tokens.iter()) {
+            let price = self.get_pool_spot_price(&pool.get_pool_id())?;
+            let pool_tokens = pool.get_tokens();
+            let rate = if pool_tokens.first() == Some(&token_from.get_address()) { price } else { 1.0 / price };
+            log_rate_sum += rate.ln();
+        }
+        Some(log_rate_sum.exp())
+    }
+
+    /// Geometric mean of the spot rates along the path minus one, positive when the cycle returns more than it takes.
+    /// None if a spot price of any pool is unknown.
+    pub fn get_path_price_deviation(&self, path: &SwapPath<LDT>) -> Option<f64> {
+        let output = self.get_path_spot_output(path)?;
+        Some((output.ln() / path.pools.len() as f64).exp() - 1.0)
+    }
+
+    /// All simple paths from `token_from` to `token_to` through enabled pools with at most `max_hops` pools. Paths are sorted
+    /// by the output of one unit of `token_from` calculated on `state`, paths that fail to calculate come last.
+    pub fn get_path_between_tokens(
+        &self,
+        state: &dyn DatabaseRef<Error = ErrReport>,
+        env: Env,
+        token_from: LDT::Address,
+        token_to: LDT::Address,
+        max_hops: usize,
+    ) -> Vec<SwapPath<LDT>> {
+        let mut paths = Vec::new();
+        if token_from != token_to {
+            self.collect_paths_between_tokens(token_to, max_hops, &mut vec![token_from], &mut Vec::new(), &mut paths);
+        }
+
+        let in_amount = U256::from(10).pow(U256::from(self.get_token_or_default(&token_from).get_decimals()));
+        let mut paths: Vec<(Option<U256>, SwapPath<LDT>)> =
+            paths.into_iter().map(|path| (Self::calculate_path_out_amount(state, &env, &path, in_amount), path)).collect();
+        paths.sort_by(|(out_amount, _), (other_out_amount, _)| other_out_amount.cmp(out_amount));
+        paths.into_iter().map(|(_, path)| path).collect()
+    }
+
+    // Output of the path for `in_amount` of the first token, None if any pool fails to calculate
+    fn calculate_path_out_amount(
+        state: &dyn DatabaseRef<Error = ErrReport>,
+        env: &Env,
+        path: &SwapPath<LDT>,
+        in_amount: U256,
+    ) -> Option<U256> {
+        let mut amount = in_amount;
+        for (pool, tokens) in path.pools.iter().zip(path.tokens.windows(2)) {
+            match pool.calculate_out_amount(state, env.clone(), &tokens[0].get_address(), &tokens[1].get_address(), amount) {
+                Ok((out_amount, _)) => amount = out_amount,
+                Err(error) => {
+                    debug!(%error, pool = %pool.get_address(), "Path out amount not calculated");
+                    return None;
+                }
+            }
+        }
+        Some(amount)
+    }
+
+    // Depth first search extending the path of `tokens` and `pools` by one pool
+    fn collect_paths_between_tokens(
+        &self,
+        token_to: LDT::Address,
+        max_hops: usize,
+        tokens: &mut Vec<LDT::Address>,
+        pools: &mut Vec<PoolId<LDT>>,
+        paths: &mut Vec<SwapPath<LDT>>,
+    ) {
+        let Some(next_token_pools) = tokens.last().and_then(|token| self.token_token_pools.get(token)) else {
+            return;
+        };
+        for (next_token, pool_ids) in next_token_pools.iter() {
+            if tokens.contains(next_token) {
+                continue;
+            }
+            for pool_id in pool_ids.iter().filter(|pool_id| !self.is_pool_disabled(pool_id)) {
+                tokens.push(*next_token);
+                pools.push(*pool_id);
+                if *next_token == token_to {
+                    paths.push(SwapPath {
+                        tokens: tokens.iter().map(|token| self.get_token_or_default(token)).collect(),
+                        pools: pools.iter().filter_map(|pool_id| self.pools.get(pool_id).cloned()).collect(),
+                        ..Default::default()
+                    });
+                } else if pools.len() < max_hops {
+                    self.collect_paths_between_tokens(token_to, max_hops, tokens, pools, paths);
+                }
+                tokens.pop();
+                pools.pop();
+            }
+        }
+    }
+
+    /// Score in [0, 1] of an arbitrage path combining the inverse path length, the sum of logarithmic pool TVL, the hit rate
+    /// over the last `ARBITRAGE_SCORE_HIT_RATE_BLOCKS` blocks and the deviation of the cycle rates from the equilibrium where
+    /// their geometric mean is one. Pools without metrics count as zero TVL, unknown spot prices give no deviation component.
+    pub fn get_arbitrage_score(&self, path: &SwapPath<LDT>) -> f64 {
+        if path.pools.is_empty() {
+            return 0.0;
+        }
+        let pools_len = path.pools.len() as f64;
+
+        let length_score = (ARBITRAGE_SCORE_MIN_POOLS / pools_len).min(1.0);
+
+        let log_tvl_sum: f64 = path
+            .pools
+            .iter()
+            .map(|pool| self.pool_metrics.get(&pool.get_pool_id()).map_or(0.0, |metrics| metrics.tvl_usd.max(0.0).ln_1p()))
+            .sum();
+        let tvl_score = log_tvl_sum / (pools_len * ARBITRAGE_SCORE_MAX_TVL_USD.ln_1p());
+
+        let hit_rate_score = self.get_path_hit_rate(path);
+
+        let deviation = self.get_path_price_deviation(path).unwrap_or_default().max(0.0);
+        let deviation_score = deviation / (deviation + ARBITRAGE_SCORE_DEVIATION_HALF);
+
+        let score = ARBITRAGE_SCORE_LENGTH_WEIGHT * length_score
+            + ARBITRAGE_SCORE_TVL_WEIGHT * tvl_score.min(1.0)
+            + ARBITRAGE_SCORE_HIT_RATE_WEIGHT * hit_rate_score
+            + ARBITRAGE_SCORE_DEVIATION_WEIGHT * deviation_score;
+        score.clamp(0.0, 1.0)
+    }
+
+    /// Block of the last profitable swap recorded on the path with `record_path_profit`
+    pub fn get_path_last_profit_block(&self, path: &SwapPath<LDT>) -> Option<u64> {
+        self.path_profitable_blocks.get(&path.fingerprint()).and_then(|blocks| blocks.back().cloned())
+    }
+
+    /// Decay scores of the paths by `0.5^((current_block - last_profit_block) / half_life_blocks)`. Only blocks since the
+    /// previous call are applied, so the decay does not compound when called every block. Paths never found profitable decay
+    /// from the first call on, returns the number of decayed paths.
+    pub fn decay_path_scores(&mut self, current_block: u64, half_life_blocks: u64) -> usize {
+        let decayed_block = self.path_scores_decayed_block;
+        if current_block <= decayed_block || half_life_blocks == 0 {
+            return 0;
+        }
+        self.path_scores_decayed_block = current_block;
+        if decayed_block == 0 {
+            return 0;
+        }
+
+        let mut decayed = 0;
+        for swap_path in self.swap_paths.paths.iter_mut() {
+            let Some(score) = swap_path.score else {
+                continue;
+            };
+            let last_profit_block = self.path_profitable_blocks.get(&swap_path.fingerprint()).and_then(|blocks| blocks.back());
+            let decay_from_block = last_profit_block.map_or(decayed_block, |b| (*b).max(decayed_block));
+            let blocks = current_block.saturating_sub(decay_from_block);
+            if blocks == 0 {
+                continue;
+            }
+            swap_path.score = Some(score * 0.5f64.powf(blocks as f64 / half_life_blocks as f64));
+            decayed += 1;
+        }
+        decayed
+    }
+
     pub fn get_pool_id_for_cell(&self, pool_manager_address: &LDT::Address, cell: &U256) -> Option<&PoolId<LDT>> {
         self.pools_manager_cells.get(pool_manager_address).and_then(|pool_manager_cell| pool_manager_cell.get(cell))
     }
 }
 
+impl Market<LoomDataTypesEthereum> {
+    /// Save all swap paths to `path` with the block number they were built at
+    pub fn save_paths_to_file(&self, path: &Path, block_number: u64) -> Result<()> {
+        SwapPathsCache::new(block_number, &self.swap_paths.paths).write_to_file(path)
+    }
+
+    /// Load swap paths saved with `save_paths_to_file`. Paths with pools that are not in the market are dropped.
+    pub fn load_paths_from_file(&mut self, path: &Path) -> Result<Vec<usize>> {
+        let swap_paths = SwapPathsCache::read_from_file(path)?.swap_paths(self);
+        Ok(self.add_paths(swap_paths))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -294,6 +761,7 @@ mod tests {
     use alloy_primitives::Address;
     use eyre::Result;
     use loom_defi_address_book::TokenAddressEth;
+    use revm::db::EmptyDBTyped;
 
     #[test]
     fn test_add_pool() {
@@ -301,7 +769,7 @@ mod tests {
         let pool_address = Address::random();
         let token0 = Address::random();
         let token1 = Address::random();
-        let mock_pool = MockPool { address: pool_address, token0, token1 };
+        let mock_pool = MockPool::new(token0, token1, pool_address);
 
         let result = market.add_pool(mock_pool);
 
@@ -317,6 +785,284 @@ mod tests {
 
         assert!(market.get_token_pools(&token0).unwrap().contains(&PoolId::Address(pool_address)));
         assert!(market.get_token_pools(&token1).unwrap().contains(&PoolId::Address(pool_address)));
+
+        let protocol_pools = market.get_token_pools_by_protocol(&token0, PoolProtocol::UniswapV2);
+        assert_eq!(protocol_pools.len(), 1);
+        assert_eq!(protocol_pools[0].get_pool_id(), PoolId::Address(pool_address));
+        assert!(market.get_token_pools_by_protocol(&token0, PoolProtocol::UniswapV3).is_empty());
+    }
+
+    #[test]
+    fn test_compute_arbitrage_depth_score() {
+        let mut market = Market::default();
+        let pool_address = Address::random();
+        let token0 = Address::random();
+        let token1 = Address::random();
+        let mock_pool = MockPool::new(token0, token1, pool_address);
+        let path = SwapPath::new(vec![Token::new(token0), Token::new(token1)], vec![mock_pool]);
+
+        let pool_id = PoolId::Address(pool_address);
+        let ether = U256::from(10).pow(U256::from(18));
+
+        // no metrics: no competition and full reliability only
+        assert!((market.compute_arbitrage_depth_score(&path) - 0.3).abs() < 1e-9);
+
+        // minimum TVL
+        market.set_pool_tvl(pool_id, 1_000_000.0);
+        assert!((market.compute_arbitrage_depth_score(&path) - 0.5).abs() < 1e-9);
+
+        // window volume, the lifetime volume is not counted
+        market.record_pool_swaps(pool_id, 1, U256::from(1000) * ether, 1);
+        assert!((market.compute_arbitrage_depth_score(&path) - 0.5).abs() < 1e-9);
+        market.record_pool_window_activity(pool_id, 1_000, U256::from(100) * ether, 0);
+        assert!((market.compute_arbitrage_depth_score(&path) - 0.65).abs() < 1e-9);
+
+        // competing arbitrage transactions
+        market.record_pool_window_activity(pool_id, 2_000, U256::ZERO, 10);
+        assert!((market.compute_arbitrage_depth_score(&path) - 0.55).abs() < 1e-9);
+
+        // reliability
+        market.set_pool_reliability(pool_id, 0.0);
+        assert!((market.compute_arbitrage_depth_score(&path) - 0.45).abs() < 1e-9);
+
+        // the volume leaves the window a day later, the competition a day after its block
+        market.record_pool_window_activity(PoolId::Address(Address::random()), 1_000 + 86_400, U256::ZERO, 0);
+        assert!((market.compute_arbitrage_depth_score(&path) - 0.3).abs() < 1e-9);
+        market.record_pool_window_activity(PoolId::Address(Address::random()), 2_000 + 86_400, U256::ZERO, 0);
+        assert!((market.compute_arbitrage_depth_score(&path) - 0.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_get_arbitrage_score() {
+        let mut market = Market::default();
+        let (token0, token1, token2) = (Address::repeat_byte(1), Address::repeat_byte(2), Address::repeat_byte(3));
+        let pool0 = MockPool::new(token0, token1, Address::repeat_byte(10));
+        let pool1 = MockPool::new(token1, token2, Address::repeat_byte(11));
+        let pool2 = MockPool::new(token0, token2, Address::repeat_byte(12));
+        let path =
+            SwapPath::new(vec![Token::new(token0), Token::new(token1), Token::new(token2), Token::new(token0)], vec![pool0, pool1, pool2]);
+
+        // no data: length component only, below the 0.1 pruning threshold
+        assert!((market.get_arbitrage_score(&path) - 0.08 * 2.0 / 3.0).abs() < 1e-9);
+        assert!(market.get_arbitrage_score(&path) < 0.1);
+
+        for pool_address in [Address::repeat_byte(10), Address::repeat_byte(11), Address::repeat_byte(12)] {
+            market.set_pool_tvl(PoolId::Address(pool_address), 1_000_000_000.0);
+        }
+        assert!((market.get_arbitrage_score(&path) - (0.08 * 2.0 / 3.0 + 0.22)).abs() < 1e-9);
+
+        // 1 token0 -> 2 token1 -> 4 token2 -> 1.01 token0
+        market.set_pool_spot_price(PoolId::Address(Address::repeat_byte(10)), 2.0);
+        market.set_pool_spot_price(PoolId::Address(Address::repeat_byte(11)), 2.0);
+        market.set_pool_spot_price(PoolId::Address(Address::repeat_byte(12)), 4.0 / 1.01);
+        assert!((market.get_path_spot_output(&path).unwrap() - 1.01).abs() < 1e-9);
+        let deviation = market.get_path_price_deviation(&path).unwrap();
+        assert!((deviation - (1.01f64.powf(1.0 / 3.0) - 1.0)).abs() < 1e-9);
+        let score_with_deviation = market.get_arbitrage_score(&path);
+        assert!((score_with_deviation - (0.08 * 2.0 / 3.0 + 0.22 + 0.4 * deviation / (deviation + 0.005))).abs() < 1e-9);
+
+        for block_number in 1000..1010 {
+            market.record_path_profit(&path, block_number);
+        }
+        market.record_path_profit(&path, 1009);
+        assert!((market.get_path_hit_rate(&path) - 0.1).abs() < 1e-9);
+        assert!((market.get_arbitrage_score(&path) - score_with_deviation - 0.03).abs() < 1e-9);
+
+        market.set_block_number(1104);
+        assert!((market.get_path_hit_rate(&path) - 0.05).abs() < 1e-9);
+        market.set_block_number(1200);
+        assert_eq!(market.get_path_hit_rate(&path), 0.0);
+        assert_eq!(market.get_path_last_profit_block(&path), Some(1009));
+
+        // a cycle returning less than it takes gets no deviation component
+        market.set_pool_spot_price(PoolId::Address(Address::repeat_byte(12)), 4.0 / 0.99);
+        assert!(market.get_path_price_deviation(&path).unwrap() < 0.0);
+        assert!((market.get_arbitrage_score(&path) - (0.08 * 2.0 / 3.0 + 0.22)).abs() < 1e-9);
+
+        market.set_pool_spot_price(PoolId::Address(Address::repeat_byte(12)), f64::NAN);
+        assert_eq!(market.get_pool_spot_price(&PoolId::Address(Address::repeat_byte(12))), None);
+        assert_eq!(market.get_path_spot_output(&path), None);
+    }
+
+    #[test]
+    fn test_get_path_between_tokens() {
+        let mut market = Market::default();
+        let (token0, token1, token2, token3) =
+            (Address::repeat_byte(1), Address::repeat_byte(2), Address::repeat_byte(3), Address::repeat_byte(4));
+        // 1 token0 -> 2 token1 -> 6 token2, 1 token0 -> 5 token2, pool 13 fails to calculate
+        market.add_pool(MockPool::new(token0, token1, Address::repeat_byte(10)).with_rate(2, 1)).unwrap();
+        market.add_pool(MockPool::new(token1, token2, Address::repeat_byte(11)).with_rate(3, 1)).unwrap();
+        market.add_pool(MockPool::new(token0, token2, Address::repeat_byte(12)).with_rate(5, 1)).unwrap();
+        market.add_pool(MockPool::new(token2, token0, Address::repeat_byte(13))).unwrap();
+        market.add_pool(MockPool::new(token2, token3, Address::repeat_byte(14)).with_rate(1, 1)).unwrap();
+
+        let state = EmptyDBTyped::<ErrReport>::new();
+        let pool_addresses = |paths: &Vec<SwapPath>| -> Vec<Vec<Address>> {
+            paths.iter().map(|path| path.pools.iter().map(|pool| pool.get_address()).collect()).collect()
+        };
+
+        let paths = market.get_path_between_tokens(&state, Env::default(), token0, token2, 2);
+        assert_eq!(
+            pool_addresses(&paths),
+            vec![vec![Address::repeat_byte(10), Address::repeat_byte(11)], vec![Address::repeat_byte(12)], vec![Address::repeat_byte(13)]]
+        );
+        assert_eq!(paths[0].tokens.iter().map(|token| token.get_address()).collect::<Vec<_>>(), vec![token0, token1, token2]);
+
+        assert_eq!(market.get_path_between_tokens(&state, Env::default(), token0, token2, 1).len(), 2);
+        assert_eq!(market.get_path_between_tokens(&state, Env::default(), token0, token3, 2).len(), 2);
+        assert_eq!(market.get_path_between_tokens(&state, Env::default(), token0, token3, 3).len(), 3);
+        assert!(market.get_path_between_tokens(&state, Env::default(), token0, token0, 3).is_empty());
+
+        // 1 token2 -> 0.2 token0 ahead of the failing pool
+        let paths = market.get_path_between_tokens(&state, Env::default(), token2, token0, 1);
+        assert_eq!(pool_addresses(&paths), vec![vec![Address::repeat_byte(12)], vec![Address::repeat_byte(13)]]);
+
+        // 1 token3 -> 1 token2 -> 0.2 token0 -> 0.4 token1 ahead of 1 token3 -> 1 token2 -> 0.33 token1
+        assert_eq!(
+            pool_addresses(&market.get_path_between_tokens(&state, Env::default(), token3, token1, 3)),
+            vec![
+                vec![Address::repeat_byte(14), Address::repeat_byte(12), Address::repeat_byte(10)],
+                vec![Address::repeat_byte(14), Address::repeat_byte(11)],
+                vec![Address::repeat_byte(14), Address::repeat_byte(13), Address::repeat_byte(10)]
+            ]
+        );
+
+        market.disable_pool(PoolId::Address(Address::repeat_byte(12)));
+        assert_eq!(
+            pool_addresses(&market.get_path_between_tokens(&state, Env::default(), token0, token2, 2)),
+            vec![vec![Address::repeat_byte(10), Address::repeat_byte(11)], vec![Address::repeat_byte(13)]]
+        );
+    }
+
+    #[test]
+    fn test_update_path_score() {
+        let mut market = Market::default();
+        let token0 = Address::random();
+        let token1 = Address::random();
+        let mock_pool = MockPool::new(token0, token1, Address::random());
+        let path = SwapPath::new(vec![Token::new(token0), Token::new(token1)], vec![mock_pool]);
+        let path_idx = market.add_paths(vec![path])[0];
+
+        assert!(market.update_path_score(path_idx, 0.5));
+        assert_eq!(market.swap_paths().get_path_by_idx(path_idx).unwrap().score, Some(0.5));
+        assert!(!market.update_path_score(path_idx + 1, 0.5));
+    }
+
+    #[test]
+    fn test_decay_path_scores() {
+        let mut market = Market::default();
+        let (token0, token1) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let (pool0, pool1) =
+            (MockPool::new(token0, token1, Address::repeat_byte(10)), MockPool::new(token0, token1, Address::repeat_byte(11)));
+        let path0 = SwapPath::new(vec![Token::new(token0), Token::new(token1)], vec![pool0]);
+        let path1 = SwapPath::new(vec![Token::new(token0), Token::new(token1)], vec![pool1]);
+        let path_idx = market.add_paths(vec![path0.clone(), path1.clone()]);
+        market.update_path_score(path_idx[0], 1.0);
+        market.update_path_score(path_idx[1], 1.0);
+        let score = |market: &Market, idx: usize| market.swap_paths().get_path_by_idx(idx).unwrap().score.unwrap();
+
+        // the first call only sets the starting block
+        assert_eq!(market.decay_path_scores(1000, 100), 0);
+
+        market.record_path_profit(&path0, 1050);
+        assert_eq!(market.decay_path_scores(1100, 100), 2);
+        assert!((score(&market, path_idx[0]) - 0.5f64.powf(0.5)).abs() < 1e-9);
+        assert!((score(&market, path_idx[1]) - 0.5).abs() < 1e-9);
+
+        // decay does not compound over calls
+        market.decay_path_scores(1150, 100);
+        market.decay_path_scores(1250, 100);
+        assert!((score(&market, path_idx[0]) - 0.5f64.powf(2.0)).abs() < 1e-9);
+        assert!((score(&market, path_idx[1]) - 0.5f64.powf(2.5)).abs() < 1e-9);
+        assert_eq!(market.get_path_last_profit_block(&path0), Some(1050));
+        assert_eq!(market.get_path_last_profit_block(&path1), None);
+    }
+
+    #[test]
+    fn test_decay_path_scores_by_direction() {
+        let mut market = Market::default();
+        let (token0, token1) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let (pool0, pool1) =
+            (MockPool::new(token0, token1, Address::repeat_byte(10)), MockPool::new(token0, token1, Address::repeat_byte(11)));
+        let tokens = vec![Token::new(token0), Token::new(token1), Token::new(token0)];
+        let path = SwapPath::new(tokens.clone(), vec![pool0.clone(), pool1.clone()]);
+        let reverse_path = SwapPath::new(tokens, vec![pool1, pool0]);
+        assert_ne!(path.fingerprint(), reverse_path.fingerprint());
+        let path_idx = market.add_paths(vec![path.clone(), reverse_path.clone()]);
+        market.update_path_score(path_idx[0], 1.0);
+        market.update_path_score(path_idx[1], 1.0);
+        let score = |market: &Market, idx: usize| market.swap_paths().get_path_by_idx(idx).unwrap().score.unwrap();
+
+        market.decay_path_scores(1000, 100);
+        market.record_path_profit(&path, 1100);
+        market.decay_path_scores(1100, 100);
+        assert_eq!(score(&market, path_idx[0]), 1.0);
+        assert!((score(&market, path_idx[1]) - 0.5).abs() < 1e-9);
+        assert_eq!(market.get_path_last_profit_block(&path), Some(1100));
+        assert_eq!(market.get_path_last_profit_block(&reverse_path), None);
+        assert_eq!(market.get_path_hit_rate(&reverse_path), 0.0);
+    }
+
+    #[test]
+    fn test_disable_pool() {
+        let mut market = Market::default();
+        let pool_address = Address::random();
+        let mock_pool = MockPool::new(Address::random(), Address::random(), pool_address);
+        market.add_pool(mock_pool).unwrap();
+        let pool_id = PoolId::Address(pool_address);
+
+        assert!(market.disable_pool(pool_id));
+        assert!(market.is_pool_disabled(&pool_id));
+        assert_eq!(market.disabled_pools_count(), 1);
+
+        assert!(market.enable_pool(pool_id));
+        assert!(!market.is_pool_disabled(&pool_id));
+        assert_eq!(market.disabled_pools_count(), 0);
+
+        assert!(!market.disable_pool(PoolId::Address(Address::random())));
+    }
+
+    #[test]
+    fn test_remove_pool() {
+        let mut market = Market::default();
+        let token0 = Address::random();
+        let token1 = Address::random();
+        let pool_address = Address::random();
+        let other_pool_address = Address::random();
+        market.add_pool(MockPool::new(token0, token1, pool_address)).unwrap();
+        market.add_pool(MockPool::new(token0, token1, other_pool_address)).unwrap();
+        market.add_token(Token::new(token0));
+        market.add_token(Token::new(token1));
+        let pool_id = PoolId::Address(pool_address);
+        let other_pool_id = PoolId::Address(other_pool_address);
+
+        let path = market.swap_path(vec![token0, token1, token0], vec![pool_id, other_pool_id]).unwrap();
+        market.add_paths(vec![path]);
+
+        assert!(market.remove_pool(&pool_id));
+        assert!(!market.is_pool(&pool_id));
+        assert_eq!(market.get_token_token_pools(&token0, &token1), Some(&vec![other_pool_id]));
+        assert_eq!(market.get_token_pools(&token1), Some(&vec![other_pool_id]));
+        assert_eq!(market.get_token_tokens(&token0), Some(&vec![token1]));
+        assert!(market.swap_paths().paths.iter().all(|path| path.disabled));
+        assert_eq!(market.get_disabled_pools(), vec![pool_id]);
+
+        assert!(!market.remove_pool(&pool_id));
+        assert!(market.is_pool(&other_pool_id));
+    }
+
+    #[test]
+    fn test_record_pool_swaps() {
+        let mut market = Market::default();
+        let pool_id = PoolId::Address(Address::random());
+
+        assert_eq!(market.get_pool_stats(&pool_id), None);
+
+        assert_eq!(market.record_pool_swaps(pool_id, 1, U256::from(100), 10), 1);
+        assert_eq!(market.record_pool_swaps(pool_id, 3, U256::from(50), 12), 4);
+
+        let stats = market.get_pool_stats(&pool_id).unwrap();
+        assert_eq!(stats, PoolStats { swap_count: 4, volume_eth: U256::from(150), last_block: 12 });
     }
 
     #[test]
@@ -341,7 +1087,7 @@ mod tests {
     fn test_get_pool() {
         let mut market = Market::default();
         let pool_address = Address::random();
-        let mock_pool = MockPool { address: pool_address, token0: Address::ZERO, token1: Address::ZERO };
+        let mock_pool = MockPool::new(Address::ZERO, Address::ZERO, pool_address);
         market.add_pool(mock_pool.clone());
 
         let pool = market.get_pool(&PoolId::Address(pool_address));
@@ -353,7 +1099,7 @@ mod tests {
     fn test_is_pool() {
         let mut market = Market::default();
         let pool_address = Address::random();
-        let mock_pool = MockPool { address: pool_address, token0: Address::ZERO, token1: Address::ZERO };
+        let mock_pool = MockPool::new(Address::ZERO, Address::ZERO, pool_address);
         market.add_pool(mock_pool.clone());
 
         let is_pool = market.is_pool(&PoolId::Address(pool_address));
@@ -377,7 +1123,7 @@ mod tests {
         let pool_address = Address::random();
         let token0 = Address::random();
         let token1 = Address::random();
-        let mock_pool = MockPool { address: pool_address, token0, token1 };
+        let mock_pool = MockPool::new(token0, token1, pool_address);
         market.add_pool(mock_pool.clone());
 
         assert!(!market.is_pool_disabled(&PoolId::Address(pool_address)));
@@ -400,7 +1146,7 @@ mod tests {
         let pool_address = Address::random();
         let token0 = Address::random();
         let token1 = Address::random();
-        let mock_pool = MockPool { address: pool_address, token0, token1 };
+        let mock_pool = MockPool::new(token0, token1, pool_address);
         market.add_pool(mock_pool);
 
         let pools = market.get_token_token_pools(&token0, &token1);
@@ -414,7 +1160,7 @@ mod tests {
         let pool_address = Address::random();
         let token0 = Address::random();
         let token1 = Address::random();
-        let mock_pool = MockPool { address: pool_address, token0, token1 };
+        let mock_pool = MockPool::new(token0, token1, pool_address);
         market.add_pool(mock_pool);
 
         let tokens = market.get_token_tokens(&token0);
@@ -428,7 +1174,7 @@ mod tests {
         let pool_address = Address::random();
         let token0 = Address::random();
         let token1 = Address::random();
-        let mock_pool = MockPool { address: pool_address, token0, token1 };
+        let mock_pool = MockPool::new(token0, token1, pool_address);
         market.add_pool(mock_pool);
 
         let pools = market.get_token_pools(&token0).cloned();
@@ -447,12 +1193,12 @@ mod tests {
         // Swap pool: token weth -> token1
         let pool_address1 = Address::random();
         let token1 = Address::random();
-        let mock_pool1 = PoolWrapper::new(Arc::new(MockPool { address: pool_address1, token0: TokenAddressEth::WETH, token1 }));
+        let mock_pool1 = PoolWrapper::new(Arc::new(MockPool::new(TokenAddressEth::WETH, token1, pool_address1)));
         market.add_pool(mock_pool1.clone());
 
         // Swap pool: token weth -> token1
         let pool_address2 = Address::random();
-        let mock_pool2 = PoolWrapper::new(Arc::new(MockPool { address: pool_address2, token0: TokenAddressEth::WETH, token1 }));
+        let mock_pool2 = PoolWrapper::new(Arc::new(MockPool::new(TokenAddressEth::WETH, token1, pool_address2)));
         market.add_pool(mock_pool2.clone());
 
         // Add test swap paths
@@ -511,17 +1257,17 @@ mod tests {
 
         // Swap pool: weth -> token1
         let pool_address1 = Address::random();
-        let mock_pool = PoolWrapper::new(Arc::new(MockPool { address: pool_address1, token0: token1, token1: TokenAddressEth::WETH }));
+        let mock_pool = PoolWrapper::new(Arc::new(MockPool::new(token1, TokenAddressEth::WETH, pool_address1)));
         market.add_pool(mock_pool);
 
         // Swap pool: token1 -> token2
         let pool_address2 = Address::random();
-        let mock_pool2 = PoolWrapper::new(Arc::new(MockPool { address: pool_address2, token0: token1, token1: token2 }));
+        let mock_pool2 = PoolWrapper::new(Arc::new(MockPool::new(token1, token2, pool_address2)));
         market.add_pool(mock_pool2);
 
         // Swap pool: token2 -> weth
         let pool_address3 = Address::random();
-        let mock_pool3 = PoolWrapper::new(Arc::new(MockPool { address: pool_address3, token0: token2, token1: TokenAddressEth::WETH }));
+        let mock_pool3 = PoolWrapper::new(Arc::new(MockPool::new(token2, TokenAddressEth::WETH, pool_address3)));
         market.add_pool(mock_pool3.clone());
 
         // under test
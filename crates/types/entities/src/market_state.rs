@@ -1,5 +1,9 @@
+use alloy_network::primitives::{BlockTransactionsKind, HeaderResponse};
+use alloy_network::{BlockResponse, Network};
 use alloy_primitives::{Address, BlockHash, BlockNumber, U256};
-use loom_evm_db::DatabaseHelpers;
+use alloy_provider::Provider;
+use eyre::{OptionExt, Result};
+use loom_evm_db::{AlloyDB, DatabaseHelpers, DatabaseLoomExt};
 use loom_types_blockchain::{GethStateUpdate, GethStateUpdateVec};
 use revm::{Database, DatabaseCommit, DatabaseRef};
 use std::collections::{HashMap, HashSet};
@@ -8,6 +12,8 @@ use std::collections::{HashMap, HashSet};
 pub struct MarketStateConfig {
     pub force_insert_accounts: HashSet<Address>,
     pub read_only_cells: HashMap<Address, HashSet<U256>>,
+    // Accounts with state restored from a market state snapshot
+    pub restored_accounts: HashSet<Address>,
 }
 
 impl MarketStateConfig {
@@ -19,6 +25,14 @@ impl MarketStateConfig {
         self.force_insert_accounts.insert(address);
     }
 
+    pub fn add_restored(&mut self, address: Address) {
+        self.restored_accounts.insert(address);
+    }
+
+    pub fn is_restored(&self, address: &Address) -> bool {
+        self.restored_accounts.contains(address)
+    }
+
     pub fn disable_cell(&mut self, address: Address, cell: U256) {
         self.read_only_cells.entry(address).or_default().insert(cell);
     }
@@ -98,3 +112,54 @@ impl<DB: DatabaseRef + Database + DatabaseCommit> MarketState<DB> {
     //     //debug!("Added state : {}", state.len());
     // }
 }
+
+impl<DB: DatabaseRef + Database + DatabaseCommit + DatabaseLoomExt + Default> MarketState<DB> {
+    /// Returns a fork of the market state pinned to `block_number`. Accounts and storage are fetched from `client` at the block
+    /// and cached in a new database, updates applied to the fork are never written back.
+    pub async fn fork_at_block<P, N>(&self, client: P, block_number: BlockNumber) -> Result<MarketState<DB>>
+    where
+        N: Network,
+        P: Provider<N> + 'static,
+    {
+        let block = client.get_block_by_number(block_number.into(), BlockTransactionsKind::Hashes).await?.ok_or_eyre("BLOCK_NOT_FOUND")?;
+        let block_hash = block.header().hash();
+
+        let ext_db = AlloyDB::new(client, block_number.into()).ok_or_eyre("EXT_DB_NOT_CREATED")?;
+        let mut state_db = DB::default();
+        state_db.with_ext_db(ext_db);
+
+        Ok(MarketState { block_number, block_hash, state_db, config: self.config.clone() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_provider::ProviderBuilder;
+    use alloy_rpc_client::{ClientBuilder, WsConnect};
+    use loom_defi_address_book::TokenAddressEth;
+    use loom_evm_db::LoomDBType;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fork_at_block() -> Result<()> {
+        let node_url = std::env::var("MAINNET_WS")?;
+        let ws_connect = WsConnect::new(node_url);
+        let client = ClientBuilder::default().ws(ws_connect).await?;
+        let client = ProviderBuilder::new().disable_recommended_fillers().on_client(client);
+
+        let block_number: BlockNumber = 20_000_000;
+        let block = client.get_block_by_number(block_number.into(), BlockTransactionsKind::Hashes).await?.unwrap();
+        let weth_balance = client.get_balance(TokenAddressEth::WETH).block_id(block_number.into()).await?;
+
+        let market_state = MarketState::new(LoomDBType::default());
+        let forked_state = market_state.fork_at_block(client.clone(), block_number).await?;
+        assert_eq!(forked_state.number_and_hash(), (block_number, block.header().hash()));
+
+        let account = forked_state.state_db.basic_ref(TokenAddressEth::WETH)?.unwrap();
+        assert_eq!(account.balance, weth_balance);
+        // Original state is not changed
+        assert_eq!(market_state.number(), 0);
+
+        Ok(())
+    }
+}
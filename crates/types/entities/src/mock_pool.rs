@@ -2,22 +2,28 @@ use crate::pool_id::PoolId;
 use crate::required_state::RequiredState;
 use crate::{Pool, PoolAbiEncoder, PoolClass, PoolProtocol, PreswapRequirement, SwapDirection};
 use alloy_primitives::{Address, U256};
-use eyre::ErrReport;
 use eyre::Result;
+use eyre::{ErrReport, OptionExt};
 use revm::primitives::Env;
 use revm::DatabaseRef;
 use std::any::Any;
 
+/// Pool swapping at a fixed rate of `rate.0 / rate.1` token1 per token0, fails to calculate without a rate
 #[derive(Clone)]
 pub struct MockPool {
     pub(crate) token0: Address,
     pub(crate) token1: Address,
     pub(crate) address: Address,
+    pub(crate) rate: Option<(u64, u64)>,
 }
 
 impl MockPool {
     pub fn new(token0: Address, token1: Address, address: Address) -> Self {
-        Self { token0, token1, address }
+        Self { token0, token1, address, rate: None }
+    }
+
+    pub fn with_rate(self, numerator: u64, denominator: u64) -> Self {
+        Self { rate: Some((numerator, denominator)), ..self }
     }
 }
 
@@ -56,13 +62,18 @@ impl Pool for MockPool {
 
     fn calculate_out_amount(
         &self,
-        state: &dyn DatabaseRef<Error = ErrReport>,
-        env: Env,
+        _state: &dyn DatabaseRef<Error = ErrReport>,
+        _env: Env,
         token_address_from: &Address,
-        token_address_to: &Address,
+        _token_address_to: &Address,
         in_amount: U256,
     ) -> Result<(U256, u64), ErrReport> {
-        panic!("Not implemented")
+        let (numerator, denominator) = self.rate.ok_or_eyre("NO_RATE")?;
+        if *token_address_from == self.token0 {
+            Ok((in_amount * U256::from(numerator) / U256::from(denominator), 100_000))
+        } else {
+            Ok((in_amount * U256::from(denominator) / U256::from(numerator), 100_000))
+        }
     }
 
     fn calculate_in_amount(
@@ -77,7 +88,7 @@ impl Pool for MockPool {
     }
 
     fn can_flash_swap(&self) -> bool {
-        panic!("Not implemented")
+        false
     }
 
     fn can_calculate_in_amount(&self) -> bool {
@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+
+/// Simulation outcomes collected for a single swap path
+#[derive(Clone, Debug, Default)]
+pub struct PathSimulationStats {
+    pub successful_simulations: u64,
+    pub total_simulations: u64,
+}
+
+impl PathSimulationStats {
+    pub fn success_rate(&self) -> Option<f64> {
+        if self.total_simulations == 0 {
+            None
+        } else {
+            Some(self.successful_simulations as f64 / self.total_simulations as f64)
+        }
+    }
+}
+
+/// Historical simulation results keyed by `SwapPath::get_hash`
+#[derive(Clone, Debug, Default)]
+pub struct PnlHistory {
+    paths: HashMap<u64, PathSimulationStats>,
+}
+
+impl PnlHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_simulation(&mut self, path_hash: u64, success: bool) {
+        let entry = self.paths.entry(path_hash).or_default();
+        entry.total_simulations += 1;
+        if success {
+            entry.successful_simulations += 1;
+        }
+    }
+
+    pub fn get(&self, path_hash: u64) -> Option<&PathSimulationStats> {
+        self.paths.get(&path_hash)
+    }
+
+    pub fn success_rate(&self, path_hash: u64) -> Option<f64> {
+        self.paths.get(&path_hash).and_then(|stats| stats.success_rate())
+    }
+
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+}
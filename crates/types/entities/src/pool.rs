@@ -44,6 +44,8 @@ pub fn get_protocol_by_factory(factory_address: Address) -> PoolProtocol {
         PoolProtocol::Maverick
     } else if factory_address == FactoryAddress::INTEGRAL {
         PoolProtocol::Integral
+    } else if factory_address == FactoryAddress::AERODROME_CL {
+        PoolProtocol::AerodromeCL
     } else {
         PoolProtocol::Unknown
     }
@@ -92,12 +94,15 @@ pub enum PoolClass {
     #[serde(rename = "balancer2")]
     #[strum(serialize = "balancer2")]
     BalancerV2,
+    #[serde(rename = "erc4626")]
+    #[strum(serialize = "erc4626")]
+    Erc4626Vault,
     #[serde(rename = "custom")]
     #[strum(serialize = "custom")]
     Custom(u64),
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum PoolProtocol {
     Unknown,
     AaveV2,
@@ -117,6 +122,7 @@ pub enum PoolProtocol {
     UniswapV3Like,
     UniswapV4,
     PancakeV3,
+    AerodromeCL,
     Integral,
     Maverick,
     MaverickV2,
@@ -126,6 +132,7 @@ pub enum PoolProtocol {
     RocketEth,
     BalancerV1,
     BalancerV2,
+    Erc4626Vault,
     Custom(u64),
 }
 
@@ -139,6 +146,7 @@ impl Display for PoolProtocol {
             Self::UniswapV2Like => "UniswapV2Like",
             Self::UniswapV3 => "UniswapV3",
             Self::PancakeV3 => "PancakeV3",
+            Self::AerodromeCL => "AerodromeCL",
             Self::UniswapV4 => "UniswapV4",
             Self::UniswapV3Like => "UniswapV3Like",
             Self::NomiswapStable => "NomiswapStable",
@@ -159,6 +167,7 @@ impl Display for PoolProtocol {
             Self::RocketEth => "RocketEth",
             Self::BalancerV1 => "BalancerV1",
             Self::BalancerV2 => "BalancerV2",
+            Self::Erc4626Vault => "Erc4626Vault",
             Self::Custom(x) => "Custom",
         };
         write!(f, "{}", protocol_name)
@@ -231,6 +240,19 @@ impl<LDT: LoomDataTypes> PoolWrapper<LDT> {
     pub fn new(pool: Arc<dyn Pool<LDT>>) -> Self {
         PoolWrapper { pool }
     }
+
+    /// Returns (out_amount, fee_amount), the fee is charged on `in_amount` and is in `token_address_from` units
+    pub fn simulate_swap_with_fees(
+        &self,
+        state: &dyn DatabaseRef<Error = ErrReport>,
+        env: Env,
+        token_address_from: &LDT::Address,
+        token_address_to: &LDT::Address,
+        in_amount: U256,
+    ) -> Result<(U256, U256)> {
+        let (out_amount, _) = self.pool.calculate_out_amount(state, env, token_address_from, token_address_to, in_amount)?;
+        Ok((out_amount, self.pool.calculate_fee_amount(in_amount)))
+    }
 }
 
 impl<T: 'static + Pool<LoomDataTypesEthereum>> From<T> for PoolWrapper<LoomDataTypesEthereum> {
@@ -275,10 +297,20 @@ pub trait Pool<LDT: LoomDataTypes = LoomDataTypesEthereum>: Sync + Send {
         out_amount: U256,
     ) -> Result<(U256, u64), ErrReport>;
 
+    /// Swap fee charged on `in_amount` in input token units. The fee is already deducted by `calculate_out_amount`.
+    fn calculate_fee_amount(&self, _in_amount: U256) -> U256 {
+        U256::ZERO
+    }
+
     fn can_flash_swap(&self) -> bool;
 
     fn can_calculate_in_amount(&self) -> bool;
 
+    /// Pool supports exactOutput swaps, so the input amount for a target output can be swapped directly
+    fn supports_exact_output(&self) -> bool {
+        false
+    }
+
     fn get_abi_encoder(&self) -> Option<&dyn PoolAbiEncoder>;
 
     fn get_read_only_cell_vec(&self) -> Vec<U256>;
@@ -292,6 +324,16 @@ pub trait Pool<LDT: LoomDataTypes = LoomDataTypesEthereum>: Sync + Send {
     fn get_pool_manager_cells(&self) -> Vec<(Address, Vec<U256>)> {
         vec![]
     }
+
+    /// Contracts other than the pool holding state its swaps are calculated from, the pool manager by default
+    fn get_state_addresses(&self) -> Vec<Address> {
+        self.get_pool_manager_cells().into_iter().map(|(address, _)| address).collect()
+    }
+
+    /// Hooks contract executed on swaps, output of such pools can only be calculated with full EVM execution
+    fn get_hooks(&self) -> Option<Address> {
+        None
+    }
 }
 
 pub struct DefaultAbiSwapEncoder {}
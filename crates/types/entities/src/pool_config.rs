@@ -1,11 +1,15 @@
 use crate::PoolClass;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use strum::IntoEnumIterator;
 
 #[derive(Clone)]
 pub struct PoolsLoadingConfig {
     threads: Option<usize>,
     is_enabled: HashMap<PoolClass, bool>,
+    swap_paths_cache_file: Option<PathBuf>,
+    swap_paths_cache_max_age_blocks: u64,
+    tick_preload_range: Option<i32>,
 }
 
 impl PoolsLoadingConfig {
@@ -15,7 +19,7 @@ impl PoolsLoadingConfig {
             is_enabled.insert(pool_class, true);
         }
 
-        Self { threads: None, is_enabled }
+        Self { threads: None, is_enabled, swap_paths_cache_file: None, swap_paths_cache_max_age_blocks: 0, tick_preload_range: None }
     }
 
     pub fn disable_all(self) -> Self {
@@ -52,6 +56,28 @@ impl PoolsLoadingConfig {
     pub fn threads(&self) -> Option<usize> {
         self.threads
     }
+
+    /// Load swap paths from `file` if it was saved within `max_age_blocks` and save them to it periodically
+    pub fn with_swap_paths_cache(self, file: PathBuf, max_age_blocks: u64) -> Self {
+        Self { swap_paths_cache_file: Some(file), swap_paths_cache_max_age_blocks: max_age_blocks, ..self }
+    }
+
+    pub fn swap_paths_cache_file(&self) -> Option<&PathBuf> {
+        self.swap_paths_cache_file.as_ref()
+    }
+
+    pub fn swap_paths_cache_max_age_blocks(&self) -> u64 {
+        self.swap_paths_cache_max_age_blocks
+    }
+
+    /// Preload initialized ticks within `current tick ± tick_range` of UniswapV3 like pools
+    pub fn with_tick_preload(self, tick_range: i32) -> Self {
+        Self { tick_preload_range: Some(tick_range), ..self }
+    }
+
+    pub fn tick_preload_range(&self) -> Option<i32> {
+        self.tick_preload_range
+    }
 }
 
 impl Default for PoolsLoadingConfig {
@@ -1,7 +1,7 @@
 use crate::pool_config::PoolsLoadingConfig;
 use crate::{PoolClass, PoolId, PoolWrapper};
 use alloy_network::{Ethereum, Network};
-use alloy_primitives::Bytes;
+use alloy_primitives::{Bytes, B256};
 use alloy_provider::Provider;
 use eyre::{eyre, ErrReport, Result};
 use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
@@ -21,6 +21,8 @@ where
     LDT: Send + Sync + LoomDataTypes,
 {
     fn get_pool_class_by_log(&self, log_entry: &LDT::Log) -> Option<(PoolId<LDT>, PoolClass)>;
+    /// Signatures of the events changing the state of the pools of this loader
+    fn event_signatures(&self) -> Vec<B256>;
     fn fetch_pool_by_id<'a>(&'a self, pool_id: PoolId<LDT>) -> Pin<Box<dyn Future<Output = Result<PoolWrapper<LDT>>> + Send + 'a>>;
     fn fetch_pool_by_id_from_provider<'a>(
         &'a self,
@@ -66,11 +68,35 @@ where
         PoolLoaders { provider: Some(provider), map: HashMap::new(), config: self.config }
     }
 
+    pub fn provider(&self) -> Option<&P> {
+        self.provider.as_ref()
+    }
+
+    pub fn config(&self) -> Option<&PoolsLoadingConfig> {
+        self.config.as_ref()
+    }
+
     pub fn add_loader<L: PoolLoader<P, N, LDT> + Send + Sync + Clone + 'static>(self, pool_class: PoolClass, loader: L) -> Self {
         let mut map = self.map;
         map.insert(pool_class, Arc::new(loader));
         Self { map, ..self }
     }
+
+    /// Event signatures of the loaders of the pool classes enabled in the config, all loaders without a config
+    pub fn event_signatures(&self) -> Vec<B256> {
+        let mut signatures = Vec::new();
+        for (pool_class, pool_loader) in self.map.iter() {
+            if self.config.as_ref().is_some_and(|config| !config.is_enabled(*pool_class)) {
+                continue;
+            }
+            for signature in pool_loader.event_signatures() {
+                if !signatures.contains(&signature) {
+                    signatures.push(signature);
+                }
+            }
+        }
+        signatures
+    }
 }
 
 impl<P, N, LDT> Default for PoolLoaders<P, N, LDT>
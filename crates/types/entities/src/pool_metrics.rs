@@ -0,0 +1,14 @@
+/// Liquidity metrics of a pool used to score swap paths
+#[derive(Clone, Debug)]
+pub struct PoolMetrics {
+    /// Total value locked in USD, set by the price actor
+    pub tvl_usd: f64,
+    /// Reliability in [0, 1], lowered by pool health monitor on swap errors
+    pub reliability: f64,
+}
+
+impl Default for PoolMetrics {
+    fn default() -> Self {
+        Self { tvl_usd: 0.0, reliability: 1.0 }
+    }
+}
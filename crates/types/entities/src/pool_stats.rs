@@ -0,0 +1,12 @@
+use alloy_primitives::U256;
+
+/// Observed trading activity of a pool
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Number of blocks in which the pool state was touched
+    pub swap_count: u64,
+    /// Accumulated swap volume valued in ETH
+    pub volume_eth: U256,
+    /// Last block the pool was touched in
+    pub last_block: u64,
+}
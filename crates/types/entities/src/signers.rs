@@ -1,7 +1,9 @@
-use alloy_consensus::{SignableTransaction, TxEnvelope};
+use alloy_consensus::{SignableTransaction, TxEnvelope, TypedTransaction};
+use alloy_eips::eip7702::{Authorization, SignedAuthorization};
 use alloy_network::{TransactionBuilder, TxSigner as AlloyTxSigner, TxSignerSync};
 use alloy_primitives::{hex, Address, Bytes, B256};
 use alloy_rpc_types::Transaction;
+use alloy_signer::SignerSync;
 use alloy_signer_local::PrivateKeySigner;
 use eyre::{eyre, OptionExt, Result};
 use indexmap::IndexMap;
@@ -17,6 +19,10 @@ pub trait LoomTxSigner<LDT: LoomDataTypes>: Send + Sync + Debug {
     fn sign<'a>(&'a self, tx: LDT::TransactionRequest) -> Pin<Box<dyn std::future::Future<Output = Result<LDT::Transaction>> + Send + 'a>>;
     fn sign_sync(&self, tx: LDT::TransactionRequest) -> Result<LDT::Transaction>;
     fn address(&self) -> LDT::Address;
+    /// Signs an EIP-7702 authorization delegating the signer account to `authorization.address`
+    fn sign_authorization(&self, _authorization: Authorization) -> Result<SignedAuthorization> {
+        Err(eyre!("AUTHORIZATION_SIGNING_NOT_SUPPORTED"))
+    }
 }
 
 #[derive(Clone)]
@@ -47,15 +53,17 @@ impl LoomTxSigner<LoomDataTypesEthereum> for TxSignerEth {
         tx_req: <LoomDataTypesEthereum as LoomDataTypes>::TransactionRequest,
     ) -> Pin<Box<dyn Future<Output = Result<<LoomDataTypesEthereum as LoomDataTypes>::Transaction>> + Send + 'a>> {
         let fut = async move {
-            let mut typed_tx = tx_req
-                .build_typed_tx()
-                .map_err(|e| eyre!("TRANSACTION_TYPE_IS_MISSING"))?
-                .eip1559()
-                .ok_or_eyre("TRANSACTION_IS_NOT_EIP1559")?
-                .clone();
-            let signature = self.wallet.sign_transaction(&mut typed_tx).await?;
-            let signed_tx = typed_tx.clone().into_signed(signature);
-            let tx_env: TxEnvelope = signed_tx.into();
+            let tx_env: TxEnvelope = match tx_req.build_typed_tx().map_err(|e| eyre!("TRANSACTION_TYPE_IS_MISSING"))? {
+                TypedTransaction::Eip7702(mut typed_tx) => {
+                    let signature = self.wallet.sign_transaction(&mut typed_tx).await?;
+                    typed_tx.into_signed(signature).into()
+                }
+                typed_tx => {
+                    let mut typed_tx = typed_tx.eip1559().ok_or_eyre("TRANSACTION_IS_NOT_EIP1559")?.clone();
+                    let signature = self.wallet.sign_transaction(&mut typed_tx).await?;
+                    typed_tx.into_signed(signature).into()
+                }
+            };
             let tx = Transaction {
                 inner: tx_env,
                 block_hash: None,
@@ -78,18 +86,20 @@ impl LoomTxSigner<LoomDataTypesEthereum> for TxSignerEth {
         &self,
         tx_req: <LoomDataTypesEthereum as LoomDataTypes>::TransactionRequest,
     ) -> Result<<LoomDataTypesEthereum as LoomDataTypes>::Transaction> {
-        let mut typed_tx = tx_req
-            .build_unsigned()
-            .map_err(|e| eyre!(format!("CANNOT_BUILD_UNSIGNED with error: {}", e)))?
-            .eip1559()
-            .ok_or_eyre("TRANSACTION_IS_NOT_EIP1559")?
-            .clone();
-
-        let signature = self.wallet.sign_transaction_sync(&mut typed_tx)?;
-        let signed_tx = typed_tx.clone().into_signed(signature);
-
-        let hash = signed_tx.signature_hash();
-        let tx_env: TxEnvelope = signed_tx.into();
+        let typed_tx = tx_req.build_unsigned().map_err(|e| eyre!(format!("CANNOT_BUILD_UNSIGNED with error: {}", e)))?;
+
+        // Transactions with an authorization list are EIP-7702, all others must be EIP-1559
+        let tx_env: TxEnvelope = match typed_tx {
+            TypedTransaction::Eip7702(mut typed_tx) => {
+                let signature = self.wallet.sign_transaction_sync(&mut typed_tx)?;
+                typed_tx.into_signed(signature).into()
+            }
+            typed_tx => {
+                let mut typed_tx = typed_tx.eip1559().ok_or_eyre("TRANSACTION_IS_NOT_EIP1559")?.clone();
+                let signature = self.wallet.sign_transaction_sync(&mut typed_tx)?;
+                typed_tx.into_signed(signature).into()
+            }
+        };
         let tx = Transaction {
             inner: tx_env,
             block_hash: None,
@@ -100,6 +110,11 @@ impl LoomTxSigner<LoomDataTypesEthereum> for TxSignerEth {
         };
         Ok(tx)
     }
+
+    fn sign_authorization(&self, authorization: Authorization) -> Result<SignedAuthorization> {
+        let signature = self.wallet.sign_hash_sync(&authorization.signature_hash())?;
+        Ok(authorization.into_signed(signature))
+    }
 }
 
 impl TxSignerEth {
@@ -163,11 +178,17 @@ impl<LDT: LoomDataTypes> TxSigners<LDT> {
     pub fn get_address_vec(&self) -> Vec<LDT::Address> {
         self.signers.keys().cloned().collect()
     }
+
+    /// Add a signer keeping its key outside of the process, e.g. in a hardware wallet
+    pub fn add_signer(&mut self, signer: Arc<dyn LoomTxSigner<LDT>>) {
+        self.signers.insert(signer.address(), signer);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloy_network::TransactionBuilder7702;
     use alloy_primitives::{address, TxHash};
     use alloy_rpc_types::TransactionRequest;
     use eyre::Result;
@@ -224,6 +245,30 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_sign_sync_eip7702() -> Result<()> {
+        let wallet = PrivateKeySigner::from_bytes(&B256::repeat_byte(1))?;
+        let signer = TxSignerEth::new(wallet);
+        let authorization = signer.sign_authorization(Authorization {
+            chain_id: alloy_primitives::U256::from(1),
+            address: Address::repeat_byte(2),
+            nonce: 2,
+        })?;
+        let tx_req = TransactionRequest::default()
+            .with_to(signer.address())
+            .with_nonce(1)
+            .with_gas_limit(1)
+            .with_max_fee_per_gas(1)
+            .with_max_priority_fee_per_gas(1)
+            .with_authorization_list(vec![authorization.clone()]);
+        let tx = signer.sign_sync(tx_req)?;
+        match tx.inner {
+            TxEnvelope::Eip7702(signed_tx) => assert_eq!(signed_tx.tx().authorization_list, vec![authorization]),
+            _ => panic!("TRANSACTION_IS_NOT_EIP7702"),
+        }
+        Ok(())
+    }
+
     // TxSigners tests
 
     #[test]
@@ -295,6 +340,15 @@ mod tests {
         assert!(signers.get_signer_by_address(&unknown_address).is_err());
     }
 
+    #[test]
+    fn test_add_signer() {
+        let mut signers = TxSigners::new();
+        let signer = TxSignerEth::default();
+        signers.add_signer(Arc::new(signer.clone()));
+        assert_eq!(signers.get_address_vec(), vec![signer.address()]);
+        assert!(signers.get_signer_by_address(&signer.address()).is_ok());
+    }
+
     #[test]
     fn test_get_address_vec() {
         let mut signers = TxSigners::new();
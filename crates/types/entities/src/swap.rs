@@ -1,7 +1,7 @@
 use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 
-use crate::{PoolId, PoolWrapper, SwapLine, SwapStep, Token};
+use crate::{FlashLoanProvider, PoolId, PoolWrapper, SwapLine, SwapStep, Token};
 use alloy_primitives::U256;
 use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
 
@@ -12,6 +12,13 @@ pub enum Swap<LDT: LoomDataTypes = LoomDataTypesEthereum> {
     BackrunSwapSteps((SwapStep<LDT>, SwapStep<LDT>)),
     BackrunSwapLine(SwapLine<LDT>),
     Multiple(Vec<Swap<LDT>>),
+    /// Swap line with `amount` of `asset` borrowed from `provider`, `asset` is the first token of `inner`
+    FlashLoanSwapLine {
+        provider: FlashLoanProvider,
+        asset: LDT::Address,
+        amount: U256,
+        inner: SwapLine<LDT>,
+    },
 }
 
 impl<LDT: LoomDataTypes> Display for Swap<LDT> {
@@ -21,12 +28,40 @@ impl<LDT: LoomDataTypes> Display for Swap<LDT> {
             Swap::BackrunSwapLine(path) => write!(f, "{path}"),
             Swap::BackrunSwapSteps((sp0, sp1)) => write!(f, "{sp0} {sp1}"),
             Swap::Multiple(_) => write!(f, "MULTIPLE_SWAP"),
+            Swap::FlashLoanSwapLine { provider, inner, .. } => write!(f, "FlashLoan({provider}) {inner}"),
             Swap::None => write!(f, "UNKNOWN_SWAP_TYPE"),
         }
     }
 }
 
 impl<LDT: LoomDataTypes> Swap<LDT> {
+    /// Borrow the input amount of the swap line from `provider`, `None` when a pool on the path is locked by the lender
+    pub fn new_flash_loan(provider: FlashLoanProvider, inner: SwapLine<LDT>) -> Option<Self> {
+        if inner.pools().iter().any(|pool| provider.is_pool_locked(pool)) {
+            return None;
+        }
+        let asset = inner.get_first_token()?.get_address();
+        let amount = inner.amount_in.unwrap_or_default();
+        Some(Swap::FlashLoanSwapLine { provider, asset, amount, inner })
+    }
+
+    /// Fee of the flash loan, zero for swaps using own capital
+    pub fn flash_loan_fee(&self) -> U256 {
+        match self {
+            Swap::FlashLoanSwapLine { provider, amount, .. } => provider.fee(*amount),
+            _ => U256::ZERO,
+        }
+    }
+
+    /// `SwapLine::path_fingerprint` of swaps made of a single swap line
+    pub fn path_fingerprint(&self) -> Option<[u8; 32]> {
+        match self {
+            Swap::ExchangeSwapLine(swap_line) | Swap::BackrunSwapLine(swap_line) => Some(swap_line.path_fingerprint()),
+            Swap::FlashLoanSwapLine { inner, .. } => Some(inner.path_fingerprint()),
+            _ => None,
+        }
+    }
+
     pub fn to_swap_steps(self: &Swap<LDT>, multicaller: LDT::Address) -> Option<(SwapStep<LDT>, SwapStep<LDT>)> {
         match self {
             Swap::BackrunSwapLine(swap_line) => swap_line.to_swap_steps(multicaller),
@@ -40,6 +75,7 @@ impl<LDT: LoomDataTypes> Swap<LDT> {
             Swap::BackrunSwapLine(path) => path.abs_profit(),
             Swap::BackrunSwapSteps((sp0, sp1)) => SwapStep::abs_profit(sp0, sp1),
             Swap::Multiple(swap_vec) => swap_vec.iter().map(|x| x.abs_profit()).sum(),
+            Swap::FlashLoanSwapLine { inner, .. } => inner.abs_profit().saturating_sub(self.flash_loan_fee()),
             Swap::None => U256::ZERO,
             Swap::ExchangeSwapLine(_) => U256::ZERO,
         }
@@ -54,6 +90,7 @@ impl<LDT: LoomDataTypes> Swap<LDT> {
                     + sp1.swap_line_vec().iter().map(|i| i.gas_used.unwrap_or_default()).sum::<u64>()
             }
             Swap::Multiple(swap_vec) => swap_vec.iter().map(|x| x.pre_estimate_gas()).sum(),
+            Swap::FlashLoanSwapLine { inner, .. } => inner.gas_used.unwrap_or_default(),
             Swap::None => 0,
         }
     }
@@ -64,6 +101,9 @@ impl<LDT: LoomDataTypes> Swap<LDT> {
             Swap::BackrunSwapLine(path) => path.abs_profit_eth(),
             Swap::BackrunSwapSteps((sp0, sp1)) => SwapStep::abs_profit_eth(sp0, sp1),
             Swap::Multiple(swap_vec) => swap_vec.iter().map(|x| x.abs_profit_eth()).sum(),
+            Swap::FlashLoanSwapLine { inner, .. } => {
+                inner.get_first_token().and_then(|token| token.calc_eth_value(self.abs_profit())).unwrap_or_default()
+            }
             Swap::None => U256::ZERO,
         }
     }
@@ -74,6 +114,7 @@ impl<LDT: LoomDataTypes> Swap<LDT> {
             Swap::BackrunSwapLine(swap_path) => swap_path.get_first_token(),
             Swap::BackrunSwapSteps((sp0, _sp1)) => sp0.get_first_token(),
             Swap::Multiple(_) => None,
+            Swap::FlashLoanSwapLine { inner, .. } => inner.get_first_token(),
             Swap::None => None,
         }
     }
@@ -88,6 +129,7 @@ impl<LDT: LoomDataTypes> Swap<LDT> {
                 swap_line_vec.iter().flat_map(|item| item.pools().iter().map(|p| p.get_pool_id()).collect::<Vec<_>>()).collect()
             }
             Swap::Multiple(swap_vec) => swap_vec.iter().flat_map(|x| x.get_pool_id_vec()).collect(),
+            Swap::FlashLoanSwapLine { inner, .. } => inner.pools().iter().map(|item| item.get_pool_id()).collect(),
             Swap::None => Vec::new(),
         }
     }
@@ -102,6 +144,7 @@ impl<LDT: LoomDataTypes> Swap<LDT> {
                 swap_line_vec.iter().flat_map(|item| item.pools().iter().map(|p| p.get_address()).collect::<Vec<_>>()).collect()
             }
             Swap::Multiple(swap_vec) => swap_vec.iter().flat_map(|x| x.get_pool_address_vec()).collect(),
+            Swap::FlashLoanSwapLine { inner, .. } => inner.pools().iter().map(|item| item.get_address()).collect(),
             Swap::None => Vec::new(),
         }
     }
@@ -116,7 +159,36 @@ impl<LDT: LoomDataTypes> Swap<LDT> {
                 swap_line_vec.iter().flat_map(|item| item.pools().iter().cloned()).collect::<Vec<_>>().to_vec()
             }
             Swap::Multiple(swap_vec) => swap_vec.iter().flat_map(|x| x.get_pools_vec()).collect(),
+            Swap::FlashLoanSwapLine { inner, .. } => inner.pools().clone(),
             Swap::None => Vec::new(),
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{MockPool, SwapAmountType, SwapPath};
+    use alloy_primitives::Address;
+
+    #[test]
+    fn test_flash_loan_profit() {
+        let (token_a, token_b) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let swap_line = SwapLine {
+            path: SwapPath::new(
+                vec![Token::new(token_a), Token::new(token_b), Token::new(token_a)],
+                vec![MockPool::new(token_a, token_b, Address::repeat_byte(10)), MockPool::new(token_a, token_b, Address::repeat_byte(11))],
+            ),
+            amount_in: SwapAmountType::Set(U256::from(1_000_000)),
+            amount_out: SwapAmountType::Set(U256::from(1_001_000)),
+            ..SwapLine::default()
+        };
+
+        let swap = Swap::new_flash_loan(FlashLoanProvider::BalancerV2, swap_line).unwrap();
+        let Swap::FlashLoanSwapLine { asset, amount, .. } = &swap else { panic!("NOT_FLASH_LOAN_SWAP") };
+        assert_eq!(*asset, token_a);
+        assert_eq!(*amount, U256::from(1_000_000));
+        assert_eq!(swap.flash_loan_fee(), U256::ZERO);
+        assert_eq!(swap.abs_profit(), U256::from(1000));
+    }
+}
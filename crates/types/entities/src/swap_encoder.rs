@@ -1,7 +1,8 @@
 use crate::tips::Tips;
 use crate::Swap;
+use alloy_eips::eip7702::Authorization;
 use alloy_primitives::{Address, BlockNumber, Bytes, U256};
-use eyre::Result;
+use eyre::{OptionExt, Result};
 use std::ops::Deref;
 use std::sync::Arc;
 
@@ -32,6 +33,55 @@ pub trait SwapEncoder {
     fn address(&self) -> Address;
 }
 
+/// Encodes swaps for an EOA delegated to the encoder contract with EIP-7702. The EOA calls itself, so swap steps are encoded with
+/// the EOA as the contract address and no external contract call is made.
+///
+/// The delegated code runs on the storage and balances of the EOA, not of the encoder contract. Swaps must be funded by
+/// the EOA, the swap router falls back to the contract when the EOA does not hold the input amount.
+#[derive(Clone)]
+pub struct Eip7702SwapEncoder<E> {
+    inner: E,
+}
+
+impl<E: SwapEncoder + Clone> Eip7702SwapEncoder<E> {
+    pub fn new(inner: E) -> Self {
+        Self { inner }
+    }
+
+    /// Delegation to the encoder contract. The sender nonce is increased before authorizations are applied, so an
+    /// authorization of the sender itself uses the transaction nonce plus one.
+    pub fn authorization(&self, chain_id: u64, nonce: u64) -> Authorization {
+        Authorization { chain_id: U256::from(chain_id), address: self.inner.address(), nonce }
+    }
+}
+
+impl<E: SwapEncoder + Clone> SwapEncoder for Eip7702SwapEncoder<E> {
+    fn encode(
+        &self,
+        swap: Swap,
+        tips_pct: Option<u32>,
+        next_block_number: Option<BlockNumber>,
+        gas_cost: Option<U256>,
+        sender_address: Option<Address>,
+        sender_eth_balance: Option<U256>,
+    ) -> Result<(Address, Option<U256>, Bytes, Vec<Tips>)> {
+        let sender_address = sender_address.ok_or_eyre("NO_SENDER_ADDRESS")?;
+        let mut encoder = self.inner.clone();
+        encoder.set_address(sender_address);
+        let (_, call_value, call_data, tips_vec) =
+            encoder.encode(swap, tips_pct, next_block_number, gas_cost, Some(sender_address), sender_eth_balance)?;
+        Ok((sender_address, call_value, call_data, tips_vec))
+    }
+
+    fn set_address(&mut self, address: Address) {
+        self.inner.set_address(address);
+    }
+
+    fn address(&self) -> Address {
+        self.inner.address()
+    }
+}
+
 #[derive(Clone)]
 pub struct SwapEncoderWrapper {
     pub inner: Arc<dyn SwapEncoder>,
@@ -55,3 +105,59 @@ impl Deref for SwapEncoderWrapper {
         self.inner.deref()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use eyre::eyre;
+
+    #[derive(Clone)]
+    struct AddressEncoder {
+        address: Address,
+    }
+
+    impl SwapEncoder for AddressEncoder {
+        fn encode(
+            &self,
+            _swap: Swap,
+            _tips_pct: Option<u32>,
+            _next_block_number: Option<BlockNumber>,
+            _gas_cost: Option<U256>,
+            sender_address: Option<Address>,
+            _sender_eth_balance: Option<U256>,
+        ) -> Result<(Address, Option<U256>, Bytes, Vec<Tips>)> {
+            if sender_address.is_none() {
+                return Err(eyre!("NO_SENDER_ADDRESS"));
+            }
+            // The contract address is encoded into the call data
+            Ok((self.address, None, Bytes::copy_from_slice(self.address.as_slice()), vec![]))
+        }
+
+        fn set_address(&mut self, address: Address) {
+            self.address = address
+        }
+
+        fn address(&self) -> Address {
+            self.address
+        }
+    }
+
+    #[test]
+    fn test_eip7702_swap_encoder() -> Result<()> {
+        let (multicaller, eoa) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let encoder = Eip7702SwapEncoder::new(AddressEncoder { address: multicaller });
+
+        let (to, _, call_data, _) = encoder.encode(Swap::None, None, None, None, Some(eoa), None)?;
+        assert_eq!(to, eoa);
+        assert_eq!(call_data, Bytes::copy_from_slice(eoa.as_slice()));
+        // The wrapped encoder keeps the contract address, authorizations delegate to it
+        assert_eq!(encoder.address(), multicaller);
+        assert!(encoder.encode(Swap::None, None, None, None, None, None).is_err());
+
+        let authorization = encoder.authorization(8453, 7);
+        assert_eq!(authorization.address, multicaller);
+        assert_eq!(authorization.chain_id, U256::from(8453));
+        assert_eq!(authorization.nonce, 7);
+        Ok(())
+    }
+}
@@ -28,6 +28,22 @@ pub struct SwapError<LDT: LoomDataTypes = LoomDataTypesEthereum> {
     pub amount: U256,
 }
 
+impl<LDT: LoomDataTypes> SwapError<LDT> {
+    /// Message of errors for calculations that did not finish within the simulation timeout
+    pub const TIMEOUT: &'static str = "SIMULATION_TIMEOUT";
+
+    /// Message of errors for paths that simulated successfully without a profitable amount
+    pub const NO_PROFIT: &'static str = "NO_PROFITABLE_AMOUNT_FOUND";
+
+    pub fn is_timeout(&self) -> bool {
+        self.msg == Self::TIMEOUT
+    }
+
+    pub fn is_no_profit(&self) -> bool {
+        self.msg == Self::NO_PROFIT
+    }
+}
+
 impl<LDT: LoomDataTypes> From<SwapError<LDT>> for Report {
     fn from(value: SwapError<LDT>) -> Self {
         eyre!(value.msg)
@@ -2,8 +2,10 @@ use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
-use alloy_primitives::{I256, U256};
+use alloy_primitives::{Address, Bytes, I256, U256};
+use alloy_sol_types::SolCall;
 use eyre::{eyre, ErrReport, Report, Result};
+use loom_defi_abi::ISignatureTransfer;
 use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
 use revm::primitives::Env;
 use revm::DatabaseRef;
@@ -145,6 +147,11 @@ impl<LDT: LoomDataTypes> From<SwapPath<LDT>> for SwapLine<LDT> {
 }
 
 impl<LDT: LoomDataTypes> SwapLine<LDT> {
+    /// Stable identifier of the swap path, see `SwapPath::fingerprint`
+    pub fn path_fingerprint(&self) -> [u8; 32] {
+        self.path.fingerprint()
+    }
+
     pub fn to_error(&self, msg: String) -> SwapError<LDT> {
         SwapError {
             msg,
@@ -249,6 +256,32 @@ impl<LDT: LoomDataTypes> SwapLine<LDT> {
         Ok((first, second))
     }
 
+    /// Split the swap line into two swap lines on the same path with `ratio` and `1 - ratio` of the input amount. The ratio is
+    /// clamped to [0, 1] and applied with a precision of 1e-6, the two input amounts always sum up to the original one. Output
+    /// amounts are not proportional to the input and have to be calculated again.
+    pub fn split_at_ratio(&self, ratio: f64) -> (SwapLine<LDT>, SwapLine<LDT>) {
+        const RATIO_PRECISION: u64 = 1_000_000;
+
+        let (first_amount_in, second_amount_in) = match self.amount_in {
+            SwapAmountType::Set(amount_in) => {
+                let ratio_scaled = U256::from((ratio.clamp(0.0, 1.0) * RATIO_PRECISION as f64).round() as u64);
+                let first_amount_in = amount_in.saturating_mul(ratio_scaled) / U256::from(RATIO_PRECISION);
+                (SwapAmountType::Set(first_amount_in), SwapAmountType::Set(amount_in - first_amount_in))
+            }
+            amount_in => (amount_in, amount_in),
+        };
+        let first = SwapLine::<LDT> {
+            path: self.path.clone(),
+            amount_in: first_amount_in,
+            amount_out: SwapAmountType::NotSet,
+            calculation_results: vec![],
+            swap_to: self.swap_to,
+            gas_used: None,
+        };
+        let second = SwapLine::<LDT> { amount_in: second_amount_in, ..first.clone() };
+        (first, second)
+    }
+
     /// Check if all pools in the swap line can be flash swapped
     pub fn can_flash_swap(&self) -> bool {
         for pool in self.pools().iter() {
@@ -530,6 +563,56 @@ impl<LDT: LoomDataTypes> SwapLine<LDT> {
     }
 }
 
+impl SwapLine<LoomDataTypesEthereum> {
+    /// Per gas priority fee paying `bribe_pct` percent of the profit left after the base fee cost of `gas_estimate` gas
+    /// to the block builder
+    pub fn estimated_miner_bribe(profit_eth: U256, gas_estimate: u64, base_fee: u64, bribe_pct: u8) -> u64 {
+        if gas_estimate == 0 {
+            return 0;
+        }
+        let base_fee_cost = U256::from(gas_estimate) * U256::from(base_fee);
+        let net_profit = profit_eth.saturating_sub(base_fee_cost);
+        let bribe = net_profit * U256::from(bribe_pct.min(100)) / U256::from(100);
+        (bribe / U256::from(gas_estimate)).saturating_to::<u64>()
+    }
+
+    /// Build Permit2 batch permits for the input tokens of the swap line and encode `ISignatureTransfer.permitBatchTransferFrom`
+    /// pulling them from `owner` to `recipient`. The permit is deterministic for the given nonce and deadline, so it can be
+    /// built with an empty signature first, signed by the owner and encoded again with the signature.
+    pub fn encode_for_permit2_batch(
+        &self,
+        owner: Address,
+        recipient: Address,
+        nonce: U256,
+        deadline: U256,
+        signature: Bytes,
+    ) -> Result<(Vec<ISignatureTransfer::PermitBatchTransferFrom>, Bytes)> {
+        let token_in = self.get_first_token().ok_or(eyre!("NO_FIRST_TOKEN"))?;
+        let amount_in = match self.amount_in {
+            SwapAmountType::Set(amount) => amount,
+            _ => return Err(eyre!("AMOUNT_IN_NOT_SET")),
+        };
+
+        let permitted = vec![ISignatureTransfer::TokenPermissions { token: token_in.get_address(), amount: amount_in }];
+        let transfer_details: Vec<ISignatureTransfer::SignatureTransferDetails> = permitted
+            .iter()
+            .map(|permission| ISignatureTransfer::SignatureTransferDetails { to: recipient, requestedAmount: permission.amount })
+            .collect();
+
+        let permit = ISignatureTransfer::PermitBatchTransferFrom { permitted, nonce, deadline };
+
+        let call_data = ISignatureTransfer::permitBatchTransferFromCall {
+            permit: permit.clone(),
+            transferDetails: transfer_details,
+            owner,
+            signature,
+        }
+        .abi_encode();
+
+        Ok((vec![permit], call_data.into()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -542,10 +625,9 @@ mod tests {
     fn default_swap_line() -> (MockPool, MockPool, SwapLine<LoomDataTypesEthereum>) {
         let token0 = Arc::new(Token::new_with_data(TokenAddressEth::WETH, Some("WETH".to_string()), None, Some(18), true, false));
         let token1 = Arc::new(Token::new_with_data(TokenAddressEth::USDT, Some("USDT".to_string()), None, Some(6), true, false));
-        let pool1 =
-            MockPool { token0: TokenAddressEth::WETH, token1: TokenAddressEth::USDT, address: UniswapV3PoolAddress::WETH_USDT_3000 };
+        let pool1 = MockPool::new(TokenAddressEth::WETH, TokenAddressEth::USDT, UniswapV3PoolAddress::WETH_USDT_3000);
         let pool2_address = Address::random();
-        let pool2 = MockPool { token0: TokenAddressEth::WETH, token1: TokenAddressEth::USDT, address: UniswapV2PoolAddress::WETH_USDT };
+        let pool2 = MockPool::new(TokenAddressEth::WETH, TokenAddressEth::USDT, UniswapV2PoolAddress::WETH_USDT);
 
         let swap_path =
             SwapPath::new(vec![token0.clone(), token1.clone(), token1.clone(), token0.clone()], vec![pool1.clone(), pool2.clone()]);
@@ -576,6 +658,93 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_path_fingerprint() {
+        for _ in 0..100 {
+            let len = rand::random::<usize>() % 4 + 1;
+            let token_addresses: Vec<Address> = (0..=len).map(|_| Address::random()).collect();
+            let pool_addresses: Vec<Address> = (0..len).map(|_| Address::random()).collect();
+
+            // Lines with own token and pool instances, amounts and scores
+            let new_swap_line = |amount_in: u64, score: Option<f64>| {
+                let tokens: Vec<Token> = token_addresses.iter().map(|address| Token::new(*address)).collect();
+                let pools: Vec<MockPool> = pool_addresses
+                    .iter()
+                    .zip(token_addresses.windows(2))
+                    .map(|(address, tokens)| MockPool::new(tokens[0], tokens[1], *address))
+                    .collect();
+                let mut path = SwapPath::new(tokens, pools);
+                path.score = score;
+                SwapLine { path, amount_in: SwapAmountType::Set(U256::from(amount_in)), ..SwapLine::default() }
+            };
+
+            let swap_line = new_swap_line(rand::random(), None);
+            let other_swap_line = new_swap_line(rand::random(), Some(rand::random()));
+            assert_eq!(swap_line.path_fingerprint(), other_swap_line.path_fingerprint());
+
+            let mut reversed_swap_line = swap_line.clone();
+            reversed_swap_line.path.tokens.reverse();
+            reversed_swap_line.path.pools.reverse();
+            assert_ne!(swap_line.path_fingerprint(), reversed_swap_line.path_fingerprint());
+        }
+    }
+
+    #[test]
+    fn test_split_at_ratio() {
+        let (_, _, swap_line) = default_swap_line();
+        let amount_in = swap_line.amount_in.unwrap();
+
+        let (probe, rest) = swap_line.split_at_ratio(0.5);
+        assert_eq!(probe.amount_in.unwrap(), amount_in / U256::from(2));
+        assert_eq!(probe.amount_in.unwrap() + rest.amount_in.unwrap(), amount_in);
+        assert!(probe.amount_out.is_not_set());
+        assert_eq!(probe.path, swap_line.path);
+        assert_eq!(rest.swap_to, swap_line.swap_to);
+
+        let (all, none) = swap_line.split_at_ratio(1.5);
+        assert_eq!(all.amount_in.unwrap(), amount_in);
+        assert_eq!(none.amount_in.unwrap(), U256::ZERO);
+
+        let (first, second) = SwapLine::<LoomDataTypesEthereum>::default().split_at_ratio(0.5);
+        assert!(first.amount_in.is_not_set() && second.amount_in.is_not_set());
+    }
+
+    #[test]
+    fn test_estimated_miner_bribe() {
+        let profit = parse_units("0.01", "ether").unwrap().get_absolute();
+        let base_fee = 10_000_000_000u64;
+
+        // (0.01 ETH - 200_000 * 10 gwei) * 50% / 200_000 = 20 gwei
+        assert_eq!(SwapLine::estimated_miner_bribe(profit, 200_000, base_fee, 50), 20_000_000_000);
+        // (0.01 ETH - 100_000 * 10 gwei) * 90% / 100_000 = 81 gwei
+        assert_eq!(SwapLine::estimated_miner_bribe(profit, 100_000, base_fee, 90), 81_000_000_000);
+        // Percent is capped at 100
+        assert_eq!(SwapLine::estimated_miner_bribe(profit, 200_000, base_fee, 200), 40_000_000_000);
+        // Profit does not cover the base fee cost
+        assert_eq!(SwapLine::estimated_miner_bribe(profit, 1_000_000, base_fee, 50), 0);
+        assert_eq!(SwapLine::estimated_miner_bribe(profit, 200_000, base_fee, 0), 0);
+        assert_eq!(SwapLine::estimated_miner_bribe(profit, 0, base_fee, 50), 0);
+    }
+
+    #[test]
+    fn test_encode_for_permit2_batch() {
+        let (_, _, swap_line) = default_swap_line();
+        let owner = Address::random();
+        let recipient = Address::random();
+
+        let (permits, call_data) =
+            swap_line.encode_for_permit2_batch(owner, recipient, U256::from(1), U256::from(1000), Bytes::default()).unwrap();
+
+        assert_eq!(permits.len(), 1);
+        assert_eq!(permits[0].permitted[0].token, TokenAddressEth::WETH);
+        assert_eq!(permits[0].permitted[0].amount, swap_line.amount_in.unwrap());
+
+        let decoded = ISignatureTransfer::permitBatchTransferFromCall::abi_decode(&call_data, true).unwrap();
+        assert_eq!(decoded.owner, owner);
+        assert_eq!(decoded.transferDetails[0].to, recipient);
+        assert_eq!(decoded.permit.nonce, U256::from(1));
+    }
+
     #[test]
     fn test_contains_pool() {
         let (pool1, pool2, swap_line) = default_swap_line();
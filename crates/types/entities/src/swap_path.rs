@@ -1,6 +1,7 @@
 use crate::pool_id::PoolId;
-use crate::{PoolWrapper, SwapDirection, Token};
+use crate::{PnlHistory, PoolWrapper, SwapDirection, Token};
 use alloy_primitives::map::HashMap;
+use alloy_primitives::Keccak256;
 use eyre::Result;
 use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
 use std::fmt;
@@ -134,6 +135,38 @@ impl<LDT: LoomDataTypes> SwapPath<LDT> {
         let hash = self.hash(&mut h);
         h.finish()
     }
+
+    /// Keccak256 of the ordered token addresses and pool ids, equal for paths found by different searchers regardless of
+    /// amounts, score and disabled state. It does not depend on the hasher implementation, so it identifies the path across restarts
+    pub fn fingerprint(&self) -> [u8; 32] {
+        let mut hasher = Keccak256::new();
+        for token in self.tokens.iter() {
+            hasher.update(token.get_address().as_ref());
+        }
+        hasher.update(b"|");
+        for pool in self.pools.iter() {
+            hasher.update(pool.get_pool_id().as_slice());
+        }
+        hasher.finalize().0
+    }
+
+    /// Leading 8 bytes of `fingerprint` as a key of paths found by different searchers
+    #[inline]
+    pub fn canonical_path_key(&self) -> u64 {
+        let mut key = [0u8; 8];
+        key.copy_from_slice(&self.fingerprint()[..8]);
+        u64::from_be_bytes(key)
+    }
+
+    /// Base score multiplied by the share of successful simulations recorded for this path.
+    /// Paths without history keep their base score.
+    pub fn score_by_historical_success_rate(&self, history: &PnlHistory) -> f64 {
+        let base_score = self.score.unwrap_or_default();
+        match history.success_rate(self.get_hash()) {
+            Some(success_rate) => base_score * success_rate,
+            None => base_score,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -461,6 +494,51 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_score_by_historical_success_rate() {
+        let basic_token = Token::new(Address::repeat_byte(0x11));
+        let mut path = SwapPath::new(
+            vec![basic_token.clone(), Token::new(Address::repeat_byte(1)), basic_token.clone()],
+            vec![
+                PoolWrapper::new(Arc::new(EmptyPool::new(Address::repeat_byte(2)))),
+                PoolWrapper::new(Arc::new(EmptyPool::new(Address::repeat_byte(3)))),
+            ],
+        );
+        path.score = Some(0.99);
+
+        let mut history = PnlHistory::new();
+        assert_eq!(path.score_by_historical_success_rate(&history), 0.99);
+
+        history.record_simulation(path.get_hash(), true);
+        history.record_simulation(path.get_hash(), false);
+        history.record_simulation(path.get_hash(), false);
+
+        assert!(path.score_by_historical_success_rate(&history) < 0.5);
+    }
+
+    #[test]
+    fn test_canonical_path_key() {
+        let basic_token = Token::new(Address::repeat_byte(0x11));
+        let pools = vec![
+            PoolWrapper::new(Arc::new(EmptyPool::new(Address::repeat_byte(2)))),
+            PoolWrapper::new(Arc::new(EmptyPool::new(Address::repeat_byte(3)))),
+        ];
+
+        let path = SwapPath::new(vec![basic_token.clone(), Token::new(Address::repeat_byte(1)), basic_token.clone()], pools.clone());
+        let mut scored_path = path.clone();
+        scored_path.score = Some(0.5);
+        scored_path.disabled = true;
+        assert_eq!(path.fingerprint(), scored_path.fingerprint());
+        assert_eq!(path.canonical_path_key(), scored_path.canonical_path_key());
+
+        let reversed_path = SwapPath::new(
+            vec![basic_token.clone(), Token::new(Address::repeat_byte(1)), basic_token.clone()],
+            pools.into_iter().rev().collect::<Vec<_>>(),
+        );
+        assert_ne!(path.fingerprint(), reversed_path.fingerprint());
+        assert_ne!(path.canonical_path_key(), reversed_path.canonical_path_key());
+    }
+
     #[test]
     fn test_disable_path() {
         let basic_token = Token::new(Address::repeat_byte(0x11));
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use alloy_primitives::{Address, BlockNumber, B256};
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{Market, PoolId, PoolWrapper, SwapPath, Token};
+
+/// Swap path referencing tokens and pools by their index in the `SwapPathsCache` lookup tables
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct SwapPathCacheEntry {
+    tokens: Vec<usize>,
+    pools: Vec<usize>,
+    disabled: bool,
+    score: Option<f64>,
+}
+
+/// Swap paths saved to a file to skip path discovery on restart. Token addresses and pool ids are stored once
+/// in lookup tables, pools are resolved from the market when the paths are loaded.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SwapPathsCache {
+    pub block_number: BlockNumber,
+    tokens: Vec<Address>,
+    // Pool ids as 32 bytes words, addresses are left padded with zeros
+    pools: Vec<B256>,
+    paths: Vec<SwapPathCacheEntry>,
+}
+
+fn pool_id_to_word(pool_id: &PoolId) -> B256 {
+    match pool_id {
+        PoolId::Address(address) => address.into_word(),
+        PoolId::Bytes32(bytes) => *bytes,
+    }
+}
+
+fn pool_id_from_word(word: &B256) -> PoolId {
+    if word[..12].iter().all(|byte| *byte == 0) {
+        PoolId::Address(Address::from_word(*word))
+    } else {
+        PoolId::Bytes32(*word)
+    }
+}
+
+impl SwapPathsCache {
+    pub fn new(block_number: BlockNumber, swap_paths: &[SwapPath]) -> Self {
+        let mut token_idx: HashMap<Address, usize> = HashMap::new();
+        let mut pool_idx: HashMap<PoolId, usize> = HashMap::new();
+        let mut cache = SwapPathsCache { block_number, ..Default::default() };
+
+        for swap_path in swap_paths.iter() {
+            let tokens = swap_path
+                .tokens
+                .iter()
+                .map(|token| {
+                    *token_idx.entry(token.get_address()).or_insert_with(|| {
+                        cache.tokens.push(token.get_address());
+                        cache.tokens.len() - 1
+                    })
+                })
+                .collect();
+            let pools = swap_path
+                .pools
+                .iter()
+                .map(|pool| {
+                    *pool_idx.entry(pool.get_pool_id()).or_insert_with(|| {
+                        cache.pools.push(pool_id_to_word(&pool.get_pool_id()));
+                        cache.pools.len() - 1
+                    })
+                })
+                .collect();
+            cache.paths.push(SwapPathCacheEntry { tokens, pools, disabled: swap_path.disabled, score: swap_path.score });
+        }
+        cache
+    }
+
+    pub fn read_from_file(path: &Path) -> Result<Self> {
+        let data = fs::read(path)?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    /// Writes to a temporary file first, a crash while saving never leaves a partially written cache
+    pub fn write_to_file(&self, path: &Path) -> Result<()> {
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_vec(self)?)?;
+        fs::rename(tmp_path, path)?;
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Cache is fresh when it was saved no more than `max_age_blocks` before `block_number`
+    pub fn is_fresh(&self, block_number: BlockNumber, max_age_blocks: u64) -> bool {
+        block_number.saturating_sub(self.block_number) <= max_age_blocks
+    }
+
+    pub fn contains_pool(&self, pool_id: &PoolId) -> bool {
+        let word = pool_id_to_word(pool_id);
+        self.pools.contains(&word)
+    }
+
+    fn resolve_entry(&self, market: &Market, entry: &SwapPathCacheEntry) -> Option<SwapPath> {
+        let tokens: Option<Vec<Arc<Token>>> =
+            entry.tokens.iter().map(|idx| self.tokens.get(*idx).map(|address| market.get_token_or_default(address))).collect();
+        let pools: Option<Vec<PoolWrapper>> = entry
+            .pools
+            .iter()
+            .map(|idx| self.pools.get(*idx).and_then(|word| market.get_pool(&pool_id_from_word(word)).cloned()))
+            .collect();
+        Some(SwapPath { tokens: tokens?, pools: pools?, disabled: entry.disabled, score: entry.score, ..Default::default() })
+    }
+
+    /// Swap paths with all pools present in the market. Paths of pools that are no longer in the market are dropped.
+    pub fn swap_paths(&self, market: &Market) -> Vec<SwapPath> {
+        self.paths.iter().filter_map(|entry| self.resolve_entry(market, entry)).collect()
+    }
+
+    /// Swap paths going through `pool_id` with all pools present in the market
+    pub fn pool_swap_paths(&self, market: &Market, pool_id: &PoolId) -> Vec<SwapPath> {
+        let word = pool_id_to_word(pool_id);
+        let Some(pool_idx) = self.pools.iter().position(|pool| *pool == word) else {
+            return Vec::new();
+        };
+        self.paths.iter().filter(|entry| entry.pools.contains(&pool_idx)).filter_map(|entry| self.resolve_entry(market, entry)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockPool;
+    use loom_defi_address_book::TokenAddressEth;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_swap_paths_cache() -> Result<()> {
+        let token1 = Address::repeat_byte(0x01);
+        let pool1 = PoolWrapper::new(Arc::new(MockPool::new(TokenAddressEth::WETH, token1, Address::repeat_byte(0x11))));
+        let pool2 = PoolWrapper::new(Arc::new(MockPool::new(TokenAddressEth::WETH, token1, Address::repeat_byte(0x12))));
+
+        let mut market = Market::default();
+        market.add_token(Token::new_with_data(TokenAddressEth::WETH, Some("WETH".to_string()), None, Some(18), true, false));
+        market.add_pool(pool1.clone())?;
+        market.add_pool(pool2.clone())?;
+
+        let mut directions = BTreeMap::new();
+        directions.insert(pool2.clone(), pool2.get_swap_directions());
+        let swap_paths = market.build_swap_path_vec(&directions)?;
+        assert_eq!(swap_paths.len(), 2);
+
+        let cache = SwapPathsCache::new(100, &swap_paths);
+        let cache: SwapPathsCache = serde_json::from_str(&serde_json::to_string(&cache)?)?;
+        assert_eq!(cache.len(), 2);
+        assert!(cache.contains_pool(&pool1.get_pool_id()));
+        assert!(cache.is_fresh(150, 50));
+        assert!(!cache.is_fresh(151, 50));
+
+        let loaded_paths = cache.swap_paths(&market);
+        assert_eq!(loaded_paths, swap_paths);
+        assert_eq!(cache.pool_swap_paths(&market, &pool1.get_pool_id()).len(), 2);
+
+        // Paths with pools missing in the market are dropped
+        let mut market = Market::default();
+        market.add_pool(pool1.clone())?;
+        assert!(cache.swap_paths(&market).is_empty());
+        assert!(cache.pool_swap_paths(&market, &pool1.get_pool_id()).is_empty());
+
+        Ok(())
+    }
+}
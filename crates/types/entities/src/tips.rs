@@ -83,7 +83,7 @@ pub fn tips_and_value_for_swap_type(
     }
 
     match swap {
-        Swap::BackrunSwapLine(_) | Swap::BackrunSwapSteps(_) => {
+        Swap::BackrunSwapLine(_) | Swap::BackrunSwapSteps(_) | Swap::FlashLoanSwapLine { .. } => {
             let profit = swap.abs_profit();
             if profit.is_zero() {
                 error!(profit = NWETH::to_float(profit), %swap, "Zero profit");
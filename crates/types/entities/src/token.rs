@@ -6,7 +6,8 @@ use std::sync::Arc;
 use std::sync::RwLock;
 
 use alloy_primitives::utils::Unit;
-use alloy_primitives::{I256, U256};
+use alloy_primitives::{Address, I256, U256};
+use loom_defi_address_book::{TokenAddressArbitrum, TokenAddressBase, TokenAddressEth};
 use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
 
 const ONE_ETHER: U256 = Unit::ETHER.wei_const();
@@ -104,9 +105,10 @@ impl<LDT: LoomDataTypes> Token<LDT> {
         self.address
     }
 
+    /// Tokens flagged basic, native ETH and the wrapped native tokens of supported chains
     #[inline]
     pub fn is_basic(&self) -> bool {
-        self.basic
+        self.basic || LDT::is_native_or_wrapped(&self.address)
     }
 
     #[inline]
@@ -191,10 +193,30 @@ impl<LDT: LoomDataTypes> Token<LDT> {
     }
 }
 
+impl Token<LoomDataTypesEthereum> {
+    /// Native ETH, the zero address or the `0xEeee...EEeE` sentinel
+    pub fn is_native_eth(&self) -> bool {
+        TokenAddressEth::is_eth_or_sentinel(&self.address)
+    }
+
+    /// Address of the token for path finding on `chain_id`. Native ETH resolves to the wrapped native token of the chain,
+    /// other tokens and native ETH on unknown chains keep their address.
+    pub fn get_canonical_address(&self, chain_id: u64) -> Address {
+        if !self.is_native_eth() {
+            return self.address;
+        }
+        match chain_id {
+            1 => TokenAddressEth::WETH,
+            8453 => TokenAddressBase::WETH,
+            42161 => TokenAddressArbitrum::WETH,
+            _ => self.address,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use loom_defi_address_book::TokenAddressEth;
 
     #[test]
     fn test_to_float() {
@@ -213,4 +235,39 @@ mod test {
 
         println!("{}", weth_token.to_float(one_ether));
     }
+
+    #[test]
+    fn test_get_canonical_address() {
+        let eth_token: Token = Token::new(TokenAddressEth::ETH_NATIVE);
+        let eth_sentinel_token: Token = Token::new(TokenAddressEth::ETH_SENTINEL);
+        let usdc_token: Token = Token::new(TokenAddressBase::USDC);
+
+        // Ethereum mainnet
+        assert_eq!(eth_token.get_canonical_address(1), TokenAddressEth::WETH);
+        assert_eq!(eth_sentinel_token.get_canonical_address(1), TokenAddressEth::WETH);
+        // Base
+        assert_eq!(eth_token.get_canonical_address(8453), TokenAddressBase::WETH);
+        assert_eq!(eth_sentinel_token.get_canonical_address(8453), TokenAddressBase::WETH);
+        // Arbitrum
+        assert_eq!(eth_token.get_canonical_address(42161), TokenAddressArbitrum::WETH);
+        assert_eq!(eth_sentinel_token.get_canonical_address(42161), TokenAddressArbitrum::WETH);
+
+        assert_eq!(eth_token.get_canonical_address(100), TokenAddressEth::ETH_NATIVE);
+        assert_eq!(usdc_token.get_canonical_address(8453), TokenAddressBase::USDC);
+        assert!(!usdc_token.is_native_eth());
+    }
+
+    #[test]
+    fn test_is_basic() {
+        assert!(Token::<LoomDataTypesEthereum>::new(TokenAddressEth::ETH_NATIVE).is_basic());
+        assert!(Token::<LoomDataTypesEthereum>::new(TokenAddressEth::ETH_SENTINEL).is_basic());
+        assert!(Token::<LoomDataTypesEthereum>::new(TokenAddressEth::WETH).is_basic());
+        assert!(Token::<LoomDataTypesEthereum>::new(TokenAddressBase::WETH).is_basic());
+        assert!(Token::<LoomDataTypesEthereum>::new(TokenAddressArbitrum::WETH).is_basic());
+        assert!(!Token::<LoomDataTypesEthereum>::new(TokenAddressBase::USDC).is_basic());
+
+        let mut usdc_token = Token::<LoomDataTypesEthereum>::new(TokenAddressBase::USDC);
+        usdc_token.set_basic();
+        assert!(usdc_token.is_basic());
+    }
 }
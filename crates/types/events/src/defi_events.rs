@@ -1,6 +1,6 @@
 use alloy_primitives::BlockNumber;
 use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
-use loom_types_entities::PoolId;
+use loom_types_entities::{PoolId, PoolStats};
 
 #[derive(Clone, Debug)]
 pub enum MarketEvents<LDT: LoomDataTypes = LoomDataTypesEthereum> {
@@ -9,6 +9,16 @@ pub enum MarketEvents<LDT: LoomDataTypes = LoomDataTypesEthereum> {
     BlockLogsUpdate { block_number: BlockNumber, block_hash: LDT::BlockHash },
     BlockStateUpdate { block_hash: LDT::BlockHash },
     NewPoolLoaded { pool_id: PoolId<LDT>, swap_path_idx_vec: Vec<usize> },
+    PoolStatsUpdate { pool_id: PoolId<LDT>, stats: PoolStats },
+    /// Blocks above the common ancestor of `old_tip` and `new_tip` were replaced, `depth` is the number of dropped blocks
+    Reorg { depth: usize, old_tip: LDT::BlockHash, new_tip: LDT::BlockHash },
+    /// Reorg deeper than block history is trusted to roll back or with the common ancestor missing in block history,
+    /// the market state is reloaded at `new_tip`
+    DeepReorg { old_tip: LDT::BlockHash, new_tip: LDT::BlockHash },
+    /// Node ExEx gRPC streams were lost, block and state updates received until `ExExReconnected` may be stale
+    ExExDisconnected,
+    /// Node ExEx gRPC streams were subscribed again after `ExExDisconnected`
+    ExExReconnected,
 }
 
 #[derive(Clone, Debug)]
@@ -27,4 +37,9 @@ pub enum MempoolEvents<LDT: LoomDataTypes = LoomDataTypesEthereum> {
     MempoolLogUpdate {
         tx_hash: LDT::TxHash,
     },
+    /// The transaction is the victim of a sandwich with the front transaction `front_tx`.
+    SandwichDetected {
+        victim_tx: LDT::TxHash,
+        front_tx: LDT::TxHash,
+    },
 }
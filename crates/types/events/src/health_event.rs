@@ -1,4 +1,5 @@
 use crate::Message;
+use alloy_primitives::{I256, U256};
 use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
 use loom_types_entities::{EstimationError, SwapError};
 
@@ -7,6 +8,24 @@ pub enum HealthEvent<LDT: LoomDataTypes = LoomDataTypesEthereum> {
     PoolSwapError(SwapError<LDT>),
     SwapLineEstimationError(EstimationError<LDT>),
     MonitorTx(LDT::TxHash),
+    /// EVM and Geth estimators disagree on the net profit of the same swap path
+    SimulationDiscrepancy { path_hash: u64, evm_profit: U256, geth_profit: U256, delta_bps: u64 },
+    /// A channel subscriber is behind the sender by `lag` messages
+    ChannelLag { subscriber: &'static str, lag: usize },
+    /// Account balance of `token` covers fewer trades of `amount` than required
+    LowBalance { token: LDT::Address, balance: U256, amount: U256 },
+    /// Gas used by a landed transaction differs from the estimated gas by more than the allowed share
+    EstimatorDivergence { tx_hash: LDT::TxHash, estimated_gas: u64, gas_used: u64 },
+    /// The same asset is priced differently on two chains, prices are in tokens per ETH of the chain
+    CrossChainPriceDiscrepancy { symbol: String, chain_id: u64, other_chain_id: u64, price: f64, other_price: f64, delta_bps: u64 },
+    /// Own backrun transaction landed at `position` of the block, the profit is net of the gas cost
+    BundleConfirmed { tx_hash: LDT::TxHash, block_number: u64, position: usize, realized_profit_wei: I256 },
+    /// Bundle for `block_number` reverted in the relay simulation and was not broadcast
+    BundleRevertedInSimulation { block_number: u64, revert_reason: Option<String> },
+    /// Bundle for `block_number` competes with `conflicts` pending transactions for the same senders and nonces
+    BundleConflicts { block_number: u64, conflicts: usize, inclusion_probability: f64 },
+    /// Pool derived ETH price of `token` deviates from the Chainlink price, prices are in token units per ETH
+    PriceManipulationSuspected { token: LDT::Address, pool_price: U256, oracle_price: U256 },
 }
 
 pub type MessageHealthEvent<LDT = LoomDataTypesEthereum> = Message<HealthEvent<LDT>>;
@@ -5,6 +5,7 @@ pub use message::Message;
 pub use node::*;
 pub use state_update_event::*;
 pub use swap_compose::*;
+pub use swap_trace::{SwapTraces, TraceFrame};
 pub use tasks::LoomTask;
 pub use tx_compose::*;
 
@@ -14,6 +15,7 @@ mod health_event;
 mod message;
 mod node;
 mod swap_compose;
+mod swap_trace;
 
 mod state_update_event;
 mod tasks;
@@ -1,4 +1,5 @@
 use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
 use std::ops::Deref;
 
 use chrono::Utc;
@@ -38,3 +39,10 @@ impl<T: Debug + Clone> Deref for Message<T> {
         &self.inner
     }
 }
+
+/// Only the payload is hashed, so the same message coming from different sources is considered equal
+impl<T: Hash> Hash for Message<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.inner.hash(state);
+    }
+}
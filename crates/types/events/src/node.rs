@@ -1,8 +1,9 @@
 use alloy_rpc_types::Header;
+use std::hash::{Hash, Hasher};
 
 use crate::Message;
 use loom_types_blockchain::{GethStateUpdateVec, MempoolTx};
-use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
+use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum, LoomHeader};
 
 #[derive(Clone, Debug)]
 pub struct NodeMempoolDataUpdate<LDT: LoomDataTypes = LoomDataTypesEthereum> {
@@ -48,3 +49,21 @@ impl BlockHeader<LoomDataTypesEthereum> {
         Self { header, next_block_number, next_block_timestamp }
     }
 }
+
+/// Updates are identified by tx hash and the kind of data they carry
+impl<LDT: LoomDataTypes> Hash for NodeMempoolDataUpdate<LDT> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.tx_hash.hash(state);
+        self.mempool_tx.tx.is_some().hash(state);
+        self.mempool_tx.logs.is_some().hash(state);
+        self.mempool_tx.state_update.is_some().hash(state);
+        self.mempool_tx.mined.hash(state);
+        self.mempool_tx.failed.hash(state);
+    }
+}
+
+impl<LDT: LoomDataTypes> Hash for BlockHeader<LDT> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        LoomHeader::hash(&self.header).hash(state);
+    }
+}
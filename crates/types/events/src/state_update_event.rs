@@ -1,6 +1,7 @@
 #![allow(clippy::type_complexity)]
 
 use std::collections::BTreeMap;
+use std::sync::Arc;
 
 use revm::primitives::Env;
 use revm::DatabaseRef;
@@ -14,7 +15,8 @@ pub struct StateUpdateEvent<DB, LDT: LoomDataTypes = LoomDataTypesEthereum> {
     pub next_block_number: u64,
     pub next_block_timestamp: u64,
     pub next_base_fee: u64,
-    market_state: DB,
+    // Shared by all receivers of the event, searchers add their own read-write layer on top
+    market_state: Arc<DB>,
     state_update: Vec<LDT::StateUpdate>,
     state_required: Option<Vec<LDT::StateUpdate>>,
     directions: BTreeMap<PoolWrapper, Vec<SwapDirection<LDT>>>,
@@ -45,7 +47,7 @@ impl<DB: DatabaseRef, LDT: LoomDataTypes> StateUpdateEvent<DB, LDT> {
             next_base_fee,
             state_update,
             state_required,
-            market_state,
+            market_state: Arc::new(market_state),
             directions,
             stuffing_txs_hashes,
             stuffing_txs,
@@ -66,6 +68,10 @@ impl<DB: DatabaseRef, LDT: LoomDataTypes> StateUpdateEvent<DB, LDT> {
         &self.market_state
     }
 
+    pub fn shared_market_state(&self) -> Arc<DB> {
+        self.market_state.clone()
+    }
+
     pub fn state_update(&self) -> &Vec<LDT::StateUpdate> {
         &self.state_update
     }
@@ -1,4 +1,6 @@
 use crate::tx_compose::TxComposeData;
+#[cfg(feature = "debug-traces")]
+use crate::TraceFrame;
 use crate::Message;
 use alloy_eips::eip2718::Encodable2718;
 use alloy_primitives::{Bytes, U256};
@@ -31,6 +33,8 @@ pub enum SwapComposeMessage<DB, LDT: LoomDataTypes = LoomDataTypesEthereum> {
     Prepare(SwapComposeData<DB, LDT>),
     Estimate(SwapComposeData<DB, LDT>),
     Ready(SwapComposeData<DB, LDT>),
+    /// Estimation failed, reserved resources like the nonce can be reused
+    Rejected(SwapComposeData<DB, LDT>),
 }
 
 impl<DB, LDT: LoomDataTypes> Deref for SwapComposeMessage<DB, LDT> {
@@ -44,7 +48,10 @@ impl<DB, LDT: LoomDataTypes> Deref for SwapComposeMessage<DB, LDT> {
 impl<DB, LDT: LoomDataTypes> SwapComposeMessage<DB, LDT> {
     pub fn data(&self) -> &SwapComposeData<DB, LDT> {
         match self {
-            SwapComposeMessage::Prepare(x) | SwapComposeMessage::Estimate(x) | SwapComposeMessage::Ready(x) => x,
+            SwapComposeMessage::Prepare(x)
+            | SwapComposeMessage::Estimate(x)
+            | SwapComposeMessage::Ready(x)
+            | SwapComposeMessage::Rejected(x) => x,
         }
     }
 }
@@ -59,6 +66,13 @@ pub struct SwapComposeData<DB, LDT: LoomDataTypes = LoomDataTypesEthereum> {
     pub origin: Option<String>,
     pub tips_pct: Option<u32>,
     pub tips: Option<U256>,
+    /// Profit in ETH the swap transaction realized in the simulation of the estimator
+    pub simulated_profit_eth: Option<U256>,
+    /// Landing rate of recent bundles with a similar priority fee to base fee ratio
+    pub estimated_landing_probability: Option<f32>,
+    /// EVM execution trace of the estimated transaction
+    #[cfg(feature = "debug-traces")]
+    pub simulation_trace: Option<Vec<TraceFrame>>,
 }
 
 impl<DB: Clone + 'static, LDT: LoomDataTypes> SwapComposeData<DB, LDT> {
@@ -118,12 +132,21 @@ impl<DB: DatabaseRef + Send + Sync + Clone + 'static, LDT: LoomDataTypes> Defaul
             origin: None,
             tips_pct: None,
             tips: None,
+            simulated_profit_eth: None,
+            estimated_landing_probability: None,
+            #[cfg(feature = "debug-traces")]
+            simulation_trace: None,
         }
     }
 }
 
 pub type MessageSwapCompose<DB, LDT = LoomDataTypesEthereum> = Message<SwapComposeMessage<DB, LDT>>;
 
+/// Message source of ready requests produced by the EVM estimator
+pub const EVM_ESTIMATOR_SOURCE: &str = "evm_estimator";
+/// Message source of ready requests produced by the Geth estimator
+pub const GETH_ESTIMATOR_SOURCE: &str = "geth_estimator";
+
 impl<DB, LDT: LoomDataTypes> MessageSwapCompose<DB, LDT> {
     pub fn prepare(data: SwapComposeData<DB, LDT>) -> Self {
         Message::new(SwapComposeMessage::Prepare(data))
@@ -136,4 +159,12 @@ impl<DB, LDT: LoomDataTypes> MessageSwapCompose<DB, LDT> {
     pub fn ready(data: SwapComposeData<DB, LDT>) -> Self {
         Message::new(SwapComposeMessage::Ready(data))
     }
+
+    pub fn ready_with_source(data: SwapComposeData<DB, LDT>, source: &str) -> Self {
+        Message::new_with_source(SwapComposeMessage::Ready(data), source.to_string())
+    }
+
+    pub fn rejected(data: SwapComposeData<DB, LDT>) -> Self {
+        Message::new(SwapComposeMessage::Rejected(data))
+    }
 }
@@ -0,0 +1,72 @@
+use alloy_primitives::B256;
+use alloy_rpc_types_trace::parity::TransactionTrace;
+use std::collections::{HashMap, VecDeque};
+
+/// Call frame of an EVM execution trace
+pub type TraceFrame = TransactionTrace;
+
+const DEFAULT_SWAP_TRACES_CAPACITY: usize = 1000;
+
+/// Execution traces of simulated swaps keyed by swap id, the oldest trace is evicted once capacity is reached
+#[derive(Clone, Debug)]
+pub struct SwapTraces {
+    capacity: usize,
+    traces: HashMap<B256, Vec<TraceFrame>>,
+    order: VecDeque<B256>,
+}
+
+impl Default for SwapTraces {
+    fn default() -> Self {
+        Self::new(DEFAULT_SWAP_TRACES_CAPACITY)
+    }
+}
+
+impl SwapTraces {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, traces: HashMap::new(), order: VecDeque::new() }
+    }
+
+    pub fn insert(&mut self, swap_id: B256, trace: Vec<TraceFrame>) {
+        if self.traces.insert(swap_id, trace).is_some() {
+            return;
+        }
+        self.order.push_back(swap_id);
+        while self.order.len() > self.capacity {
+            if let Some(evicted) = self.order.pop_front() {
+                self.traces.remove(&evicted);
+            }
+        }
+    }
+
+    pub fn get(&self, swap_id: &B256) -> Option<&Vec<TraceFrame>> {
+        self.traces.get(swap_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.traces.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.traces.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_oldest_trace_evicted() {
+        let mut swap_traces = SwapTraces::new(2);
+        swap_traces.insert(B256::repeat_byte(1), vec![]);
+        swap_traces.insert(B256::repeat_byte(2), vec![]);
+        // Replacing a trace keeps its position
+        swap_traces.insert(B256::repeat_byte(1), vec![]);
+        swap_traces.insert(B256::repeat_byte(3), vec![]);
+
+        assert_eq!(swap_traces.len(), 2);
+        assert!(swap_traces.get(&B256::repeat_byte(1)).is_none());
+        assert!(swap_traces.get(&B256::repeat_byte(2)).is_some());
+        assert!(swap_traces.get(&B256::repeat_byte(3)).is_some());
+    }
+}
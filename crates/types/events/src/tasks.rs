@@ -1,7 +1,24 @@
 use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
 use loom_types_entities::{PoolClass, PoolId};
+use std::any::Any;
+use std::sync::Arc;
 
 #[derive(Clone, Debug)]
 pub enum LoomTask<LDT: LoomDataTypes = LoomDataTypesEthereum> {
     FetchAndAddPools(Vec<(PoolId<LDT>, PoolClass)>),
+    /// A config section was re-parsed after the config file changed. Shared with `Arc` as broadcast messages are cloned
+    /// for every receiver.
+    ConfigReloaded(Arc<dyn Any + Send + Sync>),
+    /// An actor was restarted by the actors manager after a failure
+    ActorRestarted { name: String },
+}
+
+impl<LDT: LoomDataTypes> LoomTask<LDT> {
+    /// Reloaded config if the payload is of type `C`
+    pub fn reloaded_config<C: Clone + 'static>(&self) -> Option<C> {
+        match self {
+            LoomTask::ConfigReloaded(config) => config.downcast_ref::<C>().cloned(),
+            _ => None,
+        }
+    }
 }
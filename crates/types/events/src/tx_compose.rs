@@ -51,6 +51,12 @@ pub struct TxComposeData<LDT: LoomDataTypes = LoomDataTypesEthereum> {
     pub origin: Option<String>,
     pub swap: Option<Swap>,
     pub tips: Option<U256>,
+    /// Call the swap on the EOA delegated to the multicaller with an EIP-7702 authorization
+    pub use_eip7702: bool,
+    /// Sign the EIP-7702 authorization, set by the router when the EOA is not delegated to the multicaller yet
+    pub eip7702_authorize: bool,
+    /// Let the Geth estimator attach the `eth_createAccessList` access list when it lowers gas used
+    pub use_access_list: bool,
 }
 
 impl<LDT: LoomDataTypes> Default for TxComposeData<LDT> {
@@ -73,6 +79,9 @@ impl<LDT: LoomDataTypes> Default for TxComposeData<LDT> {
             origin: None,
             swap: None,
             tips: None,
+            use_eip7702: false,
+            eip7702_authorize: false,
+            use_access_list: false,
         }
     }
 }